@@ -1,3 +1,6 @@
+// Contract calls mirror the Solidity signatures
+#![allow(clippy::too_many_arguments)]
+
 use alloy::sol;
 
 // EIP-8004 Identity Registry contract interface
@@ -25,6 +28,7 @@ sol! {
         // Events
         event Registered(uint256 indexed agentId, string agentURI, address indexed owner);
         event URIUpdated(uint256 indexed agentId, string newURI, address indexed updatedBy);
+        event Transfer(address indexed from, address indexed to, uint256 indexed tokenId);
         event MetadataSet(uint256 indexed agentId, string indexed indexedMetadataKey, string metadataKey, bytes metadataValue);
 
         // Errors
@@ -77,3 +81,42 @@ sol! {
     }
 }
 
+// ENS registry and resolver (reverse resolution of owner / wallet addresses)
+sol! {
+    #[sol(rpc)]
+    interface IENSRegistry {
+        function resolver(bytes32 node) external view returns (address);
+    }
+}
+
+sol! {
+    #[sol(rpc)]
+    interface IENSResolver {
+        function name(bytes32 node) external view returns (string memory);
+        function addr(bytes32 node) external view returns (address);
+    }
+}
+
+// Ethereum Attestation Service (optional per-audit attestations)
+sol! {
+    #[sol(rpc)]
+    interface IEAS {
+        struct AttestationRequestData {
+            address recipient;
+            uint64 expirationTime;
+            bool revocable;
+            bytes32 refUID;
+            bytes data;
+            uint256 value;
+        }
+
+        struct AttestationRequest {
+            bytes32 schema;
+            AttestationRequestData data;
+        }
+
+        function attest(AttestationRequest calldata request) external payable returns (bytes32);
+
+        event Attested(address indexed recipient, address indexed attester, bytes32 uid, bytes32 indexed schemaUID);
+    }
+}
@@ -0,0 +1,345 @@
+//! A2A facade: Watchy as an A2A agent
+//!
+//! `GET /.well-known/agent.json` (and `agent-card.json`) serves Watchy's agent
+//! card, and `POST /a2a` takes JSON-RPC 2.0 requests, so other agents can ask
+//! for audits over the protocol Watchy audits. `message/send` starts an audit
+//! and returns it as a task whose ID is the audit ID; `tasks/get` polls it.
+//! The agent to audit comes from a data part (`{"agent_id": 17, "chain_id": 8453}`,
+//! the `POST /audit` body) or from text such as "audit agent 17 on base".
+//! `/a2a` requires the API key like `/audit`.
+
+use axum::{
+    extract::State,
+    http::HeaderMap,
+    Extension, Json,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use validator::Validate;
+
+use super::handlers::{audit_status, create_audit, AuditStatusResponse};
+use super::middleware::RequestId;
+use crate::chains::get_chain_by_name;
+use crate::receipts;
+use crate::tenant::Tenant;
+use crate::types::{AuditRequest, AuditStatus, WatchyError};
+use crate::AppState;
+
+/// A2A protocol version the card declares
+const PROTOCOL_VERSION: &str = "0.3.0";
+/// ID of the card's only skill
+const AUDIT_SKILL: &str = "audit-agent";
+
+// JSON-RPC and A2A error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+const TASK_NOT_FOUND: i64 = -32001;
+const TASK_NOT_CANCELABLE: i64 = -32002;
+
+/// GET /.well-known/agent.json - Watchy's A2A agent card
+///
+/// Needs `PUBLIC_URL`: the request's Host and forwarded headers are the
+/// client's to set, so the card's URL isn't taken from them.
+pub async fn agent_card(State(state): State<Arc<AppState>>) -> Result<Json<Value>, WatchyError> {
+    let base_url = state.config.public_url.clone().ok_or_else(|| {
+        WatchyError::Forbidden("The agent card is disabled (PUBLIC_URL is not set)".to_string())
+    })?;
+    let mut card = json!({
+        "protocolVersion": PROTOCOL_VERSION,
+        "name": "Watchy",
+        "description": "Audits EIP-8004 agents: metadata, on-chain registration, \
+            endpoints and security, published as a signed report and on-chain feedback.",
+        "url": format!("{}/a2a", base_url),
+        "preferredTransport": "JSONRPC",
+        "version": env!("CARGO_PKG_VERSION"),
+        "capabilities": {
+            "streaming": false,
+            "pushNotifications": false,
+            "stateTransitionHistory": false
+        },
+        "defaultInputModes": ["application/json", "text/plain"],
+        "defaultOutputModes": ["application/json"],
+        "skills": [{
+            "id": AUDIT_SKILL,
+            "name": "Audit an agent",
+            "description": "Audit an EIP-8004 registered agent. Send \
+                {\"agent_id\": 17, \"chain_id\": 8453} as a data part, or text naming the \
+                agent and chain; poll the returned task with tasks/get.",
+            "tags": ["audit", "eip-8004", "security", "reputation"],
+            "examples": ["Audit agent 17 on base", "{\"agent_id\": 17, \"chain_id\": 8453}"]
+        }]
+    });
+    if state.config.api_key.is_some() || !state.config.tenant_keys.is_empty() {
+        card["securitySchemes"] = json!({
+            "apiKey": { "type": "apiKey", "in": "header", "name": "X-API-Key" }
+        });
+        card["security"] = json!([{ "apiKey": [] }]);
+    }
+    Ok(Json(card))
+}
+
+#[derive(Deserialize)]
+struct RpcRequest {
+    jsonrpc: String,
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Deserialize)]
+struct SendParams {
+    message: Message,
+}
+
+#[derive(Deserialize)]
+struct Message {
+    #[serde(default)]
+    parts: Vec<Part>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum Part {
+    Text { text: String },
+    Data { data: Value },
+    File {},
+}
+
+#[derive(Deserialize)]
+struct TaskIdParams {
+    id: String,
+}
+
+/// A JSON-RPC error: code and message
+type RpcError = (i64, String);
+
+/// POST /a2a - JSON-RPC 2.0 endpoint (`message/send`, `tasks/get`, `tasks/cancel`)
+pub async fn rpc(
+    State(state): State<Arc<AppState>>,
+    Extension(tenant): Extension<Tenant>,
+    request_id: Option<Extension<RequestId>>,
+    headers: HeaderMap,
+    body: String,
+) -> Json<Value> {
+    let request: RpcRequest = match serde_json::from_str(&body) {
+        Ok(request) => request,
+        Err(e) => {
+            let code = match e.classify() {
+                serde_json::error::Category::Data => INVALID_REQUEST,
+                _ => PARSE_ERROR,
+            };
+            return Json(error_response(Value::Null, (code, e.to_string())));
+        }
+    };
+    if request.jsonrpc != "2.0" {
+        let error = (INVALID_REQUEST, "jsonrpc must be \"2.0\"".to_string());
+        return Json(error_response(request.id, error));
+    }
+
+    let result = match request.method.as_str() {
+        "message/send" => {
+            let request_id = request_id.map(|Extension(RequestId(id))| id);
+            let requester = receipts::requester(&state.config, &headers);
+            send_message(&state, tenant, request_id, requester, request.params).await
+        }
+        "tasks/get" => get_task(&state, &tenant, request.params).await,
+        "tasks/cancel" => match get_task(&state, &tenant, request.params).await {
+            Ok(_) => Err((TASK_NOT_CANCELABLE, "Audits can't be canceled".to_string())),
+            Err(e) => Err(e),
+        },
+        method => Err((METHOD_NOT_FOUND, format!("Method not found: {}", method))),
+    };
+    Json(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": request.id, "result": result }),
+        Err(error) => error_response(request.id, error),
+    })
+}
+
+fn error_response(id: Value, (code, message): RpcError) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+/// `message/send`: start an audit of the agent the message names
+async fn send_message(
+    state: &Arc<AppState>,
+    tenant: Tenant,
+    request_id: Option<String>,
+    requester: Option<String>,
+    params: Value,
+) -> Result<Value, RpcError> {
+    let params: SendParams = serde_json::from_value(params)
+        .map_err(|e| (INVALID_PARAMS, format!("Invalid params: {}", e)))?;
+    let request = audit_request(&params.message).ok_or_else(|| {
+        let message = "Name the agent to audit, e.g. \"audit agent 17 on base\" or a data part \
+            {\"agent_id\": 17, \"chain_id\": 8453}";
+        (INVALID_PARAMS, message.to_string())
+    })?;
+    request
+        .validate()
+        .map_err(|e| (INVALID_PARAMS, format!("Invalid audit request: {}", e)))?;
+
+    let created = create_audit(state, tenant, request_id, requester, request)
+        .await
+        .map_err(rpc_error)?;
+    let job = state
+        .audit_store
+        .get_job(&created.audit_id)
+        .await
+        .ok_or_else(|| (INTERNAL_ERROR, "Audit job not stored".to_string()))?;
+    let mut task = task(&audit_status(state, &job).await);
+    if let Some(receipt) = created.receipt {
+        task["metadata"] = json!({ "receipt": receipt });
+    }
+    Ok(task)
+}
+
+/// `tasks/get`: the audit with the task's ID
+async fn get_task(state: &AppState, tenant: &Tenant, params: Value) -> Result<Value, RpcError> {
+    let params: TaskIdParams = serde_json::from_value(params)
+        .map_err(|e| (INVALID_PARAMS, format!("Invalid params: {}", e)))?;
+    let job = state
+        .audit_store
+        .get_tenant_job(&params.id, tenant.id())
+        .await
+        .ok_or_else(|| (TASK_NOT_FOUND, format!("Task not found: {}", params.id)))?;
+    Ok(task(&audit_status(state, &job).await))
+}
+
+fn rpc_error(error: WatchyError) -> RpcError {
+    match error {
+        WatchyError::InvalidRequest(_) | WatchyError::Validation { .. } => {
+            (INVALID_PARAMS, error.to_string())
+        }
+        _ => (INTERNAL_ERROR, error.to_string()),
+    }
+}
+
+/// The audit to run, from the message's first data part or else its text
+fn audit_request(message: &Message) -> Option<AuditRequest> {
+    let data = message.parts.iter().find_map(|part| match part {
+        Part::Data { data } => serde_json::from_value(data.clone()).ok(),
+        _ => None,
+    });
+    data.or_else(|| {
+        message.parts.iter().find_map(|part| match part {
+            Part::Text { text } => parse_text(text),
+            _ => None,
+        })
+    })
+}
+
+/// Agent and chain from text such as "audit agent 17 on base" or "agent #17, chain 8453"
+fn parse_text(text: &str) -> Option<AuditRequest> {
+    let text = text.to_lowercase();
+    let words: Vec<&str> = text
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+        .filter(|word| !word.is_empty())
+        .collect();
+    let number = |word: &&str| word.parse::<u64>().ok();
+
+    let mut agent_id = None;
+    let mut chain_id = None;
+    for pair in words.windows(2) {
+        match pair[0] {
+            "agent" if agent_id.is_none() => agent_id = number(&pair[1]),
+            "chain" | "on" if chain_id.is_none() => {
+                chain_id = number(&pair[1])
+                    .or_else(|| get_chain_by_name(pair[1]).map(|chain| chain.chain_id));
+            }
+            _ => {}
+        }
+    }
+    // Without "agent", the first number that isn't the chain
+    let agent_id = agent_id.or_else(|| {
+        words
+            .iter()
+            .filter_map(number)
+            .find(|n| Some(*n) != chain_id)
+    })?;
+
+    Some(AuditRequest {
+        agent_id,
+        chain_id,
+        timeout_secs: None,
+        lang: None,
+    })
+}
+
+/// An audit as an A2A task
+fn task(status: &AuditStatusResponse) -> Value {
+    let (state, summary) = match status.status {
+        AuditStatus::Pending => ("submitted", "Audit queued".to_string()),
+        AuditStatus::InProgress => ("working", "Audit in progress".to_string()),
+        AuditStatus::Completed => (
+            "completed",
+            match &status.result {
+                Some(result) => format!("Audit completed, score {}/100", result.scores.overall),
+                None => "Audit completed".to_string(),
+            },
+        ),
+        AuditStatus::Failed => (
+            "failed",
+            match &status.error {
+                Some(error) => format!("Audit failed: {}", error.message),
+                None => "Audit failed".to_string(),
+            },
+        ),
+    };
+    let timestamp = status.completed_at.unwrap_or(status.created_at);
+    let mut task = json!({
+        "kind": "task",
+        "id": status.audit_id,
+        "contextId": status.audit_id,
+        "status": {
+            "state": state,
+            "timestamp": chrono::DateTime::from_timestamp(timestamp as i64, 0)
+                .unwrap_or_default()
+                .to_rfc3339(),
+            "message": {
+                "kind": "message",
+                "role": "agent",
+                "messageId": format!("{}-{}", status.audit_id, state),
+                "parts": [{ "kind": "text", "text": summary }]
+            }
+        }
+    });
+    if matches!(status.status, AuditStatus::Completed | AuditStatus::Failed) {
+        task["artifacts"] = json!([{
+            "artifactId": format!("{}-result", status.audit_id),
+            "name": "audit-result",
+            "parts": [{ "kind": "data", "data": status }]
+        }]);
+    }
+    task
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audit_request_from_message() {
+        let parsed = parse_text("Please audit agent #17 on base").unwrap();
+        assert_eq!((parsed.agent_id, parsed.chain_id), (17, Some(8453)));
+        let parsed = parse_text("chain 1, agent 42").unwrap();
+        assert_eq!((parsed.agent_id, parsed.chain_id), (42, Some(1)));
+        assert_eq!(parse_text("audit 9").unwrap().chain_id, None);
+        assert!(parse_text("audit my agent").is_none());
+
+        let message: Message = serde_json::from_value(json!({
+            "parts": [
+                { "kind": "text", "text": "audit agent 5" },
+                { "kind": "data", "data": { "agent_id": 17, "chain_id": 84532 } }
+            ]
+        }))
+        .unwrap();
+        let request = audit_request(&message).unwrap();
+        assert_eq!((request.agent_id, request.chain_id), (17, Some(84532)));
+    }
+}
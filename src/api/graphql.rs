@@ -0,0 +1,442 @@
+//! GraphQL API over stored audits
+//!
+//! Read-only view of the audit store for dashboards: audits, agents, scores and
+//! issues, filterable and nestable, so a client fetches exactly the fields it
+//! needs in one request instead of stitching several REST calls.
+
+use async_graphql::{
+    http::GraphiQLSource, Context, EmptyMutation, EmptySubscription, Enum, InputObject, Json,
+    Object, Schema, SimpleObject,
+};
+use axum::{extract::State, response::Html, Extension};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::store::AuditJob;
+use crate::tenant::Tenant;
+use crate::types::{AuditReport, Issue};
+use crate::AppState;
+
+/// Default page size for list fields
+const DEFAULT_LIMIT: usize = 20;
+/// Largest page a list field returns
+const MAX_LIMIT: usize = 100;
+
+pub type WatchySchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Schema with depth and complexity limits; the app state is attached per request
+pub fn build_schema() -> WatchySchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .limit_depth(8)
+        .limit_complexity(1000)
+        .finish()
+}
+
+/// POST /graphql
+pub async fn graphql_handler(
+    State(state): State<Arc<AppState>>,
+    Extension(schema): Extension<WatchySchema>,
+    Extension(tenant): Extension<Tenant>,
+    axum::Json(request): axum::Json<async_graphql::Request>,
+) -> axum::Json<async_graphql::Response> {
+    axum::Json(schema.execute(request.data(state).data(tenant)).await)
+}
+
+/// GET /graphql - GraphiQL explorer
+pub async fn graphiql() -> Html<String> {
+    Html(GraphiQLSource::build().endpoint("/graphql").finish())
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(remote = "crate::types::AuditStatus")]
+pub enum AuditStatus {
+    Pending,
+    InProgress,
+    Completed,
+    Failed,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(remote = "crate::types::Severity")]
+pub enum Severity {
+    Critical,
+    Error,
+    Warning,
+    Info,
+}
+
+/// Audit list filter; all conditions must hold
+#[derive(InputObject, Default)]
+pub struct AuditFilter {
+    pub agent_id: Option<u64>,
+    pub chain_id: Option<u64>,
+    pub status: Option<AuditStatus>,
+    /// Overall score at least this (completed audits only)
+    pub min_score: Option<u8>,
+    /// Overall score at most this (completed audits only)
+    pub max_score: Option<u8>,
+}
+
+impl AuditFilter {
+    fn matches(&self, job: &AuditJob) -> bool {
+        let overall = job.result.as_ref().map(|r| r.scores.overall);
+        self.agent_id.is_none_or(|id| job.agent_id == Some(id))
+            && self.chain_id.is_none_or(|id| job.chain_id == id)
+            && self
+                .status
+                .is_none_or(|status| job.status == crate::types::AuditStatus::from(status))
+            && self
+                .min_score
+                .is_none_or(|min| overall.is_some_and(|score| score >= min))
+            && self
+                .max_score
+                .is_none_or(|max| overall.is_some_and(|score| score <= max))
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct Scores {
+    pub overall: u8,
+    pub metadata: u8,
+    pub onchain: u8,
+    pub endpoint_availability: u8,
+    pub endpoint_performance: u8,
+    pub security: u8,
+    pub consistency: u8,
+    pub content: u8,
+}
+
+impl From<&crate::types::Scores> for Scores {
+    fn from(scores: &crate::types::Scores) -> Self {
+        Self {
+            overall: scores.overall,
+            metadata: scores.metadata,
+            onchain: scores.onchain,
+            endpoint_availability: scores.endpoint_availability,
+            endpoint_performance: scores.endpoint_performance,
+            security: scores.security,
+            consistency: scores.consistency,
+            content: scores.content,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct AuditIssue {
+    /// Check category (metadata, onchain, endpoints, security, consistency, content)
+    pub category: String,
+    pub severity: Severity,
+    pub code: String,
+    pub message: String,
+}
+
+impl AuditIssue {
+    fn new(category: &str, issue: &Issue) -> Self {
+        Self {
+            category: category.to_string(),
+            severity: issue.severity.clone().into(),
+            code: issue.code.clone(),
+            message: issue.message.clone(),
+        }
+    }
+}
+
+/// A stored audit job
+pub struct Audit(AuditJob);
+
+#[Object]
+impl Audit {
+    async fn id(&self) -> &str {
+        &self.0.id
+    }
+
+    /// Null for an unregistered agent
+    async fn agent_id(&self) -> Option<u64> {
+        self.0.agent_id
+    }
+
+    async fn chain_id(&self) -> u64 {
+        self.0.chain_id
+    }
+
+    async fn status(&self) -> AuditStatus {
+        self.0.status.clone().into()
+    }
+
+    /// Unix seconds
+    async fn created_at(&self) -> u64 {
+        self.0.created_at
+    }
+
+    /// Unix seconds
+    async fn completed_at(&self) -> Option<u64> {
+        self.0.completed_at
+    }
+
+    async fn error(&self) -> Option<&str> {
+        self.0.error.as_ref().map(|error| error.message.as_str())
+    }
+
+    /// Structured failure: code, phase, retryability and upstream cause
+    async fn failure(&self) -> Option<Json<&crate::types::AuditError>> {
+        self.0.error.as_ref().map(Json)
+    }
+
+    /// Unix seconds after which the audit is stale
+    async fn valid_until(&self) -> Option<u64> {
+        self.report()?.valid_until
+    }
+
+    /// Past `valid_until`; the score shouldn't be relied on
+    async fn stale(&self) -> bool {
+        self.report()
+            .is_some_and(|r| r.is_stale(chrono::Utc::now().timestamp() as u64))
+    }
+
+    async fn scores(&self) -> Option<Scores> {
+        self.report().map(|r| Scores::from(&r.scores))
+    }
+
+    /// Issues, optionally narrowed to one severity or check category
+    async fn issues(&self, severity: Option<Severity>, category: Option<String>) -> Vec<AuditIssue> {
+        let Some(report) = self.report() else {
+            return Vec::new();
+        };
+        report
+            .issues_by_category()
+            .into_iter()
+            .filter(|(c, issue)| {
+                severity.is_none_or(|s| issue.severity == crate::types::Severity::from(s))
+                    && category.as_deref().is_none_or(|wanted| *c == wanted)
+            })
+            .map(|(c, issue)| AuditIssue::new(c, issue))
+            .collect()
+    }
+
+    /// Published JSON report
+    async fn report_url(&self) -> Option<&str> {
+        self.report()?.report_json_url.as_deref()
+    }
+
+    /// Published Markdown report
+    async fn report_markdown_url(&self) -> Option<&str> {
+        self.report()?.report_markdown_url.as_deref()
+    }
+
+    /// The full report as JSON, for fields not modeled in the schema
+    async fn report_json(&self) -> Option<Json<&AuditReport>> {
+        self.report().map(Json)
+    }
+}
+
+impl Audit {
+    fn report(&self) -> Option<&AuditReport> {
+        self.0.result.as_ref()
+    }
+}
+
+/// An agent and its audits, newest first
+pub struct Agent {
+    agent_id: u64,
+    chain_id: u64,
+    jobs: Vec<AuditJob>,
+}
+
+#[Object]
+impl Agent {
+    async fn agent_id(&self) -> u64 {
+        self.agent_id
+    }
+
+    async fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    async fn audit_count(&self) -> usize {
+        self.jobs.len()
+    }
+
+    /// Most recent completed audit
+    async fn latest_audit(&self) -> Option<Audit> {
+        self.latest_completed().cloned().map(Audit)
+    }
+
+    /// Overall score of the most recent completed audit
+    async fn latest_score(&self) -> Option<u8> {
+        self.latest_completed()?
+            .result
+            .as_ref()
+            .map(|r| r.scores.overall)
+    }
+
+    async fn audits(&self, limit: Option<usize>, offset: Option<usize>) -> Vec<Audit> {
+        page(self.jobs.iter().cloned(), limit, offset)
+            .map(Audit)
+            .collect()
+    }
+}
+
+impl Agent {
+    fn latest_completed(&self) -> Option<&AuditJob> {
+        self.jobs
+            .iter()
+            .find(|job| job.status == crate::types::AuditStatus::Completed && job.result.is_some())
+    }
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn audit(&self, ctx: &Context<'_>, id: String) -> async_graphql::Result<Option<Audit>> {
+        let state = ctx.data::<Arc<AppState>>()?;
+        let tenant = ctx.data::<Tenant>()?;
+        Ok(state
+            .audit_store
+            .get_tenant_job(&id, tenant.id())
+            .await
+            .map(Audit))
+    }
+
+    /// Audits, newest first
+    async fn audits(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<AuditFilter>,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> async_graphql::Result<Vec<Audit>> {
+        let filter = filter.unwrap_or_default();
+        let jobs = newest_first(ctx).await?;
+        Ok(page(jobs.into_iter().filter(|job| filter.matches(job)), limit, offset)
+            .map(Audit)
+            .collect())
+    }
+
+    /// An agent by id (on the default chain unless `chainId` is given)
+    async fn agent(
+        &self,
+        ctx: &Context<'_>,
+        agent_id: u64,
+        chain_id: Option<u64>,
+    ) -> async_graphql::Result<Option<Agent>> {
+        let chain_id = chain_id.unwrap_or(ctx.data::<Arc<AppState>>()?.config.default_chain_id);
+        let jobs: Vec<AuditJob> = newest_first(ctx)
+            .await?
+            .into_iter()
+            .filter(|job| job.agent_id == Some(agent_id) && job.chain_id == chain_id)
+            .collect();
+
+        Ok((!jobs.is_empty()).then_some(Agent {
+            agent_id,
+            chain_id,
+            jobs,
+        }))
+    }
+
+    /// Audited agents, ordered by chain and agent id
+    async fn agents(
+        &self,
+        ctx: &Context<'_>,
+        chain_id: Option<u64>,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> async_graphql::Result<Vec<Agent>> {
+        let mut agents: BTreeMap<(u64, u64), Vec<AuditJob>> = BTreeMap::new();
+        for job in newest_first(ctx).await? {
+            let Some(agent_id) = job.agent_id else {
+                continue;
+            };
+            if chain_id.is_none_or(|id| job.chain_id == id) {
+                agents.entry((job.chain_id, agent_id)).or_default().push(job);
+            }
+        }
+
+        Ok(page(agents.into_iter(), limit, offset)
+            .map(|((chain_id, agent_id), jobs)| Agent {
+                agent_id,
+                chain_id,
+                jobs,
+            })
+            .collect())
+    }
+}
+
+/// The caller's tenant's audits, newest first
+async fn newest_first(ctx: &Context<'_>) -> async_graphql::Result<Vec<AuditJob>> {
+    let state = ctx.data::<Arc<AppState>>()?;
+    let tenant = ctx.data::<Tenant>()?;
+    let mut jobs = state.audit_store.list_jobs().await;
+    jobs.retain(|job| job.tenant.as_deref() == tenant.id());
+    jobs.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| a.id.cmp(&b.id)));
+    Ok(jobs)
+}
+
+fn page<T>(
+    items: impl Iterator<Item = T>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> impl Iterator<Item = T> {
+    items
+        .skip(offset.unwrap_or(0))
+        .take(limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(agent_id: u64, score: Option<u8>) -> AuditJob {
+        let result = score.map(|overall| {
+            let mut report = AuditReport::new(agent_id, 8453, "0x8004", String::new(), None);
+            report.scores.overall = overall;
+            report
+        });
+        AuditJob {
+            id: format!("audit-{}", agent_id),
+            agent_id: Some(agent_id),
+            chain_id: 8453,
+            status: if result.is_some() {
+                crate::types::AuditStatus::Completed
+            } else {
+                crate::types::AuditStatus::Pending
+            },
+            created_at: 0,
+            completed_at: None,
+            result,
+            error: None,
+            archived_to: None,
+            events: Vec::new(),
+            tenant: None,
+            request_id: None,
+            params: None,
+            attempt: 1,
+            attempts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_audit_filter() {
+        let filter = AuditFilter {
+            min_score: Some(70),
+            ..Default::default()
+        };
+        assert!(filter.matches(&job(1, Some(80))));
+        assert!(!filter.matches(&job(1, Some(60))));
+        assert!(!filter.matches(&job(1, None)));
+
+        let filter = AuditFilter {
+            agent_id: Some(2),
+            status: Some(AuditStatus::Pending),
+            ..Default::default()
+        };
+        assert!(filter.matches(&job(2, None)));
+        assert!(!filter.matches(&job(2, Some(90))));
+    }
+
+    #[tokio::test]
+    async fn test_schema_validates_queries() {
+        let response = build_schema().execute("{ audits { nope } }").await;
+        assert!(response.errors[0].message.contains("nope"));
+    }
+}
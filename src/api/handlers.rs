@@ -1,42 +1,78 @@
+use alloy::primitives::Address;
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
-    Json,
+    extract::{Form, Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{Html, IntoResponse, Response},
+    Extension, Json,
 };
+use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
-use tracing::{error, info};
-
-use crate::arweave::{irys::sign_report, IrysClient};
-use crate::audit::{generate_markdown_report, metadata, AuditEngine};
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+use std::sync::{Arc, OnceLock};
+use tracing::{error, info, info_span, warn, Instrument};
+use validator::{Validate, ValidationError};
+
+use crate::arweave::irys::verify_report_signature;
+use crate::arweave::{funding, uploader::ProviderBalance, uploader_from_config, IrysClient};
+use crate::attestation::{self, Attestation};
+use crate::audit::{catalog, generate_markdown_report, i18n::Lang, metadata, AuditEngine};
+use crate::blockchain::account::AccountClient;
 use crate::blockchain::registry::RegistryClient;
-use crate::blockchain::reputation::ReputationClient;
-use crate::chains::{get_chain, get_rpc_url, supported_chain_ids, ChainType};
+use crate::blockchain::reputation::compute_feedback_hash;
+use crate::breaker::BreakerState;
+use crate::caip::{self, AccountId};
+use crate::canonical;
+use crate::chains::{
+    chains_with_registry, get_chain, get_rpc_url, supported_chain_ids, ChainConfig, ChainType,
+};
+use crate::config::Config;
+use crate::events::{timeline, AuditEvent, EventKind, EventLog, TimelineEntry};
+use crate::http::read_body_limited;
+use crate::indexer::RegisteredAgent;
+use crate::integrity::IntegrityStats;
 use crate::ipfs::IpfsClient;
-use crate::store::AuditJob;
-use crate::types::{AuditRequest, AuditStatus, WatchyError};
+use crate::pause::{self, Paused, Subsystem};
+use crate::publish::{publish_report, ReportStore};
+use crate::readiness::ChainStatus;
+use crate::receipts::{self, Receipt};
+use crate::reload::{self, LiveSettings, ReloadReport};
+use crate::report_links;
+use crate::retention::{purge_jobs, PurgeFilter};
+use crate::search::{self, IndexedAgent, SearchQuery, SearchResult};
+use crate::self_registration::SelfAgent;
+use crate::stats::{self, AuditStats};
+use crate::store::{AuditAttempt, AuditJob, AuditParams};
+use crate::subscriptions::{
+    self, SignedSubscriptionRequest, Subscription, SubscriptionAction, SubscriptionRequest,
+};
+use crate::tenant::Tenant;
+use crate::usage::{self, Usage};
+use crate::api;
+use crate::api::middleware::RequestId;
+use crate::api::validation::ValidatedJson;
+use crate::types::{
+    AuditError, AuditReport, AuditRequest, AuditStatus, EndpointArtifact, EndpointCheck,
+    SecurityChecks, Service, ServiceType, WatchyError,
+};
 use crate::AppState;
 
-// =============================================================================
-// ALLOWED CHAINS
-// =============================================================================
-// Set to `false` to allow all configured chains
-const RESTRICT_CHAINS: bool = true;
-
-/// Chain IDs allowed when RESTRICT_CHAINS is true
-const ALLOWED_CHAINS: &[u64] = &[
-    8453,     // Base
-    84532,    // Base Sepolia
-    11155111, // Sepolia
-];
-
-/// Check if a chain is allowed for audits
-fn is_chain_allowed(chain_id: u64) -> bool {
-    if RESTRICT_CHAINS {
-        ALLOWED_CHAINS.contains(&chain_id)
-    } else {
-        true // All configured chains are allowed
-    }
+/// Error for a chain outside `ALLOWED_CHAINS`
+fn chain_not_allowed(settings: &LiveSettings, chain_id: u64) -> WatchyError {
+    let allowed: Vec<String> = settings
+        .allowed_chains
+        .iter()
+        .flatten()
+        .map(|&id| match get_chain(id) {
+            Some(chain) => format!("{} ({})", chain.name, id),
+            None => id.to_string(),
+        })
+        .collect();
+    WatchyError::InvalidRequest(format!(
+        "Chain {} is not enabled. Allowed chains: {}",
+        chain_id,
+        allowed.join(", ")
+    ))
 }
 
 #[derive(Serialize)]
@@ -46,25 +82,394 @@ pub struct HealthResponse {
     pub supported_chains: Vec<u64>,
     pub default_chain: u64,
     pub storage: String,
+    /// Startup checks passed (see /health/ready)
+    pub ready: bool,
+    /// Redis has been unreachable since this time (unix seconds); jobs are
+    /// kept in memory meanwhile
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_degraded_since: Option<u64>,
+    /// Jobs whose latest update hasn't reached Redis yet
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_pending_jobs: Option<usize>,
     pub wallet_mode: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub signer_address: Option<String>,
+    /// Conditions that don't affect serving but need an operator (e.g. a low signer balance)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// Subsystems paused through /admin/pause or PAUSED_SUBSYSTEMS
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub paused: Vec<Paused>,
+    /// Watchy's own agent on each chain, with SELF_REGISTER
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub self_agents: Vec<SelfAgent>,
+}
+
+/// Seconds a signer balance reading is reused by /health
+const SIGNER_BALANCE_TTL_SECS: u64 = 60;
+
+/// An address's native balance on a chain, cached for `SIGNER_BALANCE_TTL_SECS`
+async fn cached_balance(state: &AppState, chain_id: u64, address: &str) -> Option<u128> {
+    let now = chrono::Utc::now().timestamp() as u64;
+    let cached = state.signer_balance.read().await.get(&chain_id).copied();
+    if let Some((checked_at, balance)) = cached {
+        if now < checked_at + SIGNER_BALANCE_TTL_SECS {
+            return Some(balance);
+        }
+    }
+
+    let account = AccountClient::new(&get_rpc_url(chain_id)?).ok()?;
+    let lookup = account.balance_wei(address);
+    match tokio::time::timeout(std::time::Duration::from_secs(5), lookup).await {
+        Ok(Ok(balance)) => {
+            state.signer_balance.write().await.insert(chain_id, (now, balance));
+            Some(balance)
+        }
+        Ok(Err(e)) => {
+            warn!("Signer balance check on chain {} failed: {}", chain_id, e);
+            None
+        }
+        Err(_) => {
+            warn!("Signer balance check on chain {} timed out", chain_id);
+            None
+        }
+    }
+}
+
+/// Warnings for each allowed chain with a registry where the feedback signer's
+/// balance is below SIGNER_MIN_BALANCE_WEI
+async fn signer_balance_warnings(state: &AppState) -> Vec<String> {
+    let Some(min_balance) = state.config.signer_min_balance_wei else {
+        return Vec::new();
+    };
+    let retired = state.audit_store.retired_signers().await;
+    let Some(key) = state.config.wallet.feedback_key(&retired) else {
+        return Vec::new();
+    };
+    let address = key.address.as_str();
+    let settings = state.live.get();
+    let chains: Vec<u64> = chains_with_registry()
+        .into_iter()
+        .map(|chain| chain.chain_id)
+        .filter(|&chain_id| settings.chain_allowed(chain_id))
+        .collect();
+
+    let balances = futures_util::future::join_all(
+        chains.iter().map(|&chain_id| cached_balance(state, chain_id, address)),
+    )
+    .await;
+    chains
+        .into_iter()
+        .zip(balances)
+        .filter_map(|(chain_id, balance)| {
+            let balance = balance.filter(|&balance| balance < min_balance)?;
+            Some(format!(
+                "Signer {} holds {} wei on chain {}, below SIGNER_MIN_BALANCE_WEI ({})",
+                address, balance, chain_id, min_balance
+            ))
+        })
+        .collect()
 }
 
 /// GET /health
 pub async fn health(State(state): State<Arc<AppState>>) -> Json<HealthResponse> {
+    let storage_degraded_since = state.audit_store.degraded_since().await;
     Json(HealthResponse {
-        status: "healthy".to_string(),
+        status: if storage_degraded_since.is_some() {
+            "degraded".to_string()
+        } else {
+            "healthy".to_string()
+        },
         version: env!("CARGO_PKG_VERSION").to_string(),
         supported_chains: supported_chain_ids(),
         default_chain: state.config.default_chain_id,
-        storage: if state.audit_store.has_redis() {
-            "redis".to_string()
-        } else {
-            "memory".to_string()
-        },
+        storage: state.audit_store.backend_name().to_string(),
+        ready: state.readiness.is_ready(),
+        storage_degraded_since,
+        storage_pending_jobs: Some(state.audit_store.pending_jobs().await).filter(|n| *n > 0),
         wallet_mode: state.config.key_mode().as_str().to_string(),
         signer_address: state.config.signer_address().map(|s| s.to_string()),
+        warnings: signer_balance_warnings(&state).await,
+        paused: state.pauses.paused(),
+        self_agents: state.self_agents.list().await,
+    })
+}
+
+#[derive(Serialize)]
+pub struct LivenessResponse {
+    pub status: String,
+    pub uptime_secs: u64,
+}
+
+/// GET /health/live - The process is up and serving requests
+///
+/// Doesn't touch storage or RPCs, so a slow dependency never gets the process restarted.
+pub async fn health_live(State(state): State<Arc<AppState>>) -> Json<LivenessResponse> {
+    Json(LivenessResponse {
+        status: "alive".to_string(),
+        uptime_secs: state.readiness.uptime_secs(),
+    })
+}
+
+#[derive(Serialize)]
+pub struct ReadinessResponse {
+    /// "starting" until the startup checks pass, then "ready", or "degraded"
+    /// while a required chain fails a later check
+    pub status: String,
+    pub ready: bool,
+    /// RPC checks of the latest run (empty while the first is running)
+    pub chains: Vec<ChainStatus>,
+}
+
+/// GET /health/ready - 200 while the latest readiness checks pass, 503 otherwise
+pub async fn health_ready(
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<ReadinessResponse>) {
+    let ready = state.readiness.is_ready();
+    let response = ReadinessResponse {
+        status: state.readiness.status().to_string(),
+        ready,
+        chains: state.readiness.chains().await,
+    };
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(response))
+}
+
+#[derive(Deserialize)]
+pub struct AttestationQuery {
+    /// Caller's challenge; when set, a fresh quote bound to it is returned
+    pub nonce: Option<String>,
+}
+
+/// GET /attestation - Watchy's own TEE attestation quote
+pub async fn get_attestation(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<AttestationQuery>,
+) -> Result<Json<Attestation>, WatchyError> {
+    let attestation = state.attestation.clone().ok_or_else(|| {
+        WatchyError::InvalidRequest(
+            "No TEE attestation (TEE_ATTESTATION_URL or TEE_ATTESTATION_FILE not set or unreadable)"
+                .to_string(),
+        )
+    })?;
+    let (Some(nonce), Some(config)) = (query.nonce, &state.config.tee_attestation) else {
+        return Ok(Json(attestation));
+    };
+    // Same unguarded client as at startup: the attestation service is operator-configured
+    attestation::fresh(config, &state.http.upload, &attestation.signer, &nonce)
+        .await
+        .map(Json)
+}
+
+/// GET /schema/audit-report.json - JSON Schema of the published JSON report
+///
+/// `$id` names the report format version (`version` in each report).
+pub async fn get_report_schema() -> Json<serde_json::Value> {
+    static SCHEMA: OnceLock<serde_json::Value> = OnceLock::new();
+    Json(SCHEMA.get_or_init(AuditReport::json_schema).clone())
+}
+
+/// Response for POST /verify
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyReportResponse {
+    /// Serialization the hash and signature are computed over (`legacy` =
+    /// serde_json's compact output, used before canonicalization)
+    pub canonicalization: String,
+    /// keccak256 of the report as published; compare with the on-chain feedbackHash
+    pub feedback_hash: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auditor_address: Option<String>,
+    /// Whether the signature recovers to `auditorAddress` and that address is
+    /// one of this Watchy's report signers (absent if unsigned)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature_valid: Option<bool>,
+}
+
+/// Query for `POST /verify`
+#[derive(Debug, Default, Deserialize)]
+pub struct VerifyReportQuery {
+    /// Audit that published the report, dating its signature for retired signers
+    pub audit_id: Option<String>,
+}
+
+/// POST /verify - Recompute a published JSON report's feedbackHash and check its signature
+///
+/// The report is re-serialized as its `canonicalization` field declares, so
+/// whitespace and key order in the submitted copy don't matter. The report
+/// names its own auditor, so a signature only counts from a configured signer.
+pub async fn verify_report(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<VerifyReportQuery>,
+    Json(report): Json<serde_json::Value>,
+) -> Result<Json<VerifyReportResponse>, WatchyError> {
+    if !report.is_object() {
+        return Err(WatchyError::InvalidRequest(
+            "Expected a JSON audit report object".to_string(),
+        ));
+    }
+    let feedback_hash = format!("0x{}", hex::encode(compute_feedback_hash(&report)?));
+
+    // The signature covers the report without its own field
+    let mut unsigned = report.clone();
+    let signature = unsigned
+        .as_object_mut()
+        .and_then(|obj| obj.remove("signature"))
+        .and_then(|sig| sig.as_str().map(str::to_string));
+    let auditor_address = report
+        .pointer("/auditor/address")
+        .and_then(serde_json::Value::as_str)
+        .map(str::to_string);
+    // A retired signer's key may have leaked, so only reports signed before its
+    // retirement count. Whoever holds the key can backdate `created_at`: the
+    // signing time comes from the stored audit that published this exact report
+    let signed_at = match &query.audit_id {
+        Some(audit_id) => published_at(&state, audit_id, &feedback_hash).await,
+        None => None,
+    };
+    let retired = state.audit_store.retired_signers().await;
+    let signature_valid = match (&signature, &auditor_address) {
+        (Some(signature), Some(address)) => Some(
+            state.config.wallet.is_report_signer(address, signed_at, &retired)
+                && verify_report_signature(&unsigned, signature, address).unwrap_or(false),
+        ),
+        _ => None,
+    };
+
+    Ok(Json(VerifyReportResponse {
+        canonicalization: report
+            .get(canonical::FIELD)
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or("legacy")
+            .to_string(),
+        feedback_hash,
+        auditor_address,
+        signature_valid,
+    }))
+}
+
+/// When audit `audit_id` completed, if it published the report hashing to `feedback_hash`
+async fn published_at(state: &AppState, audit_id: &str, feedback_hash: &str) -> Option<u64> {
+    let job = state.audit_store.get_job(audit_id).await?;
+    let published = job.result.as_ref()?.report_json_hash.as_deref()?;
+    if published.eq_ignore_ascii_case(feedback_hash) {
+        job.completed_at
+    } else {
+        None
+    }
+}
+
+#[derive(Serialize)]
+pub struct VerifyReceiptResponse {
+    pub signer: String,
+    pub signature_valid: bool,
+}
+
+/// POST /verify/receipt - Check a receipt's signature
+///
+/// Public, so a third party can check a receipt it was shown. Only receipts
+/// signed by one of Watchy's report signers are valid.
+pub async fn verify_receipt(
+    State(state): State<Arc<AppState>>,
+    Json(receipt): Json<Receipt>,
+) -> Json<VerifyReceiptResponse> {
+    let retired = state.audit_store.retired_signers().await;
+    Json(VerifyReceiptResponse {
+        signature_valid: receipt.verify(&state.config.wallet, &retired),
+        signer: receipt.signer,
+    })
+}
+
+/// Sign a receipt with the active primary signer; `None` without a wallet
+async fn sign_receipt(state: &AppState, receipt: Receipt) -> Option<Receipt> {
+    let retired = state.audit_store.retired_signers().await;
+    let key = state.config.wallet.receipt_signer(&retired)?;
+    receipt.sign(key).await
+}
+
+/// Query for aggregate statistics
+#[derive(Debug, Deserialize)]
+pub struct StatsQuery {
+    /// Window in hours, counted back from now (default: 7 days)
+    #[serde(default = "default_stats_window_hours")]
+    pub window_hours: u64,
+}
+
+fn default_stats_window_hours() -> u64 {
+    7 * 24
+}
+
+/// Seconds a /stats window is reused before the jobs are scanned again
+const STATS_TTL_SECS: u64 = 60;
+
+/// GET /stats - Audit counts, failure rates, average scores and common issues
+pub async fn get_stats(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<StatsQuery>,
+) -> Json<AuditStats> {
+    let window_hours = query.window_hours.clamp(1, 365 * 24);
+    let now = chrono::Utc::now().timestamp() as u64;
+    if let Some((computed_at, stats)) = state.stats.read().await.get(&window_hours) {
+        if now < computed_at + STATS_TTL_SECS {
+            return Json(stats.clone());
+        }
+    }
+    let since = now.saturating_sub(window_hours * 3600);
+
+    // Public: tenants' audits are only visible through their own key
+    let jobs: Vec<AuditJob> = state
+        .audit_store
+        .list_jobs()
+        .await
+        .into_iter()
+        .filter(|job| job.tenant.is_none())
+        .collect();
+    let stats = stats::compute(&jobs, since, window_hours);
+
+    let mut cache = state.stats.write().await;
+    cache.retain(|_, (computed_at, _)| now < *computed_at + STATS_TTL_SECS);
+    cache.insert(window_hours, (now, stats.clone()));
+    Json(stats)
+}
+
+#[derive(Deserialize)]
+pub struct UsageQuery {
+    /// `YYYY-MM` (default: the current month)
+    pub month: Option<String>,
+}
+
+/// GET /usage - Audits requested with the caller's API key in a month, and its quota
+pub async fn get_usage(
+    State(state): State<Arc<AppState>>,
+    Extension(tenant): Extension<Tenant>,
+    Query(query): Query<UsageQuery>,
+) -> Result<Json<Usage>, WatchyError> {
+    let month = match query.month {
+        Some(month) => chrono::NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d")
+            .map_err(|_| WatchyError::InvalidRequest("month must be YYYY-MM".to_string()))?
+            .format("%Y-%m")
+            .to_string(),
+        None => usage::month(chrono::Utc::now().timestamp() as u64),
+    };
+
+    Ok(Json(
+        usage::usage(&state.audit_store, &state.live.get().usage, &tenant, month).await,
+    ))
+}
+
+#[derive(Serialize)]
+pub struct IssueCatalogResponse {
+    pub issues: &'static [catalog::CatalogEntry],
+}
+
+/// GET /issues/catalog - Every issue code an audit can raise
+pub async fn get_issue_catalog() -> Json<IssueCatalogResponse> {
+    Json(IssueCatalogResponse {
+        issues: catalog::CATALOG,
     })
 }
 
@@ -76,23 +481,221 @@ pub struct AuditCreatedResponse {
     pub status: AuditStatus,
     pub created_at: u64,
     pub estimated_completion: u64,
+    /// Signed receipt that the audit was requested (none without a wallet)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub receipt: Option<Receipt>,
 }
 
-/// POST /audit
-pub async fn request_audit(
+/// Request body for an ad-hoc endpoint check
+#[derive(Debug, Deserialize, Validate)]
+pub struct CheckEndpointRequest {
+    /// Endpoint URL (http(s), ws(s) or grpc(s))
+    #[validate(length(min = 1, message = "url must not be empty"))]
+    pub url: String,
+    /// Service type as declared in metadata: A2A, MCP, OASF, gRPC or web
+    #[validate(length(min = 1, message = "service must not be empty"))]
+    pub service: String,
+    /// Declared protocol version
+    pub version: Option<String>,
+    /// Declared A2A skills, compared with the agent card
+    #[serde(default)]
+    pub a2a_skills: Vec<String>,
+    /// Declared MCP tools, compared with `tools/list`
+    #[serde(default)]
+    pub mcp_tools: Vec<String>,
+    /// Check deadline in seconds (capped by AUDIT_MAX_TIMEOUT_SECS)
+    #[validate(range(min = 1, message = "timeout_secs must be greater than 0"))]
+    pub timeout_secs: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct EndpointScores {
+    pub availability: u8,
+    pub performance: u8,
+    pub security: u8,
+}
+
+#[derive(Serialize)]
+pub struct CheckEndpointResponse {
+    pub service: String,
+    pub url: String,
+    pub endpoint: EndpointCheck,
+    pub security: SecurityChecks,
+    pub scores: EndpointScores,
+}
+
+/// POST /check/endpoint - Run the endpoint and security checks on one URL
+///
+/// Uses the validators, probe policy and SSRF guard of an audit, without an
+/// agent: nothing is read from a registry, stored or published. A check
+/// counts against the usage quota like an audit, at most
+/// ENDPOINT_CHECK_CONCURRENCY run at once, and it stops before the request
+/// timeout would cut it off.
+pub async fn check_endpoint(
     State(state): State<Arc<AppState>>,
-    Json(request): Json<AuditRequest>,
-) -> Result<(StatusCode, Json<AuditCreatedResponse>), WatchyError> {
-    // Validate agent_id
-    if request.agent_id == 0 {
+    Extension(tenant): Extension<Tenant>,
+    ValidatedJson(request): ValidatedJson<CheckEndpointRequest>,
+) -> Result<Json<CheckEndpointResponse>, WatchyError> {
+    if let ServiceType::Unknown(other) = ServiceType::from(request.service.as_str()) {
+        return Err(WatchyError::InvalidRequest(format!(
+            "Unsupported service '{}' (expected A2A, MCP, OASF, gRPC or web)",
+            other
+        )));
+    }
+    const SCHEMES: [&str; 6] = ["http://", "https://", "ws://", "wss://", "grpc://", "grpcs://"];
+    if !SCHEMES.iter().any(|scheme| request.url.starts_with(scheme)) {
         return Err(WatchyError::InvalidRequest(
-            "agent_id must be greater than 0".to_string(),
+            "url must be an http(s), ws(s) or grpc(s) URL".to_string(),
         ));
     }
 
+    let service = Service {
+        name: request.service.clone(),
+        endpoint: Some(request.url.clone()),
+        version: request.version,
+        a2a_skills: request.a2a_skills,
+        mcp_tools: request.mcp_tools,
+        mcp_prompts: vec![],
+        grpc_services: vec![],
+        skills: vec![],
+        domains: vec![],
+    };
+    let _slot = state
+        .endpoint_checks
+        .try_acquire()
+        .map_err(|_| WatchyError::Overloaded)?;
+    let settings = state.live.get();
+    let timeout_secs = endpoint_check_timeout(
+        &state.config,
+        settings.limits.request_timeout_secs,
+        request.timeout_secs,
+    );
+    let now = chrono::Utc::now().timestamp() as u64;
+    let usage = usage::reserve(&state.audit_store, &settings.usage, &tenant, now).await?;
+    usage::meter_check(&state, usage, &request.url);
+
+    info!("Checking {} endpoint {}", request.service, request.url);
+    let mut report = AuditEngine::new(state.clone())
+        .check_endpoint(service, timeout_secs)
+        .await;
+    let endpoint = report.checks.endpoints.pop().ok_or_else(|| {
+        WatchyError::Internal(format!("No endpoint check ran for {}", request.url))
+    })?;
+
+    Ok(Json(CheckEndpointResponse {
+        service: request.service,
+        url: request.url,
+        endpoint,
+        security: report.checks.security,
+        scores: EndpointScores {
+            availability: report.scores.endpoint_availability,
+            performance: report.scores.endpoint_performance,
+            security: report.scores.security,
+        },
+    }))
+}
+
+/// Deadline of an endpoint check in seconds: the audit timeout, cut short of
+/// the request timeout so the answer still gets out
+fn endpoint_check_timeout(
+    config: &Config,
+    request_timeout_secs: u64,
+    requested: Option<u64>,
+) -> u64 {
+    let timeout_secs = config.audit_timeout(requested);
+    match request_timeout_secs {
+        0 => timeout_secs,
+        request_timeout => timeout_secs.min(request_timeout.saturating_sub(1).max(1)),
+    }
+}
+
+/// POST /audit
+pub async fn request_audit(
+    State(state): State<Arc<AppState>>,
+    Extension(tenant): Extension<Tenant>,
+    request_id: Option<Extension<RequestId>>,
+    headers: HeaderMap,
+    ValidatedJson(request): ValidatedJson<AuditRequest>,
+) -> Result<(StatusCode, Json<AuditCreatedResponse>), WatchyError> {
+    let request_id = request_id.map(|Extension(RequestId(id))| id);
+    let requester = receipts::requester(&state.config, &headers);
+    let created = create_audit(&state, tenant, request_id, requester, request).await?;
+    Ok((StatusCode::ACCEPTED, Json(created)))
+}
+
+/// Check and start a requested audit, counting it against the tenant's quota
+///
+/// Shared by `POST /audit` and the A2A facade (see `api::a2a`).
+pub async fn create_audit(
+    state: &Arc<AppState>,
+    tenant: Tenant,
+    request_id: Option<String>,
+    requester: Option<String>,
+    request: AuditRequest,
+) -> Result<AuditCreatedResponse, WatchyError> {
+    queue_audit(state, tenant, request_id, requester, request, None, None).await
+}
+
+/// `create_audit`, recording the batch the audit belongs to, or auditing an
+/// unregistered agent from its metadata URI
+async fn queue_audit(
+    state: &Arc<AppState>,
+    tenant: Tenant,
+    request_id: Option<String>,
+    requester: Option<String>,
+    request: AuditRequest,
+    batch_id: Option<String>,
+    metadata_uri: Option<String>,
+) -> Result<AuditCreatedResponse, WatchyError> {
+    let timeout_secs = state.config.audit_timeout(request.timeout_secs);
+    let lang = request.lang.unwrap_or(state.config.report_lang);
+
     // Resolve chain_id (use request or default)
     let chain_id = request.chain_id.unwrap_or(state.config.default_chain_id);
+    let chain = audit_chain(state, chain_id)?;
+
+    match &metadata_uri {
+        Some(metadata_uri) => info!(
+            "Audit requested for unregistered agent {} on {} (chain_id: {})",
+            metadata_uri, chain.name, chain_id
+        ),
+        None => info!(
+            "Audit requested for agent {} on {} (chain_id: {}, registry: {})",
+            request.agent_id,
+            chain.name,
+            chain_id,
+            chain.registry_address.unwrap()
+        ),
+    }
+
+    let now = chrono::Utc::now().timestamp() as u64;
+    let usage = usage::reserve(&state.audit_store, &state.live.get().usage, &tenant, now).await?;
+    let agent_id = metadata_uri.is_none().then_some(request.agent_id);
+    let params = AuditParams {
+        timeout_secs,
+        lang,
+        requester: requester.clone(),
+        batch_id,
+        metadata_uri,
+    };
+    let audit_id = spawn_audit(state, agent_id, chain_id, params, tenant.0, request_id).await;
+    usage::meter(state, usage, &audit_id, agent_id, chain_id);
+    let receipt = Receipt::requested(&audit_id, agent_id, chain_id, 1, requester, now);
+    let receipt = sign_receipt(state, receipt).await;
+
+    Ok(AuditCreatedResponse {
+        audit_id,
+        chain_id,
+        chain_name: chain.name.to_string(),
+        status: AuditStatus::Pending,
+        created_at: now,
+        estimated_completion: now + timeout_secs, // Audit phases stop at the deadline
+        receipt,
+    })
+}
 
+/// A chain audits may be requested on: supported, EVM, allowed and with a registry
+fn audit_chain(state: &AppState, chain_id: u64) -> Result<&'static ChainConfig, WatchyError> {
     // Look up chain config
     let chain = get_chain(chain_id).ok_or_else(|| {
         WatchyError::InvalidRequest(format!(
@@ -110,12 +713,10 @@ pub async fn request_audit(
         )));
     }
 
-    // Check if chain is allowed (testnet-only mode)
-    if !is_chain_allowed(chain_id) {
-        return Err(WatchyError::InvalidRequest(format!(
-            "Chain {} ({}) is not enabled. Allowed chains: Base (8453), Base Sepolia (84532), Sepolia (11155111)",
-            chain.name, chain_id
-        )));
+    // Check if chain is allowed (ALLOWED_CHAINS)
+    let settings = state.live.get();
+    if !settings.chain_allowed(chain_id) {
+        return Err(chain_not_allowed(&settings, chain_id));
     }
 
     // Check if registry is deployed on this chain
@@ -125,58 +726,428 @@ pub async fn request_audit(
             chain.name, chain_id
         )));
     }
+    Ok(chain)
+}
+
+/// Most agents `POST /audit/by-owner` queues at once
+const MAX_BATCH_AGENTS: usize = 100;
+
+/// Concurrent `ownerOf` checks of a by-owner batch
+const OWNER_CHECK_CONCURRENCY: usize = 8;
+
+/// Request body for auditing every agent of an owner
+#[derive(Debug, Deserialize, Validate)]
+pub struct AuditByOwnerRequest {
+    /// Owner address
+    #[validate(length(min = 1, message = "owner must not be empty"))]
+    pub owner: String,
+    /// Chain ID (default: config default_chain_id)
+    #[serde(default, deserialize_with = "caip::deserialize_optional_chain_id")]
+    pub chain_id: Option<u64>,
+    /// Deadline of each audit in seconds (capped by AUDIT_MAX_TIMEOUT_SECS)
+    #[validate(range(min = 1, message = "timeout_secs must be greater than 0"))]
+    pub timeout_secs: Option<u64>,
+    /// Report language (en, zh, es); REPORT_LANG when omitted
+    pub lang: Option<Lang>,
+}
+
+#[derive(Serialize)]
+pub struct BatchAudit {
+    pub agent_id: u64,
+    pub audit_id: String,
+}
 
+#[derive(Serialize)]
+pub struct BatchSkipped {
+    pub agent_id: u64,
+    pub error: String,
+}
+
+#[derive(Serialize)]
+pub struct AuditBatchCreatedResponse {
+    pub batch_id: String,
+    pub owner: String,
+    pub chain_id: u64,
+    pub chain_name: String,
+    /// Agents the owner holds according to the registry (`balanceOf`)
+    pub owned: u64,
+    pub audits: Vec<BatchAudit>,
+    /// Owned agents whose audit couldn't be queued (e.g. quota exceeded)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<BatchSkipped>,
+    /// Owned agents within the batch cap the indexer doesn't attribute to the
+    /// owner (not indexed yet)
+    pub not_found: u64,
+    /// The owner holds more agents than a batch covers (`MAX_BATCH_AGENTS`)
+    pub truncated: bool,
+}
+
+/// POST /audit/by-owner - Audit every agent an address owns on a chain
+///
+/// The registry can't enumerate an owner's tokens, so agents come from the
+/// indexer's owner index (kept current by Transfer events) and are confirmed
+/// with `ownerOf`; `balanceOf` tells how many weren't found. Each audit counts
+/// against the quota, and all share a batch ID for `GET /audit/batch/:batch_id`.
+pub async fn audit_by_owner(
+    State(state): State<Arc<AppState>>,
+    Extension(tenant): Extension<Tenant>,
+    request_id: Option<Extension<RequestId>>,
+    headers: HeaderMap,
+    ValidatedJson(request): ValidatedJson<AuditByOwnerRequest>,
+) -> Result<(StatusCode, Json<AuditBatchCreatedResponse>), WatchyError> {
+    let chain_id = request.chain_id.unwrap_or(state.config.default_chain_id);
+    let chain = audit_chain(&state, chain_id)?;
+    let owner = Address::from_str(&request.owner)
+        .map_err(|e| WatchyError::InvalidAddress(format!("Invalid owner address: {}", e)))?;
+    let rpc_url = get_rpc_url(chain_id).ok_or_else(|| {
+        WatchyError::InvalidRequest(format!("No RPC URL for chain {}", chain_id))
+    })?;
+    let registry = RegistryClient::new(&rpc_url, chain.registry_address.unwrap())?;
+
+    let owned = registry.balance_of(owner).await?;
+    if owned == 0 {
+        return Err(WatchyError::InvalidRequest(format!(
+            "{} owns no agents on {}",
+            owner, chain.name
+        )));
+    }
+    // The index may lag the chain, so each agent is confirmed before queuing
+    let candidates = state
+        .audit_store
+        .owned_agents(chain_id, &owner.to_string())
+        .await;
+    let mut confirmed = futures_util::stream::iter(candidates)
+        .map(|agent| {
+            let registry = &registry;
+            async move {
+                let holder = registry.owner_of(agent.agent_id).await.ok();
+                (holder == Some(owner)).then_some(agent.agent_id)
+            }
+        })
+        .buffered(OWNER_CHECK_CONCURRENCY)
+        .filter_map(std::future::ready);
+    let mut agent_ids = vec![];
+    while agent_ids.len() < MAX_BATCH_AGENTS {
+        match confirmed.next().await {
+            Some(agent_id) => agent_ids.push(agent_id),
+            None => break,
+        }
+    }
+    if agent_ids.is_empty() {
+        return Err(WatchyError::InvalidRequest(format!(
+            "{} owns {} agents on {}, but none are in the registry index \
+             (the indexer must cover chain {})",
+            owner, owned, chain.name, chain_id
+        )));
+    }
+
+    let batch_id = format!("bat_{}", uuid::Uuid::new_v4().simple());
+    let request_id = request_id.map(|Extension(RequestId(id))| id);
+    let requester = receipts::requester(&state.config, &headers);
     info!(
-        "Audit requested for agent {} on {} (chain_id: {}, registry: {})",
-        request.agent_id,
+        "Queuing {} audits for owner {} on {} as {}",
+        agent_ids.len(),
+        owner,
         chain.name,
-        chain_id,
-        chain.registry_address.unwrap()
+        batch_id
     );
+    let mut audits = vec![];
+    let mut skipped = vec![];
+    for &agent_id in &agent_ids {
+        let audit = AuditRequest {
+            agent_id,
+            chain_id: Some(chain_id),
+            timeout_secs: request.timeout_secs,
+            lang: request.lang,
+        };
+        let queued = queue_audit(
+            &state,
+            tenant.clone(),
+            request_id.clone(),
+            requester.clone(),
+            audit,
+            Some(batch_id.clone()),
+            None,
+        )
+        .await;
+        match queued {
+            Ok(created) => audits.push(BatchAudit {
+                agent_id,
+                audit_id: created.audit_id,
+            }),
+            Err(e) => skipped.push(BatchSkipped {
+                agent_id,
+                error: e.to_string(),
+            }),
+        }
+    }
 
-    // Create job in store
-    let audit_id = state.audit_store.create_job(request.agent_id, chain_id).await;
-    let now = chrono::Utc::now().timestamp() as u64;
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(AuditBatchCreatedResponse {
+            batch_id,
+            owner: owner.to_string(),
+            chain_id,
+            chain_name: chain.name.to_string(),
+            owned,
+            audits,
+            skipped,
+            not_found: owned
+                .min(MAX_BATCH_AGENTS as u64)
+                .saturating_sub(agent_ids.len() as u64),
+            truncated: owned > MAX_BATCH_AGENTS as u64,
+        }),
+    ))
+}
 
+#[derive(Serialize)]
+pub struct AuditBatchResponse {
+    pub batch_id: String,
+    pub total: usize,
+    /// Audits per status
+    pub statuses: BTreeMap<String, usize>,
+    pub audits: Vec<AuditStatusResponse>,
+}
+
+/// GET /audit/batch/:batch_id - Status of every audit in a batch
+pub async fn get_audit_batch(
+    State(state): State<Arc<AppState>>,
+    Extension(tenant): Extension<Tenant>,
+    Path(batch_id): Path<String>,
+) -> Result<Json<AuditBatchResponse>, WatchyError> {
+    let jobs = state.audit_store.batch_jobs(&batch_id, tenant.id()).await;
+    if jobs.is_empty() {
+        return Err(WatchyError::AuditNotFound(batch_id));
+    }
+    let mut statuses = BTreeMap::new();
+    let mut audits = vec![];
+    for job in &jobs {
+        let status = serde_json::to_value(&job.status)
+            .ok()
+            .and_then(|status| status.as_str().map(str::to_string))
+            .unwrap_or_default();
+        *statuses.entry(status).or_insert(0) += 1;
+        audits.push(audit_status(&state, job).await);
+    }
+    Ok(Json(AuditBatchResponse {
+        batch_id,
+        total: jobs.len(),
+        statuses,
+        audits,
+    }))
+}
+
+/// Request body for auditing an agent that isn't registered yet
+#[derive(Debug, Deserialize, Validate)]
+pub struct AuditByUriRequest {
+    /// Metadata URI the agent will register with (https, ipfs, ar or data URI)
+    #[validate(length(min = 1, message = "metadata_uri must not be empty"))]
+    pub metadata_uri: String,
+    /// Chain ID the agent will register on (default: config default_chain_id)
+    #[serde(default, deserialize_with = "caip::deserialize_optional_chain_id")]
+    pub chain_id: Option<u64>,
+    /// Overall audit deadline in seconds (capped by AUDIT_MAX_TIMEOUT_SECS)
+    #[validate(range(min = 1, message = "timeout_secs must be greater than 0"))]
+    pub timeout_secs: Option<u64>,
+    /// Report language (en, zh, es); REPORT_LANG when omitted
+    pub lang: Option<Lang>,
+}
+
+/// POST /audit/by-uri - Audit an agent before registering it
+///
+/// Runs every phase but the on-chain one (reported as skipped) against the
+/// given metadata. The job has no agent ID, so it stays out of every agent's
+/// history; its report is stored but never published, submitted as feedback
+/// or indexed.
+pub async fn audit_by_uri(
+    State(state): State<Arc<AppState>>,
+    Extension(tenant): Extension<Tenant>,
+    request_id: Option<Extension<RequestId>>,
+    headers: HeaderMap,
+    ValidatedJson(request): ValidatedJson<AuditByUriRequest>,
+) -> Result<(StatusCode, Json<AuditCreatedResponse>), WatchyError> {
+    let request_id = request_id.map(|Extension(RequestId(id))| id);
+    let requester = receipts::requester(&state.config, &headers);
+    // The agent ID is unused: the job is created without one
+    let audit = AuditRequest {
+        agent_id: 0,
+        chain_id: request.chain_id,
+        timeout_secs: request.timeout_secs,
+        lang: request.lang,
+    };
+    let created = queue_audit(
+        &state,
+        tenant,
+        request_id,
+        requester,
+        audit,
+        None,
+        Some(request.metadata_uri),
+    )
+    .await?;
+    Ok((StatusCode::ACCEPTED, Json(created)))
+}
+
+/// Create an audit job and run it in the background; returns the audit ID
+///
+/// The task runs in an `audit` span, a child of the current (request) span,
+/// so its logs carry the audit ID and the request ID.
+pub async fn spawn_audit(
+    state: &Arc<AppState>,
+    agent_id: Option<u64>,
+    chain_id: u64,
+    params: AuditParams,
+    tenant: Option<String>,
+    request_id: Option<String>,
+) -> String {
+    let audit_id = state
+        .audit_store
+        .create_job(
+            agent_id,
+            chain_id,
+            tenant.as_deref(),
+            request_id.as_deref(),
+            Some(params.clone()),
+        )
+        .await;
     info!("Created audit job: {}", audit_id);
 
-    // Spawn background task to run the audit
-    let state_clone = state.clone();
-    let audit_id_clone = audit_id.clone();
-    let agent_id = request.agent_id;
+    start_audit(state, &audit_id, agent_id, chain_id, params, tenant);
+    audit_id
+}
+
+/// Run a pending job's audit in the background
+fn start_audit(
+    state: &Arc<AppState>,
+    audit_id: &str,
+    agent_id: Option<u64>,
+    chain_id: u64,
+    params: AuditParams,
+    tenant: Option<String>,
+) {
+    let state = state.clone();
+    let job_id = audit_id.to_string();
+    let span = info_span!("audit", audit_id = %audit_id, agent_id, chain_id);
+    let AuditParams {
+        timeout_secs,
+        lang,
+        metadata_uri,
+        ..
+    } = params;
+    let audits = state.audits.clone();
+    audits.spawn(
+        async move {
+            run_audit_job(
+                state,
+                job_id,
+                agent_id,
+                chain_id,
+                timeout_secs,
+                lang,
+                tenant,
+                metadata_uri,
+            )
+            .await;
+        }
+        .instrument(span),
+    );
+}
 
-    tokio::spawn(async move {
-        run_audit_job(state_clone, audit_id_clone, agent_id, chain_id).await;
+/// POST /audit/:audit_id/retry
+///
+/// Re-runs a failed or partially completed audit under the same ID, with the
+/// parameters it was requested with. The job's previous outcome is kept in
+/// its `attempts`. Counts against the usage quota like a new audit.
+pub async fn retry_audit(
+    State(state): State<Arc<AppState>>,
+    Extension(tenant): Extension<Tenant>,
+    headers: HeaderMap,
+    Path(audit_id): Path<String>,
+) -> Result<(StatusCode, Json<AuditCreatedResponse>), WatchyError> {
+    let job = state
+        .audit_store
+        .get_tenant_job(&audit_id, tenant.id())
+        .await
+        .ok_or_else(|| WatchyError::AuditNotFound(audit_id.clone()))?;
+    if !job.retryable() {
+        return Err(WatchyError::InvalidRequest(format!(
+            "Audit {} is {:?}; only failed or partially completed audits can be retried",
+            audit_id, job.status
+        )));
+    }
+    let settings = state.live.get();
+    if !settings.chain_allowed(job.chain_id) {
+        return Err(chain_not_allowed(&settings, job.chain_id));
+    }
+    // Jobs created before parameters were recorded ran with the defaults
+    let params = job.params.clone().unwrap_or(AuditParams {
+        timeout_secs: state.config.audit_timeout(None),
+        lang: state.config.report_lang,
+        requester: None,
+        batch_id: None,
+        metadata_uri: None,
     });
 
+    let now = chrono::Utc::now().timestamp() as u64;
+    let usage = usage::reserve(&state.audit_store, &settings.usage, &tenant, now).await?;
+    let Some(job) = state.audit_store.retry_job(&audit_id).await else {
+        // Retried concurrently
+        usage::release(&state.audit_store, &usage).await;
+        return Err(WatchyError::InvalidRequest(format!(
+            "Audit {} can no longer be retried",
+            audit_id
+        )));
+    };
+    info!("Retrying audit {} (attempt {})", audit_id, job.attempt);
+    start_audit(&state, &audit_id, job.agent_id, job.chain_id, params.clone(), job.tenant);
+    usage::meter(&state, usage, &audit_id, job.agent_id, job.chain_id);
+    let requester = receipts::requester(&state.config, &headers);
+    let receipt =
+        Receipt::requested(&audit_id, job.agent_id, job.chain_id, job.attempt, requester, now);
+    let receipt = sign_receipt(&state, receipt).await;
+
     Ok((
         StatusCode::ACCEPTED,
         Json(AuditCreatedResponse {
             audit_id,
-            chain_id,
-            chain_name: chain.name.to_string(),
+            chain_id: job.chain_id,
+            chain_name: get_chain(job.chain_id)
+                .map(|chain| chain.name.to_string())
+                .unwrap_or_default(),
             status: AuditStatus::Pending,
             created_at: now,
-            estimated_completion: now + 30, // ~30 seconds estimate
+            estimated_completion: now + params.timeout_secs,
+            receipt,
         }),
     ))
 }
 
 /// Background job runner for audits
 ///
-/// Flow (Option A):
+/// Flow:
 /// 1. Run audit → get report
-/// 2. Generate markdown report
-/// 3. Upload MD to Arweave → get md_arweave_url
-/// 4. Add MD URL to report (report_markdown_url)
-/// 5. Sign the JSON report
-/// 6. Upload JSON to Arweave → get json_arweave_url
-/// 7. Submit on-chain feedback with json_arweave_url as feedbackURI
-async fn run_audit_job(state: Arc<AppState>, audit_id: String, agent_id: u64, chain_id: u64) {
-    info!(
-        "Starting audit job {} for agent {} on chain {}",
-        audit_id, agent_id, chain_id
-    );
+/// 2. Publish MD + signed JSON reports and submit on-chain feedback (see `publish`)
+/// 3. Store the result
+///
+/// Audits of unregistered agents (no `agent_id`) are only stored, as are
+/// retries of an audit whose report an earlier attempt already published.
+#[allow(clippy::too_many_arguments)]
+async fn run_audit_job(
+    state: Arc<AppState>,
+    audit_id: String,
+    agent_id: Option<u64>,
+    chain_id: u64,
+    timeout_secs: u64,
+    lang: Lang,
+    tenant: Option<String>,
+    metadata_uri: Option<String>,
+) {
+    let agent = match agent_id {
+        Some(agent_id) => format!("agent {}", agent_id),
+        None => "unregistered agent".to_string(),
+    };
+    info!("Starting audit job {} for {} on chain {}", audit_id, agent, chain_id);
+    let registered = agent_id.is_some();
 
     // Update status to in_progress
     state
@@ -184,11 +1155,43 @@ async fn run_audit_job(state: Arc<AppState>, audit_id: String, agent_id: u64, ch
         .update_status(&audit_id, AuditStatus::InProgress)
         .await;
 
+    // A retry mustn't publish (and submit feedback) for the same audit twice
+    let published = state
+        .audit_store
+        .get_job(&audit_id)
+        .await
+        .and_then(|job| job.published_attempt().and_then(|attempt| attempt.report_uri.clone()));
+
+    let events = EventLog::new(state.clone(), &audit_id);
+    events
+        .emit(
+            EventKind::AuditStarted,
+            format!("Audit of {} on chain {} started", agent, chain_id),
+        )
+        .await;
+
+    // Signer of this report and its feedback, per SIGNER_ROTATION
+    let retired = state.audit_store.retired_signers().await;
+    let signer = state
+        .config
+        .wallet
+        .select_signer(chain_id, agent_id.unwrap_or_default(), &retired)
+        .cloned();
+    if signer.is_none() && state.config.wallet.can_sign() {
+        warn!("All signers are retired, audit {} won't be published", audit_id);
+    }
+
     // Create audit engine and request
-    let engine = AuditEngine::new(state.clone());
+    let engine = AuditEngine::new(state.clone())
+        .with_events(events.clone())
+        .with_tenant(tenant.clone())
+        .with_signer(signer.clone())
+        .with_metadata_uri(metadata_uri);
     let request = AuditRequest {
-        agent_id,
+        agent_id: agent_id.unwrap_or_default(),
         chain_id: Some(chain_id),
+        timeout_secs: Some(timeout_secs),
+        lang: Some(lang),
     };
 
     // Run the audit
@@ -199,143 +1202,59 @@ async fn run_audit_job(state: Arc<AppState>, audit_id: String, agent_id: u64, ch
                 audit_id, report.scores.overall
             );
 
-            // Fetch metadata for the report (we need the name)
-            let agent_metadata = metadata::fetch_metadata(
-                &state.http_client,
-                &report.agent.metadata_uri,
-            )
-            .await
-            .ok();
-
-            // Upload to Arweave and submit on-chain feedback (if private key is configured)
-            if let Some(private_key) = state.config.private_key() {
-                match IrysClient::new(Some(private_key)) {
-                    Ok(irys) => {
-                        let md_filename = format!("watchy-audit-{}-{}.md", agent_id, audit_id);
-                        let json_filename = format!("watchy-audit-{}-{}.json", agent_id, audit_id);
-
-                        // Step 1: Generate and upload Markdown FIRST
-                        let markdown = generate_markdown_report(&report, agent_metadata.as_ref());
-                        match irys.upload_markdown(&markdown, &md_filename).await {
-                            Ok(md_result) => {
-                                info!("Markdown uploaded to Arweave: {}", md_result.arweave_url);
-                                // Step 2: Add MD URL to report
-                                report.set_markdown_url(&md_result.arweave_url);
-                            }
-                            Err(e) => {
-                                error!("Failed to upload MD to Irys: {}", e);
-                            }
-                        }
-
-                        // Step 3: Serialize report to JSON (now includes MD URL)
-                        match serde_json::to_value(&report) {
-                            Ok(mut report_json) => {
-                                // Step 4: Sign the report
-                                match sign_report(&report_json, private_key).await {
-                                    Ok(signature) => {
-                                        info!(
-                                            "Report signed: {}...{}",
-                                            &signature[..10],
-                                            &signature[signature.len() - 8..]
-                                        );
-
-                                        // Add signature to JSON
-                                        if let Some(obj) = report_json.as_object_mut() {
-                                            obj.insert(
-                                                "signature".to_string(),
-                                                serde_json::json!(signature),
-                                            );
-                                        }
-
-                                        // Step 5: Upload signed JSON to Arweave
-                                        match irys.upload_json(&report_json, &json_filename).await {
-                                            Ok(json_result) => {
-                                                info!(
-                                                    "JSON report uploaded to Arweave: {}",
-                                                    json_result.arweave_url
-                                                );
-                                                report.set_json_url(&json_result.arweave_url);
-
-                                                // Step 6: Submit on-chain feedback
-                                                // IMPORTANT: Use report_json (the uploaded JSON) for hash computation
-                                                // to ensure feedbackHash matches the content at feedbackURI
-                                                let chain = get_chain(chain_id);
-                                                let rpc_url = get_rpc_url(chain_id);
-
-                                                if let (Some(chain), Some(rpc), Some(rep_addr)) =
-                                                    (chain, rpc_url, chain.and_then(|c| c.reputation_address))
-                                                {
-                                                    info!(
-                                                        "Submitting on-chain feedback to {} ({})",
-                                                        chain.name, rep_addr
-                                                    );
-
-                                                    match ReputationClient::new(&rpc, rep_addr, Some(private_key)) {
-                                                        Ok(rep_client) => {
-                                                            let endpoint = report.endpoint.as_deref();
-
-                                                            match rep_client
-                                                                .submit_feedback(
-                                                                    agent_id,
-                                                                    report.scores.overall,
-                                                                    "starred",
-                                                                    "",
-                                                                    endpoint,
-                                                                    &json_result.arweave_url,
-                                                                    &report_json, // Use the exact JSON that was uploaded
-                                                                )
-                                                                .await
-                                                            {
-                                                                Ok(tx_hash) => {
-                                                                    info!(
-                                                                        "On-chain feedback submitted: {} (tx: {})",
-                                                                        json_result.arweave_url, tx_hash
-                                                                    );
-                                                                    report.set_feedback_tx(chain_id, &tx_hash);
-                                                                }
-                                                                Err(e) => {
-                                                                    error!("Failed to submit on-chain feedback: {}", e);
-                                                                }
-                                                            }
-                                                        }
-                                                        Err(e) => {
-                                                            error!("Failed to create reputation client: {}", e);
-                                                        }
-                                                    }
-                                                } else {
-                                                    info!(
-                                                        "No reputation registry on chain {}, skipping on-chain feedback",
-                                                        chain_id
-                                                    );
-                                                }
-                                            }
-                                            Err(e) => {
-                                                error!("Failed to upload JSON to Irys: {}", e);
-                                            }
-                                        }
-                                    }
-                                    Err(e) => {
-                                        error!("Failed to sign report: {}", e);
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                error!("Failed to serialize report: {}", e);
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        error!("Failed to create Irys client: {}", e);
-                    }
+            // Metadata for the report (we need the name): the audited copy, or a re-fetch
+            let agent_metadata = match engine.audited_metadata().await {
+                Some(audited) => Some(audited),
+                None => match state.http.guard.check(&report.agent.metadata_uri).await {
+                    Ok(()) => metadata::fetch_metadata(
+                        &state.http.metadata,
+                        &report.agent.metadata_uri,
+                        &events,
+                    )
+                    .await
+                    .ok(),
+                    Err(_) => None,
+                },
+            };
+
+            // Upload reports and submit on-chain feedback (if private key is configured)
+            if !registered {
+                info!("Agent not registered, skipping report upload and on-chain feedback");
+            } else if let Some(uri) = &published {
+                info!(
+                    "Audit {} was already published as {}, skipping report upload and on-chain feedback",
+                    audit_id, uri
+                );
+            } else if let Some(signer) = &signer {
+                if let Err(e) = publish_report(
+                    &state,
+                    &mut report,
+                    agent_metadata.as_ref(),
+                    &audit_id,
+                    chain_id,
+                    state.config.wallet.signing_keys(signer, &retired),
+                    &events,
+                )
+                .await
+                {
+                    error!("Failed to publish report for audit {}: {}", audit_id, e);
                 }
             } else {
-                info!("No private key configured, skipping Arweave upload and on-chain feedback");
+                info!("No private key configured, skipping report upload and on-chain feedback");
             }
 
-            // Optional IPFS upload (legacy, if configured separately)
-            if let Some(ref api_key) = state.config.ipfs_api_key {
+            // Optional IPFS copy (legacy, when IPFS isn't already the primary store)
+            if let (true, None, ReportStore::Arweave, Some(api_key)) = (
+                registered,
+                &published,
+                state.config.report_store,
+                &state.config.ipfs_api_key,
+            )
+            {
                 let ipfs_client =
-                    IpfsClient::new(state.config.ipfs_api_url.clone(), Some(api_key.clone()));
+                    IpfsClient::new(state.config.ipfs_api_url.clone(), Some(api_key.clone()))
+                        .with_http_client(state.http.upload.clone())
+                        .with_gateway(&state.config.ipfs_gateway);
 
                 match serde_json::to_value(&report) {
                     Ok(report_json) => {
@@ -343,9 +1262,12 @@ async fn run_audit_job(state: Arc<AppState>, audit_id: String, agent_id: u64, ch
                         match ipfs_client.upload_json(&report_json, &filename).await {
                             Ok(cid) => {
                                 info!("Audit report uploaded to IPFS: {}", cid);
+                                events.upload("ipfs", &filename, Ok(&cid)).await;
+                                report.set_ipfs_location(&ipfs_client.gateway_url(&cid), &cid);
                             }
                             Err(e) => {
                                 error!("Failed to upload to IPFS: {}", e);
+                                events.upload("ipfs", &filename, Err(e.to_string())).await;
                             }
                         }
                     }
@@ -355,12 +1277,47 @@ async fn run_audit_job(state: Arc<AppState>, audit_id: String, agent_id: u64, ch
                 }
             }
 
-            // Store result
-            state.audit_store.set_result(&audit_id, report).await;
+            events
+                .emit(
+                    EventKind::AuditCompleted,
+                    format!("Audit completed with score {}", report.scores.overall),
+                )
+                .await;
+
+            // Store result, then tell the owner's subscribers
+            let previous_score = match agent_id {
+                Some(agent_id) => state
+                    .audit_store
+                    .latest_completed(agent_id, chain_id, tenant.as_deref())
+                    .await
+                    .and_then(|job| job.result)
+                    .map(|previous| previous.scores.overall),
+                None => None,
+            };
+            let artifacts = engine.artifacts().await;
+            state.audit_store.set_result(&audit_id, report.clone(), &artifacts).await;
+            // Tenants' and unregistered agents' audits stay out of the shared
+            // skill index and notifications
+            let (None, Some(agent_id)) = (&tenant, agent_id) else {
+                return;
+            };
+            let now = chrono::Utc::now().timestamp() as u64;
+            let mut entry =
+                search::index_entry(&audit_id, chain_id, &report, agent_metadata.as_ref(), now);
+            entry.consecutive_failures = state.breaker.record(chain_id, agent_id, &report, now);
+            state.audit_store.index_agent(&entry).await;
+            subscriptions::notify(&state, &audit_id, chain_id, &report, previous_score).await;
         }
         Err(e) => {
             error!("Audit {} failed: {}", audit_id, e);
-            state.audit_store.set_error(&audit_id, e.to_string()).await;
+            events
+                .emit(EventKind::AuditFailed, format!("Audit failed: {}", e))
+                .await;
+            state.audit_store.set_error(&audit_id, (&e).into()).await;
+            // No verdict on the agent, but its automatic audit is over
+            if let Some(agent_id) = agent_id {
+                state.breaker.finish(chain_id, agent_id);
+            }
         }
     }
 }
@@ -369,7 +1326,9 @@ async fn run_audit_job(state: Arc<AppState>, audit_id: String, agent_id: u64, ch
 #[derive(Serialize)]
 pub struct AuditStatusResponse {
     pub audit_id: String,
-    pub agent_id: u64,
+    /// `None` for an unregistered agent (`POST /audit/by-uri`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent_id: Option<u64>,
     pub status: AuditStatus,
     pub created_at: u64,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -377,13 +1336,28 @@ pub struct AuditStatusResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub result: Option<AuditResultSummary>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub error: Option<String>,
+    pub error: Option<AuditError>,
+    /// `X-Request-Id` of the request that created the audit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// Current attempt, from 1 (see `POST /audit/:audit_id/retry`)
+    pub attempt: u32,
+    /// Attempts replaced by retries, oldest first
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub attempts: Vec<AuditAttempt>,
+    /// Signed receipt that the audit completed (none while it runs or without a wallet)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub receipt: Option<Receipt>,
 }
 
 #[derive(Serialize)]
 pub struct AuditResultSummary {
     pub scores: ScoresSummary,
     pub issues_count: IssuesCount,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<u64>,
+    /// Past `valid_until`; the score shouldn't be relied on
+    pub stale: bool,
 }
 
 #[derive(Serialize)]
@@ -405,6 +1379,7 @@ pub struct IssuesCount {
 
 impl From<&AuditJob> for AuditStatusResponse {
     fn from(job: &AuditJob) -> Self {
+        let now = chrono::Utc::now().timestamp() as u64;
         let result = job.result.as_ref().map(|r| {
             let issues = r.count_issues();
             AuditResultSummary {
@@ -421,6 +1396,8 @@ impl From<&AuditJob> for AuditStatusResponse {
                     warning: issues.warning,
                     info: issues.info,
                 },
+                valid_until: r.valid_until,
+                stale: r.is_stale(now),
             }
         });
 
@@ -432,6 +1409,10 @@ impl From<&AuditJob> for AuditStatusResponse {
             completed_at: job.completed_at,
             result,
             error: job.error.clone(),
+            request_id: job.request_id.clone(),
+            attempt: job.attempt,
+            attempts: job.attempts.clone(),
+            receipt: None,
         }
     }
 }
@@ -439,41 +1420,567 @@ impl From<&AuditJob> for AuditStatusResponse {
 /// GET /audit/:audit_id
 pub async fn get_audit(
     State(state): State<Arc<AppState>>,
+    Extension(tenant): Extension<Tenant>,
     Path(audit_id): Path<String>,
 ) -> Result<Json<AuditStatusResponse>, WatchyError> {
     info!("Getting audit {}", audit_id);
 
-    match state.audit_store.get_job(&audit_id).await {
-        Some(job) => Ok(Json(AuditStatusResponse::from(&job))),
-        None => Err(WatchyError::AuditNotFound(audit_id)),
-    }
+    let job = state
+        .audit_store
+        .get_tenant_job(&audit_id, tenant.id())
+        .await
+        .ok_or(WatchyError::AuditNotFound(audit_id))?;
+    Ok(Json(audit_status(&state, &job).await))
+}
+
+/// Status of a job, with its completion receipt once it has finished
+pub async fn audit_status(state: &AppState, job: &AuditJob) -> AuditStatusResponse {
+    let mut response = AuditStatusResponse::from(job);
+    if let Some(receipt) = Receipt::completed(job) {
+        response.receipt = sign_receipt(state, receipt).await;
+    }
+    response
+}
+
+/// Response for an audit's event log
+#[derive(Serialize)]
+pub struct AuditEventsResponse {
+    pub audit_id: String,
+    pub status: AuditStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    pub events: Vec<AuditEvent>,
+}
+
+/// GET /audit/:audit_id/events
+pub async fn get_audit_events(
+    State(state): State<Arc<AppState>>,
+    Extension(tenant): Extension<Tenant>,
+    Path(audit_id): Path<String>,
+) -> Result<Json<AuditEventsResponse>, WatchyError> {
+    info!("Getting events for audit {}", audit_id);
+
+    let job = state
+        .audit_store
+        .get_tenant_job(&audit_id, tenant.id())
+        .await
+        .ok_or_else(|| WatchyError::AuditNotFound(audit_id.clone()))?;
+
+    Ok(Json(AuditEventsResponse {
+        events: state.audit_store.get_events(&audit_id).await,
+        audit_id,
+        status: job.status,
+        request_id: job.request_id,
+    }))
+}
+
+/// Response for an audit's stored endpoint responses
+#[derive(Serialize)]
+pub struct AuditArtifactsResponse {
+    pub audit_id: String,
+    pub status: AuditStatus,
+    pub artifacts: Vec<EndpointArtifact>,
+}
+
+/// GET /audit/:audit_id/artifacts
+///
+/// The A2A / MCP / OASF responses the audit judged, kept when
+/// `STORE_ENDPOINT_ARTIFACTS` is on. Empty for audits run without it.
+/// Response bodies are only shown to admins and to the tenant that owns the
+/// audit; everyone else gets hashes and sizes.
+pub async fn get_audit_artifacts(
+    State(state): State<Arc<AppState>>,
+    Extension(tenant): Extension<Tenant>,
+    Path(audit_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<AuditArtifactsResponse>, WatchyError> {
+    info!("Getting artifacts for audit {}", audit_id);
+
+    let job = state
+        .audit_store
+        .get_tenant_job(&audit_id, tenant.id())
+        .await
+        .ok_or_else(|| WatchyError::AuditNotFound(audit_id.clone()))?;
+
+    let mut artifacts = state.audit_store.artifacts(&audit_id).await;
+    if job.tenant.is_none() && !api::middleware::is_admin(&state, &headers) {
+        for artifact in &mut artifacts {
+            artifact.body = None;
+        }
+    }
+
+    Ok(Json(AuditArtifactsResponse {
+        audit_id,
+        status: job.status,
+        artifacts,
+    }))
+}
+
+/// Response for an audit's timeline
+#[derive(Serialize)]
+pub struct AuditTimelineResponse {
+    pub audit_id: String,
+    pub status: AuditStatus,
+    pub created_at: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<u64>,
+    pub entries: Vec<TimelineEntry>,
+}
+
+/// GET /audit/:audit_id/timeline
+///
+/// State transitions and external call outcomes (RPC and gateway used, upload
+/// locations, feedback tx) with offsets from job creation.
+pub async fn get_audit_timeline(
+    State(state): State<Arc<AppState>>,
+    Extension(tenant): Extension<Tenant>,
+    Path(audit_id): Path<String>,
+) -> Result<Json<AuditTimelineResponse>, WatchyError> {
+    info!("Getting timeline for audit {}", audit_id);
+
+    let job = state
+        .audit_store
+        .get_tenant_job(&audit_id, tenant.id())
+        .await
+        .ok_or_else(|| WatchyError::AuditNotFound(audit_id.clone()))?;
+
+    Ok(Json(AuditTimelineResponse {
+        entries: timeline(job.created_at, &state.audit_store.job_events(&job).await),
+        audit_id,
+        status: job.status,
+        created_at: job.created_at,
+        completed_at: job.completed_at,
+    }))
+}
+
+/// GET /audit/:audit_id/report
+pub async fn get_audit_report(
+    State(state): State<Arc<AppState>>,
+    Extension(tenant): Extension<Tenant>,
+    Path(audit_id): Path<String>,
+) -> Result<Json<serde_json::Value>, WatchyError> {
+    info!("Getting audit report for {}", audit_id);
+
+    match state.audit_store.get_tenant_job(&audit_id, tenant.id()).await {
+        Some(job) => {
+            if let Some(report) = job.result {
+                Ok(Json(serde_json::to_value(report).unwrap_or_default()))
+            } else if job.status == AuditStatus::Failed {
+                Err(WatchyError::Internal(format!(
+                    "Audit failed: {}",
+                    job.error.map(|error| error.message).unwrap_or_default()
+                )))
+            } else {
+                // Still in progress
+                Err(WatchyError::InvalidRequest(
+                    "Audit not yet completed".to_string(),
+                ))
+            }
+        }
+        None => Err(WatchyError::AuditNotFound(audit_id)),
+    }
+}
+
+/// Largest published Markdown report served back
+const MAX_MARKDOWN_BYTES: usize = 2 * 1024 * 1024;
+
+/// Signed link parameters for GET /audit/:audit_id/report.md
+#[derive(Debug, Deserialize)]
+pub struct ReportLinkQuery {
+    /// Unix seconds after which the link stops working
+    pub expires: Option<u64>,
+    pub signature: Option<String>,
+}
+
+/// GET /audit/:audit_id/report.md - Markdown report (API key or signed link)
+///
+/// Serves the published copy; if the audit wasn't published or the store is
+/// unreachable, the report is regenerated from the stored result.
+pub async fn get_audit_markdown(
+    State(state): State<Arc<AppState>>,
+    Path(audit_id): Path<String>,
+    Query(link): Query<ReportLinkQuery>,
+    headers: HeaderMap,
+) -> Result<Response, WatchyError> {
+    let now = chrono::Utc::now().timestamp() as u64;
+    let signed = match (&state.config.report_link_secret, link.expires, &link.signature) {
+        (Some(secret), Some(expires), Some(signature)) => {
+            report_links::verify(secret, &audit_id, expires, signature, now)
+        }
+        _ => false,
+    };
+    let tenant = api::middleware::authenticate(&state, &headers);
+    if !signed && tenant.is_none() {
+        return Err(WatchyError::Unauthorized(
+            "An API key or a valid signed link is required".to_string(),
+        ));
+    }
+
+    // A signed link is for this audit whoever's it is; a key only sees its tenant's
+    let job = state
+        .audit_store
+        .get_job(&audit_id)
+        .await
+        .filter(|job| signed || tenant.as_ref().is_some_and(|t| t.id() == job.tenant.as_deref()))
+        .ok_or_else(|| WatchyError::AuditNotFound(audit_id.clone()))?;
+    let Some(report) = job.result else {
+        return Err(WatchyError::InvalidRequest(
+            "Audit not yet completed".to_string(),
+        ));
+    };
+
+    let markdown = match fetch_published_markdown(&state, &report).await {
+        Some(markdown) => markdown,
+        None => generate_markdown_report(&report, None),
+    };
+    Ok(([(header::CONTENT_TYPE, "text/markdown; charset=utf-8")], markdown).into_response())
+}
+
+async fn fetch_published_markdown(state: &AppState, report: &AuditReport) -> Option<String> {
+    let url = report.report_markdown_url.as_deref()?;
+    let result = async {
+        let response = state.http.metadata.get(url).send().await?.error_for_status()?;
+        read_body_limited(response, MAX_MARKDOWN_BYTES)
+            .await
+            .map_err(|e| WatchyError::StorageError(e.to_string()))
+            .and_then(|body| {
+                String::from_utf8(body).map_err(|e| WatchyError::StorageError(e.to_string()))
+            })
+    };
+    match result.await {
+        Ok(markdown) => Some(markdown),
+        Err(e) => {
+            warn!("Failed to fetch published report {}, regenerating: {}", url, e);
+            None
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReportLinkRequest {
+    /// Link lifetime in seconds (default 15 minutes, at most 24 hours)
+    pub ttl_secs: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct ReportLinkResponse {
+    /// Path and query of the signed link, relative to this service
+    pub path: String,
+    pub expires_at: u64,
+}
+
+/// POST /audit/:audit_id/report.md/link - Short-lived signed link to the Markdown report
+pub async fn create_report_link(
+    State(state): State<Arc<AppState>>,
+    Extension(tenant): Extension<Tenant>,
+    Path(audit_id): Path<String>,
+    Query(request): Query<ReportLinkRequest>,
+) -> Result<Json<ReportLinkResponse>, WatchyError> {
+    let Some(secret) = &state.config.report_link_secret else {
+        return Err(WatchyError::InvalidRequest(
+            "Signed report links are disabled (REPORT_LINK_SECRET is not set)".to_string(),
+        ));
+    };
+    if request.ttl_secs == Some(0) {
+        return Err(WatchyError::InvalidRequest(
+            "ttl_secs must be greater than 0".to_string(),
+        ));
+    }
+    if state
+        .audit_store
+        .get_tenant_job(&audit_id, tenant.id())
+        .await
+        .is_none()
+    {
+        return Err(WatchyError::AuditNotFound(audit_id));
+    }
+
+    let ttl = request
+        .ttl_secs
+        .unwrap_or(report_links::DEFAULT_TTL_SECS)
+        .min(report_links::MAX_TTL_SECS);
+    let expires_at = chrono::Utc::now().timestamp() as u64 + ttl;
+    Ok(Json(ReportLinkResponse {
+        path: report_links::path(secret, &audit_id, expires_at),
+        expires_at,
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct SubscriptionChallengeRequest {
+    pub action: SubscriptionAction,
+    #[serde(flatten)]
+    pub request: SubscriptionRequest,
+}
+
+impl Validate for SubscriptionChallengeRequest {
+    fn validate(&self) -> Result<(), validator::ValidationErrors> {
+        self.request.validate()
+    }
+}
+
+#[derive(Serialize)]
+pub struct SubscriptionChallengeResponse {
+    /// Text to sign with the agent owner's key (`personal_sign`)
+    pub message: String,
+    pub expires_at: u64,
+}
+
+/// POST /subscriptions/challenge - Message the agent owner signs to (un)subscribe
+pub async fn subscription_challenge(
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(body): ValidatedJson<SubscriptionChallengeRequest>,
+) -> Result<Json<SubscriptionChallengeResponse>, WatchyError> {
+    let settings = state.live.get();
+    if !settings.chain_allowed(body.request.chain_id) {
+        return Err(chain_not_allowed(&settings, body.request.chain_id));
+    }
+    if body.action == SubscriptionAction::Subscribe {
+        body.request.check_destination(state.config.smtp.as_ref())?;
+    }
+
+    let expires_at = chrono::Utc::now().timestamp() as u64 + subscriptions::CHALLENGE_TTL_SECS;
+    Ok(Json(SubscriptionChallengeResponse {
+        message: body.request.challenge(body.action, expires_at),
+        expires_at,
+    }))
+}
+
+#[derive(Serialize)]
+pub struct SubscriptionResponse {
+    pub id: String,
+    #[serde(flatten)]
+    pub request: SubscriptionRequest,
+    pub owner: String,
+    pub created_at: u64,
+    /// False until the destination uses its confirmation link
+    pub confirmed: bool,
+    /// Key for checking `X-Watchy-Signature` on webhook bodies
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub webhook_secret: Option<String>,
+}
+
+impl From<Subscription> for SubscriptionResponse {
+    fn from(subscription: Subscription) -> Self {
+        Self {
+            id: subscription.id,
+            request: subscription.request,
+            owner: subscription.owner,
+            created_at: subscription.created_at,
+            confirmed: subscription.confirmed,
+            webhook_secret: subscription.webhook_secret,
+        }
+    }
+}
+
+/// POST /subscriptions - Register a webhook or email with a signed challenge
+///
+/// The destination is sent a confirmation link and gets no notifications
+/// until it's used.
+pub async fn create_subscription(
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(signed): ValidatedJson<SignedSubscriptionRequest>,
+) -> Result<(StatusCode, Json<SubscriptionResponse>), WatchyError> {
+    let settings = state.live.get();
+    if !settings.chain_allowed(signed.request.chain_id) {
+        return Err(chain_not_allowed(&settings, signed.request.chain_id));
+    }
+    signed.request.check_destination(state.config.smtp.as_ref())?;
+    if signed.request.email.is_some() && state.config.public_url.is_none() {
+        return Err(WatchyError::InvalidRequest(
+            "Email notifications need PUBLIC_URL for their confirmation links".to_string(),
+        ));
+    }
+    if let Some(url) = &signed.request.webhook_url {
+        state
+            .http
+            .guard
+            .check_literal(url)
+            .map_err(WatchyError::InvalidRequest)?;
+    }
+
+    let now = chrono::Utc::now().timestamp() as u64;
+    let owner = subscriptions::verify_owner(&signed, SubscriptionAction::Subscribe, now).await?;
+
+    let request = &signed.request;
+    let id = request.subscription_id();
+    let existing = state
+        .audit_store
+        .subscriptions(request.chain_id, request.agent_id)
+        .await;
+    if let Some(current) = existing.iter().find(|subscription| subscription.id == id) {
+        if current.confirmed {
+            // Same destination, possibly a new owner or threshold: keep its secrets
+            let subscription = Subscription {
+                request: signed.request,
+                owner,
+                ..current.clone()
+            };
+            state.audit_store.put_subscription(&subscription).await;
+            return Ok((StatusCode::OK, Json(subscription.into())));
+        }
+        if current.is_live(now) {
+            return Err(WatchyError::InvalidRequest(format!(
+                "Subscription {} is waiting for confirmation",
+                id
+            )));
+        }
+    }
+    let live = existing
+        .iter()
+        .filter(|subscription| subscription.id != id && subscription.is_live(now))
+        .count();
+    if live >= subscriptions::MAX_SUBSCRIPTIONS_PER_AGENT {
+        return Err(WatchyError::QuotaExceeded(format!(
+            "Agent {} already has {} subscriptions",
+            request.agent_id, live
+        )));
+    }
+
+    let subscription = Subscription::new(signed.request, owner, now);
+    state.audit_store.put_subscription(&subscription).await;
+    if let Err(e) = subscriptions::send_confirmation(&state, &subscription).await {
+        state
+            .audit_store
+            .remove_subscription(
+                subscription.request.chain_id,
+                subscription.request.agent_id,
+                &subscription.id,
+            )
+            .await;
+        return Err(WatchyError::InvalidRequest(format!(
+            "Could not deliver the confirmation: {}",
+            e
+        )));
+    }
+    info!(
+        "Subscription {} pending confirmation for agent {} on chain {}",
+        subscription.id, subscription.request.agent_id, subscription.request.chain_id
+    );
+
+    Ok((StatusCode::CREATED, Json(subscription.into())))
+}
+
+#[derive(Deserialize)]
+pub struct SubscriptionLinkQuery {
+    pub token: String,
+}
+
+/// GET /subscriptions/confirm - Page with a button that confirms a subscription
+///
+/// Link scanners follow GETs, so only the POST changes anything.
+pub async fn confirm_subscription_page(
+    State(state): State<Arc<AppState>>,
+    Query(link): Query<SubscriptionLinkQuery>,
+) -> Result<Html<String>, WatchyError> {
+    let subscription = subscription_for_link(&state, &link.token).await?;
+    Ok(link_page(
+        &format!(
+            "Send Watchy audit results for agent {} on chain {} here?",
+            subscription.request.agent_id, subscription.request.chain_id
+        ),
+        "/subscriptions/confirm",
+        "Confirm",
+        &subscription.link_token(),
+    ))
+}
+
+/// POST /subscriptions/confirm - Confirm a subscription with its link token
+pub async fn confirm_subscription(
+    State(state): State<Arc<AppState>>,
+    Form(link): Form<SubscriptionLinkQuery>,
+) -> Result<Html<String>, WatchyError> {
+    let mut subscription = subscription_for_link(&state, &link.token).await?;
+    let now = chrono::Utc::now().timestamp() as u64;
+    if !subscription.is_live(now) {
+        return Err(WatchyError::InvalidRequest(
+            "Confirmation link has expired; subscribe again".to_string(),
+        ));
+    }
+    if !subscription.confirmed {
+        subscription.confirmed = true;
+        state.audit_store.put_subscription(&subscription).await;
+        info!("Subscription {} confirmed", subscription.id);
+    }
+    Ok(Html("<p>Subscription confirmed.</p>".to_string()))
+}
+
+/// GET /subscriptions/unsubscribe - Page with a button that ends a subscription
+pub async fn unsubscribe_page(
+    State(state): State<Arc<AppState>>,
+    Query(link): Query<SubscriptionLinkQuery>,
+) -> Result<Html<String>, WatchyError> {
+    let subscription = subscription_for_link(&state, &link.token).await?;
+    Ok(link_page(
+        &format!(
+            "Stop Watchy audit results for agent {} on chain {}?",
+            subscription.request.agent_id, subscription.request.chain_id
+        ),
+        "/subscriptions/unsubscribe/link",
+        "Unsubscribe",
+        &subscription.link_token(),
+    ))
+}
+
+/// POST /subscriptions/unsubscribe/link - Remove a subscription with its link token
+pub async fn unsubscribe_with_link(
+    State(state): State<Arc<AppState>>,
+    Form(link): Form<SubscriptionLinkQuery>,
+) -> Result<Html<String>, WatchyError> {
+    let subscription = subscription_for_link(&state, &link.token).await?;
+    state
+        .audit_store
+        .remove_subscription(
+            subscription.request.chain_id,
+            subscription.request.agent_id,
+            &subscription.id,
+        )
+        .await;
+    info!("Subscription {} removed by its destination", subscription.id);
+    Ok(Html("<p>Unsubscribed.</p>".to_string()))
+}
+
+async fn subscription_for_link(
+    state: &AppState,
+    token: &str,
+) -> Result<Subscription, WatchyError> {
+    subscriptions::find_by_token(state, token)
+        .await
+        .ok_or_else(|| WatchyError::Unauthorized("Unknown or expired subscription link".to_string()))
 }
 
-/// GET /audit/:audit_id/report
-pub async fn get_audit_report(
+/// Confirmation form; `token` is the stored subscription's, never the caller's input
+fn link_page(question: &str, action: &str, button: &str, token: &str) -> Html<String> {
+    Html(format!(
+        "<!doctype html><title>Watchy</title><p>{}</p>\
+         <form method=\"post\" action=\"{}\">\
+         <input type=\"hidden\" name=\"token\" value=\"{}\">\
+         <button type=\"submit\">{}</button></form>",
+        question, action, token, button
+    ))
+}
+
+/// POST /subscriptions/unsubscribe - Remove a subscription with a signed challenge
+pub async fn remove_subscription(
     State(state): State<Arc<AppState>>,
-    Path(audit_id): Path<String>,
-) -> Result<Json<serde_json::Value>, WatchyError> {
-    info!("Getting audit report for {}", audit_id);
+    ValidatedJson(signed): ValidatedJson<SignedSubscriptionRequest>,
+) -> Result<StatusCode, WatchyError> {
+    let now = chrono::Utc::now().timestamp() as u64;
+    subscriptions::verify_owner(&signed, SubscriptionAction::Unsubscribe, now).await?;
 
-    match state.audit_store.get_job(&audit_id).await {
-        Some(job) => {
-            if let Some(report) = job.result {
-                Ok(Json(serde_json::to_value(report).unwrap_or_default()))
-            } else if job.status == AuditStatus::Failed {
-                Err(WatchyError::Internal(format!(
-                    "Audit failed: {}",
-                    job.error.unwrap_or_default()
-                )))
-            } else {
-                // Still in progress
-                Err(WatchyError::InvalidRequest(
-                    "Audit not yet completed".to_string(),
-                ))
-            }
-        }
-        None => Err(WatchyError::AuditNotFound(audit_id)),
+    let request = &signed.request;
+    let id = request.subscription_id();
+    if !state
+        .audit_store
+        .remove_subscription(request.chain_id, request.agent_id, &id)
+        .await
+    {
+        return Err(WatchyError::InvalidRequest(format!(
+            "No subscription {} for agent {}",
+            id, request.agent_id
+        )));
     }
+    Ok(StatusCode::NO_CONTENT)
 }
 
 #[derive(Deserialize)]
@@ -489,8 +1996,11 @@ fn default_limit() -> u32 {
 }
 
 /// GET /agents/:registry/:agent_id/audits
+///
+/// `registry` names the chain, by ID or name; audits are listed newest first.
 pub async fn list_agent_audits(
-    State(_state): State<Arc<AppState>>,
+    State(state): State<Arc<AppState>>,
+    Extension(tenant): Extension<Tenant>,
     Path((registry, agent_id)): Path<(String, u64)>,
     Query(query): Query<ListAuditsQuery>,
 ) -> Result<Json<serde_json::Value>, WatchyError> {
@@ -499,26 +2009,182 @@ pub async fn list_agent_audits(
         agent_id, registry, query.limit, query.offset
     );
 
-    // TODO: Implement listing from store (filter by agent_id)
+    let chain = registry_chain(&registry)?;
+    let limit = query.limit.clamp(1, 100);
+    let jobs = state
+        .audit_store
+        .agent_jobs(chain.chain_id, agent_id, tenant.id())
+        .await;
+    let audits: Vec<AuditStatusResponse> = jobs
+        .iter()
+        .skip(query.offset as usize)
+        .take(limit as usize)
+        .map(AuditStatusResponse::from)
+        .collect();
+
     Ok(Json(serde_json::json!({
         "agent_id": agent_id,
         "registry": registry,
-        "audits": [],
-        "total": 0,
-        "limit": query.limit,
+        "chain_id": chain.chain_id,
+        "audits": audits,
+        "total": jobs.len(),
+        "limit": limit,
         "offset": query.offset
     })))
 }
 
+/// Chain a registry path segment names: a chain ID or name, a CAIP-2 chain
+/// (`eip155:8453`) or the chain's CAIP-10 registry
+fn registry_chain(registry: &str) -> Result<&'static ChainConfig, WatchyError> {
+    let unknown = || WatchyError::InvalidRequest(format!("Unknown chain: {}", registry));
+    if registry.matches(':').count() == 2 {
+        let account: AccountId = registry.parse()?;
+        let chain = account.chain.evm_chain_id().and_then(get_chain).ok_or_else(unknown)?;
+        let deployed = chain
+            .registry_address
+            .map(|address| AccountId::eip155_string(chain.chain_id, address));
+        if deployed != Some(account.to_string()) {
+            return Err(WatchyError::InvalidRequest(format!(
+                "{} is not the EIP-8004 registry on {}",
+                registry, chain.name
+            )));
+        }
+        return Ok(chain);
+    }
+    caip::parse_chain_param(registry).ok().and_then(get_chain).ok_or_else(unknown)
+}
+
+#[derive(Deserialize)]
+pub struct ListAgentsQuery {
+    #[serde(default, deserialize_with = "caip::deserialize_optional_chain_id")]
+    pub chain_id: Option<u64>,
+    #[serde(default = "default_agents_limit")]
+    pub limit: usize,
+    #[serde(default)]
+    pub offset: usize,
+}
+
+fn default_agents_limit() -> usize {
+    50
+}
+
+#[derive(Serialize)]
+pub struct ListedAgent {
+    #[serde(flatten)]
+    pub agent: RegisteredAgent,
+    /// Overall score of the latest audit, if audited
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audit_id: Option<String>,
+    /// The latest audit is past its `valid_until`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stale: Option<bool>,
+    /// Recent audits keep finding the agent dead; automatic audits are backed off
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unresponsive: Option<bool>,
+}
+
+#[derive(Serialize)]
+pub struct ListAgentsResponse {
+    pub chain_id: u64,
+    /// Last block the indexer has processed (None before the first scan)
+    pub indexed_to_block: Option<u64>,
+    pub total: usize,
+    pub limit: usize,
+    pub offset: usize,
+    pub agents: Vec<ListedAgent>,
+}
+
+/// GET /agents - Agents indexed from the registry's events, newest first
+pub async fn list_agents(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ListAgentsQuery>,
+) -> Json<ListAgentsResponse> {
+    let chain_id = query.chain_id.unwrap_or(state.config.default_chain_id);
+    let limit = query.limit.clamp(1, 500);
+
+    let audited: HashMap<u64, IndexedAgent> = state
+        .audit_store
+        .indexed_agents()
+        .await
+        .into_iter()
+        .filter(|agent| agent.chain_id == chain_id)
+        .map(|agent| (agent.agent_id, agent))
+        .collect();
+    let registered = state.audit_store.registered_agents(chain_id).await;
+    let total = registered.len();
+    let now = chrono::Utc::now().timestamp() as u64;
+
+    let agents = registered
+        .into_iter()
+        .rev()
+        .skip(query.offset)
+        .take(limit)
+        .map(|agent| {
+            let latest = audited.get(&agent.agent_id);
+            ListedAgent {
+                score: latest.map(|a| a.score),
+                audit_id: latest.map(|a| a.audit_id.clone()),
+                stale: latest.map(|a| a.is_stale(now)),
+                unresponsive: latest.map(|a| {
+                    state.config.breaker.state(a, now) != BreakerState::Closed
+                }),
+                agent,
+            }
+        })
+        .collect();
+
+    Json(ListAgentsResponse {
+        chain_id,
+        indexed_to_block: state.audit_store.indexer_cursor(chain_id).await,
+        total,
+        limit,
+        offset: query.offset,
+        agents,
+    })
+}
+
+#[derive(Serialize)]
+pub struct AgentSearchResponse {
+    pub skill: String,
+    pub min_score: u8,
+    pub agents: Vec<SearchResult>,
+}
+
+/// GET /agents/search?skill=...&min_score=... - Audited agents offering a skill
+pub async fn search_agents(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<SearchQuery>,
+) -> Result<Json<AgentSearchResponse>, WatchyError> {
+    if query.skill.trim().is_empty() {
+        return Err(WatchyError::InvalidRequest("skill must not be empty".to_string()));
+    }
+    if query.min_score > 100 {
+        return Err(WatchyError::InvalidRequest(
+            "min_score must be between 0 and 100".to_string(),
+        ));
+    }
+
+    let now = chrono::Utc::now().timestamp() as u64;
+    let agents = search::search(state.audit_store.indexed_agents().await, &query, now);
+    Ok(Json(AgentSearchResponse {
+        skill: query.skill,
+        min_score: query.min_score,
+        agents,
+    }))
+}
+
 // =============================================================================
 // ADMIN ENDPOINTS (protected by ADMIN_API_KEY)
 // =============================================================================
 
 /// Request body for registering a new agent
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Validate)]
 #[serde(rename_all = "camelCase")]
 pub struct RegisterAgentRequest {
     /// Chain ID to register on (default: config default_chain_id)
+    #[serde(default, deserialize_with = "caip::deserialize_optional_chain_id")]
     pub chain_id: Option<u64>,
 }
 
@@ -540,16 +2206,14 @@ pub struct RegisterAgentResponse {
 /// Uses the TEE wallet (derived from mnemonic) to sign the transaction.
 pub async fn register_agent(
     State(state): State<Arc<AppState>>,
-    Json(request): Json<RegisterAgentRequest>,
+    ValidatedJson(request): ValidatedJson<RegisterAgentRequest>,
 ) -> Result<(StatusCode, Json<RegisterAgentResponse>), WatchyError> {
     let chain_id = request.chain_id.unwrap_or(state.config.default_chain_id);
 
-    // Check if chain is allowed (testnet-only mode)
-    if !is_chain_allowed(chain_id) {
-        return Err(WatchyError::InvalidRequest(format!(
-            "Chain {} is not enabled. Allowed chains: Base (8453), Base Sepolia (84532), Sepolia (11155111)",
-            chain_id
-        )));
+    // Check if chain is allowed (ALLOWED_CHAINS)
+    let settings = state.live.get();
+    if !settings.chain_allowed(chain_id) {
+        return Err(chain_not_allowed(&settings, chain_id));
     }
 
     // Get chain config
@@ -605,14 +2269,17 @@ pub async fn register_agent(
 }
 
 /// Request body for updating an agent's URI
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Validate)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateAgentUriRequest {
     /// The agent token ID to update
+    #[validate(range(min = 1, message = "agentId must be greater than 0"))]
     pub agent_id: u64,
     /// The URI to set (e.g., "data:application/json;base64,..." or IPFS/Arweave URL)
+    #[validate(length(min = 1, message = "uri must not be empty"))]
     pub uri: String,
     /// Chain ID (default: config default_chain_id)
+    #[serde(default, deserialize_with = "caip::deserialize_optional_chain_id")]
     pub chain_id: Option<u64>,
 }
 
@@ -638,16 +2305,14 @@ pub struct UpdateAgentUriResponse {
 /// - An Arweave URL: "https://arweave.net/..."
 pub async fn set_agent_uri(
     State(state): State<Arc<AppState>>,
-    Json(request): Json<UpdateAgentUriRequest>,
+    ValidatedJson(request): ValidatedJson<UpdateAgentUriRequest>,
 ) -> Result<Json<UpdateAgentUriResponse>, WatchyError> {
     let chain_id = request.chain_id.unwrap_or(state.config.default_chain_id);
 
     // Check if chain is allowed
-    if !is_chain_allowed(chain_id) {
-        return Err(WatchyError::InvalidRequest(format!(
-            "Chain {} is not enabled. Allowed chains: Base (8453), Base Sepolia (84532), Sepolia (11155111)",
-            chain_id
-        )));
+    let settings = state.live.get();
+    if !settings.chain_allowed(chain_id) {
+        return Err(chain_not_allowed(&settings, chain_id));
     }
 
     // Get chain config
@@ -695,3 +2360,586 @@ pub async fn set_agent_uri(
         uri: request.uri,
     }))
 }
+
+/// Response for Arweave upload balance
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArweaveBalanceResponse {
+    pub provider: String,
+    pub address: String,
+    /// None if the provider doesn't track balances
+    pub balance: Option<ProviderBalance>,
+    /// Address to transfer funds to, if the provider is funded by direct transfer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub funding_address: Option<String>,
+}
+
+/// GET /admin/arweave/balance - Upload credit held by the signer at the configured provider
+pub async fn get_arweave_balance(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<ArweaveBalanceResponse>, WatchyError> {
+    // Upload credit belongs to the upload key
+    let retired = state.audit_store.retired_signers().await;
+    let upload_key = state.config.wallet.upload_key(&retired).ok_or_else(|| {
+        WatchyError::Internal("No wallet configured (MNEMONIC or PRIVATE_KEY required)".to_string())
+    })?;
+    let signer_address = &upload_key.address;
+
+    let irys = IrysClient::new(Some(&upload_key.private_key))?
+        .with_uploader(uploader_from_config(&state.config, state.http.upload.clone()));
+
+    let balance = irys.balance().await?;
+    let funding_address = irys.funding_address().await?;
+
+    Ok(Json(ArweaveBalanceResponse {
+        provider: irys.provider_name().to_string(),
+        address: signer_address.to_string(),
+        balance,
+        funding_address,
+    }))
+}
+
+/// Response for upload funding status
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FundingResponse {
+    pub provider: String,
+    pub address: String,
+    /// None if the provider doesn't track balances
+    pub balance: Option<ProviderBalance>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub funding_address: Option<String>,
+    /// Average upload size of a published audit (None before the first completed audit)
+    pub average_report_bytes: Option<u64>,
+    /// Provider price for `average_report_bytes`
+    pub cost_per_audit: Option<ProviderBalance>,
+    /// Audits the balance still pays for at that price
+    pub estimated_audits_remaining: Option<u128>,
+    /// Whether `POST /admin/funding/topup` is enabled, and its limit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub topup_max_wei: Option<String>,
+}
+
+/// GET /admin/funding - Upload credit and the audits it still pays for
+pub async fn get_funding(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<FundingResponse>, WatchyError> {
+    // Upload credit belongs to the upload key
+    let retired = state.audit_store.retired_signers().await;
+    let upload_key = state.config.wallet.upload_key(&retired).ok_or_else(|| {
+        WatchyError::Internal("No wallet configured (MNEMONIC or PRIVATE_KEY required)".to_string())
+    })?;
+    let signer_address = &upload_key.address;
+
+    let irys = IrysClient::new(Some(&upload_key.private_key))?
+        .with_uploader(uploader_from_config(&state.config, state.http.upload.clone()));
+
+    let balance = irys.balance().await?;
+    let funding_address = irys.funding_address().await?;
+    let jobs = state.audit_store.list_jobs().await;
+    let average_report_bytes = funding::average_report_bytes(&jobs);
+    let cost_per_audit = match average_report_bytes {
+        Some(bytes) => irys.price(bytes).await?,
+        None => None,
+    };
+    let estimated_audits_remaining = balance
+        .as_ref()
+        .zip(cost_per_audit.as_ref())
+        .and_then(|(balance, cost)| funding::audits_remaining(balance, cost));
+
+    Ok(Json(FundingResponse {
+        provider: irys.provider_name().to_string(),
+        address: signer_address.to_string(),
+        balance,
+        funding_address,
+        average_report_bytes,
+        cost_per_audit,
+        estimated_audits_remaining,
+        topup_max_wei: state.config.arweave_topup_max_wei.map(|max| max.to_string()),
+    }))
+}
+
+/// Request body for funding the upload provider
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct TopupRequest {
+    /// Amount to send, in wei (decimal string)
+    #[validate(length(min = 1, message = "amountWei must not be empty"))]
+    pub amount_wei: String,
+}
+
+/// Response for a provider top-up
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopupResponse {
+    pub provider: String,
+    pub chain_id: u64,
+    pub funding_address: String,
+    pub amount_wei: String,
+    pub tx_hash: String,
+    /// Why the provider didn't accept the transfer; submit `txHash` to it again
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confirm_error: Option<String>,
+    /// Balance after the provider credited the transfer
+    pub balance: Option<ProviderBalance>,
+}
+
+/// POST /admin/funding/topup - Fund the upload provider from the signer wallet
+///
+/// Sends ETH on Ethereum mainnet to the provider's funding address and submits
+/// the transaction to the provider. Disabled unless ARWEAVE_TOPUP_MAX_WEI is set.
+pub async fn topup_funding(
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(request): ValidatedJson<TopupRequest>,
+) -> Result<Json<TopupResponse>, WatchyError> {
+    if state.pauses.is_paused(Subsystem::Onchain) {
+        return Err(WatchyError::Forbidden(
+            "On-chain submission is paused, top-ups are refused".to_string(),
+        ));
+    }
+    let max_wei = state.config.arweave_topup_max_wei.ok_or_else(|| {
+        WatchyError::InvalidRequest(
+            "Top-ups are disabled (set ARWEAVE_TOPUP_MAX_WEI to enable)".to_string(),
+        )
+    })?;
+    let amount: u128 = request.amount_wei.parse().map_err(|_| {
+        WatchyError::InvalidRequest("amountWei must be a decimal amount of wei".to_string())
+    })?;
+    if amount == 0 || amount > max_wei {
+        return Err(WatchyError::InvalidRequest(format!(
+            "amountWei must be between 1 and {}",
+            max_wei
+        )));
+    }
+
+    // Credit goes to the sender, so the upload key pays
+    let retired = state.audit_store.retired_signers().await;
+    let upload_key = state.config.wallet.upload_key(&retired).ok_or_else(|| {
+        WatchyError::Internal("No wallet configured (MNEMONIC or PRIVATE_KEY required)".to_string())
+    })?;
+
+    let irys = IrysClient::new(Some(&upload_key.private_key))?
+        .with_uploader(uploader_from_config(&state.config, state.http.upload.clone()));
+    let funding_address = irys.funding_address().await?.ok_or_else(|| {
+        WatchyError::InvalidRequest(format!(
+            "The {} provider isn't funded by direct transfer",
+            irys.provider_name()
+        ))
+    })?;
+
+    let rpc_url = get_rpc_url(funding::FUNDING_CHAIN_ID).ok_or_else(|| {
+        WatchyError::InvalidRequest(format!(
+            "No RPC URL for chain {}",
+            funding::FUNDING_CHAIN_ID
+        ))
+    })?;
+
+    info!(
+        "Funding {} with {} wei at {}",
+        irys.provider_name(),
+        amount,
+        funding_address
+    );
+    let tx_hash = AccountClient::new(&rpc_url)?
+        .transfer(&upload_key.private_key, &funding_address, amount)
+        .await?;
+    // The ETH has left the wallet: report the transaction even if the provider
+    // didn't take it
+    let confirm_error = match irys.confirm_funding(&tx_hash).await {
+        Ok(()) => {
+            info!("{} funded (tx: {})", irys.provider_name(), tx_hash);
+            None
+        }
+        Err(e) => {
+            error!("{} didn't accept funding tx {}: {}", irys.provider_name(), tx_hash, e);
+            Some(e.to_string())
+        }
+    };
+
+    // Providers may credit the transfer only after a few confirmations
+    let balance = irys.balance().await.unwrap_or_else(|e| {
+        warn!("Balance query after top-up failed: {}", e);
+        None
+    });
+
+    Ok(Json(TopupResponse {
+        provider: irys.provider_name().to_string(),
+        chain_id: funding::FUNDING_CHAIN_ID,
+        funding_address,
+        amount_wei: amount.to_string(),
+        tx_hash,
+        confirm_error,
+        balance,
+    }))
+}
+
+/// A configured signer as listed by the admin API (without its key)
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignerInfo {
+    pub label: String,
+    pub address: String,
+    /// Used for admin transactions (registration, URI updates, top-ups)
+    pub primary: bool,
+    /// "report", or the dedicated "upload"/"feedback" key
+    pub role: String,
+    pub retired: bool,
+    /// When it was retired (unix seconds); reports it signed later don't verify
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retired_at: Option<u64>,
+}
+
+/// Response for the signer list
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignersResponse {
+    pub rotation: String,
+    pub signers: Vec<SignerInfo>,
+}
+
+async fn signers_response(state: &AppState) -> SignersResponse {
+    let retired = state.audit_store.retired_signers().await;
+    let wallet = &state.config.wallet;
+    let report_keys = wallet.signers.iter().map(|signer| ("report", signer));
+    SignersResponse {
+        rotation: wallet.rotation.as_str().to_string(),
+        signers: report_keys
+            .chain(wallet.role_keys())
+            .enumerate()
+            .map(|(position, (role, signer))| {
+                let retired_at = retired
+                    .iter()
+                    .find(|retired| retired.address.eq_ignore_ascii_case(&signer.address))
+                    .map(|retired| retired.retired_at);
+                SignerInfo {
+                    label: signer.label.clone(),
+                    address: signer.address.clone(),
+                    primary: position == 0,
+                    role: role.to_string(),
+                    retired: retired_at.is_some(),
+                    retired_at,
+                }
+            })
+            .collect(),
+    }
+}
+
+/// GET /admin/signers - Configured signers, the rotation policy and retirements
+pub async fn list_signers(State(state): State<Arc<AppState>>) -> Json<SignersResponse> {
+    Json(signers_response(&state).await)
+}
+
+/// Request body for retiring a signer
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct RetireSignerRequest {
+    #[validate(length(min = 1, message = "address must not be empty"))]
+    pub address: String,
+}
+
+/// POST /admin/signers/retire - Stop signing reports and feedback with a signer
+///
+/// Takes effect for audits starting after the call and survives restarts.
+/// A retired primary signer still signs admin transactions; a retired upload
+/// or feedback key is replaced by the report signer.
+pub async fn retire_signer(
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(request): ValidatedJson<RetireSignerRequest>,
+) -> Result<Json<SignersResponse>, WatchyError> {
+    let address = request.address.to_lowercase();
+    let wallet = &state.config.wallet;
+    let configured = wallet
+        .signers
+        .iter()
+        .chain(wallet.role_keys().map(|(_, key)| key))
+        .any(|signer| signer.address.to_lowercase() == address);
+    if !configured {
+        return Err(WatchyError::InvalidRequest(format!(
+            "{} is not a configured signer",
+            request.address
+        )));
+    }
+
+    state.audit_store.retire_signer(&address).await;
+    warn!("Signer {} retired", address);
+
+    let response = signers_response(&state).await;
+    let mut reporting = response.signers.iter().filter(|signer| signer.role == "report");
+    if reporting.all(|signer| signer.retired) {
+        warn!("All signers are retired, reports won't be published");
+    }
+    Ok(Json(response))
+}
+
+/// Response for published report integrity stats
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityResponse {
+    pub enabled: bool,
+    pub interval_secs: u64,
+    pub sample_size: usize,
+    #[serde(flatten)]
+    pub stats: IntegrityStats,
+}
+
+/// GET /admin/integrity - Results of background re-verification of published reports
+pub async fn get_integrity_stats(
+    State(state): State<Arc<AppState>>,
+) -> Json<IntegrityResponse> {
+    let stats = state.integrity.read().await.clone();
+
+    Json(IntegrityResponse {
+        enabled: state.config.integrity_check_interval_secs > 0,
+        interval_secs: state.config.integrity_check_interval_secs,
+        sample_size: state.config.integrity_sample_size,
+        stats,
+    })
+}
+
+/// Request body for purging stored audit jobs
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+#[validate(schema(function = "validate_purge_filter"))]
+pub struct PurgeAuditsRequest {
+    #[validate(range(min = 1, message = "agentId must be greater than 0"))]
+    pub agent_id: Option<u64>,
+    #[serde(default, deserialize_with = "caip::deserialize_optional_chain_id")]
+    pub chain_id: Option<u64>,
+    pub status: Option<AuditStatus>,
+    /// Only purge jobs created at least this many seconds ago
+    pub older_than_secs: Option<u64>,
+}
+
+/// A purge needs an agent or an age, never "everything"
+fn validate_purge_filter(request: &PurgeAuditsRequest) -> Result<(), ValidationError> {
+    if request.agent_id.is_none() && request.older_than_secs.is_none() {
+        return Err(ValidationError::new("required")
+            .with_message("agentId or olderThanSecs is required".into()));
+    }
+    Ok(())
+}
+
+/// Response for an audit purge
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeAuditsResponse {
+    pub purged: usize,
+    pub audit_ids: Vec<String>,
+}
+
+/// POST /admin/audits/purge - Delete stored audit jobs by agent and/or age
+///
+/// Purged jobs are not archived. Published reports (Arweave/IPFS) are unaffected.
+pub async fn purge_audits(
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(request): ValidatedJson<PurgeAuditsRequest>,
+) -> Result<Json<PurgeAuditsResponse>, WatchyError> {
+    let filter = PurgeFilter {
+        agent_id: request.agent_id,
+        chain_id: request.chain_id,
+        status: request.status,
+        older_than_secs: request.older_than_secs,
+    };
+    let audit_ids = purge_jobs(&state, &filter).await;
+
+    Ok(Json(PurgeAuditsResponse {
+        purged: audit_ids.len(),
+        audit_ids,
+    }))
+}
+
+/// POST /admin/reload - Re-read the settings file and apply its live settings
+///
+/// Score weights, allowed chains, audit quotas and the probe timeout apply to
+/// audits started after the call; other changed keys are reported as needing
+/// a restart. An invalid file changes nothing.
+pub async fn reload_settings(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<ReloadReport>, WatchyError> {
+    let report = reload::reload(&state)
+        .await
+        .map_err(|e| WatchyError::InvalidRequest(format!("Settings not reloaded: {:#}", e)))?;
+    reload::log_report(&report);
+    Ok(Json(report))
+}
+
+#[derive(Serialize)]
+pub struct PauseResponse {
+    pub subsystem: Subsystem,
+    pub paused: bool,
+    /// When the subsystem was paused (unix seconds)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<u64>,
+}
+
+fn parse_subsystem(name: &str) -> Result<Subsystem, WatchyError> {
+    Subsystem::parse(name).ok_or_else(|| {
+        WatchyError::InvalidRequest(format!(
+            "Unknown subsystem '{}' (expected {})",
+            name,
+            pause::names()
+        ))
+    })
+}
+
+/// POST /admin/pause/:subsystem - Stop a background subsystem from starting new work
+///
+/// Pausing `onchain` stops feedback and EAS submissions (reports are still
+/// uploaded); pausing an already paused subsystem keeps its original time.
+pub async fn pause_subsystem(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> Result<Json<PauseResponse>, WatchyError> {
+    let subsystem = parse_subsystem(&name)?;
+    let since = state.pauses.pause(subsystem);
+    warn!("Paused {}", subsystem.as_str());
+    Ok(Json(PauseResponse {
+        subsystem,
+        paused: true,
+        since: Some(since),
+    }))
+}
+
+/// POST /admin/resume/:subsystem - Resume a paused subsystem
+pub async fn resume_subsystem(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> Result<Json<PauseResponse>, WatchyError> {
+    let subsystem = parse_subsystem(&name)?;
+    if state.pauses.resume(subsystem) {
+        info!("Resumed {}", subsystem.as_str());
+    }
+    Ok(Json(PauseResponse {
+        subsystem,
+        paused: false,
+        since: None,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arweave::irys::sign_report;
+    use crate::types::AuditReport;
+
+    /// A report signed with the test state's key, claiming to be from `created_at`
+    async fn signed_report(state: &AppState, created_at: &str) -> serde_json::Value {
+        let key = &state.config.wallet.signers[0];
+        let mut report = AuditReport::new(17, 8453, "0x8004", String::new(), None);
+        report.auditor.address = Some(key.address.clone());
+        let mut json = serde_json::to_value(&report).unwrap();
+        json["created_at"] = created_at.into();
+        let signature = sign_report(&json, &key.private_key).await.unwrap();
+        json["signature"] = signature.into();
+        json
+    }
+
+    async fn signature_valid(
+        state: &Arc<AppState>,
+        audit_id: Option<&str>,
+        report: &serde_json::Value,
+    ) -> Option<bool> {
+        let query = VerifyReportQuery {
+            audit_id: audit_id.map(str::to_string),
+        };
+        let response = verify_report(State(state.clone()), Query(query), Json(report.clone()))
+            .await
+            .unwrap();
+        response.0.signature_valid
+    }
+
+    fn check_request(service: &str, url: &str) -> ValidatedJson<CheckEndpointRequest> {
+        ValidatedJson(CheckEndpointRequest {
+            url: url.to_string(),
+            service: service.to_string(),
+            version: None,
+            a2a_skills: vec![],
+            mcp_tools: vec![],
+            timeout_secs: None,
+        })
+    }
+
+    /// Status `check_endpoint` refuses the request with
+    async fn check_refused(state: &Arc<AppState>, service: &str, url: &str) -> StatusCode {
+        let tenant = Extension(Tenant(None));
+        let request = check_request(service, url);
+        let result = check_endpoint(State(state.clone()), tenant, request).await;
+        result.err().unwrap().into_response().status()
+    }
+
+    #[tokio::test]
+    async fn test_check_endpoint_refused() {
+        let state = AppState::for_tests(|config| {
+            config.endpoint_check_concurrency = 1;
+            config.usage.monthly_quota = Some(1);
+        });
+        let url = "https://agent.example.com/a2a";
+        let used = || async {
+            let month = usage::month(chrono::Utc::now().timestamp() as u64);
+            state.audit_store.usage(usage::account(&Tenant(None)), &month).await
+        };
+
+        let status = check_refused(&state, "FTP", url).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let status = check_refused(&state, "A2A", "ftp://agent.example.com").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        // Every slot is taken
+        let slot = state.endpoint_checks.try_acquire().unwrap();
+        assert_eq!(check_refused(&state, "A2A", url).await, StatusCode::SERVICE_UNAVAILABLE);
+        drop(slot);
+        // None of these counted against the quota
+        assert_eq!(used().await, 0);
+
+        // The month's quota is used up
+        let settings = state.live.get();
+        let now = chrono::Utc::now().timestamp() as u64;
+        usage::reserve(&state.audit_store, &settings.usage, &Tenant(None), now).await.unwrap();
+        assert_eq!(check_refused(&state, "MCP", url).await, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(used().await, 1);
+        // The slot is given back
+        assert_eq!(state.endpoint_checks.available_permits(), 1);
+    }
+
+    #[test]
+    fn test_endpoint_check_timeout() {
+        let mut config = Config::from_env().unwrap();
+        config.audit_timeout_secs = 30;
+        config.audit_max_timeout_secs = 120;
+
+        assert_eq!(endpoint_check_timeout(&config, 60, None), 30);
+        assert_eq!(endpoint_check_timeout(&config, 60, Some(10)), 10);
+        // Cut a second short of the request timeout
+        assert_eq!(endpoint_check_timeout(&config, 60, Some(90)), 59);
+        assert_eq!(endpoint_check_timeout(&config, 1, Some(90)), 1);
+        // Without a request timeout only the audit maximum applies
+        assert_eq!(endpoint_check_timeout(&config, 0, Some(90)), 90);
+        assert_eq!(endpoint_check_timeout(&config, 0, Some(600)), 120);
+    }
+
+    #[tokio::test]
+    async fn test_verify_report_retired_signer() {
+        let state = AppState::for_tests(|_| {});
+        let address = state.config.wallet.signers[0].address.clone();
+        let published = signed_report(&state, "2026-01-01T00:00:00Z").await;
+        let backdated = signed_report(&state, "2020-01-01T00:00:00Z").await;
+        assert_eq!(signature_valid(&state, None, &backdated).await, Some(true));
+
+        // The stored audit dates the report it published, and only that one
+        let mut result = AuditReport::new(17, 8453, "0x8004", String::new(), None);
+        result.set_json_hash(&compute_feedback_hash(&published).unwrap());
+        let audit_id = state.audit_store.create_job(Some(17), 8453, None, None, None).await;
+        state.audit_store.set_result(&audit_id, result, &[]).await;
+        let job = state.audit_store.get_job(&audit_id).await.unwrap();
+        let hash = |report| format!("0x{}", hex::encode(compute_feedback_hash(report).unwrap()));
+        assert_eq!(published_at(&state, &audit_id, &hash(&published)).await, job.completed_at);
+        assert_eq!(published_at(&state, &audit_id, &hash(&backdated)).await, None);
+        assert_eq!(published_at(&state, "aud_missing", &hash(&published)).await, None);
+
+        // Signed with the retired key and backdated to before its retirement
+        state.audit_store.retire_signer(&address).await;
+        assert_eq!(signature_valid(&state, None, &backdated).await, Some(false));
+        let valid = signature_valid(&state, Some(&audit_id), &backdated).await;
+        assert_eq!(valid, Some(false));
+    }
+}
@@ -1,48 +1,68 @@
 use axum::{
     body::Body,
     extract::State,
-    http::{Request, StatusCode},
+    http::{header, HeaderMap, HeaderValue, Method, Request, StatusCode},
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
+    Json,
 };
 use std::sync::Arc;
-use tracing::warn;
+use tracing::{info_span, warn, Instrument};
 
+use crate::tenant::Tenant;
+use crate::types::{ErrorResponse, WatchyError};
 use crate::AppState;
 
 /// Middleware to validate API key for service-to-service authentication.
 ///
-/// If `API_KEY` is configured, all requests must include a matching `X-API-Key` header.
-/// If `API_KEY` is not set, all requests are allowed (open mode).
+/// If `API_KEY` or `TENANT_API_KEYS` is configured, all requests must include a matching
+/// `X-API-Key` header. If neither is set, all requests are allowed (open mode).
+/// The request's `Tenant` is added to its extensions for handlers.
 pub async fn require_api_key(
     State(state): State<Arc<AppState>>,
-    request: Request<Body>,
+    mut request: Request<Body>,
     next: Next,
-) -> Result<Response, StatusCode> {
-    // If no API key configured, allow all requests
-    let Some(expected_key) = &state.config.api_key else {
-        return Ok(next.run(request).await);
-    };
-
-    // Check X-API-Key header
-    let provided_key = request
-        .headers()
-        .get("X-API-Key")
-        .and_then(|v| v.to_str().ok());
-
-    match provided_key {
-        Some(key) if key == expected_key => Ok(next.run(request).await),
-        Some(_) => {
+) -> Result<Response, WatchyError> {
+    match authenticate(&state, request.headers()) {
+        Some(tenant) => {
+            request.extensions_mut().insert(tenant);
+            Ok(next.run(request).await)
+        }
+        None if request.headers().contains_key("X-API-Key") => {
             warn!("Invalid API key provided");
-            Err(StatusCode::UNAUTHORIZED)
+            Err(WatchyError::Unauthorized("Invalid API key".to_string()))
         }
         None => {
             warn!("Missing X-API-Key header");
-            Err(StatusCode::UNAUTHORIZED)
+            Err(WatchyError::Unauthorized("Missing X-API-Key header".to_string()))
         }
     }
 }
 
+/// Tenant of the request's API key, or `None` if the key is missing or wrong
+///
+/// The default tenant in open mode. Also for public routes that accept the
+/// API key as one of several credentials.
+pub fn authenticate(state: &AppState, headers: &HeaderMap) -> Option<Tenant> {
+    let config = &state.config;
+    // If no API key configured, allow all requests
+    if config.api_key.is_none() && config.tenant_keys.is_empty() {
+        return Some(Tenant::default());
+    }
+
+    let provided_key = headers.get("X-API-Key").and_then(|v| v.to_str().ok())?;
+    if config.api_key.as_deref() == Some(provided_key) {
+        return Some(Tenant::default());
+    }
+    config.tenant_keys.tenant_for(provided_key)
+}
+
+/// Whether a request carries the configured admin API key
+pub fn is_admin(state: &AppState, headers: &HeaderMap) -> bool {
+    let provided = headers.get("X-Admin-API-Key").and_then(|v| v.to_str().ok());
+    matches!((&state.config.admin_api_key, provided), (Some(expected), Some(key)) if key == expected)
+}
+
 /// Middleware to validate Admin API key for privileged operations.
 ///
 /// ADMIN_API_KEY is REQUIRED for admin endpoints. If not configured, admin endpoints are disabled.
@@ -50,11 +70,13 @@ pub async fn require_admin_api_key(
     State(state): State<Arc<AppState>>,
     request: Request<Body>,
     next: Next,
-) -> Result<Response, StatusCode> {
+) -> Result<Response, WatchyError> {
     // Admin API key is required - if not configured, deny all requests
     let Some(expected_key) = &state.config.admin_api_key else {
         warn!("Admin endpoint called but ADMIN_API_KEY is not configured");
-        return Err(StatusCode::FORBIDDEN);
+        return Err(WatchyError::Forbidden(
+            "Admin endpoints are disabled (ADMIN_API_KEY is not set)".to_string(),
+        ));
     };
 
     // Check X-Admin-API-Key header
@@ -67,11 +89,213 @@ pub async fn require_admin_api_key(
         Some(key) if key == expected_key => Ok(next.run(request).await),
         Some(_) => {
             warn!("Invalid admin API key provided");
-            Err(StatusCode::UNAUTHORIZED)
+            Err(WatchyError::Unauthorized("Invalid admin API key".to_string()))
         }
         None => {
             warn!("Missing X-Admin-API-Key header");
-            Err(StatusCode::UNAUTHORIZED)
+            Err(WatchyError::Unauthorized("Missing X-Admin-API-Key header".to_string()))
+        }
+    }
+}
+
+/// Largest error body rewritten by `json_errors`; rejections are a line of text
+const MAX_ERROR_BODY_BYTES: usize = 16 * 1024;
+
+/// Middleware giving every error response the `ErrorResponse` JSON envelope.
+///
+/// Handlers and Watchy's own middleware already answer with it; this covers
+/// what answers with plain text or nothing: axum's extractor rejections (bad
+/// path or query parameters), unknown routes and methods, and tower layers.
+/// The status is kept and the text, if any, becomes the message. Responses
+/// to HEAD requests are left without a body.
+pub async fn json_errors(request: Request<Body>, next: Next) -> Response {
+    let head = request.method() == Method::HEAD;
+    let response = next.run(request).await;
+    let status = response.status();
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.contains("json"));
+    if head || !(status.is_client_error() || status.is_server_error()) || is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let text = axum::body::to_bytes(body, MAX_ERROR_BODY_BYTES)
+        .await
+        .map(|bytes| String::from_utf8_lossy(&bytes).trim().to_string())
+        .unwrap_or_default();
+    let message = if text.is_empty() {
+        status.canonical_reason().unwrap_or("Error").to_string()
+    } else {
+        text
+    };
+    let body = ErrorResponse {
+        error: status_code(status).to_string(),
+        message,
+        details: None,
+        retry_after: None,
+    };
+    // Keep headers such as Allow (405) and Retry-After; the body sets its own type
+    parts.headers.remove(header::CONTENT_TYPE);
+    parts.headers.remove(header::CONTENT_LENGTH);
+    (parts, Json(body)).into_response()
+}
+
+/// `ErrorResponse::error` code for a status, matching `WatchyError::code`
+fn status_code(status: StatusCode) -> &'static str {
+    match status {
+        StatusCode::BAD_REQUEST => "invalid_request",
+        StatusCode::UNAUTHORIZED => "unauthorized",
+        StatusCode::FORBIDDEN => "forbidden",
+        StatusCode::NOT_FOUND => "not_found",
+        StatusCode::METHOD_NOT_ALLOWED => "method_not_allowed",
+        StatusCode::REQUEST_TIMEOUT | StatusCode::GATEWAY_TIMEOUT => "timeout",
+        StatusCode::PAYLOAD_TOO_LARGE => "payload_too_large",
+        StatusCode::UNSUPPORTED_MEDIA_TYPE => "unsupported_media_type",
+        StatusCode::UNPROCESSABLE_ENTITY => "validation_failed",
+        StatusCode::TOO_MANY_REQUESTS => "rate_limited",
+        StatusCode::SERVICE_UNAVAILABLE => "overloaded",
+        status if status.is_client_error() => "invalid_request",
+        _ => "internal_error",
+    }
+}
+
+/// Header carrying the request's correlation ID
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+/// Longest client-supplied request ID that is kept
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Correlation ID of a request, in its extensions
+#[derive(Debug, Clone)]
+pub struct RequestId(pub String);
+
+/// Middleware giving every request a correlation ID.
+///
+/// Keeps the caller's `X-Request-Id` when it is a plausible ID (at most 128
+/// characters of letters, digits, `-`, `_`, `.` and `:`), otherwise generates
+/// a UUID. Everything logged while handling the request, including audit
+/// tasks it spawns, is in a span carrying the ID, and the response echoes it.
+pub async fn request_id(mut request: Request<Body>, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|id| is_valid_request_id(id))
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    request.extensions_mut().insert(RequestId(id.clone()));
+
+    let span = info_span!(
+        "request",
+        request_id = %id,
+        method = %request.method(),
+        path = %request.uri().path(),
+    );
+    let mut response = next.run(request).instrument(span).await;
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+fn is_valid_request_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LEN
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_id_validation() {
+        assert!(is_valid_request_id("3f2b7c1e-9a4d-4e8f-b0c2-5d6e7f8a9b0c"));
+        assert!(is_valid_request_id("lb:req.42_a"));
+        assert!(!is_valid_request_id(""));
+        assert!(!is_valid_request_id("id with spaces"));
+        assert!(!is_valid_request_id(&"a".repeat(MAX_REQUEST_ID_LEN + 1)));
+    }
+
+    #[test]
+    fn test_status_code_matches_watchy_error() {
+        let errors = [
+            WatchyError::InvalidRequest(String::new()),
+            WatchyError::Unauthorized(String::new()),
+            WatchyError::Forbidden(String::new()),
+            WatchyError::PayloadTooLarge(String::new()),
+            WatchyError::RateLimited,
+            WatchyError::Overloaded,
+            WatchyError::Internal(String::new()),
+        ];
+        for error in errors {
+            let code = error.code();
+            let status = error.into_response().status();
+            assert_eq!(status_code(status), code, "{}", status);
         }
+        assert_eq!(status_code(StatusCode::METHOD_NOT_ALLOWED), "method_not_allowed");
+        assert_eq!(status_code(StatusCode::IM_A_TEAPOT), "invalid_request");
+    }
+
+    #[tokio::test]
+    async fn test_json_errors() {
+        use crate::server::{with_limits, ServerLimits};
+        use axum::extract::Query;
+        use axum::routing::{get, post};
+        use axum::Router;
+        use tower::Service;
+
+        #[derive(serde::Deserialize)]
+        struct Page {
+            #[allow(dead_code)]
+            limit: u32,
+        }
+        let limits = ServerLimits {
+            max_body_bytes: 16,
+            ..ServerLimits::default()
+        };
+        let routes = Router::new()
+            .route("/page", get(|_: Query<Page>| async { "page" }))
+            .route("/echo", post(|body: String| async { body }));
+        let mut app = with_limits(routes, move || limits.clone())
+            .layer(axum::middleware::from_fn(json_errors));
+
+        let cases = [
+            (Request::get("/missing"), Body::empty(), StatusCode::NOT_FOUND, "not_found"),
+            (
+                Request::get("/page?limit=x"),
+                Body::empty(),
+                StatusCode::BAD_REQUEST,
+                "invalid_request",
+            ),
+            // No Content-Length, so the limit applies while the body is read
+            (
+                Request::post("/echo"),
+                Body::from("x".repeat(17)),
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "payload_too_large",
+            ),
+        ];
+        for (request, body, status, code) in cases {
+            let response = app.call(request.body(body).unwrap()).await.unwrap();
+            assert_eq!(response.status(), status);
+            assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(error["error"], code);
+            assert!(error["message"].as_str().is_some_and(|message| !message.is_empty()));
+        }
+
+        // HEAD responses stay bodiless
+        let request = Request::head("/missing").body(Body::empty()).unwrap();
+        let response = app.call(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert!(body.is_empty());
     }
 }
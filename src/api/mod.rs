@@ -1,3 +1,6 @@
+pub mod a2a;
+pub mod graphql;
 pub mod handlers;
 pub mod middleware;
 pub mod routes;
+pub mod validation;
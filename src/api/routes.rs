@@ -1,25 +1,63 @@
 use axum::{
     routing::{get, post},
-    Router,
+    Extension, Router,
 };
 use std::sync::Arc;
 
-use crate::AppState;
+use crate::{mcp_server, AppState};
 
-use super::handlers;
+use super::{a2a, graphql, handlers};
 
 pub fn audit_routes() -> Router<Arc<AppState>> {
     Router::new()
         .route("/", post(handlers::request_audit))
+        .route("/by-owner", post(handlers::audit_by_owner))
+        .route("/by-uri", post(handlers::audit_by_uri))
+        .route("/batch/:batch_id", get(handlers::get_audit_batch))
         .route("/:audit_id", get(handlers::get_audit))
+        .route("/:audit_id/retry", post(handlers::retry_audit))
         .route("/:audit_id/report", get(handlers::get_audit_report))
+        .route("/:audit_id/report.md/link", post(handlers::create_report_link))
+        .route("/:audit_id/events", get(handlers::get_audit_events))
+        .route("/:audit_id/timeline", get(handlers::get_audit_timeline))
+        .route("/:audit_id/artifacts", get(handlers::get_audit_artifacts))
 }
 
 pub fn agent_routes() -> Router<Arc<AppState>> {
-    Router::new().route(
-        "/:registry/:agent_id/audits",
-        get(handlers::list_agent_audits),
-    )
+    Router::new()
+        .route("/", get(handlers::list_agents))
+        .route("/search", get(handlers::search_agents))
+        .route(
+            "/:registry/:agent_id/audits",
+            get(handlers::list_agent_audits),
+        )
+}
+
+/// Ad-hoc endpoint checks, outside any audit
+pub fn check_routes() -> Router<Arc<AppState>> {
+    Router::new().route("/check/endpoint", post(handlers::check_endpoint))
+}
+
+/// A2A JSON-RPC endpoint (see `a2a`)
+pub fn a2a_routes() -> Router<Arc<AppState>> {
+    Router::new().route("/a2a", post(a2a::rpc))
+}
+
+/// MCP over HTTP (see `mcp_server`)
+pub fn mcp_routes() -> Router<Arc<AppState>> {
+    Router::new().route("/mcp", post(mcp_server::http))
+}
+
+/// The caller's audit usage and quota
+pub fn usage_routes() -> Router<Arc<AppState>> {
+    Router::new().route("/usage", get(handlers::get_usage))
+}
+
+/// GraphQL endpoint (POST) and GraphiQL explorer (GET)
+pub fn graphql_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/graphql", get(graphql::graphiql).post(graphql::graphql_handler))
+        .layer(Extension(graphql::build_schema()))
 }
 
 /// Admin routes for privileged operations (protected by ADMIN_API_KEY)
@@ -27,4 +65,14 @@ pub fn admin_routes() -> Router<Arc<AppState>> {
     Router::new()
         .route("/register", post(handlers::register_agent))
         .route("/set-uri", post(handlers::set_agent_uri))
+        .route("/arweave/balance", get(handlers::get_arweave_balance))
+        .route("/funding", get(handlers::get_funding))
+        .route("/funding/topup", post(handlers::topup_funding))
+        .route("/signers", get(handlers::list_signers))
+        .route("/signers/retire", post(handlers::retire_signer))
+        .route("/integrity", get(handlers::get_integrity_stats))
+        .route("/audits/purge", post(handlers::purge_audits))
+        .route("/reload", post(handlers::reload_settings))
+        .route("/pause/:subsystem", post(handlers::pause_subsystem))
+        .route("/resume/:subsystem", post(handlers::resume_subsystem))
 }
@@ -0,0 +1,219 @@
+//! Request body validation
+//!
+//! `ValidatedJson` replaces `Json` for request bodies: malformed JSON, type
+//! mismatches and rule violations (`#[validate(...)]` on the request type) are
+//! all answered with a 422 listing the offending fields in `details.fields`.
+
+use axum::{
+    async_trait,
+    body::Bytes,
+    extract::{FromRequest, Request},
+    http::{header, StatusCode},
+};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Map, Value};
+use validator::{Validate, ValidationErrors, ValidationErrorsKind};
+
+use crate::types::WatchyError;
+
+/// Key for errors not tied to a single field (syntax errors, schema rules)
+const BODY_FIELD: &str = "body";
+
+/// JSON request body, deserialized and validated
+pub struct ValidatedJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = WatchyError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let is_json = req
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(is_json_content_type);
+        if !is_json {
+            return Err(WatchyError::InvalidRequest(
+                "Expected a JSON body (Content-Type: application/json)".to_string(),
+            ));
+        }
+
+        let bytes = Bytes::from_request(req, state).await.map_err(|e| {
+            if e.status() == StatusCode::PAYLOAD_TOO_LARGE {
+                WatchyError::PayloadTooLarge(e.body_text())
+            } else {
+                WatchyError::InvalidRequest(e.body_text())
+            }
+        })?;
+        parse(&bytes).map(ValidatedJson)
+    }
+}
+
+/// `application/json` or a `+json` subtype, parameters allowed
+fn is_json_content_type(value: &str) -> bool {
+    let essence = value.split(';').next().unwrap_or_default().trim();
+    let essence = essence.to_ascii_lowercase();
+    essence == "application/json"
+        || essence
+            .strip_prefix("application/")
+            .is_some_and(|subtype| subtype.len() > "+json".len() && subtype.ends_with("+json"))
+}
+
+/// Deserialize and validate a JSON body
+pub fn parse<T: DeserializeOwned + Validate>(bytes: &[u8]) -> Result<T, WatchyError> {
+    let body: Value = serde_json::from_slice(bytes)
+        .map_err(|e| invalid_field(BODY_FIELD.to_string(), "invalid_json", e.to_string()))?;
+    let value: T = serde_path_to_error::deserialize(body.clone()).map_err(|e| {
+        let path = e.path().to_string();
+        let field = if path == "." {
+            BODY_FIELD.to_string()
+        } else {
+            path
+        };
+        invalid_field(field, "invalid_type", e.into_inner().to_string())
+    })?;
+
+    value.validate().map_err(|errors| {
+        let mut fields = Map::new();
+        collect(&errors, &body, None, &mut fields);
+        let message = fields
+            .keys()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(", ");
+        WatchyError::Validation {
+            message: format!("Invalid fields: {}", message),
+            fields: Value::Object(fields),
+        }
+    })?;
+    Ok(value)
+}
+
+fn invalid_field(field: String, code: &str, message: String) -> WatchyError {
+    WatchyError::Validation {
+        message: format!("{}: {}", field, message),
+        fields: json!({ field: [{ "code": code, "message": message }] }),
+    }
+}
+
+/// Flatten validation errors into `{"path": [{"code", "message", "params"}]}`
+fn collect(
+    errors: &ValidationErrors,
+    body: &Value,
+    prefix: Option<&str>,
+    out: &mut Map<String, Value>,
+) {
+    for (field, kind) in errors.errors() {
+        let name = wire_name(field, body);
+        let path = match (prefix, name.as_str()) {
+            (_, "__all__") => prefix.unwrap_or(BODY_FIELD).to_string(),
+            (Some(prefix), _) => format!("{}.{}", prefix, name),
+            (None, _) => name.clone(),
+        };
+        match kind {
+            ValidationErrorsKind::Field(errors) => {
+                let entries = errors.iter().map(|error| {
+                    let params: Map<String, Value> = error
+                        .params
+                        .iter()
+                        .filter(|(key, _)| *key != "value")
+                        .map(|(key, value)| (key.to_string(), value.clone()))
+                        .collect();
+                    json!({
+                        "code": error.code,
+                        "message": error.message.as_deref().unwrap_or(error.code.as_ref()),
+                        "params": params,
+                    })
+                });
+                out.entry(path)
+                    .or_insert_with(|| Value::Array(Vec::new()))
+                    .as_array_mut()
+                    .expect("field errors are arrays")
+                    .extend(entries);
+            }
+            ValidationErrorsKind::Struct(errors) => {
+                collect(errors, &body[name.as_str()], Some(&path), out)
+            }
+            ValidationErrorsKind::List(items) => {
+                for (index, errors) in items {
+                    let item = &body[name.as_str()][*index];
+                    collect(errors, item, Some(&format!("{}[{}]", path, index)), out);
+                }
+            }
+        }
+    }
+}
+
+/// A field's key as sent: validator reports Rust names, while some request
+/// bodies are camelCase (only fields that were sent can fail a rule)
+fn wire_name(field: &str, body: &Value) -> String {
+    let mut camel = String::with_capacity(field.len());
+    let mut upper = false;
+    for c in field.chars() {
+        match c {
+            '_' => upper = true,
+            c if upper => {
+                camel.extend(c.to_uppercase());
+                upper = false;
+            }
+            c => camel.push(c),
+        }
+    }
+    if body.get(field).is_none() && body.get(&camel).is_some() {
+        camel
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::handlers::PurgeAuditsRequest;
+    use crate::types::AuditRequest;
+
+    fn fields<T: std::fmt::Debug>(result: Result<T, WatchyError>) -> Value {
+        match result {
+            Err(WatchyError::Validation { fields, .. }) => fields,
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_json_content_type() {
+        assert!(is_json_content_type("application/json"));
+        assert!(is_json_content_type("Application/JSON; charset=utf-8"));
+        assert!(is_json_content_type("application/problem+json"));
+        assert!(!is_json_content_type("application/jsonp"));
+        assert!(!is_json_content_type("application/json-patch"));
+        assert!(!is_json_content_type("application/+json"));
+        assert!(!is_json_content_type("text/json"));
+    }
+
+    #[test]
+    fn test_field_level_errors() {
+        assert!(parse::<AuditRequest>(br#"{"agent_id": 7}"#).is_ok());
+
+        let errors = fields(parse::<AuditRequest>(
+            br#"{"agent_id": 0, "timeout_secs": 0}"#,
+        ));
+        assert_eq!(errors["agent_id"][0]["code"], "range");
+        assert_eq!(errors["timeout_secs"][0]["params"]["min"], 1.0);
+
+        let errors = fields(parse::<AuditRequest>(br#"{"agent_id": "seven"}"#));
+        assert_eq!(errors["agent_id"][0]["code"], "invalid_type");
+
+        let errors = fields(parse::<AuditRequest>(br#"{"agent_id": 7"#));
+        assert_eq!(errors["body"][0]["code"], "invalid_json");
+
+        // camelCase bodies keep their keys; struct-level rules land on "body"
+        let errors = fields(parse::<PurgeAuditsRequest>(br#"{"agentId": 0}"#));
+        assert_eq!(errors["agentId"][0]["code"], "range");
+        let errors = fields(parse::<PurgeAuditsRequest>(br#"{"chainId": 8453}"#));
+        assert_eq!(errors["body"][0]["code"], "required");
+    }
+}
@@ -0,0 +1,90 @@
+//! Upload credit and bundler top-ups
+//!
+//! `GET /admin/funding` estimates how many more audits the signer's credit at
+//! the upload provider covers: the provider's price for an average published
+//! audit (Markdown + JSON report of recent completed jobs), divided into the
+//! balance. Turbo and Irys are funded by sending ETH on Ethereum mainnet to
+//! their funding address and then submitting the transaction hash.
+
+use crate::arweave::uploader::ProviderBalance;
+use crate::audit::generate_markdown_report;
+use crate::store::AuditJob;
+use crate::types::AuditStatus;
+
+/// Chain that top-up transfers are sent on (the providers' "ethereum" currency)
+pub const FUNDING_CHAIN_ID: u64 = 1;
+
+/// Recent completed audits averaged for the report size
+const REPORT_SIZE_SAMPLE: usize = 20;
+
+/// DataItem bytes around each uploaded report (signature, owner, tags)
+const DATA_ITEM_OVERHEAD: u64 = 1024;
+
+/// Average upload size of one published audit, over recent completed jobs
+///
+/// Counts the Markdown and JSON reports as separate DataItems. `None` until
+/// an audit has completed.
+pub fn average_report_bytes(jobs: &[AuditJob]) -> Option<u64> {
+    let mut completed: Vec<&AuditJob> = jobs
+        .iter()
+        .filter(|job| job.status == AuditStatus::Completed)
+        .filter(|job| job.result.is_some())
+        .collect();
+    completed.sort_by_key(|job| std::cmp::Reverse(job.created_at));
+
+    let sizes: Vec<u64> = completed
+        .into_iter()
+        .take(REPORT_SIZE_SAMPLE)
+        .filter_map(|job| job.result.as_ref())
+        .map(|report| {
+            let json = serde_json::to_vec(report).map_or(0, |bytes| bytes.len());
+            let markdown = generate_markdown_report(report, None).len();
+            (json + markdown) as u64 + 2 * DATA_ITEM_OVERHEAD
+        })
+        .collect();
+    if sizes.is_empty() {
+        return None;
+    }
+    Some(sizes.iter().sum::<u64>() / sizes.len() as u64)
+}
+
+/// Whole audits the balance still pays for at `cost` per audit
+///
+/// `None` when the amounts are in different units or the cost is zero.
+pub fn audits_remaining(balance: &ProviderBalance, cost: &ProviderBalance) -> Option<u128> {
+    if balance.unit != cost.unit {
+        return None;
+    }
+    let balance: u128 = balance.amount.parse().ok()?;
+    let cost: u128 = cost.amount.parse().ok()?;
+    balance.checked_div(cost)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn amount(amount: &str, unit: &str) -> ProviderBalance {
+        ProviderBalance {
+            amount: amount.to_string(),
+            unit: unit.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_audits_remaining() {
+        assert_eq!(
+            audits_remaining(&amount("1000000", "winc"), &amount("300000", "winc")),
+            Some(3)
+        );
+        assert_eq!(
+            audits_remaining(&amount("1000000", "winc"), &amount("0", "winc")),
+            None
+        );
+        assert_eq!(
+            audits_remaining(&amount("1000000", "winc"), &amount("10", "wei")),
+            None
+        );
+        assert_eq!(average_report_bytes(&[]), None);
+    }
+}
@@ -1,41 +1,45 @@
 use alloy::primitives::{keccak256, Address, PrimitiveSignature};
 use alloy::signers::local::PrivateKeySigner;
 use alloy::signers::Signer;
-use bundles_rs::ans104::data_item::DataItem;
-use bundles_rs::ans104::tags::Tag;
-use bundles_rs::crypto::ethereum::EthereumSigner;
-use serde::{Deserialize, Serialize};
+use std::time::Duration;
 use tracing::{debug, info, warn};
 
+use super::ans104::{self, DataItem, Tag};
+use super::uploader::{ArweaveUploader, ProviderBalance, TurboUploader};
+use crate::canonical;
 use crate::types::WatchyError;
 
-/// Turbo upload endpoint for Ethereum
-const TURBO_UPLOAD_URL: &str = "https://turbo.ardrive.io/tx/ethereum";
+/// Initial delay between retrieval attempts when verifying an upload
+const VERIFY_INITIAL_BACKOFF_MS: u64 = 1_000;
 
-/// Irys client for uploading data to Arweave via Turbo
+/// Upper bound for the delay between retrieval attempts
+const VERIFY_MAX_BACKOFF_MS: u64 = 30_000;
+
+/// Gateway used for generated links unless overridden
+pub const DEFAULT_ARWEAVE_GATEWAY: &str = "https://arweave.net";
+
+/// Client for signing ANS-104 DataItems and uploading them to Arweave
+///
+/// Uploads go through Turbo unless another provider is set with `with_uploader`.
 pub struct IrysClient {
     http_client: reqwest::Client,
-    private_key: Option<String>,
+    uploader: Box<dyn ArweaveUploader>,
+    signer: Option<PrivateKeySigner>,
     #[allow(dead_code)]
     address: Option<Address>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct TurboUploadResponse {
-    pub id: String,
-    #[serde(default)]
-    pub owner: String,
-    #[serde(default)]
-    pub data_caches: Vec<String>,
-    #[serde(default)]
-    pub fast_finality_indexes: Vec<String>,
+    /// Gateway retrieval attempts after upload (None = don't verify)
+    verify_attempts: Option<u32>,
+    /// Gateway base URL for generated links
+    gateway: String,
 }
 
 #[derive(Debug, Clone)]
 pub struct UploadResult {
     pub tx_id: String,
+    /// Gateway URL
     pub arweave_url: String,
+    /// Protocol-native URI ("ar://<tx>")
+    pub uri: String,
 }
 
 impl IrysClient {
@@ -44,56 +48,113 @@ impl IrysClient {
     /// If private_key is provided, uploads will be signed.
     /// Without a key, uploads will fail.
     pub fn new(private_key: Option<&str>) -> Result<Self, WatchyError> {
-        let (pk, address) = if let Some(key) = private_key {
+        let (signer, address) = if let Some(key) = private_key {
             let key_clean = key.strip_prefix("0x").unwrap_or(key);
             let signer: PrivateKeySigner = key_clean
                 .parse()
                 .map_err(|e| WatchyError::Internal(format!("Invalid private key: {}", e)))?;
 
             let address = signer.address();
-            (Some(key.to_string()), Some(address))
+            (Some(signer), Some(address))
         } else {
             (None, None)
         };
 
         Ok(Self {
             http_client: reqwest::Client::new(),
-            private_key: pk,
+            uploader: Box::new(TurboUploader::default()),
+            signer,
             address,
+            verify_attempts: None,
+            gateway: DEFAULT_ARWEAVE_GATEWAY.to_string(),
         })
     }
 
+    /// Generate links (and verify retrievability) through a different gateway
+    pub fn with_gateway(mut self, gateway: &str) -> Self {
+        self.gateway = gateway.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Upload through a different provider (see `uploader::uploader_from_config`)
+    pub fn with_uploader(mut self, uploader: Box<dyn ArweaveUploader>) -> Self {
+        self.uploader = uploader;
+        self
+    }
+
+    /// Verify uploads through a shared client instead of a private one
+    pub fn with_http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.http_client = http_client;
+        self
+    }
+
+    /// Confirm every upload is retrievable from the gateway before returning it
+    ///
+    /// The gateway is polled up to `attempts` times with exponential backoff and the
+    /// served bytes must match what was uploaded. Passing 0 disables verification.
+    pub fn with_verification(mut self, attempts: u32) -> Self {
+        self.verify_attempts = (attempts > 0).then_some(attempts);
+        self
+    }
+
     /// Get the signer's address if available
     #[allow(dead_code)]
     pub fn address(&self) -> Option<Address> {
         self.address
     }
 
-    /// Upload data to Arweave via Turbo using ANS-104 DataItem format
-    pub async fn upload(
+    /// Name of the configured upload provider
+    pub fn provider_name(&self) -> &'static str {
+        self.uploader.provider().as_str()
+    }
+
+    /// Signer's upload credit at the provider, if it tracks balances
+    pub async fn balance(&self) -> Result<Option<ProviderBalance>, WatchyError> {
+        let address = self.address.ok_or_else(|| {
+            WatchyError::Internal("Balance query requires a signer (PRIVATE_KEY)".to_string())
+        })?;
+        self.uploader.balance(&address.to_string()).await
+    }
+
+    /// Address that accepts top-ups, if the provider is funded by direct transfer
+    pub async fn funding_address(&self) -> Result<Option<String>, WatchyError> {
+        self.uploader.funding_address().await
+    }
+
+    /// Provider's price for uploading `bytes`, if it publishes one
+    pub async fn price(&self, bytes: u64) -> Result<Option<ProviderBalance>, WatchyError> {
+        self.uploader.price(bytes).await
+    }
+
+    /// Have the provider credit a transfer to its funding address
+    pub async fn confirm_funding(&self, tx_id: &str) -> Result<(), WatchyError> {
+        self.uploader.confirm_funding(tx_id).await
+    }
+
+    /// Gateway URL for an Arweave ID
+    pub fn url_for(&self, id: &str) -> String {
+        format!("{}/{}", self.gateway, id)
+    }
+
+    /// Protocol-native URI for an Arweave ID
+    pub fn uri_for(id: &str) -> String {
+        format!("ar://{}", id)
+    }
+
+    /// Build and sign a DataItem without uploading it
+    ///
+    /// The ID (and so the final URL) is known as soon as the item is signed,
+    /// which lets callers reference it before the upload happens.
+    pub async fn sign_item(
         &self,
         data: &[u8],
         content_type: &str,
         tags: Vec<(&str, &str)>,
-    ) -> Result<UploadResult, WatchyError> {
-        let size = data.len();
-        debug!(
-            "Uploading {} bytes to Turbo (content-type: {})",
-            size, content_type
-        );
-
-        let private_key = self.private_key.as_ref().ok_or_else(|| {
-            WatchyError::Internal("Turbo upload requires a signer (PRIVATE_KEY)".to_string())
+    ) -> Result<DataItem, WatchyError> {
+        let signer = self.signer.as_ref().ok_or_else(|| {
+            WatchyError::Internal("Arweave upload requires a signer (PRIVATE_KEY)".to_string())
         })?;
 
-        // Parse private key and create bundles_rs signer
-        let key_clean = private_key.strip_prefix("0x").unwrap_or(private_key);
-        let key_bytes = hex::decode(key_clean)
-            .map_err(|e| WatchyError::Internal(format!("Invalid private key hex: {}", e)))?;
-
-        let signer = EthereumSigner::from_bytes(&key_bytes)
-            .map_err(|e| WatchyError::Internal(format!("Failed to create signer: {}", e)))?;
-
         // Build tags - always include Content-Type first
         let mut all_tags = vec![Tag::new("Content-Type", content_type)];
         for (name, value) in tags {
@@ -102,84 +163,235 @@ impl IrysClient {
             }
         }
 
-        // Create and sign the DataItem using bundles_rs
-        let data_item = DataItem::build_and_sign(
-            &signer,
-            None, // no target
-            None, // no anchor
-            all_tags,
-            data.to_vec(),
-        )
-        .map_err(|e| WatchyError::Internal(format!("Failed to create DataItem: {}", e)))?;
-
-        // Serialize the DataItem to bytes
-        let item_bytes = data_item
-            .to_bytes()
-            .map_err(|e| WatchyError::Internal(format!("Failed to serialize DataItem: {}", e)))?;
+        DataItem::sign(signer, all_tags, data.to_vec()).await
+    }
 
+    /// Upload data to Arweave using ANS-104 DataItem format
+    pub async fn upload(
+        &self,
+        data: &[u8],
+        content_type: &str,
+        tags: Vec<(&str, &str)>,
+    ) -> Result<UploadResult, WatchyError> {
         debug!(
-            "Built DataItem: {} bytes total (data: {} bytes)",
-            item_bytes.len(),
-            size
+            "Uploading {} bytes via {} (content-type: {})",
+            data.len(),
+            self.provider_name(),
+            content_type
         );
 
-        // Upload to Turbo
-        let response = self
-            .http_client
-            .post(TURBO_UPLOAD_URL)
-            .header("Content-Type", "application/octet-stream")
-            .body(item_bytes)
-            .send()
-            .await
-            .map_err(|e| WatchyError::Internal(format!("Turbo upload failed: {}", e)))?;
+        let item = self.sign_item(data, content_type, tags).await?;
+        let result = self.submit(&item).await?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            warn!("Turbo upload failed: HTTP {} - {}", status, body);
-            return Err(WatchyError::Internal(format!(
-                "Turbo upload failed: HTTP {} - {}",
-                status, body
-            )));
+        if let Some(attempts) = self.verify_attempts {
+            self.verify_retrievable(&result, &item.data, attempts).await?;
         }
 
-        let upload_response: TurboUploadResponse = response
-            .json()
-            .await
-            .map_err(|e| WatchyError::Internal(format!("Failed to parse Turbo response: {}", e)))?;
+        Ok(result)
+    }
 
-        let result = UploadResult {
-            tx_id: upload_response.id.clone(),
-            arweave_url: format!("https://arweave.net/{}", upload_response.id),
-        };
+    /// Upload several signed DataItems as one ANS-104 bundle
+    ///
+    /// Returns one result per item, in order. Each item stays addressable by its own ID.
+    pub async fn upload_bundle(&self, items: Vec<DataItem>) -> Result<Vec<UploadResult>, WatchyError> {
+        let bundle = ans104::bundle(&items);
+        // Catch malformed bundles before spending upload credit
+        ans104::unbundle(&bundle)?;
+        debug!(
+            "Uploading bundle of {} items ({} bytes) via {}",
+            items.len(),
+            bundle.len(),
+            self.provider_name()
+        );
+
+        let wrapper = self
+            .sign_item(
+                &bundle,
+                "application/octet-stream",
+                vec![
+                    ("Bundle-Format", "binary"),
+                    ("Bundle-Version", "2.0.0"),
+                    ("App-Name", "Watchy"),
+                    ("App-Version", env!("CARGO_PKG_VERSION")),
+                ],
+            )
+            .await?;
+        self.submit(&wrapper).await?;
+
+        let mut results = Vec::with_capacity(items.len());
+        for item in &items {
+            let result = UploadResult {
+                tx_id: item.id(),
+                arweave_url: self.url_for(&item.id()),
+                uri: Self::uri_for(&item.id()),
+            };
+            if let Some(attempts) = self.verify_attempts {
+                self.verify_retrievable(&result, &item.data, attempts).await?;
+            }
+            results.push(result);
+        }
+
+        Ok(results)
+    }
+
+    /// Send a signed DataItem to the provider and check the ID it assigned
+    async fn submit(&self, item: &DataItem) -> Result<UploadResult, WatchyError> {
+        let expected_id = item.id();
+        let bytes = item.to_bytes();
+
+        // Providers reject bad items anyway, but this keeps a broken encoder from
+        // ever producing an ID we'd record as a feedbackURI
+        DataItem::from_bytes(&bytes)?.verify()?;
+
+        let tx_id = self.uploader.submit(bytes).await?;
+
+        if tx_id != expected_id {
+            warn!(
+                "{} returned ID {} for DataItem {}",
+                self.provider_name(),
+                tx_id,
+                expected_id
+            );
+            return Err(WatchyError::Internal(format!(
+                "{} returned ID {} but the signed DataItem is {}",
+                self.provider_name(),
+                tx_id,
+                expected_id
+            )));
+        }
 
         info!(
-            "Uploaded to Arweave via Turbo: {} (size: {} bytes)",
-            result.tx_id, size
+            "Uploaded to Arweave via {}: {} (size: {} bytes)",
+            self.provider_name(),
+            tx_id,
+            item.data.len()
         );
 
-        Ok(result)
+        Ok(UploadResult {
+            arweave_url: self.url_for(&tx_id),
+            uri: Self::uri_for(&tx_id),
+            tx_id,
+        })
     }
 
-    /// Upload JSON data with appropriate tags
+    /// Poll the gateway until the uploaded data is served and matches `expected`
+    async fn verify_retrievable(
+        &self,
+        result: &UploadResult,
+        expected: &[u8],
+        attempts: u32,
+    ) -> Result<(), WatchyError> {
+        let mut last_error = String::new();
+
+        for attempt in 0..attempts {
+            if attempt > 0 {
+                tokio::time::sleep(verify_backoff(attempt)).await;
+            }
+
+            let response = match self.http_client.get(&result.arweave_url).send().await {
+                Ok(r) => r,
+                Err(e) => {
+                    last_error = format!("request failed: {}", e);
+                    debug!(
+                        "Arweave retrieval attempt {}/{} for {}: {}",
+                        attempt + 1,
+                        attempts,
+                        result.tx_id,
+                        last_error
+                    );
+                    continue;
+                }
+            };
+
+            if !response.status().is_success() {
+                last_error = format!("HTTP {}", response.status());
+                debug!(
+                    "Arweave retrieval attempt {}/{} for {}: {}",
+                    attempt + 1,
+                    attempts,
+                    result.tx_id,
+                    last_error
+                );
+                continue;
+            }
+
+            let body = match response.bytes().await {
+                Ok(b) => b,
+                Err(e) => {
+                    last_error = format!("failed to read body: {}", e);
+                    continue;
+                }
+            };
+
+            // A gateway serving different bytes under this ID won't fix itself by retrying
+            if body.as_ref() != expected {
+                return Err(WatchyError::Internal(format!(
+                    "Arweave content mismatch for {}: expected {} bytes (keccak {}), got {} bytes (keccak {})",
+                    result.tx_id,
+                    expected.len(),
+                    keccak256(expected),
+                    body.len(),
+                    keccak256(&body)
+                )));
+            }
+
+            info!(
+                "Verified Arweave upload {} is retrievable (attempt {}/{})",
+                result.tx_id,
+                attempt + 1,
+                attempts
+            );
+            return Ok(());
+        }
+
+        warn!(
+            "Arweave upload {} not retrievable after {} attempts: {}",
+            result.tx_id, attempts, last_error
+        );
+        Err(WatchyError::Internal(format!(
+            "Arweave upload {} not retrievable after {} attempts: {}",
+            result.tx_id, attempts, last_error
+        )))
+    }
+
+    /// Upload a JSON report with appropriate tags
+    ///
+    /// The stored bytes are the ones its feedbackHash is computed over
+    /// (`canonical::report_bytes`).
     pub async fn upload_json(
         &self,
         json: &serde_json::Value,
         filename: &str,
     ) -> Result<UploadResult, WatchyError> {
-        let data = serde_json::to_vec_pretty(json)
-            .map_err(|e| WatchyError::Internal(format!("JSON serialization failed: {}", e)))?;
-
-        self.upload(
-            &data,
-            "application/json",
-            vec![
-                ("filename", filename),
-                ("App-Name", "Watchy"),
-                ("App-Version", env!("CARGO_PKG_VERSION")),
-            ],
-        )
-        .await
+        let data = crate::canonical::report_bytes(json);
+
+        self.upload(&data, "application/json", report_tags(filename))
+            .await
+    }
+
+    /// Upload JSON in one bundle together with already-signed items
+    ///
+    /// Falls back to a plain upload when there is nothing to bundle with.
+    pub async fn upload_json_bundled(
+        &self,
+        json: &serde_json::Value,
+        filename: &str,
+        mut companions: Vec<DataItem>,
+    ) -> Result<UploadResult, WatchyError> {
+        if companions.is_empty() {
+            return self.upload_json(json, filename).await;
+        }
+
+        let data = crate::canonical::report_bytes(json);
+        let item = self
+            .sign_item(&data, "application/json", report_tags(filename))
+            .await?;
+        companions.push(item);
+
+        self.upload_bundle(companions)
+            .await?
+            .pop()
+            .ok_or_else(|| WatchyError::Internal("Bundle upload returned no results".to_string()))
     }
 
     /// Upload Markdown data with appropriate tags
@@ -188,20 +400,35 @@ impl IrysClient {
         markdown: &str,
         filename: &str,
     ) -> Result<UploadResult, WatchyError> {
-        self.upload(
-            markdown.as_bytes(),
-            "text/markdown",
-            vec![
-                ("filename", filename),
-                ("App-Name", "Watchy"),
-                ("App-Version", env!("CARGO_PKG_VERSION")),
-            ],
-        )
-        .await
+        self.upload(markdown.as_bytes(), "text/markdown", report_tags(filename))
+            .await
     }
+
+    /// Sign Markdown data for a later bundled upload
+    pub async fn sign_markdown(&self, markdown: &str, filename: &str) -> Result<DataItem, WatchyError> {
+        self.sign_item(markdown.as_bytes(), "text/markdown", report_tags(filename))
+            .await
+    }
+}
+
+/// Tags attached to every uploaded report file
+fn report_tags(filename: &str) -> Vec<(&str, &str)> {
+    vec![
+        ("filename", filename),
+        ("App-Name", "Watchy"),
+        ("App-Version", env!("CARGO_PKG_VERSION")),
+    ]
+}
+
+/// Delay before the given retrieval attempt (1-based), doubling up to a cap
+fn verify_backoff(attempt: u32) -> Duration {
+    let factor = 1u64 << attempt.saturating_sub(1).min(16);
+    Duration::from_millis((VERIFY_INITIAL_BACKOFF_MS * factor).min(VERIFY_MAX_BACKOFF_MS))
 }
 
 /// Sign an audit report and return the signature
+///
+/// The signature covers keccak256 of the report's canonical bytes (see `canonical`).
 pub async fn sign_report(
     report_json: &serde_json::Value,
     private_key: &str,
@@ -211,11 +438,7 @@ pub async fn sign_report(
         .parse()
         .map_err(|e| WatchyError::Internal(format!("Invalid private key: {}", e)))?;
 
-    // Create a deterministic hash of the report
-    let report_bytes = serde_json::to_vec(report_json)
-        .map_err(|e| WatchyError::Internal(format!("Serialization failed: {}", e)))?;
-
-    let hash = keccak256(&report_bytes);
+    let hash = keccak256(canonical::report_bytes(report_json));
 
     // Sign the hash
     let signature = signer
@@ -227,16 +450,12 @@ pub async fn sign_report(
 }
 
 /// Verify a report signature
-#[allow(dead_code)]
 pub fn verify_report_signature(
     report_json: &serde_json::Value,
     signature: &str,
     expected_address: &str,
 ) -> Result<bool, WatchyError> {
-    let report_bytes = serde_json::to_vec(report_json)
-        .map_err(|e| WatchyError::Internal(format!("Serialization failed: {}", e)))?;
-
-    let hash = keccak256(&report_bytes);
+    let hash = keccak256(canonical::report_bytes(report_json));
 
     let sig_bytes = hex::decode(signature.strip_prefix("0x").unwrap_or(signature))
         .map_err(|e| WatchyError::Internal(format!("Invalid signature hex: {}", e)))?;
@@ -259,6 +478,15 @@ pub fn verify_report_signature(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_verify_backoff() {
+        assert_eq!(verify_backoff(1), Duration::from_millis(1_000));
+        assert_eq!(verify_backoff(2), Duration::from_millis(2_000));
+        assert_eq!(verify_backoff(4), Duration::from_millis(8_000));
+        assert_eq!(verify_backoff(10), Duration::from_millis(VERIFY_MAX_BACKOFF_MS));
+        assert_eq!(verify_backoff(u32::MAX), Duration::from_millis(VERIFY_MAX_BACKOFF_MS));
+    }
+
     /// Integration test for Turbo upload
     /// Run with: cargo test turbo_upload_real -- --ignored --nocapture
     #[tokio::test]
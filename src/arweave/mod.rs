@@ -1,3 +1,7 @@
+pub mod ans104;
+pub mod funding;
 pub mod irys;
+pub mod uploader;
 
 pub use irys::IrysClient;
+pub use uploader::uploader_from_config;
@@ -0,0 +1,563 @@
+//! Arweave upload providers
+//!
+//! All providers accept signed ANS-104 DataItems over HTTP; they differ in
+//! endpoint layout and in whether they expose account balances, prices and
+//! funding by direct transfer.
+//! - `turbo`: ArDrive Turbo (default)
+//! - `irys`: an Irys bundler node
+//! - `bundler`: a self-hosted ANS-104 bundler (`POST {url}/tx`)
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::config::Config;
+use crate::types::WatchyError;
+
+/// Default Turbo upload service
+const TURBO_UPLOAD_URL: &str = "https://turbo.ardrive.io";
+
+/// Turbo payment service (balances are tracked separately from uploads)
+const TURBO_PAYMENT_URL: &str = "https://payment.ardrive.io";
+
+/// Default Irys bundler node
+const IRYS_NODE_URL: &str = "https://node1.irys.xyz";
+
+/// Which upload provider to use
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArweaveProvider {
+    /// ArDrive Turbo
+    Turbo,
+    /// Irys bundler node
+    IrysNode,
+    /// Self-hosted ANS-104 bundler
+    Bundler,
+}
+
+impl ArweaveProvider {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "turbo" | "ardrive" => Some(ArweaveProvider::Turbo),
+            "irys" | "irys_node" | "bundlr" => Some(ArweaveProvider::IrysNode),
+            "bundler" | "self_hosted" | "custom" => Some(ArweaveProvider::Bundler),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ArweaveProvider::Turbo => "turbo",
+            ArweaveProvider::IrysNode => "irys",
+            ArweaveProvider::Bundler => "bundler",
+        }
+    }
+}
+
+/// Upload credit held by the signer at a provider
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderBalance {
+    /// Amount in the provider's base unit, as a decimal string
+    pub amount: String,
+    /// Unit of `amount` (e.g. "winc" for Turbo, "wei" for Irys on Ethereum)
+    pub unit: String,
+}
+
+/// Response returned by bundlers after accepting a DataItem
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataItemReceipt {
+    pub id: String,
+    #[serde(default)]
+    pub owner: String,
+    #[serde(default)]
+    pub data_caches: Vec<String>,
+    #[serde(default)]
+    pub fast_finality_indexes: Vec<String>,
+}
+
+/// A service that accepts signed ANS-104 DataItems for posting to Arweave
+#[async_trait]
+pub trait ArweaveUploader: Send + Sync {
+    fn provider(&self) -> ArweaveProvider;
+
+    /// Submit a serialized DataItem, returning the Arweave ID
+    async fn submit(&self, data_item: Vec<u8>) -> Result<String, WatchyError>;
+
+    /// Upload credit held by `address`, if the provider tracks balances
+    async fn balance(&self, _address: &str) -> Result<Option<ProviderBalance>, WatchyError> {
+        Ok(None)
+    }
+
+    /// Address that accepts top-ups, if the provider is funded by direct transfer
+    async fn funding_address(&self) -> Result<Option<String>, WatchyError> {
+        Ok(None)
+    }
+
+    /// Cost of uploading `bytes`, in the same unit as `balance`
+    async fn price(&self, _bytes: u64) -> Result<Option<ProviderBalance>, WatchyError> {
+        Ok(None)
+    }
+
+    /// Credit a transfer to `funding_address` (by transaction hash) to the sender
+    async fn confirm_funding(&self, _tx_id: &str) -> Result<(), WatchyError> {
+        Err(WatchyError::InvalidRequest(format!(
+            "The {} provider isn't funded by direct transfer",
+            self.provider().as_str()
+        )))
+    }
+}
+
+/// Build the uploader selected by `ARWEAVE_PROVIDER`
+pub fn uploader_from_config(config: &Config, http_client: reqwest::Client) -> Box<dyn ArweaveUploader> {
+    let url = config.arweave_upload_url.clone();
+
+    match config.arweave_provider {
+        ArweaveProvider::Turbo => Box::new(TurboUploader {
+            http_client,
+            upload_url: url.unwrap_or_else(|| TURBO_UPLOAD_URL.to_string()),
+            payment_url: TURBO_PAYMENT_URL.to_string(),
+        }),
+        ArweaveProvider::IrysNode => Box::new(IrysNodeUploader {
+            http_client,
+            node_url: url.unwrap_or_else(|| IRYS_NODE_URL.to_string()),
+        }),
+        // Config::from_env guarantees a URL for the bundler provider
+        ArweaveProvider::Bundler => Box::new(BundlerUploader {
+            http_client,
+            url: url.unwrap_or_default(),
+        }),
+    }
+}
+
+/// ArDrive Turbo
+pub struct TurboUploader {
+    http_client: reqwest::Client,
+    upload_url: String,
+    payment_url: String,
+}
+
+impl Default for TurboUploader {
+    fn default() -> Self {
+        Self {
+            http_client: reqwest::Client::new(),
+            upload_url: TURBO_UPLOAD_URL.to_string(),
+            payment_url: TURBO_PAYMENT_URL.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl ArweaveUploader for TurboUploader {
+    fn provider(&self) -> ArweaveProvider {
+        ArweaveProvider::Turbo
+    }
+
+    async fn submit(&self, data_item: Vec<u8>) -> Result<String, WatchyError> {
+        let url = format!("{}/tx/ethereum", self.upload_url.trim_end_matches('/'));
+        post_data_item(&self.http_client, &url, data_item, "Turbo").await
+    }
+
+    async fn balance(&self, address: &str) -> Result<Option<ProviderBalance>, WatchyError> {
+        #[derive(Deserialize)]
+        struct TurboBalance {
+            winc: String,
+        }
+
+        let url = format!(
+            "{}/v1/account/balance/ethereum?address={}",
+            self.payment_url, address
+        );
+        let response = self
+            .http_client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| WatchyError::Internal(format!("Turbo balance query failed: {}", e)))?;
+
+        // Turbo has no account record until the first top-up
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(Some(ProviderBalance {
+                amount: "0".to_string(),
+                unit: "winc".to_string(),
+            }));
+        }
+
+        let balance: TurboBalance = parse_json_response(response, "Turbo balance").await?;
+        Ok(Some(ProviderBalance {
+            amount: balance.winc,
+            unit: "winc".to_string(),
+        }))
+    }
+
+    async fn funding_address(&self) -> Result<Option<String>, WatchyError> {
+        #[derive(Deserialize)]
+        struct TurboInfo {
+            #[serde(default)]
+            addresses: std::collections::HashMap<String, String>,
+        }
+
+        let url = format!("{}/v1/info", self.payment_url);
+        let response = self
+            .http_client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| WatchyError::Internal(format!("Turbo info query failed: {}", e)))?;
+
+        let info: TurboInfo = parse_json_response(response, "Turbo info").await?;
+        Ok(info.addresses.get("ethereum").cloned())
+    }
+
+    async fn price(&self, bytes: u64) -> Result<Option<ProviderBalance>, WatchyError> {
+        #[derive(Deserialize)]
+        struct TurboPrice {
+            winc: String,
+        }
+
+        let url = format!("{}/v1/price/bytes/{}", self.payment_url, bytes);
+        let response = self
+            .http_client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| WatchyError::Internal(format!("Turbo price query failed: {}", e)))?;
+
+        let price: TurboPrice = parse_json_response(response, "Turbo price").await?;
+        Ok(Some(ProviderBalance {
+            amount: price.winc,
+            unit: "winc".to_string(),
+        }))
+    }
+
+    async fn confirm_funding(&self, tx_id: &str) -> Result<(), WatchyError> {
+        let url = format!("{}/v1/account/balance/ethereum", self.payment_url);
+        post_funding_tx(&self.http_client, &url, tx_id, "Turbo").await
+    }
+}
+
+/// Irys bundler node
+pub struct IrysNodeUploader {
+    http_client: reqwest::Client,
+    node_url: String,
+}
+
+#[async_trait]
+impl ArweaveUploader for IrysNodeUploader {
+    fn provider(&self) -> ArweaveProvider {
+        ArweaveProvider::IrysNode
+    }
+
+    async fn submit(&self, data_item: Vec<u8>) -> Result<String, WatchyError> {
+        let url = format!("{}/tx/ethereum", self.node_url.trim_end_matches('/'));
+        post_data_item(&self.http_client, &url, data_item, "Irys").await
+    }
+
+    async fn balance(&self, address: &str) -> Result<Option<ProviderBalance>, WatchyError> {
+        #[derive(Deserialize)]
+        struct IrysBalance {
+            balance: String,
+        }
+
+        let url = format!(
+            "{}/account/balance/ethereum?address={}",
+            self.node_url.trim_end_matches('/'),
+            address
+        );
+        let response = self
+            .http_client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| WatchyError::Internal(format!("Irys balance query failed: {}", e)))?;
+
+        let balance: IrysBalance = parse_json_response(response, "Irys balance").await?;
+        Ok(Some(ProviderBalance {
+            amount: balance.balance,
+            unit: "wei".to_string(),
+        }))
+    }
+
+    async fn funding_address(&self) -> Result<Option<String>, WatchyError> {
+        #[derive(Deserialize)]
+        struct IrysInfo {
+            #[serde(default)]
+            addresses: std::collections::HashMap<String, String>,
+        }
+
+        let url = format!("{}/info", self.node_url.trim_end_matches('/'));
+        let response = self
+            .http_client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| WatchyError::Internal(format!("Irys info query failed: {}", e)))?;
+
+        let info: IrysInfo = parse_json_response(response, "Irys info").await?;
+        Ok(info.addresses.get("ethereum").cloned())
+    }
+
+    async fn price(&self, bytes: u64) -> Result<Option<ProviderBalance>, WatchyError> {
+        let url = format!(
+            "{}/price/ethereum/{}",
+            self.node_url.trim_end_matches('/'),
+            bytes
+        );
+        let response = self
+            .http_client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| WatchyError::Internal(format!("Irys price query failed: {}", e)))?;
+
+        // The node answers with a bare number
+        let price: serde_json::Value = parse_json_response(response, "Irys price").await?;
+        let amount = match price {
+            serde_json::Value::Number(n) => n.to_string(),
+            serde_json::Value::String(s) => s,
+            other => {
+                return Err(WatchyError::Internal(format!(
+                    "Unexpected Irys price response: {}",
+                    other
+                )))
+            }
+        };
+        Ok(Some(ProviderBalance {
+            amount,
+            unit: "wei".to_string(),
+        }))
+    }
+
+    async fn confirm_funding(&self, tx_id: &str) -> Result<(), WatchyError> {
+        let url = format!(
+            "{}/account/balance/ethereum",
+            self.node_url.trim_end_matches('/')
+        );
+        post_funding_tx(&self.http_client, &url, tx_id, "Irys").await
+    }
+}
+
+/// Self-hosted ANS-104 bundler
+pub struct BundlerUploader {
+    http_client: reqwest::Client,
+    url: String,
+}
+
+#[async_trait]
+impl ArweaveUploader for BundlerUploader {
+    fn provider(&self) -> ArweaveProvider {
+        ArweaveProvider::Bundler
+    }
+
+    async fn submit(&self, data_item: Vec<u8>) -> Result<String, WatchyError> {
+        let url = format!("{}/tx", self.url.trim_end_matches('/'));
+        post_data_item(&self.http_client, &url, data_item, "Bundler").await
+    }
+}
+
+/// POST a DataItem and extract the assigned ID
+async fn post_data_item(
+    http_client: &reqwest::Client,
+    url: &str,
+    data_item: Vec<u8>,
+    label: &str,
+) -> Result<String, WatchyError> {
+    let response = http_client
+        .post(url)
+        .header("Content-Type", "application/octet-stream")
+        .body(data_item)
+        .send()
+        .await
+        .map_err(|e| WatchyError::Internal(format!("{} upload failed: {}", label, e)))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        warn!("{} upload failed: HTTP {} - {}", label, status, body);
+        return Err(WatchyError::Internal(format!(
+            "{} upload failed: HTTP {} - {}",
+            label, status, body
+        )));
+    }
+
+    let receipt: DataItemReceipt = response.json().await.map_err(|e| {
+        WatchyError::Internal(format!("Failed to parse {} response: {}", label, e))
+    })?;
+
+    Ok(receipt.id)
+}
+
+/// Tell a provider about a funding transfer so it credits the sender
+async fn post_funding_tx(
+    http_client: &reqwest::Client,
+    url: &str,
+    tx_id: &str,
+    label: &str,
+) -> Result<(), WatchyError> {
+    let response = http_client
+        .post(url)
+        .json(&serde_json::json!({ "tx_id": tx_id }))
+        .send()
+        .await
+        .map_err(|e| WatchyError::Internal(format!("{} funding failed: {}", label, e)))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        warn!("{} funding of {} failed: HTTP {} - {}", label, tx_id, status, body);
+        return Err(WatchyError::Internal(format!(
+            "{} funding failed: HTTP {} - {}",
+            label, status, body
+        )));
+    }
+    Ok(())
+}
+
+async fn parse_json_response<T: serde::de::DeserializeOwned>(
+    response: reqwest::Response,
+    label: &str,
+) -> Result<T, WatchyError> {
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(WatchyError::Internal(format!(
+            "{} query failed: HTTP {} - {}",
+            label, status, body
+        )));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| WatchyError::Internal(format!("Failed to parse {} response: {}", label, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const ADDRESS: &str = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+
+    fn uploader(provider: ArweaveProvider, url: Option<String>) -> Box<dyn ArweaveUploader> {
+        let mut config = Config::from_env().unwrap();
+        config.arweave_provider = provider;
+        config.arweave_upload_url = url;
+        uploader_from_config(&config, reqwest::Client::new())
+    }
+
+    async fn respond(server: &MockServer, at: &str, response: ResponseTemplate) {
+        server.reset().await;
+        Mock::given(method("GET"))
+            .and(path(at))
+            .respond_with(response)
+            .mount(server)
+            .await;
+    }
+
+    fn json(body: serde_json::Value) -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_json(body)
+    }
+
+    #[test]
+    fn test_parse_provider() {
+        assert_eq!(ArweaveProvider::parse("ArDrive"), Some(ArweaveProvider::Turbo));
+        assert_eq!(ArweaveProvider::parse("bundlr"), Some(ArweaveProvider::IrysNode));
+        assert_eq!(ArweaveProvider::parse("self_hosted"), Some(ArweaveProvider::Bundler));
+        assert_eq!(ArweaveProvider::parse("filecoin"), None);
+    }
+
+    #[tokio::test]
+    async fn test_uploader_from_config() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(json(serde_json::json!({ "id": "item1" })))
+            .mount(&server)
+            .await;
+
+        // Each provider posts DataItems to its own endpoint under the configured URL
+        for (provider, endpoint) in [
+            (ArweaveProvider::Turbo, "/tx/ethereum"),
+            (ArweaveProvider::IrysNode, "/tx/ethereum"),
+            (ArweaveProvider::Bundler, "/tx"),
+        ] {
+            let uploader = uploader(provider, Some(format!("{}/", server.uri())));
+            assert_eq!(uploader.provider(), provider);
+            assert_eq!(uploader.submit(vec![1, 2, 3]).await.unwrap(), "item1");
+            let requests = server.received_requests().await.unwrap();
+            assert_eq!(requests.last().unwrap().url.path(), endpoint);
+        }
+
+        // Without a URL, the public services are used
+        assert_eq!(uploader(ArweaveProvider::Turbo, None).provider(), ArweaveProvider::Turbo);
+        assert_eq!(uploader(ArweaveProvider::IrysNode, None).provider(), ArweaveProvider::IrysNode);
+
+        // Bundlers don't track balances
+        let bundler = uploader(ArweaveProvider::Bundler, Some(server.uri()));
+        assert!(bundler.balance(ADDRESS).await.unwrap().is_none());
+        assert!(bundler.price(1024).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_turbo_balance() {
+        let server = MockServer::start().await;
+        let turbo = TurboUploader {
+            payment_url: server.uri(),
+            ..Default::default()
+        };
+        let balance_path = "/v1/account/balance/ethereum";
+
+        Mock::given(method("GET"))
+            .and(path(balance_path))
+            .and(query_param("address", ADDRESS))
+            .respond_with(json(serde_json::json!({ "winc": "1234", "controlledWinc": "0" })))
+            .mount(&server)
+            .await;
+        let balance = turbo.balance(ADDRESS).await.unwrap().unwrap();
+        assert_eq!((balance.amount.as_str(), balance.unit.as_str()), ("1234", "winc"));
+
+        // No account until the first top-up
+        respond(&server, balance_path, ResponseTemplate::new(404)).await;
+        assert_eq!(turbo.balance(ADDRESS).await.unwrap().unwrap().amount, "0");
+
+        // Malformed or failed responses are errors, not a zero balance
+        respond(&server, balance_path, json(serde_json::json!({ "balance": "1234" }))).await;
+        assert!(turbo.balance(ADDRESS).await.is_err());
+        respond(&server, balance_path, ResponseTemplate::new(200).set_body_string("<html>")).await;
+        assert!(turbo.balance(ADDRESS).await.is_err());
+        respond(&server, balance_path, ResponseTemplate::new(502)).await;
+        assert!(turbo.balance(ADDRESS).await.is_err());
+
+        let price_path = "/v1/price/bytes/1024";
+        respond(&server, price_path, json(serde_json::json!({ "winc": "99" }))).await;
+        assert_eq!(turbo.price(1024).await.unwrap().unwrap().amount, "99");
+        respond(&server, price_path, json(serde_json::json!({ "winc": 99 }))).await;
+        assert!(turbo.price(1024).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_irys_balance() {
+        let server = MockServer::start().await;
+        let irys = uploader(ArweaveProvider::IrysNode, Some(server.uri()));
+        let balance_path = "/account/balance/ethereum";
+
+        respond(&server, balance_path, json(serde_json::json!({ "balance": "5000" }))).await;
+        let balance = irys.balance(ADDRESS).await.unwrap().unwrap();
+        assert_eq!((balance.amount.as_str(), balance.unit.as_str()), ("5000", "wei"));
+
+        // Unlike Turbo, a missing account isn't a zero balance
+        respond(&server, balance_path, ResponseTemplate::new(404)).await;
+        assert!(irys.balance(ADDRESS).await.is_err());
+        respond(&server, balance_path, json(serde_json::json!({ "winc": "5000" }))).await;
+        assert!(irys.balance(ADDRESS).await.is_err());
+
+        // Prices come as a bare number or string
+        let price_path = "/price/ethereum/1024";
+        respond(&server, price_path, json(serde_json::json!(731))).await;
+        assert_eq!(irys.price(1024).await.unwrap().unwrap().amount, "731");
+        respond(&server, price_path, json(serde_json::json!("731"))).await;
+        assert_eq!(irys.price(1024).await.unwrap().unwrap().amount, "731");
+        respond(&server, price_path, json(serde_json::json!({ "price": 731 }))).await;
+        assert!(irys.price(1024).await.is_err());
+    }
+}
@@ -0,0 +1,253 @@
+//! Watchy's own TEE attestation
+//!
+//! When Watchy runs inside a TEE (EigenCloud), the platform's attestation
+//! quote is loaded once at startup from a file or a local endpoint. Reports
+//! reference it by hash inside the signed `auditor` block, and the full quote
+//! is served at `GET /attestation` so consumers can verify the auditor itself.
+//!
+//! Every quote is bound to the report signer: its report data is
+//! `sha256("watchy-attestation-v1:" || signer || sha256(request))`, where the
+//! request is empty for the startup quote and the caller's nonce for a fresh
+//! one (`GET /attestation?nonce=`). A quote that doesn't carry those bytes is
+//! rejected, so a quote from another enclave or key can't be passed off.
+
+use base64::Engine;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::env;
+use tracing::info;
+
+use crate::types::WatchyError;
+
+/// Longest nonce accepted for a fresh quote
+pub const MAX_NONCE_LEN: usize = 128;
+
+/// Where the quote comes from and how consumers verify it
+#[derive(Debug, Clone)]
+pub struct AttestationConfig {
+    /// TEE platform name reported to consumers
+    pub platform: String,
+    /// Endpoint returning the raw quote (e.g. the platform's local attestation service)
+    pub quote_url: Option<String>,
+    /// File containing the raw quote
+    pub quote_file: Option<String>,
+    /// Public page or API where the quote can be verified
+    pub verify_url: Option<String>,
+}
+
+impl AttestationConfig {
+    /// Load from `TEE_ATTESTATION_*`; `None` unless a quote URL or file is set
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        let quote_url = env::var("TEE_ATTESTATION_URL").ok();
+        let quote_file = env::var("TEE_ATTESTATION_FILE").ok();
+        if quote_url.is_some() && quote_file.is_some() {
+            anyhow::bail!("Set only one of TEE_ATTESTATION_URL and TEE_ATTESTATION_FILE");
+        }
+        if quote_url.is_none() && quote_file.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            platform: env::var("TEE_PLATFORM").unwrap_or_else(|_| "eigencloud".to_string()),
+            quote_url,
+            quote_file,
+            verify_url: env::var("TEE_ATTESTATION_VERIFY_URL").ok(),
+        }))
+    }
+}
+
+/// The attestation served at `GET /attestation`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Attestation {
+    pub platform: String,
+    /// Base64 of the raw quote
+    pub quote: String,
+    /// Hex SHA-256 of the raw quote
+    pub quote_sha256: String,
+    /// Address that signs reports and feedback from inside the enclave
+    pub signer: String,
+    /// Hex SHA-256 of the request the quote was produced for
+    pub request_sha256: String,
+    /// Hex report data the quote carries (binds it to `signer` and the request)
+    pub report_data: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verify_url: Option<String>,
+    pub loaded_at: u64,
+}
+
+/// What a report carries: enough to fetch and match the full quote
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AttestationRef {
+    pub platform: String,
+    pub quote_sha256: String,
+    pub report_data: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verify_url: Option<String>,
+}
+
+impl Attestation {
+    fn from_quote(
+        config: &AttestationConfig,
+        quote: &[u8],
+        signer: &str,
+        request: &[u8],
+    ) -> Result<Self, WatchyError> {
+        let report_data = report_data(signer, request);
+        if !quote.windows(report_data.len()).any(|window| window == report_data) {
+            return Err(WatchyError::Internal(format!(
+                "Attestation quote doesn't carry report data {} for signer {}",
+                hex::encode(report_data),
+                signer
+            )));
+        }
+        Ok(Self {
+            platform: config.platform.clone(),
+            quote: base64::engine::general_purpose::STANDARD.encode(quote),
+            quote_sha256: hex::encode(Sha256::digest(quote)),
+            signer: signer.to_lowercase(),
+            request_sha256: hex::encode(Sha256::digest(request)),
+            report_data: hex::encode(report_data),
+            verify_url: config.verify_url.clone(),
+            loaded_at: chrono::Utc::now().timestamp() as u64,
+        })
+    }
+
+    pub fn reference(&self) -> AttestationRef {
+        AttestationRef {
+            platform: self.platform.clone(),
+            quote_sha256: self.quote_sha256.clone(),
+            report_data: self.report_data.clone(),
+            verify_url: self.verify_url.clone(),
+        }
+    }
+}
+
+/// Report data binding a quote to the signer and the request it answers
+pub fn report_data(signer: &str, request: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(b"watchy-attestation-v1:");
+    hasher.update(signer.to_lowercase().as_bytes());
+    hasher.update(Sha256::digest(request));
+    hasher.finalize().into()
+}
+
+/// Read the startup quote from the configured file or endpoint
+///
+/// `client` must not be SSRF-guarded: the attestation service is usually local.
+pub async fn load(
+    config: &AttestationConfig,
+    client: &reqwest::Client,
+    signer: Option<&str>,
+) -> Result<Attestation, WatchyError> {
+    let signer = signer.ok_or_else(|| {
+        WatchyError::Internal("TEE attestation needs a signing key to bind to".to_string())
+    })?;
+    let quote = match &config.quote_file {
+        Some(path) => tokio::fs::read(path).await.map_err(|e| {
+            WatchyError::Internal(format!("Failed to read attestation quote {}: {}", path, e))
+        })?,
+        None => fetch_quote(config, client, &report_data(signer, b"")).await?,
+    };
+
+    let attestation = Attestation::from_quote(config, &quote, signer, b"")?;
+    info!(
+        "Loaded {} attestation ({} bytes, sha256 {})",
+        attestation.platform,
+        quote.len(),
+        attestation.quote_sha256
+    );
+    Ok(attestation)
+}
+
+/// A fresh quote for a caller's nonce (needs `TEE_ATTESTATION_URL`)
+pub async fn fresh(
+    config: &AttestationConfig,
+    client: &reqwest::Client,
+    signer: &str,
+    nonce: &str,
+) -> Result<Attestation, WatchyError> {
+    if nonce.is_empty() || nonce.len() > MAX_NONCE_LEN {
+        return Err(WatchyError::InvalidRequest(format!(
+            "nonce must be 1-{} characters",
+            MAX_NONCE_LEN
+        )));
+    }
+    if config.quote_url.is_none() {
+        return Err(WatchyError::InvalidRequest(
+            "Fresh quotes need TEE_ATTESTATION_URL".to_string(),
+        ));
+    }
+    let quote = fetch_quote(config, client, &report_data(signer, nonce.as_bytes())).await?;
+    Attestation::from_quote(config, &quote, signer, nonce.as_bytes())
+}
+
+/// Ask the attestation endpoint for a quote over `report_data`
+async fn fetch_quote(
+    config: &AttestationConfig,
+    client: &reqwest::Client,
+    report_data: &[u8; 32],
+) -> Result<Vec<u8>, WatchyError> {
+    let quote = match &config.quote_url {
+        Some(url) => {
+            let response = client
+                .get(url)
+                .query(&[("report_data", hex::encode(report_data))])
+                .send()
+                .await?;
+            if !response.status().is_success() {
+                return Err(WatchyError::Internal(format!(
+                    "Attestation endpoint returned {}",
+                    response.status()
+                )));
+            }
+            response.bytes().await?.to_vec()
+        }
+        None => {
+            return Err(WatchyError::Internal(
+                "No attestation endpoint configured".to_string(),
+            ))
+        }
+    };
+    if quote.is_empty() {
+        return Err(WatchyError::Internal("Attestation quote is empty".to_string()));
+    }
+    Ok(quote)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_load_from_file() {
+        let path = std::env::temp_dir().join(format!("watchy-quote-{}", std::process::id()));
+        let mut quote = b"header".to_vec();
+        quote.extend_from_slice(&report_data("0xABC", b""));
+        quote.extend_from_slice(&[0; 32]);
+        std::fs::write(&path, &quote).unwrap();
+        let config = AttestationConfig {
+            platform: "eigencloud".to_string(),
+            quote_url: None,
+            quote_file: Some(path.to_string_lossy().to_string()),
+            verify_url: Some("https://verify.example.com".to_string()),
+        };
+
+        let attestation = load(&config, &reqwest::Client::new(), Some("0xabc")).await;
+        let other_signer = load(&config, &reqwest::Client::new(), Some("0xdef")).await;
+        std::fs::remove_file(&path).ok();
+
+        let attestation = attestation.unwrap();
+        assert_eq!(attestation.quote_sha256, hex::encode(Sha256::digest(&quote)));
+        assert_eq!(attestation.signer, "0xabc");
+        assert_eq!(attestation.request_sha256, hex::encode(Sha256::digest(b"")));
+        assert_eq!(attestation.reference().verify_url, config.verify_url);
+        assert!(other_signer.is_err());
+    }
+
+    #[test]
+    fn test_report_data_binds_request() {
+        assert_eq!(report_data("0xAbC", b"n1"), report_data("0xabc", b"n1"));
+        assert_ne!(report_data("0xabc", b"n1"), report_data("0xabc", b"n2"));
+        assert_ne!(report_data("0xabc", b"n1"), report_data("0xdef", b"n1"));
+    }
+}
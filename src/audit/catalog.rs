@@ -0,0 +1,588 @@
+//! Issue catalog
+//!
+//! One entry per issue code with the check category it belongs to, its
+//! severity, a short description, how it affects the scores and how to fix
+//! it. Reports are annotated from here once an audit finishes, so the checks
+//! themselves only state what they found. The whole list is published at
+//! `GET /issues/catalog`.
+
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+use super::{consistency, content, credentials, engine, endpoints, security, trust};
+use crate::types::{AuditReport, Severity};
+
+const EIP_8004: &str = "https://eips.ethereum.org/EIPS/eip-8004";
+const A2A_SPEC: &str = "https://a2a-protocol.org/latest/specification/";
+const MCP_SPEC: &str = "https://modelcontextprotocol.io/specification";
+const X402: &str = "https://x402.org";
+const SECURITY_TXT: &str = "https://www.rfc-editor.org/rfc/rfc9116";
+const SECURE_HEADERS: &str = "https://owasp.org/www-project-secure-headers/";
+const LETS_ENCRYPT: &str = "https://letsencrypt.org/docs/";
+const CONTENT_ADDRESSING: &str = "https://docs.ipfs.tech/concepts/content-addressing/";
+const ENS_PRIMARY_NAME: &str = "https://docs.ens.domains/web/reverse";
+const HTTP_COMPRESSION: &str = "https://developer.mozilla.org/en-US/docs/Web/HTTP/Compression";
+const DID_CORE: &str = "https://www.w3.org/TR/did-core/";
+const VC_DATA_MODEL: &str = "https://www.w3.org/TR/vc-data-model-2.0/";
+
+/// Guidance for one issue code
+#[derive(Debug, Clone, Serialize)]
+pub struct CatalogEntry {
+    pub code: &'static str,
+    /// Check category (metadata, onchain, endpoints, security, consistency, content, audit)
+    pub category: &'static str,
+    pub severity: Severity,
+    pub description: &'static str,
+    /// Effect on the scores when raised ("None" for informational codes)
+    pub score_impact: ScoreImpact,
+    pub remediation: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub docs_url: Option<&'static str>,
+}
+
+/// How an issue moves the scores, rendered as text ("-40 metadata")
+///
+/// Deductions take their points from the constants the checks apply, so the
+/// catalog can't drift from the scoring.
+#[derive(Debug, Clone, Copy)]
+pub enum ScoreImpact {
+    /// Points off a category score, and a qualifier ("per credential")
+    Minus(u8, &'static str, &'static str),
+    /// At most this many points off
+    UpTo(u8, &'static str),
+    /// Between two deductions, depending on the finding
+    Between(u8, u8, &'static str),
+    /// Anything that isn't a fixed deduction
+    Text(&'static str),
+}
+
+impl fmt::Display for ScoreImpact {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Minus(points, category, "") => write!(f, "-{} {}", points, category),
+            Self::Minus(points, category, qualifier) => {
+                write!(f, "-{} {} {}", points, category, qualifier)
+            }
+            Self::UpTo(points, category) => write!(f, "Up to -{} {}", points, category),
+            Self::Between(min, max, category) => write!(f, "-{} to -{} {}", min, max, category),
+            Self::Text(text) => f.write_str(text),
+        }
+    }
+}
+
+impl Serialize for ScoreImpact {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+const NONE: ScoreImpact = ScoreImpact::Text("None");
+
+const RECOMMENDED_FIELD: ScoreImpact = minus_when(
+    engine::MISSING_RECOMMENDED_PENALTY,
+    "metadata",
+    "(once for all missing recommended fields)",
+);
+
+const fn minus(points: u8, category: &'static str) -> ScoreImpact {
+    ScoreImpact::Minus(points, category, "")
+}
+
+const fn minus_when(points: u8, category: &'static str, qualifier: &'static str) -> ScoreImpact {
+    ScoreImpact::Minus(points, category, qualifier)
+}
+
+const fn up_to(points: u8, category: &'static str) -> ScoreImpact {
+    ScoreImpact::UpTo(points, category)
+}
+
+const fn text(text: &'static str) -> ScoreImpact {
+    ScoreImpact::Text(text)
+}
+
+/// Performance points a missing transport capability costs at most
+const fn transport_share(points: u8) -> u8 {
+    (points as u16 * engine::TRANSPORT_SCORE_PERCENT as u16 / 100) as u8
+}
+
+/// Content points a description quality penalty costs at most
+const fn description_share(penalty: u8) -> u8 {
+    (penalty as u16 * content::DESCRIPTION_POINTS as u16 / 100) as u8
+}
+
+const fn entry(
+    code: &'static str,
+    category: &'static str,
+    severity: Severity,
+    description: &'static str,
+    score_impact: ScoreImpact,
+    remediation: &'static str,
+    docs_url: Option<&'static str>,
+) -> CatalogEntry {
+    CatalogEntry {
+        code,
+        category,
+        severity,
+        description,
+        score_impact,
+        remediation,
+        docs_url,
+    }
+}
+
+/// Every issue code the audit can raise
+pub const CATALOG: &[CatalogEntry] = &[
+    // ----- Metadata -----
+    entry("METADATA_FETCH_FAILED", "metadata", Severity::Critical, "Metadata unreachable", text("Metadata score 0; remaining phases skipped"),
+        "Make sure the tokenURI resolves and returns the registration JSON; pinned IPFS or Arweave content avoids gateway outages.",
+        Some(EIP_8004)),
+    entry("MISSING_REQUIRED_FIELDS", "metadata", Severity::Critical, "Required fields missing", minus(engine::MISSING_REQUIRED_FIELDS_PENALTY, "metadata"),
+        "Add the required registration fields (type, name, description, image) to the metadata.",
+        Some(EIP_8004)),
+    entry("INVALID_TYPE", "metadata", Severity::Critical, "Wrong type field", minus(engine::INVALID_TYPE_PENALTY, "metadata"),
+        "Set `type` to the EIP-8004 registration type URI.",
+        Some(EIP_8004)),
+    entry("REGISTRATION_MISMATCH", "metadata", Severity::Critical, "Registration not listed", minus(engine::REGISTRATION_MISMATCH_PENALTY, "metadata"),
+        "Add an entry for this agent id and registry (CAIP-10) to the metadata's `registrations`.",
+        Some(EIP_8004)),
+    entry("INVALID_REGISTRY_ID", "metadata", Severity::Warning, "Malformed registry ID", minus(engine::INVALID_REGISTRY_ID_PENALTY, "metadata"),
+        "Write each `agentRegistry` as `eip155:<chainId>:<address>`, with the address lowercase or EIP-55 checksummed.",
+        Some("https://github.com/ChainAgnostic/CAIPs/blob/main/CAIPs/caip-10.md")),
+    entry("MISSING_ACTIVE", "metadata", Severity::Warning, "No active flag", RECOMMENDED_FIELD,
+        "Add `active: true` (or false while the agent is offline).",
+        Some(EIP_8004)),
+    entry("MISSING_SERVICES", "metadata", Severity::Warning, "No services", RECOMMENDED_FIELD,
+        "List the agent's endpoints under `services` so clients can reach it.",
+        Some(EIP_8004)),
+    entry("MISSING_SUPPORTEDTRUST", "metadata", Severity::Warning, "No trust models", RECOMMENDED_FIELD,
+        "Declare the trust models the agent supports in `supportedTrust` (e.g. reputation).",
+        Some(EIP_8004)),
+    entry("MISSING_UPDATEDAT", "metadata", Severity::Warning, "No update time", RECOMMENDED_FIELD,
+        "Set `updatedAt` whenever the metadata changes.",
+        Some(EIP_8004)),
+    entry("INVALID_URLS", "metadata", Severity::Warning, "Malformed URLs", minus(engine::INVALID_URLS_PENALTY, "metadata"),
+        "Fix the URLs flagged in the metadata; each must be absolute and parseable.",
+        None),
+    entry("SECRET_LEAKED", "metadata", Severity::Critical, "Credential published", minus_when(engine::SECRET_LEAKED_PENALTY, "metadata", "when found in the metadata"),
+        "Revoke the credential now, then remove it from the metadata or endpoint response. Published data stays public.",
+        None),
+    entry("INSECURE_METADATA_URI", "metadata", Severity::Error, "Metadata over plain HTTP", minus(engine::STORAGE_ERROR_PENALTY, "metadata"),
+        "Serve the metadata over HTTPS, or better, pin it to IPFS or Arweave.",
+        Some(CONTENT_ADDRESSING)),
+    entry("MUTABLE_METADATA_HOST", "metadata", Severity::Warning, "Metadata on a mutable host", minus(engine::STORAGE_WARNING_PENALTY, "metadata"),
+        "Pin the metadata to IPFS or Arweave and point the tokenURI at the content address.",
+        Some(CONTENT_ADDRESSING)),
+    entry("DATA_URI_METADATA", "metadata", Severity::Info, "Inline data: URI metadata", NONE,
+        "Consider pinning the metadata to IPFS or Arweave so it can be verified and cached like any document.",
+        Some(CONTENT_ADDRESSING)),
+    entry("OVERSIZED_DATA_URI", "metadata", Severity::Warning, "Large inline metadata", minus(engine::STORAGE_WARNING_PENALTY, "metadata"),
+        "Move the metadata to IPFS or Arweave; large data: URIs make every update expensive.",
+        Some(CONTENT_ADDRESSING)),
+    entry("UNKNOWN_METADATA_STORAGE", "metadata", Severity::Warning, "Unknown URI scheme", minus(engine::STORAGE_WARNING_PENALTY, "metadata"),
+        "Use an ipfs://, ar://, https:// or data: tokenURI.",
+        Some(EIP_8004)),
+    entry("DID_UNRESOLVABLE", "metadata", Severity::Warning, "DID doesn't resolve", NONE,
+        "Publish the DID document (did.json for did:web) or remove the DID from the metadata.",
+        Some(DID_CORE)),
+    entry("DID_METHOD_UNSUPPORTED", "metadata", Severity::Info, "DID method not resolvable", NONE,
+        "Nothing required; only did:web and did:key are resolved. Add a did:web alias for verifiers that can't resolve this method.",
+        Some(DID_CORE)),
+    entry("CREDENTIAL_INVALID_SIGNATURE", "metadata", Severity::Error, "Credential signature invalid", minus_when(credentials::INVALID_SIGNATURE_PENALTY, "metadata", "per credential"),
+        "Re-issue the credential: it was altered after signing or isn't signed by a key of its issuer's DID.",
+        Some(VC_DATA_MODEL)),
+    entry("CREDENTIAL_UNVERIFIED", "metadata", Severity::Info, "Credential not verified", NONE,
+        "Nothing required; verifiable credentials signed as EdDSA/ES256K JWTs or eddsa-jcs-2022 proofs by a did:web or did:key issuer are checked.",
+        Some(VC_DATA_MODEL)),
+    entry("CREDENTIAL_EXPIRED", "metadata", Severity::Warning, "Credential expired", NONE,
+        "Ask the issuer for a new credential and replace the expired one.",
+        Some(VC_DATA_MODEL)),
+    // ----- On-chain -----
+    entry("AGENT_NOT_FOUND", "onchain", Severity::Critical, "Agent not registered", text("On-chain score 0"),
+        "Check the agent id and chain; the registry has no token with this id.",
+        Some(EIP_8004)),
+    entry("NO_WALLET", "onchain", Severity::Warning, "No agent wallet", minus(engine::NO_WALLET_PENALTY, "on-chain"),
+        "Set the agent wallet in the identity registry so payments and feedback can be attributed.",
+        Some(EIP_8004)),
+    entry("MUTABLE_METADATA_URI", "onchain", Severity::Info, "tokenURI not content-addressed", minus(engine::MUTABLE_URI_PENALTY, "on-chain"),
+        "Pin the metadata and call setAgentURI with its ipfs:// or ar:// URI.",
+        Some(CONTENT_ADDRESSING)),
+    entry("FREQUENT_URI_CHANGES", "onchain", Severity::Warning, "Frequent tokenURI changes", minus(engine::URI_CHURN_PENALTY, "on-chain"),
+        "Batch metadata changes; frequent rewrites make it hard for clients to trust what they fetched.",
+        None),
+    entry("AGENT_HISTORY_UNAVAILABLE", "onchain", Severity::Info, "History not fetched", NONE,
+        "Nothing to fix in the agent. Operators can set REGISTRY_START_BLOCK_<CHAIN> or use an RPC without log range limits.",
+        None),
+    entry("REGISTRATION_TOKEN_NOT_FOUND", "onchain", Severity::Warning, "Listed registration doesn't exist", minus_when(engine::REGISTRATION_PENALTY, "on-chain", "per registration"),
+        "Remove the registration from the metadata, or register the agent on that chain with the listed id.",
+        Some(EIP_8004)),
+    entry("REGISTRATION_URI_MISMATCH", "onchain", Severity::Warning, "Registrations point at different metadata", minus_when(engine::REGISTRATION_PENALTY, "on-chain", "per registration"),
+        "Call setAgentURI on each chain so every registration carries the same tokenURI.",
+        Some(EIP_8004)),
+    entry("REGISTRATION_OWNER_MISMATCH", "onchain", Severity::Info, "Registrations have different owners", NONE,
+        "No action required if intended; otherwise transfer the registrations to one owner.",
+        None),
+    entry("OWNER_RECENTLY_CREATED", "onchain", Severity::Info, "New owner address", NONE,
+        "No action required; established owners build trust over time.",
+        None),
+    entry("OWNER_LOW_ACTIVITY", "onchain", Severity::Info, "Barely used owner address", NONE,
+        "No action required; consider owning the agent from an established address or multisig.",
+        None),
+    entry("WALLET_IS_OWNER", "onchain", Severity::Info, "Wallet reuses the owner key", NONE,
+        "Set a dedicated agent wallet so the key that controls the registration isn't used for payments.",
+        Some(EIP_8004)),
+    entry("REPUTATION_NO_FEEDBACK", "onchain", Severity::Warning, "Reputation trust without feedback", minus(trust::REPUTATION_PENALTY, "on-chain"),
+        "Collect feedback in the reputation registry, or drop reputation from supportedTrust until you have some.",
+        Some(EIP_8004)),
+    entry("CRYPTO_ECONOMIC_NO_WALLET", "onchain", Severity::Warning, "Crypto-economic trust without a wallet", minus(trust::CRYPTO_ECONOMIC_PENALTY, "on-chain"),
+        "Set an agent wallet in the registry to hold the agent's stake.",
+        Some(EIP_8004)),
+    entry("CRYPTO_ECONOMIC_UNFUNDED", "onchain", Severity::Info, "Agent wallet is empty", NONE,
+        "Fund the agent wallet so the stake backing crypto-economic trust is visible.",
+        None),
+    // ----- Endpoints -----
+    entry("ENDPOINT_UNREACHABLE", "endpoints", Severity::Critical, "Endpoint unreachable", text("Lowers endpoint availability by the endpoint's service weight"),
+        "Make sure the endpoint is deployed, publicly resolvable and answering HTTPS requests.",
+        None),
+    entry("ENDPOINT_TRANSIENT_FAILURE", "endpoints", Severity::Info, "Endpoint failed once", text("None; the endpoint counts as reachable"),
+        "Check for intermittent DNS, load balancer or cold-start failures if this recurs.",
+        None),
+    entry("ENDPOINT_NOT_PROBED", "endpoints", Severity::Info, "Endpoint not probed", text("Lowers endpoint availability by the endpoint's service weight"),
+        "Nothing to fix unless unintended: the endpoint was skipped by the auditor's policy or the host's watchy.json.",
+        None),
+    entry("ENDPOINT_RATE_LIMITED", "endpoints", Severity::Info, "Probes rate limited", text("None; throttled probes don't count as latency samples, so an endpoint that throttles every probe earns no performance points"),
+        "Allow a few requests per minute from the auditor, or publish a watchy.json host policy asking for slower sampling.",
+        None),
+    entry("WS_NO_PONG", "endpoints", Severity::Warning, "WebSocket pings unanswered", text("Latency falls back to the handshake time"),
+        "Answer WebSocket ping frames with pongs so clients can keep streaming connections alive.",
+        None),
+    entry("WS_NOT_ENCRYPTED", "endpoints", Severity::Warning, "Unencrypted WebSocket", NONE,
+        "Serve the streaming endpoint over `wss://`.",
+        None),
+    entry("GRPC_NOT_SERVING", "endpoints", Severity::Error, "gRPC server not serving", NONE,
+        "Fix the failing dependency or report SERVING from grpc.health.v1 once the server can take requests.",
+        None),
+    entry("GRPC_HEALTH_UNIMPLEMENTED", "endpoints", Severity::Info, "No gRPC health service", NONE,
+        "Register the standard grpc.health.v1 Health service so clients and load balancers can check the server.",
+        None),
+    entry("GRPC_HEALTH_FAILED", "endpoints", Severity::Warning, "gRPC health check failed", text("Endpoint schema check fails unless the server answered with a gRPC status"),
+        "Serve gRPC over HTTP/2 at the declared endpoint and make grpc.health.v1/Check answer.",
+        None),
+    entry("GRPC_REFLECTION_UNAVAILABLE", "endpoints", Severity::Info, "No gRPC reflection", NONE,
+        "Enable server reflection so clients (and the auditor) can discover the served services.",
+        None),
+    entry("GRPC_SERVICES_MISMATCH", "endpoints", Severity::Warning, "Declared gRPC services not served", NONE,
+        "Keep `grpcServices` in the metadata in sync with the services the server registers.",
+        None),
+    entry("IPV6_UNREACHABLE", "endpoints", Severity::Warning, "Unreachable over IPv6", NONE,
+        "Serve the endpoint on the addresses in its AAAA records, or remove the records; IPv6-preferring clients try them first.",
+        None),
+    entry("IPV4_UNREACHABLE", "endpoints", Severity::Warning, "Unreachable over IPv4", NONE,
+        "Serve the endpoint on the addresses in its A records, or remove the records; IPv4-only clients can't reach it.",
+        None),
+    entry("HIGH_LATENCY", "endpoints", Severity::Warning, "Slow responses", text("Lowers endpoint performance"),
+        "Reduce response time (caching, closer region, lighter cold starts).",
+        None),
+    entry("INVALID_JSON", "endpoints", Severity::Error, "Invalid JSON", text("Endpoint schema check fails"),
+        "Return valid JSON from the endpoint.",
+        None),
+    entry("UNEXPECTED_CONTENT_TYPE", "endpoints", Severity::Error, "Unexpected Content-Type", text("Endpoint schema check fails"),
+        "Serve JSON documents with `Content-Type: application/json`.",
+        None),
+    entry("RESPONSE_TOO_LARGE", "endpoints", Severity::Error, "Response too large", text("Endpoint schema check fails"),
+        "Trim the response; agent cards and manifests should be small documents.",
+        None),
+    entry("A2A_FETCH_FAILED", "endpoints", Severity::Error, "Agent card unavailable", text("Endpoint schema check fails"),
+        "Serve the agent card at /.well-known/agent-card.json (or the declared URL).",
+        Some(A2A_SPEC)),
+    entry("A2A_MISSING_NAME", "endpoints", Severity::Error, "Agent card has no name", text("Endpoint schema check fails"),
+        "Add `name` to the agent card.",
+        Some(A2A_SPEC)),
+    entry("A2A_SKILLS_MISMATCH", "endpoints", Severity::Warning, "Skills differ from the agent card", text("Endpoint skills check fails"),
+        "Make the metadata's declared skills match the agent card's skill ids or names.",
+        Some(A2A_SPEC)),
+    entry("MCP_TOOLS_MISMATCH", "endpoints", Severity::Warning, "Tools differ from the MCP manifest", text("Endpoint skills check fails"),
+        "Make the metadata's `mcpTools` match the tools the server lists.",
+        Some(MCP_SPEC)),
+    entry("MCP_TOOL_DRIFT", "endpoints", Severity::Info, "MCP tool schema changed", NONE,
+        "Confirm the input schema change was intended and bump the server version.",
+        Some(MCP_SPEC)),
+    entry("HTTP2_NOT_SUPPORTED", "endpoints", Severity::Info, "No HTTP/2", up_to(transport_share(endpoints::HTTP2_POINTS), "endpoint performance"),
+        "Enable HTTP/2 (ALPN h2) on the server or proxy.",
+        None),
+    entry("COMPRESSION_NOT_SUPPORTED", "endpoints", Severity::Info, "No compression", up_to(transport_share(endpoints::COMPRESSION_POINTS), "endpoint performance"),
+        "Enable gzip or Brotli for JSON responses.",
+        Some(HTTP_COMPRESSION)),
+    entry("KEEP_ALIVE_DISABLED", "endpoints", Severity::Info, "Connections not reused", up_to(transport_share(endpoints::KEEP_ALIVE_POINTS), "endpoint performance"),
+        "Allow persistent connections instead of sending `Connection: close`.",
+        None),
+    // ----- Security -----
+    entry("NO_HTTPS", "security", Severity::Critical, "No HTTPS", text("Security score 0"),
+        "Serve the endpoint over HTTPS with a publicly trusted certificate.",
+        Some(LETS_ENCRYPT)),
+    entry("NO_HTTPS_ENDPOINTS", "security", Severity::Critical, "No HTTPS endpoints", text("Security score 0"),
+        "Expose at least one endpoint over HTTPS.",
+        Some(LETS_ENCRYPT)),
+    entry("SECURITY_NOT_PROBED", "security", Severity::Info, "Security not evaluated", text("Security score 0, phase skipped"),
+        "Nothing to fix unless unintended: no HTTPS endpoint could be probed.",
+        None),
+    entry("HTTP_NOT_REDIRECTED", "security", Severity::Info, "HTTP not redirected", minus(security::HTTP_NOT_REDIRECTED_PENALTY, "security"),
+        "Redirect plain HTTP requests to HTTPS (301 or 308).",
+        Some(SECURE_HEADERS)),
+    entry("MISSING_SECURITY_HEADERS", "security", Severity::Warning, "Security headers missing", up_to(security::HEADERS_MAX_PENALTY, "security"),
+        "Add Strict-Transport-Security, X-Content-Type-Options and a Content-Security-Policy.",
+        Some(SECURE_HEADERS)),
+    entry("TLS_CHECK_FAILED", "security", Severity::Critical, "TLS handshake failed", text("Security score 0"),
+        "Check the server's TLS configuration; the handshake didn't complete.",
+        None),
+    entry("CERT_EXPIRED", "security", Severity::Critical, "Certificate expired", minus(security::CERT_EXPIRED_PENALTY, "security"),
+        "Renew the certificate and automate renewal.",
+        Some(LETS_ENCRYPT)),
+    entry("CERT_EXPIRING_SOON", "security", Severity::Warning, "Certificate expiring soon", ScoreImpact::Between(security::CERT_EXPIRING_PENALTY, security::CERT_EXPIRING_WEEK_PENALTY, "security"),
+        "Renew the certificate before it expires; ACME clients do this automatically.",
+        Some(LETS_ENCRYPT)),
+    entry("CERT_UNTRUSTED", "security", Severity::Critical, "Untrusted certificate", minus(security::CERT_INVALID_PENALTY, "security"),
+        "Use a certificate from a publicly trusted CA and serve the full chain.",
+        Some(LETS_ENCRYPT)),
+    entry("CERT_HOSTNAME_MISMATCH", "security", Severity::Critical, "Certificate hostname mismatch", minus(security::CERT_INVALID_PENALTY, "security"),
+        "Issue the certificate for the endpoint's hostname.",
+        Some(LETS_ENCRYPT)),
+    entry("WEAK_CERT_KEY", "security", Severity::Warning, "Weak certificate key", NONE,
+        "Reissue the certificate with an RSA key of at least 2048 bits or an ECDSA key.",
+        None),
+    entry("SECURITY_TXT_MISSING", "security", Severity::Info, "No security.txt", minus(security::SECURITY_TXT_PENALTY, "security"),
+        "Publish /.well-known/security.txt with a Contact and an Expires field.",
+        Some(SECURITY_TXT)),
+    entry("SECURITY_TXT_EXPIRED", "security", Severity::Warning, "security.txt expired", minus(security::SECURITY_TXT_PENALTY, "security"),
+        "Update the Expires field of security.txt.",
+        Some(SECURITY_TXT)),
+    entry("SECURITY_TXT_NO_EXPIRES", "security", Severity::Info, "security.txt has no Expires", NONE,
+        "Add an Expires field to security.txt (it's required by RFC 9116).",
+        Some(SECURITY_TXT)),
+    entry("URL_FLAGGED", "security", Severity::Critical, "URL flagged as malicious", text("Security score 0"),
+        "Remove the flagged URL or clean the host, then request a review from the reputation provider.",
+        Some("https://transparencyreport.google.com/safe-browsing/search")),
+    entry("URL_REPUTATION_UNAVAILABLE", "security", Severity::Info, "Reputation lookup failed", NONE,
+        "Nothing to fix in the agent; the reputation provider couldn't be reached.",
+        None),
+    entry("TEE_ATTESTATION_MISSING", "security", Severity::Warning, "TEE trust without attestation", minus(trust::TEE_PENALTY, "security"),
+        "Declare the attestation endpoint as a `tee` service (or `teeAttestation`), or drop tee from supportedTrust.",
+        None),
+    entry("TEE_ATTESTATION_UNREACHABLE", "security", Severity::Warning, "TEE attestation unreachable", minus(trust::TEE_PENALTY, "security"),
+        "Serve the attestation quote at the declared endpoint with a 200 response.",
+        None),
+    // ----- Consistency -----
+    entry("NAME_MISMATCH_A2A", "consistency", Severity::Warning, "Name differs from the agent card", minus(consistency::NAME_PENALTY, "consistency"),
+        "Use the same name in the metadata and the A2A agent card.",
+        Some(A2A_SPEC)),
+    entry("NAME_MISMATCH_MCP", "consistency", Severity::Warning, "Name differs from the MCP server", minus(consistency::NAME_PENALTY, "consistency"),
+        "Use the same name in the metadata and the MCP server info.",
+        Some(MCP_SPEC)),
+    entry("A2A_SKILL_NOT_FOUND", "consistency", Severity::Warning, "Declared skill missing", minus(consistency::SKILLS_PENALTY, "consistency"),
+        "Add the skill to the agent card or remove it from the metadata.",
+        Some(A2A_SPEC)),
+    entry("A2A_VERSION_MISMATCH", "consistency", Severity::Info, "A2A version differs", minus(consistency::VERSION_PENALTY, "consistency"),
+        "Declare the protocol version the agent card reports.",
+        Some(A2A_SPEC)),
+    entry("MCP_TOOL_NOT_FOUND", "consistency", Severity::Warning, "Declared tool missing", minus(consistency::SKILLS_PENALTY, "consistency"),
+        "Expose the tool on the MCP server or remove it from `mcpTools`.",
+        Some(MCP_SPEC)),
+    entry("MCP_VERSION_MISMATCH", "consistency", Severity::Info, "MCP version differs", minus(consistency::VERSION_PENALTY, "consistency"),
+        "Declare the protocol version the MCP server reports.",
+        Some(MCP_SPEC)),
+    entry("IMAGE_INACCESSIBLE", "consistency", Severity::Warning, "Image unavailable", minus(consistency::IMAGE_PENALTY, "consistency"),
+        "Host the image at a public URL (ideally IPFS) that returns an image Content-Type.",
+        None),
+    entry("IMAGE_TOO_LARGE", "consistency", Severity::Warning, "Image too large", minus(consistency::IMAGE_PENALTY, "consistency"),
+        "Use a smaller image; a few hundred KB is plenty for an avatar.",
+        None),
+    entry("IMAGE_URL_BLOCKED", "consistency", Severity::Warning, "Image URL not fetched", minus(consistency::IMAGE_PENALTY, "consistency"),
+        "Use a public image URL; private and local addresses aren't fetched.",
+        None),
+    entry("AUTHOR_IDENTITY_MISMATCH", "consistency", Severity::Warning, "Author ENS name doesn't match", minus(consistency::AUTHOR_PENALTY, "consistency"),
+        "Set the claimed name as the owner's or wallet's primary ENS name, or fix the author field.",
+        Some(ENS_PRIMARY_NAME)),
+    entry("AUTHOR_IDENTITY_UNVERIFIED", "consistency", Severity::Info, "Author ENS name unverified", NONE,
+        "Set the claimed ENS name as the primary name of the owner or agent wallet.",
+        Some(ENS_PRIMARY_NAME)),
+    // ----- Content -----
+    entry("DESCRIPTION_TOO_SHORT", "content", Severity::Warning, "Short description", up_to(description_share(content::DESCRIPTION_TOO_SHORT_PENALTY), "content"),
+        "Describe what the agent does, its inputs and outputs, in a few sentences.",
+        None),
+    entry("DESCRIPTION_PLACEHOLDER", "content", Severity::Warning, "Placeholder description", up_to(description_share(content::DESCRIPTION_PLACEHOLDER_PENALTY), "content"),
+        "Replace the placeholder text with a real description.",
+        None),
+    entry("DESCRIPTION_LOW_QUALITY", "content", Severity::Info, "Low-quality description", up_to(description_share(content::DESCRIPTION_LOW_QUALITY_PENALTY), "content"),
+        "Expand the description with concrete capabilities and use cases.",
+        None),
+    entry("NO_CONTACT_INFO", "content", Severity::Info, "No contact information", minus(content::CONTACT_POINTS, "content"),
+        "Add an author URL, email or support link.",
+        None),
+    entry("UNKNOWN_SKILL_DOMAIN", "content", Severity::Info, "Skill outside the OASF taxonomy", up_to(content::SKILL_TAXONOMY_POINTS, "content"),
+        "Use an OASF taxonomy path for the skill (e.g. natural_language_processing/summarization).",
+        None),
+    entry("X402_INVALID", "content", Severity::Warning, "x402 misconfigured", minus(content::X402_POINTS, "content"),
+        "Return a valid x402 payment requirement (402 with accepts) from the paid endpoint.",
+        Some(X402)),
+    entry("X402_PAYTO_MISMATCH", "content", Severity::Critical, "x402 pays an unrelated wallet", minus_when(content::X402_POINTS, "content", "(x402 points withheld), fails content"),
+        "Set payTo to the agent wallet registered on-chain (setAgentWallet), or register the wallet that receives payments.",
+        Some(X402)),
+    entry("X402_PAYTO_UNVERIFIED", "content", Severity::Warning, "x402 payTo unverified", NONE,
+        "Register the wallet that receives x402 payments as the agent wallet so payments can be attributed.",
+        Some(X402)),
+    entry("X402_ASSET_NOT_FOUND", "content", Severity::Error, "x402 asset doesn't exist", minus_when(content::X402_POINTS, "content", "(x402 points withheld), fails content"),
+        "Set asset to the token contract address on the stated network (e.g. USDC on Base).",
+        Some(X402)),
+    entry("X402_UNKNOWN_ASSET", "content", Severity::Warning, "x402 asset isn't a known stablecoin", NONE,
+        "Accept payment in a widely used stablecoin such as USDC; payers can't easily value other tokens.",
+        Some(X402)),
+    entry("X402_UNSUPPORTED_NETWORK", "content", Severity::Info, "x402 network not verifiable", NONE,
+        "Use a network name or CAIP-2 ID (eip155:<chainId>) for a chain Watchy supports to have the asset verified.",
+        Some(X402)),
+    entry("X402_PRICE_ZERO", "content", Severity::Warning, "x402 price is zero", NONE,
+        "Set maxAmountRequired to the real price, or serve the endpoint without x402.",
+        Some(X402)),
+    entry("X402_PRICE_INVALID", "content", Severity::Warning, "x402 price isn't a valid amount", NONE,
+        "Give maxAmountRequired as an integer string in the asset's base units (e.g. \"10000\" for 0.01 USDC).",
+        Some(X402)),
+    entry("X402_PRICE_EXCESSIVE", "content", Severity::Warning, "x402 price implausibly high", NONE,
+        "Check maxAmountRequired is in base units: 1 USDC is \"1000000\" (6 decimals).",
+        Some(X402)),
+    entry("DOCS_UNREACHABLE", "content", Severity::Warning, "Documentation link is down", minus(content::DOCS_PENALTY, "content"),
+        "Fix or remove the documentation URL; it should return 200 with the agent's docs.",
+        None),
+    entry("DOCS_EMPTY", "content", Severity::Warning, "Documentation page is empty or parked", minus(content::DOCS_PENALTY, "content"),
+        "Publish real documentation at the documentation URL (usage, endpoints, pricing).",
+        None),
+    entry("LLM_CLAIM_INCONSISTENT", "content", Severity::Info, "Claims look contradictory", NONE,
+        "Make the description and declared skills describe the same agent; drop unrelated skills.",
+        None),
+    entry("SOURCE_CODE_NOT_FOUND", "content", Severity::Warning, "sourceCode repository missing", minus_when(content::SOURCE_MISSING_PENALTY, "content", "and fails content"),
+        "Point sourceCode at a public repository, or remove it from the metadata.",
+        None),
+    entry("SOURCE_CODE_PRIVATE", "content", Severity::Warning, "sourceCode repository is private", minus_when(content::SOURCE_MISSING_PENALTY, "content", "and fails content"),
+        "Make the repository public so the code can be reviewed, or remove sourceCode.",
+        None),
+    entry("SOURCE_CODE_NAME_MISMATCH", "content", Severity::Info, "sourceCode doesn't mention the agent", NONE,
+        "Mention the agent's name in the repository description or README.",
+        None),
+    entry("SOURCE_COMMIT_NOT_FOUND", "content", Severity::Warning, "Stated commit not in the repository", minus_when(content::SOURCE_MISMATCH_PENALTY, "content", "and fails content"),
+        "Set sourceCommit to a commit that exists in the sourceCode repository.",
+        None),
+    entry("SOURCE_RELEASE_NOT_FOUND", "content", Severity::Warning, "Stated release not in the repository", minus_when(content::SOURCE_MISMATCH_PENALTY, "content", "and fails content"),
+        "Set sourceRelease to a published release tag of the sourceCode repository.",
+        None),
+    // ----- Audit -----
+    entry("AUDIT_DEADLINE_EXCEEDED", "audit", Severity::Warning, "Checks cut short", text("Unfinished checks score as failed"),
+        "Slow endpoints or RPCs used up the audit deadline; speeding up the agent's endpoints helps.",
+        None),
+];
+
+/// Catalog entry for an issue code
+pub fn lookup(code: &str) -> Option<&'static CatalogEntry> {
+    CATALOG.iter().find(|entry| entry.code == code)
+}
+
+/// Fill in remediation and docs links for every issue in a report
+pub fn annotate(report: &mut AuditReport) {
+    let checks = &mut report.checks;
+    let issues = checks
+        .metadata
+        .issues
+        .iter_mut()
+        .chain(checks.onchain.issues.iter_mut())
+        .chain(checks.endpoints.iter_mut().flat_map(|e| e.issues.iter_mut()))
+        .chain(checks.security.issues.iter_mut())
+        .chain(checks.consistency.issues.iter_mut())
+        .chain(checks.content.issues.iter_mut());
+
+    for issue in issues {
+        if let Some(entry) = lookup(&issue.code) {
+            issue.remediation.get_or_insert_with(|| entry.remediation.to_string());
+            if issue.docs_url.is_none() {
+                issue.docs_url = entry.docs_url.map(|url| url.to_string());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    /// Audit modules that raise issues
+    const SOURCES: [&str; 18] = [
+        include_str!("claims.rs"),
+        include_str!("consistency.rs"),
+        include_str!("content.rs"),
+        include_str!("credentials.rs"),
+        include_str!("documentation.rs"),
+        include_str!("dual_stack.rs"),
+        include_str!("endpoints.rs"),
+        include_str!("engine.rs"),
+        include_str!("grpc.rs"),
+        include_str!("metadata.rs"),
+        include_str!("onchain.rs"),
+        include_str!("owner_risk.rs"),
+        include_str!("secrets.rs"),
+        include_str!("security.rs"),
+        include_str!("source_code.rs"),
+        include_str!("trust.rs"),
+        include_str!("websocket.rs"),
+        include_str!("x402.rs"),
+    ];
+
+    #[test]
+    fn test_score_impact_text() {
+        let impact = |code| lookup(code).unwrap().score_impact.to_string();
+        assert_eq!(impact("MISSING_REQUIRED_FIELDS"), "-40 metadata");
+        assert_eq!(impact("CREDENTIAL_INVALID_SIGNATURE"), "-15 metadata per credential");
+        assert_eq!(impact("HTTP2_NOT_SUPPORTED"), "Up to -4 endpoint performance");
+        assert_eq!(impact("DESCRIPTION_TOO_SHORT"), "Up to -16 content");
+        assert_eq!(impact("CERT_EXPIRING_SOON"), "-10 to -20 security");
+        assert_eq!(impact("WEAK_CERT_KEY"), "None");
+
+        let json = serde_json::to_value(lookup("NO_WALLET").unwrap()).unwrap();
+        assert_eq!(json["score_impact"], "-20 on-chain");
+    }
+
+    #[test]
+    fn test_catalog_codes_are_unique() {
+        let mut seen = HashSet::new();
+        for entry in CATALOG {
+            assert!(seen.insert(entry.code), "duplicate catalog code {}", entry.code);
+        }
+    }
+
+    /// Severity and literal code of each `Issue::new` call in a source file
+    fn raised_issues(source: &str) -> Vec<(&str, &str)> {
+        source
+            .split("Issue::new(")
+            .skip(1)
+            .filter_map(|chunk| {
+                let (severity, rest) = chunk.trim_start().strip_prefix("Severity::")?.split_once(',')?;
+                let rest = rest.trim_start().strip_prefix('"')?;
+                Some((severity, &rest[..rest.find('"')?]))
+            })
+            .collect()
+    }
+
+    /// Every code raised in the source has an entry
+    #[test]
+    fn test_catalog_covers_issue_codes() {
+        let raised: Vec<_> = SOURCES.iter().flat_map(|source| raised_issues(source)).collect();
+        assert!(raised.len() > 100, "issue constructors not found in the sources");
+        for (_, code) in raised {
+            assert!(lookup(code).is_some(), "issue code {} missing from the catalog", code);
+        }
+    }
+
+    /// Codes raised with a literal severity match the catalog
+    #[test]
+    fn test_catalog_severities_match_sources() {
+        for (severity, code) in SOURCES.iter().flat_map(|source| raised_issues(source)) {
+            let entry = lookup(code).unwrap();
+            assert_eq!(format!("{:?}", entry.severity), severity, "severity of {}", code);
+        }
+    }
+}
@@ -1,10 +1,14 @@
 use tracing::{debug, warn};
 
-use crate::types::{AgentMetadata, ConsistencyChecks, Issue, Severity};
+use super::skills::{self, A2aSkill};
+use crate::ssrf::OutboundGuard;
+use crate::types::{AgentInfo, AgentMetadata, ConsistencyChecks, Issue, Severity};
 
 /// Run consistency checks across metadata and endpoint responses
 pub async fn check_consistency(
     client: &reqwest::Client,
+    guard: &OutboundGuard,
+    max_image_bytes: u64,
     metadata: &AgentMetadata,
     endpoint_responses: &EndpointResponses,
 ) -> ConsistencyChecks {
@@ -16,6 +20,7 @@ pub async fn check_consistency(
         skills_consistent: true,
         version_consistent: true,
         image_accessible: false,
+        author_identity_consistent: None,
         issues: vec![],
     };
 
@@ -25,28 +30,28 @@ pub async fn check_consistency(
     if let Some(a2a_name) = &endpoint_responses.a2a_name {
         if !names_match(metadata_name, a2a_name) {
             checks.name_consistent = false;
-            checks.issues.push(Issue {
-                severity: Severity::Warning,
-                code: "NAME_MISMATCH_A2A".to_string(),
-                message: format!(
+            checks.issues.push(Issue::new(
+                Severity::Warning,
+                "NAME_MISMATCH_A2A",
+                format!(
                     "Metadata name '{}' doesn't match A2A agent card name '{}'",
                     metadata_name, a2a_name
                 ),
-            });
+            ));
         }
     }
 
     if let Some(mcp_name) = &endpoint_responses.mcp_name {
         if !names_match(metadata_name, mcp_name) {
             checks.name_consistent = false;
-            checks.issues.push(Issue {
-                severity: Severity::Warning,
-                code: "NAME_MISMATCH_MCP".to_string(),
-                message: format!(
+            checks.issues.push(Issue::new(
+                Severity::Warning,
+                "NAME_MISMATCH_MCP",
+                format!(
                     "Metadata name '{}' doesn't match MCP manifest name '{}'",
                     metadata_name, mcp_name
                 ),
-            });
+            ));
         }
     }
 
@@ -58,13 +63,23 @@ pub async fn check_consistency(
 
     // Check if image is accessible
     if let Some(image_url) = &metadata.image {
-        checks.image_accessible = check_image_accessible(client, image_url).await;
+        if let Err(reason) = guard.check(image_url).await {
+            checks.issues.push(Issue::new(
+                Severity::Warning,
+                "IMAGE_URL_BLOCKED",
+                format!("Agent image URL was not fetched: {}", reason),
+            ));
+        } else {
+            checks.image_accessible =
+                check_image_accessible(client, image_url, max_image_bytes, &mut checks.issues)
+                    .await;
+        }
         if !checks.image_accessible {
-            checks.issues.push(Issue {
-                severity: Severity::Warning,
-                code: "IMAGE_INACCESSIBLE".to_string(),
-                message: format!("Agent image URL is not accessible: {}", image_url),
-            });
+            checks.issues.push(Issue::new(
+                Severity::Warning,
+                "IMAGE_INACCESSIBLE",
+                format!("Agent image URL is not accessible: {}", image_url),
+            ));
         }
     }
 
@@ -74,11 +89,76 @@ pub async fn check_consistency(
     checks
 }
 
+/// Compare an ENS name claimed by the metadata author against the owner's and
+/// wallet's primary names
+///
+/// Only meaningful when the chain has ENS; claims are read from `author.name`
+/// ("alice.eth") and `author.url` ("https://alice.eth.limo").
+pub fn check_author_identity(
+    checks: &mut ConsistencyChecks,
+    metadata: &AgentMetadata,
+    agent: &AgentInfo,
+) {
+    let Some(claimed) = metadata.author.as_ref().and_then(claimed_ens_name) else {
+        return;
+    };
+    let resolved: Vec<&String> = [&agent.owner_ens, &agent.wallet_ens]
+        .into_iter()
+        .flatten()
+        .collect();
+
+    if resolved.iter().any(|name| name.eq_ignore_ascii_case(&claimed)) {
+        checks.author_identity_consistent = Some(true);
+    } else if resolved.is_empty() {
+        checks.issues.push(Issue::new(
+            Severity::Info,
+            "AUTHOR_IDENTITY_UNVERIFIED",
+            format!(
+                "Metadata author claims '{}', but neither the owner nor the agent wallet has a primary ENS name",
+                claimed
+            ),
+        ));
+    } else {
+        checks.author_identity_consistent = Some(false);
+        checks.passed = false;
+        checks.issues.push(Issue::new(
+            Severity::Warning,
+            "AUTHOR_IDENTITY_MISMATCH",
+            format!(
+                "Metadata author claims '{}', but the owner / agent wallet resolve to '{}'",
+                claimed,
+                resolved
+                    .iter()
+                    .map(|name| name.as_str())
+                    .collect::<Vec<_>>()
+                    .join("', '")
+            ),
+        ));
+    }
+}
+
+/// ENS name in the author's name, or in an eth.limo / eth.link URL
+fn claimed_ens_name(author: &crate::types::Author) -> Option<String> {
+    if let Some(name) = &author.name {
+        let name = name.trim().trim_start_matches('@').to_lowercase();
+        if name.ends_with(".eth") && !name.contains(char::is_whitespace) {
+            return Some(name);
+        }
+    }
+
+    let url = url::Url::parse(author.url.as_deref()?).ok()?;
+    let host = url.host_str()?.to_lowercase();
+    host.strip_suffix(".limo")
+        .or_else(|| host.strip_suffix(".link"))
+        .filter(|name| name.ends_with(".eth"))
+        .map(|name| name.to_string())
+}
+
 /// Endpoint responses collected during endpoint testing
 #[derive(Debug, Default)]
 pub struct EndpointResponses {
     pub a2a_name: Option<String>,
-    pub a2a_skills: Vec<String>,
+    pub a2a_skills: Vec<A2aSkill>,
     pub a2a_version: Option<String>,
     pub mcp_name: Option<String>,
     pub mcp_tools: Vec<String>,
@@ -99,18 +179,7 @@ impl EndpointResponses {
             responses.a2a_name = a2a.get("name").and_then(|v| v.as_str()).map(|s| s.to_string());
             responses.a2a_version = a2a.get("version").and_then(|v| v.as_str()).map(|s| s.to_string());
 
-            if let Some(skills) = a2a.get("skills").and_then(|v| v.as_array()) {
-                responses.a2a_skills = skills
-                    .iter()
-                    .filter_map(|s| {
-                        // Skills can be objects with "id" or strings
-                        s.get("id")
-                            .and_then(|v| v.as_str())
-                            .or_else(|| s.as_str())
-                            .map(|s| s.to_string())
-                    })
-                    .collect();
-            }
+            responses.a2a_skills = skills::parse_card_skills(a2a).unwrap_or_default();
         }
 
         if let Some(mcp) = mcp_response {
@@ -166,41 +235,38 @@ fn check_skills_consistency(
     // Get declared skills from metadata services
     for service in &metadata.services {
         match service.name.to_lowercase().as_str() {
-            "a2a" => {
-                if !service.a2a_skills.is_empty() && !responses.a2a_skills.is_empty() {
-                    // Check if declared skills are present in actual response
-                    for declared in &service.a2a_skills {
-                        let found = responses.a2a_skills.iter().any(|actual| {
-                            skills_match(declared, actual)
-                        });
-                        if !found {
-                            consistent = false;
-                            issues.push(Issue {
-                                severity: Severity::Warning,
-                                code: "A2A_SKILL_NOT_FOUND".to_string(),
-                                message: format!(
-                                    "Declared A2A skill '{}' not found in agent card",
-                                    declared
-                                ),
-                            });
-                        }
+            "a2a" if !service.a2a_skills.is_empty() && !responses.a2a_skills.is_empty() => {
+                // Check if declared skills are present in actual response
+                for declared in &service.a2a_skills {
+                    let found = responses
+                        .a2a_skills
+                        .iter()
+                        .any(|actual| skills::skill_matches(declared, actual));
+                    if !found {
+                        consistent = false;
+                        issues.push(Issue::new(
+                            Severity::Warning,
+                            "A2A_SKILL_NOT_FOUND",
+                            format!(
+                                "Declared A2A skill '{}' not found in agent card",
+                                declared
+                            ),
+                        ));
                     }
                 }
             }
-            "mcp" => {
-                if !service.mcp_tools.is_empty() && !responses.mcp_tools.is_empty() {
-                    for declared in &service.mcp_tools {
-                        if !responses.mcp_tools.contains(declared) {
-                            consistent = false;
-                            issues.push(Issue {
-                                severity: Severity::Warning,
-                                code: "MCP_TOOL_NOT_FOUND".to_string(),
-                                message: format!(
-                                    "Declared MCP tool '{}' not found in manifest",
-                                    declared
-                                ),
-                            });
-                        }
+            "mcp" if !service.mcp_tools.is_empty() && !responses.mcp_tools.is_empty() => {
+                for declared in &service.mcp_tools {
+                    if !responses.mcp_tools.contains(declared) {
+                        consistent = false;
+                        issues.push(Issue::new(
+                            Severity::Warning,
+                            "MCP_TOOL_NOT_FOUND",
+                            format!(
+                                "Declared MCP tool '{}' not found in manifest",
+                                declared
+                            ),
+                        ));
                     }
                 }
             }
@@ -211,43 +277,6 @@ fn check_skills_consistency(
     consistent
 }
 
-/// Strict skill matching for consistency checks
-/// Matches if:
-/// - Exact match (case-insensitive)
-/// - OASF taxonomy path match: last segments match
-fn skills_match(declared: &str, actual: &str) -> bool {
-    let declared_lower = declared.to_lowercase();
-    let actual_lower = actual.to_lowercase();
-
-    // Exact match
-    if declared_lower == actual_lower {
-        return true;
-    }
-
-    // OASF taxonomy path matching
-    let declared_segments: Vec<&str> = declared_lower.split('/').collect();
-    let actual_segments: Vec<&str> = actual_lower.split('/').collect();
-
-    // Check if last segments match
-    if let (Some(declared_last), Some(actual_last)) = (declared_segments.last(), actual_segments.last()) {
-        if declared_last == actual_last {
-            return true;
-        }
-    }
-
-    // Check if declared is a suffix of actual or vice versa (for paths)
-    if declared_segments.len() > 1 && actual_segments.len() > 1 {
-        let min_len = declared_segments.len().min(actual_segments.len());
-        let declared_suffix: Vec<&str> = declared_segments.iter().rev().take(min_len).cloned().collect();
-        let actual_suffix: Vec<&str> = actual_segments.iter().rev().take(min_len).cloned().collect();
-        if declared_suffix == actual_suffix {
-            return true;
-        }
-    }
-
-    false
-}
-
 fn check_version_consistency(
     metadata: &AgentMetadata,
     responses: &EndpointResponses,
@@ -263,14 +292,14 @@ fn check_version_consistency(
                 if let (Some(declared), Some(actual)) = (declared_version, &responses.a2a_version) {
                     if !versions_match(declared, actual) {
                         consistent = false;
-                        issues.push(Issue {
-                            severity: Severity::Info,
-                            code: "A2A_VERSION_MISMATCH".to_string(),
-                            message: format!(
+                        issues.push(Issue::new(
+                            Severity::Info,
+                            "A2A_VERSION_MISMATCH",
+                            format!(
                                 "Declared A2A version '{}' doesn't match actual '{}'",
                                 declared, actual
                             ),
-                        });
+                        ));
                     }
                 }
             }
@@ -278,14 +307,14 @@ fn check_version_consistency(
                 if let (Some(declared), Some(actual)) = (declared_version, &responses.mcp_version) {
                     if !versions_match(declared, actual) {
                         consistent = false;
-                        issues.push(Issue {
-                            severity: Severity::Info,
-                            code: "MCP_VERSION_MISMATCH".to_string(),
-                            message: format!(
+                        issues.push(Issue::new(
+                            Severity::Info,
+                            "MCP_VERSION_MISMATCH",
+                            format!(
                                 "Declared MCP version '{}' doesn't match actual '{}'",
                                 declared, actual
                             ),
-                        });
+                        ));
                     }
                 }
             }
@@ -330,10 +359,8 @@ fn versions_match(declared: &str, actual: &str) -> bool {
     }
 
     // Minor version must match if both have it
-    if d_parts.len() >= 2 && a_parts.len() >= 2 {
-        if d_parts[1] != a_parts[1] {
-            return false;
-        }
+    if d_parts.len() >= 2 && a_parts.len() >= 2 && d_parts[1] != a_parts[1] {
+        return false;
     }
 
     // Patch can differ (compatible versions)
@@ -350,10 +377,26 @@ fn normalize_version(version: &str) -> String {
         .to_string()
 }
 
-async fn check_image_accessible(client: &reqwest::Client, image_url: &str) -> bool {
+async fn check_image_accessible(
+    client: &reqwest::Client,
+    image_url: &str,
+    max_bytes: u64,
+    issues: &mut Vec<Issue>,
+) -> bool {
     match client.head(image_url).send().await {
         Ok(response) => {
             if response.status().is_success() {
+                // Oversized images are still served, but flagged
+                if let Some(size) = response.content_length().filter(|size| *size > max_bytes) {
+                    issues.push(Issue::new(
+                        Severity::Warning,
+                        "IMAGE_TOO_LARGE",
+                        format!(
+                            "Agent image is {} bytes (max {} bytes)",
+                            size, max_bytes
+                        ),
+                    ));
+                }
                 // Check content-type is an image
                 if let Some(content_type) = response.headers().get("content-type") {
                     if let Ok(ct) = content_type.to_str() {
@@ -373,25 +416,60 @@ async fn check_image_accessible(client: &reqwest::Client, image_url: &str) -> bo
     }
 }
 
+// Consistency points lost per failed check
+pub const NAME_PENALTY: u8 = 20;
+pub const SKILLS_PENALTY: u8 = 30;
+pub const VERSION_PENALTY: u8 = 10;
+pub const IMAGE_PENALTY: u8 = 15;
+pub const AUTHOR_PENALTY: u8 = 15;
+
 /// Calculate consistency score
 pub fn calculate_consistency_score(checks: &ConsistencyChecks) -> u8 {
     let mut score = 100u8;
 
     if !checks.name_consistent {
-        score = score.saturating_sub(20);
+        score = score.saturating_sub(NAME_PENALTY);
     }
 
     if !checks.skills_consistent {
-        score = score.saturating_sub(30);
+        score = score.saturating_sub(SKILLS_PENALTY);
     }
 
     if !checks.version_consistent {
-        score = score.saturating_sub(10);
+        score = score.saturating_sub(VERSION_PENALTY);
     }
 
     if !checks.image_accessible {
-        score = score.saturating_sub(15);
+        score = score.saturating_sub(IMAGE_PENALTY);
+    }
+
+    if checks.author_identity_consistent == Some(false) {
+        score = score.saturating_sub(AUTHOR_PENALTY);
     }
 
     score
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Author;
+
+    fn author(name: Option<&str>, url: Option<&str>) -> Author {
+        Author {
+            name: name.map(|s| s.to_string()),
+            url: url.map(|s| s.to_string()),
+            twitter: None,
+        }
+    }
+
+    #[test]
+    fn test_claimed_ens_name() {
+        assert_eq!(claimed_ens_name(&author(Some("Alice.eth"), None)).as_deref(), Some("alice.eth"));
+        assert_eq!(
+            claimed_ens_name(&author(Some("Alice"), Some("https://alice.eth.limo/about"))).as_deref(),
+            Some("alice.eth")
+        );
+        assert_eq!(claimed_ens_name(&author(Some("Alice"), Some("https://alice.dev"))), None);
+    }
+}
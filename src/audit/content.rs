@@ -1,5 +1,8 @@
 use tracing::debug;
 
+use super::{documentation, x402};
+use crate::http::read_body_limited;
+use crate::ssrf::OutboundGuard;
 use crate::types::{AgentMetadata, ContentChecks, DescriptionQuality, Issue, Severity, X402Check};
 
 /// Minimum description length for quality check
@@ -37,6 +40,7 @@ const PLACEHOLDER_TEXTS: &[&str] = &[
 /// Run content quality checks on metadata
 pub async fn check_content(
     client: &reqwest::Client,
+    guard: &OutboundGuard,
     metadata: &AgentMetadata,
 ) -> ContentChecks {
     debug!("Running content quality checks");
@@ -47,6 +51,9 @@ pub async fn check_content(
         valid_skill_taxonomy: true,
         has_contact_info: false,
         x402_valid: None,
+        source_code: None,
+        documentation: None,
+        claims_analysis: None,
         issues: vec![],
     };
 
@@ -59,27 +66,33 @@ pub async fn check_content(
     // Check for contact/support info
     checks.has_contact_info = check_contact_info(metadata);
     if !checks.has_contact_info {
-        checks.issues.push(Issue {
-            severity: Severity::Info,
-            code: "NO_CONTACT_INFO".to_string(),
-            message: "No contact or support information provided".to_string(),
-        });
+        checks.issues.push(Issue::new(
+            Severity::Info,
+            "NO_CONTACT_INFO",
+            "No contact or support information provided",
+        ));
     }
 
     // Check x402 support if claimed
     if metadata.x402_support.unwrap_or(false) {
-        checks.x402_valid = Some(check_x402_support(client, metadata).await);
+        checks.x402_valid = Some(check_x402_support(client, guard, metadata).await);
         if let Some(x402_check) = &checks.x402_valid {
             if !x402_check.valid {
-                checks.issues.push(Issue {
-                    severity: Severity::Warning,
-                    code: "X402_INVALID".to_string(),
-                    message: x402_check.error.clone().unwrap_or_else(|| "x402 check failed".to_string()),
-                });
+                checks.issues.push(Issue::new(
+                    Severity::Warning,
+                    "X402_INVALID",
+                    x402_check.error.clone().unwrap_or_else(|| "x402 check failed".to_string()),
+                ));
             }
         }
     }
 
+    // Check the documentation link if set
+    if let Some((docs, issues)) = documentation::check_documentation(client, guard, metadata).await {
+        checks.issues.extend(issues);
+        checks.documentation = Some(docs);
+    }
+
     // Overall pass/fail
     checks.passed = checks.description_quality.score >= 60
         && checks.valid_skill_taxonomy
@@ -114,33 +127,33 @@ fn check_description_quality(metadata: &AgentMetadata, issues: &mut Vec<Issue>)
     let mut score = 100u8;
 
     if length < MIN_DESCRIPTION_LENGTH {
-        score = score.saturating_sub(40);
-        issues.push(Issue {
-            severity: Severity::Warning,
-            code: "DESCRIPTION_TOO_SHORT".to_string(),
-            message: format!(
+        score = score.saturating_sub(DESCRIPTION_TOO_SHORT_PENALTY);
+        issues.push(Issue::new(
+            Severity::Warning,
+            "DESCRIPTION_TOO_SHORT",
+            format!(
                 "Description is {} characters (minimum {} recommended)",
                 length, MIN_DESCRIPTION_LENGTH
             ),
-        });
+        ));
     }
 
     if has_placeholder {
-        score = score.saturating_sub(30);
-        issues.push(Issue {
-            severity: Severity::Warning,
-            code: "DESCRIPTION_PLACEHOLDER".to_string(),
-            message: "Description appears to contain placeholder text".to_string(),
-        });
+        score = score.saturating_sub(DESCRIPTION_PLACEHOLDER_PENALTY);
+        issues.push(Issue::new(
+            Severity::Warning,
+            "DESCRIPTION_PLACEHOLDER",
+            "Description appears to contain placeholder text",
+        ));
     }
 
     if !is_meaningful && !has_placeholder && length >= MIN_DESCRIPTION_LENGTH {
-        score = score.saturating_sub(20);
-        issues.push(Issue {
-            severity: Severity::Info,
-            code: "DESCRIPTION_LOW_QUALITY".to_string(),
-            message: "Description has low word variety or appears auto-generated".to_string(),
-        });
+        score = score.saturating_sub(DESCRIPTION_LOW_QUALITY_PENALTY);
+        issues.push(Issue::new(
+            Severity::Info,
+            "DESCRIPTION_LOW_QUALITY",
+            "Description has low word variety or appears auto-generated",
+        ));
     }
 
     DescriptionQuality {
@@ -178,14 +191,14 @@ fn check_skill_taxonomy(metadata: &AgentMetadata, issues: &mut Vec<Issue>) -> bo
                     // Extract top-level domain
                     let domain = skill.split('/').next().unwrap_or("");
                     if !known_domains.contains(&domain) {
-                        issues.push(Issue {
-                            severity: Severity::Info,
-                            code: "UNKNOWN_SKILL_DOMAIN".to_string(),
-                            message: format!(
+                        issues.push(Issue::new(
+                            Severity::Info,
+                            "UNKNOWN_SKILL_DOMAIN",
+                            format!(
                                 "Skill '{}' uses unknown domain '{}' (not in OASF taxonomy)",
                                 skill, domain
                             ),
-                        });
+                        ));
                         // Don't fail for unknown domains, just warn
                     }
                 }
@@ -265,7 +278,11 @@ fn check_has_email(text: &str) -> bool {
 /// Timeout for x402 test requests in seconds
 const X402_TEST_TIMEOUT_SECS: u64 = 10;
 
-async fn check_x402_support(client: &reqwest::Client, metadata: &AgentMetadata) -> X402Check {
+async fn check_x402_support(
+    client: &reqwest::Client,
+    guard: &OutboundGuard,
+    metadata: &AgentMetadata,
+) -> X402Check {
     let mut check = X402Check::default();
 
     // Find all MCP/A2A endpoints to test
@@ -289,6 +306,10 @@ async fn check_x402_support(client: &reqwest::Client, metadata: &AgentMetadata)
     let mut errors = vec![];
 
     for endpoint in &test_endpoints {
+        if let Err(reason) = guard.check(endpoint).await {
+            errors.push(format!("{}: not probed ({})", endpoint, reason));
+            continue;
+        }
         debug!("Testing x402 support at {}", endpoint);
 
         // Send request without payment credentials
@@ -328,6 +349,25 @@ async fn check_x402_support(client: &reqwest::Client, metadata: &AgentMetadata)
                         .and_then(|v| v.to_str().ok())
                         .map(|s| s.to_string());
 
+                    // Standard x402 puts the requirements in the body
+                    let accepts = read_body_limited(response, x402::MAX_BODY_BYTES)
+                        .await
+                        .map(|body| x402::parse_accepts(&body))
+                        .unwrap_or_default();
+                    let requirement = accepts.first();
+                    let payment_address = payment_address
+                        .or_else(|| requirement.map(|r| r.pay_to.clone()))
+                        .filter(|address| !address.is_empty());
+                    let payment_amount = payment_amount
+                        .or_else(|| requirement.map(|r| r.max_amount_required.clone()));
+                    let payment_network =
+                        payment_network.or_else(|| requirement.map(|r| r.network.clone()));
+                    for requirement in accepts {
+                        if !check.accepts.contains(&requirement) {
+                            check.accepts.push(requirement);
+                        }
+                    }
+
                     // Update check fields with first valid response
                     if payment_address.is_some() && check.payment_address.is_none() {
                         check.has_payment_address = true;
@@ -371,31 +411,71 @@ async fn check_x402_support(client: &reqwest::Client, metadata: &AgentMetadata)
     check
 }
 
+// Description quality points lost per finding (out of 100)
+pub const DESCRIPTION_TOO_SHORT_PENALTY: u8 = 40;
+pub const DESCRIPTION_PLACEHOLDER_PENALTY: u8 = 30;
+pub const DESCRIPTION_LOW_QUALITY_PENALTY: u8 = 20;
+
+// Content points per check
+/// At full description quality
+pub const DESCRIPTION_POINTS: u8 = 40;
+pub const SKILL_TAXONOMY_POINTS: u8 = 20;
+pub const CONTACT_POINTS: u8 = 15;
+pub const X402_POINTS: u8 = 25;
+pub const DOCS_PENALTY: u8 = 10;
+/// Declared repository missing or private
+pub const SOURCE_MISSING_PENALTY: u8 = 15;
+/// Repository found, but not the declared commit or release
+pub const SOURCE_MISMATCH_PENALTY: u8 = 10;
+
 /// Calculate content quality score
 pub fn calculate_content_score(checks: &ContentChecks) -> u8 {
     let mut score = 0u8;
 
-    // Description quality (40 points max)
-    score += (checks.description_quality.score as f64 * 0.4) as u8;
+    // Description quality
+    score += (checks.description_quality.score as u16 * DESCRIPTION_POINTS as u16 / 100) as u8;
 
-    // Skill taxonomy (20 points)
+    // Skill taxonomy
     if checks.valid_skill_taxonomy {
-        score += 20;
+        score += SKILL_TAXONOMY_POINTS;
     }
 
-    // Contact info (15 points)
+    // Contact info
     if checks.has_contact_info {
-        score += 15;
+        score += CONTACT_POINTS;
     }
 
-    // x402 validity (25 points if claimed)
+    // x402 validity (withheld if payments go elsewhere or in an asset that
+    // doesn't exist)
     if let Some(x402) = &checks.x402_valid {
-        if x402.valid {
-            score += 25;
+        if x402.valid
+            && x402.pay_to_matches_wallet != Some(false)
+            && !x402.assets.iter().any(|asset| asset.exists == Some(false))
+        {
+            score += X402_POINTS;
         }
     } else {
         // Not claiming x402, give points anyway
-        score += 25;
+        score += X402_POINTS;
+    }
+
+    // Documentation link that's down, empty or parked
+    if let Some(docs) = &checks.documentation {
+        if !docs.reachable || docs.parked || docs.text_chars < documentation::MIN_TEXT_CHARS {
+            score = score.saturating_sub(DOCS_PENALTY);
+        }
+    }
+
+    // Declared source code that doesn't check out
+    if let Some(source) = &checks.source_code {
+        if !source.verified() {
+            let penalty = if source.exists && source.public != Some(false) {
+                SOURCE_MISMATCH_PENALTY
+            } else {
+                SOURCE_MISSING_PENALTY
+            };
+            score = score.saturating_sub(penalty);
+        }
     }
 
     score
@@ -0,0 +1,721 @@
+//! DIDs and verifiable credentials in agent metadata
+//!
+//! Agents can publish decentralized identifiers and verifiable credentials in
+//! `supportedTrust`, extensions or any other field. DIDs are resolved
+//! (`did:web` over HTTPS, `did:key` locally) and credential signatures are
+//! checked against the issuer's DID document:
+//!
+//! - JWT credentials (`vc` claim) signed with `EdDSA` or `ES256K`
+//! - Data Integrity proofs with the `eddsa-jcs-2022` cryptosuite
+//!
+//! Proofs over RDF-canonicalized documents (e.g. `Ed25519Signature2020`)
+//! aren't verified and are reported as unverified rather than invalid.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use tracing::debug;
+
+use crate::canonical;
+use crate::http::read_body_limited;
+use crate::ssrf::OutboundGuard;
+use crate::types::{
+    AgentMetadata, CredentialCheck, CredentialChecks, CredentialFormat, DidCheck, Issue, Severity,
+};
+
+/// DIDs and credentials checked per audit
+const MAX_DIDS: usize = 10;
+const MAX_CREDENTIALS: usize = 10;
+
+const MAX_DID_DOCUMENT_BYTES: usize = 64 * 1024;
+
+/// Metadata points lost per credential whose signature doesn't verify
+pub const INVALID_SIGNATURE_PENALTY: u8 = 15;
+
+/// Multicodec prefixes of `did:key` / `publicKeyMultibase` keys
+const ED25519_CODEC: [u8; 2] = [0xed, 0x01];
+const SECP256K1_CODEC: [u8; 2] = [0xe7, 0x01];
+
+/// Find, resolve and verify the metadata's DIDs and credentials
+///
+/// Returns `None` when the metadata has neither.
+pub async fn check_credentials(
+    client: &reqwest::Client,
+    guard: &OutboundGuard,
+    metadata: &AgentMetadata,
+) -> Option<(CredentialChecks, Vec<Issue>)> {
+    let mut found = Found::default();
+    collect(&metadata.raw, "", &mut found);
+    if found.dids.is_empty() && found.credentials.is_empty() {
+        return None;
+    }
+    debug!(
+        "Found {} DIDs and {} credentials in metadata",
+        found.dids.len(),
+        found.credentials.len()
+    );
+
+    let mut resolver = Resolver {
+        client,
+        guard,
+        cache: HashMap::new(),
+    };
+    let mut checks = CredentialChecks::default();
+    for (path, did) in found.dids {
+        let method = did.split(':').nth(1).unwrap_or_default().to_string();
+        let (resolved, verification_methods, error) = match resolver.resolve(&did).await {
+            Ok(document) => (true, verification_method_ids(&document, &did), None),
+            Err(e) => (false, vec![], Some(e)),
+        };
+        checks.dids.push(DidCheck {
+            did,
+            path,
+            method,
+            resolved,
+            verification_methods,
+            error,
+        });
+    }
+    for (path, credential) in found.credentials {
+        let check = match credential {
+            Credential::Jwt(token) => verify_jwt(&mut resolver, path, &token).await,
+            Credential::DataIntegrity(document) => {
+                verify_data_integrity(&mut resolver, path, &document).await
+            }
+        };
+        checks.credentials.push(check);
+    }
+
+    let issues = credential_issues(&checks);
+    Some((checks, issues))
+}
+
+enum Credential {
+    Jwt(String),
+    DataIntegrity(Value),
+}
+
+#[derive(Default)]
+struct Found {
+    /// (JSON pointer, DID)
+    dids: Vec<(String, String)>,
+    credentials: Vec<(String, Credential)>,
+}
+
+impl Found {
+    fn add_did(&mut self, path: &str, did: &str) {
+        if self.dids.len() < MAX_DIDS && !self.dids.iter().any(|(_, seen)| seen == did) {
+            self.dids.push((path.to_string(), did.to_string()));
+        }
+    }
+
+    fn add_credential(&mut self, path: &str, credential: Credential) {
+        if self.credentials.len() < MAX_CREDENTIALS {
+            self.credentials.push((path.to_string(), credential));
+        }
+    }
+}
+
+/// Walk the metadata for DIDs and credentials (not descending into credentials)
+fn collect(value: &Value, path: &str, found: &mut Found) {
+    match value {
+        Value::String(s) if is_did(s) => found.add_did(path, s),
+        Value::String(s) if jwt_vc(s).is_some() => {
+            found.add_credential(path, Credential::Jwt(s.clone()))
+        }
+        Value::Object(_) if is_credential(value) => {
+            found.add_credential(path, Credential::DataIntegrity(value.clone()))
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                collect(item, &format!("{}/{}", path, i), found);
+            }
+        }
+        Value::Object(map) => {
+            for (k, v) in map {
+                let escaped = k.replace('~', "~0").replace('/', "~1");
+                collect(v, &format!("{}/{}", path, escaped), found);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// `did:<method>:<id>` without whitespace or a fragment
+fn is_did(value: &str) -> bool {
+    let mut parts = value.splitn(3, ':');
+    parts.next() == Some("did")
+        && parts.next().is_some_and(|m| {
+            !m.is_empty()
+                && m.bytes()
+                    .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit())
+        })
+        && parts.next().is_some_and(|id| !id.is_empty())
+        && !value.contains(|c: char| c.is_whitespace() || c == '#')
+}
+
+/// JSON object whose `type` includes VerifiableCredential and that carries a proof
+fn is_credential(value: &Value) -> bool {
+    value.get("proof").is_some()
+        && string_list(value.get("type"))
+            .iter()
+            .any(|t| t == "VerifiableCredential")
+}
+
+/// Header and payload of a JWT carrying a `vc` claim
+fn jwt_vc(token: &str) -> Option<(Value, Value)> {
+    let mut parts = token.split('.');
+    let (header, payload, _signature) = (parts.next()?, parts.next()?, parts.next()?);
+    if parts.next().is_some() {
+        return None;
+    }
+    let decode = |part: &str| -> Option<Value> {
+        serde_json::from_slice(&URL_SAFE_NO_PAD.decode(part).ok()?).ok()
+    };
+    let (header, payload) = (decode(header)?, decode(payload)?);
+    payload.get("vc")?;
+    Some((header, payload))
+}
+
+/// A string or an array of strings
+fn string_list(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::String(s)) => vec![s.clone()],
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|item| item.as_str().map(str::to_string))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// `issuer` as a string or `{ "id": ... }`
+fn issuer_id(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::String(s) => Some(s.clone()),
+        other => other.get("id")?.as_str().map(str::to_string),
+    }
+}
+
+/// Parse an expiry and whether it has passed
+fn expiry(expires: Option<DateTime<Utc>>) -> (Option<String>, bool) {
+    match expires {
+        Some(at) => (Some(at.to_rfc3339()), at < Utc::now()),
+        None => (None, false),
+    }
+}
+
+fn parse_date(value: Option<&Value>) -> Option<DateTime<Utc>> {
+    let parsed = DateTime::parse_from_rfc3339(value?.as_str()?).ok()?;
+    Some(parsed.with_timezone(&Utc))
+}
+
+async fn verify_jwt(resolver: &mut Resolver<'_>, path: String, token: &str) -> CredentialCheck {
+    // Collected only if jwt_vc matched
+    let (header, payload) = jwt_vc(token).unwrap_or_default();
+    let vc = &payload["vc"];
+    let issuer = payload["iss"]
+        .as_str()
+        .map(str::to_string)
+        .or_else(|| issuer_id(vc.get("issuer")));
+    let expires = payload["exp"]
+        .as_i64()
+        .and_then(|exp| DateTime::from_timestamp(exp, 0))
+        .or_else(|| parse_date(vc.get("expirationDate").or(vc.get("validUntil"))));
+    let (expires, expired) = expiry(expires);
+
+    let mut check = CredentialCheck {
+        path,
+        format: CredentialFormat::Jwt,
+        types: string_list(vc.get("type")),
+        issuer: issuer.clone(),
+        subject: payload["sub"]
+            .as_str()
+            .or(vc["credentialSubject"]["id"].as_str())
+            .map(str::to_string),
+        expires,
+        expired,
+        signature_valid: None,
+        error: None,
+    };
+
+    let alg = header["alg"].as_str().unwrap_or_default();
+    if !matches!(alg, "EdDSA" | "ES256K") {
+        check.error = Some(format!("unsupported JWT algorithm '{}'", alg));
+        return check;
+    }
+    let Some(issuer) = issuer.filter(|issuer| is_did(issuer)) else {
+        check.error = Some("issuer is not a DID".to_string());
+        return check;
+    };
+    let (signing_input, signature) = token.rsplit_once('.').unwrap_or_default();
+    let result = match URL_SAFE_NO_PAD.decode(signature) {
+        Ok(signature) => {
+            let kid = header["kid"].as_str();
+            match resolver.key(&issuer, kid).await {
+                Ok(key) => key.verify(alg, signing_input.as_bytes(), &signature),
+                Err(e) => Err(e),
+            }
+        }
+        Err(e) => Ok(Err(format!("invalid signature encoding: {}", e))),
+    };
+    apply_result(&mut check, result);
+    check
+}
+
+async fn verify_data_integrity(
+    resolver: &mut Resolver<'_>,
+    path: String,
+    document: &Value,
+) -> CredentialCheck {
+    let (expires, expired) = expiry(parse_date(
+        document
+            .get("validUntil")
+            .or(document.get("expirationDate")),
+    ));
+    let issuer = issuer_id(document.get("issuer"));
+    let mut check = CredentialCheck {
+        path,
+        format: CredentialFormat::DataIntegrity,
+        types: string_list(document.get("type")),
+        issuer: issuer.clone(),
+        subject: document["credentialSubject"]["id"]
+            .as_str()
+            .map(str::to_string),
+        expires,
+        expired,
+        signature_valid: None,
+        error: None,
+    };
+
+    // Multiple proofs: the first one is checked
+    let proof = match &document["proof"] {
+        Value::Array(proofs) => proofs.first().cloned().unwrap_or_default(),
+        proof => proof.clone(),
+    };
+    let proof_type = proof["type"].as_str().unwrap_or_default();
+    let cryptosuite = proof["cryptosuite"].as_str().unwrap_or_default();
+    if proof_type != "DataIntegrityProof" || cryptosuite != "eddsa-jcs-2022" {
+        check.error = Some(format!(
+            "unsupported proof '{}'",
+            if cryptosuite.is_empty() {
+                proof_type
+            } else {
+                cryptosuite
+            }
+        ));
+        return check;
+    }
+
+    let method = proof["verificationMethod"].as_str().unwrap_or_default();
+    let did = method.split('#').next().unwrap_or_default();
+    if issuer.as_deref() != Some(did) {
+        check.signature_valid = Some(false);
+        check.error = Some(format!(
+            "proof key {} isn't controlled by the issuer",
+            method
+        ));
+        return check;
+    }
+
+    let result = match proof["proofValue"]
+        .as_str()
+        .and_then(|value| value.strip_prefix('z'))
+        .and_then(|value| bs58::decode(value).into_vec().ok())
+    {
+        Some(signature) => match resolver.key(did, Some(method)).await {
+            Ok(key) => {
+                let message = jcs_hash_data(document, &proof);
+                key.verify("EdDSA", &message, &signature)
+            }
+            Err(e) => Err(e),
+        },
+        None => Ok(Err("proofValue is not base58btc multibase".to_string())),
+    };
+    apply_result(&mut check, result);
+    check
+}
+
+/// Record a verification result: `Err` means the key couldn't be obtained
+/// (unverified), `Ok(Err)` a signature that doesn't verify
+fn apply_result(check: &mut CredentialCheck, result: Result<Result<(), String>, String>) {
+    match result {
+        Ok(Ok(())) => check.signature_valid = Some(true),
+        Ok(Err(e)) => {
+            check.signature_valid = Some(false);
+            check.error = Some(e);
+        }
+        Err(e) => check.error = Some(e),
+    }
+}
+
+/// Bytes signed by an `eddsa-jcs-2022` proof: the SHA-256 of the canonical
+/// proof options followed by the SHA-256 of the canonical unsecured document
+fn jcs_hash_data(document: &Value, proof: &Value) -> Vec<u8> {
+    let mut options = proof.clone();
+    if let Some(options) = options.as_object_mut() {
+        options.remove("proofValue");
+    }
+    let mut unsecured = document.clone();
+    if let Some(unsecured) = unsecured.as_object_mut() {
+        unsecured.remove("proof");
+    }
+    let mut data = Sha256::digest(canonical::to_vec(&options)).to_vec();
+    data.extend_from_slice(&Sha256::digest(canonical::to_vec(&unsecured)));
+    data
+}
+
+enum PublicKey {
+    Ed25519(ed25519_dalek::VerifyingKey),
+    Secp256k1(k256::ecdsa::VerifyingKey),
+}
+
+impl PublicKey {
+    /// Key of a verification method (`publicKeyMultibase` or `publicKeyJwk`)
+    fn from_method(method: &Value) -> Option<Self> {
+        if let Some(multibase) = method["publicKeyMultibase"].as_str() {
+            return Self::from_multibase(multibase);
+        }
+        let jwk = method.get("publicKeyJwk")?;
+        let coordinate = |name: &str| URL_SAFE_NO_PAD.decode(jwk[name].as_str()?).ok();
+        match (jwk["kty"].as_str()?, jwk["crv"].as_str()?) {
+            ("OKP", "Ed25519") => {
+                let bytes: [u8; 32] = coordinate("x")?.try_into().ok()?;
+                ed25519_dalek::VerifyingKey::from_bytes(&bytes)
+                    .ok()
+                    .map(Self::Ed25519)
+            }
+            ("EC", "secp256k1") => {
+                let mut sec1 = vec![0x04];
+                sec1.extend(coordinate("x")?);
+                sec1.extend(coordinate("y")?);
+                k256::ecdsa::VerifyingKey::from_sec1_bytes(&sec1)
+                    .ok()
+                    .map(Self::Secp256k1)
+            }
+            _ => None,
+        }
+    }
+
+    /// Multicodec key in base58btc multibase (`z...`)
+    fn from_multibase(multibase: &str) -> Option<Self> {
+        let bytes = bs58::decode(multibase.strip_prefix('z')?).into_vec().ok()?;
+        if let Some(key) = bytes.strip_prefix(&ED25519_CODEC) {
+            let key: [u8; 32] = key.try_into().ok()?;
+            ed25519_dalek::VerifyingKey::from_bytes(&key)
+                .ok()
+                .map(Self::Ed25519)
+        } else if let Some(key) = bytes.strip_prefix(&SECP256K1_CODEC) {
+            k256::ecdsa::VerifyingKey::from_sec1_bytes(key)
+                .ok()
+                .map(Self::Secp256k1)
+        } else {
+            None
+        }
+    }
+
+    /// Verify a JOSE-style signature (`EdDSA` or `ES256K`, r || s)
+    ///
+    /// `Err` when the algorithm doesn't fit the key; `Ok(Err)` when the
+    /// signature doesn't verify.
+    fn verify(
+        &self,
+        alg: &str,
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<Result<(), String>, String> {
+        let verified = match (self, alg) {
+            (Self::Ed25519(key), "EdDSA") => ed25519_dalek::Signature::from_slice(signature)
+                .and_then(|signature| ed25519_dalek::Verifier::verify(key, message, &signature))
+                .is_ok(),
+            (Self::Secp256k1(key), "ES256K") => k256::ecdsa::Signature::from_slice(signature)
+                .map(|signature| signature.normalize_s().unwrap_or(signature))
+                .and_then(|signature| {
+                    k256::ecdsa::signature::Verifier::verify(key, message, &signature)
+                })
+                .is_ok(),
+            _ => return Err(format!("{} signature with a mismatched key type", alg)),
+        };
+        Ok(if verified {
+            Ok(())
+        } else {
+            Err("signature doesn't verify".to_string())
+        })
+    }
+}
+
+/// IDs of a DID document's verification methods, made absolute
+fn verification_method_ids(document: &Value, did: &str) -> Vec<String> {
+    document["verificationMethod"]
+        .as_array()
+        .map(|methods| {
+            methods
+                .iter()
+                .filter_map(|method| method["id"].as_str())
+                .map(|id| absolute_id(did, id))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn absolute_id(did: &str, id: &str) -> String {
+    if id.starts_with('#') {
+        format!("{}{}", did, id)
+    } else {
+        id.to_string()
+    }
+}
+
+/// DID resolution, cached for the audit
+struct Resolver<'a> {
+    client: &'a reqwest::Client,
+    guard: &'a OutboundGuard,
+    cache: HashMap<String, Result<Value, String>>,
+}
+
+impl Resolver<'_> {
+    async fn resolve(&mut self, did: &str) -> Result<Value, String> {
+        if let Some(cached) = self.cache.get(did) {
+            return cached.clone();
+        }
+        let resolved = self.fetch(did).await;
+        self.cache.insert(did.to_string(), resolved.clone());
+        resolved
+    }
+
+    async fn fetch(&self, did: &str) -> Result<Value, String> {
+        if let Some(multibase) = did.strip_prefix("did:key:") {
+            PublicKey::from_multibase(multibase)
+                .ok_or_else(|| "unsupported did:key key type".to_string())?;
+            return Ok(serde_json::json!({
+                "id": did,
+                "verificationMethod": [{
+                    "id": format!("{}#{}", did, multibase),
+                    "type": "Multikey",
+                    "controller": did,
+                    "publicKeyMultibase": multibase,
+                }],
+            }));
+        }
+        let Some(url) = did_web_url(did) else {
+            return Err(format!(
+                "unsupported DID method '{}'",
+                did.split(':').nth(1).unwrap_or_default()
+            ));
+        };
+        // The DID names the host, so it's as untrusted as any metadata URL
+        self.guard
+            .check(&url)
+            .await
+            .map_err(|e| format!("{} blocked: {}", url, e))?;
+
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| format!("fetching {} failed: {}", url, e))?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "{} returned HTTP {}",
+                url,
+                response.status().as_u16()
+            ));
+        }
+        let body = read_body_limited(response, MAX_DID_DOCUMENT_BYTES)
+            .await
+            .map_err(|e| format!("{}: {}", url, e))?;
+        let document: Value = serde_json::from_slice(&body)
+            .map_err(|e| format!("{} is not valid JSON: {}", url, e))?;
+        if document["id"].as_str() != Some(did) {
+            return Err(format!("{} describes a different DID", url));
+        }
+        Ok(document)
+    }
+
+    /// Public key for a DID, by verification method ID (or the first usable one)
+    async fn key(&mut self, did: &str, kid: Option<&str>) -> Result<PublicKey, String> {
+        let document = self.resolve(did).await?;
+        let methods = document["verificationMethod"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+        let kid = kid.map(|kid| absolute_id(did, kid));
+        methods
+            .iter()
+            .filter(|method| {
+                kid.is_none() || method["id"].as_str().map(|id| absolute_id(did, id)) == kid
+            })
+            .find_map(PublicKey::from_method)
+            .ok_or_else(|| format!("no usable key for {} in the DID document", did))
+    }
+}
+
+/// HTTPS URL of a `did:web` document
+fn did_web_url(did: &str) -> Option<String> {
+    let id = did.strip_prefix("did:web:")?;
+    let mut segments = id.split(':');
+    // Ports are percent-encoded in the host segment
+    let host = segments.next()?.replace("%3A", ":").replace("%3a", ":");
+    let path: Vec<&str> = segments.collect();
+    Some(if path.is_empty() {
+        format!("https://{}/.well-known/did.json", host)
+    } else {
+        format!("https://{}/{}/did.json", host, path.join("/"))
+    })
+}
+
+fn credential_issues(checks: &CredentialChecks) -> Vec<Issue> {
+    let mut issues = vec![];
+    for did in checks.dids.iter().filter(|did| !did.resolved) {
+        let error = did.error.as_deref().unwrap_or("not resolved");
+        issues.push(if error.starts_with("unsupported DID method") {
+            Issue::new(
+                Severity::Info,
+                "DID_METHOD_UNSUPPORTED",
+                format!("{} can't be resolved by the auditor: {}", did.did, error),
+            )
+        } else {
+            Issue::new(
+                Severity::Warning,
+                "DID_UNRESOLVABLE",
+                format!("{} doesn't resolve: {}", did.did, error),
+            )
+        });
+    }
+    for credential in &checks.credentials {
+        let error = credential.error.as_deref().unwrap_or_default();
+        match credential.signature_valid {
+            Some(true) => {}
+            Some(false) => issues.push(Issue::new(
+                Severity::Error,
+                "CREDENTIAL_INVALID_SIGNATURE",
+                format!(
+                    "Credential at {} fails verification: {}",
+                    credential.path, error
+                ),
+            )),
+            None => issues.push(Issue::new(
+                Severity::Info,
+                "CREDENTIAL_UNVERIFIED",
+                format!("Credential at {} not verified: {}", credential.path, error),
+            )),
+        }
+        if credential.expired {
+            issues.push(Issue::new(
+                Severity::Warning,
+                "CREDENTIAL_EXPIRED",
+                format!(
+                    "Credential at {} expired {}",
+                    credential.path,
+                    credential.expires.as_deref().unwrap_or_default()
+                ),
+            ));
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    #[tokio::test]
+    async fn test_credential_verification() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let mut multicodec = ED25519_CODEC.to_vec();
+        multicodec.extend_from_slice(key.verifying_key().as_bytes());
+        let multibase = format!("z{}", bs58::encode(multicodec).into_string());
+        let did = format!("did:key:{}", multibase);
+
+        // eddsa-jcs-2022 credential issued by the did:key
+        let proof = serde_json::json!({
+            "type": "DataIntegrityProof",
+            "cryptosuite": "eddsa-jcs-2022",
+            "verificationMethod": format!("{}#{}", did, multibase),
+            "proofPurpose": "assertionMethod",
+        });
+        let mut credential = serde_json::json!({
+            "type": ["VerifiableCredential", "AgentAuditCredential"],
+            "issuer": did,
+            "credentialSubject": { "id": "did:web:agent.example.com" },
+        });
+        let signature = key.sign(&jcs_hash_data(&credential, &proof));
+        let mut signed_proof = proof.clone();
+        signed_proof["proofValue"] =
+            format!("z{}", bs58::encode(signature.to_bytes()).into_string()).into();
+        credential["proof"] = signed_proof;
+
+        // JWT credential from the same issuer
+        let encode = |value: Value| URL_SAFE_NO_PAD.encode(value.to_string());
+        let signing_input = format!(
+            "{}.{}",
+            encode(serde_json::json!({ "alg": "EdDSA" })),
+            encode(serde_json::json!({
+                "iss": did,
+                "exp": 1,
+                "vc": { "type": ["VerifiableCredential"] },
+            }))
+        );
+        let jwt = format!(
+            "{}.{}",
+            signing_input,
+            URL_SAFE_NO_PAD.encode(key.sign(signing_input.as_bytes()).to_bytes())
+        );
+
+        let mut tampered = credential.clone();
+        tampered["credentialSubject"]["id"] = "did:web:attacker.example".into();
+        let metadata: AgentMetadata =
+            serde_json::from_value(serde_json::json!({ "name": "Agent" })).unwrap();
+        let metadata = AgentMetadata {
+            raw: serde_json::json!({
+                "supportedTrust": ["reputation", did],
+                "extensions": { "credentials": [credential, jwt, tampered] },
+            }),
+            ..metadata
+        };
+
+        let client = reqwest::Client::new();
+        let guard = OutboundGuard::default();
+        let (checks, issues) = check_credentials(&client, &guard, &metadata).await.unwrap();
+        assert_eq!(checks.dids.len(), 1);
+        assert!(checks.dids[0].resolved);
+        assert_eq!(checks.dids[0].path, "/supportedTrust/1");
+
+        let valid: Vec<Option<bool>> = checks
+            .credentials
+            .iter()
+            .map(|c| c.signature_valid)
+            .collect();
+        assert_eq!(valid, [Some(true), Some(true), Some(false)]);
+        assert!(checks.credentials[1].expired);
+        assert_eq!(checks.credentials[2].path, "/extensions/credentials/2");
+
+        let codes: Vec<&str> = issues.iter().map(|i| i.code.as_str()).collect();
+        assert_eq!(
+            codes,
+            ["CREDENTIAL_EXPIRED", "CREDENTIAL_INVALID_SIGNATURE"]
+        );
+
+        assert_eq!(
+            did_web_url("did:web:example.com%3A8443:agents:alice").as_deref(),
+            Some("https://example.com:8443/agents/alice/did.json")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_did_web_guarded() {
+        let client = reqwest::Client::new();
+        let guard = OutboundGuard::default();
+        let mut resolver = Resolver {
+            client: &client,
+            guard: &guard,
+            cache: HashMap::new(),
+        };
+        let error = resolver.resolve("did:web:127.0.0.1%3A8080").await.unwrap_err();
+        assert!(error.contains("blocked"), "{}", error);
+    }
+}
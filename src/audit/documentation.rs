@@ -0,0 +1,205 @@
+//! Documentation link check
+//!
+//! When metadata sets `documentation`, the page must load, have some actual
+//! text on it, and not be a domain parking page.
+
+use tracing::debug;
+
+use crate::http::read_body_limited;
+use crate::ssrf::OutboundGuard;
+use crate::types::{AgentMetadata, DocumentationCheck, Issue, Severity};
+
+/// Bytes of the page read for the text and parking checks
+const MAX_PAGE_BYTES: usize = 1024 * 1024;
+
+/// Visible characters below which a page counts as empty
+pub const MIN_TEXT_CHARS: usize = 100;
+
+/// Phrases found on domain parking and for-sale pages
+const PARKING_PHRASES: &[&str] = &[
+    "this domain is for sale",
+    "this domain may be for sale",
+    "buy this domain",
+    "domain is parked",
+    "parked free",
+    "parkingcrew",
+    "sedoparking",
+];
+
+/// Marketplaces and parking services parked domains redirect to
+const PARKING_HOSTS: &[&str] = &[
+    "bodis.com",
+    "dan.com",
+    "afternic.com",
+    "hugedomains.com",
+    "sedo.com",
+    "undeveloped.com",
+];
+
+/// Check the metadata's `documentation` URL
+///
+/// Returns `None` when there's no http(s) documentation URL to check.
+pub async fn check_documentation(
+    client: &reqwest::Client,
+    guard: &OutboundGuard,
+    metadata: &AgentMetadata,
+) -> Option<(DocumentationCheck, Vec<Issue>)> {
+    let url = metadata.documentation.as_deref()?;
+    if !url.starts_with("https://") && !url.starts_with("http://") {
+        return None;
+    }
+    debug!("Checking documentation at {}", url);
+
+    let mut check = DocumentationCheck {
+        url: url.to_string(),
+        ..Default::default()
+    };
+    if let Err(e) = fetch(client, guard, url, &mut check).await {
+        debug!("Documentation {} unreachable: {}", url, e);
+        check.error = Some(e);
+    }
+
+    let issues = issues_for(&check);
+    Some((check, issues))
+}
+
+async fn fetch(
+    client: &reqwest::Client,
+    guard: &OutboundGuard,
+    url: &str,
+    check: &mut DocumentationCheck,
+) -> Result<(), String> {
+    guard.check(url).await.map_err(|e| format!("Not fetched: {}", e))?;
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    let status = response.status();
+    check.status = Some(status.as_u16());
+    if !status.is_success() {
+        return Err(format!("HTTP {}", status.as_u16()));
+    }
+    check.reachable = true;
+
+    let final_host = response.url().host_str().unwrap_or_default().to_lowercase();
+    let body = read_body_limited(response, MAX_PAGE_BYTES)
+        .await
+        .map_err(|e| e.to_string())?;
+    let body = String::from_utf8_lossy(&body);
+
+    check.text_chars = visible_text_chars(&body);
+    let lower = body.to_lowercase();
+    check.parked = PARKING_PHRASES.iter().any(|phrase| lower.contains(phrase))
+        || PARKING_HOSTS
+            .iter()
+            .any(|host| final_host == *host || final_host.ends_with(&format!(".{}", host)));
+    Ok(())
+}
+
+/// Count non-whitespace characters outside tags, scripts and styles
+fn visible_text_chars(html: &str) -> usize {
+    let lower = html.to_lowercase();
+    let mut count = 0;
+    let mut rest = lower.as_str();
+    while let Some(start) = rest.find('<') {
+        count += rest[..start].chars().filter(|c| !c.is_whitespace()).count();
+        rest = &rest[start..];
+        // Skip the contents of script and style elements entirely
+        let skip_to = ["script", "style"]
+            .iter()
+            .find(|tag| rest[1..].starts_with(*tag))
+            .and_then(|tag| rest.find(&format!("</{}", tag)));
+        let from = skip_to.unwrap_or(0);
+        rest = match rest[from..].find('>') {
+            Some(end) => &rest[from + end + 1..],
+            None => "",
+        };
+    }
+    count + rest.chars().filter(|c| !c.is_whitespace()).count()
+}
+
+fn issues_for(check: &DocumentationCheck) -> Vec<Issue> {
+    if !check.reachable {
+        return vec![Issue::new(
+            Severity::Warning,
+            "DOCS_UNREACHABLE",
+            format!(
+                "Documentation {} is unreachable ({})",
+                check.url,
+                check.error.as_deref().unwrap_or("no response")
+            ),
+        )];
+    }
+    if check.parked || check.text_chars < MIN_TEXT_CHARS {
+        return vec![Issue::new(
+            Severity::Warning,
+            "DOCS_EMPTY",
+            if check.parked {
+                format!("Documentation {} is a parked or for-sale domain", check.url)
+            } else {
+                format!(
+                    "Documentation {} has almost no text ({} characters)",
+                    check.url, check.text_chars
+                )
+            },
+        )];
+    }
+    vec![]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::path;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn test_visible_text_chars() {
+        let html = "<html><head><style>body { color: red }</style><script>let x = '<b>';</script></head>\
+                    <body><h1>Docs</h1> <p>Hi there</p></body></html>";
+        assert_eq!(visible_text_chars(html), "DocsHithere".len());
+    }
+
+    #[tokio::test]
+    async fn test_check_documentation() {
+        let server = MockServer::start().await;
+        Mock::given(path("/docs"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+                "<html><body><p>{}</p></body></html>",
+                "Usage guide for the agent. ".repeat(10)
+            )))
+            .mount(&server)
+            .await;
+        Mock::given(path("/parked"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string("<html><body>This domain is for sale! Contact us.</body></html>"),
+            )
+            .mount(&server)
+            .await;
+
+        let guard = OutboundGuard {
+            enabled: false,
+            ..Default::default()
+        };
+        let codes = |docs: &str| {
+            let metadata: AgentMetadata = serde_json::from_value(serde_json::json!({
+                "documentation": format!("{}{}", server.uri(), docs)
+            }))
+            .unwrap();
+            let guard = guard.clone();
+            async move {
+                let (_, issues) = check_documentation(&reqwest::Client::new(), &guard, &metadata)
+                    .await
+                    .unwrap();
+                issues.into_iter().map(|i| i.code).collect::<Vec<_>>()
+            }
+        };
+
+        assert!(codes("/docs").await.is_empty());
+        assert_eq!(codes("/parked").await, vec!["DOCS_EMPTY"]);
+        assert_eq!(codes("/missing").await, vec!["DOCS_UNREACHABLE"]);
+    }
+}
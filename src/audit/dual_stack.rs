@@ -0,0 +1,156 @@
+//! IPv4 / IPv6 reachability of dual-stack endpoints
+//!
+//! Clients prefer IPv6 when a host publishes an AAAA record, so an endpoint
+//! that only answers over IPv4 is broken for some of them even though the
+//! main probe (which falls back between families) succeeds. Hosts resolving
+//! to both families are probed once more over each, through resolvers pinned
+//! to that family.
+
+use std::net::{IpAddr, Ipv6Addr, UdpSocket};
+use std::time::{Duration, Instant};
+use tracing::debug;
+use url::{Host, Url};
+
+use super::endpoints::{calculate_percentiles, ProbeRate};
+use crate::http::FamilyClients;
+use crate::types::{DualStackCheck, FamilyReachability, Issue, Severity};
+
+/// Most samples taken per family
+const FAMILY_SAMPLES: u32 = 3;
+
+/// Public IPv6 address used to look up the auditor's own IPv6 route
+const IPV6_ROUTE_PROBE: &str = "[2001:4860:4860::8888]:53";
+
+/// Probe an endpoint over IPv4 and IPv6
+///
+/// Returns `None` for IP literals, hosts without both A and AAAA records, and
+/// when the auditor has no IPv6 connectivity itself.
+pub async fn probe(
+    clients: &FamilyClients,
+    endpoint: &str,
+    rate: ProbeRate,
+) -> Option<DualStackCheck> {
+    let url = Url::parse(endpoint).ok()?;
+    let Some(Host::Domain(host)) = url.host() else {
+        return None;
+    };
+    let port = url.port_or_known_default()?;
+    let addrs: Vec<IpAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .ok()?
+        .map(|addr| addr.ip())
+        .collect();
+    let (ipv4, ipv6): (Vec<IpAddr>, Vec<IpAddr>) = addrs.into_iter().partition(IpAddr::is_ipv4);
+    if ipv4.is_empty() || ipv6.is_empty() {
+        return None;
+    }
+    if !has_ipv6_route() {
+        debug!("No IPv6 route, skipping dual-stack check of {}", endpoint);
+        return None;
+    }
+
+    let samples = rate.samples.min(FAMILY_SAMPLES);
+    let interval = Duration::from_millis(rate.interval_ms);
+    Some(DualStackCheck {
+        ipv4: probe_family(&clients.ipv4, endpoint, ipv4, samples, interval).await,
+        ipv6: probe_family(&clients.ipv6, endpoint, ipv6, samples, interval).await,
+    })
+}
+
+async fn probe_family(
+    client: &reqwest::Client,
+    endpoint: &str,
+    mut addresses: Vec<IpAddr>,
+    samples: u32,
+    interval: Duration,
+) -> FamilyReachability {
+    let mut latencies = vec![];
+    let mut error = None;
+    for _ in 0..samples {
+        let start = Instant::now();
+        match client.head(endpoint).send().await {
+            Ok(_) => latencies.push(start.elapsed().as_millis() as u64),
+            Err(e) => error = Some(e.to_string()),
+        }
+        tokio::time::sleep(interval).await;
+    }
+
+    addresses.sort();
+    addresses.dedup();
+    let reachable = !latencies.is_empty();
+    FamilyReachability {
+        addresses: addresses.iter().map(IpAddr::to_string).collect(),
+        reachable,
+        latency: reachable.then(|| calculate_percentiles(&latencies)),
+        error: if reachable { None } else { error },
+    }
+}
+
+/// Whether the auditor itself can reach the IPv6 internet
+///
+/// Connecting a UDP socket only looks up a route; nothing is sent.
+fn has_ipv6_route() -> bool {
+    UdpSocket::bind((Ipv6Addr::UNSPECIFIED, 0))
+        .and_then(|socket| socket.connect(IPV6_ROUTE_PROBE))
+        .is_ok()
+}
+
+/// Warnings for a family that fails while the other works
+pub fn issues(service_name: &str, check: &DualStackCheck) -> Vec<Issue> {
+    let mut issues = vec![];
+    if check.ipv4.reachable && !check.ipv6.reachable {
+        issues.push(Issue::new(
+            Severity::Warning,
+            "IPV6_UNREACHABLE",
+            format!(
+                "{} endpoint publishes AAAA records ({}) but doesn't answer over IPv6",
+                service_name,
+                check.ipv6.addresses.join(", ")
+            ),
+        ));
+    }
+    if check.ipv6.reachable && !check.ipv4.reachable {
+        issues.push(Issue::new(
+            Severity::Warning,
+            "IPV4_UNREACHABLE",
+            format!(
+                "{} endpoint publishes A records ({}) but doesn't answer over IPv4",
+                service_name,
+                check.ipv4.addresses.join(", ")
+            ),
+        ));
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn family(address: &str, reachable: bool) -> FamilyReachability {
+        FamilyReachability {
+            addresses: vec![address.to_string()],
+            reachable,
+            latency: None,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_dual_stack_issues() {
+        let both = DualStackCheck {
+            ipv4: family("203.0.113.7", true),
+            ipv6: family("2001:db8::7", true),
+        };
+        assert!(issues("A2A", &both).is_empty());
+
+        let ipv4_only = DualStackCheck {
+            ipv6: family("2001:db8::7", false),
+            ..both
+        };
+        let found = issues("A2A", &ipv4_only);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].code, "IPV6_UNREACHABLE");
+        assert!(found[0].message.contains("2001:db8::7"));
+    }
+}
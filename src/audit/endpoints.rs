@@ -1,7 +1,19 @@
-use std::time::Instant;
+use sha2::{Digest, Sha256};
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
 use tracing::{debug, warn};
 
-use crate::types::{EndpointCheck, Issue, LatencyMetrics, Service, ServiceType, Severity};
+use super::policy::HostPolicy;
+use super::skills;
+use crate::canonical;
+use crate::http::{non_json_content_type, read_body_limited, BodyError, ResponseLimits};
+use crate::ssrf;
+use crate::types::{
+    Availability, Confidence, EndpointArtifact, EndpointCheck, Issue, LatencyMetrics,
+    RateLimitInfo, Service, ServiceType, Severity, SkillStatus, TransportInfo,
+};
 
 /// Test a service endpoint
 #[allow(dead_code)]
@@ -10,6 +22,8 @@ pub async fn test_endpoint(
     service_name: &str,
     endpoint: &str,
     service: &Service,
+    limits: &ResponseLimits,
+    rate: ProbeRate,
 ) -> EndpointCheck {
     debug!("Testing {} endpoint: {}", service_name, endpoint);
 
@@ -23,35 +37,58 @@ pub async fn test_endpoint(
         skills_match: None,
         latency: None,
         error: None,
+        not_probed: None,
+        host_policy: None,
+        skills: vec![],
+        transport: None,
+        tool_fingerprints: BTreeMap::new(),
+        content_hash: None,
+        availability: None,
+        rate_limit: None,
+        dual_stack: None,
+        websocket: None,
+        grpc: None,
         issues: vec![],
     };
 
     // Measure latency with multiple requests
-    let latencies = measure_latency(client, endpoint, LATENCY_SAMPLES).await;
-
-    if latencies.is_empty() {
-        check.error = Some("Connection failed".to_string());
-        check.issues.push(Issue {
-            severity: Severity::Critical,
-            code: "ENDPOINT_UNREACHABLE".to_string(),
-            message: format!("{} endpoint is not reachable", service_name),
-        });
+    let sampling = measure_latency(client, endpoint, rate).await;
+    check.rate_limit = sampling.rate_limit;
+    if let Some(reason) = sampling.blocked {
+        return not_probed(service_name, endpoint, &reason);
+    }
+
+    // A host that only answered 429 is up, just throttling us
+    if sampling.latencies.is_empty() && check.rate_limit.is_none() {
+        check.error = Some(format!(
+            "Connection failed: {}",
+            sampling.failure.unwrap_or("no response")
+        ));
+        check.issues.push(Issue::new(
+            Severity::Critical,
+            "ENDPOINT_UNREACHABLE",
+            format!("{} endpoint is not reachable", service_name),
+        ));
         return check;
     }
 
     check.reachable = true;
-    check.latency = Some(calculate_percentiles(&latencies));
+    if !sampling.latencies.is_empty() {
+        check.latency = Some(calculate_percentiles(&sampling.latencies));
+    }
+    record_transport(client, endpoint, &mut check).await;
 
     // Validate response based on service type
+    let max_bytes = limits.endpoint_json_bytes;
     match service_type {
         ServiceType::A2A => {
-            validate_a2a(client, endpoint, service, &mut check).await;
+            validate_a2a(client, endpoint, service, max_bytes, rate, &mut check).await;
         }
         ServiceType::MCP => {
-            validate_mcp(client, endpoint, service, &mut check).await;
+            validate_mcp(client, endpoint, service, max_bytes, rate, &mut check).await;
         }
         ServiceType::OASF => {
-            validate_oasf(client, endpoint, service, &mut check).await;
+            validate_oasf(client, endpoint, service, max_bytes, rate, &mut check).await;
         }
         ServiceType::Web => {
             // Web endpoints just need to be reachable with valid TLS
@@ -62,14 +99,18 @@ pub async fn test_endpoint(
         }
     }
 
+    if let Some(issue) = rate_limited_issue(service_name, check.rate_limit.as_ref()) {
+        check.issues.push(issue);
+    }
+
     // Check for high latency
     if let Some(latency) = &check.latency {
         if latency.p95 > 2000 {
-            check.issues.push(Issue {
-                severity: Severity::Warning,
-                code: "HIGH_LATENCY".to_string(),
-                message: format!("Endpoint p95 latency is {}ms (> 2000ms)", latency.p95),
-            });
+            check.issues.push(Issue::new(
+                Severity::Warning,
+                "HIGH_LATENCY",
+                format!("Endpoint p95 latency is {}ms (> 2000ms)", latency.p95),
+            ));
         }
     }
 
@@ -82,6 +123,8 @@ pub async fn test_endpoint_with_response(
     service_name: &str,
     endpoint: &str,
     service: &Service,
+    limits: &ResponseLimits,
+    rate: ProbeRate,
 ) -> (EndpointCheck, Option<serde_json::Value>) {
     debug!("Testing {} endpoint: {}", service_name, endpoint);
 
@@ -95,35 +138,58 @@ pub async fn test_endpoint_with_response(
         skills_match: None,
         latency: None,
         error: None,
+        not_probed: None,
+        host_policy: None,
+        skills: vec![],
+        transport: None,
+        tool_fingerprints: BTreeMap::new(),
+        content_hash: None,
+        availability: None,
+        rate_limit: None,
+        dual_stack: None,
+        websocket: None,
+        grpc: None,
         issues: vec![],
     };
 
     // Measure latency with multiple requests
-    let latencies = measure_latency(client, endpoint, LATENCY_SAMPLES).await;
-
-    if latencies.is_empty() {
-        check.error = Some("Connection failed".to_string());
-        check.issues.push(Issue {
-            severity: Severity::Critical,
-            code: "ENDPOINT_UNREACHABLE".to_string(),
-            message: format!("{} endpoint is not reachable", service_name),
-        });
+    let sampling = measure_latency(client, endpoint, rate).await;
+    check.rate_limit = sampling.rate_limit;
+    if let Some(reason) = sampling.blocked {
+        return (not_probed(service_name, endpoint, &reason), None);
+    }
+
+    // A host that only answered 429 is up, just throttling us
+    if sampling.latencies.is_empty() && check.rate_limit.is_none() {
+        check.error = Some(format!(
+            "Connection failed: {}",
+            sampling.failure.unwrap_or("no response")
+        ));
+        check.issues.push(Issue::new(
+            Severity::Critical,
+            "ENDPOINT_UNREACHABLE",
+            format!("{} endpoint is not reachable", service_name),
+        ));
         return (check, None);
     }
 
     check.reachable = true;
-    check.latency = Some(calculate_percentiles(&latencies));
+    if !sampling.latencies.is_empty() {
+        check.latency = Some(calculate_percentiles(&sampling.latencies));
+    }
+    record_transport(client, endpoint, &mut check).await;
 
     // Validate response based on service type and capture JSON
+    let max_bytes = limits.endpoint_json_bytes;
     let json_response = match service_type {
         ServiceType::A2A => {
-            validate_a2a_with_response(client, endpoint, service, &mut check).await
+            validate_a2a_with_response(client, endpoint, service, max_bytes, rate, &mut check).await
         }
         ServiceType::MCP => {
-            validate_mcp_with_response(client, endpoint, service, &mut check).await
+            validate_mcp_with_response(client, endpoint, service, max_bytes, rate, &mut check).await
         }
         ServiceType::OASF => {
-            validate_oasf_with_response(client, endpoint, service, &mut check).await
+            validate_oasf_with_response(client, endpoint, service, max_bytes, rate, &mut check).await
         }
         ServiceType::Web => {
             // Web endpoints just need to be reachable with valid TLS
@@ -133,50 +199,638 @@ pub async fn test_endpoint_with_response(
         _ => None,
     };
 
+    if let Some(issue) = rate_limited_issue(service_name, check.rate_limit.as_ref()) {
+        check.issues.push(issue);
+    }
+
     // Check for high latency
     if let Some(latency) = &check.latency {
         if latency.p95 > 2000 {
-            check.issues.push(Issue {
-                severity: Severity::Warning,
-                code: "HIGH_LATENCY".to_string(),
-                message: format!("Endpoint p95 latency is {}ms (> 2000ms)", latency.p95),
-            });
+            check.issues.push(Issue::new(
+                Severity::Warning,
+                "HIGH_LATENCY",
+                format!("Endpoint p95 latency is {}ms (> 2000ms)", latency.p95),
+            ));
         }
     }
 
     (check, json_response)
 }
 
+/// Record an endpoint that was skipped by the probe policy
+pub fn not_probed(service_name: &str, endpoint: &str, reason: &str) -> EndpointCheck {
+    skipped(
+        service_name,
+        endpoint,
+        reason,
+        Issue::new(
+            Severity::Info,
+            "ENDPOINT_NOT_PROBED",
+            format!("{} endpoint not probed by policy: {}", service_name, reason),
+        ),
+    )
+}
+
+/// Record an endpoint whose test didn't finish before the audit deadline
+pub fn deadline_exceeded(service_name: &str, endpoint: &str) -> EndpointCheck {
+    skipped(
+        service_name,
+        endpoint,
+        "audit deadline exceeded",
+        Issue::new(
+            Severity::Warning,
+            "AUDIT_DEADLINE_EXCEEDED",
+            format!(
+                "{} endpoint test didn't finish before the audit deadline",
+                service_name
+            ),
+        ),
+    )
+}
+
+/// Record how sure a probe's reachability verdict is
+///
+/// `earlier_errors` are the errors of failed attempts before the one `check`
+/// came from. An endpoint that only answered on retry gets an informational
+/// `ENDPOINT_TRANSIENT_FAILURE`; one that is down counts as confirmed when
+/// every attempt failed the same way.
+pub fn record_availability(check: &mut EndpointCheck, mut earlier_errors: Vec<String>) {
+    let attempts = earlier_errors.len() as u32 + 1;
+    let confidence = if check.reachable {
+        if let Some(error) = earlier_errors.first() {
+            check.issues.push(Issue::new(
+                Severity::Info,
+                "ENDPOINT_TRANSIENT_FAILURE",
+                format!(
+                    "{} endpoint failed once ({}) and answered on retry",
+                    check.service, error
+                ),
+            ));
+            Confidence::Medium
+        } else {
+            Confidence::High
+        }
+    } else {
+        earlier_errors.push(check.error.clone().unwrap_or_default());
+        let consistent = attempts > 1 && earlier_errors.windows(2).all(|w| w[0] == w[1]);
+        let confidence = if consistent {
+            Confidence::High
+        } else {
+            Confidence::Medium
+        };
+        for issue in &mut check.issues {
+            if issue.code == "ENDPOINT_UNREACHABLE" {
+                issue.message = format!(
+                    "{} after {} attempt{} ({} confidence)",
+                    issue.message,
+                    attempts,
+                    if attempts == 1 { "" } else { "s" },
+                    if consistent { "high" } else { "medium" }
+                );
+            }
+        }
+        confidence
+    };
+    check.availability = Some(Availability {
+        confidence,
+        attempts,
+        errors: earlier_errors,
+    });
+}
+
+fn skipped(service_name: &str, endpoint: &str, reason: &str, issue: Issue) -> EndpointCheck {
+    EndpointCheck {
+        service: service_name.to_string(),
+        endpoint: endpoint.to_string(),
+        reachable: false,
+        valid_schema: None,
+        skills_match: None,
+        latency: None,
+        error: None,
+        not_probed: Some(reason.to_string()),
+        host_policy: None,
+        skills: vec![],
+        transport: None,
+        tool_fingerprints: BTreeMap::new(),
+        content_hash: None,
+        availability: None,
+        rate_limit: None,
+        dual_stack: None,
+        websocket: None,
+        grpc: None,
+        issues: vec![issue],
+    }
+}
+
+/// Read an endpoint's JSON response, enforcing content type and size
+///
+/// Failures mark the schema invalid and record an issue. A response that is
+/// still throttled after backing off leaves the schema unjudged.
+async fn read_json(
+    response: reqwest::Response,
+    label: &str,
+    max_bytes: usize,
+    check: &mut EndpointCheck,
+) -> Option<serde_json::Value> {
+    if response.status() == StatusCode::TOO_MANY_REQUESTS {
+        debug!("{} endpoint still rate limited, schema not validated", label);
+        return None;
+    }
+
+    let result = match non_json_content_type(&response) {
+        Some(content_type) => Err(Issue::new(
+            Severity::Error,
+            "UNEXPECTED_CONTENT_TYPE",
+            format!(
+                "{} endpoint returned Content-Type '{}', expected JSON",
+                label, content_type
+            ),
+        )),
+        None => match read_body_limited(response, max_bytes).await {
+            Ok(body) => serde_json::from_slice(&body).map_err(|e| Issue::new(
+                Severity::Error,
+                "INVALID_JSON",
+                format!("{} endpoint returned invalid JSON: {}", label, e),
+            )),
+            Err(BodyError::TooLarge(limit)) => Err(Issue::new(
+                Severity::Error,
+                "RESPONSE_TOO_LARGE",
+                format!("{} endpoint response exceeds {} bytes", label, limit),
+            )),
+            Err(e) => Err(Issue::new(
+                Severity::Error,
+                "INVALID_JSON",
+                format!("{} endpoint returned invalid JSON: {}", label, e),
+            )),
+        },
+    };
+
+    match result {
+        Ok(json) => Some(json),
+        Err(issue) => {
+            check.valid_schema = Some(false);
+            check.issues.push(issue);
+            None
+        }
+    }
+}
+
+/// Probe HTTP version, compression and keep-alive, adding tuning hints
+async fn record_transport(client: &reqwest::Client, endpoint: &str, check: &mut EndpointCheck) {
+    let Some(transport) = probe_transport(client, endpoint).await else {
+        return;
+    };
+
+    if transport.http_version != "HTTP/2.0" {
+        check.issues.push(Issue::new(
+            Severity::Info,
+            "HTTP2_NOT_SUPPORTED",
+            format!(
+                "Endpoint negotiated {}; enabling HTTP/2 reduces connection overhead",
+                transport.http_version
+            ),
+        ));
+    }
+    if !transport.gzip && !transport.brotli {
+        check.issues.push(Issue::new(
+            Severity::Info,
+            "COMPRESSION_NOT_SUPPORTED",
+            "Endpoint doesn't compress responses (gzip or br)",
+        ));
+    }
+    if !transport.keep_alive {
+        check.issues.push(Issue::new(
+            Severity::Info,
+            "KEEP_ALIVE_DISABLED",
+            "Endpoint closes connections after each response",
+        ));
+    }
+
+    check.transport = Some(transport);
+}
+
+/// One request per encoding: the response shows which were accepted
+async fn probe_transport(client: &reqwest::Client, endpoint: &str) -> Option<TransportInfo> {
+    let encoded = |response: &reqwest::Response, encoding: &str| {
+        response
+            .headers()
+            .get(reqwest::header::CONTENT_ENCODING)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.eq_ignore_ascii_case(encoding))
+    };
+
+    let response = client
+        .get(endpoint)
+        .header(reqwest::header::ACCEPT_ENCODING, "gzip")
+        .send()
+        .await
+        .ok()?;
+    let version = response.version();
+    let connection = response
+        .headers()
+        .get(reqwest::header::CONNECTION)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_lowercase();
+    // HTTP/1.1+ is persistent unless closed; HTTP/1.0 only when asked
+    let keep_alive = if version == reqwest::Version::HTTP_10 {
+        connection.contains("keep-alive")
+    } else {
+        !connection.contains("close")
+    };
+    let gzip = encoded(&response, "gzip");
+
+    let brotli = match client
+        .get(endpoint)
+        .header(reqwest::header::ACCEPT_ENCODING, "br")
+        .send()
+        .await
+    {
+        Ok(response) => encoded(&response, "br"),
+        Err(_) => false,
+    };
+
+    Some(TransportInfo {
+        http_version: format!("{:?}", version),
+        gzip,
+        brotli,
+        keep_alive,
+    })
+}
+
+/// Transport score points for HTTP/2 (or 3), compression and keep-alive
+pub const HTTP2_POINTS: u8 = 40;
+pub const COMPRESSION_POINTS: u8 = 40;
+pub const KEEP_ALIVE_POINTS: u8 = 20;
+
+/// Share of the performance score from transport capabilities (0-100)
+pub fn transport_score(transport: &TransportInfo) -> u64 {
+    let mut score = 0;
+    if transport.http_version == "HTTP/2.0" || transport.http_version == "HTTP/3.0" {
+        score += HTTP2_POINTS as u64;
+    }
+    if transport.gzip || transport.brotli {
+        score += COMPRESSION_POINTS as u64;
+    }
+    if transport.keep_alive {
+        score += KEEP_ALIVE_POINTS as u64;
+    }
+    score
+}
+
+/// SHA-256 of each manifest tool's input schema, by tool name
+fn tool_fingerprints(manifest: &serde_json::Value) -> BTreeMap<String, String> {
+    let Some(tools) = manifest.get("tools").and_then(|v| v.as_array()) else {
+        return BTreeMap::new();
+    };
+
+    tools
+        .iter()
+        .filter_map(|tool| {
+            let name = tool.get("name")?.as_str()?;
+            let schema = tool
+                .get("inputSchema")
+                .or_else(|| tool.get("input_schema"))
+                .unwrap_or(&serde_json::Value::Null);
+            let digest = Sha256::digest(canonical::to_vec(schema));
+            Some((name.to_string(), hex::encode(digest)))
+        })
+        .collect()
+}
+
+/// Hash pinning an endpoint's response: SHA-256 of its canonical JSON (RFC 8785)
+///
+/// Anyone can refetch the endpoint, canonicalize the body with any JCS
+/// implementation and compare, regardless of key order or whitespace.
+pub fn content_hash(body: &serde_json::Value) -> String {
+    hash_canonical(&canonical::to_vec(body))
+}
+
+fn hash_canonical(canonical: &[u8]) -> String {
+    format!("0x{}", hex::encode(Sha256::digest(canonical)))
+}
+
+/// Keep an endpoint's response for `GET /audit/:id/artifacts`
+///
+/// The body is kept while it fits the audit's remaining `budget` bytes;
+/// past that only its hash and size are.
+pub fn artifact(
+    check: &EndpointCheck,
+    body: &serde_json::Value,
+    budget: &mut usize,
+) -> EndpointArtifact {
+    let canonical = canonical::to_vec(body);
+    let size_bytes = canonical.len();
+    let fits = size_bytes <= *budget;
+    if fits {
+        *budget -= size_bytes;
+    }
+    EndpointArtifact {
+        service: check.service.clone(),
+        endpoint: check.endpoint.clone(),
+        content_hash: hash_canonical(&canonical),
+        size_bytes,
+        body: fits.then(|| body.clone()),
+    }
+}
+
+/// Flag MCP tools whose input schema changed since a previous audit
+///
+/// Endpoints are matched by URL; tools added or removed since aren't flagged.
+pub fn flag_tool_drift(current: &mut [EndpointCheck], previous: &[EndpointCheck], since: &str) {
+    for check in current.iter_mut().filter(|c| !c.tool_fingerprints.is_empty()) {
+        let Some(before) = previous.iter().find(|p| p.endpoint == check.endpoint) else {
+            continue;
+        };
+
+        let drifted: Vec<String> = check
+            .tool_fingerprints
+            .iter()
+            .filter(|(tool, fingerprint)| {
+                before
+                    .tool_fingerprints
+                    .get(*tool)
+                    .is_some_and(|old| old != *fingerprint)
+            })
+            .map(|(tool, _)| tool.clone())
+            .collect();
+
+        for tool in drifted {
+            check.issues.push(Issue::new(
+                Severity::Info,
+                "MCP_TOOL_DRIFT",
+                format!(
+                    "MCP tool '{}' input schema changed since the audit of {}",
+                    tool, since
+                ),
+            ));
+        }
+    }
+}
+
 /// Timeout for HEAD requests in milliseconds
 const HEAD_REQUEST_TIMEOUT_MS: u64 = 10000;
 
 /// Number of samples for latency measurement (more samples = more accurate percentiles)
 const LATENCY_SAMPLES: u32 = 10;
 
-async fn measure_latency(client: &reqwest::Client, endpoint: &str, samples: u32) -> Vec<u64> {
-    let mut latencies = vec![];
+/// Delay between latency samples in milliseconds
+const LATENCY_SAMPLE_INTERVAL_MS: u64 = 50;
+
+/// Longest delay between samples a host policy can ask for
+const MAX_SAMPLE_INTERVAL_MS: u64 = 1000;
+
+/// How many latency samples to take and how far apart
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProbeRate {
+    pub samples: u32,
+    pub interval_ms: u64,
+    /// Audit deadline: a 429 asking to wait past it isn't waited out
+    pub deadline: Option<Instant>,
+}
+
+impl Default for ProbeRate {
+    fn default() -> Self {
+        Self {
+            samples: LATENCY_SAMPLES,
+            interval_ms: LATENCY_SAMPLE_INTERVAL_MS,
+            deadline: None,
+        }
+    }
+}
+
+impl ProbeRate {
+    /// Rate honoring a host's policy (never more aggressive than the default)
+    pub fn for_policy(policy: Option<&HostPolicy>) -> Self {
+        let default = Self::default();
+        let Some(policy) = policy else {
+            return default;
+        };
+
+        Self {
+            samples: policy
+                .max_samples
+                .map_or(default.samples, |max| max.clamp(1, default.samples)),
+            interval_ms: policy.min_interval_ms.map_or(default.interval_ms, |min| {
+                min.clamp(default.interval_ms, MAX_SAMPLE_INTERVAL_MS)
+            }),
+            deadline: None,
+        }
+    }
+
+    /// How long to back off after a 429, if it's short enough to wait out
+    /// (at most `MAX_RETRY_AFTER_SECS` and ending before the deadline)
+    fn backoff(&self, wait: Duration) -> Option<Duration> {
+        let fits = self
+            .deadline
+            .is_none_or(|deadline| Instant::now() + wait < deadline);
+        (wait.as_secs() <= MAX_RETRY_AFTER_SECS && fits).then_some(wait)
+    }
+}
+
+/// Longest `Retry-After` the prober waits out; longer ones end sampling
+const MAX_RETRY_AFTER_SECS: u64 = 5;
+
+/// 429s after which sampling stops
+const MAX_THROTTLED_SAMPLES: u32 = 3;
+
+/// Wait after a 429 without `Retry-After` or a reset time
+const DEFAULT_RETRY_AFTER_SECS: u64 = 1;
+
+/// Latency samples and the rate limits seen while taking them
+struct Sampling {
+    /// Latencies of non-throttled responses
+    latencies: Vec<u64>,
+    rate_limit: Option<RateLimitInfo>,
+    /// Kind of the last failed request
+    failure: Option<&'static str>,
+    /// Why the SSRF guard refused a request (e.g. a redirect to a private address)
+    blocked: Option<String>,
+}
+
+async fn measure_latency(client: &reqwest::Client, endpoint: &str, rate: ProbeRate) -> Sampling {
+    let mut sampling = Sampling {
+        latencies: vec![],
+        rate_limit: None,
+        failure: None,
+        blocked: None,
+    };
     let timeout = std::time::Duration::from_millis(HEAD_REQUEST_TIMEOUT_MS);
 
-    for _ in 0..samples {
+    for _ in 0..rate.samples {
         let start = Instant::now();
         let result = client
             .head(endpoint)
             .timeout(timeout)
             .send()
             .await;
+        let elapsed = start.elapsed().as_millis() as u64;
+
+        // Small delay between requests, longer when asked to back off
+        let mut interval = Duration::from_millis(rate.interval_ms);
+        match result {
+            Ok(response) => match observe_rate_limit(&mut sampling.rate_limit, &response) {
+                Some(wait) => {
+                    let throttled = sampling.rate_limit.as_ref().map_or(0, |r| r.throttled);
+                    match rate.backoff(wait) {
+                        Some(wait) if throttled < MAX_THROTTLED_SAMPLES => {
+                            interval = interval.max(wait)
+                        }
+                        _ => {
+                            debug!("{} keeps rate limiting probes, sampling stopped", endpoint);
+                            break;
+                        }
+                    }
+                }
+                None => sampling.latencies.push(elapsed),
+            },
+            Err(e) => {
+                // Asking again gets the same answer
+                if let Some(reason) = ssrf::blocked_reason(&e) {
+                    sampling.blocked = Some(reason);
+                    break;
+                }
+                sampling.failure = Some(failure_kind(&e));
+            }
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+
+    sampling
+}
+
+/// Coarse cause of a failed request, stable across attempts
+fn failure_kind(e: &reqwest::Error) -> &'static str {
+    if e.is_timeout() {
+        return "timed out";
+    }
+    let mut chain = e.to_string();
+    let mut source = std::error::Error::source(e);
+    while let Some(cause) = source {
+        chain.push_str(&format!(": {}", cause));
+        source = cause.source();
+    }
+    let chain = chain.to_lowercase();
+    if chain.contains("dns error") || chain.contains("failed to lookup address") {
+        "DNS resolution failed"
+    } else if chain.contains("certificate") || chain.contains("tls") || chain.contains("ssl") {
+        "TLS handshake failed"
+    } else if chain.contains("refused") {
+        "connection refused"
+    } else if e.is_connect() {
+        "connection failed"
+    } else {
+        "request failed"
+    }
+}
 
-        if result.is_ok() {
-            latencies.push(start.elapsed().as_millis() as u64);
+/// GET an endpoint, retrying once after a 429 if the wait is short enough
+async fn get_with_backoff(
+    client: &reqwest::Client,
+    endpoint: &str,
+    rate: ProbeRate,
+    check: &mut EndpointCheck,
+) -> reqwest::Result<reqwest::Response> {
+    let response = client.get(endpoint).send().await?;
+    let wait = observe_rate_limit(&mut check.rate_limit, &response);
+    match wait.and_then(|wait| rate.backoff(wait)) {
+        Some(wait) => {
+            debug!("{} rate limited, retrying in {:?}", endpoint, wait);
+            tokio::time::sleep(wait).await;
+            let response = client.get(endpoint).send().await?;
+            observe_rate_limit(&mut check.rate_limit, &response);
+            Ok(response)
         }
+        _ => Ok(response),
+    }
+}
 
-        // Small delay between requests
-        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+/// Record a response's rate-limit headers
+///
+/// Returns how long to back off if the response was a 429.
+fn observe_rate_limit(
+    rate_limit: &mut Option<RateLimitInfo>,
+    response: &reqwest::Response,
+) -> Option<Duration> {
+    let headers = response.headers();
+    let throttled = response.status() == StatusCode::TOO_MANY_REQUESTS;
+    let now = chrono::Utc::now().timestamp();
+    let limit = header_u64(headers, &["ratelimit-limit", "x-ratelimit-limit"]);
+    let remaining = header_u64(headers, &["ratelimit-remaining", "x-ratelimit-remaining"]);
+    let reset = reset_secs(headers, now);
+    let retry_after = if throttled {
+        retry_after_secs(headers, now)
+    } else {
+        None
+    };
+
+    if !throttled && limit.is_none() && remaining.is_none() && reset.is_none() {
+        return None;
     }
 
-    latencies
+    let info = rate_limit.get_or_insert_with(RateLimitInfo::default);
+    info.limit = limit.or(info.limit);
+    info.remaining = remaining.or(info.remaining);
+    info.reset_secs = reset.or(info.reset_secs);
+    if let Some(secs) = retry_after {
+        info.retry_after_secs = Some(info.retry_after_secs.map_or(secs, |prev| prev.max(secs)));
+    }
+    if !throttled {
+        return None;
+    }
+    info.throttled += 1;
+    let secs = retry_after.or(reset).unwrap_or(DEFAULT_RETRY_AFTER_SECS);
+    Some(Duration::from_secs(secs))
 }
 
-fn calculate_percentiles(latencies: &[u64]) -> LatencyMetrics {
+/// First numeric value of the first present header
+///
+/// Draft `RateLimit-Limit` values may carry a policy (`100, 100;w=60`).
+fn header_u64(headers: &HeaderMap, names: &[&str]) -> Option<u64> {
+    names.iter().find_map(|name| {
+        let value = headers.get(*name)?.to_str().ok()?;
+        value.split([',', ';']).next()?.trim().parse().ok()
+    })
+}
+
+/// Seconds until the rate-limit window resets
+///
+/// `X-RateLimit-Reset` is a Unix timestamp on some APIs (e.g. GitHub) and a
+/// delta on others; values past 2001 are taken as timestamps.
+fn reset_secs(headers: &HeaderMap, now: i64) -> Option<u64> {
+    let value = header_u64(headers, &["ratelimit-reset", "x-ratelimit-reset"])?;
+    if value > 1_000_000_000 {
+        Some((value as i64 - now).max(0) as u64)
+    } else {
+        Some(value)
+    }
+}
+
+/// `Retry-After` in seconds, given as a delay or an HTTP date
+fn retry_after_secs(headers: &HeaderMap, now: i64) -> Option<u64> {
+    let value = headers.get("retry-after")?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse() {
+        return Some(secs);
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    Some((date.timestamp() - now).max(0) as u64)
+}
+
+/// Info issue for an endpoint that throttled probes
+fn rate_limited_issue(service_name: &str, rate_limit: Option<&RateLimitInfo>) -> Option<Issue> {
+    let rate_limit = rate_limit.filter(|r| r.throttled > 0)?;
+    let mut message = format!(
+        "{} endpoint answered {} probe(s) with 429 Too Many Requests",
+        service_name, rate_limit.throttled
+    );
+    if let Some(secs) = rate_limit.retry_after_secs {
+        message.push_str(&format!(" (Retry-After {}s)", secs));
+    }
+    Some(Issue::new(Severity::Info, "ENDPOINT_RATE_LIMITED", message))
+}
+
+pub(crate) fn calculate_percentiles(latencies: &[u64]) -> LatencyMetrics {
     let mut sorted = latencies.to_vec();
     sorted.sort();
 
@@ -201,33 +855,26 @@ async fn validate_a2a(
     client: &reqwest::Client,
     endpoint: &str,
     service: &Service,
+    max_bytes: usize,
+    rate: ProbeRate,
     check: &mut EndpointCheck,
 ) {
     // Fetch and validate A2A agent card
-    let response = match client.get(endpoint).send().await {
+    let response = match get_with_backoff(client, endpoint, rate, check).await {
         Ok(r) => r,
         Err(e) => {
             check.valid_schema = Some(false);
-            check.issues.push(Issue {
-                severity: Severity::Error,
-                code: "A2A_FETCH_FAILED".to_string(),
-                message: format!("Failed to fetch A2A agent card: {}", e),
-            });
+            check.issues.push(Issue::new(
+                Severity::Error,
+                "A2A_FETCH_FAILED",
+                format!("Failed to fetch A2A agent card: {}", e),
+            ));
             return;
         }
     };
 
-    let json: serde_json::Value = match response.json().await {
-        Ok(j) => j,
-        Err(e) => {
-            check.valid_schema = Some(false);
-            check.issues.push(Issue {
-                severity: Severity::Error,
-                code: "INVALID_JSON".to_string(),
-                message: format!("A2A endpoint returned invalid JSON: {}", e),
-            });
-            return;
-        }
+    let Some(json) = read_json(response, "A2A", max_bytes, check).await else {
+        return;
     };
 
     // Basic A2A schema validation
@@ -237,88 +884,38 @@ async fn validate_a2a(
     check.valid_schema = Some(has_name && has_skills);
 
     if !has_name {
-        check.issues.push(Issue {
-            severity: Severity::Error,
-            code: "A2A_MISSING_NAME".to_string(),
-            message: "A2A agent card missing 'name' field".to_string(),
-        });
+        check.issues.push(Issue::new(
+            Severity::Error,
+            "A2A_MISSING_NAME",
+            "A2A agent card missing 'name' field",
+        ));
     }
 
-    // Check if declared skills match
+    // Compare declared skills against the card's, by id and name
     if !service.a2a_skills.is_empty() {
-        if let Some(skills) = json.get("skills").and_then(|v| v.as_array()) {
-            let actual_skills: Vec<String> = skills
+        if let Some(card_skills) = skills::parse_card_skills(&json) {
+            check.skills = skills::compare_skills(&service.a2a_skills, &card_skills);
+            let missing: Vec<&str> = check
+                .skills
                 .iter()
-                .filter_map(|s| {
-                    // Skills can be objects with "id" field or plain strings
-                    s.get("id")
-                        .and_then(|v| v.as_str())
-                        .or_else(|| s.as_str())
-                        .map(|s| s.to_string())
-                })
+                .filter(|skill| skill.status == SkillStatus::DeclaredOnly)
+                .filter_map(|skill| skill.declared.as_deref())
                 .collect();
 
-            let declared_present = service
-                .a2a_skills
-                .iter()
-                .all(|declared| skills_match_strict(declared, &actual_skills));
-
-            check.skills_match = Some(declared_present);
-
-            if !declared_present {
-                check.issues.push(Issue {
-                    severity: Severity::Warning,
-                    code: "A2A_SKILLS_MISMATCH".to_string(),
-                    message: "Declared A2A skills don't match agent card".to_string(),
-                });
-            }
-        }
-    }
-}
-
-/// Strict skill matching for A2A skills
-/// Matches if:
-/// - Exact match (case-insensitive)
-/// - OASF taxonomy path match: "domain/subdomain/skill" matches "skill" or "subdomain/skill"
-fn skills_match_strict(declared: &str, actual_skills: &[String]) -> bool {
-    let declared_lower = declared.to_lowercase();
-
-    for actual in actual_skills {
-        let actual_lower = actual.to_lowercase();
-
-        // Exact match
-        if declared_lower == actual_lower {
-            return true;
-        }
-
-        // OASF taxonomy path matching
-        // declared: "agent_orchestration/task_delegation" should match actual: "task_delegation"
-        // declared: "task_delegation" should match actual: "agent_orchestration/task_delegation"
-        let declared_segments: Vec<&str> = declared_lower.split('/').collect();
-        let actual_segments: Vec<&str> = actual_lower.split('/').collect();
-
-        // Check if the last segment(s) match
-        if let Some(declared_last) = declared_segments.last() {
-            if let Some(actual_last) = actual_segments.last() {
-                if declared_last == actual_last {
-                    return true;
-                }
-            }
-        }
-
-        // Check if declared is a suffix of actual or vice versa
-        if declared_segments.len() > 1 && actual_segments.len() > 1 {
-            // Compare from the end
-            let min_len = declared_segments.len().min(actual_segments.len());
-            let declared_suffix: Vec<&str> = declared_segments.iter().rev().take(min_len).cloned().collect();
-            let actual_suffix: Vec<&str> = actual_segments.iter().rev().take(min_len).cloned().collect();
-            if declared_suffix == actual_suffix {
-                return true;
+            check.skills_match = Some(missing.is_empty());
+
+            if !missing.is_empty() {
+                check.issues.push(Issue::new(
+                    Severity::Warning,
+                    "A2A_SKILLS_MISMATCH",
+                    format!(
+                        "Declared A2A skills not in agent card: {}",
+                        missing.join(", ")
+                    ),
+                ));
             }
         }
     }
-
-    false
 }
 
 #[allow(dead_code)]
@@ -326,9 +923,11 @@ async fn validate_mcp(
     client: &reqwest::Client,
     endpoint: &str,
     service: &Service,
+    max_bytes: usize,
+    rate: ProbeRate,
     check: &mut EndpointCheck,
 ) {
-    let response = match client.get(endpoint).send().await {
+    let response = match get_with_backoff(client, endpoint, rate, check).await {
         Ok(r) => r,
         Err(e) => {
             check.valid_schema = Some(false);
@@ -337,23 +936,19 @@ async fn validate_mcp(
         }
     };
 
-    let json: serde_json::Value = match response.json().await {
-        Ok(j) => j,
-        Err(e) => {
-            check.valid_schema = Some(false);
-            check.issues.push(Issue {
-                severity: Severity::Error,
-                code: "INVALID_JSON".to_string(),
-                message: format!("MCP endpoint returned invalid JSON: {}", e),
-            });
-            return;
-        }
+    let Some(json) = read_json(response, "MCP", max_bytes, check).await else {
+        return;
     };
 
     // Basic MCP schema validation
     let has_tools = json.get("tools").is_some();
     check.valid_schema = Some(has_tools);
 
+    // Fingerprint input schemas so later audits can detect drift
+    if !service.mcp_tools.is_empty() {
+        check.tool_fingerprints = tool_fingerprints(&json);
+    }
+
     // Check if declared tools match
     if !service.mcp_tools.is_empty() {
         if let Some(tools) = json.get("tools").and_then(|v| v.as_array()) {
@@ -370,11 +965,11 @@ async fn validate_mcp(
             check.skills_match = Some(declared_present);
 
             if !declared_present {
-                check.issues.push(Issue {
-                    severity: Severity::Warning,
-                    code: "MCP_TOOLS_MISMATCH".to_string(),
-                    message: "Declared MCP tools don't match manifest".to_string(),
-                });
+                check.issues.push(Issue::new(
+                    Severity::Warning,
+                    "MCP_TOOLS_MISMATCH",
+                    "Declared MCP tools don't match manifest",
+                ));
             }
         }
     }
@@ -385,9 +980,11 @@ async fn validate_oasf(
     client: &reqwest::Client,
     endpoint: &str,
     _service: &Service,
+    max_bytes: usize,
+    rate: ProbeRate,
     check: &mut EndpointCheck,
 ) {
-    let response = match client.get(endpoint).send().await {
+    let response = match get_with_backoff(client, endpoint, rate, check).await {
         Ok(r) => r,
         Err(e) => {
             check.valid_schema = Some(false);
@@ -396,17 +993,8 @@ async fn validate_oasf(
         }
     };
 
-    let json: serde_json::Value = match response.json().await {
-        Ok(j) => j,
-        Err(e) => {
-            check.valid_schema = Some(false);
-            check.issues.push(Issue {
-                severity: Severity::Error,
-                code: "INVALID_JSON".to_string(),
-                message: format!("OASF endpoint returned invalid JSON: {}", e),
-            });
-            return;
-        }
+    let Some(json) = read_json(response, "OASF", max_bytes, check).await else {
+        return;
     };
 
     // OASF validation - check for skills/domains
@@ -420,34 +1008,34 @@ async fn validate_a2a_with_response(
     client: &reqwest::Client,
     endpoint: &str,
     service: &Service,
+    max_bytes: usize,
+    rate: ProbeRate,
     check: &mut EndpointCheck,
 ) -> Option<serde_json::Value> {
-    let response = match client.get(endpoint).send().await {
+    let response = match get_with_backoff(client, endpoint, rate, check).await {
         Ok(r) => r,
         Err(e) => {
             check.valid_schema = Some(false);
-            check.issues.push(Issue {
-                severity: Severity::Error,
-                code: "A2A_FETCH_FAILED".to_string(),
-                message: format!("Failed to fetch A2A agent card: {}", e),
-            });
+            check.issues.push(Issue::new(
+                Severity::Error,
+                "A2A_FETCH_FAILED",
+                format!("Failed to fetch A2A agent card: {}", e),
+            ));
             return None;
         }
     };
 
-    let json: serde_json::Value = match response.json().await {
-        Ok(j) => j,
-        Err(e) => {
-            check.valid_schema = Some(false);
-            check.issues.push(Issue {
-                severity: Severity::Error,
-                code: "INVALID_JSON".to_string(),
-                message: format!("A2A endpoint returned invalid JSON: {}", e),
-            });
-            return None;
-        }
-    };
+    let json = read_json(response, "A2A", max_bytes, check).await?;
+    check_a2a_card(&json, service, check);
+    Some(json)
+}
 
+/// Validate an A2A agent card and compare its skills with the declared ones
+pub(crate) fn check_a2a_card(
+    json: &serde_json::Value,
+    service: &Service,
+    check: &mut EndpointCheck,
+) {
     // Basic A2A schema validation
     let has_name = json.get("name").and_then(|v| v.as_str()).is_some();
     let has_skills = json.get("skills").is_some() || json.get("capabilities").is_some();
@@ -455,54 +1043,49 @@ async fn validate_a2a_with_response(
     check.valid_schema = Some(has_name && has_skills);
 
     if !has_name {
-        check.issues.push(Issue {
-            severity: Severity::Error,
-            code: "A2A_MISSING_NAME".to_string(),
-            message: "A2A agent card missing 'name' field".to_string(),
-        });
+        check.issues.push(Issue::new(
+            Severity::Error,
+            "A2A_MISSING_NAME",
+            "A2A agent card missing 'name' field",
+        ));
     }
 
-    // Check if declared skills match
+    // Compare declared skills against the card's, by id and name
     if !service.a2a_skills.is_empty() {
-        if let Some(skills) = json.get("skills").and_then(|v| v.as_array()) {
-            let actual_skills: Vec<String> = skills
+        if let Some(card_skills) = skills::parse_card_skills(json) {
+            check.skills = skills::compare_skills(&service.a2a_skills, &card_skills);
+            let missing: Vec<&str> = check
+                .skills
                 .iter()
-                .filter_map(|s| {
-                    // Skills can be objects with "id" field or plain strings
-                    s.get("id")
-                        .and_then(|v| v.as_str())
-                        .or_else(|| s.as_str())
-                        .map(|s| s.to_string())
-                })
+                .filter(|skill| skill.status == SkillStatus::DeclaredOnly)
+                .filter_map(|skill| skill.declared.as_deref())
                 .collect();
 
-            let declared_present = service
-                .a2a_skills
-                .iter()
-                .all(|declared| skills_match_strict(declared, &actual_skills));
-
-            check.skills_match = Some(declared_present);
-
-            if !declared_present {
-                check.issues.push(Issue {
-                    severity: Severity::Warning,
-                    code: "A2A_SKILLS_MISMATCH".to_string(),
-                    message: "Declared A2A skills don't match agent card".to_string(),
-                });
+            check.skills_match = Some(missing.is_empty());
+
+            if !missing.is_empty() {
+                check.issues.push(Issue::new(
+                    Severity::Warning,
+                    "A2A_SKILLS_MISMATCH",
+                    format!(
+                        "Declared A2A skills not in agent card: {}",
+                        missing.join(", ")
+                    ),
+                ));
             }
         }
     }
-
-    Some(json)
 }
 
 async fn validate_mcp_with_response(
     client: &reqwest::Client,
     endpoint: &str,
     service: &Service,
+    max_bytes: usize,
+    rate: ProbeRate,
     check: &mut EndpointCheck,
 ) -> Option<serde_json::Value> {
-    let response = match client.get(endpoint).send().await {
+    let response = match get_with_backoff(client, endpoint, rate, check).await {
         Ok(r) => r,
         Err(e) => {
             check.valid_schema = Some(false);
@@ -511,23 +1094,17 @@ async fn validate_mcp_with_response(
         }
     };
 
-    let json: serde_json::Value = match response.json().await {
-        Ok(j) => j,
-        Err(e) => {
-            check.valid_schema = Some(false);
-            check.issues.push(Issue {
-                severity: Severity::Error,
-                code: "INVALID_JSON".to_string(),
-                message: format!("MCP endpoint returned invalid JSON: {}", e),
-            });
-            return None;
-        }
-    };
+    let json = read_json(response, "MCP", max_bytes, check).await?;
 
     // Basic MCP schema validation
     let has_tools = json.get("tools").is_some();
     check.valid_schema = Some(has_tools);
 
+    // Fingerprint input schemas so later audits can detect drift
+    if !service.mcp_tools.is_empty() {
+        check.tool_fingerprints = tool_fingerprints(&json);
+    }
+
     // Check if declared tools match
     if !service.mcp_tools.is_empty() {
         if let Some(tools) = json.get("tools").and_then(|v| v.as_array()) {
@@ -544,11 +1121,11 @@ async fn validate_mcp_with_response(
             check.skills_match = Some(declared_present);
 
             if !declared_present {
-                check.issues.push(Issue {
-                    severity: Severity::Warning,
-                    code: "MCP_TOOLS_MISMATCH".to_string(),
-                    message: "Declared MCP tools don't match manifest".to_string(),
-                });
+                check.issues.push(Issue::new(
+                    Severity::Warning,
+                    "MCP_TOOLS_MISMATCH",
+                    "Declared MCP tools don't match manifest",
+                ));
             }
         }
     }
@@ -560,9 +1137,11 @@ async fn validate_oasf_with_response(
     client: &reqwest::Client,
     endpoint: &str,
     _service: &Service,
+    max_bytes: usize,
+    rate: ProbeRate,
     check: &mut EndpointCheck,
 ) -> Option<serde_json::Value> {
-    let response = match client.get(endpoint).send().await {
+    let response = match get_with_backoff(client, endpoint, rate, check).await {
         Ok(r) => r,
         Err(e) => {
             check.valid_schema = Some(false);
@@ -571,18 +1150,7 @@ async fn validate_oasf_with_response(
         }
     };
 
-    let json: serde_json::Value = match response.json().await {
-        Ok(j) => j,
-        Err(e) => {
-            check.valid_schema = Some(false);
-            check.issues.push(Issue {
-                severity: Severity::Error,
-                code: "INVALID_JSON".to_string(),
-                message: format!("OASF endpoint returned invalid JSON: {}", e),
-            });
-            return None;
-        }
-    };
+    let json = read_json(response, "OASF", max_bytes, check).await?;
 
     // OASF validation - check for skills/domains
     let has_structure = json.get("skills").is_some() || json.get("domains").is_some();
@@ -590,3 +1158,190 @@ async fn validate_oasf_with_response(
 
     Some(json)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mcp_check(fingerprints: BTreeMap<String, String>) -> EndpointCheck {
+        let mut check = not_probed("MCP", "https://agent.example.com/mcp", "test");
+        check.tool_fingerprints = fingerprints;
+        check
+    }
+
+    #[test]
+    fn test_tool_drift() {
+        let before = tool_fingerprints(&serde_json::json!({
+            "tools": [
+                { "name": "search", "inputSchema": { "type": "object", "required": ["q"] } },
+                { "name": "fetch", "inputSchema": { "type": "object" } }
+            ]
+        }));
+        // Key order doesn't change the fingerprint, a new field does
+        let after = tool_fingerprints(&serde_json::json!({
+            "tools": [
+                { "name": "search", "inputSchema": { "required": ["q"], "type": "object" } },
+                { "name": "fetch", "inputSchema": { "type": "object", "required": ["url"] } },
+                { "name": "new_tool" }
+            ]
+        }));
+        assert_eq!(before["search"], after["search"]);
+        assert_ne!(before["fetch"], after["fetch"]);
+
+        let mut current = vec![mcp_check(after)];
+        flag_tool_drift(&mut current, &[mcp_check(before)], "2026-01-01T00:00:00Z");
+        let drift: Vec<&Issue> = current[0]
+            .issues
+            .iter()
+            .filter(|i| i.code == "MCP_TOOL_DRIFT")
+            .collect();
+        assert_eq!(drift.len(), 1);
+        assert!(drift[0].message.contains("'fetch'"));
+    }
+
+    #[test]
+    fn test_record_availability() {
+        let unreachable = |error: &str| {
+            let mut check = not_probed("A2A", "https://agent.example.com/a2a", "test");
+            check.not_probed = None;
+            check.error = Some(error.to_string());
+            check.issues = vec![Issue::new(
+                Severity::Critical,
+                "ENDPOINT_UNREACHABLE",
+                "A2A endpoint is not reachable",
+            )];
+            check
+        };
+
+        // Same failure twice: confirmed down
+        let mut check = unreachable("Connection failed: connection refused");
+        record_availability(&mut check, vec!["Connection failed: connection refused".to_string()]);
+        let availability = check.availability.as_ref().unwrap();
+        assert_eq!((availability.attempts, availability.confidence), (2, Confidence::High));
+        assert!(check.issues[0].message.ends_with("after 2 attempts (high confidence)"));
+
+        // Different failures: down, but less certain
+        let mut check = unreachable("Connection failed: timed out");
+        record_availability(&mut check, vec!["Connection failed: DNS resolution failed".into()]);
+        assert_eq!(check.availability.unwrap().confidence, Confidence::Medium);
+
+        // Recovered on retry
+        let mut check = not_probed("A2A", "https://agent.example.com/a2a", "test");
+        check.reachable = true;
+        check.issues.clear();
+        record_availability(&mut check, vec!["Connection failed: DNS resolution failed".into()]);
+        assert_eq!(check.availability.unwrap().confidence, Confidence::Medium);
+        assert_eq!(check.issues[0].code, "ENDPOINT_TRANSIENT_FAILURE");
+    }
+
+    #[test]
+    fn test_content_hash() {
+        // Key order and whitespace don't change the hash, content does
+        let card: serde_json::Value =
+            serde_json::from_str(r#"{ "name": "Agent", "skills": [{ "id": "chat" }] }"#).unwrap();
+        let reordered: serde_json::Value =
+            serde_json::from_str(r#"{"skills":[{"id":"chat"}],"name":"Agent"}"#).unwrap();
+        assert_eq!(content_hash(&card), content_hash(&reordered));
+        assert_ne!(content_hash(&card), content_hash(&serde_json::json!({ "name": "Agent" })));
+        assert_eq!(
+            content_hash(&serde_json::json!({})),
+            "0x44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a"
+        );
+
+        // Artifacts keep bodies until the budget runs out, hashes always
+        let check = not_probed("A2A", "https://agent.example.com/a2a", "test");
+        let mut budget = 60;
+        let kept = artifact(&check, &card, &mut budget);
+        assert_eq!(kept.body.as_ref(), Some(&card));
+        assert_eq!(kept.content_hash, content_hash(&card));
+        assert_eq!(budget, 60 - kept.size_bytes);
+        let dropped = artifact(&check, &card, &mut budget);
+        assert!(dropped.body.is_none());
+        assert_eq!(dropped.content_hash, kept.content_hash);
+    }
+
+    #[test]
+    fn test_rate_limit_headers() {
+        let now = 1_700_000_000;
+        let mut headers = HeaderMap::new();
+        headers.insert("ratelimit-limit", "100, 100;w=60".parse().unwrap());
+        headers.insert("x-ratelimit-remaining", "0".parse().unwrap());
+        headers.insert("x-ratelimit-reset", "1700000030".parse().unwrap());
+        headers.insert("retry-after", "7".parse().unwrap());
+
+        assert_eq!(header_u64(&headers, &["ratelimit-limit", "x-ratelimit-limit"]), Some(100));
+        assert_eq!(header_u64(&headers, &["ratelimit-remaining", "x-ratelimit-remaining"]), Some(0));
+        assert_eq!(reset_secs(&headers, now), Some(30));
+        assert_eq!(retry_after_secs(&headers, now), Some(7));
+
+        // HTTP-date form
+        headers.insert("retry-after", "Tue, 14 Nov 2023 22:13:40 GMT".parse().unwrap());
+        assert_eq!(retry_after_secs(&headers, now), Some(20));
+
+        assert!(rate_limited_issue("MCP", Some(&RateLimitInfo::default())).is_none());
+    }
+
+    #[test]
+    fn test_backoff_capped_at_deadline() {
+        let wait = Duration::from_secs(3);
+        assert_eq!(ProbeRate::default().backoff(wait), Some(wait));
+        assert_eq!(ProbeRate::default().backoff(Duration::from_secs(60)), None);
+
+        let rate = ProbeRate {
+            deadline: Some(Instant::now() + Duration::from_secs(1)),
+            ..Default::default()
+        };
+        assert_eq!(rate.backoff(wait), None);
+        assert_eq!(rate.backoff(Duration::ZERO), Some(Duration::ZERO));
+    }
+
+    #[tokio::test]
+    async fn test_guard_rejection_not_retried() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .respond_with(
+                ResponseTemplate::new(302).insert_header("Location", "http://169.254.169.254/"),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        let guard = std::sync::Arc::new(ssrf::OutboundGuard::default());
+        let client = reqwest::Client::builder()
+            .redirect(ssrf::redirect_policy(guard, 5))
+            .build()
+            .unwrap();
+        let service: Service = serde_json::from_value(serde_json::json!({ "name": "MCP" })).unwrap();
+
+        let (check, response) = test_endpoint_with_response(
+            &client,
+            "MCP",
+            &server.uri(),
+            &service,
+            &ResponseLimits::default(),
+            ProbeRate::default(),
+        )
+        .await;
+        assert!(response.is_none());
+        assert!(!check.reachable);
+        assert!(check.not_probed.unwrap().contains("169.254.169.254"));
+        assert_eq!(check.issues[0].code, "ENDPOINT_NOT_PROBED");
+    }
+
+    #[test]
+    fn test_transport_score() {
+        let mut transport = TransportInfo {
+            http_version: "HTTP/2.0".to_string(),
+            gzip: false,
+            brotli: true,
+            keep_alive: true,
+        };
+        assert_eq!(transport_score(&transport), 100);
+
+        transport.http_version = "HTTP/1.1".to_string();
+        transport.brotli = false;
+        assert_eq!(transport_score(&transport), 20);
+    }
+}
@@ -1,68 +1,146 @@
+use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
 use tracing::{debug, info, warn};
 
-use crate::chains::{get_chain, ChainType};
+use crate::caip::AccountId;
+use crate::chains::{chain_profile, get_chain, ChainConfig, ChainType};
+use crate::events::{EventKind, EventLog};
+use crate::reload::LiveSettings;
 use crate::types::{
-    AgentMetadata, AuditReport, AuditRequest, CheckResult, Issue,
-    RecommendedFieldsCheck, Severity, WatchyError,
+    AgentHistory, AgentMetadata, AuditReport, AuditRequest, CheckResult, CredentialChecks,
+    EndpointArtifact, Issue, PhaseOutcome, RecommendedFieldsCheck, RegistrationCheck,
+    RegistrationStatus, ReputationCheck, Service, Severity, TrustChecks, TrustProfile, WatchyError,
 };
+use crate::wallet::SignerKey;
 use crate::AppState;
 
 use super::consistency::{self, EndpointResponses};
-use super::{content, endpoints, metadata, onchain, security};
+use super::policy::{self, HostPolicy, HOST_POLICY_PATH};
+use super::reputation::ReputationProvider;
+use super::scoring::ScoreWeights;
+use super::{
+    catalog, content, credentials, dual_stack, endpoints, grpc, metadata, onchain, owner_risk,
+    secrets, security, source_code, trust, websocket, x402,
+};
 
 pub struct AuditEngine {
     state: Arc<AppState>,
+    events: EventLog,
+    /// Host policies fetched during this audit, by origin
+    host_policies: Mutex<HashMap<String, Option<HostPolicy>>>,
+    /// Metadata the last audit ran against
+    audited_metadata: Mutex<Option<AgentMetadata>>,
+    /// Endpoint responses the last audit judged, if kept
+    artifacts: Mutex<Vec<EndpointArtifact>>,
+    /// Tenant the audit runs for, whose previous audits it compares against
+    tenant: Option<String>,
+    /// Live settings when the audit started (see `reload`)
+    settings: Arc<LiveSettings>,
+    /// Signer the report is attributed to (the primary unless `with_signer`)
+    signer: Option<SignerKey>,
+    /// Metadata URI of an agent that isn't registered yet (see `with_metadata_uri`)
+    metadata_uri: Option<String>,
 }
 
 impl AuditEngine {
     pub fn new(state: Arc<AppState>) -> Self {
-        Self { state }
+        Self {
+            events: EventLog::disabled(),
+            host_policies: Mutex::new(HashMap::new()),
+            audited_metadata: Mutex::new(None),
+            artifacts: Mutex::new(Vec::new()),
+            tenant: None,
+            signer: state.config.wallet.signers.first().cloned(),
+            metadata_uri: None,
+            settings: state.live.get(),
+            state,
+        }
+    }
+
+    pub fn with_tenant(mut self, tenant: Option<String>) -> Self {
+        self.tenant = tenant;
+        self
+    }
+
+    /// Attribute the report to a signer picked by the rotation policy (`None` = unsigned)
+    pub fn with_signer(mut self, signer: Option<SignerKey>) -> Self {
+        self.signer = signer;
+        self
+    }
+
+    /// Record phase, failover and fallback events to the given log
+    pub fn with_events(mut self, events: EventLog) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Audit an agent that isn't registered yet from its metadata URI; the
+    /// on-chain phase and the checks that need a registration are skipped
+    pub fn with_metadata_uri(mut self, metadata_uri: Option<String>) -> Self {
+        self.metadata_uri = metadata_uri;
+        self
     }
 
     /// Run a full audit for an agent
     pub async fn run_audit(&self, request: &AuditRequest) -> Result<AuditReport, WatchyError> {
         // Resolve chain_id
         let chain_id = request.chain_id.unwrap_or(self.state.config.default_chain_id);
+        let (chain, registry_address) =
+            audited_chain(chain_id).map_err(|e| e.in_phase("setup"))?;
 
-        // Get chain config
-        let chain = get_chain(chain_id).ok_or_else(|| {
-            WatchyError::InvalidRequest(format!("Unsupported chain_id: {}", chain_id))
-        })?;
+        let registry_full = AccountId::eip155_string(chain_id, registry_address);
 
-        // Validate chain type
-        if chain.chain_type != ChainType::Evm {
-            return Err(WatchyError::InvalidRequest(format!(
-                "Chain {} is not an EVM chain",
-                chain.name
-            )));
-        }
-
-        // Get registry address
-        let registry_address = chain.registry_address.ok_or_else(|| {
-            WatchyError::InvalidRequest(format!(
-                "No registry deployed on {} (chain_id: {})",
-                chain.name, chain_id
-            ))
-        })?;
-
-        let registry_full = format!("eip155:{}:{}", chain_id, registry_address);
+        // Every phase runs against what's left of one overall deadline
+        let timeout_secs = self.state.config.audit_timeout(request.timeout_secs);
+        let deadline = Instant::now() + Duration::from_secs(timeout_secs);
 
         info!(
-            "Starting audit for agent {} on {} ({})",
-            request.agent_id, chain.name, registry_full
+            "Starting audit for agent {} on {} ({}), deadline {}s",
+            request.agent_id, chain.name, registry_full, timeout_secs
         );
 
-        // Phase 1: Fetch on-chain data
-        let onchain_data = onchain::fetch_onchain_data(
-            chain_id,
-            request.agent_id,
-            registry_address,
-        )
-        .await?;
+        // Phase 1: Fetch on-chain data (nothing to fetch for an unregistered agent)
+        let registered = self.metadata_uri.is_none();
+        let onchain_data = match &self.metadata_uri {
+            Some(metadata_uri) => onchain::OnchainData {
+                exists: false,
+                metadata_uri: metadata_uri.clone(),
+                owner: String::new(),
+                wallet: None,
+                block_number: 0,
+            },
+            None => {
+                let started = self.events.phase_started("onchain").await;
+                let onchain_data = self
+                    .within_deadline(
+                        deadline,
+                        "onchain",
+                        onchain::fetch_onchain_data(
+                            chain_id,
+                            request.agent_id,
+                            registry_address,
+                            &self.events,
+                        ),
+                    )
+                    .await
+                    .unwrap_or_else(|| {
+                        Err(WatchyError::Timeout(format!(
+                            "Audit deadline of {}s exceeded while fetching on-chain data",
+                            timeout_secs
+                        )))
+                    })
+                    .map_err(|e| e.in_phase("onchain"))?;
+                self.events.phase_finished("onchain", started).await;
+                onchain_data
+            }
+        };
 
         // Get signer address if private key is configured
-        let signer_address = self.get_signer_address();
+        let signer_address = self.signer.as_ref().map(|signer| signer.address.clone());
 
         let mut report = AuditReport::new(
             request.agent_id,
@@ -71,51 +149,289 @@ impl AuditEngine {
             onchain_data.metadata_uri.clone(),
             signer_address.as_deref(),
         );
+        report.lang = request.lang.unwrap_or(self.state.config.report_lang);
+        report.set_validity(self.state.config.audit_validity_secs);
+        report.auditor.signer = self.signer.as_ref().map(|signer| signer.label.clone());
+        report.auditor.attestation = self.state.attestation.as_ref().map(|a| a.reference());
         report.block_number = onchain_data.block_number;
-        report.agent.owner = Some(onchain_data.owner.clone());
+
+        // The chain's profile adjusts the configured weights
+        let profile = chain_profile(chain_id);
+        report.auditor.profile = Some(profile.name.to_string());
+        let weights = self.settings.score_weights.scaled(profile.weight_factors);
+        if registered {
+            report.agent.owner = Some(onchain_data.owner.clone());
+            report.agent.wallet = onchain_data.wallet.clone();
+            report.record_phase("onchain", PhaseOutcome::Completed, None);
+        } else {
+            report.skip_phases(&["onchain"], UNREGISTERED_REASON);
+        }
+
+        if let (true, Some(ens_chain_id)) = (registered, chain.ens_chain_id) {
+            let resolve = async {
+                report.agent.owner_ens =
+                    onchain::resolve_ens_name(ens_chain_id, &onchain_data.owner).await;
+                if let Some(wallet) = &onchain_data.wallet {
+                    report.agent.wallet_ens = onchain::resolve_ens_name(ens_chain_id, wallet).await;
+                }
+            };
+            self.within_deadline(deadline, "ens", resolve).await;
+        }
 
         // Phase 2: Fetch off-chain metadata
-        let metadata_result = metadata::fetch_metadata(
-            &self.state.http_client,
-            &onchain_data.metadata_uri,
-        )
-        .await;
+        let started = self.events.phase_started("metadata").await;
+        // Agent-supplied URIs pointing at private addresses are not fetched
+        let metadata_result = match self.state.http.guard.check(&onchain_data.metadata_uri).await {
+            Err(reason) => Err(WatchyError::MetadataFetchFailed(format!(
+                "not fetched: {}",
+                reason
+            ))),
+            Ok(()) => self
+                .within_deadline(
+                    deadline,
+                    "metadata",
+                    metadata::fetch_metadata(
+                        &self.state.http.metadata,
+                        &onchain_data.metadata_uri,
+                        &self.events,
+                    ),
+                )
+                .await
+                .unwrap_or_else(|| {
+                    Err(WatchyError::MetadataFetchFailed(
+                        "audit deadline exceeded".to_string(),
+                    ))
+                }),
+        };
+        self.events.phase_finished("metadata", started).await;
 
         let agent_metadata = match metadata_result {
-            Ok(m) => m,
+            Ok(m) => {
+                report.record_phase("metadata", PhaseOutcome::Completed, None);
+                Some(m)
+            }
             Err(e) => {
                 warn!("Failed to fetch metadata: {}", e);
-                report.checks.metadata.issues.push(Issue {
-                    severity: Severity::Critical,
-                    code: "METADATA_FETCH_FAILED".to_string(),
-                    message: format!("Failed to fetch metadata: {}", e),
-                });
+                let (storage, storage_issue) =
+                    metadata::check_metadata_storage(&onchain_data.metadata_uri);
+                report.checks.metadata.storage = storage;
+                report.checks.metadata.issues.extend(storage_issue);
+                report.checks.metadata.issues.push(Issue::new(
+                    Severity::Critical,
+                    "METADATA_FETCH_FAILED",
+                    format!("Failed to fetch metadata: {}", e),
+                ));
                 report.scores.metadata = 0;
-                report.calculate_overall_score();
-                return Ok(report);
+                report.record_phase("metadata", PhaseOutcome::Failed, Some(e.to_string()));
+                None
             }
         };
 
-        // Phase 3: Validate metadata
-        self.validate_metadata(&mut report, &agent_metadata, request.agent_id, &registry_full);
+        if let Some(agent_metadata) = &agent_metadata {
+            report.agent.metadata_snapshot = Some(metadata::snapshot(agent_metadata));
+            *self.audited_metadata.lock().await = Some(agent_metadata.clone());
+
+            // Phase 3: Validate metadata
+            self.validate_metadata(&mut report, agent_metadata, request.agent_id, &registry_full);
+            let verified = self
+                .within_deadline(
+                    deadline,
+                    "credentials",
+                    credentials::check_credentials(
+                        &self.state.http.metadata,
+                        &self.state.http.guard,
+                        agent_metadata,
+                    ),
+                )
+                .await;
+            match verified {
+                Some(Some((checks, issues))) => record_credentials(&mut report, checks, issues),
+                Some(None) => {}
+                None => report.checks.metadata.issues.push(deadline_issue("Credential")),
+            }
+        }
 
         // Phase 4: Verify on-chain consistency
-        self.verify_onchain(&mut report, &onchain_data);
+        if registered {
+            self.verify_onchain(&mut report, &onchain_data);
+            let history = self
+                .within_deadline(
+                    deadline,
+                    "history",
+                    onchain::fetch_agent_history(chain_id, registry_address, request.agent_id),
+                )
+                .await
+                .unwrap_or_else(|| Err(WatchyError::Timeout("audit deadline exceeded".to_string())));
+            self.record_history(&mut report, history);
+            if let Some(agent_metadata) = &agent_metadata {
+                let checked = self
+                    .within_deadline(
+                        deadline,
+                        "registrations",
+                        onchain::check_registrations(
+                            &agent_metadata.registrations,
+                            &registry_full,
+                            request.agent_id,
+                            &onchain_data,
+                        ),
+                    )
+                    .await;
+                match checked {
+                    Some(checks) => record_registrations(&mut report, checks),
+                    None => report.checks.onchain.issues.push(deadline_issue("Registration")),
+                }
+            }
+        }
+        if registered && self.state.config.owner_risk_checks {
+            let assessed = self
+                .within_deadline(deadline, "owner risk", self.assess_owner_risk(&mut report, chain_id, &onchain_data))
+                .await;
+            if assessed.is_none() {
+                report.checks.onchain.issues.push(deadline_issue("Owner risk"));
+            }
+        }
+
+        // Endpoints and security only need the declared services, which the
+        // snapshot pinned by the agent's last audit can stand in for
+        let previous = if registered {
+            self.state
+                .audit_store
+                .latest_completed(request.agent_id, chain_id, self.tenant.as_deref())
+                .await
+                .and_then(|job| job.result.map(|result| (job.id, result)))
+        } else {
+            None
+        };
+        let cached = match (&agent_metadata, &previous) {
+            (None, Some((audit_id, previous))) => self
+                .cached_metadata(previous)
+                .await
+                .map(|cached| (cached, format!("using the metadata audited in {}", audit_id))),
+            _ => None,
+        };
+        let probe_metadata = agent_metadata.as_ref().or(cached.as_ref().map(|(cached, _)| cached));
+        let cache_note = cached.as_ref().map(|(_, note)| note.clone());
+
+        let Some(probe_metadata) = probe_metadata else {
+            report.skip_phases(
+                &["endpoints", "security", "consistency", "content", "trust"],
+                "metadata unavailable",
+            );
+            self.finish(&mut report, &weights);
+            return Ok(report);
+        };
 
         // Phase 5: Test endpoints and collect responses
-        let endpoint_responses = self.test_endpoints(&mut report, &agent_metadata).await;
+        let started = self.events.phase_started("endpoints").await;
+        let (endpoint_responses, evaluated) =
+            self.test_endpoints(&mut report, probe_metadata, deadline).await;
+        self.events.phase_finished("endpoints", started).await;
+        if evaluated {
+            report.record_phase("endpoints", PhaseOutcome::Completed, cache_note.clone());
+        } else {
+            report.record_phase("endpoints", PhaseOutcome::Skipped, Some(NO_ENDPOINTS_REASON.to_string()));
+        }
+
+        // Flag MCP tool schemas that changed since the agent's last audit
+        if let Some((_, previous)) = &previous {
+            endpoints::flag_tool_drift(
+                &mut report.checks.endpoints,
+                &previous.checks.endpoints,
+                &previous.created_at,
+            );
+        }
 
         // Phase 6: Security checks (on first HTTPS endpoint)
-        self.run_security_checks(&mut report, &agent_metadata).await;
+        let started = self.events.phase_started("security").await;
+        let security_done = self
+            .within_deadline(deadline, "security", self.run_security_checks(&mut report, probe_metadata))
+            .await;
+        match security_done {
+            None => {
+                report.checks.security.issues.push(deadline_issue("Security"));
+                report.record_phase("security", PhaseOutcome::Failed, Some(DEADLINE_REASON.to_string()));
+            }
+            Some(false) => {
+                report.record_phase("security", PhaseOutcome::Skipped, Some(NOT_PROBED_REASON.to_string()));
+            }
+            Some(true) => report.record_phase("security", PhaseOutcome::Completed, cache_note),
+        }
+        self.events.phase_finished("security", started).await;
+
+        // The remaining phases judge the agent's current metadata
+        let Some(agent_metadata) = &agent_metadata else {
+            report.skip_phases(&["consistency", "content", "trust"], "metadata unavailable");
+            self.finish(&mut report, &weights);
+            return Ok(report);
+        };
 
         // Phase 7: Consistency checks
-        self.run_consistency_checks(&mut report, &agent_metadata, &endpoint_responses).await;
+        let started = self.events.phase_started("consistency").await;
+        let consistency_done = self
+            .within_deadline(
+                deadline,
+                "consistency",
+                self.run_consistency_checks(
+                    &mut report,
+                    agent_metadata,
+                    &endpoint_responses,
+                    chain.ens_chain_id.is_some(),
+                ),
+            )
+            .await;
+        if consistency_done.is_none() {
+            report.checks.consistency.issues.push(deadline_issue("Consistency"));
+            report.record_phase("consistency", PhaseOutcome::Failed, Some(DEADLINE_REASON.to_string()));
+        } else {
+            report.record_phase("consistency", PhaseOutcome::Completed, None);
+        }
+        self.events.phase_finished("consistency", started).await;
 
         // Phase 8: Content quality checks
-        self.run_content_checks(&mut report, &agent_metadata).await;
+        let started = self.events.phase_started("content").await;
+        let content_done = self
+            .within_deadline(deadline, "content", self.run_content_checks(&mut report, agent_metadata))
+            .await;
+        if content_done.is_none() {
+            report.checks.content.issues.push(deadline_issue("Content"));
+            report.record_phase("content", PhaseOutcome::Failed, Some(DEADLINE_REASON.to_string()));
+        } else {
+            report.record_phase("content", PhaseOutcome::Completed, None);
+        }
+        self.events.phase_finished("content", started).await;
+
+        // Phase 9: Checks for the declared trust mechanisms, which also
+        // reweight the categories those mechanisms rely on
+        let mut weights = weights;
+        let profiles = trust::select_profiles(&agent_metadata.supported_trust);
+        if self.state.config.trust_profiles && !profiles.is_empty() {
+            let started = self.events.phase_started("trust").await;
+            let trust_done = self
+                .within_deadline(
+                    deadline,
+                    "trust",
+                    self.run_trust_checks(
+                        &mut report,
+                        agent_metadata,
+                        &profiles,
+                        chain_id,
+                        request.agent_id,
+                        &onchain_data,
+                    ),
+                )
+                .await;
+            if trust_done.is_none() {
+                report.checks.onchain.issues.push(deadline_issue("Trust profile"));
+                report.record_phase("trust", PhaseOutcome::Failed, Some(DEADLINE_REASON.to_string()));
+            } else {
+                report.record_phase("trust", PhaseOutcome::Completed, None);
+            }
+            self.events.phase_finished("trust", started).await;
+            weights = trust::weights_for(&weights, &profiles);
+        }
 
         // Calculate final scores
-        report.calculate_overall_score();
+        self.finish(&mut report, &weights);
 
         info!(
             "Audit completed for agent {}. Overall score: {}",
@@ -125,6 +441,86 @@ impl AuditEngine {
         Ok(report)
     }
 
+    /// Overall score, feedback tags (set whether or not the report is
+    /// published) and catalog annotations
+    fn finish(&self, report: &mut AuditReport, weights: &ScoreWeights) {
+        report.calculate_overall_score(weights);
+        report.set_feedback_tags(&self.state.config.feedback_tags);
+        catalog::annotate(report);
+    }
+
+    /// The metadata snapshot pinned by a previous audit, if it can still be
+    /// fetched and matches the recorded hash
+    async fn cached_metadata(&self, previous: &AuditReport) -> Option<AgentMetadata> {
+        let snapshot = previous.agent.metadata_snapshot.as_ref()?;
+        let uri = snapshot.uri.as_ref()?;
+        self.state.http.guard.check(uri).await.ok()?;
+        let cached = metadata::fetch_metadata(&self.state.http.metadata, uri, &self.events)
+            .await
+            .ok()?;
+        if metadata::snapshot(&cached).sha256 != snapshot.sha256 {
+            warn!("Metadata snapshot at {} doesn't match its recorded hash", uri);
+            return None;
+        }
+        Some(cached)
+    }
+
+    /// Metadata the last audit ran against (`None` if it couldn't be fetched)
+    ///
+    /// Reports are rendered and pinned from this copy rather than a re-fetch, so
+    /// they describe exactly what was audited.
+    pub async fn audited_metadata(&self) -> Option<AgentMetadata> {
+        self.audited_metadata.lock().await.clone()
+    }
+
+    /// Probe a single endpoint with the audit's validators, outside any audit
+    ///
+    /// Runs the endpoint and security phases against a one-service metadata
+    /// document: no registry lookup, metadata checks or publishing. The
+    /// returned report has only the endpoint and security checks and scores.
+    pub async fn check_endpoint(&self, service: Service, timeout_secs: u64) -> AuditReport {
+        let metadata = AgentMetadata {
+            services: vec![service],
+            ..Default::default()
+        };
+        let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+        let mut report = AuditReport::new(0, 0, "", String::new(), None);
+        self.test_endpoints(&mut report, &metadata, deadline).await;
+        let security = self.run_security_checks(&mut report, &metadata);
+        if self.within_deadline(deadline, "security", security).await.is_none() {
+            report.checks.security.issues.push(deadline_issue("Security"));
+        }
+        catalog::annotate(&mut report);
+        report
+    }
+
+    /// Endpoint responses the last audit judged (`STORE_ENDPOINT_ARTIFACTS`)
+    pub async fn artifacts(&self) -> Vec<EndpointArtifact> {
+        self.artifacts.lock().await.clone()
+    }
+
+    /// Run a phase with whatever remains of the deadline; `None` if it ran out
+    async fn within_deadline<T>(
+        &self,
+        deadline: Instant,
+        phase: &str,
+        fut: impl Future<Output = T>,
+    ) -> Option<T> {
+        match tokio::time::timeout_at(deadline, fut).await {
+            Ok(value) => Some(value),
+            Err(_) => {
+                warn!("Audit deadline exceeded during {} phase", phase);
+                self.events
+                    .emit(
+                        EventKind::DeadlineExceeded,
+                        format!("Audit deadline exceeded during {} phase", phase),
+                    )
+                    .await;
+                None
+            }
+        }
+    }
+
     fn validate_metadata(
         &self,
         report: &mut AuditReport,
@@ -148,12 +544,12 @@ impl AuditEngine {
         };
 
         if !checks.required_fields.passed {
-            score = score.saturating_sub(40);
-            checks.issues.push(Issue {
-                severity: Severity::Critical,
-                code: "MISSING_REQUIRED_FIELDS".to_string(),
-                message: "One or more required fields are missing".to_string(),
-            });
+            score = score.saturating_sub(MISSING_REQUIRED_FIELDS_PENALTY);
+            checks.issues.push(Issue::new(
+                Severity::Critical,
+                "MISSING_REQUIRED_FIELDS",
+                "One or more required fields are missing",
+            ));
         }
 
         // Check type field
@@ -166,25 +562,40 @@ impl AuditEngine {
         };
 
         if !checks.type_field.passed {
-            score = score.saturating_sub(20);
-            checks.issues.push(Issue {
-                severity: Severity::Critical,
-                code: "INVALID_TYPE".to_string(),
-                message: "Type field doesn't match EIP-8004 specification".to_string(),
-            });
+            score = score.saturating_sub(INVALID_TYPE_PENALTY);
+            checks.issues.push(Issue::new(
+                Severity::Critical,
+                "INVALID_TYPE",
+                "Type field doesn't match EIP-8004 specification",
+            ));
         }
 
-        // Check registration matches
-        if metadata.find_registration(agent_id, registry).is_none() {
-            score = score.saturating_sub(20);
-            checks.issues.push(Issue {
-                severity: Severity::Critical,
-                code: "REGISTRATION_MISMATCH".to_string(),
-                message: format!(
+        // Check registration matches (an unregistered agent has none yet)
+        if self.metadata_uri.is_none() && metadata.find_registration(agent_id, registry).is_none() {
+            score = score.saturating_sub(REGISTRATION_MISMATCH_PENALTY);
+            checks.issues.push(Issue::new(
+                Severity::Critical,
+                "REGISTRATION_MISMATCH",
+                format!(
                     "No registration found for agent {} in {}",
                     agent_id, registry
                 ),
-            });
+            ));
+        }
+
+        // Registries must be CAIP-10 accounts, with valid checksums
+        let malformed: Vec<String> = metadata
+            .registrations
+            .iter()
+            .filter_map(|r| r.registry().err().map(|e| e.to_string()))
+            .collect();
+        if !malformed.is_empty() {
+            score = score.saturating_sub(INVALID_REGISTRY_ID_PENALTY);
+            checks.issues.push(Issue::new(
+                Severity::Warning,
+                "INVALID_REGISTRY_ID",
+                format!("Malformed agentRegistry: {}", malformed.join("; ")),
+            ));
         }
 
         // Check recommended fields
@@ -208,13 +619,13 @@ impl AuditEngine {
         };
 
         if !missing_recommended.is_empty() {
-            score = score.saturating_sub(10);
+            score = score.saturating_sub(MISSING_RECOMMENDED_PENALTY);
             for field in &missing_recommended {
-                checks.issues.push(Issue {
-                    severity: Severity::Warning,
-                    code: format!("MISSING_{}", field.to_uppercase()),
-                    message: format!("Recommended field '{}' is missing", field),
-                });
+                checks.issues.push(Issue::new(
+                    Severity::Warning,
+                    format!("MISSING_{}", field.to_uppercase()),
+                    format!("Recommended field '{}' is missing", field),
+                ));
             }
         }
 
@@ -226,12 +637,32 @@ impl AuditEngine {
         };
 
         if !checks.urls_valid.passed {
-            score = score.saturating_sub(10);
-            checks.issues.push(Issue {
-                severity: Severity::Warning,
-                code: "INVALID_URLS".to_string(),
-                message: "One or more URLs in metadata are malformed".to_string(),
+            score = score.saturating_sub(INVALID_URLS_PENALTY);
+            checks.issues.push(Issue::new(
+                Severity::Warning,
+                "INVALID_URLS",
+                "One or more URLs in metadata are malformed",
+            ));
+        }
+
+        // Credentials published in the metadata
+        let leaked = secrets::secret_issues("Metadata", &metadata.raw);
+        if !leaked.is_empty() {
+            score = score.saturating_sub(SECRET_LEAKED_PENALTY);
+            checks.issues.extend(leaked);
+        }
+
+        // Storage backing the metadata URI (mutable hosts and oversized data: URIs)
+        let (storage, storage_issue) = metadata::check_metadata_storage(&report.agent.metadata_uri);
+        let checks = &mut report.checks.metadata;
+        checks.storage = storage;
+        if let Some(issue) = storage_issue {
+            score = score.saturating_sub(match issue.severity {
+                Severity::Critical | Severity::Error => STORAGE_ERROR_PENALTY,
+                Severity::Warning => STORAGE_WARNING_PENALTY,
+                Severity::Info => 0,
             });
+            checks.issues.push(issue);
         }
 
         checks.passed = score >= 60;
@@ -245,66 +676,279 @@ impl AuditEngine {
         checks.agent_exists = onchain_data.exists;
         if !checks.agent_exists {
             score = 0;
-            checks.issues.push(Issue {
-                severity: Severity::Critical,
-                code: "AGENT_NOT_FOUND".to_string(),
-                message: "Agent does not exist on-chain".to_string(),
-            });
+            checks.issues.push(Issue::new(
+                Severity::Critical,
+                "AGENT_NOT_FOUND",
+                "Agent does not exist on-chain",
+            ));
         }
 
         checks.uri_matches = true; // We fetched from on-chain URI, so it matches
+
+        // Content-addressed metadata can't change under an existing registration
+        let (storage, _) = metadata::check_metadata_storage(&onchain_data.metadata_uri);
+        checks.uri_immutable = storage.immutable;
+        if !checks.uri_immutable {
+            score = score.saturating_sub(MUTABLE_URI_PENALTY);
+            checks.issues.push(Issue::new(
+                Severity::Info,
+                "MUTABLE_METADATA_URI",
+                format!(
+                    "tokenURI uses {} storage, which isn't content-addressed; ipfs:// or ar:// pins the document",
+                    storage.storage_type.as_str()
+                ),
+            ));
+        }
         checks.wallet_set = onchain_data.wallet.is_some();
 
         if !checks.wallet_set {
-            score = score.saturating_sub(20);
-            checks.issues.push(Issue {
-                severity: Severity::Warning,
-                code: "NO_WALLET".to_string(),
-                message: "Agent wallet is not set".to_string(),
-            });
+            score = score.saturating_sub(NO_WALLET_PENALTY);
+            checks.issues.push(Issue::new(
+                Severity::Warning,
+                "NO_WALLET",
+                "Agent wallet is not set",
+            ));
         }
 
         checks.passed = score >= 60;
         report.scores.onchain = score;
     }
 
-    async fn test_endpoints(&self, report: &mut AuditReport, metadata: &AgentMetadata) -> EndpointResponses {
-        let mut total_reachable = 0;
-        let mut total_endpoints = 0;
-        let mut total_latency_score = 0u64;
+    /// Attach registration provenance; frequent tokenURI changes cost on-chain points
+    fn record_history(&self, report: &mut AuditReport, history: Result<AgentHistory, WatchyError>) {
+        match history {
+            Ok(history) => {
+                if let Some(issue) = onchain::uri_churn_issue(&history, chrono::Utc::now()) {
+                    report.scores.onchain = report.scores.onchain.saturating_sub(URI_CHURN_PENALTY);
+                    report.checks.onchain.passed = report.scores.onchain >= 60;
+                    report.checks.onchain.issues.push(issue);
+                }
+                report.history = Some(history);
+            }
+            Err(e) => {
+                warn!("Agent history lookup failed: {}", e);
+                report.checks.onchain.issues.push(Issue::new(
+                    Severity::Info,
+                    "AGENT_HISTORY_UNAVAILABLE",
+                    format!("Registration history couldn't be fetched: {}", e),
+                ));
+            }
+        }
+    }
+
+    /// Owner address heuristics; informational, never affects the score
+    async fn assess_owner_risk(
+        &self,
+        report: &mut AuditReport,
+        chain_id: u64,
+        onchain_data: &onchain::OnchainData,
+    ) {
+        let activity = match owner_risk::fetch_account_activity(chain_id, &onchain_data.owner).await {
+            Ok(activity) => activity,
+            Err(e) => {
+                warn!("Owner activity lookup failed: {}", e);
+                return;
+            }
+        };
+
+        let first_seen = match &self.state.config.etherscan_api_key {
+            Some(api_key) => owner_risk::first_seen(
+                &self.state.http.assets,
+                api_key,
+                chain_id,
+                &onchain_data.owner,
+            )
+            .await
+            .unwrap_or_else(|e| {
+                warn!("Owner age lookup failed: {}", e);
+                None
+            }),
+            None => None,
+        };
+
+        let (risk, issues) = owner_risk::assess(
+            &onchain_data.owner,
+            onchain_data.wallet.as_deref(),
+            &activity,
+            first_seen,
+            chrono::Utc::now(),
+        );
+        report.checks.onchain.owner_risk = Some(risk);
+        report.checks.onchain.issues.extend(issues);
+    }
+
+    /// Probe the declared endpoints and score availability and performance
+    ///
+    /// Endpoints on private or blocked addresses count as unreachable; ones the
+    /// operator's policy, the host's opt-out or the deadline kept from being
+    /// probed aren't evaluated. Returns whether any endpoint was evaluated: if
+    /// none was, both scores are 0 and the phase counts as skipped.
+    async fn test_endpoints(
+        &self,
+        report: &mut AuditReport,
+        metadata: &AgentMetadata,
+        deadline: Instant,
+    ) -> (EndpointResponses, bool) {
+        // Endpoints count in proportion to their service type's weight
+        let service_weights = &self.settings.score_weights.services;
+        let mut weighted_reachable = 0.0;
+        let mut weighted_total = 0.0;
+        let mut weighted_latency_score = 0.0;
 
         // Collect endpoint responses for consistency checks
         let mut a2a_response: Option<serde_json::Value> = None;
         let mut mcp_response: Option<serde_json::Value> = None;
         let mut oasf_response: Option<serde_json::Value> = None;
+        let mut artifacts = vec![];
+        let mut artifact_budget = self.state.config.endpoint_artifact_bytes;
 
         for service in &metadata.services {
             let Some(endpoint) = &service.endpoint else {
                 continue;
             };
 
-            // Skip non-HTTP endpoints
-            if !endpoint.starts_with("http") {
+            // Skip endpoints that are neither HTTP, WebSocket nor gRPC
+            let is_websocket = websocket::is_websocket(endpoint);
+            let is_grpc = grpc::is_grpc(service, endpoint);
+            if !endpoint.starts_with("http") && !is_websocket && !is_grpc {
                 continue;
             }
 
-            total_endpoints += 1;
+            let weight = service_weights.weight_for(&service.name);
+
+            // An endpoint on a private or blocked address is unreachable for
+            // everyone else too
+            if let Err(reason) = self.state.http.guard.check(endpoint).await {
+                debug!("Not probing {}: {}", endpoint, reason);
+                report
+                    .checks
+                    .endpoints
+                    .push(endpoints::not_probed(&service.name, endpoint, &reason));
+                weighted_total += weight;
+                continue;
+            }
 
-            let (check, response) = endpoints::test_endpoint_with_response(
-                &self.state.http_client,
-                &service.name,
-                endpoint,
-                service,
-            )
-            .await;
+            // Endpoints outside the probe allowlist or opted out aren't evaluated
+            if let Err(reason) = self.probe_policy_allows(endpoint).await {
+                debug!("Not probing {}: {}", endpoint, reason);
+                report
+                    .checks
+                    .endpoints
+                    .push(endpoints::not_probed(&service.name, endpoint, &reason));
+                continue;
+            }
+
+            // WebSocket connections are opened directly, not through the probe proxy
+            if is_websocket && !self.state.config.proxies.probe_proxies().is_empty() {
+                report.checks.endpoints.push(endpoints::not_probed(
+                    &service.name,
+                    endpoint,
+                    "WebSocket probes don't go through the probe proxy",
+                ));
+                continue;
+            }
+
+            // Endpoints cut off by the deadline are reported but not scored
+            let host_policy = self.host_policy(endpoint).await;
+            let rate = endpoints::ProbeRate {
+                deadline: Some(deadline.into_std()),
+                ..endpoints::ProbeRate::for_policy(host_policy.as_ref())
+            };
+            let probe = || async move {
+                if is_websocket {
+                    websocket::test_endpoint(
+                        &self.state.http.guard,
+                        &self.state.config.probe_user_agent,
+                        service,
+                        endpoint,
+                        self.state.config.response_limits.endpoint_json_bytes,
+                        rate,
+                    )
+                    .await
+                } else if is_grpc {
+                    let check = grpc::test_endpoint(
+                        &self.settings.probes.grpc,
+                        service,
+                        endpoint,
+                        self.state.config.response_limits.endpoint_json_bytes,
+                        rate,
+                    )
+                    .await;
+                    (check, None)
+                } else {
+                    endpoints::test_endpoint_with_response(
+                        &self.settings.probes.probe,
+                        &service.name,
+                        endpoint,
+                        service,
+                        &self.state.config.response_limits,
+                        rate,
+                    )
+                    .await
+                }
+            };
+            let Some((mut check, mut response)) =
+                self.within_deadline(deadline, "endpoints", probe()).await
+            else {
+                report
+                    .checks
+                    .endpoints
+                    .push(endpoints::deadline_exceeded(&service.name, endpoint));
+                continue;
+            };
+            // A failure is confirmed once before the endpoint counts as down;
+            // a request the guard refused isn't retried
+            let mut earlier_errors = vec![];
+            if !check.reachable && check.not_probed.is_none() {
+                let confirm = async {
+                    tokio::time::sleep(CONFIRM_RETRY_DELAY).await;
+                    probe().await
+                };
+                if let Some(retry) = self.within_deadline(deadline, "endpoints", confirm).await {
+                    earlier_errors.push(check.error.take().unwrap_or_default());
+                    (check, response) = retry;
+                }
+            }
+            if check.not_probed.is_none() {
+                endpoints::record_availability(&mut check, earlier_errors);
+            }
+            check.host_policy = host_policy;
+            let is_http = !is_websocket && !is_grpc;
+            if check.reachable && is_http && self.state.config.probe_dual_stack {
+                if let Some(clients) = &self.settings.probes.probe_families {
+                    let probe = dual_stack::probe(clients, endpoint, rate);
+                    if let Some(Some(dual_stack)) =
+                        self.within_deadline(deadline, "dual-stack", probe).await
+                    {
+                        check.issues.extend(dual_stack::issues(&service.name, &dual_stack));
+                        check.dual_stack = Some(dual_stack);
+                    }
+                }
+            }
+            if let Some(body) = &response {
+                check.content_hash = Some(endpoints::content_hash(body));
+                if self.state.config.store_endpoint_artifacts {
+                    artifacts.push(endpoints::artifact(&check, body, &mut artifact_budget));
+                }
+                let source = format!("{} response", service.name);
+                check.issues.extend(secrets::secret_issues(&source, body));
+            }
+
+            weighted_total += weight;
 
             if check.reachable {
-                total_reachable += 1;
+                weighted_reachable += weight;
             }
 
             // Calculate latency score
+            // Latency dominates; transport capabilities add a small share
             if let Some(latency) = &check.latency {
-                total_latency_score += latency_to_score(latency.p95);
+                let mut score = latency_to_score(latency.p95) as f64;
+                if let Some(transport) = &check.transport {
+                    let share = TRANSPORT_SCORE_PERCENT as f64 / 100.0;
+                    score = score * (1.0 - share) + endpoints::transport_score(transport) as f64 * share;
+                }
+                weighted_latency_score += score * weight;
             }
 
             // Store responses for consistency checks
@@ -319,48 +963,134 @@ impl AuditEngine {
         }
 
         // Calculate availability score
-        if total_endpoints > 0 {
+        if weighted_total > 0.0 {
             report.scores.endpoint_availability =
-                ((total_reachable as f64 / total_endpoints as f64) * 100.0) as u8;
+                ((weighted_reachable / weighted_total) * 100.0) as u8;
 
-            // Calculate performance score (average latency score)
-            if total_reachable > 0 {
+            // Calculate performance score (weighted average latency score)
+            if weighted_reachable > 0.0 {
                 report.scores.endpoint_performance =
-                    (total_latency_score / total_reachable as u64) as u8;
+                    (weighted_latency_score / weighted_reachable) as u8;
             }
         } else {
-            // No testable endpoints
-            report.scores.endpoint_availability = 100; // Not penalized
-            report.scores.endpoint_performance = 100;
+            // Nothing to judge availability by
+            report.scores.endpoint_availability = 0;
+            report.scores.endpoint_performance = 0;
         }
 
-        EndpointResponses::from_json_responses(
+        *self.artifacts.lock().await = artifacts;
+
+        let responses = EndpointResponses::from_json_responses(
             a2a_response.as_ref(),
             mcp_response.as_ref(),
             oasf_response.as_ref(),
-        )
+        );
+        (responses, weighted_total > 0.0)
     }
 
-    async fn run_security_checks(&self, report: &mut AuditReport, metadata: &AgentMetadata) {
+    /// Returns false when no HTTPS endpoint could be probed (not evaluated)
+    async fn run_security_checks(&self, report: &mut AuditReport, metadata: &AgentMetadata) -> bool {
         debug!("Running security checks");
 
-        // Find first HTTPS endpoint to test
-        let test_endpoint = metadata.services.iter()
+        // Find first HTTPS endpoint that may be probed
+        let https_endpoints: Vec<&String> = metadata.services.iter()
             .filter_map(|s| s.endpoint.as_ref())
-            .find(|e| e.starts_with("https://"));
+            .filter(|e| e.starts_with("https://"))
+            .collect();
+        let mut test_endpoint = None;
+        for endpoint in &https_endpoints {
+            if self.may_probe(endpoint).await.is_ok() {
+                test_endpoint = Some(*endpoint);
+                break;
+            }
+        }
 
-        if let Some(endpoint) = test_endpoint {
-            let checks = security::check_endpoint_security(&self.state.http_client, endpoint).await;
+        let evaluated = test_endpoint.is_some() || https_endpoints.is_empty();
+        if test_endpoint.is_none() && !https_endpoints.is_empty() {
+            // Not evaluated: nothing was checked, so nothing earns marks
+            report.scores.security = 0;
+            report.checks.security.issues.push(Issue::new(
+                Severity::Info,
+                "SECURITY_NOT_PROBED",
+                "No HTTPS endpoint may be probed (allowlist or private address); security not evaluated",
+            ));
+        } else if let Some(endpoint) = test_endpoint {
+            let checks = security::check_endpoint_security(
+                &self.settings.probes.probe,
+                &self.settings.probes.probe_no_redirect,
+                &self.state.http.guard,
+                endpoint,
+            )
+            .await;
             report.scores.security = security::calculate_security_score(&checks);
             report.checks.security = checks;
         } else {
             // No HTTPS endpoints - critical security issue
             report.scores.security = 0;
-            report.checks.security.issues.push(Issue {
-                severity: Severity::Critical,
-                code: "NO_HTTPS_ENDPOINTS".to_string(),
-                message: "No HTTPS endpoints found".to_string(),
-            });
+            report.checks.security.issues.push(Issue::new(
+                Severity::Critical,
+                "NO_HTTPS_ENDPOINTS",
+                "No HTTPS endpoints found",
+            ));
+        }
+
+        if let Some(provider) = &self.state.config.url_reputation {
+            self.check_url_reputation(report, metadata, provider).await;
+        }
+        evaluated
+    }
+
+    /// Look up declared endpoints and the image with the reputation provider
+    async fn check_url_reputation(
+        &self,
+        report: &mut AuditReport,
+        metadata: &AgentMetadata,
+        provider: &ReputationProvider,
+    ) {
+        let mut urls: Vec<String> = metadata
+            .services
+            .iter()
+            .filter_map(|s| s.endpoint.clone())
+            .chain(metadata.image.clone())
+            .filter(|url| url.starts_with("http://") || url.starts_with("https://"))
+            .collect();
+        urls.sort();
+        urls.dedup();
+
+        let checks = &mut report.checks.security;
+        match provider.check_urls(&self.state.http.assets, &urls).await {
+            Ok(flagged) => {
+                for entry in &flagged {
+                    checks.issues.push(Issue::new(
+                        Severity::Critical,
+                        "URL_FLAGGED",
+                        format!(
+                            "{} is flagged as {} by {}",
+                            entry.url,
+                            entry.threat,
+                            provider.name()
+                        ),
+                    ));
+                }
+                // Known-malicious URLs override every other security result
+                if !flagged.is_empty() {
+                    checks.passed = false;
+                    report.scores.security = 0;
+                }
+                checks.reputation = Some(ReputationCheck {
+                    provider: provider.name().to_string(),
+                    urls_checked: urls.len(),
+                    flagged,
+                });
+            }
+            Err(e) => {
+                warn!("URL reputation lookup failed: {}", e);
+                checks.issues.push(Issue::new(
+                    Severity::Info,
+                    "URL_REPUTATION_UNAVAILABLE",
+                    format!("URL reputation lookup failed: {}", e),
+                ));
+            }
         }
     }
 
@@ -369,15 +1099,21 @@ impl AuditEngine {
         report: &mut AuditReport,
         metadata: &AgentMetadata,
         endpoint_responses: &EndpointResponses,
+        ens_available: bool,
     ) {
         debug!("Running consistency checks");
 
-        let checks = consistency::check_consistency(
-            &self.state.http_client,
+        let mut checks = consistency::check_consistency(
+            &self.state.http.assets,
+            &self.state.http.guard,
+            self.state.config.response_limits.image_bytes,
             metadata,
             endpoint_responses,
         )
         .await;
+        if ens_available {
+            consistency::check_author_identity(&mut checks, metadata, &report.agent);
+        }
 
         report.scores.consistency = consistency::calculate_consistency_score(&checks);
         report.checks.consistency = checks;
@@ -386,17 +1122,236 @@ impl AuditEngine {
     async fn run_content_checks(&self, report: &mut AuditReport, metadata: &AgentMetadata) {
         debug!("Running content quality checks");
 
-        let checks = content::check_content(&self.state.http_client, metadata).await;
+        let mut checks =
+            content::check_content(&self.state.http.assets, &self.state.http.guard, metadata).await;
+        x402::check_pay_to(&mut checks, &report.agent);
+        x402::check_assets(&mut checks).await;
+        x402::check_prices(&mut checks, &self.state.http.assets, &self.state.config.x402_pricing)
+            .await;
+        if let Some((source, issues)) = source_code::check_source_code(
+            &self.state.http.assets,
+            &self.state.http.guard,
+            source_code::GITHUB_API_URL,
+            self.state.config.github_token.as_deref(),
+            metadata,
+        )
+        .await
+        {
+            checks.passed &= source.verified();
+            checks.issues.extend(issues);
+            checks.source_code = Some(source);
+        }
+        // Operator-configured endpoint: unguarded client
+        #[cfg(feature = "llm")]
+        if let Some(llm) = &self.state.config.llm {
+            if let Some((analysis, issues)) =
+                super::claims::analyze_claims(&self.state.http.upload, llm, metadata).await
+            {
+                checks.issues.extend(issues);
+                checks.claims_analysis = Some(analysis);
+            }
+        }
         report.scores.content = content::calculate_content_score(&checks);
         report.checks.content = checks;
     }
 
-    /// Get the signer address from the configured wallet
-    fn get_signer_address(&self) -> Option<String> {
-        self.state.config.signer_address().map(|s| s.to_string())
+    async fn run_trust_checks(
+        &self,
+        report: &mut AuditReport,
+        metadata: &AgentMetadata,
+        profiles: &[TrustProfile],
+        chain_id: u64,
+        agent_id: u64,
+        onchain_data: &onchain::OnchainData,
+    ) {
+        debug!("Running trust profile checks for {:?}", profiles);
+        let mut checks = TrustChecks {
+            profiles: profiles.to_vec(),
+            ..Default::default()
+        };
+
+        for profile in profiles {
+            // Feedback and stake are read from the chain, where an unregistered agent isn't
+            if self.metadata_uri.is_some() && !matches!(profile, TrustProfile::Tee) {
+                continue;
+            }
+            match profile {
+                TrustProfile::Tee => {
+                    checks.attestation_url = trust::attestation_url(metadata);
+                    if let Some(url) = &checks.attestation_url {
+                        checks.attestation_reachable = Some(
+                            trust::attestation_reachable(
+                                &self.state.http.assets,
+                                &self.state.http.guard,
+                                url,
+                            )
+                            .await,
+                        );
+                    }
+                    if let Some((issue, penalty)) = trust::assess_tee(&checks) {
+                        report.scores.security = report.scores.security.saturating_sub(penalty);
+                        report.checks.security.issues.push(issue);
+                    }
+                }
+                TrustProfile::Reputation => {
+                    let own_addresses = self.state.config.wallet.own_addresses();
+                    match trust::fetch_feedback_summary(chain_id, agent_id, &own_addresses).await {
+                        Ok((count, clients)) => {
+                            checks.feedback_count = Some(count);
+                            checks.feedback_clients = Some(clients);
+                        }
+                        Err(e) => warn!("Feedback lookup failed: {}", e),
+                    }
+                    if let Some((issue, penalty)) = trust::assess_reputation(&checks) {
+                        report.scores.onchain = report.scores.onchain.saturating_sub(penalty);
+                        report.checks.onchain.issues.push(issue);
+                    }
+                }
+                TrustProfile::CryptoEconomic => {
+                    let wallet = onchain_data.wallet.as_deref();
+                    if let Some(wallet) = wallet {
+                        checks.wallet_balance_wei =
+                            trust::fetch_wallet_balance(chain_id, wallet).await;
+                    }
+                    let (issues, penalty) = trust::assess_crypto_economic(wallet, &checks);
+                    report.scores.onchain = report.scores.onchain.saturating_sub(penalty);
+                    report.checks.onchain.issues.extend(issues);
+                }
+            }
+        }
+        report.checks.trust = Some(checks);
     }
+
+    /// Whether an endpoint may be contacted (SSRF guard, probe allowlist and
+    /// the host's own opt-out)
+    async fn may_probe(&self, endpoint: &str) -> Result<(), String> {
+        self.state.http.guard.check(endpoint).await?;
+        self.probe_policy_allows(endpoint).await
+    }
+
+    /// Whether the probe allowlist and the host's own policy allow probing
+    async fn probe_policy_allows(&self, endpoint: &str) -> Result<(), String> {
+        self.state.config.probe_policy.check(endpoint).await?;
+        if self.host_policy(endpoint).await.is_some_and(|p| p.opt_out) {
+            return Err(format!("operator opted out via {}", HOST_POLICY_PATH));
+        }
+        Ok(())
+    }
+
+    /// Policy published by an endpoint's host, fetched once per origin per audit
+    async fn host_policy(&self, endpoint: &str) -> Option<HostPolicy> {
+        if !self.state.config.honor_host_policy {
+            return None;
+        }
+        let origin = policy::origin(endpoint)?;
+
+        let mut cache = self.host_policies.lock().await;
+        if let Some(cached) = cache.get(&origin) {
+            return cached.clone();
+        }
+        let fetched = policy::fetch_host_policy(&self.settings.probes.probe, &origin).await;
+        cache.insert(origin, fetched.clone());
+        fetched
+    }
+}
+
+/// Attach DID / credential results to the metadata checks; each credential
+/// whose signature fails costs metadata points
+fn record_credentials(report: &mut AuditReport, checks: CredentialChecks, issues: Vec<Issue>) {
+    let invalid = checks
+        .credentials
+        .iter()
+        .filter(|credential| credential.signature_valid == Some(false))
+        .count();
+    let penalty = (invalid as u8).saturating_mul(credentials::INVALID_SIGNATURE_PENALTY);
+    report.scores.metadata = report.scores.metadata.saturating_sub(penalty);
+    let metadata = &mut report.checks.metadata;
+    metadata.passed = report.scores.metadata >= 60;
+    metadata.issues.extend(issues);
+    metadata.credentials = Some(checks);
+}
+
+/// Attach the listed registrations to the on-chain checks; each that points
+/// at no token or at other metadata costs on-chain points
+fn record_registrations(report: &mut AuditReport, checks: Vec<RegistrationCheck>) {
+    let issues = onchain::registration_issues(&checks);
+    let flagged = checks
+        .iter()
+        .filter(|check| {
+            matches!(check.status, RegistrationStatus::NotFound | RegistrationStatus::UriMismatch)
+        })
+        .count();
+    report.scores.onchain = report.scores.onchain.saturating_sub((flagged as u8).saturating_mul(REGISTRATION_PENALTY));
+    let onchain = &mut report.checks.onchain;
+    onchain.passed = report.scores.onchain >= 60;
+    onchain.issues.extend(issues);
+    onchain.registrations = checks;
+}
+
+/// Wait before confirming that an endpoint is down
+const CONFIRM_RETRY_DELAY: Duration = Duration::from_secs(3);
+
+/// The chain config and registry of an EVM chain Watchy can audit on
+fn audited_chain(chain_id: u64) -> Result<(&'static ChainConfig, &'static str), WatchyError> {
+    let chain = get_chain(chain_id).ok_or_else(|| {
+        WatchyError::InvalidRequest(format!("Unsupported chain_id: {}", chain_id))
+    })?;
+    if chain.chain_type != ChainType::Evm {
+        return Err(WatchyError::InvalidRequest(format!(
+            "Chain {} is not an EVM chain",
+            chain.name
+        )));
+    }
+    let registry_address = chain.registry_address.ok_or_else(|| {
+        WatchyError::InvalidRequest(format!(
+            "No registry deployed on {} (chain_id: {})",
+            chain.name, chain_id
+        ))
+    })?;
+    Ok((chain, registry_address))
+}
+
+/// Why the on-chain phase of an audit by metadata URI is skipped
+const UNREGISTERED_REASON: &str = "agent not registered";
+
+/// Why the endpoints phase is skipped when no endpoint could be evaluated
+const NO_ENDPOINTS_REASON: &str = "no endpoint could be probed";
+
+/// Why the security phase is skipped when no HTTPS endpoint could be probed
+const NOT_PROBED_REASON: &str = "no HTTPS endpoint could be probed";
+
+/// Why a phase cut short by the deadline failed
+const DEADLINE_REASON: &str = "audit deadline exceeded";
+
+fn deadline_issue(phase: &str) -> Issue {
+    Issue::new(
+        Severity::Warning,
+        "AUDIT_DEADLINE_EXCEEDED",
+        format!("{} checks didn't finish before the audit deadline", phase),
+    )
 }
 
+/// Weight of HTTP/2, compression and keep-alive in the performance score (%)
+pub const TRANSPORT_SCORE_PERCENT: u8 = 10;
+
+// Points lost per finding, also quoted by the issue catalog
+pub const MISSING_REQUIRED_FIELDS_PENALTY: u8 = 40;
+pub const INVALID_TYPE_PENALTY: u8 = 20;
+pub const REGISTRATION_MISMATCH_PENALTY: u8 = 20;
+pub const INVALID_REGISTRY_ID_PENALTY: u8 = 10;
+/// Once, however many recommended fields are missing
+pub const MISSING_RECOMMENDED_PENALTY: u8 = 10;
+pub const INVALID_URLS_PENALTY: u8 = 10;
+pub const SECRET_LEAKED_PENALTY: u8 = 40;
+/// Metadata storage findings, by severity (error / warning)
+pub const STORAGE_ERROR_PENALTY: u8 = 10;
+pub const STORAGE_WARNING_PENALTY: u8 = 5;
+pub const MUTABLE_URI_PENALTY: u8 = 10;
+pub const NO_WALLET_PENALTY: u8 = 20;
+pub const URI_CHURN_PENALTY: u8 = 10;
+/// Per listed registration with no token or other metadata
+pub const REGISTRATION_PENALTY: u8 = 10;
+
 fn latency_to_score(p95_ms: u64) -> u64 {
     match p95_ms {
         0..=200 => 100,
@@ -477,3 +1432,93 @@ fn is_valid_url(url: &str) -> bool {
 
     false
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::AuditParams;
+    use crate::types::AuditStatus;
+
+    /// State with a signer, so nothing but the missing registration keeps
+    /// reports from being published
+    fn signing_state() -> Arc<AppState> {
+        AppState::for_tests(|_| {})
+    }
+
+    #[test]
+    fn test_bad_checksum_registry() {
+        let state = signing_state();
+        let registry = get_chain(8453).unwrap().registry_address.unwrap();
+        let registry_full = AccountId::eip155_string(8453, registry);
+        // Same address with one letter's case flipped
+        let (prefix, address) = registry_full.rsplit_once(':').unwrap();
+        let flip = address.find(|c: char| c.is_ascii_alphabetic() && c != 'x').unwrap();
+        let mut address = address.to_string();
+        let flipped = match address.as_bytes()[flip] {
+            c if c.is_ascii_uppercase() => c.to_ascii_lowercase(),
+            c => c.to_ascii_uppercase(),
+        };
+        address.replace_range(flip..flip + 1, &(flipped as char).to_string());
+        let metadata: AgentMetadata = serde_json::from_value(serde_json::json!({
+            "registrations": [{ "agentId": 17, "agentRegistry": format!("{}:{}", prefix, address) }]
+        }))
+        .unwrap();
+
+        let mut report = AuditReport::new(17, 8453, registry, String::new(), None);
+        AuditEngine::new(state).validate_metadata(&mut report, &metadata, 17, &registry_full);
+        let codes: Vec<&str> =
+            report.checks.metadata.issues.iter().map(|issue| issue.code.as_str()).collect();
+        assert!(codes.contains(&"INVALID_REGISTRY_ID"));
+        assert!(!codes.contains(&"REGISTRATION_MISMATCH"));
+    }
+
+    #[tokio::test]
+    async fn test_unregistered_audit() {
+        let state = signing_state();
+        // Fails to parse without a fetch, so the audit stays offline
+        let metadata_uri = "data:text/plain,draft".to_string();
+        let request = AuditRequest {
+            agent_id: 0,
+            chain_id: Some(8453),
+            timeout_secs: Some(5),
+            lang: None,
+        };
+        let report = AuditEngine::new(state.clone())
+            .with_metadata_uri(Some(metadata_uri.clone()))
+            .run_audit(&request)
+            .await
+            .unwrap();
+        assert!(report.is_skipped("onchain"));
+        assert_eq!(report.agent.owner, None);
+
+        let params = AuditParams {
+            timeout_secs: 5,
+            lang: Default::default(),
+            requester: None,
+            batch_id: None,
+            metadata_uri: Some(metadata_uri),
+        };
+        let id =
+            crate::api::handlers::spawn_audit(&state, None, 8453, params, None, None).await;
+        state.audits.close();
+        state.audits.wait().await;
+
+        let job = state.audit_store.get_job(&id).await.unwrap();
+        assert_eq!(job.status, AuditStatus::Completed);
+        assert_eq!(job.agent_id, None);
+        let report = job.result.as_ref().unwrap();
+        assert!(report.is_skipped("onchain"));
+        assert!(report.report_json_uri.is_none() && report.report_markdown_uri.is_none());
+        // No upload or feedback was attempted
+        let events = state.audit_store.job_events(&job).await;
+        assert!(events.iter().all(|event| !matches!(
+            event.kind,
+            EventKind::UploadAttempt
+                | EventKind::UploadFailed
+                | EventKind::FeedbackSubmitted
+                | EventKind::FeedbackFailed
+        )));
+        assert!(events.iter().any(|event| event.kind == EventKind::AuditCompleted));
+        assert!(state.audit_store.agent_jobs(8453, 0, None).await.is_empty());
+    }
+}
@@ -0,0 +1,411 @@
+//! gRPC agent endpoints
+//!
+//! Agents declaring gRPC services (`grpc://` / `grpcs://` endpoints or a
+//! `gRPC` service) get `grpc.health.v1` health checks, whose round trips are
+//! the latency samples, and a server reflection query listing the services,
+//! which is compared with the declared `grpcServices`. The few protobuf
+//! fields involved are encoded by hand.
+//!
+//! reqwest doesn't expose HTTP/2 trailers, so a status is only seen on
+//! trailers-only responses (errors, where `grpc-status` arrives with the
+//! headers); a 200 carrying a well-formed message counts as OK.
+
+use reqwest::header::CONTENT_TYPE;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use tracing::debug;
+use url::Url;
+
+use super::endpoints::{calculate_percentiles, ProbeRate};
+use crate::http::read_body_limited;
+use crate::types::{EndpointCheck, GrpcInfo, Issue, Service, ServiceType, Severity};
+
+const HEALTH_CHECK_PATH: &str = "/grpc.health.v1.Health/Check";
+
+/// Reflection services, newest first
+const REFLECTION_PATHS: [&str; 2] = [
+    "/grpc.reflection.v1.ServerReflection/ServerReflectionInfo",
+    "/grpc.reflection.v1alpha.ServerReflection/ServerReflectionInfo",
+];
+
+/// `ServerReflectionRequest { list_services: "" }`
+const LIST_SERVICES_REQUEST: [u8; 2] = [0x3a, 0x00];
+
+/// gRPC status code for methods the server doesn't implement
+const GRPC_UNIMPLEMENTED: u32 = 12;
+
+/// `grpc.health.v1.HealthCheckResponse.ServingStatus` names
+const SERVING_STATUSES: [&str; 4] = ["UNKNOWN", "SERVING", "NOT_SERVING", "SERVICE_UNKNOWN"];
+
+/// Whether an endpoint should be probed as gRPC
+pub fn is_grpc(service: &Service, endpoint: &str) -> bool {
+    endpoint.starts_with("grpc://")
+        || endpoint.starts_with("grpcs://")
+        || ServiceType::from(service.name.as_str()) == ServiceType::Grpc
+}
+
+/// HTTP URL a gRPC endpoint is reached at (`grpc://` is plaintext HTTP/2)
+pub fn http_url(endpoint: &str) -> String {
+    if let Some(rest) = endpoint.strip_prefix("grpcs://") {
+        format!("https://{}", rest)
+    } else if let Some(rest) = endpoint.strip_prefix("grpc://") {
+        format!("http://{}", rest)
+    } else {
+        endpoint.to_string()
+    }
+}
+
+/// Why a unary call didn't return a message
+#[derive(Debug)]
+enum CallError {
+    /// No response at all
+    Transport(String),
+    /// gRPC status and message from a trailers-only response
+    Status(u32, String),
+    /// Answered, but not as a gRPC server
+    NotGrpc(String),
+}
+
+/// Test a gRPC endpoint with health checks and a reflection query
+///
+/// `client` must speak HTTP/2 without negotiation (prior knowledge).
+pub async fn test_endpoint(
+    client: &reqwest::Client,
+    service: &Service,
+    endpoint: &str,
+    max_bytes: usize,
+    rate: ProbeRate,
+) -> EndpointCheck {
+    let service_name = service.name.as_str();
+    debug!("Testing {} gRPC endpoint: {}", service_name, endpoint);
+
+    let mut check = EndpointCheck {
+        service: service_name.to_string(),
+        endpoint: endpoint.to_string(),
+        reachable: false,
+        valid_schema: None,
+        skills_match: None,
+        latency: None,
+        error: None,
+        not_probed: None,
+        host_policy: None,
+        skills: vec![],
+        transport: None,
+        tool_fingerprints: BTreeMap::new(),
+        content_hash: None,
+        availability: None,
+        rate_limit: None,
+        dual_stack: None,
+        websocket: None,
+        grpc: None,
+        issues: vec![],
+    };
+
+    let base = match Url::parse(&http_url(endpoint)) {
+        Ok(url) => url,
+        Err(e) => {
+            check.error = Some(format!("Invalid URL: {}", e));
+            check.issues.push(unreachable_issue(service_name));
+            return check;
+        }
+    };
+
+    // Health check round trips are the latency samples
+    let mut latencies = vec![];
+    let mut health = None;
+    for _ in 0..rate.samples {
+        let start = Instant::now();
+        let result = call(client, &base, HEALTH_CHECK_PATH, &[], max_bytes).await;
+        if let Err(CallError::Transport(e)) = &result {
+            check.error = Some(e.clone());
+        } else {
+            latencies.push(start.elapsed().as_millis() as u64);
+            health = Some(result);
+        }
+        tokio::time::sleep(Duration::from_millis(rate.interval_ms)).await;
+    }
+    let Some(health) = health else {
+        check.issues.push(unreachable_issue(service_name));
+        return check;
+    };
+    check.reachable = true;
+    check.error = None;
+    check.latency = Some(calculate_percentiles(&latencies));
+
+    let mut info = GrpcInfo {
+        health: None,
+        reflection: false,
+        services: vec![],
+    };
+    match health {
+        Ok(message) => {
+            let status = health_status(&message);
+            check.valid_schema = Some(status.is_some());
+            if let Some(status) = status.filter(|status| *status != "SERVING") {
+                check.issues.push(Issue::new(
+                    Severity::Error,
+                    "GRPC_NOT_SERVING",
+                    format!("{} gRPC health check reports {}", service_name, status),
+                ));
+            }
+            info.health = status.map(str::to_string);
+        }
+        Err(CallError::Status(GRPC_UNIMPLEMENTED, _)) => {
+            check.valid_schema = Some(true);
+            check.issues.push(Issue::new(
+                Severity::Info,
+                "GRPC_HEALTH_UNIMPLEMENTED",
+                format!(
+                    "{} gRPC server doesn't implement grpc.health.v1",
+                    service_name
+                ),
+            ));
+        }
+        Err(e) => {
+            check.valid_schema = Some(matches!(e, CallError::Status(..)));
+            let reason = match e {
+                CallError::Status(code, message) => format!("status {} {}", code, message),
+                CallError::NotGrpc(reason) | CallError::Transport(reason) => reason,
+            };
+            check.issues.push(Issue::new(
+                Severity::Warning,
+                "GRPC_HEALTH_FAILED",
+                format!("{} gRPC health check failed: {}", service_name, reason),
+            ));
+        }
+    }
+
+    for path in REFLECTION_PATHS {
+        let result = call(client, &base, path, &LIST_SERVICES_REQUEST, max_bytes).await;
+        if let Some(services) = result.ok().as_deref().and_then(listed_services) {
+            info.reflection = true;
+            info.services = services;
+            break;
+        }
+    }
+
+    if !info.reflection {
+        check.issues.push(Issue::new(
+            Severity::Info,
+            "GRPC_REFLECTION_UNAVAILABLE",
+            format!(
+                "{} gRPC server doesn't answer reflection queries; declared services can't be checked",
+                service_name
+            ),
+        ));
+    } else if !service.grpc_services.is_empty() {
+        let missing: Vec<&str> = service
+            .grpc_services
+            .iter()
+            .filter(|declared| !info.services.contains(declared))
+            .map(String::as_str)
+            .collect();
+        check.skills_match = Some(missing.is_empty());
+        if !missing.is_empty() {
+            check.issues.push(Issue::new(
+                Severity::Warning,
+                "GRPC_SERVICES_MISMATCH",
+                format!("Declared gRPC services not served: {}", missing.join(", ")),
+            ));
+        }
+    }
+
+    check.grpc = Some(info);
+    check
+}
+
+fn unreachable_issue(service_name: &str) -> Issue {
+    Issue::new(
+        Severity::Critical,
+        "ENDPOINT_UNREACHABLE",
+        format!("{} endpoint is not reachable", service_name),
+    )
+}
+
+/// Make a unary call (or a one-message stream) and return the first reply
+async fn call(
+    client: &reqwest::Client,
+    base: &Url,
+    path: &str,
+    message: &[u8],
+    max_bytes: usize,
+) -> Result<Vec<u8>, CallError> {
+    let url = base
+        .join(path)
+        .map_err(|e| CallError::Transport(e.to_string()))?;
+    let mut body = Vec::with_capacity(message.len() + 5);
+    body.push(0);
+    body.extend_from_slice(&(message.len() as u32).to_be_bytes());
+    body.extend_from_slice(message);
+
+    let response = client
+        .post(url)
+        .header(CONTENT_TYPE, "application/grpc")
+        .header("te", "trailers")
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| CallError::Transport(e.to_string()))?;
+
+    let header = |name: &str| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    if let Some(status) = header("grpc-status").and_then(|s| s.parse::<u32>().ok()) {
+        if status != 0 {
+            return Err(CallError::Status(
+                status,
+                header("grpc-message").unwrap_or_default(),
+            ));
+        }
+    }
+    if !response.status().is_success() {
+        return Err(CallError::NotGrpc(format!(
+            "HTTP {}",
+            response.status().as_u16()
+        )));
+    }
+    if !header("content-type").is_some_and(|t| t.starts_with("application/grpc")) {
+        return Err(CallError::NotGrpc(
+            "response is not application/grpc".to_string(),
+        ));
+    }
+
+    let body = read_body_limited(response, max_bytes)
+        .await
+        .map_err(|e| CallError::NotGrpc(e.to_string()))?;
+    first_message(&body).ok_or_else(|| CallError::NotGrpc("malformed gRPC frame".to_string()))
+}
+
+/// Payload of the first length-prefixed message (uncompressed only)
+fn first_message(body: &[u8]) -> Option<Vec<u8>> {
+    let (&compressed, rest) = body.split_first()?;
+    if compressed != 0 || rest.len() < 4 {
+        return None;
+    }
+    let len = u32::from_be_bytes(rest[..4].try_into().ok()?) as usize;
+    rest.get(4..4 + len).map(<[u8]>::to_vec)
+}
+
+/// A protobuf field value (fixed-width fields are skipped)
+enum Field<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+}
+
+fn read_varint(data: &mut &[u8]) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = data.split_first()?;
+        *data = rest;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// Field numbers and values of a message, in order; `None` if malformed
+fn fields(mut data: &[u8]) -> Option<Vec<(u64, Field<'_>)>> {
+    let mut fields = vec![];
+    while !data.is_empty() {
+        let key = read_varint(&mut data)?;
+        let field = match key & 7 {
+            0 => Field::Varint(read_varint(&mut data)?),
+            2 => {
+                let len = read_varint(&mut data)? as usize;
+                let value = data.get(..len)?;
+                data = &data[len..];
+                Field::Bytes(value)
+            }
+            wire_type => {
+                let width = match wire_type {
+                    1 => 8,
+                    5 => 4,
+                    _ => return None,
+                };
+                data = data.get(width..)?;
+                continue;
+            }
+        };
+        fields.push((key >> 3, field));
+    }
+    Some(fields)
+}
+
+/// `HealthCheckResponse.status` (an absent field is the proto3 default, UNKNOWN)
+fn health_status(message: &[u8]) -> Option<&'static str> {
+    let status = fields(message)?
+        .into_iter()
+        .find_map(|(number, field)| match field {
+            Field::Varint(value) if number == 1 => Some(value),
+            _ => None,
+        })
+        .unwrap_or(0);
+    SERVING_STATUSES.get(status as usize).copied()
+}
+
+/// Service names in a `ServerReflectionResponse.list_services_response`
+fn listed_services(message: &[u8]) -> Option<Vec<String>> {
+    let list = fields(message)?
+        .into_iter()
+        .find_map(|(number, field)| match field {
+            Field::Bytes(value) if number == 6 => Some(value),
+            _ => None,
+        })?;
+    let mut services = vec![];
+    for (number, field) in fields(list)? {
+        let (1, Field::Bytes(service)) = (number, field) else {
+            continue;
+        };
+        for (number, field) in fields(service)? {
+            if let (1, Field::Bytes(name)) = (number, field) {
+                services.push(String::from_utf8_lossy(name).into_owned());
+            }
+        }
+    }
+    Some(services)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grpc_messages() {
+        assert_eq!(
+            http_url("grpcs://agent.example.com:443"),
+            "https://agent.example.com:443"
+        );
+        assert_eq!(
+            http_url("grpc://agent.example.com:50051"),
+            "http://agent.example.com:50051"
+        );
+
+        // Frame with HealthCheckResponse { status: SERVING }
+        let message = first_message(&[0, 0, 0, 0, 2, 0x08, 0x01]).unwrap();
+        assert_eq!(health_status(&message), Some("SERVING"));
+        assert_eq!(health_status(&[]), Some("UNKNOWN"));
+        assert!(first_message(&[1, 0, 0, 0, 0]).is_none());
+
+        // ServerReflectionResponse { valid_host: "", list_services_response: {
+        //   service: [{ name: "agent.v1.Chat" }, { name: "grpc.health.v1.Health" }] } }
+        let mut list = vec![];
+        for name in ["agent.v1.Chat", "grpc.health.v1.Health"] {
+            list.extend_from_slice(&[0x0a, name.len() as u8 + 2, 0x0a, name.len() as u8]);
+            list.extend_from_slice(name.as_bytes());
+        }
+        let mut response = vec![0x0a, 0x00, 0x32, list.len() as u8];
+        response.extend_from_slice(&list);
+        assert_eq!(
+            listed_services(&response).unwrap(),
+            ["agent.v1.Chat", "grpc.health.v1.Health"]
+        );
+        // Error responses have no service list
+        assert!(listed_services(&[0x3a, 0x02, 0x08, 0x0c]).is_none());
+    }
+}
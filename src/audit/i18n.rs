@@ -0,0 +1,647 @@
+//! Report translations
+//!
+//! The Markdown report can be rendered in English, Chinese or Spanish. Section
+//! headings, table headers, verdicts and the severity legend come from a
+//! per-language [`ReportText`]; issue messages are prefixed with a localized
+//! summary of their code. Check names, technical values and the free-form
+//! detail of each issue stay as the audit produced them.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::catalog;
+use super::scoring;
+
+/// Report language
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Lang {
+    #[default]
+    En,
+    Zh,
+    Es,
+}
+
+impl Lang {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "en" | "english" => Some(Lang::En),
+            "zh" | "zh-cn" | "chinese" => Some(Lang::Zh),
+            "es" | "spanish" => Some(Lang::Es),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Lang::En => "en",
+            Lang::Zh => "zh",
+            Lang::Es => "es",
+        }
+    }
+
+    pub fn text(&self) -> &'static ReportText {
+        match self {
+            Lang::En => &EN,
+            Lang::Zh => &ZH,
+            Lang::Es => &ES,
+        }
+    }
+
+    /// Short localized summary of an issue code
+    pub fn issue_summary(&self, code: &str) -> Option<&'static str> {
+        let table = match self {
+            Lang::En => return catalog::lookup(code).map(|entry| entry.description),
+            Lang::Zh => ZH_ISSUES,
+            Lang::Es => ES_ISSUES,
+        };
+        table
+            .iter()
+            .find(|(c, _)| *c == code)
+            .map(|(_, summary)| *summary)
+    }
+}
+
+/// Fixed report strings for one language
+pub struct ReportText {
+    pub title: &'static str,
+    pub agent: &'static str,
+    pub overall_score: &'static str,
+    pub audited_on: &'static str,
+    pub valid_until: &'static str,
+    pub block: &'static str,
+    pub what_this_audit_covers: &'static str,
+    pub score_breakdown: &'static str,
+    pub overall: &'static str,
+    pub component: &'static str,
+    pub score: &'static str,
+    pub weight: &'static str,
+    pub endpoint_availability: &'static str,
+    pub endpoint_performance: &'static str,
+    pub security: &'static str,
+    pub metadata: &'static str,
+    pub onchain: &'static str,
+    pub consistency: &'static str,
+    pub content: &'static str,
+    pub verdict: &'static str,
+    pub incomplete_audit: &'static str,
+    pub incomplete_audit_note: &'static str,
+    pub phase: &'static str,
+    pub reason: &'static str,
+    pub failed: &'static str,
+    pub skipped: &'static str,
+    /// Verdict per score band, best first (90+, 75+, 60+, 40+, below)
+    pub verdicts: [&'static str; 5],
+    pub verdict_explanations: [&'static str; 5],
+    pub agent_identity: &'static str,
+    pub agent_history: &'static str,
+    pub agent_claims: &'static str,
+    pub declared_services: &'static str,
+    pub detailed_results: &'static str,
+    pub onchain_verification: &'static str,
+    pub metadata_compliance: &'static str,
+    pub endpoint_testing: &'static str,
+    pub security_analysis: &'static str,
+    pub consistency_analysis: &'static str,
+    pub content_quality: &'static str,
+    pub property: &'static str,
+    pub value: &'static str,
+    pub check: &'static str,
+    pub result: &'static str,
+    pub description: &'static str,
+    pub why_it_matters: &'static str,
+    pub what_we_compare: &'static str,
+    pub details: &'static str,
+    pub pass: &'static str,
+    pub fail: &'static str,
+    pub yes: &'static str,
+    pub no: &'static str,
+    pub issues_found: &'static str,
+    /// (label, call to action) for critical, error, warning and info
+    pub severity_legend: [(&'static str, &'static str); 4],
+    pub all_issues: &'static str,
+    pub severity: &'static str,
+    pub code: &'static str,
+    pub message: &'static str,
+    pub how_to_fix: &'static str,
+    pub about_this_report: &'static str,
+    pub limitations: &'static str,
+    pub learn_more: &'static str,
+}
+
+impl ReportText {
+    pub fn verdict(&self, score: u8) -> (&'static str, &'static str) {
+        let band = scoring::score_band(score);
+        (self.verdicts[band], self.verdict_explanations[band])
+    }
+}
+
+pub const EN: ReportText = ReportText {
+    title: "Watchy Audit Report",
+    agent: "Agent",
+    overall_score: "Overall Score",
+    audited_on: "Audited on",
+    valid_until: "Valid until",
+    block: "Block",
+    what_this_audit_covers: "What This Audit Covers",
+    score_breakdown: "Score Breakdown",
+    overall: "Overall",
+    component: "Component",
+    score: "Score",
+    weight: "Weight",
+    endpoint_availability: "Endpoint Availability",
+    endpoint_performance: "Endpoint Performance",
+    security: "Security",
+    metadata: "Metadata",
+    onchain: "On-chain",
+    consistency: "Consistency",
+    content: "Content",
+    verdict: "Verdict",
+    incomplete_audit: "Incomplete Audit",
+    incomplete_audit_note: "Some phases didn't complete. Failed phases ran and are scored on what they found; skipped phases never ran and are left out of the overall score.",
+    phase: "Phase",
+    reason: "Reason",
+    failed: "❌ Failed",
+    skipped: "⏭️ Skipped",
+    verdicts: [
+        "**Excellent** - Agent passes all critical checks",
+        "**Good** - Agent passes most checks with minor issues",
+        "**Fair** - Agent has issues that should be addressed",
+        "**Poor** - Agent has significant problems",
+        "**Critical** - Agent fails multiple critical checks",
+    ],
+    verdict_explanations: [
+        "This agent has excellent infrastructure. All endpoints are reachable, metadata is complete, and security basics are in place. Safe to interact with from an infrastructure standpoint.",
+        "This agent has solid infrastructure with some minor issues. Consider reviewing the warnings below, but the agent is generally well-configured.",
+        "This agent has noticeable issues that may affect reliability. Review the problems listed and consider whether they impact your use case.",
+        "This agent has significant infrastructure problems. Proceed with caution and review all issues carefully.",
+        "This agent has critical problems that make it unreliable. We recommend against interacting with this agent until issues are resolved.",
+    ],
+    agent_identity: "Agent Identity",
+    agent_history: "Agent History",
+    agent_claims: "What The Agent Claims",
+    declared_services: "Declared Services",
+    detailed_results: "Detailed Audit Results",
+    onchain_verification: "On-chain Verification",
+    metadata_compliance: "Metadata Compliance",
+    endpoint_testing: "Endpoint Testing",
+    security_analysis: "Security Analysis",
+    consistency_analysis: "Consistency Analysis",
+    content_quality: "Content Quality",
+    property: "Property",
+    value: "Value",
+    check: "Check",
+    result: "Result",
+    description: "Description",
+    why_it_matters: "Why It Matters",
+    what_we_compare: "What We Compare",
+    details: "Details",
+    pass: "✅ Pass",
+    fail: "❌ Fail",
+    yes: "Yes",
+    no: "No",
+    issues_found: "Issues Found",
+    severity_legend: [
+        ("Critical", "Must be fixed"),
+        ("Errors", "Should be fixed"),
+        ("Warnings", "Consider fixing"),
+        ("Info", "For your information"),
+    ],
+    all_issues: "All Issues",
+    severity: "Severity",
+    code: "Code",
+    message: "Message",
+    how_to_fix: "How to fix",
+    about_this_report: "About This Report",
+    limitations: "Limitations",
+    learn_more: "Learn More",
+};
+
+pub const ZH: ReportText = ReportText {
+    title: "Watchy 审计报告",
+    agent: "智能体",
+    overall_score: "总分",
+    audited_on: "审计时间",
+    valid_until: "有效期至",
+    block: "区块",
+    what_this_audit_covers: "审计范围",
+    score_breakdown: "评分明细",
+    overall: "总分",
+    component: "项目",
+    score: "得分",
+    weight: "权重",
+    endpoint_availability: "端点可用性",
+    endpoint_performance: "端点性能",
+    security: "安全",
+    metadata: "元数据",
+    onchain: "链上",
+    consistency: "一致性",
+    content: "内容",
+    verdict: "结论",
+    incomplete_audit: "审计未完成",
+    incomplete_audit_note: "部分阶段未完成。失败的阶段已运行，按其结果计分；跳过的阶段未运行，不计入总分。",
+    phase: "阶段",
+    reason: "原因",
+    failed: "❌ 失败",
+    skipped: "⏭️ 已跳过",
+    verdicts: [
+        "**优秀** - 智能体通过了所有关键检查",
+        "**良好** - 智能体通过了大部分检查，仅有少量问题",
+        "**一般** - 智能体存在需要处理的问题",
+        "**较差** - 智能体存在严重问题",
+        "**危险** - 智能体未通过多项关键检查",
+    ],
+    verdict_explanations: [
+        "该智能体的基础设施非常完善：所有端点均可访问，元数据完整，基本安全措施到位。从基础设施角度看可以放心交互。",
+        "该智能体的基础设施稳固，但有少量小问题。建议查看下方的警告，总体配置良好。",
+        "该智能体存在明显问题，可能影响可靠性。请查看所列问题，并评估其是否影响您的使用场景。",
+        "该智能体的基础设施存在严重问题。请谨慎使用，并仔细查看所有问题。",
+        "该智能体存在导致其不可靠的关键问题。在问题解决之前，我们建议不要与其交互。",
+    ],
+    agent_identity: "智能体身份",
+    agent_history: "智能体历史",
+    agent_claims: "智能体声明的信息",
+    declared_services: "声明的服务",
+    detailed_results: "详细审计结果",
+    onchain_verification: "链上验证",
+    metadata_compliance: "元数据合规性",
+    endpoint_testing: "端点测试",
+    security_analysis: "安全分析",
+    consistency_analysis: "一致性分析",
+    content_quality: "内容质量",
+    property: "属性",
+    value: "值",
+    check: "检查项",
+    result: "结果",
+    description: "说明",
+    why_it_matters: "重要性",
+    what_we_compare: "比较内容",
+    details: "详情",
+    pass: "✅ 通过",
+    fail: "❌ 未通过",
+    yes: "是",
+    no: "否",
+    issues_found: "发现的问题",
+    severity_legend: [
+        ("严重", "必须修复"),
+        ("错误", "应当修复"),
+        ("警告", "建议修复"),
+        ("提示", "仅供参考"),
+    ],
+    all_issues: "全部问题",
+    severity: "级别",
+    code: "代码",
+    message: "信息",
+    how_to_fix: "修复方法",
+    about_this_report: "关于本报告",
+    limitations: "局限性",
+    learn_more: "了解更多",
+};
+
+pub const ES: ReportText = ReportText {
+    title: "Informe de auditoría de Watchy",
+    agent: "Agente",
+    overall_score: "Puntuación global",
+    audited_on: "Auditado el",
+    valid_until: "Válido hasta",
+    block: "Bloque",
+    what_this_audit_covers: "Qué cubre esta auditoría",
+    score_breakdown: "Desglose de la puntuación",
+    overall: "Global",
+    component: "Componente",
+    score: "Puntuación",
+    weight: "Peso",
+    endpoint_availability: "Disponibilidad de endpoints",
+    endpoint_performance: "Rendimiento de endpoints",
+    security: "Seguridad",
+    metadata: "Metadatos",
+    onchain: "On-chain",
+    consistency: "Consistencia",
+    content: "Contenido",
+    verdict: "Veredicto",
+    incomplete_audit: "Auditoría incompleta",
+    incomplete_audit_note: "Algunas fases no se completaron. Las fases fallidas se ejecutaron y se puntúan según lo encontrado; las omitidas no se ejecutaron y no cuentan en la puntuación global.",
+    phase: "Fase",
+    reason: "Motivo",
+    failed: "❌ Fallida",
+    skipped: "⏭️ Omitida",
+    verdicts: [
+        "**Excelente** - El agente supera todas las comprobaciones críticas",
+        "**Bueno** - El agente supera la mayoría de las comprobaciones con problemas menores",
+        "**Aceptable** - El agente tiene problemas que deberían resolverse",
+        "**Deficiente** - El agente tiene problemas importantes",
+        "**Crítico** - El agente falla varias comprobaciones críticas",
+    ],
+    verdict_explanations: [
+        "Este agente tiene una infraestructura excelente. Todos los endpoints responden, los metadatos están completos y la seguridad básica está en orden. Desde el punto de vista de la infraestructura es seguro interactuar con él.",
+        "Este agente tiene una infraestructura sólida con algunos problemas menores. Conviene revisar las advertencias de abajo, pero en general está bien configurado.",
+        "Este agente tiene problemas apreciables que pueden afectar a su fiabilidad. Revise los problemas indicados y valore si afectan a su caso de uso.",
+        "Este agente tiene problemas de infraestructura importantes. Proceda con precaución y revise todos los problemas con atención.",
+        "Este agente tiene problemas críticos que lo hacen poco fiable. Recomendamos no interactuar con él hasta que se resuelvan.",
+    ],
+    agent_identity: "Identidad del agente",
+    agent_history: "Historial del agente",
+    agent_claims: "Lo que declara el agente",
+    declared_services: "Servicios declarados",
+    detailed_results: "Resultados detallados",
+    onchain_verification: "Verificación on-chain",
+    metadata_compliance: "Cumplimiento de los metadatos",
+    endpoint_testing: "Pruebas de endpoints",
+    security_analysis: "Análisis de seguridad",
+    consistency_analysis: "Análisis de consistencia",
+    content_quality: "Calidad del contenido",
+    property: "Propiedad",
+    value: "Valor",
+    check: "Comprobación",
+    result: "Resultado",
+    description: "Descripción",
+    why_it_matters: "Por qué importa",
+    what_we_compare: "Qué comparamos",
+    details: "Detalles",
+    pass: "✅ Correcto",
+    fail: "❌ Fallo",
+    yes: "Sí",
+    no: "No",
+    issues_found: "Problemas encontrados",
+    severity_legend: [
+        ("críticos", "Deben corregirse"),
+        ("errores", "Deberían corregirse"),
+        ("advertencias", "Conviene corregirlas"),
+        ("informativos", "Para su información"),
+    ],
+    all_issues: "Todos los problemas",
+    severity: "Gravedad",
+    code: "Código",
+    message: "Mensaje",
+    how_to_fix: "Cómo corregirlo",
+    about_this_report: "Acerca de este informe",
+    limitations: "Limitaciones",
+    learn_more: "Más información",
+};
+
+const ZH_ISSUES: &[(&str, &str)] = &[
+    ("METADATA_FETCH_FAILED", "无法获取元数据"),
+    ("MISSING_REQUIRED_FIELDS", "缺少必填字段"),
+    ("INVALID_TYPE", "type 字段错误"),
+    ("REGISTRATION_MISMATCH", "未列出该注册信息"),
+    ("INVALID_REGISTRY_ID", "注册表标识格式错误"),
+    ("MISSING_ACTIVE", "缺少 active 标志"),
+    ("MISSING_SERVICES", "未声明服务"),
+    ("MISSING_SUPPORTEDTRUST", "未声明信任模型"),
+    ("MISSING_UPDATEDAT", "缺少更新时间"),
+    ("INVALID_URLS", "URL 格式错误"),
+    ("SECRET_LEAKED", "凭证已公开泄露"),
+    ("INSECURE_METADATA_URI", "元数据通过明文 HTTP 提供"),
+    ("MUTABLE_METADATA_HOST", "元数据托管在可变主机上"),
+    ("DATA_URI_METADATA", "元数据为内联 data: URI"),
+    ("OVERSIZED_DATA_URI", "内联元数据过大"),
+    ("UNKNOWN_METADATA_STORAGE", "未知的 URI 协议"),
+    ("DID_UNRESOLVABLE", "DID 无法解析"),
+    ("DID_METHOD_UNSUPPORTED", "不支持解析的 DID 方法"),
+    ("CREDENTIAL_INVALID_SIGNATURE", "凭证签名无效"),
+    ("CREDENTIAL_UNVERIFIED", "凭证未验证"),
+    ("CREDENTIAL_EXPIRED", "凭证已过期"),
+    ("AGENT_NOT_FOUND", "智能体未注册"),
+    ("NO_WALLET", "未设置智能体钱包"),
+    ("MUTABLE_METADATA_URI", "tokenURI 不是内容寻址"),
+    ("FREQUENT_URI_CHANGES", "tokenURI 变更频繁"),
+    ("AGENT_HISTORY_UNAVAILABLE", "未能获取历史记录"),
+    ("REGISTRATION_TOKEN_NOT_FOUND", "列出的注册不存在"),
+    ("REGISTRATION_URI_MISMATCH", "各注册指向不同的元数据"),
+    ("REGISTRATION_OWNER_MISMATCH", "各注册的所有者不同"),
+    ("OWNER_RECENTLY_CREATED", "所有者地址较新"),
+    ("OWNER_LOW_ACTIVITY", "所有者地址几乎未使用"),
+    ("WALLET_IS_OWNER", "钱包复用了所有者密钥"),
+    ("REPUTATION_NO_FEEDBACK", "声明信誉信任但没有反馈"),
+    ("CRYPTO_ECONOMIC_NO_WALLET", "声明加密经济信任但没有钱包"),
+    ("CRYPTO_ECONOMIC_UNFUNDED", "代理钱包余额为空"),
+    ("ENDPOINT_UNREACHABLE", "端点无法访问"),
+    ("ENDPOINT_TRANSIENT_FAILURE", "端点出现暂时性故障"),
+    ("ENDPOINT_NOT_PROBED", "端点未探测"),
+    ("ENDPOINT_RATE_LIMITED", "端点限制了请求速率"),
+    ("IPV6_UNREACHABLE", "IPv6 不可达"),
+    ("IPV4_UNREACHABLE", "IPv4 不可达"),
+    ("WS_NO_PONG", "WebSocket 不响应 ping"),
+    ("WS_NOT_ENCRYPTED", "WebSocket 未加密"),
+    ("GRPC_NOT_SERVING", "gRPC 服务未就绪"),
+    ("GRPC_HEALTH_UNIMPLEMENTED", "未实现 gRPC 健康检查"),
+    ("GRPC_HEALTH_FAILED", "gRPC 健康检查失败"),
+    ("GRPC_REFLECTION_UNAVAILABLE", "gRPC 反射不可用"),
+    ("GRPC_SERVICES_MISMATCH", "声明的 gRPC 服务未提供"),
+    ("HIGH_LATENCY", "响应缓慢"),
+    ("INVALID_JSON", "JSON 无效"),
+    ("UNEXPECTED_CONTENT_TYPE", "Content-Type 不符合预期"),
+    ("RESPONSE_TOO_LARGE", "响应过大"),
+    ("A2A_FETCH_FAILED", "无法获取智能体卡片"),
+    ("A2A_MISSING_NAME", "智能体卡片缺少名称"),
+    ("A2A_SKILLS_MISMATCH", "技能与智能体卡片不一致"),
+    ("MCP_TOOLS_MISMATCH", "工具与 MCP 清单不一致"),
+    ("MCP_TOOL_DRIFT", "MCP 工具模式已变更"),
+    ("HTTP2_NOT_SUPPORTED", "不支持 HTTP/2"),
+    ("COMPRESSION_NOT_SUPPORTED", "不支持压缩"),
+    ("KEEP_ALIVE_DISABLED", "连接未复用"),
+    ("NO_HTTPS", "未使用 HTTPS"),
+    ("NO_HTTPS_ENDPOINTS", "没有 HTTPS 端点"),
+    ("SECURITY_NOT_PROBED", "未评估安全性"),
+    ("HTTP_NOT_REDIRECTED", "HTTP 未重定向到 HTTPS"),
+    ("MISSING_SECURITY_HEADERS", "缺少安全响应头"),
+    ("TLS_CHECK_FAILED", "TLS 握手失败"),
+    ("CERT_EXPIRED", "证书已过期"),
+    ("CERT_EXPIRING_SOON", "证书即将过期"),
+    ("CERT_UNTRUSTED", "证书不受信任"),
+    ("CERT_HOSTNAME_MISMATCH", "证书主机名不匹配"),
+    ("WEAK_CERT_KEY", "证书密钥强度不足"),
+    ("SECURITY_TXT_MISSING", "缺少 security.txt"),
+    ("SECURITY_TXT_EXPIRED", "security.txt 已过期"),
+    ("SECURITY_TXT_NO_EXPIRES", "security.txt 缺少 Expires"),
+    ("URL_FLAGGED", "URL 被标记为恶意"),
+    ("URL_REPUTATION_UNAVAILABLE", "信誉查询失败"),
+    ("TEE_ATTESTATION_MISSING", "声明 TEE 信任但没有证明"),
+    ("TEE_ATTESTATION_UNREACHABLE", "TEE 证明无法访问"),
+    ("NAME_MISMATCH_A2A", "名称与智能体卡片不一致"),
+    ("NAME_MISMATCH_MCP", "名称与 MCP 服务器不一致"),
+    ("A2A_SKILL_NOT_FOUND", "声明的技能不存在"),
+    ("A2A_VERSION_MISMATCH", "A2A 版本不一致"),
+    ("MCP_TOOL_NOT_FOUND", "声明的工具不存在"),
+    ("MCP_VERSION_MISMATCH", "MCP 版本不一致"),
+    ("IMAGE_INACCESSIBLE", "图片无法访问"),
+    ("IMAGE_TOO_LARGE", "图片过大"),
+    ("IMAGE_URL_BLOCKED", "未获取图片 URL"),
+    ("AUTHOR_IDENTITY_MISMATCH", "作者 ENS 名称不匹配"),
+    ("AUTHOR_IDENTITY_UNVERIFIED", "作者 ENS 名称未验证"),
+    ("DESCRIPTION_TOO_SHORT", "描述过短"),
+    ("DESCRIPTION_PLACEHOLDER", "描述为占位文本"),
+    ("DESCRIPTION_LOW_QUALITY", "描述质量较低"),
+    ("NO_CONTACT_INFO", "缺少联系方式"),
+    ("UNKNOWN_SKILL_DOMAIN", "技能不在 OASF 分类中"),
+    ("X402_INVALID", "x402 配置错误"),
+    ("X402_PAYTO_MISMATCH", "x402 收款地址与代理钱包无关"),
+    ("X402_PAYTO_UNVERIFIED", "x402 收款地址未验证"),
+    ("X402_ASSET_NOT_FOUND", "x402 支付资产不存在"),
+    ("X402_UNKNOWN_ASSET", "x402 支付资产不是已知稳定币"),
+    ("X402_UNSUPPORTED_NETWORK", "x402 网络无法验证"),
+    ("X402_PRICE_ZERO", "x402 价格为零"),
+    ("X402_PRICE_INVALID", "x402 价格不是有效金额"),
+    ("X402_PRICE_EXCESSIVE", "x402 价格高得不合理"),
+    ("DOCS_UNREACHABLE", "文档链接无法访问"),
+    ("DOCS_EMPTY", "文档页面为空或为停放域名"),
+    ("LLM_CLAIM_INCONSISTENT", "声明内容似乎相互矛盾"),
+    ("SOURCE_CODE_NOT_FOUND", "sourceCode 仓库不存在"),
+    ("SOURCE_CODE_PRIVATE", "sourceCode 仓库为私有"),
+    ("SOURCE_CODE_NAME_MISMATCH", "sourceCode 未提及该代理"),
+    ("SOURCE_COMMIT_NOT_FOUND", "声明的提交不在仓库中"),
+    ("SOURCE_RELEASE_NOT_FOUND", "声明的版本不在仓库中"),
+    ("AUDIT_DEADLINE_EXCEEDED", "检查因超时中止"),
+];
+
+const ES_ISSUES: &[(&str, &str)] = &[
+    ("METADATA_FETCH_FAILED", "Metadatos inaccesibles"),
+    ("MISSING_REQUIRED_FIELDS", "Faltan campos obligatorios"),
+    ("INVALID_TYPE", "Campo type incorrecto"),
+    ("REGISTRATION_MISMATCH", "Registro no incluido"),
+    ("INVALID_REGISTRY_ID", "Identificador de registro mal formado"),
+    ("MISSING_ACTIVE", "Sin indicador active"),
+    ("MISSING_SERVICES", "Sin servicios"),
+    ("MISSING_SUPPORTEDTRUST", "Sin modelos de confianza"),
+    ("MISSING_UPDATEDAT", "Sin fecha de actualización"),
+    ("INVALID_URLS", "URLs mal formadas"),
+    ("SECRET_LEAKED", "Credencial publicada"),
+    ("INSECURE_METADATA_URI", "Metadatos servidos por HTTP sin cifrar"),
+    ("MUTABLE_METADATA_HOST", "Metadatos en un host mutable"),
+    ("DATA_URI_METADATA", "Metadatos en una URI data: en línea"),
+    ("OVERSIZED_DATA_URI", "Metadatos en línea demasiado grandes"),
+    ("UNKNOWN_METADATA_STORAGE", "Esquema de URI desconocido"),
+    ("DID_UNRESOLVABLE", "DID no resoluble"),
+    ("DID_METHOD_UNSUPPORTED", "Método DID no resoluble"),
+    ("CREDENTIAL_INVALID_SIGNATURE", "Firma de credencial no válida"),
+    ("CREDENTIAL_UNVERIFIED", "Credencial no verificada"),
+    ("CREDENTIAL_EXPIRED", "Credencial caducada"),
+    ("AGENT_NOT_FOUND", "Agente no registrado"),
+    ("NO_WALLET", "Sin wallet del agente"),
+    ("MUTABLE_METADATA_URI", "tokenURI sin direccionamiento por contenido"),
+    ("FREQUENT_URI_CHANGES", "Cambios frecuentes del tokenURI"),
+    ("AGENT_HISTORY_UNAVAILABLE", "Historial no obtenido"),
+    ("REGISTRATION_TOKEN_NOT_FOUND", "El registro listado no existe"),
+    ("REGISTRATION_URI_MISMATCH", "Los registros apuntan a metadatos distintos"),
+    ("REGISTRATION_OWNER_MISMATCH", "Los registros tienen propietarios distintos"),
+    ("OWNER_RECENTLY_CREATED", "Dirección del propietario reciente"),
+    ("OWNER_LOW_ACTIVITY", "Dirección del propietario apenas usada"),
+    ("WALLET_IS_OWNER", "La wallet reutiliza la clave del propietario"),
+    ("REPUTATION_NO_FEEDBACK", "Confianza por reputación sin valoraciones"),
+    ("CRYPTO_ECONOMIC_NO_WALLET", "Confianza criptoeconómica sin wallet"),
+    ("CRYPTO_ECONOMIC_UNFUNDED", "La wallet del agente está vacía"),
+    ("ENDPOINT_UNREACHABLE", "Endpoint inaccesible"),
+    ("ENDPOINT_TRANSIENT_FAILURE", "Fallo transitorio del endpoint"),
+    ("ENDPOINT_NOT_PROBED", "Endpoint no sondeado"),
+    ("ENDPOINT_RATE_LIMITED", "Endpoint con límite de solicitudes"),
+    ("IPV6_UNREACHABLE", "Inaccesible por IPv6"),
+    ("IPV4_UNREACHABLE", "Inaccesible por IPv4"),
+    ("WS_NO_PONG", "WebSocket no responde a ping"),
+    ("WS_NOT_ENCRYPTED", "WebSocket sin cifrar"),
+    ("GRPC_NOT_SERVING", "Servidor gRPC no disponible"),
+    ("GRPC_HEALTH_UNIMPLEMENTED", "Sin servicio de salud gRPC"),
+    ("GRPC_HEALTH_FAILED", "Fallo en la comprobación de salud gRPC"),
+    ("GRPC_REFLECTION_UNAVAILABLE", "Sin reflexión gRPC"),
+    ("GRPC_SERVICES_MISMATCH", "Servicios gRPC declarados no ofrecidos"),
+    ("HIGH_LATENCY", "Respuestas lentas"),
+    ("INVALID_JSON", "JSON no válido"),
+    ("UNEXPECTED_CONTENT_TYPE", "Content-Type inesperado"),
+    ("RESPONSE_TOO_LARGE", "Respuesta demasiado grande"),
+    ("A2A_FETCH_FAILED", "Tarjeta del agente no disponible"),
+    ("A2A_MISSING_NAME", "La tarjeta del agente no tiene nombre"),
+    ("A2A_SKILLS_MISMATCH", "Las habilidades difieren de la tarjeta del agente"),
+    ("MCP_TOOLS_MISMATCH", "Las herramientas difieren del manifiesto MCP"),
+    ("MCP_TOOL_DRIFT", "Cambió el esquema de una herramienta MCP"),
+    ("HTTP2_NOT_SUPPORTED", "Sin HTTP/2"),
+    ("COMPRESSION_NOT_SUPPORTED", "Sin compresión"),
+    ("KEEP_ALIVE_DISABLED", "Conexiones no reutilizadas"),
+    ("NO_HTTPS", "Sin HTTPS"),
+    ("NO_HTTPS_ENDPOINTS", "Ningún endpoint HTTPS"),
+    ("SECURITY_NOT_PROBED", "Seguridad no evaluada"),
+    ("HTTP_NOT_REDIRECTED", "HTTP no redirige a HTTPS"),
+    ("MISSING_SECURITY_HEADERS", "Faltan cabeceras de seguridad"),
+    ("TLS_CHECK_FAILED", "Fallo en el handshake TLS"),
+    ("CERT_EXPIRED", "Certificado caducado"),
+    ("CERT_EXPIRING_SOON", "El certificado caduca pronto"),
+    ("CERT_UNTRUSTED", "Certificado no confiable"),
+    ("CERT_HOSTNAME_MISMATCH", "El certificado no cubre el host"),
+    ("WEAK_CERT_KEY", "Clave del certificado débil"),
+    ("SECURITY_TXT_MISSING", "Sin security.txt"),
+    ("SECURITY_TXT_EXPIRED", "security.txt caducado"),
+    ("SECURITY_TXT_NO_EXPIRES", "security.txt sin Expires"),
+    ("URL_FLAGGED", "URL marcada como maliciosa"),
+    ("URL_REPUTATION_UNAVAILABLE", "Falló la consulta de reputación"),
+    ("TEE_ATTESTATION_MISSING", "Confianza TEE sin atestación"),
+    ("TEE_ATTESTATION_UNREACHABLE", "Atestación TEE inaccesible"),
+    ("NAME_MISMATCH_A2A", "El nombre difiere de la tarjeta del agente"),
+    ("NAME_MISMATCH_MCP", "El nombre difiere del servidor MCP"),
+    ("A2A_SKILL_NOT_FOUND", "Falta una habilidad declarada"),
+    ("A2A_VERSION_MISMATCH", "La versión A2A difiere"),
+    ("MCP_TOOL_NOT_FOUND", "Falta una herramienta declarada"),
+    ("MCP_VERSION_MISMATCH", "La versión MCP difiere"),
+    ("IMAGE_INACCESSIBLE", "Imagen no disponible"),
+    ("IMAGE_TOO_LARGE", "Imagen demasiado grande"),
+    ("IMAGE_URL_BLOCKED", "URL de la imagen no consultada"),
+    ("AUTHOR_IDENTITY_MISMATCH", "El nombre ENS del autor no coincide"),
+    ("AUTHOR_IDENTITY_UNVERIFIED", "Nombre ENS del autor sin verificar"),
+    ("DESCRIPTION_TOO_SHORT", "Descripción corta"),
+    ("DESCRIPTION_PLACEHOLDER", "Descripción de relleno"),
+    ("DESCRIPTION_LOW_QUALITY", "Descripción de baja calidad"),
+    ("NO_CONTACT_INFO", "Sin información de contacto"),
+    ("UNKNOWN_SKILL_DOMAIN", "Habilidad fuera de la taxonomía OASF"),
+    ("X402_INVALID", "x402 mal configurado"),
+    ("X402_PAYTO_MISMATCH", "x402 paga a una billetera no relacionada"),
+    ("X402_PAYTO_UNVERIFIED", "Destinatario x402 sin verificar"),
+    ("X402_ASSET_NOT_FOUND", "El activo x402 no existe"),
+    ("X402_UNKNOWN_ASSET", "El activo x402 no es una stablecoin conocida"),
+    ("X402_UNSUPPORTED_NETWORK", "Red x402 no verificable"),
+    ("X402_PRICE_ZERO", "El precio x402 es cero"),
+    ("X402_PRICE_INVALID", "El precio x402 no es un importe válido"),
+    ("X402_PRICE_EXCESSIVE", "Precio x402 excesivamente alto"),
+    ("DOCS_UNREACHABLE", "Documentación inaccesible"),
+    ("DOCS_EMPTY", "Documentación vacía o dominio aparcado"),
+    ("LLM_CLAIM_INCONSISTENT", "Declaraciones aparentemente contradictorias"),
+    ("SOURCE_CODE_NOT_FOUND", "Repositorio de sourceCode inexistente"),
+    ("SOURCE_CODE_PRIVATE", "Repositorio de sourceCode privado"),
+    ("SOURCE_CODE_NAME_MISMATCH", "sourceCode no menciona al agente"),
+    ("SOURCE_COMMIT_NOT_FOUND", "Commit declarado no está en el repositorio"),
+    ("SOURCE_RELEASE_NOT_FOUND", "Versión declarada no está en el repositorio"),
+    ("AUDIT_DEADLINE_EXCEEDED", "Comprobaciones interrumpidas"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_catalog_code_is_translated() {
+        for entry in catalog::CATALOG {
+            for lang in [Lang::Zh, Lang::Es] {
+                assert!(
+                    lang.issue_summary(entry.code).is_some(),
+                    "{} has no {} summary",
+                    entry.code,
+                    lang.as_str()
+                );
+            }
+        }
+        assert_eq!(Lang::parse("ZH"), Some(Lang::Zh));
+        assert_eq!(Lang::parse("fr"), None);
+    }
+
+    #[test]
+    fn test_localized_report() {
+        let mut report = crate::types::AuditReport::new(1, 8453, "0x8004", String::new(), None);
+        report.lang = Lang::Es;
+        report.checks.onchain.issues.push(crate::types::Issue::new(
+            crate::types::Severity::Warning,
+            "NO_WALLET",
+            "Agent has no wallet configured",
+        ));
+
+        let md = super::super::generate_markdown_report(&report, None);
+        assert!(md.starts_with("# Informe de auditoría de Watchy"));
+        assert!(md.contains("**Sin wallet del agente**: Agent has no wallet configured"));
+        assert!(md.contains("**1 advertencias** - Conviene corregirlas"));
+    }
+}
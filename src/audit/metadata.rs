@@ -1,6 +1,12 @@
 use tracing::{debug, warn};
 
-use crate::types::{AgentMetadata, WatchyError};
+use crate::events::{AuditEvent, EventKind, EventLog};
+use crate::http::{read_body_limited, BodyError};
+use sha2::{Digest, Sha256};
+
+use crate::types::{
+    AgentMetadata, Issue, MetadataSnapshot, MetadataStorage, Severity, StorageCheck, WatchyError,
+};
 
 /// IPFS gateways in order of preference
 const IPFS_GATEWAYS: &[&str] = &[
@@ -40,6 +46,7 @@ fn resolve_uri_with_fallbacks(uri: &str) -> Vec<String> {
 pub async fn fetch_metadata(
     client: &reqwest::Client,
     uri: &str,
+    events: &EventLog,
 ) -> Result<AgentMetadata, WatchyError> {
     // Handle data: URLs (inline base64 JSON)
     if let Some(data_content) = uri.strip_prefix("data:") {
@@ -66,10 +73,29 @@ pub async fn fetch_metadata(
                     metadata.name.as_deref().unwrap_or("unknown"),
                     url
                 );
+                events
+                    .record(
+                        AuditEvent::new(EventKind::GatewayUsed, format!("Fetched metadata from {}", url))
+                            .with_phase("metadata")
+                            .with_data(serde_json::json!({ "url": url })),
+                    )
+                    .await;
                 return Ok(metadata);
             }
             Err(e) => {
                 warn!("Gateway {} failed: {}", url, e);
+                if i + 1 < urls.len() {
+                    events
+                        .record(
+                            AuditEvent::new(
+                                EventKind::GatewayFallback,
+                                format!("Gateway {}/{} failed, trying next", i + 1, urls.len()),
+                            )
+                            .with_phase("metadata")
+                            .with_data(serde_json::json!({ "url": url, "error": e })),
+                        )
+                        .await;
+                }
                 last_error = e;
                 // Continue to next gateway
             }
@@ -106,7 +132,7 @@ fn parse_data_uri(content: &str) -> Result<AgentMetadata, WatchyError> {
         let json_str = String::from_utf8(decoded)
             .map_err(|e| WatchyError::MetadataFetchFailed(format!("UTF-8 decode error: {}", e)))?;
 
-        let metadata: AgentMetadata = serde_json::from_str(&json_str)
+        let metadata = parse_metadata(json_str.as_bytes())
             .map_err(|e| WatchyError::MetadataFetchFailed(format!("JSON parse error: {}", e)))?;
 
         debug!(
@@ -122,7 +148,7 @@ fn parse_data_uri(content: &str) -> Result<AgentMetadata, WatchyError> {
         let decoded = urlencoding::decode(json_data)
             .map_err(|e| WatchyError::MetadataFetchFailed(format!("URL decode error: {}", e)))?;
 
-        let metadata: AgentMetadata = serde_json::from_str(&decoded)
+        let metadata = parse_metadata(decoded.as_bytes())
             .map_err(|e| WatchyError::MetadataFetchFailed(format!("JSON parse error: {}", e)))?;
 
         return Ok(metadata);
@@ -133,6 +159,116 @@ fn parse_data_uri(content: &str) -> Result<AgentMetadata, WatchyError> {
     ))
 }
 
+/// data: URIs above this size are flagged (stored in contract storage on every update)
+const MAX_DATA_URI_SIZE: usize = 4 * 1024;
+
+/// Classify where a metadata URI is stored
+///
+/// Returns the check and, if the storage choice is a concern, the issue to report.
+pub fn check_metadata_storage(uri: &str) -> (StorageCheck, Option<Issue>) {
+    let storage_type = classify_storage(uri);
+    let mut check = StorageCheck {
+        passed: true,
+        storage_type,
+        immutable: matches!(storage_type, MetadataStorage::Ipfs | MetadataStorage::Arweave),
+        size_bytes: None,
+    };
+
+    let issue = match storage_type {
+        MetadataStorage::Data => {
+            let size = uri.len();
+            check.size_bytes = Some(size);
+            if size > MAX_DATA_URI_SIZE {
+                check.passed = false;
+                Some(Issue::new(
+                    Severity::Warning,
+                    "OVERSIZED_DATA_URI",
+                    format!(
+                        "Inline data: URI metadata is {} bytes (recommended max {}); every update stores it on-chain. Consider IPFS or Arweave",
+                        size, MAX_DATA_URI_SIZE
+                    ),
+                ))
+            } else {
+                Some(Issue::new(
+                    Severity::Info,
+                    "DATA_URI_METADATA",
+                    "Metadata is stored inline as a data: URI; any change requires an on-chain transaction",
+                ))
+            }
+        }
+        MetadataStorage::Https => {
+            check.passed = false;
+            Some(Issue::new(
+                Severity::Warning,
+                "MUTABLE_METADATA_HOST",
+                "Metadata is hosted on a mutable HTTPS URL; its content can change without an on-chain update",
+            ))
+        }
+        MetadataStorage::Http => {
+            check.passed = false;
+            Some(Issue::new(
+                Severity::Error,
+                "INSECURE_METADATA_URI",
+                "Metadata is served over plain HTTP and can be modified in transit",
+            ))
+        }
+        MetadataStorage::Unknown => {
+            check.passed = false;
+            Some(Issue::new(
+                Severity::Warning,
+                "UNKNOWN_METADATA_STORAGE",
+                format!("Unrecognized metadata URI scheme: {}", truncate_uri(uri)),
+            ))
+        }
+        MetadataStorage::Ipfs | MetadataStorage::Arweave => None,
+    };
+
+    (check, issue)
+}
+
+fn classify_storage(uri: &str) -> MetadataStorage {
+    if uri.starts_with("data:") {
+        return MetadataStorage::Data;
+    }
+    if uri.starts_with("ipfs://") {
+        return MetadataStorage::Ipfs;
+    }
+    if uri.starts_with("ar://") {
+        return MetadataStorage::Arweave;
+    }
+
+    let Ok(parsed) = url::Url::parse(uri) else {
+        return MetadataStorage::Unknown;
+    };
+
+    // Gateway URLs are still content-addressed
+    let host = parsed.host_str().unwrap_or_default();
+    if parsed.path().starts_with("/ipfs/") || host.contains(".ipfs.") {
+        return MetadataStorage::Ipfs;
+    }
+    if ARWEAVE_GATEWAYS
+        .iter()
+        .any(|gateway| uri.starts_with(gateway))
+    {
+        return MetadataStorage::Arweave;
+    }
+
+    match parsed.scheme() {
+        "https" => MetadataStorage::Https,
+        "http" => MetadataStorage::Http,
+        _ => MetadataStorage::Unknown,
+    }
+}
+
+pub(crate) fn truncate_uri(uri: &str) -> String {
+    if uri.len() > 64 {
+        let end = (0..=64).rev().find(|&i| uri.is_char_boundary(i)).unwrap_or(0);
+        format!("{}...", &uri[..end])
+    } else {
+        uri.to_string()
+    }
+}
+
 /// Maximum metadata size in bytes (1 MB)
 const MAX_METADATA_SIZE: usize = 1024 * 1024;
 
@@ -153,35 +289,36 @@ async fn try_fetch_metadata(
         return Err(format!("HTTP {}", response.status()));
     }
 
-    // Check content-length if available
-    if let Some(content_length) = response.content_length() {
-        if content_length as usize > MAX_METADATA_SIZE {
-            return Err(format!(
-                "Metadata too large: {} bytes (max {} bytes)",
-                content_length, MAX_METADATA_SIZE
-            ));
-        }
-    }
-
-    // Read body with size limit
-    let bytes = response
-        .bytes()
+    // Read body with size limit (also enforced while streaming)
+    let bytes = read_body_limited(response, MAX_METADATA_SIZE)
         .await
-        .map_err(|e| format!("Failed to read response body: {}", e))?;
-
-    if bytes.len() > MAX_METADATA_SIZE {
-        return Err(format!(
-            "Metadata too large: {} bytes (max {} bytes)",
-            bytes.len(), MAX_METADATA_SIZE
-        ));
-    }
+        .map_err(|e| match e {
+            BodyError::TooLarge(limit) => format!("Metadata too large (max {} bytes)", limit),
+            e => e.to_string(),
+        })?;
 
-    let metadata: AgentMetadata = serde_json::from_slice(&bytes)
-        .map_err(|e| format!("JSON parse error: {}", e))?;
+    parse_metadata(&bytes).map_err(|e| format!("JSON parse error: {}", e))
+}
 
+/// Parse metadata JSON, keeping the raw document alongside
+fn parse_metadata(bytes: &[u8]) -> Result<AgentMetadata, serde_json::Error> {
+    let raw: serde_json::Value = serde_json::from_slice(bytes)?;
+    let mut metadata: AgentMetadata = serde_json::from_value(raw.clone())?;
+    metadata.raw = raw;
+    metadata.source = bytes.to_vec();
     Ok(metadata)
 }
 
+/// Content hash of the metadata bytes that were audited
+pub fn snapshot(metadata: &AgentMetadata) -> MetadataSnapshot {
+    MetadataSnapshot {
+        sha256: format!("0x{}", hex::encode(Sha256::digest(&metadata.source))),
+        size_bytes: metadata.source.len(),
+        uri: None,
+        url: None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -209,6 +346,38 @@ mod tests {
         assert_eq!(urls[0], "https://example.com/metadata.json");
     }
 
+    #[test]
+    fn test_metadata_storage_classification() {
+        assert_eq!(classify_storage("ipfs://bafkreitest123"), MetadataStorage::Ipfs);
+        assert_eq!(classify_storage("https://ipfs.io/ipfs/bafkreitest123"), MetadataStorage::Ipfs);
+        assert_eq!(classify_storage("ar://abc123xyz"), MetadataStorage::Arweave);
+        assert_eq!(classify_storage("https://arweave.net/abc123xyz"), MetadataStorage::Arweave);
+        assert_eq!(classify_storage("https://example.com/agent.json"), MetadataStorage::Https);
+        assert_eq!(classify_storage("http://example.com/agent.json"), MetadataStorage::Http);
+        assert_eq!(classify_storage("data:application/json,{}"), MetadataStorage::Data);
+    }
+
+    #[test]
+    fn test_metadata_immutability() {
+        assert!(check_metadata_storage("ipfs://bafkreitest123").0.immutable);
+        assert!(check_metadata_storage("https://arweave.net/abc123xyz").0.immutable);
+        assert!(!check_metadata_storage("https://example.com/agent.json").0.immutable);
+        assert!(!check_metadata_storage("data:application/json,{}").0.immutable);
+    }
+
+    #[test]
+    fn test_oversized_data_uri_flagged() {
+        let small = format!("data:application/json;base64,{}", "A".repeat(100));
+        let (check, issue) = check_metadata_storage(&small);
+        assert!(check.passed && !check.immutable);
+        assert_eq!(issue.unwrap().severity, Severity::Info);
+
+        let large = format!("data:application/json;base64,{}", "A".repeat(MAX_DATA_URI_SIZE));
+        let (check, issue) = check_metadata_storage(&large);
+        assert!(!check.passed);
+        assert_eq!(issue.unwrap().code, "OVERSIZED_DATA_URI");
+    }
+
     #[test]
     fn test_data_uri_base64() {
         use base64::Engine;
@@ -222,4 +391,17 @@ mod tests {
         let metadata = result.unwrap();
         assert_eq!(metadata.name, Some("Test Agent".to_string()));
     }
+
+    #[test]
+    fn test_snapshot_hashes_source_bytes() {
+        let json = r#"{"name": "Agent",  "description": "spacing is kept"}"#;
+        let metadata = parse_metadata(json.as_bytes()).unwrap();
+        let snapshot = snapshot(&metadata);
+        assert_eq!(snapshot.size_bytes, json.len());
+        assert_eq!(
+            snapshot.sha256,
+            format!("0x{}", hex::encode(Sha256::digest(json.as_bytes())))
+        );
+        assert!(snapshot.uri.is_none());
+    }
 }
@@ -1,11 +1,30 @@
+pub mod catalog;
+#[cfg(feature = "llm")]
+pub mod claims;
 pub mod consistency;
 pub mod content;
+pub mod credentials;
+pub mod documentation;
+pub mod dual_stack;
 pub mod endpoints;
 pub mod engine;
+pub mod grpc;
+pub mod i18n;
 pub mod metadata;
 pub mod onchain;
+pub mod owner_risk;
+pub mod policy;
+pub mod pricing;
 pub mod report;
+pub mod reputation;
+pub mod scoring;
+pub mod secrets;
 pub mod security;
+pub mod skills;
+pub mod source_code;
+pub mod trust;
+pub mod websocket;
+pub mod x402;
 
 pub use engine::AuditEngine;
 pub use report::generate_markdown_report;
@@ -1,8 +1,17 @@
+use alloy::rpc::types::Log;
+use futures_util::future::join_all;
+use std::collections::HashMap;
 use tracing::{debug, info, warn};
 
+use crate::blockchain::ens::EnsClient;
 use crate::blockchain::registry::RegistryClient;
-use crate::chains::get_all_rpcs;
-use crate::types::WatchyError;
+use crate::caip::AccountId;
+use crate::chains::{get_all_rpcs, get_chain, registry_start_block, ChainType};
+use crate::events::{AuditEvent, EventKind, EventLog};
+use crate::types::{
+    AgentHistory, Issue, Registration, RegistrationCheck, RegistrationStatus, RegistryEvent,
+    Severity, WatchyError,
+};
 
 /// On-chain data fetched for an agent
 pub struct OnchainData {
@@ -18,6 +27,7 @@ pub async fn fetch_onchain_data(
     chain_id: u64,
     agent_id: u64,
     registry_address: &str,
+    events: &EventLog,
 ) -> Result<OnchainData, WatchyError> {
     debug!(
         "Fetching on-chain data for agent {} from registry {} on chain {}",
@@ -34,6 +44,8 @@ pub async fn fetch_onchain_data(
     }
 
     let mut last_error = String::new();
+    // An RPC lagging behind may not know a new agent yet, so keep trying
+    let mut not_found = false;
 
     // Try each RPC until one succeeds
     for (i, rpc_url) in rpcs.iter().enumerate() {
@@ -44,16 +56,42 @@ pub async fn fetch_onchain_data(
                 if i > 0 {
                     info!("RPC {} succeeded after {} failures", rpc_url, i);
                 }
+                events
+                    .record(
+                        AuditEvent::new(EventKind::RpcUsed, format!("Fetched on-chain data from RPC {}", i + 1))
+                            .with_phase("onchain")
+                            .with_data(serde_json::json!({
+                                "rpc": rpc_url,
+                                "blockNumber": data.block_number,
+                            })),
+                    )
+                    .await;
                 return Ok(data);
             }
             Err(e) => {
                 warn!("RPC {} failed: {}", rpc_url, e);
+                not_found |= matches!(e, WatchyError::AgentNotFound(_));
                 last_error = e.to_string();
+                if i + 1 < rpcs.len() {
+                    events
+                        .record(
+                            AuditEvent::new(
+                                EventKind::RpcFailover,
+                                format!("RPC {}/{} failed, trying next", i + 1, rpcs.len()),
+                            )
+                            .with_phase("onchain")
+                            .with_data(serde_json::json!({ "rpc": rpc_url, "error": last_error })),
+                        )
+                        .await;
+                }
             }
         }
     }
 
-    Err(WatchyError::Internal(format!(
+    if not_found {
+        return Err(WatchyError::AgentNotFound(agent_id));
+    }
+    Err(WatchyError::BlockchainError(format!(
         "All {} RPCs failed for chain {}. Last error: {}",
         rpcs.len(),
         chain_id,
@@ -61,6 +99,298 @@ pub async fn fetch_onchain_data(
     )))
 }
 
+/// URI updates within this window count towards churn
+const URI_CHURN_WINDOW_DAYS: i64 = 30;
+
+/// More updates than this within the window is flagged
+const URI_CHURN_THRESHOLD: usize = 3;
+
+/// Registration and tokenURI update history with RPC failover
+pub async fn fetch_agent_history(
+    chain_id: u64,
+    registry_address: &str,
+    agent_id: u64,
+) -> Result<AgentHistory, WatchyError> {
+    let from_block = registry_start_block(chain_id);
+    let mut last_error = format!("No RPC URLs available for chain {}", chain_id);
+
+    for rpc_url in get_all_rpcs(chain_id) {
+        match try_fetch_agent_history(&rpc_url, registry_address, agent_id, from_block).await {
+            Ok(history) => return Ok(history),
+            Err(e) => {
+                warn!("History lookup via {} failed: {}", rpc_url, e);
+                last_error = e.to_string();
+            }
+        }
+    }
+    Err(WatchyError::BlockchainError(last_error))
+}
+
+async fn try_fetch_agent_history(
+    rpc_url: &str,
+    registry_address: &str,
+    agent_id: u64,
+    from_block: u64,
+) -> Result<AgentHistory, WatchyError> {
+    let registry = RegistryClient::new(rpc_url, registry_address)?;
+    let (registered, updates) = registry.agent_events(agent_id, from_block).await?;
+
+    // Logs rarely carry the block timestamp, so look blocks up (once each)
+    let mut timestamps = HashMap::new();
+    let registered = match registered {
+        Some((log, e)) => Some(
+            registry_event(&registry, &mut timestamps, log, format!("{:?}", e.owner), e.agentURI).await,
+        ),
+        None => None,
+    };
+    let mut uri_updates = Vec::new();
+    for (log, e) in updates {
+        uri_updates.push(
+            registry_event(&registry, &mut timestamps, log, format!("{:?}", e.updatedBy), e.newURI)
+                .await,
+        );
+    }
+
+    Ok(AgentHistory {
+        registered,
+        uri_updates,
+        from_block,
+    })
+}
+
+async fn registry_event(
+    registry: &RegistryClient,
+    timestamps: &mut HashMap<u64, Option<String>>,
+    log: Log,
+    actor: String,
+    uri: String,
+) -> RegistryEvent {
+    let block_number = log.block_number.unwrap_or_default();
+    let timestamp = match timestamps.get(&block_number) {
+        Some(timestamp) => timestamp.clone(),
+        None => {
+            let secs = match log.block_timestamp {
+                Some(secs) => Some(secs),
+                None => registry.block_timestamp(block_number).await.ok().flatten(),
+            };
+            let timestamp = secs
+                .and_then(|s| chrono::DateTime::from_timestamp(s as i64, 0))
+                .map(|t| t.to_rfc3339());
+            timestamps.insert(block_number, timestamp.clone());
+            timestamp
+        }
+    };
+
+    RegistryEvent {
+        block_number,
+        timestamp,
+        tx_hash: log.transaction_hash.map(|h| format!("0x{}", hex::encode(h))),
+        actor,
+        uri,
+    }
+}
+
+/// Warning when the tokenURI changed often in the last month
+pub fn uri_churn_issue(history: &AgentHistory, now: chrono::DateTime<chrono::Utc>) -> Option<Issue> {
+    let window_start = now - chrono::Duration::days(URI_CHURN_WINDOW_DAYS);
+    let recent = history
+        .uri_updates
+        .iter()
+        .filter_map(|u| u.timestamp.as_deref())
+        .filter_map(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+        .filter(|t| *t >= window_start)
+        .count();
+
+    (recent > URI_CHURN_THRESHOLD).then(|| Issue::new(
+        Severity::Warning,
+        "FREQUENT_URI_CHANGES",
+        format!(
+            "tokenURI changed {} times in the last {} days ({} total)",
+            recent,
+            URI_CHURN_WINDOW_DAYS,
+            history.uri_updates.len()
+        ),
+    ))
+}
+
+/// Most registrations looked up per audit; each costs RPC calls on its chain
+const MAX_REGISTRATIONS_CHECKED: usize = 10;
+
+/// Look up each registration the metadata lists on its own chain
+///
+/// Only runs when more than one is listed. The audited registration
+/// (`audited_registry`, `eip155:<chainId>:<address>`) isn't fetched again,
+/// and only the chain's configured EIP-8004 registry is trusted: any other
+/// contract could answer anything, so those stay `Unverified`. Lookups run
+/// concurrently.
+pub async fn check_registrations(
+    registrations: &[Registration],
+    audited_registry: &str,
+    audited_agent_id: u64,
+    audited: &OnchainData,
+) -> Vec<RegistrationCheck> {
+    if registrations.len() < 2 {
+        return vec![];
+    }
+    let audited_registry = audited_registry.parse::<AccountId>().ok();
+    let mut seen = Vec::new();
+    let mut checks = Vec::new();
+    let mut lookups = Vec::new();
+    for registration in registrations {
+        let registry = registration.registry();
+        let listed = match &registry {
+            Ok(registry) => registry.to_string(),
+            Err(_) => registration.agent_registry.to_lowercase(),
+        };
+        let key = (listed, registration.agent_id);
+        if seen.contains(&key) {
+            continue;
+        }
+        seen.push(key);
+        if checks.len() >= MAX_REGISTRATIONS_CHECKED {
+            break;
+        }
+
+        let mut check = RegistrationCheck {
+            agent_registry: registration.agent_registry.clone(),
+            agent_id: registration.agent_id,
+            chain_id: None,
+            status: RegistrationStatus::Unverified,
+            owner: None,
+            token_uri: None,
+            reason: None,
+        };
+        let registry = match registry {
+            Ok(registry) => registry,
+            Err(e) => {
+                check.reason = Some(e.to_string());
+                checks.push(check);
+                continue;
+            }
+        };
+        let Some(chain_id) = registry.chain.evm_chain_id() else {
+            check.reason = Some(format!("{} is not an EVM chain", registry.chain));
+            checks.push(check);
+            continue;
+        };
+        check.chain_id = Some(chain_id);
+
+        let is_audited =
+            audited_registry.as_ref() == Some(&registry) && registration.agent_id == audited_agent_id;
+        if is_audited {
+            check.status = RegistrationStatus::Audited;
+            check.owner = Some(audited.owner.clone());
+            check.token_uri = Some(audited.metadata_uri.clone());
+            checks.push(check);
+            continue;
+        }
+        let configured = get_chain(chain_id)
+            .filter(|chain| chain.chain_type == ChainType::Evm)
+            .and_then(|chain| chain.registry_address);
+        match configured {
+            None => check.reason = Some(format!("chain {} is not supported", chain_id)),
+            Some(address) if !address.eq_ignore_ascii_case(&registry.address) => {
+                check.reason = Some(format!(
+                    "{} is not the EIP-8004 registry on chain {}",
+                    registry.address, chain_id
+                ));
+            }
+            Some(_) => lookups.push((checks.len(), chain_id, registration.agent_id, registry.address)),
+        }
+        checks.push(check);
+    }
+
+    let events = EventLog::disabled();
+    let fetched = join_all(lookups.iter().map(|(_, chain_id, agent_id, address)| {
+        fetch_onchain_data(*chain_id, *agent_id, address, &events)
+    }))
+    .await;
+    for ((index, _, _, address), fetched) in lookups.iter().zip(fetched) {
+        let check = &mut checks[*index];
+        match fetched {
+            Ok(data) => {
+                check.status = if data.metadata_uri == audited.metadata_uri {
+                    RegistrationStatus::Consistent
+                } else {
+                    RegistrationStatus::UriMismatch
+                };
+                check.owner = Some(data.owner);
+                check.token_uri = Some(data.metadata_uri);
+            }
+            Err(WatchyError::AgentNotFound(_)) => check.status = RegistrationStatus::NotFound,
+            Err(e) => {
+                warn!("Registration {} lookup failed: {}", address, e);
+                check.reason = Some(e.to_string());
+            }
+        }
+    }
+    checks
+}
+
+/// Issues for registrations that point at no token or at other metadata, and
+/// for ones held by another owner than the audited registration
+pub fn registration_issues(checks: &[RegistrationCheck]) -> Vec<Issue> {
+    let audited_owner = checks
+        .iter()
+        .find(|check| check.status == RegistrationStatus::Audited)
+        .and_then(|check| check.owner.as_deref());
+    let mut issues = Vec::new();
+    for check in checks {
+        let name = format!("agent {} in {}", check.agent_id, check.agent_registry);
+        match check.status {
+            RegistrationStatus::NotFound => issues.push(Issue::new(
+                Severity::Warning,
+                "REGISTRATION_TOKEN_NOT_FOUND",
+                format!("Listed registration {} doesn't exist", name),
+            )),
+            RegistrationStatus::UriMismatch => issues.push(Issue::new(
+                Severity::Warning,
+                "REGISTRATION_URI_MISMATCH",
+                format!(
+                    "Listed registration {} points at different metadata ({})",
+                    name,
+                    super::metadata::truncate_uri(check.token_uri.as_deref().unwrap_or_default())
+                ),
+            )),
+            _ => {}
+        }
+        let other_owner = match (audited_owner, check.owner.as_deref()) {
+            (Some(audited), Some(owner)) => !audited.eq_ignore_ascii_case(owner),
+            _ => false,
+        };
+        if other_owner {
+            issues.push(Issue::new(
+                Severity::Info,
+                "REGISTRATION_OWNER_MISMATCH",
+                format!(
+                    "Listed registration {} is owned by {}",
+                    name,
+                    check.owner.as_deref().unwrap_or_default()
+                ),
+            ));
+        }
+    }
+    issues
+}
+
+/// Primary ENS name of an address, trying each RPC of the ENS chain
+///
+/// Lookup failures are logged and treated as "no name": ENS is cosmetic to the
+/// audit and must not fail it.
+pub async fn resolve_ens_name(ens_chain_id: u64, address: &str) -> Option<String> {
+    for rpc_url in get_all_rpcs(ens_chain_id) {
+        let result = match EnsClient::new(&rpc_url) {
+            Ok(client) => client.lookup_address(address).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(name) => return name,
+            Err(e) => warn!("ENS lookup for {} via {} failed: {}", address, rpc_url, e),
+        }
+    }
+    None
+}
+
 /// Try to fetch on-chain data from a single RPC
 async fn try_fetch_onchain_data(
     rpc_url: &str,
@@ -100,3 +430,72 @@ async fn try_fetch_onchain_data(
         block_number,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(timestamp: chrono::DateTime<chrono::Utc>) -> RegistryEvent {
+        RegistryEvent {
+            block_number: 1,
+            timestamp: Some(timestamp.to_rfc3339()),
+            tx_hash: None,
+            actor: "0x0000000000000000000000000000000000000001".to_string(),
+            uri: "ipfs://Qm".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_uri_churn_issue() {
+        let now = chrono::Utc::now();
+        let mut history = AgentHistory {
+            registered: None,
+            uri_updates: (0..4).map(|i| update(now - chrono::Duration::days(90 + i))).collect(),
+            from_block: 0,
+        };
+        assert!(uri_churn_issue(&history, now).is_none());
+
+        history
+            .uri_updates
+            .extend((1..=4).map(|i| update(now - chrono::Duration::days(i))));
+        let issue = uri_churn_issue(&history, now).unwrap();
+        assert_eq!(issue.code, "FREQUENT_URI_CHANGES");
+        assert!(issue.message.contains("4 times") && issue.message.contains("8 total"));
+    }
+
+    #[test]
+    fn test_registration_issues() {
+        let check = |agent_id, status, owner: &str, uri: &str| RegistrationCheck {
+            agent_registry: "eip155:8453:0x8004".to_string(),
+            agent_id,
+            chain_id: Some(8453),
+            status,
+            owner: Some(owner.to_string()),
+            token_uri: Some(uri.to_string()),
+            reason: None,
+        };
+        let checks = [
+            check(1, RegistrationStatus::Audited, "0xAbC", "ipfs://a"),
+            check(2, RegistrationStatus::Consistent, "0xabc", "ipfs://a"),
+            check(3, RegistrationStatus::UriMismatch, "0xdef", "ipfs://b"),
+            RegistrationCheck {
+                owner: None,
+                token_uri: None,
+                ..check(4, RegistrationStatus::NotFound, "", "")
+            },
+        ];
+        let issues = registration_issues(&checks);
+        let codes: Vec<&str> = issues
+            .iter()
+            .map(|issue| issue.code.as_str())
+            .collect();
+        assert_eq!(
+            codes,
+            [
+                "REGISTRATION_URI_MISMATCH",
+                "REGISTRATION_OWNER_MISMATCH",
+                "REGISTRATION_TOKEN_NOT_FOUND"
+            ]
+        );
+    }
+}
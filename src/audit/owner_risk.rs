@@ -0,0 +1,206 @@
+//! Owner address heuristics
+//!
+//! A registration whose owner address was created days ago, has barely
+//! transacted, or doubles as the agent's payment wallet isn't wrong, but it's
+//! worth knowing before trusting the agent. Everything here is informational
+//! and never moves the on-chain score.
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use tracing::{debug, warn};
+
+use crate::blockchain::account::{AccountActivity, AccountClient};
+use crate::chains::get_all_rpcs;
+use crate::types::{Issue, OwnerRisk, Severity, WatchyError};
+
+/// Etherscan multichain (v2) API, used for the owner's first transaction
+const ETHERSCAN_API_URL: &str = "https://api.etherscan.io/v2/api";
+
+/// Owners younger than this are flagged as recently created
+const RECENT_OWNER_DAYS: i64 = 30;
+
+/// Externally owned accounts that sent fewer transactions are flagged as fresh
+const FRESH_OWNER_TX_COUNT: u64 = 3;
+
+/// Owner nonce, balance and code with RPC failover
+pub async fn fetch_account_activity(
+    chain_id: u64,
+    address: &str,
+) -> Result<AccountActivity, WatchyError> {
+    let mut last_error = format!("No RPC URLs available for chain {}", chain_id);
+    for rpc_url in get_all_rpcs(chain_id) {
+        let result = match AccountClient::new(&rpc_url) {
+            Ok(client) => client.activity(address).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(activity) => return Ok(activity),
+            Err(e) => {
+                warn!("Account lookup for {} via {} failed: {}", address, rpc_url, e);
+                last_error = e.to_string();
+            }
+        }
+    }
+    Err(WatchyError::BlockchainError(last_error))
+}
+
+#[derive(Deserialize)]
+struct EtherscanResponse {
+    status: String,
+    #[serde(default)]
+    result: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct EtherscanTx {
+    #[serde(rename = "timeStamp")]
+    timestamp: String,
+}
+
+/// Time of the first transaction to or from an address (`None` if it has none)
+pub async fn first_seen(
+    client: &reqwest::Client,
+    api_key: &str,
+    chain_id: u64,
+    address: &str,
+) -> Result<Option<DateTime<Utc>>, String> {
+    debug!("Looking up first transaction of {} on chain {}", address, chain_id);
+
+    let response = client
+        .get(ETHERSCAN_API_URL)
+        .query(&[
+            ("chainid", chain_id.to_string().as_str()),
+            ("module", "account"),
+            ("action", "txlist"),
+            ("address", address),
+            ("startblock", "0"),
+            ("page", "1"),
+            ("offset", "1"),
+            ("sort", "asc"),
+            ("apikey", api_key),
+        ])
+        .send()
+        .await
+        .map_err(|e| format!("Explorer request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Explorer returned HTTP {}", response.status()));
+    }
+
+    let parsed: EtherscanResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid explorer response: {}", e))?;
+
+    // status "0" with an empty list just means no transactions
+    let txs: Vec<EtherscanTx> = match parsed.result {
+        serde_json::Value::Array(_) => serde_json::from_value(parsed.result)
+            .map_err(|e| format!("Invalid explorer response: {}", e))?,
+        other if parsed.status == "1" => return Err(format!("Unexpected explorer result: {}", other)),
+        other => return Err(format!("Explorer query failed: {}", other)),
+    };
+
+    Ok(txs
+        .first()
+        .and_then(|tx| tx.timestamp.parse::<i64>().ok())
+        .and_then(|secs| DateTime::from_timestamp(secs, 0)))
+}
+
+/// Summarize the owner's activity and raise Info issues for anything notable
+pub fn assess(
+    owner: &str,
+    wallet: Option<&str>,
+    activity: &AccountActivity,
+    first_seen: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> (OwnerRisk, Vec<Issue>) {
+    let mut issues = Vec::new();
+    let age_days = first_seen.map(|t| (now - t).num_days().max(0));
+
+    let recent = age_days.is_some_and(|days| days < RECENT_OWNER_DAYS);
+    if let Some(days) = age_days.filter(|_| recent) {
+        issues.push(Issue::new(
+            Severity::Info,
+            "OWNER_RECENTLY_CREATED",
+            format!(
+                "Owner address created {} ago",
+                match days {
+                    0 => "less than a day".to_string(),
+                    1 => "1 day".to_string(),
+                    n => format!("{} days", n),
+                }
+            ),
+        ));
+    }
+
+    let low_activity = !activity.is_contract && activity.tx_count < FRESH_OWNER_TX_COUNT;
+    if low_activity {
+        issues.push(Issue::new(
+            Severity::Info,
+            "OWNER_LOW_ACTIVITY",
+            format!(
+                "Owner address has sent {} transaction{}",
+                activity.tx_count,
+                if activity.tx_count == 1 { "" } else { "s" }
+            ),
+        ));
+    }
+
+    let wallet_is_owner = wallet.is_some_and(|w| w.eq_ignore_ascii_case(owner));
+    if wallet_is_owner {
+        issues.push(Issue::new(
+            Severity::Info,
+            "WALLET_IS_OWNER",
+            "Agent wallet is the owner address; a dedicated wallet keeps payments away from the key that controls the registration",
+        ));
+    }
+
+    let risk = OwnerRisk {
+        address: owner.to_string(),
+        tx_count: activity.tx_count,
+        balance_wei: activity.balance.to_string(),
+        is_contract: activity.is_contract,
+        first_seen: first_seen.map(|t| t.to_rfc3339()),
+        age_days: age_days.map(|d| d as u64),
+        fresh: recent || low_activity,
+        wallet_is_owner,
+    };
+    (risk, issues)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::U256;
+
+    #[test]
+    fn test_assess() {
+        let now = Utc::now();
+        let owner = "0x1111111111111111111111111111111111111111";
+        let activity = AccountActivity {
+            tx_count: 1,
+            balance: U256::ZERO,
+            is_contract: false,
+        };
+
+        let (risk, issues) = assess(
+            owner,
+            Some(&owner.to_uppercase().replace("0X", "0x")),
+            &activity,
+            Some(now - chrono::Duration::days(2)),
+            now,
+        );
+        assert!(risk.fresh && risk.wallet_is_owner);
+        assert_eq!(risk.age_days, Some(2));
+        let codes: Vec<&str> = issues.iter().map(|i| i.code.as_str()).collect();
+        assert_eq!(codes, vec!["OWNER_RECENTLY_CREATED", "OWNER_LOW_ACTIVITY", "WALLET_IS_OWNER"]);
+        assert_eq!(issues[0].message, "Owner address created 2 days ago");
+
+        let established = AccountActivity {
+            tx_count: 250,
+            ..activity
+        };
+        let (risk, issues) = assess(owner, None, &established, Some(now - chrono::Duration::days(400)), now);
+        assert!(!risk.fresh);
+        assert!(issues.is_empty());
+    }
+}
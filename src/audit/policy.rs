@@ -0,0 +1,297 @@
+//! Outbound probe policy
+//!
+//! In locked-down deployments the operator can restrict endpoint probing to an
+//! allowlist of domains and/or autonomous systems. Endpoints outside it are
+//! recorded as "not probed by policy" instead of being contacted. ASN rules
+//! send the endpoint's addresses to a third-party whois service, so they only
+//! apply when the operator opts in with `PROBE_ASN_LOOKUP`.
+//!
+//! Agent operators can in turn publish a [`HostPolicy`] at
+//! `/.well-known/watchy.json` to opt out of probing or slow it down.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::net::IpAddr;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tracing::{debug, warn};
+
+use crate::http::read_body_limited;
+
+/// IP-to-ASN whois service (Team Cymru)
+const ASN_WHOIS_SERVER: &str = "whois.cymru.com:43";
+const ASN_LOOKUP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Allowlist for endpoint probing (disabled when both lists are empty)
+#[derive(Debug, Clone, Default)]
+pub struct ProbePolicy {
+    /// Domains allowed together with their subdomains ("*." prefix optional)
+    pub domains: Vec<String>,
+    /// Autonomous system numbers whose addresses may be probed
+    pub asns: Vec<u32>,
+    /// Whether resolved addresses may be sent to the whois service to match `asns`
+    pub asn_lookup: bool,
+}
+
+impl ProbePolicy {
+    /// Load from `PROBE_ALLOWLIST_DOMAINS` and `PROBE_ALLOWLIST_ASNS` (comma-separated);
+    /// ASNs need `PROBE_ASN_LOOKUP=true`
+    pub fn from_env() -> anyhow::Result<Self> {
+        let domains = env::var("PROBE_ALLOWLIST_DOMAINS")
+            .map(|value| {
+                value
+                    .split(',')
+                    .map(|d| d.trim().trim_start_matches("*.").trim_end_matches('.').to_lowercase())
+                    .filter(|d| !d.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let asns = match env::var("PROBE_ALLOWLIST_ASNS") {
+            Ok(value) => value
+                .split(',')
+                .map(|a| a.trim())
+                .filter(|a| !a.is_empty())
+                .map(|a| {
+                    let digits = a.strip_prefix("AS").or_else(|| a.strip_prefix("as")).unwrap_or(a);
+                    digits
+                        .parse()
+                        .map_err(|_| anyhow::anyhow!("Invalid ASN '{}' in PROBE_ALLOWLIST_ASNS", a))
+                })
+                .collect::<anyhow::Result<Vec<u32>>>()?,
+            Err(_) => Vec::new(),
+        };
+
+        let asn_lookup = env::var("PROBE_ASN_LOOKUP")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(false);
+        if !asns.is_empty() && !asn_lookup {
+            anyhow::bail!(
+                "PROBE_ALLOWLIST_ASNS looks up endpoint addresses at {}; set PROBE_ASN_LOOKUP=true to allow it",
+                ASN_WHOIS_SERVER
+            );
+        }
+
+        Ok(Self {
+            domains,
+            asns,
+            asn_lookup,
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.domains.is_empty() || !self.asns.is_empty()
+    }
+
+    /// Check whether an endpoint may be probed. Returns the reason if not.
+    pub async fn check(&self, endpoint: &str) -> Result<(), String> {
+        if !self.is_enabled() {
+            return Ok(());
+        }
+
+        let url = url::Url::parse(endpoint).map_err(|e| format!("Invalid URL: {}", e))?;
+        let host = url
+            .host_str()
+            .ok_or_else(|| "URL has no host".to_string())?
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_lowercase();
+
+        if self.domains.iter().any(|domain| domain_matches(domain, &host)) {
+            return Ok(());
+        }
+
+        if !self.asns.is_empty() && self.asn_lookup {
+            let port = url.port_or_known_default().unwrap_or(443);
+            let addrs: Vec<IpAddr> = match tokio::net::lookup_host((host.as_str(), port)).await {
+                Ok(addrs) => addrs.map(|a| a.ip()).collect(),
+                Err(e) => return Err(format!("Could not resolve {}: {}", host, e)),
+            };
+
+            // Every address must be in an allowed AS, or DNS could steer probes elsewhere
+            let mut all_allowed = !addrs.is_empty();
+            for ip in &addrs {
+                match lookup_asn(*ip).await {
+                    Some(asn) if self.asns.contains(&asn) => {}
+                    Some(asn) => {
+                        debug!("{} ({}) is in AS{}, not allowlisted", host, ip, asn);
+                        all_allowed = false;
+                    }
+                    None => all_allowed = false,
+                }
+            }
+            if all_allowed {
+                return Ok(());
+            }
+        }
+
+        Err(format!("{} is not in the probe allowlist", host))
+    }
+}
+
+/// Where agent hosts publish their [`HostPolicy`]
+pub const HOST_POLICY_PATH: &str = "/.well-known/watchy.json";
+const MAX_HOST_POLICY_BYTES: usize = 16 * 1024;
+
+/// Probe preferences published by an endpoint's operator
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HostPolicy {
+    /// Don't probe this host at all
+    #[serde(default)]
+    pub opt_out: bool,
+    /// Maximum latency samples per endpoint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_samples: Option<u32>,
+    /// Minimum delay between requests in milliseconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_interval_ms: Option<u64>,
+    /// Operator contact for auditor traffic
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contact: Option<String>,
+}
+
+/// Origin (`scheme://host[:port]`) of an endpoint
+pub fn origin(endpoint: &str) -> Option<String> {
+    let mut url = url::Url::parse(endpoint).ok()?;
+    url.host_str()?;
+    // WebSocket and gRPC hosts publish their policy over HTTP(S) like any other
+    let http_scheme = match url.scheme() {
+        "ws" | "grpc" => Some("http"),
+        "wss" | "grpcs" => Some("https"),
+        _ => None,
+    };
+    if let Some(scheme) = http_scheme {
+        // grpc(s) URLs aren't special, so `set_scheme` would refuse them
+        let rest = &url[url::Position::AfterScheme..];
+        url = url::Url::parse(&format!("{}{}", scheme, rest)).ok()?;
+    }
+    Some(url.origin().ascii_serialization())
+}
+
+/// Fetch a host's policy file. A missing or invalid file means no policy.
+pub async fn fetch_host_policy(client: &reqwest::Client, origin: &str) -> Option<HostPolicy> {
+    let url = format!("{}{}", origin, HOST_POLICY_PATH);
+    let response = client.get(&url).send().await.ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+
+    let body = read_body_limited(response, MAX_HOST_POLICY_BYTES).await.ok()?;
+    match serde_json::from_slice::<HostPolicy>(&body) {
+        Ok(policy) => {
+            debug!("Host policy at {}: {:?}", url, policy);
+            Some(policy)
+        }
+        Err(e) => {
+            debug!("Ignoring invalid host policy at {}: {}", url, e);
+            None
+        }
+    }
+}
+
+/// Whether `host` is `domain` or one of its subdomains
+pub(crate) fn domain_matches(domain: &str, host: &str) -> bool {
+    let host = host.trim_end_matches('.');
+    host == domain
+        || host
+            .strip_suffix(domain)
+            .is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+/// Origin ASN of an address via Team Cymru's whois service
+async fn lookup_asn(ip: IpAddr) -> Option<u32> {
+    let query = async {
+        let mut stream = TcpStream::connect(ASN_WHOIS_SERVER).await?;
+        stream.write_all(format!("{}\n", ip).as_bytes()).await?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await?;
+        Ok::<_, std::io::Error>(response)
+    };
+
+    match tokio::time::timeout(ASN_LOOKUP_TIMEOUT, query).await {
+        Ok(Ok(response)) => parse_asn_response(&response),
+        Ok(Err(e)) => {
+            warn!("ASN lookup for {} failed: {}", ip, e);
+            None
+        }
+        Err(_) => {
+            warn!("ASN lookup for {} timed out", ip);
+            None
+        }
+    }
+}
+
+/// Parse "AS | IP | AS Name" lines, skipping any header
+fn parse_asn_response(response: &str) -> Option<u32> {
+    response
+        .lines()
+        .find_map(|line| line.split('|').next()?.trim().parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_domain_matches() {
+        assert!(domain_matches("example.com", "example.com"));
+        assert!(domain_matches("example.com", "api.example.com"));
+        assert!(domain_matches("example.com", "api.example.com."));
+        assert!(!domain_matches("example.com", "badexample.com"));
+        assert!(!domain_matches("example.com", "example.com.evil.io"));
+    }
+
+    #[test]
+    fn test_parse_asn_response() {
+        let response = "AS      | IP               | AS Name\n13335   | 1.1.1.1          | CLOUDFLARENET, US\n";
+        assert_eq!(parse_asn_response(response), Some(13335));
+        assert_eq!(parse_asn_response("NA      | 10.0.0.1 | NA\n"), None);
+    }
+
+    #[test]
+    fn test_host_policy() {
+        assert_eq!(
+            origin("https://agent.example.com:8443/mcp?x=1").as_deref(),
+            Some("https://agent.example.com:8443")
+        );
+        assert_eq!(
+            origin("wss://agent.example.com/a2a").as_deref(),
+            Some("https://agent.example.com")
+        );
+        assert_eq!(
+            origin("grpc://agent.example.com:50051").as_deref(),
+            Some("http://agent.example.com:50051")
+        );
+
+        let policy: HostPolicy =
+            serde_json::from_str(r#"{"optOut": true, "minIntervalMs": 500}"#).unwrap();
+        assert!(policy.opt_out);
+        assert_eq!(policy.min_interval_ms, Some(500));
+        assert_eq!(policy.max_samples, None);
+    }
+
+    #[tokio::test]
+    async fn test_disabled_policy_allows_everything() {
+        let policy = ProbePolicy::default();
+        assert!(policy.check("http://169.254.169.254/").await.is_ok());
+
+        let policy = ProbePolicy {
+            domains: vec!["example.com".to_string()],
+            asns: vec![],
+            asn_lookup: false,
+        };
+        assert!(policy.check("https://agent.example.com/mcp").await.is_ok());
+        assert!(policy.check("https://other.io/mcp").await.is_err());
+
+        // Without the opt-in, ASN rules deny rather than query whois
+        let policy = ProbePolicy {
+            domains: vec![],
+            asns: vec![13335],
+            asn_lookup: false,
+        };
+        assert!(policy.check("https://1.1.1.1/mcp").await.is_err());
+    }
+}
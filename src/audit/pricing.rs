@@ -0,0 +1,288 @@
+//! USD prices for x402 payment assets
+//!
+//! Advertised x402 prices are in the asset's base units; they're normalized
+//! to USD so agents can be compared. By default stablecoins are priced at
+//! their peg without any lookup; a CoinGecko-compatible feed can be
+//! configured to price any listed token at market instead.
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::env;
+use std::sync::{Arc, Mutex};
+use tracing::debug;
+
+use super::x402::known_asset;
+
+const COINGECKO_URL: &str = "https://api.coingecko.com/api/v3";
+
+/// Seconds a feed price (or the lack of one) is reused
+const PRICE_TTL_SECS: u64 = 10 * 60;
+
+/// Feed prices by `platform:address`, with the time they were fetched
+type PriceCache = Arc<Mutex<HashMap<String, (u64, Option<FeedPrice>)>>>;
+
+/// Where asset USD prices come from
+#[derive(Debug, Clone)]
+pub enum PriceFeed {
+    /// Stablecoins at their peg (USD stablecoins are $1, others unpriced)
+    Pegged,
+    /// CoinGecko `coins/{platform}/contract/{address}` (or a compatible API at `url`)
+    CoinGecko {
+        url: String,
+        api_key: Option<String>,
+        cache: PriceCache,
+    },
+}
+
+/// USD price of one whole token
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TokenPrice {
+    pub usd: f64,
+    /// Base units per token are `10^decimals`
+    pub decimals: u8,
+    pub source: &'static str,
+}
+
+/// What the feed knows about a token
+#[derive(Debug, Clone, Copy)]
+pub struct FeedPrice {
+    usd: f64,
+    decimals: Option<u8>,
+}
+
+/// Price feed and the sanity bound for advertised prices
+#[derive(Debug, Clone)]
+pub struct PricingConfig {
+    pub feed: PriceFeed,
+    /// Per-call prices above this many USD are flagged as implausible
+    pub max_price_usd: f64,
+}
+
+impl PricingConfig {
+    /// Load from `X402_PRICE_FEED` (`pegged` or `coingecko`),
+    /// `X402_PRICE_FEED_URL` / `X402_PRICE_FEED_API_KEY` and `X402_MAX_PRICE_USD`
+    pub fn from_env() -> anyhow::Result<Self> {
+        let feed = match env::var("X402_PRICE_FEED")
+            .unwrap_or_default()
+            .to_lowercase()
+            .as_str()
+        {
+            "" | "pegged" => PriceFeed::Pegged,
+            "coingecko" => PriceFeed::CoinGecko {
+                url: env::var("X402_PRICE_FEED_URL")
+                    .unwrap_or_else(|_| COINGECKO_URL.to_string())
+                    .trim_end_matches('/')
+                    .to_string(),
+                api_key: env::var("X402_PRICE_FEED_API_KEY")
+                    .ok()
+                    .filter(|key| !key.is_empty()),
+                cache: PriceCache::default(),
+            },
+            other => anyhow::bail!(
+                "Invalid X402_PRICE_FEED '{}' (expected pegged or coingecko)",
+                other
+            ),
+        };
+        let max_price_usd = env::var("X402_MAX_PRICE_USD")
+            .unwrap_or_else(|_| "1000".to_string())
+            .parse()?;
+        Ok(Self {
+            feed,
+            max_price_usd,
+        })
+    }
+}
+
+impl PriceFeed {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Pegged => "pegged",
+            Self::CoinGecko { .. } => "coingecko",
+        }
+    }
+
+    /// USD price of one token of `address` on the chain, and its decimals
+    ///
+    /// Known stablecoins fall back to their peg when the feed has no price
+    /// (e.g. on testnets); other tokens are only priced by the feed.
+    pub async fn usd_price(
+        &self,
+        client: &reqwest::Client,
+        chain_id: u64,
+        address: &str,
+    ) -> Option<TokenPrice> {
+        let known = known_asset(chain_id, address);
+        if let (Self::CoinGecko { url, api_key, cache }, Some(platform)) =
+            (self, coingecko_platform(chain_id))
+        {
+            // EVM addresses are case-insensitive, Solana mints aren't
+            let address = if address.starts_with("0x") {
+                address.to_lowercase()
+            } else {
+                address.to_string()
+            };
+            let key = format!("{}:{}", platform, address);
+            let now = chrono::Utc::now().timestamp() as u64;
+            let cached = cache
+                .lock()
+                .expect("price cache lock poisoned")
+                .get(&key)
+                .filter(|(fetched_at, _)| now < fetched_at + PRICE_TTL_SECS)
+                .map(|(_, price)| *price);
+            let price = match cached {
+                Some(price) => price,
+                None => match coingecko_price(client, url, api_key.as_deref(), platform, &address)
+                    .await
+                {
+                    Ok(price) => {
+                        let mut cache = cache.lock().expect("price cache lock poisoned");
+                        cache.retain(|_, (fetched_at, _)| now < *fetched_at + PRICE_TTL_SECS);
+                        cache.insert(key, (now, price));
+                        price
+                    }
+                    Err(e) => {
+                        debug!("{} lookup for {} failed: {}", self.name(), address, e);
+                        None
+                    }
+                },
+            };
+            let decimals = known.map(|known| known.decimals).or(price.and_then(|p| p.decimals));
+            if let (Some(price), Some(decimals)) = (price, decimals) {
+                return Some(TokenPrice {
+                    usd: price.usd,
+                    decimals,
+                    source: self.name(),
+                });
+            }
+            debug!("No {} price for {}", self.name(), address);
+        }
+        let known = known?;
+        known.usd_peg.map(|peg| TokenPrice {
+            usd: peg,
+            decimals: known.decimals,
+            source: "pegged",
+        })
+    }
+}
+
+/// CoinGecko asset platform of a chain (mainnets only)
+fn coingecko_platform(chain_id: u64) -> Option<&'static str> {
+    match chain_id {
+        1 => Some("ethereum"),
+        8453 => Some("base"),
+        101 => Some("solana"),
+        _ => None,
+    }
+}
+
+#[derive(Deserialize)]
+struct CoinResponse {
+    #[serde(default)]
+    market_data: Option<MarketData>,
+    #[serde(default)]
+    detail_platforms: HashMap<String, DetailPlatform>,
+}
+
+#[derive(Deserialize)]
+struct MarketData {
+    #[serde(default)]
+    current_price: HashMap<String, f64>,
+}
+
+#[derive(Deserialize)]
+struct DetailPlatform {
+    decimal_place: Option<u8>,
+}
+
+/// The token's price, `None` when it isn't listed or has no usable USD price
+async fn coingecko_price(
+    client: &reqwest::Client,
+    url: &str,
+    api_key: Option<&str>,
+    platform: &str,
+    address: &str,
+) -> Result<Option<FeedPrice>, String> {
+    let mut request = client
+        .get(format!("{}/coins/{}/contract/{}", url, platform, address))
+        .query(&[
+            ("localization", "false"),
+            ("tickers", "false"),
+            ("community_data", "false"),
+            ("developer_data", "false"),
+        ]);
+    if let Some(key) = api_key {
+        // Pro keys only work against the Pro API, demo keys against the public one
+        let header = if url.contains("pro-api") {
+            "x-cg-pro-api-key"
+        } else {
+            "x-cg-demo-api-key"
+        };
+        request = request.header(header, key);
+    }
+
+    let response = request.send().await.map_err(|e| e.to_string())?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        return Err(format!("HTTP {}", response.status().as_u16()));
+    }
+    let coin: CoinResponse = response.json().await.map_err(|e| e.to_string())?;
+    let usd = coin
+        .market_data
+        .and_then(|market| market.current_price.get("usd").copied())
+        .filter(|usd| usd.is_finite() && *usd >= 0.0);
+    Ok(usd.map(|usd| FeedPrice {
+        usd,
+        decimals: coin
+            .detail_platforms
+            .get(platform)
+            .and_then(|detail| detail.decimal_place),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::path;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const TOKEN: &str = "0xabcdefabcdefabcdefabcdefabcdefabcdefabcd";
+
+    #[tokio::test]
+    async fn test_coingecko_prices_cached() {
+        let server = MockServer::start().await;
+        Mock::given(path(format!("/coins/base/contract/{}", TOKEN)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "detail_platforms": {"base": {"decimal_place": 18}},
+                "market_data": {"current_price": {"usd": 2.5}}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let feed = PriceFeed::CoinGecko {
+            url: server.uri(),
+            api_key: None,
+            cache: PriceCache::default(),
+        };
+        let client = reqwest::Client::new();
+
+        // Tokens other than stablecoins are priced with the feed's decimals
+        let expected = TokenPrice {
+            usd: 2.5,
+            decimals: 18,
+            source: "coingecko",
+        };
+        assert_eq!(feed.usd_price(&client, 8453, TOKEN).await, Some(expected));
+        // Cached, whatever the address's case
+        let checksummed = format!("0x{}", TOKEN[2..].to_uppercase());
+        assert_eq!(feed.usd_price(&client, 8453, &checksummed).await, Some(expected));
+
+        // Unlisted tokens aren't priced; unlisted stablecoins fall back to the peg
+        let unlisted = "0x2222222222222222222222222222222222222222";
+        assert_eq!(feed.usd_price(&client, 8453, unlisted).await, None);
+        let usdc = "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913";
+        let pegged = feed.usd_price(&client, 8453, usdc).await.unwrap();
+        assert_eq!((pegged.usd, pegged.decimals, pegged.source), (1.0, 6, "pegged"));
+    }
+}
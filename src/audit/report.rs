@@ -3,9 +3,13 @@ use std::path::Path;
 use tokio::fs;
 use tracing::info;
 
-use crate::types::{AgentMetadata, AuditReport, WatchyError};
+use super::i18n::{Lang, ReportText};
+use crate::types::{
+    AgentMetadata, AuditReport, Confidence, PhaseOutcome, RegistrationStatus, SkillStatus,
+    WatchyError,
+};
 
-/// Generate a markdown report from audit results
+/// Generate a markdown report from audit results, in the report's language
 pub fn generate_markdown_report(
     report: &AuditReport,
     metadata: Option<&AgentMetadata>,
@@ -14,37 +18,46 @@ pub fn generate_markdown_report(
         .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
         .unwrap_or_else(|| "Unknown".to_string());
 
-    let agent_name = metadata
-        .and_then(|m| m.name.as_deref())
-        .unwrap_or("Unknown");
+    let agent_name = md_text(metadata.and_then(|m| m.name.as_deref()).unwrap_or("Unknown"));
 
+    let t = report.lang.text();
+    let validity = report
+        .valid_until
+        .and_then(|until| DateTime::<Utc>::from_timestamp(until as i64, 0))
+        .map(|dt| format!(" | {} {}", t.valid_until, dt.format("%Y-%m-%d")))
+        .unwrap_or_default();
     let mut md = String::new();
 
     // ========== HEADER ==========
     md.push_str(&format!(
-        r#"# Watchy Audit Report
+        r#"# {}
 
-## Agent #{} - {}
+## {} #{} - {}
 
-**Overall Score: {}/100** {}
+**{}: {}/100** {}
 
-**Audited on {} | Block #{}**
+**{} {} | {} #{}{}**
 
 ---
 
 "#,
+        t.title,
+        t.agent,
         report.agent.agent_id,
         agent_name,
+        t.overall_score,
         report.scores.overall,
         score_emoji(report.scores.overall),
+        t.audited_on,
         timestamp,
-        format_number(report.block_number)
+        t.block,
+        format_number(report.block_number),
+        validity
     ));
 
     // ========== DISCLAIMER ==========
-    md.push_str(r#"## What This Audit Covers
-
-> **Important:** This audit verifies the *infrastructure and metadata* of an EIP-8004 agent. It does **NOT** test the actual functionality of the agent's tools or skills.
+    md.push_str(&format!("## {}\n\n", t.what_this_audit_covers));
+    md.push_str(r#"> **Important:** This audit verifies the *infrastructure and metadata* of an EIP-8004 agent. It does **NOT** test the actual functionality of the agent's tools or skills.
 
 ### What We Test
 
@@ -63,22 +76,23 @@ pub fn generate_markdown_report(
 "#);
 
     // ========== SCORE BREAKDOWN ==========
-    md.push_str("## Score Breakdown\n\n");
+    md.push_str(&format!("## {}\n\n", t.score_breakdown));
 
+    let (verdict, explanation) = t.verdict(report.scores.overall);
     md.push_str(&format!(
-        r#"### Overall: {}/100 {}
+        r#"### {}: {}/100 {}
 
-| Component | Score | Weight |
+| {} | {} | {} |
 |-----------|-------|--------|
-| Endpoint Availability | {}/100 | 35% |
-| Endpoint Performance | {}/100 | 20% |
-| Security | {}/100 | 10% |
-| Metadata | {}/100 | 15% |
-| On-chain | {}/100 | 10% |
-| Consistency | {}/100 | 5% |
-| Content | {}/100 | 5% |
+| {} | {} | 35% |
+| {} | {} | 20% |
+| {} | {} | 10% |
+| {} | {} | 15% |
+| {} | {} | 10% |
+| {} | {} | 5% |
+| {} | {} | 5% |
 
-### Verdict
+### {}
 
 {}
 
@@ -87,63 +101,140 @@ pub fn generate_markdown_report(
 ---
 
 "#,
+        t.overall,
         report.scores.overall,
         score_emoji(report.scores.overall),
-        report.scores.endpoint_availability,
-        report.scores.endpoint_performance,
-        report.scores.security,
-        report.scores.metadata,
-        report.scores.onchain,
-        report.scores.consistency,
-        report.scores.content,
-        verdict_text(report.scores.overall),
-        verdict_explanation(report.scores.overall)
+        t.component,
+        t.score,
+        t.weight,
+        t.endpoint_availability,
+        component_score(report, t, "endpoints", report.scores.endpoint_availability),
+        t.endpoint_performance,
+        component_score(report, t, "endpoints", report.scores.endpoint_performance),
+        t.security,
+        component_score(report, t, "security", report.scores.security),
+        t.metadata,
+        component_score(report, t, "metadata", report.scores.metadata),
+        t.onchain,
+        component_score(report, t, "onchain", report.scores.onchain),
+        t.consistency,
+        component_score(report, t, "consistency", report.scores.consistency),
+        t.content,
+        component_score(report, t, "content", report.scores.content),
+        t.verdict,
+        verdict,
+        explanation
     ));
 
+    // ========== INCOMPLETE PHASES ==========
+    let incomplete: Vec<_> = report
+        .phases
+        .iter()
+        .filter(|phase| phase.outcome != PhaseOutcome::Completed)
+        .collect();
+    if !incomplete.is_empty() {
+        md.push_str(&format!("## {}\n\n", t.incomplete_audit));
+        md.push_str(&format!("{}\n\n", t.incomplete_audit_note));
+        md.push_str(&format!("| {} | {} | {} |\n", t.phase, t.result, t.reason));
+        md.push_str("|-------|--------|--------|\n");
+        for phase in incomplete {
+            let outcome = match phase.outcome {
+                PhaseOutcome::Skipped => t.skipped,
+                _ => t.failed,
+            };
+            md.push_str(&format!(
+                "| {} | {} | {} |\n",
+                phase.phase,
+                outcome,
+                phase.reason.as_deref().unwrap_or("-")
+            ));
+        }
+        md.push_str("\n---\n\n");
+    }
+
     // ========== AGENT IDENTITY ==========
-    md.push_str("## Agent Identity\n\n");
+    md.push_str(&format!("## {}\n\n", t.agent_identity));
     md.push_str("*Verified on-chain registration information*\n\n");
 
-    md.push_str("| Property | Value |\n");
-    md.push_str("|----------|-------|\n");
+    md.push_str(&property_header(t));
     md.push_str(&format!("| **Agent ID** | `{}` |\n", report.agent.agent_id));
     md.push_str(&format!("| **Name** | {} |\n", agent_name));
     md.push_str(&format!("| **Registry** | `{}` |\n", report.agent.registry));
     if let Some(owner) = &report.agent.owner {
-        md.push_str(&format!("| **Owner** | `{}` |\n", owner));
+        md.push_str(&format!("| **Owner** | `{}`{} |\n", owner, ens_suffix(&report.agent.owner_ens)));
+    }
+    if let Some(wallet) = &report.agent.wallet {
+        md.push_str(&format!("| **Agent Wallet** | `{}`{} |\n", wallet, ens_suffix(&report.agent.wallet_ens)));
     }
     md.push_str(&format!("| **Metadata URI** | `{}` |\n", report.agent.metadata_uri));
+    if let Some(snapshot) = &report.agent.metadata_snapshot {
+        md.push_str(&format!(
+            "| **Audited Metadata** | SHA-256 `{}` ({} bytes) |\n",
+            snapshot.sha256, snapshot.size_bytes
+        ));
+        if let Some(uri) = &snapshot.uri {
+            match &snapshot.url {
+                Some(url) => md.push_str(&format!("| **Metadata Snapshot** | [`{}`]({}) |\n", uri, url)),
+                None => md.push_str(&format!("| **Metadata Snapshot** | `{}` |\n", uri)),
+            }
+        }
+    }
     md.push_str("\n---\n\n");
 
+    // ========== AGENT HISTORY ==========
+    if let Some(history) = &report.history {
+        md.push_str(&format!("## {}\n\n", t.agent_history));
+        md.push_str("*Registration and tokenURI changes recorded by the registry*\n\n");
+
+        md.push_str(&format!(
+            "The metadata URI changed **{}** time{} since registration.\n\n",
+            history.uri_updates.len(),
+            if history.uri_updates.len() == 1 { "" } else { "s" }
+        ));
+
+        md.push_str("| Event | Block | Time | By | URI |\n");
+        md.push_str("|-------|-------|------|----|-----|\n");
+        match &history.registered {
+            Some(event) => md.push_str(&history_row("Registered", event)),
+            None => md.push_str(&format!(
+                "| Registered | before {} | - | - | - |\n",
+                history.from_block
+            )),
+        }
+        for event in &history.uri_updates {
+            md.push_str(&history_row("URI Updated", event));
+        }
+        md.push_str("\n---\n\n");
+    }
+
     // ========== WHAT THE AGENT CLAIMS ==========
     if let Some(m) = metadata {
-        md.push_str("## What The Agent Claims\n\n");
+        md.push_str(&format!("## {}\n\n", t.agent_claims));
         md.push_str("*Information declared in the agent's metadata (not verified for accuracy)*\n\n");
 
         if let Some(desc) = &m.description {
-            md.push_str(&format!("> {}\n\n", desc));
+            md.push_str(&format!("> {}\n\n", md_text(desc)));
         }
 
-        md.push_str("| Property | Value |\n");
-        md.push_str("|----------|-------|\n");
+        md.push_str(&property_header(t));
 
         if let Some(image) = &m.image {
-            md.push_str(&format!("| **Image** | [View]({}) |\n", image));
+            md.push_str(&format!("| **Image** | {} |\n", md_code(image)));
         }
         if let Some(active) = m.active {
-            md.push_str(&format!("| **Active** | {} |\n", if active { "Yes" } else { "No" }));
+            md.push_str(&format!("| **Active** | {} |\n", yes_no(t, active)));
         }
         if !m.supported_trust.is_empty() {
-            md.push_str(&format!("| **Trust Mechanisms** | {} |\n", m.supported_trust.join(", ")));
+            md.push_str(&format!("| **Trust Mechanisms** | {} |\n", md_text(&m.supported_trust.join(", "))));
         }
         if let Some(x402) = m.x402_support {
-            md.push_str(&format!("| **Paid (x402)** | {} |\n", if x402 { "Yes" } else { "No" }));
+            md.push_str(&format!("| **Paid (x402)** | {} |\n", yes_no(t, x402)));
         }
-        md.push_str("\n");
+        md.push('\n');
 
         // Services
         if !m.services.is_empty() {
-            md.push_str("### Declared Services\n\n");
+            md.push_str(&format!("### {}\n\n", t.declared_services));
 
             for service in &m.services {
                 let emoji = match service.name.to_lowercase().as_str() {
@@ -154,27 +245,27 @@ pub fn generate_markdown_report(
                     _ => "📡",
                 };
 
-                md.push_str(&format!("#### {} **{}**", emoji, service.name));
+                md.push_str(&format!("#### {} **{}**", emoji, md_text(&service.name)));
                 if let Some(version) = &service.version {
-                    md.push_str(&format!(" (v{})", version));
+                    md.push_str(&format!(" (v{})", md_text(version)));
                 }
                 md.push_str("\n\n");
 
                 if let Some(endpoint) = &service.endpoint {
-                    md.push_str(&format!("**Endpoint:** `{}`\n\n", endpoint));
+                    md.push_str(&format!("**Endpoint:** {}\n\n", md_code(endpoint)));
                 }
                 if !service.mcp_tools.is_empty() {
-                    md.push_str(&format!("**Tools:** `{}`\n\n", service.mcp_tools.join("`, `")));
+                    md.push_str(&format!("**Tools:** {}\n\n", md_code_list(&service.mcp_tools)));
                 }
                 if !service.mcp_prompts.is_empty() {
-                    md.push_str(&format!("**Prompts:** `{}`\n\n", service.mcp_prompts.join("`, `")));
+                    md.push_str(&format!("**Prompts:** {}\n\n", md_code_list(&service.mcp_prompts)));
                 }
                 if !service.a2a_skills.is_empty() {
                     md.push_str("**Skills:**\n");
                     for skill in &service.a2a_skills {
-                        md.push_str(&format!("- `{}`\n", skill));
+                        md.push_str(&format!("- {}\n", md_code(skill)));
                     }
-                    md.push_str("\n");
+                    md.push('\n');
                 }
             }
         }
@@ -182,64 +273,155 @@ pub fn generate_markdown_report(
     }
 
     // ========== AUDIT RESULTS ==========
-    md.push_str("## Detailed Audit Results\n\n");
+    md.push_str(&format!("## {}\n\n", t.detailed_results));
 
     // ----- On-chain -----
-    md.push_str("### 1. On-chain Verification\n\n");
+    md.push_str(&format!("### 1. {}\n\n", t.onchain_verification));
     md.push_str("*Checks that the agent exists in the EIP-8004 registry and has proper configuration*\n\n");
 
-    md.push_str(&format!("**Score: {}/100**\n\n", report.scores.onchain));
+    md.push_str(&format!("**{}: {}/100**\n\n", t.score, report.scores.onchain));
 
-    md.push_str("| Check | Result | Description |\n");
-    md.push_str("|-------|--------|-------------|\n");
+    md.push_str(&check_header(t, t.description));
     md.push_str(&format!(
         "| Agent Exists | {} | Token ID exists in registry contract |\n",
-        pass_fail(report.checks.onchain.agent_exists)
+        pass_fail(t, report.checks.onchain.agent_exists)
     ));
     md.push_str(&format!(
         "| Metadata URI | {} | IPFS/Arweave URI is set on-chain |\n",
-        pass_fail(report.checks.onchain.uri_matches)
+        pass_fail(t, report.checks.onchain.uri_matches)
+    ));
+    md.push_str(&format!(
+        "| Immutable Metadata | {} | tokenURI is content-addressed (`ipfs://` or `ar://`) |\n",
+        pass_fail(t, report.checks.onchain.uri_immutable)
     ));
     md.push_str(&format!(
         "| Wallet Configured | {} | Agent has a payment wallet set |\n",
-        pass_fail(report.checks.onchain.wallet_set)
+        pass_fail(t, report.checks.onchain.wallet_set)
     ));
-    md.push_str("\n");
+    if let Some(risk) = &report.checks.onchain.owner_risk {
+        let age = risk
+            .age_days
+            .map(|days| format!(", first seen {} days ago", days))
+            .unwrap_or_default();
+        md.push_str(&format!(
+            "| Owner Activity | {} | {} transactions sent{}{} |\n",
+            if risk.fresh { "⚠️ Fresh" } else { "✅ Established" },
+            risk.tx_count,
+            age,
+            if risk.is_contract { ", contract account" } else { "" }
+        ));
+        md.push_str(&format!(
+            "| Separate Wallet | {} | Agent wallet differs from the owner address |\n",
+            pass_fail(t, !risk.wallet_is_owner)
+        ));
+    }
+    md.push('\n');
+
+    if !report.checks.onchain.registrations.is_empty() {
+        md.push_str("**Listed registrations**\n\n");
+        md.push_str("| Registry | Agent | Status | Owner | tokenURI |\n");
+        md.push_str("|----------|-------|--------|-------|----------|\n");
+        for registration in &report.checks.onchain.registrations {
+            let status = match registration.status {
+                RegistrationStatus::Audited => "🔎 Audited",
+                RegistrationStatus::Consistent => "✅ Consistent",
+                RegistrationStatus::UriMismatch => "⚠️ Different URI",
+                RegistrationStatus::NotFound => "❌ Not found",
+                RegistrationStatus::Unverified => "⏭️ Unverified",
+            };
+            md.push_str(&format!(
+                "| {} | {} | {} | {} | {} |\n",
+                md_code(&registration.agent_registry),
+                registration.agent_id,
+                status,
+                registration.owner.as_deref().map(md_code).unwrap_or("-".to_string()),
+                registration
+                    .token_uri
+                    .as_deref()
+                    .map(|uri| md_code(&super::metadata::truncate_uri(uri)))
+                    .or(registration.reason.as_deref().map(md_text))
+                    .unwrap_or("-".to_string())
+            ));
+        }
+        md.push('\n');
+    }
 
     // ----- Metadata -----
-    md.push_str("### 2. Metadata Compliance\n\n");
+    md.push_str(&format!("### 2. {}\n\n", t.metadata_compliance));
     md.push_str("*Validates the agent's metadata follows the EIP-8004 specification*\n\n");
 
-    md.push_str(&format!("**Score: {}/100**\n\n", report.scores.metadata));
+    md.push_str(&format!("**{}: {}/100**\n\n", t.score, report.scores.metadata));
 
-    md.push_str("| Check | Result | Description |\n");
-    md.push_str("|-------|--------|-------------|\n");
+    md.push_str(&check_header(t, t.description));
     md.push_str(&format!(
         "| Required Fields | {} | `type`, `name`, `description`, `image`, `registrations` |\n",
-        pass_fail(report.checks.metadata.required_fields.passed)
+        pass_fail(t, report.checks.metadata.required_fields.passed)
     ));
     md.push_str(&format!(
         "| Type Field | {} | Matches `https://eips.ethereum.org/EIPS/eip-8004#registration-v1` |\n",
-        pass_fail(report.checks.metadata.type_field.passed)
+        pass_fail(t, report.checks.metadata.type_field.passed)
     ));
     md.push_str(&format!(
         "| Recommended Fields | {} | `active`, `services`, `supportedTrust`, `updatedAt` |\n",
-        pass_fail(report.checks.metadata.recommended_fields.passed)
+        pass_fail(t, report.checks.metadata.recommended_fields.passed)
     ));
     md.push_str(&format!(
         "| Valid URLs | {} | Image, endpoints, and documentation URLs are valid |\n",
-        pass_fail(report.checks.metadata.urls_valid.passed)
+        pass_fail(t, report.checks.metadata.urls_valid.passed)
     ));
-    md.push_str("\n");
+    let storage = &report.checks.metadata.storage;
+    md.push_str(&format!(
+        "| Storage | {} | `{}` ({}) |\n",
+        pass_fail(t, storage.passed),
+        storage.storage_type.as_str(),
+        if storage.immutable { "immutable" } else { "mutable" }
+    ));
+    md.push('\n');
+
+    if let Some(credentials) = &report.checks.metadata.credentials {
+        md.push_str("| DID / Credential | Status | Details |\n");
+        md.push_str("|------------------|--------|---------|\n");
+        for did in &credentials.dids {
+            let details = match &did.error {
+                Some(error) => error.clone(),
+                None => format!("{} verification methods", did.verification_methods.len()),
+            };
+            md.push_str(&format!(
+                "| `{}` | {} | {} |\n",
+                did.did,
+                if did.resolved { "✅ Resolved" } else { "❌ Unresolved" },
+                details
+            ));
+        }
+        for credential in &credentials.credentials {
+            let status = match credential.signature_valid {
+                Some(true) => "✅ Verified",
+                Some(false) => "❌ Invalid",
+                None => "⚠️ Unverified",
+            };
+            let mut details = format!(
+                "{} from {}",
+                credential.types.last().map(String::as_str).unwrap_or("Credential"),
+                credential.issuer.as_deref().unwrap_or("unknown issuer")
+            );
+            if credential.expired {
+                details.push_str(", expired");
+            }
+            md.push_str(&format!("| `{}` | {} | {} |\n", credential.path, status, details));
+        }
+        md.push('\n');
+    }
 
     // ----- Endpoints -----
     if !report.checks.endpoints.is_empty() {
-        md.push_str("### 3. Endpoint Testing\n\n");
+        md.push_str(&format!("### 3. {}\n\n", t.endpoint_testing));
         md.push_str("*Tests if declared service endpoints are reachable and respond with valid schemas*\n\n");
 
         md.push_str(&format!(
-            "**Availability: {}/100** | **Performance: {}/100**\n\n",
+            "**{}: {}/100** | **{}: {}/100**\n\n",
+            t.endpoint_availability,
             report.scores.endpoint_availability,
+            t.endpoint_performance,
             report.scores.endpoint_performance
         ));
 
@@ -247,120 +429,238 @@ pub fn generate_markdown_report(
         md.push_str("We do NOT execute tools, send tasks, or verify output correctness.\n\n");
 
         for endpoint in &report.checks.endpoints {
+            if let Some(reason) = &endpoint.not_probed {
+                md.push_str(&format!("#### ⏭️ {}\n\n", md_text(&endpoint.service)));
+                md.push_str(&format!("{}\n\n", md_code(&endpoint.endpoint)));
+                md.push_str(&format!("Not probed: {}\n\n", reason));
+                continue;
+            }
+
             let status_emoji = if endpoint.reachable { "🟢" } else { "🔴" };
 
-            md.push_str(&format!("#### {} {}\n\n", status_emoji, endpoint.service));
-            md.push_str(&format!("`{}`\n\n", endpoint.endpoint));
+            md.push_str(&format!("#### {} {}\n\n", status_emoji, md_text(&endpoint.service)));
+            md.push_str(&format!("{}\n\n", md_code(&endpoint.endpoint)));
 
             md.push_str("| Metric | Value |\n");
             md.push_str("|--------|-------|\n");
-            md.push_str(&format!("| Reachable | {} |\n", if endpoint.reachable { "Yes" } else { "No" }));
+            md.push_str(&format!("| Reachable | {} |\n", yes_no(t, endpoint.reachable)));
+            if let Some(availability) = endpoint.availability.as_ref().filter(|a| a.attempts > 1) {
+                let confidence = match availability.confidence {
+                    Confidence::High => "high",
+                    Confidence::Medium => "medium",
+                };
+                md.push_str(&format!(
+                    "| Attempts | {} ({} confidence) |\n",
+                    availability.attempts, confidence
+                ));
+            }
+            if endpoint.host_policy.is_some() {
+                md.push_str("| Host Policy | Honored (`/.well-known/watchy.json`) |\n");
+            }
 
             if let Some(valid) = endpoint.valid_schema {
-                md.push_str(&format!("| Valid Schema | {} |\n", if valid { "Yes" } else { "No" }));
+                md.push_str(&format!("| Valid Schema | {} |\n", yes_no(t, valid)));
             }
             if let Some(matches) = endpoint.skills_match {
-                md.push_str(&format!("| Skills Match | {} |\n", if matches { "Yes" } else { "No" }));
+                md.push_str(&format!("| Skills Match | {} |\n", yes_no(t, matches)));
             }
             if let Some(latency) = &endpoint.latency {
                 md.push_str(&format!("| Latency (p50) | {}ms |\n", latency.p50));
                 md.push_str(&format!("| Latency (p95) | {}ms |\n", latency.p95));
                 md.push_str(&format!("| Performance | {} |\n", latency_rating(latency.p95)));
             }
-            md.push_str("\n");
+            if let Some(transport) = &endpoint.transport {
+                let compression = match (transport.gzip, transport.brotli) {
+                    (true, true) => "gzip, br",
+                    (true, false) => "gzip",
+                    (false, true) => "br",
+                    (false, false) => "None",
+                };
+                md.push_str(&format!("| HTTP Version | {} |\n", transport.http_version));
+                md.push_str(&format!("| Compression | {} |\n", compression));
+                md.push_str(&format!(
+                    "| Keep-Alive | {} |\n",
+                    yes_no(t, transport.keep_alive)
+                ));
+            }
+            if let Some(hash) = &endpoint.content_hash {
+                md.push_str(&format!("| Content Hash | `{}` |\n", hash));
+            }
+            md.push('\n');
+
+            if !endpoint.skills.is_empty() {
+                md.push_str("| Declared Skill | Agent Card Skill | Status |\n");
+                md.push_str("|----------------|------------------|--------|\n");
+                for skill in &endpoint.skills {
+                    let card = match (&skill.card_id, &skill.card_name) {
+                        (Some(id), Some(name)) => format!("{} ({})", md_code(id), md_text(name)),
+                        (Some(id), None) => md_code(id),
+                        (None, Some(name)) => md_text(name),
+                        (None, None) => "-".to_string(),
+                    };
+                    let status = match skill.status {
+                        SkillStatus::Matched => "✅ Matched",
+                        SkillStatus::DeclaredOnly => "❌ Declared only",
+                        SkillStatus::CardOnly => "ℹ️ Card only",
+                    };
+                    md.push_str(&format!(
+                        "| {} | {} | {} |\n",
+                        skill.declared.as_deref().map_or("-".to_string(), md_code),
+                        card,
+                        status
+                    ));
+                }
+                md.push('\n');
+            }
         }
     }
 
     // ----- Security -----
-    md.push_str("### 4. Security Analysis\n\n");
+    md.push_str(&format!("### 4. {}\n\n", t.security_analysis));
     md.push_str("*Checks TLS configuration and security headers on HTTPS endpoints*\n\n");
 
-    md.push_str(&format!("**Score: {}/100**\n\n", report.scores.security));
+    md.push_str(&format!("**{}: {}/100**\n\n", t.score, report.scores.security));
 
     md.push_str("> **Note:** This checks transport security only. We do not audit the agent's code, ");
     md.push_str("authentication mechanisms, or data handling practices.\n\n");
 
-    md.push_str("| Check | Result | Why It Matters |\n");
-    md.push_str("|-------|--------|----------------|\n");
+    md.push_str(&check_header(t, t.why_it_matters));
     md.push_str(&format!(
         "| TLS Valid | {} | Encrypted connection, trusted certificate |\n",
-        pass_fail(report.checks.security.tls_valid)
+        pass_fail(t, report.checks.security.tls_valid)
     ));
     md.push_str(&format!(
         "| Certificate Valid | {} | Not expired or self-signed |\n",
-        pass_fail(report.checks.security.certificate_valid)
+        pass_fail(t, report.checks.security.certificate_valid)
     ));
     md.push_str(&format!(
         "| HTTPS Enforced | {} | HTTP requests redirect to HTTPS |\n",
-        pass_fail(report.checks.security.https_enforced)
+        pass_fail(t, report.checks.security.https_enforced)
     ));
     md.push_str(&format!(
         "| X-Content-Type-Options | {} | Prevents MIME-sniffing attacks |\n",
-        pass_fail(report.checks.security.security_headers.x_content_type_options)
+        pass_fail(t, report.checks.security.security_headers.x_content_type_options)
     ));
     md.push_str(&format!(
         "| Strict-Transport-Security | {} | Forces HTTPS for future requests |\n",
-        pass_fail(report.checks.security.security_headers.strict_transport_security)
+        pass_fail(t, report.checks.security.security_headers.strict_transport_security)
     ));
     md.push_str(&format!(
         "| Content-Security-Policy | {} | Prevents XSS attacks |\n",
-        pass_fail(report.checks.security.security_headers.content_security_policy)
+        pass_fail(t, report.checks.security.security_headers.content_security_policy)
+    ));
+    md.push_str(&format!(
+        "| security.txt | {} | Tells researchers how to report vulnerabilities |\n",
+        pass_fail(t, 
+            report
+                .checks
+                .security
+                .security_txt
+                .as_ref()
+                .is_some_and(|txt| !txt.expired)
+        )
     ));
-    md.push_str("\n");
+    md.push('\n');
+
+    if let Some(cert) = &report.checks.security.certificate {
+        md.push_str("**Certificate Details**\n\n");
+        md.push_str(&property_header(t));
+        if let Some(version) = &report.checks.security.tls_version {
+            md.push_str(&format!("| Protocol | {} |\n", version));
+        }
+        md.push_str(&format!("| Subject | {} |\n", md_code(&cert.subject)));
+        md.push_str(&format!("| Issuer | {} |\n", md_code(&cert.issuer)));
+        if !cert.san_dns_names.is_empty() {
+            md.push_str(&format!("| SANs | {} |\n", md_code_list(&cert.san_dns_names)));
+        }
+        md.push_str(&format!(
+            "| Key | {} {} |\n",
+            cert.key_algorithm,
+            cert.key_bits.map(|b| format!("{} bits", b)).unwrap_or_default()
+        ));
+        if !cert.hostname.is_empty() {
+            let status = if cert.hostname_matches { "✅ covered" } else { "❌ not covered" };
+            md.push_str(&format!("| Hostname | {} {} |\n", md_code(&cert.hostname), status));
+        }
+        md.push_str(&format!("| Chain Length | {} |\n", cert.chain_length));
+        if let Some(days) = report.checks.security.certificate_days_remaining {
+            md.push_str(&format!("| Expires In | {} days |\n", days));
+        }
+        md.push('\n');
+    }
+
+    if let Some(reputation) = &report.checks.security.reputation {
+        md.push_str(&format!(
+            "**URL Reputation** ({}): {} URLs checked, {} flagged\n\n",
+            reputation.provider,
+            reputation.urls_checked,
+            reputation.flagged.len()
+        ));
+        for flagged in &reputation.flagged {
+            md.push_str(&format!("- ❌ {}: {}\n", md_code(&flagged.url), md_text(&flagged.threat)));
+        }
+        if !reputation.flagged.is_empty() {
+            md.push('\n');
+        }
+    }
 
     // ----- Consistency -----
-    md.push_str("### 5. Consistency Analysis\n\n");
+    md.push_str(&format!("### 5. {}\n\n", t.consistency_analysis));
     md.push_str("*Verifies that information is consistent across metadata and endpoint responses*\n\n");
 
-    md.push_str(&format!("**Score: {}/100**\n\n", report.scores.consistency));
+    md.push_str(&format!("**{}: {}/100**\n\n", t.score, report.scores.consistency));
 
-    md.push_str("| Check | Result | What We Compare |\n");
-    md.push_str("|-------|--------|------------------|\n");
+    md.push_str(&check_header(t, t.what_we_compare));
     md.push_str(&format!(
         "| Name Consistent | {} | Metadata name vs A2A/MCP response names |\n",
-        pass_fail(report.checks.consistency.name_consistent)
+        pass_fail(t, report.checks.consistency.name_consistent)
     ));
     md.push_str(&format!(
         "| Skills Consistent | {} | Declared skills vs actual endpoint skills |\n",
-        pass_fail(report.checks.consistency.skills_consistent)
+        pass_fail(t, report.checks.consistency.skills_consistent)
     ));
     md.push_str(&format!(
         "| Version Consistent | {} | Declared versions vs endpoint versions |\n",
-        pass_fail(report.checks.consistency.version_consistent)
+        pass_fail(t, report.checks.consistency.version_consistent)
     ));
     md.push_str(&format!(
         "| Image Accessible | {} | Agent image URL returns valid image |\n",
-        pass_fail(report.checks.consistency.image_accessible)
+        pass_fail(t, report.checks.consistency.image_accessible)
     ));
-    md.push_str("\n");
+    if let Some(consistent) = report.checks.consistency.author_identity_consistent {
+        md.push_str(&format!(
+            "| Author Identity | {} | Author's ENS name vs owner / wallet ENS names |\n",
+            pass_fail(t, consistent)
+        ));
+    }
+    md.push('\n');
 
     // ----- Content -----
-    md.push_str("### 6. Content Quality\n\n");
+    md.push_str(&format!("### 6. {}\n\n", t.content_quality));
     md.push_str("*Evaluates the quality and completeness of metadata content*\n\n");
 
-    md.push_str(&format!("**Score: {}/100**\n\n", report.scores.content));
+    md.push_str(&format!("**{}: {}/100**\n\n", t.score, report.scores.content));
 
-    md.push_str("| Check | Result | Details |\n");
-    md.push_str("|-------|--------|----------|\n");
+    md.push_str(&check_header(t, t.details));
     md.push_str(&format!(
         "| Description Quality | {}/100 | Length: {} chars, Meaningful: {} |\n",
         report.checks.content.description_quality.score,
         report.checks.content.description_quality.length,
-        if report.checks.content.description_quality.is_meaningful { "Yes" } else { "No" }
+        yes_no(t, report.checks.content.description_quality.is_meaningful)
     ));
     md.push_str(&format!(
         "| Valid Skill Taxonomy | {} | Skills follow OASF naming conventions |\n",
-        pass_fail(report.checks.content.valid_skill_taxonomy)
+        pass_fail(t, report.checks.content.valid_skill_taxonomy)
     ));
     md.push_str(&format!(
         "| Contact Info | {} | Has support/contact information |\n",
-        pass_fail(report.checks.content.has_contact_info)
+        pass_fail(t, report.checks.content.has_contact_info)
     ));
 
     if let Some(x402) = &report.checks.content.x402_valid {
         md.push_str(&format!(
             "| x402 Payment Flow | {} | Returns 402 with payment headers |\n",
-            pass_fail(x402.valid)
+            pass_fail(t, x402.valid)
         ));
     }
     md.push_str("\n---\n\n");
@@ -370,25 +670,26 @@ pub fn generate_markdown_report(
     let total_issues = issues.critical + issues.error + issues.warning + issues.info;
 
     if total_issues > 0 {
-        md.push_str("## Issues Found\n\n");
-
-        if issues.critical > 0 {
-            md.push_str(&format!("- 🔴 **{} Critical** - Must be fixed\n", issues.critical));
-        }
-        if issues.error > 0 {
-            md.push_str(&format!("- 🟠 **{} Errors** - Should be fixed\n", issues.error));
-        }
-        if issues.warning > 0 {
-            md.push_str(&format!("- 🟡 **{} Warnings** - Consider fixing\n", issues.warning));
-        }
-        if issues.info > 0 {
-            md.push_str(&format!("- 🔵 **{} Info** - For your information\n", issues.info));
+        md.push_str(&format!("## {}\n\n", t.issues_found));
+
+        let counts = [issues.critical, issues.error, issues.warning, issues.info];
+        for ((count, emoji), (label, action)) in counts
+            .into_iter()
+            .zip(["🔴", "🟠", "🟡", "🔵"])
+            .zip(t.severity_legend)
+        {
+            if count > 0 {
+                md.push_str(&format!("- {} **{} {}** - {}\n", emoji, count, label, action));
+            }
         }
-        md.push_str("\n");
+        md.push('\n');
 
-        md.push_str("### All Issues\n\n");
-        md.push_str("| Severity | Code | Message |\n");
-        md.push_str("|----------|------|----------|\n");
+        md.push_str(&format!("### {}\n\n", t.all_issues));
+        md.push_str(&format!(
+            "| {} | {} | {} | {} |\n",
+            t.severity, t.code, t.message, t.how_to_fix
+        ));
+        md.push_str("|----------|------|---------|------------|\n");
 
         let all_issues = report.checks.metadata.issues.iter()
             .chain(report.checks.onchain.issues.iter())
@@ -404,7 +705,23 @@ pub fn generate_markdown_report(
                 crate::types::Severity::Warning => "🟡",
                 crate::types::Severity::Info => "🔵",
             };
-            md.push_str(&format!("| {} | `{}` | {} |\n", severity_emoji, issue.code, issue.message));
+            let how_to_fix = match (&issue.remediation, &issue.docs_url) {
+                (Some(fix), Some(url)) => format!("{} [Docs]({})", fix, url),
+                (Some(fix), None) => fix.clone(),
+                (None, _) => "-".to_string(),
+            };
+            // Details stay as the check wrote them (escaped: they quote agent text),
+            // after a localized summary
+            let message = match report.lang.issue_summary(&issue.code) {
+                Some(summary) if report.lang != Lang::En => {
+                    format!("**{}**: {}", summary, md_text(&issue.message))
+                }
+                _ => md_text(&issue.message),
+            };
+            md.push_str(&format!(
+                "| {} | `{}` | {} | {} |\n",
+                severity_emoji, issue.code, message, how_to_fix
+            ));
         }
 
         md.push_str("\n---\n\n");
@@ -412,18 +729,18 @@ pub fn generate_markdown_report(
 
     // ========== FOOTER ==========
     md.push_str(&format!(
-        r#"## About This Report
+        r#"## {}
 
 This report was automatically generated by **Watchy v{}**, an EIP-8004 agent auditing service.
 
-### Limitations
+### {}
 
 - This audit checks **infrastructure only**, not agent behavior or output quality
 - Endpoint tests verify **reachability and schema**, not functional correctness
 - Security checks cover **transport layer** only, not application security
 - Metadata validation checks **format**, not content truthfulness
 
-### Learn More
+### {}
 
 - [EIP-8004 Specification](https://eips.ethereum.org/EIPS/eip-8004)
 - [Watchy Documentation](https://github.com/anthropics/watchy)
@@ -432,12 +749,24 @@ This report was automatically generated by **Watchy v{}**, an EIP-8004 agent aud
 
 *Report generated by Watchy - EIP-8004 Agent Audit Service*
 "#,
-        report.auditor.version
+        t.about_this_report,
+        report.auditor.version,
+        t.limitations,
+        t.learn_more
     ));
 
     md
 }
 
+/// A category's score, or "skipped" when its phase never ran
+fn component_score(report: &AuditReport, t: &ReportText, phase: &str, score: u8) -> String {
+    if report.is_skipped(phase) {
+        t.skipped.to_string()
+    } else {
+        format!("{}/100", score)
+    }
+}
+
 fn score_emoji(score: u8) -> &'static str {
     match score {
         90..=100 => "🏆",
@@ -448,28 +777,72 @@ fn score_emoji(score: u8) -> &'static str {
     }
 }
 
-fn verdict_text(score: u8) -> &'static str {
-    match score {
-        90..=100 => "**Excellent** - Agent passes all critical checks",
-        75..=89 => "**Good** - Agent passes most checks with minor issues",
-        60..=74 => "**Fair** - Agent has issues that should be addressed",
-        40..=59 => "**Poor** - Agent has significant problems",
-        _ => "**Critical** - Agent fails multiple critical checks",
-    }
+fn history_row(label: &str, event: &crate::types::RegistryEvent) -> String {
+    format!(
+        "| {} | {} | {} | {} | {} |\n",
+        label,
+        event.block_number,
+        event.timestamp.as_deref().unwrap_or("-"),
+        md_code(&event.actor),
+        md_code(&super::metadata::truncate_uri(&event.uri))
+    )
 }
 
-fn verdict_explanation(score: u8) -> &'static str {
-    match score {
-        90..=100 => "This agent has excellent infrastructure. All endpoints are reachable, metadata is complete, and security basics are in place. Safe to interact with from an infrastructure standpoint.",
-        75..=89 => "This agent has solid infrastructure with some minor issues. Consider reviewing the warnings below, but the agent is generally well-configured.",
-        60..=74 => "This agent has noticeable issues that may affect reliability. Review the problems listed and consider whether they impact your use case.",
-        40..=59 => "This agent has significant infrastructure problems. Proceed with caution and review all issues carefully.",
-        _ => "This agent has critical problems that make it unreliable. We recommend against interacting with this agent until issues are resolved.",
+/// " (name.eth)" after an address with a primary ENS name
+fn ens_suffix(name: &Option<String>) -> String {
+    name.as_ref().map(|n| format!(" ({})", md_text(n))).unwrap_or_default()
+}
+
+/// Agent-supplied text as inert Markdown: one line, no markup, no HTML
+fn md_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\r' | '\n' => escaped.push(' '),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '\\' | '`' | '*' | '_' | '[' | ']' | '(' | ')' | '#' | '|' | '!' | '~' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c => escaped.push(c),
+        }
     }
+    escaped
 }
 
-fn pass_fail(passed: bool) -> &'static str {
-    if passed { "✅ Pass" } else { "❌ Fail" }
+/// Agent-supplied text as a table-safe code span
+fn md_code(text: &str) -> String {
+    let inner: String = text
+        .chars()
+        .map(|c| match c {
+            '`' => '\'',
+            '\r' | '\n' => ' ',
+            c => c,
+        })
+        .collect();
+    format!("`{}`", inner.replace('|', "\\|"))
+}
+
+fn md_code_list(items: &[String]) -> String {
+    items.iter().map(|item| md_code(item)).collect::<Vec<_>>().join(", ")
+}
+
+fn pass_fail(t: &ReportText, passed: bool) -> &'static str {
+    if passed { t.pass } else { t.fail }
+}
+
+fn yes_no(t: &ReportText, value: bool) -> &'static str {
+    if value { t.yes } else { t.no }
+}
+
+fn property_header(t: &ReportText) -> String {
+    format!("| {} | {} |\n|----------|-------|\n", t.property, t.value)
+}
+
+fn check_header(t: &ReportText, third: &str) -> String {
+    format!("| {} | {} | {} |\n|-------|--------|-------------|\n", t.check, t.result, third)
 }
 
 fn latency_rating(p95_ms: u64) -> &'static str {
@@ -506,11 +879,14 @@ pub async fn save_report(
         .await
         .map_err(|e| WatchyError::Internal(format!("Failed to create reports dir: {}", e)))?;
 
-    let agent_name = metadata
+    let agent_name: String = metadata
         .and_then(|m| m.name.as_deref())
         .unwrap_or("unknown")
         .to_lowercase()
-        .replace(' ', "-");
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .take(64)
+        .collect();
 
     let filename = format!("agent-{}-{}.md", report.agent.agent_id, agent_name);
     let filepath = reports_dir.join(&filename);
@@ -525,3 +901,17 @@ pub async fn save_report(
 
     Ok(filepath.to_string_lossy().to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_agent_text_is_escaped() {
+        assert_eq!(
+            md_text("Bot](https://evil.example)\n# <img src=x>"),
+            "Bot\\]\\(https://evil.example\\) \\# &lt;img src=x&gt;"
+        );
+        assert_eq!(md_code("a`b|c\nd"), "`a'b\\|c d`");
+    }
+}
@@ -0,0 +1,286 @@
+//! URL reputation lookups
+//!
+//! Optionally checks an agent's declared endpoints and image against a threat
+//! intelligence provider (Google Safe Browsing or abuse.ch URLhaus). A flagged
+//! URL means the agent isn't safe to interact with, whatever else it gets right.
+
+use serde::Deserialize;
+use std::env;
+use tracing::debug;
+
+use crate::types::FlaggedUrl;
+
+const SAFE_BROWSING_URL: &str = "https://safebrowsing.googleapis.com/v4/threatMatches:find";
+const URLHAUS_URL: &str = "https://urlhaus-api.abuse.ch/v1/url/";
+
+/// Threat intelligence provider and its credentials
+#[derive(Debug, Clone)]
+pub enum ReputationProvider {
+    SafeBrowsing { api_key: String },
+    UrlHaus { auth_key: String },
+}
+
+impl ReputationProvider {
+    /// Load from `URL_REPUTATION_PROVIDER` (`safe_browsing` or `urlhaus`) and
+    /// `SAFE_BROWSING_API_KEY` / `URLHAUS_AUTH_KEY`. Unset means disabled.
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        let Ok(provider) = env::var("URL_REPUTATION_PROVIDER") else {
+            return Ok(None);
+        };
+        let required = |name: &str| {
+            env::var(name).map_err(|_| {
+                anyhow::anyhow!("{} is required when URL_REPUTATION_PROVIDER={}", name, provider)
+            })
+        };
+
+        match provider.to_lowercase().as_str() {
+            "" | "none" => Ok(None),
+            "safe_browsing" | "safebrowsing" | "google" => Ok(Some(Self::SafeBrowsing {
+                api_key: required("SAFE_BROWSING_API_KEY")?,
+            })),
+            "urlhaus" => Ok(Some(Self::UrlHaus {
+                auth_key: required("URLHAUS_AUTH_KEY")?,
+            })),
+            other => anyhow::bail!(
+                "Invalid URL_REPUTATION_PROVIDER '{}' (expected safe_browsing or urlhaus)",
+                other
+            ),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::SafeBrowsing { .. } => "safe_browsing",
+            Self::UrlHaus { .. } => "urlhaus",
+        }
+    }
+
+    /// Look up URLs, returning those the provider flags
+    pub async fn check_urls(
+        &self,
+        client: &reqwest::Client,
+        urls: &[String],
+    ) -> Result<Vec<FlaggedUrl>, String> {
+        if urls.is_empty() {
+            return Ok(Vec::new());
+        }
+        debug!("Checking {} URLs with {}", urls.len(), self.name());
+
+        match self {
+            Self::SafeBrowsing { api_key } => {
+                check_safe_browsing(client, SAFE_BROWSING_URL, api_key, urls).await
+            }
+            Self::UrlHaus { auth_key } => {
+                let mut flagged = Vec::new();
+                for url in urls {
+                    if let Some(threat) = check_urlhaus(client, URLHAUS_URL, auth_key, url).await? {
+                        flagged.push(FlaggedUrl {
+                            url: url.clone(),
+                            threat,
+                        });
+                    }
+                }
+                Ok(flagged)
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SafeBrowsingResponse {
+    #[serde(default)]
+    matches: Vec<SafeBrowsingMatch>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SafeBrowsingMatch {
+    threat_type: String,
+    threat: SafeBrowsingThreat,
+}
+
+#[derive(Deserialize)]
+struct SafeBrowsingThreat {
+    url: String,
+}
+
+/// Safe Browsing Lookup API (v4), all URLs in one request
+async fn check_safe_browsing(
+    client: &reqwest::Client,
+    lookup_url: &str,
+    api_key: &str,
+    urls: &[String],
+) -> Result<Vec<FlaggedUrl>, String> {
+    let body = serde_json::json!({
+        "client": {
+            "clientId": "watchy",
+            "clientVersion": env!("CARGO_PKG_VERSION"),
+        },
+        "threatInfo": {
+            "threatTypes": [
+                "MALWARE",
+                "SOCIAL_ENGINEERING",
+                "UNWANTED_SOFTWARE",
+                "POTENTIALLY_HARMFUL_APPLICATION",
+            ],
+            "platformTypes": ["ANY_PLATFORM"],
+            "threatEntryTypes": ["URL"],
+            "threatEntries": urls.iter().map(|url| serde_json::json!({ "url": url })).collect::<Vec<_>>(),
+        },
+    });
+
+    let response = client
+        .post(lookup_url)
+        .query(&[("key", api_key)])
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Safe Browsing request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Safe Browsing returned HTTP {}", response.status()));
+    }
+
+    let parsed: SafeBrowsingResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid Safe Browsing response: {}", e))?;
+
+    Ok(parsed
+        .matches
+        .into_iter()
+        .map(|m| FlaggedUrl {
+            url: m.threat.url,
+            threat: m.threat_type.to_lowercase(),
+        })
+        .collect())
+}
+
+#[derive(Deserialize)]
+struct UrlHausResponse {
+    query_status: String,
+    #[serde(default)]
+    threat: Option<String>,
+}
+
+/// URLhaus URL lookup; `Some(threat)` if the URL is listed
+async fn check_urlhaus(
+    client: &reqwest::Client,
+    lookup_url: &str,
+    auth_key: &str,
+    url: &str,
+) -> Result<Option<String>, String> {
+    let response = client
+        .post(lookup_url)
+        .header("Auth-Key", auth_key)
+        .form(&[("url", url)])
+        .send()
+        .await
+        .map_err(|e| format!("URLhaus request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("URLhaus returned HTTP {}", response.status()));
+    }
+
+    let parsed: UrlHausResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid URLhaus response: {}", e))?;
+
+    match parsed.query_status.as_str() {
+        "ok" => Ok(Some(parsed.threat.unwrap_or_else(|| "malware".to_string()))),
+        "no_results" => Ok(None),
+        status => Err(format!("URLhaus query failed: {}", status)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::post;
+    use axum::{Form, Json, Router};
+    use std::collections::HashMap;
+
+    /// Stand-in for both providers, flagging URLs that contain "evil"
+    async fn mock_provider() -> String {
+        let app = Router::new()
+            .route(
+                "/safe-browsing",
+                post(|Json(body): Json<serde_json::Value>| async move {
+                    let matches: Vec<serde_json::Value> = body["threatInfo"]["threatEntries"]
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .filter(|entry| entry["url"].as_str().unwrap().contains("evil"))
+                        .map(|entry| {
+                            serde_json::json!({
+                                "threatType": "SOCIAL_ENGINEERING",
+                                "threat": { "url": entry["url"] },
+                            })
+                        })
+                        .collect();
+                    Json(serde_json::json!({ "matches": matches }))
+                }),
+            )
+            .route(
+                "/urlhaus",
+                post(|Form(form): Form<HashMap<String, String>>| async move {
+                    let url = &form["url"];
+                    Json(if url.contains("evil") {
+                        serde_json::json!({ "query_status": "ok", "threat": "malware_download" })
+                    } else if url.contains("broken") {
+                        serde_json::json!({ "query_status": "invalid_url" })
+                    } else {
+                        serde_json::json!({ "query_status": "no_results" })
+                    })
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        base
+    }
+
+    #[tokio::test]
+    async fn test_safe_browsing_lookup() {
+        let base = mock_provider().await;
+        let client = reqwest::Client::new();
+        let urls = vec![
+            "https://agent.example.com/a2a".to_string(),
+            "https://evil.example.com/login".to_string(),
+        ];
+
+        let flagged = check_safe_browsing(&client, &format!("{}/safe-browsing", base), "key", &urls)
+            .await
+            .unwrap();
+        assert_eq!(flagged.len(), 1);
+        assert_eq!(flagged[0].url, "https://evil.example.com/login");
+        assert_eq!(flagged[0].threat, "social_engineering");
+
+        let missing = check_safe_browsing(&client, &format!("{}/missing", base), "key", &urls).await;
+        assert!(missing.unwrap_err().contains("HTTP 404"));
+    }
+
+    #[tokio::test]
+    async fn test_urlhaus_lookup() {
+        let base = mock_provider().await;
+        let client = reqwest::Client::new();
+        let lookup = format!("{}/urlhaus", base);
+
+        let listed = check_urlhaus(&client, &lookup, "key", "https://evil.example.com/x").await;
+        assert_eq!(listed.unwrap(), Some("malware_download".to_string()));
+        let clean = check_urlhaus(&client, &lookup, "key", "https://agent.example.com").await;
+        assert_eq!(clean.unwrap(), None);
+        let failed = check_urlhaus(&client, &lookup, "key", "https://broken.example.com").await;
+        assert!(failed.unwrap_err().contains("invalid_url"));
+    }
+
+    #[tokio::test]
+    async fn test_no_urls_skips_lookup() {
+        let provider = ReputationProvider::UrlHaus {
+            auth_key: "key".to_string(),
+        };
+        let flagged = provider.check_urls(&reqwest::Client::new(), &[]).await.unwrap();
+        assert!(flagged.is_empty());
+        assert_eq!(provider.name(), "urlhaus");
+    }
+}
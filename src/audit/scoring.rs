@@ -0,0 +1,431 @@
+//! Score weighting
+//!
+//! Category weights combine component scores into the overall score; service
+//! weights decide how much each endpoint counts towards availability and
+//! performance (a broken MCP server matters more than a broken landing page).
+
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+
+use crate::config::Vars;
+use crate::types::Scores;
+
+/// Weight for service types without an explicit entry
+const DEFAULT_SERVICE_WEIGHT: f64 = 1.0;
+
+/// Weights used to compute availability, performance and overall scores
+#[derive(Debug, Clone)]
+pub struct ScoreWeights {
+    pub endpoint_availability: f64,
+    pub endpoint_performance: f64,
+    pub security: f64,
+    pub metadata: f64,
+    pub onchain: f64,
+    pub consistency: f64,
+    pub content: f64,
+    /// Per-service-type weights for endpoint scores
+    pub services: ServiceWeights,
+}
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        Self {
+            endpoint_availability: 0.35,
+            endpoint_performance: 0.20,
+            security: 0.10,
+            metadata: 0.15,
+            onchain: 0.10,
+            consistency: 0.05,
+            content: 0.05,
+            services: ServiceWeights::default(),
+        }
+    }
+}
+
+impl ScoreWeights {
+    /// Load weights from environment variables
+    ///
+    /// - `SCORE_WEIGHTS`: e.g. "availability=0.35,performance=0.20,security=0.10"
+    /// - `SERVICE_WEIGHTS`: e.g. "mcp=3,a2a=3,oasf=2,web=1"
+    ///
+    /// Unlisted keys keep their defaults.
+    pub fn from_vars(vars: Vars) -> anyhow::Result<Self> {
+        let mut weights = Self::default();
+
+        if let Some(value) = vars("SCORE_WEIGHTS") {
+            for (key, weight) in parse_weight_list(&value)? {
+                match weights.category_mut(&key) {
+                    Some(category) => *category = weight,
+                    None => anyhow::bail!("Unknown SCORE_WEIGHTS category '{}'", key),
+                }
+            }
+            if weights.total() <= 0.0 {
+                anyhow::bail!("SCORE_WEIGHTS must not all be zero");
+            }
+        }
+
+        if let Some(value) = vars("SERVICE_WEIGHTS") {
+            for (key, weight) in parse_weight_list(&value)? {
+                weights.services.weights.insert(key, weight);
+            }
+        }
+
+        Ok(weights)
+    }
+
+    /// The weight of a category, by its `SCORE_WEIGHTS` key
+    fn category_mut(&mut self, key: &str) -> Option<&mut f64> {
+        match key {
+            "availability" | "endpoint_availability" => Some(&mut self.endpoint_availability),
+            "performance" | "endpoint_performance" => Some(&mut self.endpoint_performance),
+            "security" => Some(&mut self.security),
+            "metadata" => Some(&mut self.metadata),
+            "onchain" => Some(&mut self.onchain),
+            "consistency" => Some(&mut self.consistency),
+            "content" => Some(&mut self.content),
+            _ => None,
+        }
+    }
+
+    /// These weights with categories multiplied by a chain profile's factors
+    pub fn scaled(&self, factors: &[(&str, f64)]) -> Self {
+        let mut weights = self.clone();
+        for (key, factor) in factors {
+            if let Some(category) = weights.category_mut(key) {
+                *category *= factor;
+            }
+        }
+        weights
+    }
+
+    fn total(&self) -> f64 {
+        self.endpoint_availability
+            + self.endpoint_performance
+            + self.security
+            + self.metadata
+            + self.onchain
+            + self.consistency
+            + self.content
+    }
+
+    /// Combine component scores (0-100) into an overall score (0-100)
+    ///
+    /// Weights that don't sum to 1 are normalized.
+    pub fn overall(&self, scores: &Scores) -> u8 {
+        let weighted = scores.endpoint_availability as f64 * self.endpoint_availability
+            + scores.endpoint_performance as f64 * self.endpoint_performance
+            + scores.security as f64 * self.security
+            + scores.metadata as f64 * self.metadata
+            + scores.onchain as f64 * self.onchain
+            + scores.consistency as f64 * self.consistency
+            + scores.content as f64 * self.content;
+
+        let total = self.total();
+        let overall = if total > 0.0 && (total - 1.0).abs() > 1e-6 {
+            weighted / total
+        } else {
+            weighted
+        };
+
+        overall.clamp(0.0, 100.0) as u8
+    }
+}
+
+/// Zero the categories of skipped audit phases: checks that never ran earn
+/// nothing, so skipping phases can't raise the overall score
+pub fn zero_skipped<'a>(scores: &mut Scores, phases: impl IntoIterator<Item = &'a str>) {
+    for phase in phases {
+        match phase {
+            "endpoints" => {
+                scores.endpoint_availability = 0;
+                scores.endpoint_performance = 0;
+            }
+            "security" => scores.security = 0,
+            "metadata" => scores.metadata = 0,
+            "onchain" => scores.onchain = 0,
+            "consistency" => scores.consistency = 0,
+            "content" => scores.content = 0,
+            _ => {}
+        }
+    }
+}
+
+/// Relative importance of each service type, keyed by lowercase service name
+#[derive(Debug, Clone)]
+pub struct ServiceWeights {
+    weights: HashMap<String, f64>,
+}
+
+impl Default for ServiceWeights {
+    fn default() -> Self {
+        let weights = [("mcp", 3.0), ("a2a", 3.0), ("oasf", 2.0), ("web", 1.0)]
+            .into_iter()
+            .map(|(name, weight)| (name.to_string(), weight))
+            .collect();
+        Self { weights }
+    }
+}
+
+impl ServiceWeights {
+    pub fn weight_for(&self, service_name: &str) -> f64 {
+        self.weights
+            .get(&service_name.to_lowercase())
+            .copied()
+            .unwrap_or(DEFAULT_SERVICE_WEIGHT)
+    }
+}
+
+/// Score bands, best first, as used for verdicts and feedback tags
+pub const SCORE_BANDS: [&str; 5] = ["excellent", "good", "fair", "poor", "critical"];
+
+/// Index into `SCORE_BANDS` for an overall score
+pub fn score_band(score: u8) -> usize {
+    match score {
+        90..=100 => 0,
+        75..=89 => 1,
+        60..=74 => 2,
+        40..=59 => 3,
+        _ => 4,
+    }
+}
+
+/// Feedback tags for reports and on-chain feedback
+///
+/// Each tag is a template where `{band}` stands for the report's score band.
+#[derive(Debug, Clone)]
+pub struct FeedbackTags {
+    pub tag1: String,
+    pub tag2: String,
+}
+
+impl Default for FeedbackTags {
+    fn default() -> Self {
+        Self {
+            tag1: "starred".to_string(),
+            tag2: "{band}".to_string(),
+        }
+    }
+}
+
+impl FeedbackTags {
+    /// Load from `FEEDBACK_TAG1` and `FEEDBACK_TAG2` (an empty value = no tag)
+    pub fn from_env() -> anyhow::Result<Self> {
+        let mut tags = Self::default();
+        let vars = [
+            ("FEEDBACK_TAG1", &mut tags.tag1),
+            ("FEEDBACK_TAG2", &mut tags.tag2),
+        ];
+        for (name, tag) in vars {
+            if let Ok(value) = env::var(name) {
+                let rest = value.replace("{band}", "");
+                if rest.contains('{') || rest.contains('}') {
+                    anyhow::bail!(
+                        "{}: only {{band}} can be substituted, got '{}'",
+                        name,
+                        value
+                    );
+                }
+                *tag = value;
+            }
+        }
+        Ok(tags)
+    }
+
+    /// Both tags for an overall score
+    pub fn render(&self, score: u8) -> (String, String) {
+        let band = SCORE_BANDS[score_band(score)];
+        (
+            self.tag1.replace("{band}", band),
+            self.tag2.replace("{band}", band),
+        )
+    }
+}
+
+/// Which scores get on-chain feedback
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FeedbackPolicy {
+    #[default]
+    Always,
+    /// Endorsements only: scores of at least this
+    Min(u8),
+    /// Warnings only: scores of at most this
+    Max(u8),
+}
+
+impl FeedbackPolicy {
+    /// "always", "min:<score>" or "max:<score>"
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().to_lowercase();
+        if value == "always" {
+            return Some(Self::Always);
+        }
+        let (kind, score) = value.split_once(':')?;
+        let score: u8 = score.trim().parse().ok().filter(|score| *score <= 100)?;
+        match kind.trim() {
+            "min" => Some(Self::Min(score)),
+            "max" => Some(Self::Max(score)),
+            _ => None,
+        }
+    }
+
+    pub fn allows(&self, score: u8) -> bool {
+        match *self {
+            Self::Always => true,
+            Self::Min(min) => score >= min,
+            Self::Max(max) => score <= max,
+        }
+    }
+}
+
+impl fmt::Display for FeedbackPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Always => write!(f, "always"),
+            Self::Min(min) => write!(f, "min:{}", min),
+            Self::Max(max) => write!(f, "max:{}", max),
+        }
+    }
+}
+
+/// Parse "key=value,key=value" into lowercase keys and non-negative weights
+fn parse_weight_list(value: &str) -> anyhow::Result<Vec<(String, f64)>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (key, weight) = entry
+                .split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("Invalid weight '{}' (expected key=value)", entry))?;
+            let weight: f64 = weight
+                .trim()
+                .parse()
+                .map_err(|_| anyhow::anyhow!("Invalid weight value in '{}'", entry))?;
+            if !weight.is_finite() || weight < 0.0 {
+                anyhow::bail!("Weight in '{}' must be a non-negative number", entry);
+            }
+            Ok((key.trim().to_lowercase(), weight))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scores(value: u8) -> Scores {
+        Scores {
+            overall: 0,
+            metadata: value,
+            onchain: value,
+            endpoint_availability: value,
+            endpoint_performance: value,
+            security: value,
+            consistency: value,
+            content: value,
+        }
+    }
+
+    #[test]
+    fn test_parse_weight_list() {
+        let parsed = parse_weight_list(" MCP=3, web=0.5 ,").unwrap();
+        assert_eq!(parsed, vec![("mcp".to_string(), 3.0), ("web".to_string(), 0.5)]);
+
+        assert!(parse_weight_list("mcp").is_err());
+        assert!(parse_weight_list("mcp=abc").is_err());
+        assert!(parse_weight_list("mcp=-1").is_err());
+    }
+
+    #[test]
+    fn test_service_weight_lookup() {
+        let weights = ServiceWeights::default();
+        assert_eq!(weights.weight_for("MCP"), 3.0);
+        assert_eq!(weights.weight_for("web"), 1.0);
+        assert_eq!(weights.weight_for("custom"), DEFAULT_SERVICE_WEIGHT);
+    }
+
+    #[test]
+    fn test_overall_normalizes_weights() {
+        let defaults = ScoreWeights::default();
+        assert_eq!(defaults.overall(&scores(100)), 100);
+
+        let doubled = ScoreWeights {
+            endpoint_availability: 0.70,
+            endpoint_performance: 0.40,
+            security: 0.20,
+            metadata: 0.30,
+            onchain: 0.20,
+            consistency: 0.10,
+            content: 0.10,
+            ..Default::default()
+        };
+        assert_eq!(doubled.overall(&scores(80)), 80);
+    }
+
+    #[test]
+    fn test_feedback_tags() {
+        let tags = FeedbackTags::default();
+        assert_eq!(tags.render(95), ("starred".to_string(), "excellent".to_string()));
+        assert_eq!(tags.render(59), ("starred".to_string(), "poor".to_string()));
+
+        let custom = FeedbackTags {
+            tag1: "auditScore".to_string(),
+            tag2: "infra-{band}".to_string(),
+        };
+        assert_eq!(custom.render(80).1, "infra-good");
+    }
+
+    #[test]
+    fn test_feedback_policy() {
+        assert_eq!(FeedbackPolicy::parse("always"), Some(FeedbackPolicy::Always));
+        let endorse = FeedbackPolicy::parse("min:70").unwrap();
+        assert!(endorse.allows(70) && !endorse.allows(69));
+        let warn = FeedbackPolicy::parse("MAX: 40").unwrap();
+        assert!(warn.allows(40) && !warn.allows(41));
+        assert_eq!(warn.to_string(), "max:40");
+
+        assert_eq!(FeedbackPolicy::parse("min:101"), None);
+        assert_eq!(FeedbackPolicy::parse("above:70"), None);
+        assert_eq!(FeedbackPolicy::parse("min"), None);
+    }
+
+    #[test]
+    fn test_scaled_weights() {
+        let defaults = ScoreWeights::default();
+        let weights = defaults.scaled(&[("availability", 1.5), ("onchain", 0.5)]);
+        assert_eq!(
+            weights.endpoint_availability,
+            defaults.endpoint_availability * 1.5
+        );
+        assert_eq!(weights.onchain, defaults.onchain * 0.5);
+        assert_eq!(weights.security, defaults.security);
+        for score in [0, 50, 80, 100] {
+            assert_eq!(weights.overall(&scores(score)), score);
+        }
+    }
+
+    #[test]
+    fn test_skipped_phases_score_zero() {
+        use crate::types::{AuditReport, PhaseOutcome};
+
+        let mut report = AuditReport::new(1, 8453, "0x8004", String::new(), None);
+        report.scores.metadata = 0;
+        report.scores.onchain = 100;
+        report.scores.security = 100;
+        report.record_phase("metadata", PhaseOutcome::Failed, Some("gateway down".into()));
+        report.skip_phases(
+            &["endpoints", "security", "consistency", "content"],
+            "metadata unavailable",
+        );
+        report.calculate_overall_score(&ScoreWeights::default());
+        // Failed metadata counts as found, skipped phases as 0: only on-chain (0.10) scores
+        assert_eq!(report.scores.overall, 10);
+        assert_eq!(report.scores.security, 0);
+
+        let md = crate::audit::generate_markdown_report(&report, None);
+        assert!(md.contains("## Incomplete Audit"));
+        assert!(md.contains("| security | ⏭️ Skipped | metadata unavailable |"));
+        assert!(md.contains("| Metadata | 0/100 | 15% |"));
+    }
+}
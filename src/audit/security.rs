@@ -1,12 +1,32 @@
-use native_tls::TlsConnector;
-use std::net::ToSocketAddrs;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::client::danger::{
+    HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier,
+};
+use tokio_rustls::rustls::client::WebPkiServerVerifier;
+use tokio_rustls::rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use tokio_rustls::rustls::{
+    CertificateError, ClientConfig, DigitallySignedStruct, Error as TlsError, ProtocolVersion,
+    RootCertStore, SignatureScheme,
+};
+use tokio_rustls::TlsConnector;
 use tracing::{debug, warn};
+use x509_parser::prelude::{FromDer, GeneralName, X509Certificate};
+use x509_parser::public_key::PublicKey;
 
-use crate::types::{Issue, SecurityChecks, SecurityHeadersCheck, Severity};
+use crate::http::read_body_limited;
+use crate::ssrf::OutboundGuard;
+use crate::types::{
+    CertificateInfo, Issue, SecurityChecks, SecurityHeadersCheck, SecurityTxt, Severity,
+};
 
 /// Run security checks on an endpoint
+/// `no_redirect_client` must not follow redirects (used for HTTPS enforcement)
 pub async fn check_endpoint_security(
     client: &reqwest::Client,
+    no_redirect_client: &reqwest::Client,
+    guard: &OutboundGuard,
     endpoint: &str,
 ) -> SecurityChecks {
     debug!("Running security checks on {}", endpoint);
@@ -19,180 +39,512 @@ pub async fn check_endpoint_security(
         certificate_days_remaining: None,
         security_headers: SecurityHeadersCheck::default(),
         https_enforced: false,
+        certificate: None,
+        security_txt: None,
+        reputation: None,
         issues: vec![],
     };
 
     // Skip non-HTTPS endpoints
     if !endpoint.starts_with("https://") {
         checks.passed = false;
-        checks.issues.push(Issue {
-            severity: Severity::Critical,
-            code: "NO_HTTPS".to_string(),
-            message: "Endpoint does not use HTTPS".to_string(),
-        });
+        checks.issues.push(Issue::new(
+            Severity::Critical,
+            "NO_HTTPS",
+            "Endpoint does not use HTTPS",
+        ));
         return checks;
     }
 
-    // Test TLS connection and get certificate info
-    match check_tls(endpoint).await {
+    // Inspect the TLS session and certificate chain
+    match check_tls(guard, endpoint).await {
         Ok(tls_info) => {
-            checks.tls_valid = tls_info.valid;
+            checks.tls_valid = true;
             checks.tls_version = Some(tls_info.version);
             checks.certificate_valid = tls_info.cert_valid;
             checks.certificate_days_remaining = tls_info.cert_days_remaining;
 
-            if !tls_info.valid {
-                checks.passed = false;
-                checks.issues.push(Issue {
-                    severity: Severity::Critical,
-                    code: "TLS_INVALID".to_string(),
-                    message: "TLS connection failed or invalid".to_string(),
-                });
-            }
+            if let Some(cert) = &tls_info.certificate {
+                if !cert.hostname_matches {
+                    checks.passed = false;
+                    let names = if cert.san_dns_names.is_empty() && cert.san_ip_addresses.is_empty() {
+                        "(no subject alternative names)".to_string()
+                    } else {
+                        cert.san_dns_names
+                            .iter()
+                            .chain(cert.san_ip_addresses.iter())
+                            .cloned()
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    };
+                    checks.issues.push(Issue::new(
+                        Severity::Critical,
+                        "CERT_HOSTNAME_MISMATCH",
+                        format!(
+                            "TLS certificate does not cover hostname '{}' (certificate names: {})",
+                            cert.hostname, names
+                        ),
+                    ));
+                }
+
+                if let Some(error) = &cert.validation_error {
+                    checks.passed = false;
+                    checks.issues.push(Issue::new(
+                        Severity::Critical,
+                        "CERT_UNTRUSTED",
+                        format!("TLS certificate failed validation: {}", error),
+                    ));
+                }
 
-            // Note: Actual TLS version detection would require rustls/openssl bindings
-            // Modern clients (including reqwest) negotiate TLS 1.2+ by default
+                if is_weak_key(cert) {
+                    checks.issues.push(Issue::new(
+                        Severity::Warning,
+                        "WEAK_CERT_KEY",
+                        format!(
+                            "TLS certificate uses a weak {} key ({} bits)",
+                            cert.key_algorithm,
+                            cert.key_bits
+                                .map(|b| b.to_string())
+                                .unwrap_or_else(|| "unknown".to_string())
+                        ),
+                    ));
+                }
+            }
+            checks.certificate = tls_info.certificate;
 
             if let Some(days) = tls_info.cert_days_remaining {
                 if days <= 0 {
                     checks.passed = false;
-                    checks.issues.push(Issue {
-                        severity: Severity::Critical,
-                        code: "CERT_EXPIRED".to_string(),
-                        message: "TLS certificate has expired".to_string(),
-                    });
+                    checks.issues.push(Issue::new(
+                        Severity::Critical,
+                        "CERT_EXPIRED",
+                        "TLS certificate has expired",
+                    ));
                 } else if days <= 14 {
-                    checks.issues.push(Issue {
-                        severity: Severity::Warning,
-                        code: "CERT_EXPIRING_SOON".to_string(),
-                        message: format!("TLS certificate expires in {} days", days),
-                    });
+                    checks.issues.push(Issue::new(
+                        Severity::Warning,
+                        "CERT_EXPIRING_SOON",
+                        format!("TLS certificate expires in {} days", days),
+                    ));
                 }
             }
         }
         Err(e) => {
             checks.passed = false;
-            checks.issues.push(Issue {
-                severity: Severity::Critical,
-                code: "TLS_CHECK_FAILED".to_string(),
-                message: format!("Failed to check TLS: {}", e),
-            });
+            checks.issues.push(Issue::new(
+                Severity::Critical,
+                "TLS_CHECK_FAILED",
+                format!("Failed to check TLS: {}", e),
+            ));
         }
     }
 
     // Check security headers
     checks.security_headers = check_security_headers(client, endpoint).await;
     if !has_minimum_headers(&checks.security_headers) {
-        checks.issues.push(Issue {
-            severity: Severity::Warning,
-            code: "MISSING_SECURITY_HEADERS".to_string(),
-            message: "Missing recommended security headers".to_string(),
-        });
+        checks.issues.push(Issue::new(
+            Severity::Warning,
+            "MISSING_SECURITY_HEADERS",
+            "Missing recommended security headers",
+        ));
     }
 
     // Check HTTPS enforcement (try HTTP, should redirect or fail)
-    checks.https_enforced = check_https_enforcement(client, endpoint).await;
+    checks.https_enforced = check_https_enforcement(no_redirect_client, endpoint).await;
     if !checks.https_enforced {
-        checks.issues.push(Issue {
-            severity: Severity::Info,
-            code: "HTTP_NOT_REDIRECTED".to_string(),
-            message: "HTTP requests are not redirected to HTTPS".to_string(),
-        });
+        checks.issues.push(Issue::new(
+            Severity::Info,
+            "HTTP_NOT_REDIRECTED",
+            "HTTP requests are not redirected to HTTPS",
+        ));
+    }
+
+    // Check for a vulnerability disclosure policy
+    checks.security_txt = fetch_security_txt(client, endpoint).await;
+    match &checks.security_txt {
+        None => checks.issues.push(Issue::new(
+            Severity::Info,
+            "SECURITY_TXT_MISSING",
+            "Host doesn't publish /.well-known/security.txt with a Contact",
+        )),
+        Some(txt) if txt.expired => checks.issues.push(Issue::new(
+            Severity::Warning,
+            "SECURITY_TXT_EXPIRED",
+            format!(
+                "security.txt expired on {}",
+                txt.expires.as_deref().unwrap_or_default()
+            ),
+        )),
+        Some(txt) if txt.expires.is_none() => checks.issues.push(Issue::new(
+            Severity::Info,
+            "SECURITY_TXT_NO_EXPIRES",
+            "security.txt has no Expires field (required by RFC 9116)",
+        )),
+        Some(_) => {}
     }
 
     checks
 }
 
+const MAX_SECURITY_TXT_BYTES: usize = 32 * 1024;
+
+/// Fetch `security.txt` from the endpoint's host, trying the legacy root location too
+async fn fetch_security_txt(client: &reqwest::Client, endpoint: &str) -> Option<SecurityTxt> {
+    let origin = super::policy::origin(endpoint)?;
+
+    for path in ["/.well-known/security.txt", "/security.txt"] {
+        let url = format!("{}{}", origin, path);
+        let Ok(response) = client.get(&url).send().await else {
+            continue;
+        };
+        // Catch-all routes often answer 200 with an HTML page
+        let is_text = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.to_lowercase().starts_with("text/plain"));
+        if !response.status().is_success() || !is_text {
+            continue;
+        }
+        let Ok(body) = read_body_limited(response, MAX_SECURITY_TXT_BYTES).await else {
+            continue;
+        };
+        let body = String::from_utf8_lossy(&body);
+        if let Some(txt) = parse_security_txt(&url, &body, chrono::Utc::now()) {
+            debug!("Found security.txt at {}", url);
+            return Some(txt);
+        }
+    }
+
+    None
+}
+
+/// Parse a `security.txt` body. Without a Contact field it doesn't count.
+fn parse_security_txt(
+    url: &str,
+    body: &str,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Option<SecurityTxt> {
+    let mut txt = SecurityTxt {
+        url: url.to_string(),
+        ..Default::default()
+    };
+
+    // Comments and PGP armor lines have no "Field: value" form worth reading
+    for line in body.lines().map(str::trim) {
+        if line.starts_with('#') || line.starts_with('-') {
+            continue;
+        }
+        let Some((field, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim().to_string();
+        match field.trim().to_lowercase().as_str() {
+            "contact" => txt.contacts.push(value),
+            "expires" if txt.expires.is_none() => {
+                txt.expired = chrono::DateTime::parse_from_rfc3339(&value)
+                    .is_ok_and(|expires| expires < now);
+                txt.expires = Some(value);
+            }
+            "policy" if txt.policy.is_none() => txt.policy = Some(value),
+            _ => {}
+        }
+    }
+
+    (!txt.contacts.is_empty()).then_some(txt)
+}
+
+/// Result of a single TLS handshake against an endpoint
 struct TlsInfo {
-    valid: bool,
     version: String,
     cert_valid: bool,
     cert_days_remaining: Option<i64>,
+    certificate: Option<CertificateInfo>,
 }
 
-async fn check_tls(endpoint: &str) -> Result<TlsInfo, String> {
+/// Timeout for the TCP connect + TLS handshake
+const TLS_HANDSHAKE_TIMEOUT_SECS: u64 = 10;
+
+/// Minimum acceptable key sizes (bits)
+const MIN_RSA_KEY_BITS: usize = 2048;
+const MIN_EC_KEY_BITS: usize = 256;
+
+/// Connect to the endpoint and inspect the TLS session in one pass.
+///
+/// The handshake always completes (the verifier records the WebPKI validation
+/// result instead of aborting), so the full chain can be inspected even for
+/// untrusted or expired certificates.
+async fn check_tls(guard: &OutboundGuard, endpoint: &str) -> Result<TlsInfo, String> {
     // Parse the URL to get host and port
     let url = url::Url::parse(endpoint).map_err(|e| format!("Invalid URL: {}", e))?;
-    let host = url.host_str().ok_or("No host in URL")?;
+    let host = match url.host().ok_or("No host in URL")? {
+        url::Host::Domain(domain) => domain.to_string(),
+        url::Host::Ipv4(ip) => ip.to_string(),
+        url::Host::Ipv6(ip) => ip.to_string(),
+    };
     let port = url.port().unwrap_or(443);
 
-    // Check if we can establish a valid TLS connection (cert must be valid)
-    let cert_valid = check_certificate_valid(host, port).await;
-
-    // Also do a standard TLS check with reqwest to verify connectivity
-    let client = reqwest::Client::builder()
-        .danger_accept_invalid_certs(false)
-        .timeout(std::time::Duration::from_secs(10))
+    let provider = Arc::new(tokio_rustls::rustls::crypto::ring::default_provider());
+    let roots = Arc::new(RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    });
+    let webpki = WebPkiServerVerifier::builder_with_provider(roots, provider.clone())
         .build()
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| format!("Failed to build verifier: {}", e))?;
+    let verifier = Arc::new(RecordingVerifier {
+        inner: webpki,
+        outcome: Mutex::new(None),
+    });
+
+    let config = ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .map_err(|e| format!("Invalid TLS config: {}", e))?
+        .dangerous()
+        .with_custom_certificate_verifier(verifier.clone())
+        .with_no_client_auth();
+
+    let server_name = ServerName::try_from(host.clone())
+        .map_err(|e| format!("Invalid server name '{}': {}", host, e))?;
+
+    let handshake = async {
+        // Connect to the addresses the guard approved, not a fresh lookup
+        let addrs = guard.resolve(&host, port).await?;
+        let tcp = TcpStream::connect(addrs.as_slice())
+            .await
+            .map_err(|e| format!("TCP connect failed: {}", e))?;
+        TlsConnector::from(Arc::new(config))
+            .connect(server_name, tcp)
+            .await
+            .map_err(|e| format!("TLS handshake failed: {}", e))
+    };
 
-    match client.head(endpoint).send().await {
-        Ok(_response) => {
-            // TLS handshake succeeded - connection is secure
-            Ok(TlsInfo {
-                valid: true,
-                version: "TLS 1.2+".to_string(),
-                cert_valid,
-                cert_days_remaining: None, // Would require x509 parsing
-            })
-        }
-        Err(e) => {
-            if e.is_connect() {
-                // Could be cert error or connection refused
-                Err(format!("Connection/TLS error: {}", e))
-            } else {
-                // Request failed but TLS handshake may have succeeded
-                Ok(TlsInfo {
-                    valid: true,
-                    version: "TLS 1.2+".to_string(),
-                    cert_valid,
-                    cert_days_remaining: None,
-                })
+    let stream = tokio::time::timeout(
+        std::time::Duration::from_secs(TLS_HANDSHAKE_TIMEOUT_SECS),
+        handshake,
+    )
+    .await
+    .map_err(|_| "TLS handshake timed out".to_string())??;
+
+    let (_, session) = stream.get_ref();
+
+    let version = session
+        .protocol_version()
+        .map(format_protocol_version)
+        .unwrap_or_else(|| "unknown".to_string());
+    let cipher_suite = session
+        .negotiated_cipher_suite()
+        .map(|cs| format!("{:?}", cs.suite()));
+
+    let validation = verifier
+        .outcome
+        .lock()
+        .ok()
+        .and_then(|o| o.clone())
+        .unwrap_or_else(|| Err(TlsError::General("Certificate was not verified".to_string())));
+
+    // A name mismatch is reported separately (CERT_HOSTNAME_MISMATCH); WebPKI only
+    // checks the name after the chain itself has validated.
+    let name_error = matches!(
+        &validation,
+        Err(TlsError::InvalidCertificate(
+            CertificateError::NotValidForName | CertificateError::NotValidForNameContext { .. }
+        ))
+    );
+
+    let chain = session.peer_certificates().unwrap_or_default();
+    let certificate = chain.first().and_then(|leaf| {
+        match parse_leaf_certificate(leaf.as_ref()) {
+            Ok(mut info) => {
+                info.chain_length = chain.len();
+                info.chain_trusted = validation.is_ok() || name_error;
+                info.validation_error = match &validation {
+                    Err(e) if !name_error => Some(e.to_string()),
+                    _ => None,
+                };
+                info.cipher_suite = cipher_suite.clone();
+                info.hostname_matches = certificate_covers_host(&info, &host);
+                info.hostname = host.clone();
+                Some(info)
+            }
+            Err(e) => {
+                warn!("Failed to parse leaf certificate for {}: {}", host, e);
+                None
             }
         }
+    });
+
+    let cert_days_remaining = certificate
+        .as_ref()
+        .map(|c| (c.not_after - chrono::Utc::now().timestamp()).div_euclid(86_400));
+
+    debug!(
+        "TLS inspection for {}:{}: {} / {:?} (trusted: {})",
+        host,
+        port,
+        version,
+        cipher_suite,
+        validation.is_ok()
+    );
+
+    Ok(TlsInfo {
+        version,
+        cert_valid: validation.is_ok(),
+        cert_days_remaining,
+        certificate,
+    })
+}
+
+fn format_protocol_version(version: ProtocolVersion) -> String {
+    match version {
+        ProtocolVersion::TLSv1_3 => "TLS 1.3".to_string(),
+        ProtocolVersion::TLSv1_2 => "TLS 1.2".to_string(),
+        other => format!("{:?}", other),
     }
 }
 
-/// Check if the certificate is valid (not expired, trusted)
-async fn check_certificate_valid(host: &str, port: u16) -> bool {
-    let host = host.to_string();
-    tokio::task::spawn_blocking(move || {
-        check_cert_valid_sync(&host, port)
+/// Extract subject, issuer, SANs, validity and key strength from a DER certificate
+fn parse_leaf_certificate(der: &[u8]) -> Result<CertificateInfo, String> {
+    let (_, cert) = X509Certificate::from_der(der).map_err(|e| e.to_string())?;
+
+    let mut san_dns_names = vec![];
+    let mut san_ip_addresses = vec![];
+    if let Ok(Some(san)) = cert.subject_alternative_name() {
+        for name in &san.value.general_names {
+            match name {
+                GeneralName::DNSName(dns) => san_dns_names.push(dns.to_string()),
+                GeneralName::IPAddress(ip) => san_ip_addresses.push(format_ip_bytes(ip)),
+                _ => {}
+            }
+        }
+    }
+
+    let (key_algorithm, key_bits) = match cert.public_key().parsed() {
+        Ok(PublicKey::RSA(rsa)) => ("RSA".to_string(), Some(rsa.key_size())),
+        Ok(PublicKey::EC(ec)) => ("EC".to_string(), Some(ec.key_size())),
+        Ok(PublicKey::DSA(_)) => ("DSA".to_string(), None),
+        Ok(PublicKey::GostR3410(_)) | Ok(PublicKey::GostR3410_2012(_)) => {
+            ("GOST".to_string(), None)
+        }
+        Ok(PublicKey::Unknown(_)) | Err(_) => (
+            cert.public_key().algorithm.algorithm.to_id_string(),
+            None,
+        ),
+    };
+
+    Ok(CertificateInfo {
+        subject: cert.subject().to_string(),
+        issuer: cert.issuer().to_string(),
+        san_dns_names,
+        san_ip_addresses,
+        not_before: cert.validity().not_before.timestamp(),
+        not_after: cert.validity().not_after.timestamp(),
+        key_algorithm,
+        key_bits,
+        signature_algorithm: cert.signature_algorithm.algorithm.to_id_string(),
+        ..Default::default()
     })
-    .await
-    .unwrap_or(false)
 }
 
-/// Synchronous certificate validity check
-fn check_cert_valid_sync(host: &str, port: u16) -> bool {
-    // Build TLS connector with strict validation
-    let connector = match TlsConnector::builder()
-        .danger_accept_invalid_certs(false) // Strict validation
-        .build()
-    {
-        Ok(c) => c,
-        Err(_) => return false,
-    };
+fn format_ip_bytes(bytes: &[u8]) -> String {
+    match bytes.len() {
+        4 => IpAddr::from(<[u8; 4]>::try_from(bytes).unwrap_or_default()).to_string(),
+        16 => IpAddr::from(<[u8; 16]>::try_from(bytes).unwrap_or_default()).to_string(),
+        _ => hex::encode(bytes),
+    }
+}
 
-    // Resolve address
-    let addr = match format!("{}:{}", host, port).to_socket_addrs() {
-        Ok(mut addrs) => match addrs.next() {
-            Some(a) => a,
-            None => return false,
-        },
-        Err(_) => return false,
-    };
+/// Check whether the certificate's SANs cover the probed host.
+///
+/// Follows RFC 6125: a wildcard is only honoured as the entire left-most label
+/// and matches exactly one label. The subject CN is not consulted.
+fn certificate_covers_host(cert: &CertificateInfo, host: &str) -> bool {
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return cert
+            .san_ip_addresses
+            .iter()
+            .any(|san| san.parse::<IpAddr>().map(|s| s == ip).unwrap_or(false));
+    }
 
-    // Connect with timeout
-    let stream = match std::net::TcpStream::connect_timeout(&addr, std::time::Duration::from_secs(5)) {
-        Ok(s) => s,
-        Err(_) => return false,
-    };
-    let _ = stream.set_read_timeout(Some(std::time::Duration::from_secs(5)));
-    let _ = stream.set_write_timeout(Some(std::time::Duration::from_secs(5)));
+    cert.san_dns_names
+        .iter()
+        .any(|pattern| san_matches_host(pattern, host))
+}
+
+fn san_matches_host(pattern: &str, host: &str) -> bool {
+    let pattern = pattern.trim_end_matches('.').to_lowercase();
+    let host = host.trim_end_matches('.').to_lowercase();
+
+    if let Some(suffix) = pattern.strip_prefix("*.") {
+        return match host.split_once('.') {
+            Some((label, rest)) => !label.is_empty() && rest == suffix,
+            None => false,
+        };
+    }
+
+    pattern == host
+}
+
+/// Whether the key is below the minimum recommended strength
+fn is_weak_key(cert: &CertificateInfo) -> bool {
+    match (cert.key_algorithm.as_str(), cert.key_bits) {
+        ("RSA", Some(bits)) => bits < MIN_RSA_KEY_BITS,
+        ("EC", Some(bits)) => bits < MIN_EC_KEY_BITS,
+        ("DSA", _) => true,
+        _ => false,
+    }
+}
+
+/// Certificate verifier that records the WebPKI result instead of failing the handshake
+#[derive(Debug)]
+struct RecordingVerifier {
+    inner: Arc<WebPkiServerVerifier>,
+    outcome: Mutex<Option<Result<(), TlsError>>>,
+}
+
+impl ServerCertVerifier for RecordingVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, TlsError> {
+        let result = self
+            .inner
+            .verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)
+            .map(|_| ());
+
+        if let Ok(mut outcome) = self.outcome.lock() {
+            *outcome = Some(result);
+        }
+
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, TlsError> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
 
-    // TLS handshake - if this succeeds, cert is valid
-    connector.connect(host, stream).is_ok()
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, TlsError> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
 }
 
 fn has_minimum_headers(headers: &SecurityHeadersCheck) -> bool {
@@ -247,16 +599,10 @@ async fn check_security_headers(client: &reqwest::Client, endpoint: &str) -> Sec
     headers_check
 }
 
-async fn check_https_enforcement(client: &reqwest::Client, endpoint: &str) -> bool {
+async fn check_https_enforcement(no_redirect_client: &reqwest::Client, endpoint: &str) -> bool {
     // Convert https:// to http:// and check if it redirects
     let http_endpoint = endpoint.replace("https://", "http://");
 
-    // Build a client that doesn't follow redirects
-    let no_redirect_client = reqwest::Client::builder()
-        .redirect(reqwest::redirect::Policy::none())
-        .build()
-        .unwrap_or_else(|_| client.clone());
-
     match no_redirect_client.head(&http_endpoint).send().await {
         Ok(response) => {
             // Check if it's a redirect to HTTPS
@@ -277,6 +623,20 @@ async fn check_https_enforcement(client: &reqwest::Client, endpoint: &str) -> bo
     }
 }
 
+// Security points lost per finding
+/// Untrusted chain or hostname mismatch
+pub const CERT_INVALID_PENALTY: u8 = 50;
+pub const CERT_EXPIRED_PENALTY: u8 = 50;
+/// Certificate expiring within 7 days
+pub const CERT_EXPIRING_WEEK_PENALTY: u8 = 20;
+/// Certificate expiring within 14 days
+pub const CERT_EXPIRING_PENALTY: u8 = 10;
+/// With no security header at all
+pub const HEADERS_MAX_PENALTY: u8 = 30;
+pub const HTTP_NOT_REDIRECTED_PENALTY: u8 = 10;
+/// Missing or expired security.txt
+pub const SECURITY_TXT_PENALTY: u8 = 5;
+
 /// Calculate security score from checks
 pub fn calculate_security_score(checks: &SecurityChecks) -> u8 {
     let mut score = 100u8;
@@ -288,28 +648,97 @@ pub fn calculate_security_score(checks: &SecurityChecks) -> u8 {
 
     // Certificate issues
     if !checks.certificate_valid {
-        score = score.saturating_sub(50);
+        score = score.saturating_sub(CERT_INVALID_PENALTY);
     }
 
     if let Some(days) = checks.certificate_days_remaining {
         if days <= 0 {
-            score = score.saturating_sub(50);
+            score = score.saturating_sub(CERT_EXPIRED_PENALTY);
         } else if days <= 7 {
-            score = score.saturating_sub(20);
+            score = score.saturating_sub(CERT_EXPIRING_WEEK_PENALTY);
         } else if days <= 14 {
-            score = score.saturating_sub(10);
+            score = score.saturating_sub(CERT_EXPIRING_PENALTY);
         }
     }
 
-    // Security headers (max 30 points deduction)
+    // Security headers
     let h_score = headers_score(&checks.security_headers);
-    let headers_penalty = ((100 - h_score) as f64 * 0.3) as u8;
-    score = score.saturating_sub(headers_penalty);
+    score = score.saturating_sub(((100 - h_score) as u16 * HEADERS_MAX_PENALTY as u16 / 100) as u8);
 
     // HTTPS enforcement
     if !checks.https_enforced {
-        score = score.saturating_sub(10);
+        score = score.saturating_sub(HTTP_NOT_REDIRECTED_PENALTY);
+    }
+
+    // Disclosure policy
+    if checks.security_txt.as_ref().is_none_or(|txt| txt.expired) {
+        score = score.saturating_sub(SECURITY_TXT_PENALTY);
     }
 
     score
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cert_with_key(algorithm: &str, bits: Option<usize>) -> CertificateInfo {
+        CertificateInfo {
+            key_algorithm: algorithm.to_string(),
+            key_bits: bits,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_weak_key_detection() {
+        assert!(is_weak_key(&cert_with_key("RSA", Some(1024))));
+        assert!(!is_weak_key(&cert_with_key("RSA", Some(2048))));
+        assert!(is_weak_key(&cert_with_key("EC", Some(192))));
+        assert!(!is_weak_key(&cert_with_key("EC", Some(256))));
+        assert!(!is_weak_key(&cert_with_key("1.3.101.112", None)));
+    }
+
+    #[test]
+    fn test_san_wildcard_matching() {
+        assert!(san_matches_host("*.example.com", "api.example.com"));
+        assert!(san_matches_host("API.Example.com.", "api.example.com"));
+        assert!(!san_matches_host("*.example.com", "example.com"));
+        assert!(!san_matches_host("*.example.com", "a.b.example.com"));
+        assert!(!san_matches_host("api.example.com", "www.example.com"));
+    }
+
+    #[test]
+    fn test_certificate_covers_host() {
+        let cert = CertificateInfo {
+            san_dns_names: vec!["example.com".to_string(), "*.example.com".to_string()],
+            san_ip_addresses: vec!["10.0.0.1".to_string()],
+            ..Default::default()
+        };
+        assert!(certificate_covers_host(&cert, "example.com"));
+        assert!(certificate_covers_host(&cert, "agent.example.com"));
+        assert!(certificate_covers_host(&cert, "10.0.0.1"));
+        assert!(!certificate_covers_host(&cert, "10.0.0.2"));
+        assert!(!certificate_covers_host(&cert, "example.org"));
+    }
+
+    #[test]
+    fn test_format_ip_bytes() {
+        assert_eq!(format_ip_bytes(&[127, 0, 0, 1]), "127.0.0.1");
+        let v6 = [0u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+        assert_eq!(format_ip_bytes(&v6), "::1");
+    }
+
+    #[test]
+    fn test_parse_security_txt() {
+        let now = chrono::Utc::now();
+        let body = "# Our policy\nContact: mailto:security@example.com\nContact: https://example.com/report\nExpires: 2020-01-01T00:00:00Z\nPolicy: https://example.com/disclosure\n";
+        let txt = parse_security_txt("https://example.com/.well-known/security.txt", body, now).unwrap();
+        assert_eq!(txt.contacts.len(), 2);
+        assert!(txt.expired);
+        assert_eq!(txt.policy.as_deref(), Some("https://example.com/disclosure"));
+
+        assert!(parse_security_txt("u", "Expires: 2099-01-01T00:00:00Z\n", now).is_none());
+        assert!(parse_security_txt("u", "<html>Not found</html>", now).is_none());
+    }
+}
@@ -0,0 +1,177 @@
+//! A2A skill comparison
+//!
+//! Agent cards describe skills as objects (`id`, `name`, `tags`,
+//! `description`); registration metadata declares them as plain strings, which
+//! may be a card skill's id, its name, or an OASF taxonomy path ending in either.
+
+use serde_json::Value;
+
+use crate::types::{SkillMatch, SkillStatus};
+
+/// A skill from an A2A agent card
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct A2aSkill {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub tags: Vec<String>,
+    pub description: Option<String>,
+}
+
+/// Skills listed in an agent card, or `None` if it has no `skills` array
+///
+/// Plain string entries are treated as ids.
+pub fn parse_card_skills(card: &Value) -> Option<Vec<A2aSkill>> {
+    let skills = card.get("skills")?.as_array()?;
+    let text = |skill: &Value, field: &str| {
+        skill
+            .get(field)
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+    };
+
+    Some(
+        skills
+            .iter()
+            .filter_map(|skill| {
+                if let Some(id) = skill.as_str() {
+                    return Some(A2aSkill {
+                        id: Some(id.to_string()),
+                        ..Default::default()
+                    });
+                }
+                let parsed = A2aSkill {
+                    id: text(skill, "id"),
+                    name: text(skill, "name"),
+                    tags: skill
+                        .get("tags")
+                        .and_then(|v| v.as_array())
+                        .map(|tags| {
+                            tags.iter()
+                                .filter_map(|t| t.as_str().map(|s| s.to_string()))
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                    description: text(skill, "description"),
+                };
+                (parsed.id.is_some() || parsed.name.is_some()).then_some(parsed)
+            })
+            .collect(),
+    )
+}
+
+/// Whether a declared skill refers to a card skill, by id or by name
+pub fn skill_matches(declared: &str, skill: &A2aSkill) -> bool {
+    [&skill.id, &skill.name]
+        .into_iter()
+        .flatten()
+        .any(|actual| token_matches(declared, actual))
+}
+
+/// Per-skill status of declared skills against a card's skills
+///
+/// Declared skills come first (matched or declared-only, in declaration
+/// order), followed by card skills nothing declared.
+pub fn compare_skills(declared: &[String], card: &[A2aSkill]) -> Vec<SkillMatch> {
+    let mut claimed = vec![false; card.len()];
+    let mut result = Vec::new();
+
+    for skill in declared {
+        match card.iter().position(|actual| skill_matches(skill, actual)) {
+            Some(index) => {
+                claimed[index] = true;
+                result.push(skill_match(SkillStatus::Matched, Some(skill), Some(&card[index])));
+            }
+            None => result.push(skill_match(SkillStatus::DeclaredOnly, Some(skill), None)),
+        }
+    }
+
+    for (skill, claimed) in card.iter().zip(claimed) {
+        if !claimed {
+            result.push(skill_match(SkillStatus::CardOnly, None, Some(skill)));
+        }
+    }
+
+    result
+}
+
+fn skill_match(status: SkillStatus, declared: Option<&String>, card: Option<&A2aSkill>) -> SkillMatch {
+    SkillMatch {
+        status,
+        declared: declared.cloned(),
+        card_id: card.and_then(|c| c.id.clone()),
+        card_name: card.and_then(|c| c.name.clone()),
+        tags: card.map(|c| c.tags.clone()).unwrap_or_default(),
+    }
+}
+
+/// Case, whitespace and separator insensitive
+fn normalize(value: &str) -> String {
+    value
+        .trim()
+        .to_lowercase()
+        .replace([' ', '-'], "_")
+}
+
+/// Exact match, or OASF taxonomy paths sharing their last segment
+///
+/// "agent_orchestration/task_delegation" matches "task_delegation" and
+/// "Task Delegation".
+fn token_matches(declared: &str, actual: &str) -> bool {
+    let declared = normalize(declared);
+    let actual = normalize(actual);
+    if declared.is_empty() || actual.is_empty() {
+        return false;
+    }
+
+    declared == actual || declared.rsplit('/').next() == actual.rsplit('/').next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card() -> Value {
+        serde_json::json!({
+            "name": "Agent",
+            "skills": [
+                { "id": "task_delegation", "name": "Task Delegation", "tags": ["orchestration"] },
+                { "id": "sk-2", "name": "Web Search" },
+                "summarize",
+                { "description": "no id or name" }
+            ]
+        })
+    }
+
+    #[test]
+    fn test_parse_card_skills() {
+        let skills = parse_card_skills(&card()).unwrap();
+        assert_eq!(skills.len(), 3);
+        assert_eq!(skills[0].tags, vec!["orchestration".to_string()]);
+        assert_eq!(skills[2].id.as_deref(), Some("summarize"));
+        assert!(parse_card_skills(&serde_json::json!({ "name": "Agent" })).is_none());
+    }
+
+    #[test]
+    fn test_compare_skills() {
+        let skills = parse_card_skills(&card()).unwrap();
+        let declared = vec![
+            "agent_orchestration/task_delegation".to_string(),
+            "web-search".to_string(),
+            "translation".to_string(),
+        ];
+
+        let result = compare_skills(&declared, &skills);
+        let statuses: Vec<SkillStatus> = result.iter().map(|m| m.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                SkillStatus::Matched,
+                SkillStatus::Matched,
+                SkillStatus::DeclaredOnly,
+                SkillStatus::CardOnly,
+            ]
+        );
+        assert_eq!(result[1].card_id.as_deref(), Some("sk-2"));
+        assert_eq!(result[3].card_id.as_deref(), Some("summarize"));
+    }
+}
@@ -0,0 +1,378 @@
+//! Source code reference verification
+//!
+//! Metadata may point at the agent's repository (`sourceCode`) and the commit
+//! or release it was built from (`sourceCommit`, `sourceRelease`). GitHub
+//! repositories are checked through the REST API: the repository must exist
+//! and be public, should mention the agent's name, and any stated commit or
+//! release must exist. Other hosts only get a reachability and name check on
+//! the linked page.
+
+use serde::Deserialize;
+use tracing::debug;
+
+use crate::http::read_body_limited;
+use crate::ssrf::OutboundGuard;
+use crate::types::{AgentMetadata, Issue, Severity, SourceCodeCheck};
+
+/// GitHub REST API
+pub const GITHUB_API_URL: &str = "https://api.github.com";
+
+/// README or linked page bytes searched for the agent name
+const MAX_PAGE_BYTES: usize = 512 * 1024;
+
+/// A GitHub repository reference parsed from `sourceCode`
+#[derive(Debug, PartialEq, Eq)]
+struct GithubRef {
+    owner: String,
+    repo: String,
+    commit: Option<String>,
+    release: Option<String>,
+}
+
+/// Parse `https://github.com/<owner>/<repo>[/tree|commit|releases/tag/<ref>]`
+fn parse_github(url: &str) -> Option<GithubRef> {
+    let parsed = url::Url::parse(url).ok()?;
+    if !matches!(parsed.host_str()?, "github.com" | "www.github.com") {
+        return None;
+    }
+    let segments: Vec<&str> = parsed.path_segments()?.filter(|s| !s.is_empty()).collect();
+    let (owner, repo) = (segments.first()?, segments.get(1)?);
+    let reference = |index: usize| segments.get(index).map(|s| s.to_string());
+
+    let (commit, release) = match segments.get(2..).unwrap_or_default() {
+        ["commit", sha, ..] => (Some(sha.to_string()), None),
+        ["tree", r, ..] if is_commit_hash(r) => (reference(3), None),
+        ["releases", "tag", _, ..] => (None, reference(4)),
+        _ => (None, None),
+    };
+    Some(GithubRef {
+        owner: owner.to_string(),
+        repo: repo.trim_end_matches(".git").to_string(),
+        commit,
+        release,
+    })
+}
+
+fn is_commit_hash(value: &str) -> bool {
+    (7..=40).contains(&value.len()) && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Lowercase alphanumerics only, so "My-Agent" matches "my_agent" and "myagent"
+fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn mentions(haystack: &str, name: &str) -> bool {
+    let name = normalize(name);
+    !name.is_empty() && normalize(haystack).contains(&name)
+}
+
+#[derive(Deserialize)]
+struct GithubRepo {
+    full_name: String,
+    #[serde(default)]
+    private: bool,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    homepage: Option<String>,
+    #[serde(default)]
+    topics: Vec<String>,
+}
+
+/// Verify the metadata's `sourceCode` reference
+///
+/// Returns `None` when there's no http(s) `sourceCode` to check. `api_url` is
+/// the GitHub API base; `token` raises its rate limit and lets private
+/// repositories be told apart from missing ones.
+pub async fn check_source_code(
+    client: &reqwest::Client,
+    guard: &OutboundGuard,
+    api_url: &str,
+    token: Option<&str>,
+    metadata: &AgentMetadata,
+) -> Option<(SourceCodeCheck, Vec<Issue>)> {
+    let url = metadata.source_code.as_deref()?;
+    if !url.starts_with("https://") && !url.starts_with("http://") {
+        return None;
+    }
+    debug!("Verifying source code reference {}", url);
+
+    let mut check = SourceCodeCheck {
+        url: url.to_string(),
+        ..Default::default()
+    };
+    let name = metadata.name.as_deref().unwrap_or_default();
+
+    match parse_github(url) {
+        Some(github) => {
+            check.repository = Some(format!("{}/{}", github.owner, github.repo));
+            check.commit = metadata.source_commit.clone().or(github.commit.clone());
+            check.release = metadata.source_release.clone().or(github.release.clone());
+            let api = GithubApi { client, base: api_url, token };
+            if let Err(e) = api.verify(&github, name, &mut check).await {
+                debug!("Source code lookup for {} failed: {}", url, e);
+                check.error = Some(e);
+            }
+        }
+        None => {
+            if let Err(e) = check_page(client, guard, url, name, &mut check).await {
+                debug!("Source code page {} failed: {}", url, e);
+                check.error = Some(e);
+            }
+        }
+    }
+
+    let issues = issues_for(&check, name);
+    Some((check, issues))
+}
+
+struct GithubApi<'a> {
+    client: &'a reqwest::Client,
+    base: &'a str,
+    token: Option<&'a str>,
+}
+
+impl GithubApi<'_> {
+    async fn get(&self, path: &str, accept: &str) -> Result<reqwest::Response, String> {
+        let mut request = self
+            .client
+            .get(format!("{}{}", self.base.trim_end_matches('/'), path))
+            .header("Accept", accept)
+            .header("X-GitHub-Api-Version", "2022-11-28");
+        if let Some(token) = self.token {
+            request = request.bearer_auth(token);
+        }
+        request
+            .send()
+            .await
+            .map_err(|e| format!("GitHub request failed: {}", e))
+    }
+
+    /// Whether `path` exists (404 and 422 mean no)
+    async fn exists(&self, path: &str) -> Result<bool, String> {
+        let response = self.get(path, "application/vnd.github+json").await?;
+        match response.status().as_u16() {
+            200..=299 => Ok(true),
+            404 | 422 => Ok(false),
+            status => Err(format!("GitHub returned HTTP {} for {}", status, path)),
+        }
+    }
+
+    async fn verify(
+        &self,
+        github: &GithubRef,
+        name: &str,
+        check: &mut SourceCodeCheck,
+    ) -> Result<(), String> {
+        let repo_path = format!("/repos/{}/{}", github.owner, github.repo);
+        let response = self.get(&repo_path, "application/vnd.github+json").await?;
+        match response.status().as_u16() {
+            200 => {}
+            // Private repositories are indistinguishable from missing ones without access
+            404 => return Ok(()),
+            status => return Err(format!("GitHub returned HTTP {}", status)),
+        }
+        let repo: GithubRepo = response
+            .json()
+            .await
+            .map_err(|e| format!("Invalid GitHub response: {}", e))?;
+        check.exists = true;
+        check.public = Some(!repo.private);
+
+        if !name.is_empty() {
+            let mut text = format!(
+                "{} {} {} {}",
+                repo.full_name,
+                repo.description.unwrap_or_default(),
+                repo.homepage.unwrap_or_default(),
+                repo.topics.join(" ")
+            );
+            if !mentions(&text, name) {
+                let readme = self
+                    .get(&format!("{}/readme", repo_path), "application/vnd.github.raw")
+                    .await?;
+                if readme.status().is_success() {
+                    if let Ok(body) = read_body_limited(readme, MAX_PAGE_BYTES).await {
+                        text.push_str(&String::from_utf8_lossy(&body));
+                    }
+                }
+            }
+            check.references_name = Some(mentions(&text, name));
+        }
+
+        if let Some(commit) = &check.commit {
+            check.commit_found = Some(
+                self.exists(&format!("{}/commits/{}", repo_path, urlencoding::encode(commit)))
+                    .await?,
+            );
+        }
+        if let Some(release) = &check.release {
+            check.release_found = Some(
+                self.exists(&format!(
+                    "{}/releases/tags/{}",
+                    repo_path,
+                    urlencoding::encode(release)
+                ))
+                .await?,
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Non-GitHub hosts: the page must load, and should mention the agent name
+async fn check_page(
+    client: &reqwest::Client,
+    guard: &OutboundGuard,
+    url: &str,
+    name: &str,
+    check: &mut SourceCodeCheck,
+) -> Result<(), String> {
+    guard.check(url).await.map_err(|e| format!("Not fetched: {}", e))?;
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Ok(());
+    }
+    check.exists = true;
+    if !name.is_empty() {
+        let body = read_body_limited(response, MAX_PAGE_BYTES)
+            .await
+            .unwrap_or_default();
+        check.references_name = Some(mentions(&String::from_utf8_lossy(&body), name));
+    }
+    Ok(())
+}
+
+fn issues_for(check: &SourceCodeCheck, name: &str) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    if check.error.is_some() {
+        return issues;
+    }
+
+    if !check.exists {
+        issues.push(Issue::new(
+            Severity::Warning,
+            "SOURCE_CODE_NOT_FOUND",
+            format!("sourceCode {} doesn't exist or isn't public", check.url),
+        ));
+        return issues;
+    }
+    if check.public == Some(false) {
+        issues.push(Issue::new(
+            Severity::Warning,
+            "SOURCE_CODE_PRIVATE",
+            format!("sourceCode repository {} is private", check.url),
+        ));
+    }
+    if check.references_name == Some(false) {
+        issues.push(Issue::new(
+            Severity::Info,
+            "SOURCE_CODE_NAME_MISMATCH",
+            format!("sourceCode {} doesn't mention the agent name '{}'", check.url, name),
+        ));
+    }
+    if let (Some(commit), Some(false)) = (&check.commit, check.commit_found) {
+        issues.push(Issue::new(
+            Severity::Warning,
+            "SOURCE_COMMIT_NOT_FOUND",
+            format!("Commit {} not found in {}", commit, check.url),
+        ));
+    }
+    if let (Some(release), Some(false)) = (&check.release, check.release_found) {
+        issues.push(Issue::new(
+            Severity::Warning,
+            "SOURCE_RELEASE_NOT_FOUND",
+            format!("Release {} not found in {}", release, check.url),
+        ));
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::path;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn test_parse_github() {
+        let parsed = parse_github("https://github.com/acme/agent.git").unwrap();
+        assert_eq!((parsed.owner.as_str(), parsed.repo.as_str()), ("acme", "agent"));
+        assert_eq!(
+            parse_github("https://github.com/acme/agent/commit/abc1234").unwrap().commit,
+            Some("abc1234".to_string())
+        );
+        assert_eq!(
+            parse_github("https://github.com/acme/agent/releases/tag/v1.2.0").unwrap().release,
+            Some("v1.2.0".to_string())
+        );
+        assert_eq!(parse_github("https://github.com/acme/agent/tree/main").unwrap().commit, None);
+        assert!(parse_github("https://gitlab.com/acme/agent").is_none());
+        assert!(parse_github("https://github.com/acme").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_check_github_repository() {
+        let server = MockServer::start().await;
+        Mock::given(path("/repos/acme/agent"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "full_name": "acme/agent",
+                "private": false,
+                "description": "Source of the Weather-Bot agent"
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(path("/repos/acme/agent/commits/abc1234"))
+            .respond_with(ResponseTemplate::new(422))
+            .mount(&server)
+            .await;
+
+        let mut metadata: AgentMetadata = serde_json::from_value(serde_json::json!({
+            "name": "weather bot",
+            "sourceCode": "https://github.com/acme/agent",
+            "sourceCommit": "abc1234"
+        }))
+        .unwrap();
+        let guard = OutboundGuard {
+            enabled: false,
+            ..Default::default()
+        };
+        let (check, issues) = check_source_code(
+            &reqwest::Client::new(),
+            &guard,
+            &server.uri(),
+            None,
+            &metadata,
+        )
+        .await
+        .unwrap();
+
+        assert!(check.exists && check.public == Some(true));
+        assert_eq!(check.references_name, Some(true));
+        assert_eq!(check.commit_found, Some(false));
+        assert!(!check.verified());
+        let codes: Vec<&str> = issues.iter().map(|i| i.code.as_str()).collect();
+        assert_eq!(codes, vec!["SOURCE_COMMIT_NOT_FOUND"]);
+
+        metadata.source_code = Some("https://github.com/acme/missing".to_string());
+        let (check, issues) = check_source_code(
+            &reqwest::Client::new(),
+            &guard,
+            &server.uri(),
+            None,
+            &metadata,
+        )
+        .await
+        .unwrap();
+        assert!(!check.exists);
+        assert_eq!(issues[0].code, "SOURCE_CODE_NOT_FOUND");
+    }
+}
@@ -0,0 +1,263 @@
+//! Trust-mechanism profiles
+//!
+//! An agent's `supportedTrust` says how clients are meant to trust it, so the
+//! audit checks that the declared mechanism is actually in place and weighs
+//! the categories it depends on more heavily:
+//!
+//! - `tee-attestation`: an attestation endpoint must be declared and reachable;
+//!   security counts double.
+//! - `reputation`: the agent must have received feedback in the reputation
+//!   registry; on-chain counts double.
+//! - `crypto-economic`: an agent wallet must be set to hold stake; on-chain
+//!   counts 1.5x.
+
+use alloy::primitives::Address;
+use std::collections::HashSet;
+use tracing::{debug, warn};
+
+use super::owner_risk;
+use super::scoring::ScoreWeights;
+use crate::blockchain::reputation::ReputationClient;
+use crate::chains::{get_all_rpcs, get_chain, registry_start_block};
+use crate::http::read_body_limited;
+use crate::ssrf::OutboundGuard;
+use crate::types::{AgentMetadata, Issue, Severity, TrustChecks, TrustProfile, WatchyError};
+
+/// Security points lost when a TEE agent's attestation can't be checked
+pub const TEE_PENALTY: u8 = 20;
+/// On-chain points lost when a reputation agent has no feedback
+pub const REPUTATION_PENALTY: u8 = 20;
+/// On-chain points lost when a crypto-economic agent has no wallet
+pub const CRYPTO_ECONOMIC_PENALTY: u8 = 20;
+
+/// Service names and raw metadata key that declare a TEE attestation endpoint
+const ATTESTATION_SERVICES: &[&str] = &["tee", "attestation", "tee-attestation"];
+const ATTESTATION_KEY: &str = "teeAttestation";
+/// Largest attestation document read
+const MAX_ATTESTATION_BYTES: usize = 256 * 1024;
+
+impl TrustProfile {
+    /// Map a `supportedTrust` value to its profile (unknown values have none)
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().to_lowercase().replace(['_', ' '], "-");
+        match value.as_str() {
+            "tee" | "tee-attestation" | "attestation" => Some(TrustProfile::Tee),
+            "reputation" | "feedback" => Some(TrustProfile::Reputation),
+            "crypto-economic" | "cryptoeconomic" | "stake" | "staking" => {
+                Some(TrustProfile::CryptoEconomic)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Profiles for the declared trust mechanisms, without duplicates
+pub fn select_profiles(supported_trust: &[String]) -> Vec<TrustProfile> {
+    let mut profiles = Vec::new();
+    for profile in supported_trust.iter().filter_map(|v| TrustProfile::parse(v)) {
+        if !profiles.contains(&profile) {
+            profiles.push(profile);
+        }
+    }
+    profiles
+}
+
+/// Category weights adjusted for the agent's profiles
+pub fn weights_for(base: &ScoreWeights, profiles: &[TrustProfile]) -> ScoreWeights {
+    let mut weights = base.clone();
+    for profile in profiles {
+        match profile {
+            TrustProfile::Tee => weights.security *= 2.0,
+            TrustProfile::Reputation => weights.onchain *= 2.0,
+            TrustProfile::CryptoEconomic => weights.onchain *= 1.5,
+        }
+    }
+    weights
+}
+
+/// Attestation endpoint from a TEE service or the `teeAttestation` field
+pub fn attestation_url(metadata: &AgentMetadata) -> Option<String> {
+    metadata
+        .services
+        .iter()
+        .find(|s| ATTESTATION_SERVICES.contains(&s.name.to_lowercase().as_str()))
+        .and_then(|s| s.endpoint.clone())
+        .or_else(|| {
+            metadata
+                .raw
+                .get(ATTESTATION_KEY)
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+        })
+}
+
+/// Whether the attestation endpoint returns a non-empty document
+pub async fn attestation_reachable(
+    client: &reqwest::Client,
+    guard: &OutboundGuard,
+    url: &str,
+) -> bool {
+    if let Err(reason) = guard.check(url).await {
+        debug!("Attestation {} not fetched: {}", url, reason);
+        return false;
+    }
+    match client.get(url).send().await {
+        Ok(response) if response.status().is_success() => {
+            read_body_limited(response, MAX_ATTESTATION_BYTES)
+                .await
+                .is_ok_and(|body| !body.iter().all(u8::is_ascii_whitespace))
+        }
+        Ok(response) => {
+            debug!("Attestation {} returned {}", url, response.status());
+            false
+        }
+        Err(e) => {
+            debug!("Attestation {} failed: {}", url, e);
+            false
+        }
+    }
+}
+
+/// TEE profile: issue and security penalty when attestation is missing or down
+pub fn assess_tee(checks: &TrustChecks) -> Option<(Issue, u8)> {
+    let issue = match (&checks.attestation_url, checks.attestation_reachable) {
+        (None, _) => Issue::new(
+            Severity::Warning,
+            "TEE_ATTESTATION_MISSING",
+            "Declares TEE trust but no attestation endpoint (a TEE service or \
+                      teeAttestation)"
+                .to_string(),
+        ),
+        (Some(url), Some(false)) => Issue::new(
+            Severity::Warning,
+            "TEE_ATTESTATION_UNREACHABLE",
+            format!("TEE attestation endpoint {} is unreachable or empty", url),
+        ),
+        _ => return None,
+    };
+    Some((issue, TEE_PENALTY))
+}
+
+/// Feedback entries and distinct clients, with RPC failover
+///
+/// Watchy's own feedback (from `own_addresses`) doesn't count: every audited
+/// agent has it.
+pub async fn fetch_feedback_summary(
+    chain_id: u64,
+    agent_id: u64,
+    own_addresses: &[String],
+) -> Result<(u64, u64), WatchyError> {
+    let excluded: HashSet<Address> = own_addresses
+        .iter()
+        .filter_map(|address| address.parse().ok())
+        .collect();
+    let reputation_address = get_chain(chain_id)
+        .and_then(|chain| chain.reputation_address)
+        .ok_or_else(|| {
+            WatchyError::InvalidRequest(format!("No reputation registry on chain {}", chain_id))
+        })?;
+    let from_block = registry_start_block(chain_id);
+
+    let mut last_error = format!("No RPC URLs available for chain {}", chain_id);
+    for rpc_url in get_all_rpcs(chain_id) {
+        let result = match ReputationClient::new(&rpc_url, reputation_address, None) {
+            Ok(client) => client.feedback_summary(agent_id, from_block, &excluded).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(summary) => return Ok(summary),
+            Err(e) => {
+                warn!("Feedback lookup via {} failed: {}", rpc_url, e);
+                last_error = e.to_string();
+            }
+        }
+    }
+    Err(WatchyError::BlockchainError(last_error))
+}
+
+/// Reputation profile: issue and on-chain penalty when there's no feedback
+pub fn assess_reputation(checks: &TrustChecks) -> Option<(Issue, u8)> {
+    (checks.feedback_count == Some(0)).then(|| {
+        (
+            Issue::new(
+                Severity::Warning,
+                "REPUTATION_NO_FEEDBACK",
+                "Declares reputation trust but has no feedback from other clients in the reputation registry"
+                    .to_string(),
+            ),
+            REPUTATION_PENALTY,
+        )
+    })
+}
+
+/// Crypto-economic profile: wallet balance, if a wallet is set
+pub async fn fetch_wallet_balance(chain_id: u64, wallet: &str) -> Option<String> {
+    match owner_risk::fetch_account_activity(chain_id, wallet).await {
+        Ok(activity) => Some(activity.balance.to_string()),
+        Err(e) => {
+            warn!("Wallet balance lookup failed: {}", e);
+            None
+        }
+    }
+}
+
+/// Crypto-economic profile: issues, and the on-chain penalty for a missing wallet
+pub fn assess_crypto_economic(wallet: Option<&str>, checks: &TrustChecks) -> (Vec<Issue>, u8) {
+    if wallet.is_none() {
+        let issue = Issue::new(
+            Severity::Warning,
+            "CRYPTO_ECONOMIC_NO_WALLET",
+            "Declares crypto-economic trust but has no agent wallet to hold stake"
+                .to_string(),
+        );
+        return (vec![issue], CRYPTO_ECONOMIC_PENALTY);
+    }
+    if checks.wallet_balance_wei.as_deref() == Some("0") {
+        let issue = Issue::new(
+            Severity::Info,
+            "CRYPTO_ECONOMIC_UNFUNDED",
+            "Agent wallet holds no native balance to back crypto-economic trust"
+                .to_string(),
+        );
+        return (vec![issue], 0);
+    }
+    (vec![], 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_profiles() {
+        let declared = ["reputation", "TEE-Attestation", "crypto_economic", "reputation", "zk"]
+            .map(str::to_string);
+        assert_eq!(
+            select_profiles(&declared),
+            vec![TrustProfile::Reputation, TrustProfile::Tee, TrustProfile::CryptoEconomic]
+        );
+
+        let base = ScoreWeights::default();
+        let weights = weights_for(&base, &[TrustProfile::Tee]);
+        assert_eq!(weights.security, base.security * 2.0);
+        assert_eq!(weights.onchain, base.onchain);
+    }
+
+    #[test]
+    fn test_assess() {
+        let mut checks = TrustChecks::default();
+        assert_eq!(assess_tee(&checks).unwrap().0.code, "TEE_ATTESTATION_MISSING");
+        checks.attestation_url = Some("https://agent.example.com/attestation".to_string());
+        checks.attestation_reachable = Some(true);
+        assert!(assess_tee(&checks).is_none());
+
+        checks.feedback_count = Some(0);
+        assert_eq!(assess_reputation(&checks).unwrap().1, REPUTATION_PENALTY);
+
+        let (issues, penalty) = assess_crypto_economic(None, &checks);
+        assert_eq!((issues[0].code.as_str(), penalty), ("CRYPTO_ECONOMIC_NO_WALLET", 20));
+        checks.wallet_balance_wei = Some("0".to_string());
+        let (issues, penalty) = assess_crypto_economic(Some("0xabc"), &checks);
+        assert_eq!((issues[0].code.as_str(), penalty), ("CRYPTO_ECONOMIC_UNFUNDED", 0));
+    }
+}
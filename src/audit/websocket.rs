@@ -0,0 +1,295 @@
+//! WebSocket agent endpoints (streaming A2A)
+//!
+//! `ws://` and `wss://` endpoints get a handshake, ping/pong round trips for
+//! latency and, for A2A, a JSON-RPC request for the agent card. The
+//! connection is opened here on addresses that passed the SSRF guard, since
+//! tokio-tungstenite would otherwise resolve the host itself.
+
+use futures_util::{SinkExt, StreamExt};
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::header::{HeaderValue, USER_AGENT};
+use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use tracing::debug;
+
+use super::endpoints::{calculate_percentiles, check_a2a_card, ProbeRate};
+use crate::ssrf::OutboundGuard;
+use crate::types::{EndpointCheck, Issue, Service, ServiceType, Severity, WebSocketInfo};
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Timeout for the handshake and for each reply
+const WS_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// JSON-RPC method asking an A2A server for its agent card
+const AGENT_CARD_METHOD: &str = "agent/getAuthenticatedExtendedCard";
+
+/// Whether an endpoint is a WebSocket URL
+pub fn is_websocket(endpoint: &str) -> bool {
+    endpoint.starts_with("wss://") || endpoint.starts_with("ws://")
+}
+
+/// Test a WebSocket endpoint and return the check and the agent card, if any
+pub async fn test_endpoint(
+    guard: &OutboundGuard,
+    user_agent: &str,
+    service: &Service,
+    endpoint: &str,
+    max_bytes: usize,
+    rate: ProbeRate,
+) -> (EndpointCheck, Option<serde_json::Value>) {
+    let service_name = service.name.as_str();
+    debug!("Testing {} WebSocket endpoint: {}", service_name, endpoint);
+
+    let mut check = EndpointCheck {
+        service: service_name.to_string(),
+        endpoint: endpoint.to_string(),
+        reachable: false,
+        valid_schema: None,
+        skills_match: None,
+        latency: None,
+        error: None,
+        not_probed: None,
+        host_policy: None,
+        skills: vec![],
+        transport: None,
+        tool_fingerprints: BTreeMap::new(),
+        content_hash: None,
+        availability: None,
+        rate_limit: None,
+        dual_stack: None,
+        websocket: None,
+        grpc: None,
+        issues: vec![],
+    };
+
+    let connected =
+        tokio::time::timeout(WS_TIMEOUT, connect(guard, user_agent, endpoint, max_bytes))
+            .await
+            .unwrap_or_else(|_| Err("WebSocket handshake timed out".to_string()));
+    let (mut ws, subprotocol, handshake_ms) = match connected {
+        Ok(connected) => connected,
+        Err(e) => {
+            check.error = Some(e);
+            check.issues.push(Issue::new(
+                Severity::Critical,
+                "ENDPOINT_UNREACHABLE",
+                format!("{} endpoint is not reachable", service_name),
+            ));
+            return (check, None);
+        }
+    };
+    check.reachable = true;
+
+    // Ping round trips stand in for request latency
+    let mut latencies = vec![];
+    for sample in 0..rate.samples {
+        let payload = sample.to_be_bytes().to_vec();
+        let start = Instant::now();
+        if ws.send(Message::Ping(payload.clone())).await.is_err() {
+            break;
+        }
+        match tokio::time::timeout(WS_TIMEOUT, wait_for_pong(&mut ws, &payload)).await {
+            Ok(true) => latencies.push(start.elapsed().as_millis() as u64),
+            _ => break,
+        }
+        tokio::time::sleep(Duration::from_millis(rate.interval_ms)).await;
+    }
+    let ping_pong = !latencies.is_empty();
+    if !ping_pong {
+        check.issues.push(Issue::new(
+            Severity::Warning,
+            "WS_NO_PONG",
+            format!(
+                "{} WebSocket endpoint doesn't answer pings; idle connections can't be kept alive",
+                service_name
+            ),
+        ));
+        latencies.push(handshake_ms);
+    }
+    check.latency = Some(calculate_percentiles(&latencies));
+
+    let mut jsonrpc = None;
+    let mut card = None;
+    if ServiceType::from(service_name) == ServiceType::A2A {
+        let reply = tokio::time::timeout(WS_TIMEOUT, request_agent_card(&mut ws))
+            .await
+            .ok()
+            .flatten();
+        jsonrpc = Some(reply.is_some());
+        // Servers that require auth answer with an error, which leaves the card unjudged
+        card = reply.and_then(|reply| reply.get("result").cloned());
+        if let Some(json) = &card {
+            check_a2a_card(json, service, &mut check);
+        }
+    }
+    let _ = ws.close(None).await;
+
+    if endpoint.starts_with("ws://") {
+        check.issues.push(Issue::new(
+            Severity::Warning,
+            "WS_NOT_ENCRYPTED",
+            format!("{} endpoint uses unencrypted ws://", service_name),
+        ));
+    }
+
+    check.websocket = Some(WebSocketInfo {
+        handshake_ms,
+        subprotocol,
+        ping_pong,
+        jsonrpc,
+    });
+    (check, card)
+}
+
+/// Open the connection on guarded addresses and upgrade it
+///
+/// # Returns
+/// (stream, selected subprotocol, handshake time in ms)
+async fn connect(
+    guard: &OutboundGuard,
+    user_agent: &str,
+    endpoint: &str,
+    max_bytes: usize,
+) -> Result<(WsStream, Option<String>, u64), String> {
+    let url = url::Url::parse(endpoint).map_err(|e| format!("Invalid URL: {}", e))?;
+    let host = url
+        .host_str()
+        .ok_or_else(|| "URL has no host".to_string())?
+        .trim_start_matches('[')
+        .trim_end_matches(']');
+    let port = url
+        .port_or_known_default()
+        .ok_or_else(|| "URL has no port".to_string())?;
+
+    let mut request = endpoint
+        .into_client_request()
+        .map_err(|e| format!("Invalid WebSocket request: {}", e))?;
+    if let Ok(value) = HeaderValue::from_str(user_agent) {
+        request.headers_mut().insert(USER_AGENT, value);
+    }
+
+    let start = Instant::now();
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| format!("Could not resolve {}: {}", host, e))?
+        .collect();
+    guard.check_resolved(host, &addrs)?;
+    let stream = TcpStream::connect(&addrs[..])
+        .await
+        .map_err(|e| format!("Connection failed: {}", e))?;
+
+    let config = WebSocketConfig {
+        max_message_size: Some(max_bytes),
+        max_frame_size: Some(max_bytes),
+        ..Default::default()
+    };
+    let (ws, response) =
+        tokio_tungstenite::client_async_tls_with_config(request, stream, Some(config), None)
+            .await
+            .map_err(|e| format!("WebSocket handshake failed: {}", e))?;
+
+    let subprotocol = response
+        .headers()
+        .get("sec-websocket-protocol")
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    Ok((ws, subprotocol, start.elapsed().as_millis() as u64))
+}
+
+/// Read until the pong for `payload`; `false` if the connection ends first
+async fn wait_for_pong(ws: &mut WsStream, payload: &[u8]) -> bool {
+    while let Some(Ok(message)) = ws.next().await {
+        match message {
+            Message::Pong(data) if data == payload => return true,
+            Message::Close(_) => return false,
+            // Server greetings and stream events aren't for us
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Send the agent card request and return the JSON-RPC reply to it
+async fn request_agent_card(ws: &mut WsStream) -> Option<serde_json::Value> {
+    let request = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": "watchy-card",
+        "method": AGENT_CARD_METHOD,
+    });
+    ws.send(Message::Text(request.to_string())).await.ok()?;
+
+    while let Some(Ok(message)) = ws.next().await {
+        match message {
+            Message::Text(text) => {
+                let Ok(reply) = serde_json::from_str::<serde_json::Value>(&text) else {
+                    continue;
+                };
+                if reply.get("id").and_then(|id| id.as_str()) == Some("watchy-card") {
+                    return Some(reply);
+                }
+            }
+            Message::Close(_) => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_websocket_probe() {
+        // A2A server that answers the card request (pongs are automatic)
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("ws://{}/a2a", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            while let Some(Ok(message)) = ws.next().await {
+                if let Message::Text(text) = message {
+                    let request: serde_json::Value = serde_json::from_str(&text).unwrap();
+                    let reply = serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "id": request["id"],
+                        "result": { "name": "Streamer", "skills": [{ "id": "chat" }] },
+                    });
+                    ws.send(Message::Text(reply.to_string())).await.unwrap();
+                }
+            }
+        });
+
+        let guard = OutboundGuard::default().with_allowed_host("127.0.0.1");
+        let service: Service = serde_json::from_value(serde_json::json!({
+            "name": "A2A",
+            "endpoint": endpoint,
+        }))
+        .unwrap();
+        let rate = ProbeRate {
+            samples: 2,
+            interval_ms: 0,
+            deadline: None,
+        };
+        let (check, card) =
+            test_endpoint(&guard, "test", &service, &endpoint, 1024 * 1024, rate).await;
+
+        assert!(check.reachable);
+        let websocket = check.websocket.unwrap();
+        assert!(websocket.ping_pong);
+        assert_eq!(websocket.jsonrpc, Some(true));
+        assert_eq!(check.valid_schema, Some(true));
+        assert_eq!(card.unwrap()["name"], "Streamer");
+        let codes: Vec<&str> = check.issues.iter().map(|i| i.code.as_str()).collect();
+        assert_eq!(codes, ["WS_NOT_ENCRYPTED"]);
+
+        assert!(is_websocket("wss://agent.example.com/a2a"));
+        assert!(!is_websocket("https://agent.example.com/a2a"));
+    }
+}
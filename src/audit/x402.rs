@@ -0,0 +1,530 @@
+//! x402 payment requirement checks
+//!
+//! A paid endpoint answers an unpaid request with 402 and a JSON body listing
+//! the payment requirements it accepts (`accepts`). The requirements are
+//! recorded on the content checks and cross-checked against the agent's
+//! on-chain identity: payments should go to the agent wallet registered with
+//! `getAgentWallet` (or the owner), not to an unrelated address. The payment
+//! asset must be a known stablecoin deployed on the stated network, and the
+//! advertised price is normalized to USD for comparison.
+
+use serde::Deserialize;
+use tracing::warn;
+
+use super::pricing::PricingConfig;
+use crate::blockchain::account::AccountClient;
+use crate::caip;
+use crate::chains::{get_all_rpcs, get_chain, ChainType};
+use crate::types::{
+    AgentInfo, ContentChecks, Issue, Severity, WatchyError, X402AssetCheck, X402Price,
+    X402Requirement,
+};
+
+/// Largest 402 body read for payment requirements
+pub const MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// RPC lookups of unknown assets per audit; the rest are left unchecked
+const MAX_ASSET_LOOKUPS: usize = 5;
+
+/// Price feed lookups of tokens other than the known stablecoins, per audit
+const MAX_PRICE_LOOKUPS: usize = 5;
+
+/// A stablecoin x402 payments are expected in
+#[derive(Debug, Clone, Copy)]
+pub struct KnownAsset {
+    pub chain_id: u64,
+    pub address: &'static str,
+    pub symbol: &'static str,
+    pub decimals: u8,
+    /// USD value the token is pegged to (None for non-USD stablecoins)
+    pub usd_peg: Option<f64>,
+}
+
+const fn asset(
+    chain_id: u64,
+    address: &'static str,
+    symbol: &'static str,
+    decimals: u8,
+    usd_peg: Option<f64>,
+) -> KnownAsset {
+    KnownAsset {
+        chain_id,
+        address,
+        symbol,
+        decimals,
+        usd_peg,
+    }
+}
+
+const USD: Option<f64> = Some(1.0);
+
+/// Stablecoins accepted as x402 payment assets, per chain
+#[rustfmt::skip]
+pub const STABLECOINS: &[KnownAsset] = &[
+    // Base
+    asset(8453, "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913", "USDC", 6, USD),
+    asset(8453, "0xd9aAEc86B65D86f6A7B5B1b0c42FFA531710b6CA", "USDbC", 6, USD),
+    asset(8453, "0x50c5725949A6F0c72E6C4a641F24049A917DB0Cb", "DAI", 18, USD),
+    asset(8453, "0x60a3E35Cc302bFA44Cb288Bc5a4F316Fdb1adb42", "EURC", 6, None),
+    // Ethereum
+    asset(1, "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48", "USDC", 6, USD),
+    asset(1, "0xdAC17F958D2ee523a2206206994597C13D831ec7", "USDT", 6, USD),
+    asset(1, "0x6B175474E89094C44Da98b954EedeAC495271d0F", "DAI", 18, USD),
+    asset(1, "0x1aBaEA1f7C830bD89Acc67eC4af516284b1bC33c", "EURC", 6, None),
+    // Testnets
+    asset(84532, "0x036CbD53842c5426634e7929541eC2318f3dCF7e", "USDC", 6, USD),
+    asset(11155111, "0x1c7D4B196Cb0C7B01d743Fbc6116a902379C7238", "USDC", 6, USD),
+    // Solana (SPL mints)
+    asset(101, "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", "USDC", 6, USD),
+    asset(101, "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB", "USDT", 6, USD),
+    asset(103, "4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU", "USDC", 6, USD),
+];
+
+/// Chain an x402 network refers to: a chain name ("base", "base-sepolia"),
+/// a CAIP-2 ID ("eip155:8453") or a bare chain ID
+pub fn network_chain_id(network: &str) -> Option<u64> {
+    let chain_id = caip::parse_chain_param(&network.to_lowercase()).ok()?;
+    get_chain(chain_id).map(|chain| chain.chain_id)
+}
+
+/// Stablecoin at an address on a chain (EVM addresses compare case-insensitively)
+pub fn known_asset(chain_id: u64, address: &str) -> Option<&'static KnownAsset> {
+    STABLECOINS.iter().find(|known| {
+        known.chain_id == chain_id
+            && (known.address == address
+                || (address.starts_with("0x") && known.address.eq_ignore_ascii_case(address)))
+    })
+}
+
+#[derive(Deserialize)]
+struct PaymentRequired {
+    #[serde(default)]
+    accepts: Vec<X402Requirement>,
+}
+
+/// Payment requirements in a 402 response body (empty when it has none)
+pub fn parse_accepts(body: &[u8]) -> Vec<X402Requirement> {
+    serde_json::from_slice::<PaymentRequired>(body)
+        .map(|required| required.accepts)
+        .unwrap_or_default()
+}
+
+/// Check that every advertised payTo address belongs to the agent
+///
+/// Runs after the on-chain phase, so `agent` carries the registered wallet.
+pub fn check_pay_to(checks: &mut ContentChecks, agent: &AgentInfo) {
+    let Some(x402) = checks.x402_valid.as_mut() else {
+        return;
+    };
+
+    let mut pay_to: Vec<String> = x402
+        .accepts
+        .iter()
+        .map(|requirement| requirement.pay_to.clone())
+        .chain(x402.payment_address.clone())
+        .filter(|address| is_evm_address(address))
+        .map(|address| address.to_lowercase())
+        .collect();
+    pay_to.sort();
+    pay_to.dedup();
+    if pay_to.is_empty() {
+        return;
+    }
+
+    let related = |address: &String| {
+        [&agent.wallet, &agent.owner]
+            .into_iter()
+            .flatten()
+            .any(|known| known.eq_ignore_ascii_case(address))
+    };
+    let unrelated: Vec<&String> = pay_to.iter().filter(|address| !related(address)).collect();
+
+    if unrelated.is_empty() {
+        x402.pay_to_matches_wallet = Some(true);
+    } else if agent.wallet.is_none() {
+        checks.issues.push(Issue::new(
+            Severity::Warning,
+            "X402_PAYTO_UNVERIFIED",
+            format!(
+                "x402 payments go to {}, but the agent has no on-chain wallet to compare against",
+                join(&unrelated)
+            ),
+        ));
+    } else {
+        x402.pay_to_matches_wallet = Some(false);
+        checks.passed = false;
+        checks.issues.push(Issue::new(
+            Severity::Critical,
+            "X402_PAYTO_MISMATCH",
+            format!(
+                "x402 payments go to {}, which is neither the agent wallet nor the owner",
+                join(&unrelated)
+            ),
+        ));
+    }
+}
+
+/// Check every advertised payment asset against the stablecoin list and the chain
+///
+/// An asset with no contract on the stated chain fails the content phase; an
+/// asset that exists but isn't a known stablecoin is flagged for review.
+/// Known stablecoins need no lookup; at most `MAX_ASSET_LOOKUPS` others are
+/// looked up.
+pub async fn check_assets(checks: &mut ContentChecks) {
+    let Some(x402) = checks.x402_valid.as_mut() else {
+        return;
+    };
+
+    let mut lookups = 0;
+    for requirement in &x402.accepts {
+        if requirement.asset.is_empty()
+            || x402.assets.iter().any(|checked| {
+                checked.network == requirement.network
+                    && checked.asset.eq_ignore_ascii_case(&requirement.asset)
+            })
+        {
+            continue;
+        }
+        let mut asset = X402AssetCheck {
+            network: requirement.network.clone(),
+            asset: requirement.asset.clone(),
+            ..Default::default()
+        };
+
+        let Some(chain_id) = network_chain_id(&requirement.network) else {
+            checks.issues.push(Issue::new(
+                Severity::Info,
+                "X402_UNSUPPORTED_NETWORK",
+                format!(
+                    "x402 asset {} is on network '{}', which Watchy can't verify",
+                    requirement.asset, requirement.network
+                ),
+            ));
+            x402.assets.push(asset);
+            continue;
+        };
+        asset.chain_id = Some(chain_id);
+        asset.symbol =
+            known_asset(chain_id, &requirement.asset).map(|known| known.symbol.to_string());
+
+        let evm = get_chain(chain_id).is_some_and(|chain| chain.chain_type == ChainType::Evm);
+        if evm {
+            if !is_evm_address(&requirement.asset) {
+                asset.exists = Some(false);
+            } else if asset.symbol.is_some() {
+                asset.exists = Some(true);
+            } else if lookups >= MAX_ASSET_LOOKUPS {
+                warn!("Not looking up x402 asset {}: lookup cap reached", requirement.asset);
+            } else {
+                lookups += 1;
+                match asset_deployed(chain_id, &requirement.asset).await {
+                    Ok(exists) => asset.exists = Some(exists),
+                    Err(e) => warn!("Could not look up x402 asset {}: {}", requirement.asset, e),
+                }
+            }
+        }
+
+        if asset.exists == Some(false) {
+            checks.passed = false;
+            checks.issues.push(Issue::new(
+                Severity::Error,
+                "X402_ASSET_NOT_FOUND",
+                format!(
+                    "x402 asset {} has no contract on {}",
+                    requirement.asset, requirement.network
+                ),
+            ));
+        } else if asset.symbol.is_none() {
+            checks.issues.push(Issue::new(
+                Severity::Warning,
+                "X402_UNKNOWN_ASSET",
+                format!(
+                    "x402 asset {} on {} isn't a known stablecoin",
+                    requirement.asset, requirement.network
+                ),
+            ));
+        }
+        x402.assets.push(asset);
+    }
+}
+
+/// Record each requirement's price per call in USD and flag implausible ones
+///
+/// Known stablecoins are always normalized; other tokens only when the price
+/// feed lists them, for at most `MAX_PRICE_LOOKUPS` of them per audit. Zero,
+/// malformed and implausibly high prices are warnings and don't affect the score.
+pub async fn check_prices(
+    checks: &mut ContentChecks,
+    client: &reqwest::Client,
+    pricing: &PricingConfig,
+) {
+    let Some(x402) = checks.x402_valid.as_mut() else {
+        return;
+    };
+
+    let mut lookups = 0;
+    for requirement in &x402.accepts {
+        if requirement.max_amount_required.is_empty()
+            || x402.prices.iter().any(|price| {
+                price.network == requirement.network
+                    && price.asset.eq_ignore_ascii_case(&requirement.asset)
+                    && price.max_amount_required == requirement.max_amount_required
+            })
+        {
+            continue;
+        }
+        let mut price = X402Price {
+            network: requirement.network.clone(),
+            asset: requirement.asset.clone(),
+            max_amount_required: requirement.max_amount_required.clone(),
+            ..Default::default()
+        };
+
+        let Ok(base_units) = requirement.max_amount_required.trim().parse::<u128>() else {
+            checks.issues.push(price_issue(
+                "X402_PRICE_INVALID",
+                format!(
+                    "x402 price '{}' on {} isn't a whole number of base units",
+                    requirement.max_amount_required, requirement.network
+                ),
+            ));
+            x402.prices.push(price);
+            continue;
+        };
+        if base_units == 0 {
+            checks.issues.push(price_issue(
+                "X402_PRICE_ZERO",
+                format!(
+                    "x402 price on {} is zero, but the endpoint asks for payment",
+                    requirement.network
+                ),
+            ));
+        }
+
+        let Some(chain_id) = network_chain_id(&requirement.network) else {
+            x402.prices.push(price);
+            continue;
+        };
+        let known = known_asset(chain_id, &requirement.asset);
+        price.symbol = known.map(|known| known.symbol.to_string());
+        let quote = if known.is_some() || lookups < MAX_PRICE_LOOKUPS {
+            lookups += usize::from(known.is_none());
+            pricing.feed.usd_price(client, chain_id, &requirement.asset).await
+        } else {
+            None
+        };
+        let decimals = known
+            .map(|known| known.decimals)
+            .or(quote.map(|quote| quote.decimals));
+        if let Some(decimals) = decimals {
+            price.amount = Some(base_units as f64 / 10f64.powi(decimals as i32));
+        }
+        let usd = price
+            .amount
+            .zip(quote)
+            .map(|(amount, quote)| (amount * quote.usd, quote.source))
+            .filter(|(usd, _)| usd.is_finite());
+        if let Some((usd, source)) = usd {
+            price.price_usd = Some(usd);
+            price.price_source = Some(source.to_string());
+            if usd > pricing.max_price_usd {
+                checks.issues.push(price_issue(
+                    "X402_PRICE_EXCESSIVE",
+                    format!(
+                        "x402 price on {} is ${:.2} per call (above ${:.2})",
+                        requirement.network, usd, pricing.max_price_usd
+                    ),
+                ));
+            }
+        }
+        x402.prices.push(price);
+    }
+}
+
+fn price_issue(code: &str, message: String) -> Issue {
+    Issue::new(Severity::Warning, code, message)
+}
+
+/// Look the asset's code up, falling back across the chain's RPCs
+async fn asset_deployed(chain_id: u64, address: &str) -> Result<bool, WatchyError> {
+    let mut last_error =
+        WatchyError::BlockchainError(format!("No RPC URLs available for chain {}", chain_id));
+    for rpc_url in get_all_rpcs(chain_id) {
+        let result = match AccountClient::new(&rpc_url) {
+            Ok(client) => client.has_code(address).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(exists) => return Ok(exists),
+            Err(e) => last_error = e,
+        }
+    }
+    Err(last_error)
+}
+
+fn is_evm_address(address: &str) -> bool {
+    address
+        .strip_prefix("0x")
+        .is_some_and(|hex| hex.len() == 40 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+fn join(addresses: &[&String]) -> String {
+    addresses
+        .iter()
+        .map(|address| address.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::pricing::PriceFeed;
+    use crate::types::X402Check;
+
+    const WALLET: &str = "0xabcdefabcdefabcdefabcdefabcdefabcdefabcd";
+    const OWNER: &str = "0x2222222222222222222222222222222222222222";
+    const OTHER: &str = "0x3333333333333333333333333333333333333333";
+
+    fn content_checks(body: &str) -> ContentChecks {
+        ContentChecks {
+            passed: true,
+            x402_valid: Some(X402Check {
+                valid: true,
+                accepts: parse_accepts(body.as_bytes()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn agent(wallet: Option<&str>) -> AgentInfo {
+        AgentInfo {
+            agent_id: 1,
+            registry: String::new(),
+            metadata_uri: String::new(),
+            owner: Some(OWNER.to_string()),
+            owner_ens: None,
+            wallet: wallet.map(str::to_string),
+            wallet_ens: None,
+            metadata_snapshot: None,
+        }
+    }
+
+    fn body(pay_to: &str) -> String {
+        format!(
+            r#"{{"x402Version":1,"accepts":[{{"scheme":"exact","network":"base","maxAmountRequired":"10000","payTo":"{}","asset":"0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913"}}]}}"#,
+            pay_to
+        )
+    }
+
+    #[test]
+    fn test_pay_to_matches_agent_wallet() {
+        // Addresses compare case-insensitively (EIP-55 checksums)
+        let mut checks = content_checks(&body("0xABCDEFabcdefABCDEFabcdefABCDEFabcdefABCD"));
+        check_pay_to(&mut checks, &agent(Some(WALLET)));
+        assert_eq!(
+            checks.x402_valid.as_ref().unwrap().pay_to_matches_wallet,
+            Some(true)
+        );
+        assert!(checks.issues.is_empty());
+
+        // The owner is related too
+        let mut checks = content_checks(&body(OWNER));
+        check_pay_to(&mut checks, &agent(Some(WALLET)));
+        assert!(checks.issues.is_empty());
+    }
+
+    #[test]
+    fn test_pay_to_unrelated_wallet() {
+        let mut checks = content_checks(&body(OTHER));
+        check_pay_to(&mut checks, &agent(Some(WALLET)));
+        assert!(!checks.passed);
+        assert_eq!(checks.issues[0].code, "X402_PAYTO_MISMATCH");
+        assert_eq!(
+            checks.x402_valid.as_ref().unwrap().pay_to_matches_wallet,
+            Some(false)
+        );
+
+        // Without an on-chain wallet there's nothing to compare against
+        let mut checks = content_checks(&body(OTHER));
+        check_pay_to(&mut checks, &agent(None));
+        assert!(checks.passed);
+        assert_eq!(checks.issues[0].code, "X402_PAYTO_UNVERIFIED");
+        assert_eq!(checks.issues[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn test_networks_and_stablecoins() {
+        assert_eq!(network_chain_id("base"), Some(8453));
+        assert_eq!(network_chain_id("Base-Sepolia"), Some(84532));
+        assert_eq!(network_chain_id("eip155:1"), Some(1));
+        assert_eq!(network_chain_id("eip155:137"), None);
+        assert_eq!(network_chain_id("polygon"), None);
+
+        let usdc = known_asset(8453, "0x833589fcd6edb6e08f4c7c32d4f71b54bda02913").unwrap();
+        assert_eq!((usdc.symbol, usdc.decimals), ("USDC", 6));
+        // Base USDC isn't USDC on Ethereum
+        assert!(known_asset(1, usdc.address).is_none());
+        // Solana mints are case-sensitive
+        assert!(known_asset(101, "epjfwdd5aufqssqem2qn1xzybapc8g4wegGkZwyTDt1v").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_prices_normalized_to_usd() {
+        let pricing = PricingConfig {
+            feed: PriceFeed::Pegged,
+            max_price_usd: 1000.0,
+        };
+        let mut checks = content_checks(
+            r#"{"accepts":[
+                {"network":"base","asset":"0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913","maxAmountRequired":"10000"},
+                {"network":"eip155:1","asset":"0x6B175474E89094C44Da98b954EedeAC495271d0F","maxAmountRequired":"5000000000000000000000"},
+                {"network":"base","asset":"0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913","maxAmountRequired":"0"},
+                {"network":"base","asset":"0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913","maxAmountRequired":"0.01"}
+            ]}"#,
+        );
+        check_prices(&mut checks, &reqwest::Client::new(), &pricing).await;
+
+        let prices = &checks.x402_valid.as_ref().unwrap().prices;
+        assert_eq!(prices.len(), 4);
+        assert_eq!(prices[0].symbol.as_deref(), Some("USDC"));
+        assert_eq!(prices[0].price_usd, Some(0.01));
+        assert_eq!(prices[0].price_source.as_deref(), Some("pegged"));
+        assert_eq!(prices[1].price_usd, Some(5000.0));
+        assert_eq!(prices[3].price_usd, None);
+        let codes: Vec<&str> = checks.issues.iter().map(|i| i.code.as_str()).collect();
+        assert_eq!(
+            codes,
+            [
+                "X402_PRICE_EXCESSIVE",
+                "X402_PRICE_ZERO",
+                "X402_PRICE_INVALID"
+            ]
+        );
+        // Price warnings don't fail the phase
+        assert!(checks.passed);
+    }
+
+    #[tokio::test]
+    async fn test_asset_checks_without_rpc() {
+        let mut checks = content_checks(
+            r#"{"accepts":[
+                {"network":"polygon","asset":"0x3c499c542cEF5E3811e1192ce70d8cC03d5c3359","payTo":""},
+                {"network":"base","asset":"usdc","payTo":""},
+                {"network":"base","asset":"0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913","payTo":""}
+            ]}"#,
+        );
+        check_assets(&mut checks).await;
+
+        let x402 = checks.x402_valid.as_ref().unwrap();
+        assert_eq!(x402.assets.len(), 3);
+        assert_eq!(x402.assets[0].chain_id, None);
+        assert_eq!(x402.assets[1].exists, Some(false));
+        // Known stablecoins aren't looked up
+        assert_eq!(x402.assets[2].exists, Some(true));
+        let codes: Vec<&str> = checks.issues.iter().map(|i| i.code.as_str()).collect();
+        assert_eq!(codes, ["X402_UNSUPPORTED_NETWORK", "X402_ASSET_NOT_FOUND"]);
+        assert!(!checks.passed);
+    }
+}
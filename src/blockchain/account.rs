@@ -0,0 +1,232 @@
+use alloy::{
+    network::{Ethereum, EthereumWallet},
+    primitives::{utils::format_ether, Address, U256},
+    providers::{Provider, ProviderBuilder, RootProvider},
+    rpc::types::TransactionRequest,
+    signers::local::PrivateKeySigner,
+    transports::http::{Client, Http},
+};
+use std::str::FromStr;
+use tracing::{debug, info};
+use url::Url;
+
+use super::rpc_client;
+use crate::types::WatchyError;
+
+type HttpProvider = RootProvider<Http<Client>, Ethereum>;
+
+/// Multiple of the current gas price a transaction must be able to pay
+///
+/// The recommended fillers cap EIP-1559 fees at about twice the base fee, and
+/// nodes reject transactions whose sender can't cover gas at the cap.
+const GAS_PRICE_HEADROOM: u128 = 2;
+
+/// Basic on-chain activity of an address
+#[derive(Debug, Clone)]
+pub struct AccountActivity {
+    /// Transactions sent (the account nonce)
+    pub tx_count: u64,
+    pub balance: U256,
+    /// Has deployed code (a contract or smart account)
+    pub is_contract: bool,
+}
+
+/// Plain account state reads (nonce, balance, code)
+pub struct AccountClient {
+    rpc_url: Url,
+}
+
+impl AccountClient {
+    pub fn new(rpc_url: &str) -> Result<Self, WatchyError> {
+        let url = Url::parse(rpc_url)
+            .map_err(|e| WatchyError::InvalidRequest(format!("Invalid RPC URL: {}", e)))?;
+
+        Ok(Self { rpc_url: url })
+    }
+
+    fn provider(&self) -> HttpProvider {
+        ProviderBuilder::new().on_client(rpc_client(self.rpc_url.clone()))
+    }
+
+    pub async fn activity(&self, address: &str) -> Result<AccountActivity, WatchyError> {
+        let address = Address::from_str(address)
+            .map_err(|e| WatchyError::InvalidAddress(format!("Invalid address: {}", e)))?;
+        let provider = self.provider();
+
+        let tx_count = provider
+            .get_transaction_count(address)
+            .await
+            .map_err(|e| WatchyError::BlockchainError(format!("Failed to get nonce: {}", e)))?;
+        let balance = self.balance(address).await?;
+        let code = provider
+            .get_code_at(address)
+            .await
+            .map_err(|e| WatchyError::BlockchainError(format!("Failed to get code: {}", e)))?;
+
+        Ok(AccountActivity {
+            tx_count,
+            balance,
+            is_contract: !code.is_empty(),
+        })
+    }
+
+    /// Chain ID the RPC reports (`eth_chainId`)
+    pub async fn chain_id(&self) -> Result<u64, WatchyError> {
+        self.provider()
+            .get_chain_id()
+            .await
+            .map_err(|e| WatchyError::BlockchainError(format!("Failed to get chain ID: {}", e)))
+    }
+
+    /// Native balance of an address
+    async fn balance(&self, address: Address) -> Result<U256, WatchyError> {
+        self.provider()
+            .get_balance(address)
+            .await
+            .map_err(|e| WatchyError::BlockchainError(format!("Failed to get balance: {}", e)))
+    }
+
+    /// Native balance of an address in wei (saturating at u128::MAX)
+    pub async fn balance_wei(&self, address: &str) -> Result<u128, WatchyError> {
+        let address = Address::from_str(address)
+            .map_err(|e| WatchyError::InvalidAddress(format!("Invalid address: {}", e)))?;
+        Ok(self.balance(address).await?.saturating_to())
+    }
+
+    /// Fail with `InsufficientFunds` unless `from` can pay for `gas` at the current price
+    ///
+    /// `action` names the transaction in the error (e.g. "feedback").
+    async fn ensure_gas_funds(
+        &self,
+        from: Address,
+        gas: u128,
+        action: &str,
+    ) -> Result<(), WatchyError> {
+        let gas_price = self
+            .provider()
+            .get_gas_price()
+            .await
+            .map_err(|e| WatchyError::BlockchainError(format!("Failed to get gas price: {}", e)))?;
+        let balance = self.balance(from).await?;
+        check_gas_funds(from, balance, gas, gas_price, action)
+    }
+
+    /// `ensure_gas_funds` for a gas estimate; a failed estimate (usually a
+    /// revert) is left for the send to report
+    pub async fn ensure_estimated_gas_funds<E: std::fmt::Display>(
+        &self,
+        estimate: Result<u128, E>,
+        from: Address,
+        action: &str,
+    ) -> Result<(), WatchyError> {
+        match estimate {
+            Ok(gas) => self.ensure_gas_funds(from, gas, action).await,
+            Err(e) => {
+                debug!("{} gas estimation failed: {}", action, e);
+                Ok(())
+            }
+        }
+    }
+
+    /// Whether a contract is deployed at the address
+    pub async fn has_code(&self, address: &str) -> Result<bool, WatchyError> {
+        let address = Address::from_str(address)
+            .map_err(|e| WatchyError::InvalidAddress(format!("Invalid address: {}", e)))?;
+        let code = self
+            .provider()
+            .get_code_at(address)
+            .await
+            .map_err(|e| WatchyError::BlockchainError(format!("Failed to get code: {}", e)))?;
+        Ok(!code.is_empty())
+    }
+
+    /// Send `value_wei` from the key's address to `to` and wait for it to be mined
+    ///
+    /// # Returns
+    /// The transaction hash
+    pub async fn transfer(
+        &self,
+        private_key: &str,
+        to: &str,
+        value_wei: u128,
+    ) -> Result<String, WatchyError> {
+        let to = Address::from_str(to)
+            .map_err(|e| WatchyError::InvalidAddress(format!("Invalid address: {}", e)))?;
+        let key = private_key.strip_prefix("0x").unwrap_or(private_key);
+        let signer: PrivateKeySigner = key
+            .parse()
+            .map_err(|e| WatchyError::Internal(format!("Invalid private key: {}", e)))?;
+        let from = signer.address();
+
+        let provider = ProviderBuilder::new()
+            .with_recommended_fillers()
+            .wallet(EthereumWallet::from(signer))
+            .on_client(rpc_client(self.rpc_url.clone()));
+        let request = TransactionRequest::default().from(from).to(to).value(U256::from(value_wei));
+
+        let pending = provider.send_transaction(request).await.map_err(|e| {
+            let err_str = e.to_string();
+            if err_str.contains("insufficient funds") {
+                WatchyError::InsufficientFunds("Insufficient funds for transaction".to_string())
+            } else {
+                WatchyError::BlockchainError(format!("Failed to send transfer: {}", err_str))
+            }
+        })?;
+
+        let tx_hash = format!("0x{}", hex::encode(pending.tx_hash().as_slice()));
+        info!("Transfer transaction sent: {}", tx_hash);
+
+        // The transfer is out: failures from here on name it so it can be traced
+        let receipt = pending.get_receipt().await.map_err(|e| {
+            WatchyError::BlockchainError(format!("Failed to get receipt of {}: {}", tx_hash, e))
+        })?;
+        if !receipt.status() {
+            return Err(WatchyError::BlockchainError(format!(
+                "Transfer transaction {} reverted",
+                tx_hash
+            )));
+        }
+        Ok(tx_hash)
+    }
+}
+
+/// Compare a balance with the worst-case cost of `gas` at `gas_price`
+fn check_gas_funds(
+    from: Address,
+    balance: U256,
+    gas: u128,
+    gas_price: u128,
+    action: &str,
+) -> Result<(), WatchyError> {
+    let needed = U256::from(gas)
+        .saturating_mul(U256::from(gas_price))
+        .saturating_mul(U256::from(GAS_PRICE_HEADROOM));
+    if balance >= needed {
+        return Ok(());
+    }
+    Err(WatchyError::InsufficientFunds(format!(
+        "Signer {} has {} ETH; {} needs about {} ETH for gas",
+        from,
+        format_ether(balance),
+        action,
+        format_ether(needed)
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_gas_funds() {
+        let from = Address::ZERO;
+        let balance = U256::from(1_000_000_000_000_000u128); // 0.001 ETH
+
+        // 100k gas at 1 gwei costs 0.0001 ETH, 0.0002 ETH with headroom
+        assert!(check_gas_funds(from, balance, 100_000, 1_000_000_000, "feedback").is_ok());
+
+        let err = check_gas_funds(from, balance, 100_000, 10_000_000_000, "feedback").unwrap_err();
+        assert_eq!(err.code(), "insufficient_funds");
+        assert!(err.to_string().contains("feedback needs about 0.002"));
+    }
+}
@@ -0,0 +1,215 @@
+//! Ethereum Attestation Service client
+//!
+//! With `EAS_ATTESTATIONS` enabled, every published audit is also attested on
+//! the audited chain, next to the reputation feedback. The attestation carries
+//! the agent ID, score, report hash (the feedbackHash) and report URI under
+//! the schema below, giving contracts a queryable on-chain object per audit.
+//!
+//! The schema must be registered once per chain in the EAS SchemaRegistry
+//! (no resolver, revocable) before attestations can be made; its UID is the
+//! same on every chain.
+
+use alloy::{
+    network::EthereumWallet,
+    primitives::{keccak256, Address, Bytes, B256, U256},
+    providers::ProviderBuilder,
+    signers::local::PrivateKeySigner,
+    sol_types::{SolEvent, SolValue},
+};
+use std::env;
+use std::str::FromStr;
+use tracing::{info, warn};
+use url::Url;
+
+use super::rpc_client;
+use crate::abi::IEAS::{AttestationRequest, AttestationRequestData, Attested, IEASInstance};
+use crate::types::WatchyError;
+
+/// EAS schema of audit attestations
+pub const AUDIT_SCHEMA: &str = "uint256 agentId,uint8 score,bytes32 reportHash,string reportURI";
+
+/// Attestation settings
+#[derive(Debug, Clone)]
+pub struct EasConfig {
+    /// Schema UID attestations are made under
+    pub schema_uid: B256,
+}
+
+impl EasConfig {
+    /// Load from `EAS_*`; `None` unless `EAS_ATTESTATIONS` is enabled
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        let enabled = env::var("EAS_ATTESTATIONS")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(false);
+        if !enabled {
+            return Ok(None);
+        }
+        let schema_uid = match env::var("EAS_SCHEMA_UID") {
+            Ok(value) => B256::from_str(&value)
+                .map_err(|e| anyhow::anyhow!("Invalid EAS_SCHEMA_UID '{}': {}", value, e))?,
+            Err(_) => schema_uid(AUDIT_SCHEMA, Address::ZERO, true),
+        };
+        Ok(Some(Self { schema_uid }))
+    }
+}
+
+/// UID the SchemaRegistry assigns a schema
+/// (`keccak256(abi.encodePacked(schema, resolver, revocable))`)
+pub fn schema_uid(schema: &str, resolver: Address, revocable: bool) -> B256 {
+    let mut packed = schema.as_bytes().to_vec();
+    packed.extend_from_slice(resolver.as_slice());
+    packed.push(revocable as u8);
+    keccak256(packed)
+}
+
+/// ABI-encoded attestation data for [`AUDIT_SCHEMA`]
+pub fn encode_audit_data(
+    agent_id: u64,
+    score: u8,
+    report_hash: [u8; 32],
+    report_uri: &str,
+) -> Bytes {
+    // A uint8 is ABI-encoded as a full word, the same as a uint256
+    (
+        U256::from(agent_id),
+        U256::from(score),
+        B256::from(report_hash),
+        report_uri.to_string(),
+    )
+        .abi_encode_params()
+        .into()
+}
+
+/// EAS contract client for creating audit attestations
+pub struct EasClient {
+    rpc_url: Url,
+    eas_address: Address,
+    signer: PrivateKeySigner,
+}
+
+impl EasClient {
+    pub fn new(rpc_url: &str, eas_address: &str, private_key: &str) -> Result<Self, WatchyError> {
+        let rpc_url = Url::parse(rpc_url)
+            .map_err(|e| WatchyError::InvalidRequest(format!("Invalid RPC URL: {}", e)))?;
+        let eas_address = Address::from_str(eas_address)
+            .map_err(|e| WatchyError::InvalidAddress(format!("Invalid EAS address: {}", e)))?;
+        let key = private_key.strip_prefix("0x").unwrap_or(private_key);
+        let signer: PrivateKeySigner = key
+            .parse()
+            .map_err(|e| WatchyError::Internal(format!("Invalid private key: {}", e)))?;
+
+        Ok(Self {
+            rpc_url,
+            eas_address,
+            signer,
+        })
+    }
+
+    /// Create a revocable attestation and wait for it to be mined
+    ///
+    /// `expiration_time` is in Unix seconds (0 = never expires).
+    ///
+    /// # Returns
+    /// (attestation UID, transaction hash)
+    pub async fn attest(
+        &self,
+        schema_uid: B256,
+        recipient: Address,
+        expiration_time: u64,
+        data: Bytes,
+    ) -> Result<(String, String), WatchyError> {
+        let wallet = EthereumWallet::from(self.signer.clone());
+        let provider = ProviderBuilder::new()
+            .with_recommended_fillers()
+            .wallet(wallet)
+            .on_client(rpc_client(self.rpc_url.clone()));
+        let contract = IEASInstance::new(self.eas_address, &provider);
+
+        let request = AttestationRequest {
+            schema: schema_uid,
+            data: AttestationRequestData {
+                recipient,
+                expirationTime: expiration_time,
+                revocable: true,
+                refUID: B256::ZERO,
+                data,
+                value: U256::ZERO,
+            },
+        };
+
+        let call = contract.attest(request);
+        let pending = call.send().await.map_err(|e| {
+            let err_str = e.to_string();
+            if err_str.contains("InvalidSchema") {
+                WatchyError::BlockchainError(format!(
+                    "EAS schema {} is not registered on this chain",
+                    schema_uid
+                ))
+            } else if err_str.contains("insufficient funds") {
+                WatchyError::InsufficientFunds("Insufficient funds for transaction".to_string())
+            } else {
+                WatchyError::BlockchainError(format!("Failed to create attestation: {}", err_str))
+            }
+        })?;
+
+        let tx_hash = format!("0x{}", hex::encode(pending.tx_hash().as_slice()));
+        info!("Attestation transaction sent: {}", tx_hash);
+
+        let receipt = pending
+            .get_receipt()
+            .await
+            .map_err(|e| WatchyError::BlockchainError(format!("Failed to get receipt: {}", e)))?;
+        if !receipt.status() {
+            warn!("Attestation transaction reverted: {}", tx_hash);
+            return Err(WatchyError::BlockchainError(
+                "Attestation transaction reverted".to_string(),
+            ));
+        }
+
+        // The UID is only in the Attested event
+        let uid = receipt
+            .inner
+            .logs()
+            .iter()
+            .filter(|log| log.address() == self.eas_address)
+            .filter(|log| log.topics().first() == Some(&Attested::SIGNATURE_HASH))
+            .find_map(|log| Attested::decode_log_data(log.data(), true).ok())
+            .map(|event| format!("0x{}", hex::encode(event.uid)))
+            .ok_or_else(|| {
+                WatchyError::BlockchainError(
+                    "Could not parse attestation UID from event".to_string(),
+                )
+            })?;
+
+        info!(
+            "Attestation {} confirmed in block {}",
+            uid,
+            receipt.block_number.unwrap_or_default()
+        );
+        Ok((uid, tx_hash))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audit_schema_encoding() {
+        // The revocable flag is part of the UID
+        assert_ne!(
+            schema_uid(AUDIT_SCHEMA, Address::ZERO, true),
+            schema_uid(AUDIT_SCHEMA, Address::ZERO, false)
+        );
+
+        let data = encode_audit_data(1434, 85, [0xab; 32], "ar://tx");
+        // Three static words, the string offset, its length and one padded word
+        assert_eq!(data.len(), 6 * 32);
+        assert_eq!(data[31], 0x9a); // 1434 = 0x059a
+        assert_eq!(data[63], 85);
+        assert_eq!(&data[64..96], &[0xab; 32]);
+        assert_eq!(data[127], 0x80);
+        assert_eq!(data[159], 7);
+        assert_eq!(&data[160..167], b"ar://tx");
+    }
+}
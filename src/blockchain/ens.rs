@@ -0,0 +1,127 @@
+use alloy::{
+    network::Ethereum,
+    primitives::{keccak256, Address, B256},
+    providers::{ProviderBuilder, RootProvider},
+    transports::http::{Client, Http},
+};
+use std::str::FromStr;
+use tracing::debug;
+use url::Url;
+
+use super::rpc_client;
+use crate::abi::IENSRegistry::IENSRegistryInstance;
+use crate::abi::IENSResolver::IENSResolverInstance;
+use crate::types::WatchyError;
+
+type HttpProvider = RootProvider<Http<Client>, Ethereum>;
+
+/// ENS registry, at the same address on Ethereum mainnet and Sepolia
+pub const ENS_REGISTRY_ADDRESS: &str = "0x00000000000C2E074eC69A0bFb2997BA6C7d2e1e";
+
+/// ENS reverse resolution client
+pub struct EnsClient {
+    rpc_url: Url,
+    registry_address: Address,
+}
+
+impl EnsClient {
+    pub fn new(rpc_url: &str) -> Result<Self, WatchyError> {
+        let url = Url::parse(rpc_url)
+            .map_err(|e| WatchyError::InvalidRequest(format!("Invalid RPC URL: {}", e)))?;
+
+        Ok(Self {
+            rpc_url: url,
+            registry_address: Address::from_str(ENS_REGISTRY_ADDRESS)
+                .map_err(|e| WatchyError::InvalidAddress(format!("Invalid ENS registry: {}", e)))?,
+        })
+    }
+
+    fn provider(&self) -> HttpProvider {
+        ProviderBuilder::new().on_client(rpc_client(self.rpc_url.clone()))
+    }
+
+    /// Primary ENS name of an address
+    ///
+    /// The reverse record is only trusted if the name resolves back to the
+    /// same address, since anyone can claim any name in their reverse record.
+    pub async fn lookup_address(&self, address: &str) -> Result<Option<String>, WatchyError> {
+        let address = Address::from_str(address)
+            .map_err(|e| WatchyError::InvalidAddress(format!("Invalid address: {}", e)))?;
+
+        let reverse_node = namehash(&format!("{:x}.addr.reverse", address));
+        let Some(resolver) = self.resolver(reverse_node).await? else {
+            return Ok(None);
+        };
+        let name = IENSResolverInstance::new(resolver, self.provider())
+            .name(reverse_node)
+            .call()
+            .await
+            .map_err(|e| WatchyError::BlockchainError(format!("ENS name() failed: {}", e)))?
+            ._0;
+        if name.is_empty() {
+            return Ok(None);
+        }
+
+        let node = namehash(&name);
+        let Some(resolver) = self.resolver(node).await? else {
+            return Ok(None);
+        };
+        let resolved = IENSResolverInstance::new(resolver, self.provider())
+            .addr(node)
+            .call()
+            .await
+            .map_err(|e| WatchyError::BlockchainError(format!("ENS addr() failed: {}", e)))?
+            ._0;
+
+        if resolved == address {
+            Ok(Some(name))
+        } else {
+            debug!("ENS name {} for {} resolves to {}, ignoring", name, address, resolved);
+            Ok(None)
+        }
+    }
+
+    async fn resolver(&self, node: B256) -> Result<Option<Address>, WatchyError> {
+        let resolver = IENSRegistryInstance::new(self.registry_address, self.provider())
+            .resolver(node)
+            .call()
+            .await
+            .map_err(|e| WatchyError::BlockchainError(format!("ENS resolver() failed: {}", e)))?
+            ._0;
+
+        Ok((!resolver.is_zero()).then_some(resolver))
+    }
+}
+
+/// ENS namehash (EIP-137) of a lowercase name
+pub fn namehash(name: &str) -> B256 {
+    let mut node = B256::ZERO;
+    if name.is_empty() {
+        return node;
+    }
+    for label in name.to_lowercase().rsplit('.') {
+        let mut buf = [0u8; 64];
+        buf[..32].copy_from_slice(node.as_slice());
+        buf[32..].copy_from_slice(keccak256(label.as_bytes()).as_slice());
+        node = keccak256(buf);
+    }
+    node
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_namehash() {
+        assert_eq!(namehash(""), B256::ZERO);
+        assert_eq!(
+            format!("{:x}", namehash("eth")),
+            "93cdeb708b7545dc668eb9280176169d1c33cfd8ed6f04690a0bcc88a93fc4ae"
+        );
+        assert_eq!(
+            format!("{:x}", namehash("foo.eth")),
+            "de9b09fd7c5f901e23a3f19fecc54828e9c848539801e86591bd9801b019f84f"
+        );
+    }
+}
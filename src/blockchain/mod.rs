@@ -1,2 +1,31 @@
+pub mod account;
+pub mod eas;
+pub mod ens;
 pub mod registry;
 pub mod reputation;
+
+use alloy::rpc::client::RpcClient;
+use alloy::transports::http::{reqwest, Client, Http};
+use std::sync::OnceLock;
+use url::Url;
+
+/// HTTP client shared by every RPC provider (and its connection pool)
+static RPC_HTTP_CLIENT: OnceLock<Client> = OnceLock::new();
+
+/// Send chain RPC traffic through an HTTP(S) proxy
+///
+/// Call once at startup, before any provider is built.
+pub fn set_rpc_proxy(proxy: &Url) -> anyhow::Result<()> {
+    let client = Client::builder()
+        .proxy(reqwest::Proxy::all(proxy.as_str())?)
+        .build()?;
+    RPC_HTTP_CLIENT
+        .set(client)
+        .map_err(|_| anyhow::anyhow!("RPC proxy already configured"))
+}
+
+/// JSON-RPC client for an endpoint, through the RPC proxy if one is set
+pub(crate) fn rpc_client(url: Url) -> RpcClient<Http<Client>> {
+    let client = RPC_HTTP_CLIENT.get_or_init(Client::default).clone();
+    RpcClient::new(Http::with_client(client, url), false)
+}
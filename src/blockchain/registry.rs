@@ -1,19 +1,30 @@
 use alloy::{
     network::{Ethereum, EthereumWallet},
-    primitives::{Address, U256},
+    primitives::{Address, B256, U256},
     providers::{Provider, ProviderBuilder, RootProvider},
+    rpc::types::{BlockNumberOrTag, Filter, Log},
     signers::local::PrivateKeySigner,
+    sol_types::SolEvent,
     transports::http::{Client, Http},
 };
 use std::str::FromStr;
 use tracing::{debug, error, info};
 use url::Url;
 
-use crate::abi::IIdentityRegistry::IIdentityRegistryInstance;
+use super::account::AccountClient;
+use super::rpc_client;
+use crate::abi::IIdentityRegistry::{IIdentityRegistryInstance, Registered, Transfer, URIUpdated};
 use crate::types::WatchyError;
 
 type HttpProvider = RootProvider<Http<Client>, Ethereum>;
 
+/// Registry events of a block range, each with its log
+pub struct RegistryEvents {
+    pub registered: Vec<(Log, Registered)>,
+    pub updates: Vec<(Log, URIUpdated)>,
+    pub transfers: Vec<(Log, Transfer)>,
+}
+
 /// EIP-8004 Registry contract client
 pub struct RegistryClient {
     rpc_url: Url,
@@ -36,7 +47,7 @@ impl RegistryClient {
 
     /// Create a provider instance
     fn provider(&self) -> HttpProvider {
-        ProviderBuilder::new().on_http(self.rpc_url.clone())
+        ProviderBuilder::new().on_client(rpc_client(self.rpc_url.clone()))
     }
 
     /// Check if an agent exists by calling ownerOf
@@ -85,6 +96,22 @@ impl RegistryClient {
         Ok(owner._0)
     }
 
+    /// Number of agents an address owns
+    pub async fn balance_of(&self, owner: Address) -> Result<u64, WatchyError> {
+        debug!("Fetching balance of {}", owner);
+
+        let provider = self.provider();
+        let contract = IIdentityRegistryInstance::new(self.registry_address, provider);
+
+        let balance = contract
+            .balanceOf(owner)
+            .call()
+            .await
+            .map_err(|e| WatchyError::BlockchainError(format!("balanceOf failed: {}", e)))?;
+
+        Ok(balance._0.try_into().unwrap_or(u64::MAX))
+    }
+
     /// Get the metadata URI for an agent
     pub async fn token_uri(&self, agent_id: u64) -> Result<String, WatchyError> {
         debug!("Fetching tokenURI for agent {}", agent_id);
@@ -158,6 +185,142 @@ impl RegistryClient {
         Ok(block_num)
     }
 
+    /// `Registered` and `URIUpdated` events for an agent since `from_block`
+    ///
+    /// Returns the registration (if in range) and the URI updates in log order.
+    pub async fn agent_events(
+        &self,
+        agent_id: u64,
+        from_block: u64,
+    ) -> Result<(Option<(Log, Registered)>, Vec<(Log, URIUpdated)>), WatchyError> {
+        let provider = self.provider();
+        let agent_topic = B256::from(U256::from(agent_id));
+        let filter = |signature: B256| {
+            Filter::new()
+                .address(self.registry_address)
+                .event_signature(signature)
+                .topic1(agent_topic)
+                .from_block(from_block)
+                .to_block(BlockNumberOrTag::Latest)
+        };
+
+        let registered = provider
+            .get_logs(&filter(Registered::SIGNATURE_HASH))
+            .await
+            .map_err(|e| WatchyError::BlockchainError(format!("Registered log query failed: {}", e)))?
+            .into_iter()
+            .find_map(|log| {
+                let event = Registered::decode_log_data(log.data(), true).ok()?;
+                Some((log, event))
+            });
+
+        let updates = provider
+            .get_logs(&filter(URIUpdated::SIGNATURE_HASH))
+            .await
+            .map_err(|e| WatchyError::BlockchainError(format!("URIUpdated log query failed: {}", e)))?
+            .into_iter()
+            .filter_map(|log| {
+                let event = URIUpdated::decode_log_data(log.data(), true).ok()?;
+                Some((log, event))
+            })
+            .collect();
+
+        Ok((registered, updates))
+    }
+
+    /// IDs of the agents registered to `owner` since `from_block`, in log order
+    pub async fn registered_to(
+        &self,
+        owner: Address,
+        from_block: u64,
+    ) -> Result<Vec<u64>, WatchyError> {
+        let filter = Filter::new()
+            .address(self.registry_address)
+            .event_signature(Registered::SIGNATURE_HASH)
+            .topic2(owner.into_word())
+            .from_block(from_block)
+            .to_block(BlockNumberOrTag::Latest);
+        let logs = self
+            .provider()
+            .get_logs(&filter)
+            .await
+            .map_err(|e| WatchyError::BlockchainError(format!("Registered log query failed: {}", e)))?;
+        Ok(logs
+            .iter()
+            .filter_map(|log| Registered::decode_log_data(log.data(), true).ok())
+            .map(|event| event.agentId.try_into().unwrap_or(u64::MAX))
+            .collect())
+    }
+
+    /// `Registered`, `URIUpdated` and `Transfer` events for all agents in a block range
+    ///
+    /// Mints are left out of the transfers: `Registered` records their owner.
+    pub async fn registry_events(
+        &self,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<RegistryEvents, WatchyError> {
+        let provider = self.provider();
+        let filter = |signature: B256| {
+            Filter::new()
+                .address(self.registry_address)
+                .event_signature(signature)
+                .from_block(from_block)
+                .to_block(to_block)
+        };
+
+        let registered = provider
+            .get_logs(&filter(Registered::SIGNATURE_HASH))
+            .await
+            .map_err(|e| WatchyError::BlockchainError(format!("Registered log query failed: {}", e)))?
+            .into_iter()
+            .filter_map(|log| {
+                let event = Registered::decode_log_data(log.data(), true).ok()?;
+                Some((log, event))
+            })
+            .collect();
+
+        let updates = provider
+            .get_logs(&filter(URIUpdated::SIGNATURE_HASH))
+            .await
+            .map_err(|e| WatchyError::BlockchainError(format!("URIUpdated log query failed: {}", e)))?
+            .into_iter()
+            .filter_map(|log| {
+                let event = URIUpdated::decode_log_data(log.data(), true).ok()?;
+                Some((log, event))
+            })
+            .collect();
+
+        let transfers = provider
+            .get_logs(&filter(Transfer::SIGNATURE_HASH))
+            .await
+            .map_err(|e| WatchyError::BlockchainError(format!("Transfer log query failed: {}", e)))?
+            .into_iter()
+            .filter_map(|log| {
+                let event = Transfer::decode_log_data(log.data(), true).ok()?;
+                Some((log, event))
+            })
+            .filter(|(_, event)| !event.from.is_zero())
+            .collect();
+
+        Ok(RegistryEvents {
+            registered,
+            updates,
+            transfers,
+        })
+    }
+
+    /// Timestamp (unix seconds) of a block
+    pub async fn block_timestamp(&self, block_number: u64) -> Result<Option<u64>, WatchyError> {
+        let block = self
+            .provider()
+            .get_block_by_number(BlockNumberOrTag::Number(block_number), false)
+            .await
+            .map_err(|e| WatchyError::BlockchainError(format!("get_block_by_number failed: {}", e)))?;
+
+        Ok(block.map(|b| b.header.timestamp))
+    }
+
     /// Check if an address is authorized or owner of an agent
     #[allow(dead_code)]
     pub async fn is_authorized_or_owner(
@@ -187,6 +350,18 @@ impl RegistryClient {
         &self.registry_address
     }
 
+    /// Fail early when the signer can't pay for an estimated transaction
+    async fn ensure_gas_funds<E: std::fmt::Display>(
+        &self,
+        estimate: Result<u128, E>,
+        from: Address,
+        action: &str,
+    ) -> Result<(), WatchyError> {
+        AccountClient::new(self.rpc_url.as_str())?
+            .ensure_estimated_gas_funds(estimate, from, action)
+            .await
+    }
+
     /// Register a new agent (mints NFT with empty URI)
     ///
     /// # Arguments
@@ -204,19 +379,22 @@ impl RegistryClient {
             .map_err(|e| WatchyError::Internal(format!("Invalid private key: {}", e)))?;
 
         info!("Registering new agent (empty URI)");
+        let signer_address = signer.address();
 
         // Create wallet and provider
         let wallet = EthereumWallet::from(signer);
         let provider = ProviderBuilder::new()
             .with_recommended_fillers()
             .wallet(wallet)
-            .on_http(self.rpc_url.clone());
+            .on_client(rpc_client(self.rpc_url.clone()));
 
         // Create contract instance
         let contract = IIdentityRegistryInstance::new(self.registry_address, &provider);
 
         // Call register() - no URI version
         let tx = contract.register_0();
+        self.ensure_gas_funds(tx.estimate_gas().await, signer_address, "registration")
+            .await?;
 
         // Send the transaction
         let pending = tx.send().await.map_err(|e| {
@@ -292,18 +470,22 @@ impl RegistryClient {
             uri.len()
         );
 
+        let signer_address = signer.address();
+
         // Create wallet and provider
         let wallet = EthereumWallet::from(signer);
         let provider = ProviderBuilder::new()
             .with_recommended_fillers()
             .wallet(wallet)
-            .on_http(self.rpc_url.clone());
+            .on_client(rpc_client(self.rpc_url.clone()));
 
         // Create contract instance
         let contract = IIdentityRegistryInstance::new(self.registry_address, &provider);
 
         // Call setAgentURI
         let tx = contract.setAgentURI(U256::from(agent_id), uri.to_string());
+        self.ensure_gas_funds(tx.estimate_gas().await, signer_address, "setAgentURI")
+            .await?;
 
         // Send the transaction
         let pending = tx.send().await.map_err(|e| {
@@ -1,14 +1,19 @@
 use alloy::{
     network::EthereumWallet,
-    primitives::{keccak256, Address, FixedBytes, U256},
-    providers::ProviderBuilder,
+    primitives::{keccak256, Address, FixedBytes, B256, U256},
+    providers::{Provider, ProviderBuilder},
+    rpc::types::{BlockNumberOrTag, Filter},
     signers::local::PrivateKeySigner,
+    sol_types::SolEvent,
 };
+use std::collections::HashSet;
 use std::str::FromStr;
 use tracing::{debug, info, warn};
 use url::Url;
 
-use crate::abi::IReputationRegistry::IReputationRegistryInstance;
+use super::account::AccountClient;
+use super::rpc_client;
+use crate::abi::IReputationRegistry::{FeedbackRevoked, IReputationRegistryInstance, NewFeedback};
 use crate::types::WatchyError;
 
 /// Reputation Registry client for submitting audit feedback on-chain
@@ -57,14 +62,15 @@ impl ReputationClient {
     /// # Arguments
     /// * `agent_id` - The agent's token ID
     /// * `score` - Score from 0-100
-    /// * `tag1` - Primary tag (e.g., "auditScore")
-    /// * `tag2` - Secondary tag (e.g., "infrastructure")
+    /// * `tag1` - Primary tag (e.g., "starred")
+    /// * `tag2` - Secondary tag (e.g., "excellent")
     /// * `endpoint` - Primary endpoint tested (optional)
     /// * `feedback_uri` - Arweave URL of the full feedback JSON
     /// * `feedback_json` - The feedback JSON for computing hash
     ///
     /// # Returns
     /// Transaction hash on success
+    #[allow(clippy::too_many_arguments)]
     pub async fn submit_feedback(
         &self,
         agent_id: u64,
@@ -84,10 +90,8 @@ impl ReputationClient {
             agent_id, score, feedback_uri
         );
 
-        // Compute feedbackHash as keccak256 of the JSON
-        let json_bytes = serde_json::to_vec(feedback_json)
-            .map_err(|e| WatchyError::Internal(format!("JSON serialization failed: {}", e)))?;
-        let feedback_hash: FixedBytes<32> = keccak256(&json_bytes);
+        // Same hash the report records and /verify recomputes
+        let feedback_hash = FixedBytes::<32>::from(compute_feedback_hash(feedback_json)?);
 
         debug!("Feedback hash: 0x{}", hex::encode(feedback_hash));
 
@@ -96,7 +100,7 @@ impl ReputationClient {
         let provider = ProviderBuilder::new()
             .with_recommended_fillers()
             .wallet(wallet)
-            .on_http(self.rpc_url.clone());
+            .on_client(rpc_client(self.rpc_url.clone()));
 
         // Create contract instance
         let contract = IReputationRegistryInstance::new(self.reputation_address, &provider);
@@ -113,13 +117,18 @@ impl ReputationClient {
             feedback_hash,
         );
 
+        // Fail early when the signer can't pay for gas; reverts surface on send
+        AccountClient::new(self.rpc_url.as_str())?
+            .ensure_estimated_gas_funds(tx.estimate_gas().await, signer.address(), "feedback")
+            .await?;
+
         // Send the transaction
         let pending = tx.send().await.map_err(|e| {
             let err_str = e.to_string();
             if err_str.contains("CannotGiveFeedbackToOwnAgent") {
                 WatchyError::Internal("Cannot give feedback to own agent".to_string())
             } else if err_str.contains("insufficient funds") {
-                WatchyError::Internal("Insufficient funds for transaction".to_string())
+                WatchyError::InsufficientFunds("Insufficient funds for transaction".to_string())
             } else {
                 WatchyError::BlockchainError(format!("Failed to submit feedback: {}", err_str))
             }
@@ -178,7 +187,7 @@ impl ReputationClient {
             WatchyError::Internal("Private key required".to_string())
         })?;
 
-        let provider = ProviderBuilder::new().on_http(self.rpc_url.clone());
+        let provider = ProviderBuilder::new().on_client(rpc_client(self.rpc_url.clone()));
         let contract = IReputationRegistryInstance::new(self.reputation_address, &provider);
 
         let count = contract
@@ -190,6 +199,51 @@ impl ReputationClient {
         Ok(count._0)
     }
 
+    /// Feedback an agent has received since `from_block`, from any client
+    ///
+    /// Returns (entries not revoked, distinct clients).
+    ///
+    /// Feedback from `excluded` clients (Watchy's own signers) isn't counted.
+    pub async fn feedback_summary(
+        &self,
+        agent_id: u64,
+        from_block: u64,
+        excluded: &HashSet<Address>,
+    ) -> Result<(u64, u64), WatchyError> {
+        let provider = ProviderBuilder::new().on_client(rpc_client(self.rpc_url.clone()));
+        let filter = |signature: B256| {
+            Filter::new()
+                .address(self.reputation_address)
+                .event_signature(signature)
+                .topic1(B256::from(U256::from(agent_id)))
+                .from_block(from_block)
+                .to_block(BlockNumberOrTag::Latest)
+        };
+
+        let given = provider
+            .get_logs(&filter(NewFeedback::SIGNATURE_HASH))
+            .await
+            .map_err(|e| WatchyError::BlockchainError(format!("NewFeedback log query failed: {}", e)))?;
+        let revoked = provider
+            .get_logs(&filter(FeedbackRevoked::SIGNATURE_HASH))
+            .await
+            .map_err(|e| {
+                WatchyError::BlockchainError(format!("FeedbackRevoked log query failed: {}", e))
+            })?;
+
+        // clientAddress is the second indexed topic
+        let client = |log: &alloy::rpc::types::Log| {
+            log.topics()
+                .get(2)
+                .map(|topic| Address::from_word(*topic))
+                .filter(|address| !excluded.contains(address))
+        };
+        let clients: HashSet<Address> = given.iter().filter_map(client).collect();
+        let count = (given.iter().filter_map(client).count() as u64)
+            .saturating_sub(revoked.iter().filter_map(client).count() as u64);
+        Ok((count, clients.len() as u64))
+    }
+
     #[allow(dead_code)]
     pub fn has_signing_key(&self) -> bool {
         self.signer.is_some()
@@ -211,12 +265,9 @@ impl ReputationClient {
     }
 }
 
-/// Helper to compute feedbackHash from JSON
-#[allow(dead_code)]
+/// Helper to compute feedbackHash from JSON (keccak256 of its canonical bytes)
 pub fn compute_feedback_hash(json: &serde_json::Value) -> Result<[u8; 32], WatchyError> {
-    let bytes = serde_json::to_vec(json)
-        .map_err(|e| WatchyError::Internal(format!("JSON serialization failed: {}", e)))?;
-    Ok(keccak256(&bytes).into())
+    Ok(keccak256(crate::canonical::report_bytes(json)).into())
 }
 
 #[cfg(test)]
@@ -228,7 +279,7 @@ mod tests {
         let json = serde_json::json!({
             "agentId": 1434,
             "value": 85,
-            "tag1": "auditScore"
+            "tag1": "starred"
         });
 
         let hash = compute_feedback_hash(&json).unwrap();
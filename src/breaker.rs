@@ -0,0 +1,301 @@
+//! Circuit breaker for agents that stopped answering
+//!
+//! Every default-tenant audit updates the agent's skill index entry: an audit
+//! that finds the agent dead (metadata unreachable, or no probed endpoint
+//! reachable) adds a failure, any other audit clears them. After `threshold`
+//! consecutive failures the breaker opens: automatic audits skip the agent and
+//! `GET /agents` marks it `unresponsive`. Once the backoff has passed the
+//! breaker is half-open and lets one automatic audit through; another failure
+//! doubles the backoff, up to `max_backoff_secs`. Audits requested through the
+//! API always run and count as that trial.
+//!
+//! The counters automatic audits are gated on are kept in memory by
+//! [`Breaker`], seeded from the skill index at startup.
+
+use std::collections::HashMap;
+use std::env;
+use std::sync::Mutex;
+
+use crate::search::IndexedAgent;
+use crate::store::AuditStore;
+use crate::types::AuditReport;
+
+/// Breaker settings
+#[derive(Debug, Clone)]
+pub struct BreakerConfig {
+    /// Consecutive dead audits that open the breaker (0 disables it)
+    pub threshold: u32,
+    /// Wait before the first trial audit
+    pub backoff_secs: u64,
+    pub max_backoff_secs: u64,
+}
+
+impl Default for BreakerConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 3,
+            backoff_secs: 24 * 60 * 60,
+            max_backoff_secs: 30 * 24 * 60 * 60,
+        }
+    }
+}
+
+impl BreakerConfig {
+    /// Load from `BREAKER_FAILURE_THRESHOLD`, `BREAKER_BACKOFF_SECS` and
+    /// `BREAKER_MAX_BACKOFF_SECS`
+    pub fn from_env() -> anyhow::Result<Self> {
+        let defaults = Self::default();
+        let var = |name: &str, default: u64| -> anyhow::Result<u64> {
+            match env::var(name) {
+                Ok(value) => Ok(value.parse()?),
+                Err(_) => Ok(default),
+            }
+        };
+        Ok(Self {
+            threshold: var("BREAKER_FAILURE_THRESHOLD", defaults.threshold as u64)? as u32,
+            backoff_secs: var("BREAKER_BACKOFF_SECS", defaults.backoff_secs)?,
+            max_backoff_secs: var("BREAKER_MAX_BACKOFF_SECS", defaults.max_backoff_secs)?,
+        })
+    }
+
+    /// Breaker state of an agent as of its latest audit
+    pub fn state(&self, entry: &IndexedAgent, now: u64) -> BreakerState {
+        self.state_after(entry.consecutive_failures, entry.audited_at, now)
+    }
+
+    fn state_after(&self, consecutive_failures: u32, audited_at: u64, now: u64) -> BreakerState {
+        if self.threshold == 0 || consecutive_failures < self.threshold {
+            return BreakerState::Closed;
+        }
+        let doublings = (consecutive_failures - self.threshold).min(32);
+        let backoff = self
+            .backoff_secs
+            .saturating_mul(1u64 << doublings)
+            .min(self.max_backoff_secs);
+        let retry_at = audited_at.saturating_add(backoff);
+        if now >= retry_at {
+            BreakerState::HalfOpen
+        } else {
+            BreakerState::Open { retry_at }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerState {
+    /// Audits run as usual
+    Closed,
+    /// Automatic audits are skipped until `retry_at` (unix seconds)
+    Open { retry_at: u64 },
+    /// One trial audit may run
+    HalfOpen,
+}
+
+/// Whether an audit found the agent dead
+///
+/// Agents with no probed endpoints count as dead only when their metadata
+/// couldn't be fetched.
+pub fn is_dead(report: &AuditReport) -> bool {
+    let metadata_failed = report
+        .checks
+        .metadata
+        .issues
+        .iter()
+        .any(|issue| issue.code == "METADATA_FETCH_FAILED");
+    let mut probed = report
+        .checks
+        .endpoints
+        .iter()
+        .filter(|endpoint| endpoint.not_probed.is_none())
+        .peekable();
+    let endpoints_dead = probed.peek().is_some() && probed.all(|endpoint| !endpoint.reachable);
+    metadata_failed || endpoints_dead
+}
+
+/// An agent's audit history as far as the breaker is concerned
+#[derive(Debug, Clone, Copy)]
+struct Tracked {
+    consecutive_failures: u32,
+    audited_at: u64,
+    /// An automatic audit was started and hasn't finished
+    running: bool,
+}
+
+/// In-memory breaker state of every default-tenant agent audited
+pub struct Breaker {
+    config: BreakerConfig,
+    agents: Mutex<HashMap<(u64, u64), Tracked>>,
+}
+
+impl Breaker {
+    pub fn new(config: BreakerConfig) -> Self {
+        Self {
+            config,
+            agents: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Seed the counters from the skill index
+    pub async fn load(&self, store: &AuditStore) {
+        let entries = store.indexed_agents().await;
+        let mut agents = self.agents.lock().unwrap_or_else(|e| e.into_inner());
+        for entry in entries {
+            agents.entry((entry.chain_id, entry.agent_id)).or_insert(Tracked {
+                consecutive_failures: entry.consecutive_failures,
+                audited_at: entry.audited_at,
+                running: false,
+            });
+        }
+    }
+
+    /// Count an audit's outcome, returning the agent's consecutive dead audits
+    pub fn record(&self, chain_id: u64, agent_id: u64, report: &AuditReport, now: u64) -> u32 {
+        let mut agents = self.agents.lock().unwrap_or_else(|e| e.into_inner());
+        let tracked = agents.entry((chain_id, agent_id)).or_insert(Tracked {
+            consecutive_failures: 0,
+            audited_at: now,
+            running: false,
+        });
+        tracked.consecutive_failures = if is_dead(report) {
+            tracked.consecutive_failures + 1
+        } else {
+            0
+        };
+        tracked.audited_at = now;
+        tracked.running = false;
+        tracked.consecutive_failures
+    }
+
+    /// An audit of the agent ended without a report
+    pub fn finish(&self, chain_id: u64, agent_id: u64) {
+        let mut agents = self.agents.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(tracked) = agents.get_mut(&(chain_id, agent_id)) {
+            tracked.running = false;
+        }
+    }
+
+    /// Whether an automatic audit of the agent may start now, marking it started if so
+    ///
+    /// A half-open breaker only lets one audit through at a time.
+    pub fn allows_automatic_audit(&self, chain_id: u64, agent_id: u64, now: u64) -> bool {
+        let mut agents = self.agents.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(tracked) = agents.get_mut(&(chain_id, agent_id)) else {
+            return true;
+        };
+        let state = self
+            .config
+            .state_after(tracked.consecutive_failures, tracked.audited_at, now);
+        let allowed = match state {
+            BreakerState::Closed => true,
+            BreakerState::Open { .. } => false,
+            BreakerState::HalfOpen => !tracked.running,
+        };
+        if allowed {
+            tracked.running = true;
+        }
+        allowed
+    }
+
+    /// Agents last audited `interval_secs` or more ago whose breaker lets an
+    /// automatic audit through
+    pub fn due(&self, interval_secs: u64, now: u64) -> Vec<(u64, u64)> {
+        let agents = self.agents.lock().unwrap_or_else(|e| e.into_inner());
+        let mut due: Vec<_> = agents
+            .iter()
+            .filter(|(_, tracked)| {
+                !tracked.running
+                    && tracked.audited_at.saturating_add(interval_secs) <= now
+                    && !matches!(
+                        self.config
+                            .state_after(tracked.consecutive_failures, tracked.audited_at, now),
+                        BreakerState::Open { .. }
+                    )
+            })
+            .map(|(key, tracked)| (tracked.audited_at, *key))
+            .collect();
+        // Longest-waiting first
+        due.sort_unstable();
+        due.into_iter().map(|(_, key)| key).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(consecutive_failures: u32, audited_at: u64) -> IndexedAgent {
+        IndexedAgent {
+            agent_id: 1,
+            chain_id: 8453,
+            name: None,
+            score: 0,
+            audit_id: "aud_1".to_string(),
+            audited_at,
+            valid_until: None,
+            skills: vec![],
+            consecutive_failures,
+        }
+    }
+
+    #[test]
+    fn test_breaker_backoff() {
+        let config = BreakerConfig {
+            threshold: 3,
+            backoff_secs: 100,
+            max_backoff_secs: 250,
+        };
+        assert_eq!(config.state(&entry(2, 1000), 1000), BreakerState::Closed);
+        assert_eq!(
+            config.state(&entry(3, 1000), 1050),
+            BreakerState::Open { retry_at: 1100 }
+        );
+        assert_eq!(config.state(&entry(3, 1000), 1100), BreakerState::HalfOpen);
+        // A failed trial doubles the backoff, up to the cap
+        assert_eq!(
+            config.state(&entry(4, 1000), 1100),
+            BreakerState::Open { retry_at: 1200 }
+        );
+        assert_eq!(
+            config.state(&entry(9, 1000), 1100),
+            BreakerState::Open { retry_at: 1250 }
+        );
+
+        let disabled = BreakerConfig {
+            threshold: 0,
+            ..config
+        };
+        assert_eq!(disabled.state(&entry(9, 1000), 1000), BreakerState::Closed);
+    }
+
+    #[test]
+    fn test_breaker_gates_automatic_audits() {
+        let breaker = Breaker::new(BreakerConfig {
+            threshold: 2,
+            backoff_secs: 100,
+            max_backoff_secs: 1000,
+        });
+        let mut dead = AuditReport::new(1, 8453, "0x8004", String::new(), None);
+        dead.checks.metadata.issues.push(crate::types::Issue::new(
+            crate::types::Severity::Critical,
+            "METADATA_FETCH_FAILED",
+            "unreachable",
+        ));
+
+        assert!(breaker.allows_automatic_audit(8453, 1, 1000));
+        assert_eq!(breaker.record(8453, 1, &dead, 1000), 1);
+        assert_eq!(breaker.due(50, 1050), [(8453, 1)]);
+        assert_eq!(breaker.record(8453, 1, &dead, 1050), 2);
+        // Open: skipped until the backoff passes
+        assert!(!breaker.allows_automatic_audit(8453, 1, 1100));
+        assert!(breaker.due(50, 1100).is_empty());
+        // Half-open: a single trial
+        assert_eq!(breaker.due(50, 1150), [(8453, 1)]);
+        assert!(breaker.allows_automatic_audit(8453, 1, 1150));
+        assert!(!breaker.allows_automatic_audit(8453, 1, 1150));
+        assert!(breaker.due(50, 1150).is_empty());
+
+        let alive = AuditReport::new(1, 8453, "0x8004", String::new(), None);
+        assert_eq!(breaker.record(8453, 1, &alive, 1160), 0);
+        assert!(breaker.allows_automatic_audit(8453, 1, 1160));
+    }
+}
@@ -0,0 +1,323 @@
+//! CAIP-2 chain and CAIP-10 account identifiers
+//!
+//! `eip155:8453` names a chain and `eip155:8453:0x8004…` an account on it,
+//! such as an EIP-8004 registry. Identifiers are checked against the CAIP
+//! grammar; for `eip155` the reference must be a decimal chain ID and the
+//! address a 20-byte hex address, normalized to its EIP-55 checksum so
+//! identifiers compare by value. Mixed-case addresses must carry a valid
+//! checksum.
+
+use std::fmt;
+use std::str::FromStr;
+
+use alloy::primitives::Address;
+use serde::de::{self, Deserializer, Visitor};
+
+use crate::chains;
+use crate::types::WatchyError;
+
+/// Namespace of EVM chains
+pub const EIP155: &str = "eip155";
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CaipError {
+    #[error("{0:?} is not a CAIP-2 chain (<namespace>:<reference>)")]
+    InvalidChain(String),
+    #[error("{0:?} is not a CAIP-10 account (<namespace>:<reference>:<address>)")]
+    InvalidAccount(String),
+    #[error("{0:?} is not a decimal EVM chain ID")]
+    InvalidChainId(String),
+    #[error("{0:?} is not an EVM address")]
+    InvalidAddress(String),
+    #[error("{0:?} has an invalid EIP-55 checksum")]
+    BadChecksum(String),
+    #[error("{0:?} is not an EVM chain (only eip155 is supported)")]
+    NotEvm(String),
+    #[error("unknown chain {0:?}; expected a chain ID, a CAIP-2 ID (eip155:<chainId>) or one of: {1}")]
+    UnknownChain(String, String),
+}
+
+impl From<CaipError> for WatchyError {
+    fn from(e: CaipError) -> Self {
+        match e {
+            CaipError::InvalidAddress(_) | CaipError::BadChecksum(_) => {
+                WatchyError::InvalidAddress(e.to_string())
+            }
+            _ => WatchyError::InvalidRequest(e.to_string()),
+        }
+    }
+}
+
+/// A CAIP-2 chain ID
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ChainId {
+    pub namespace: String,
+    pub reference: String,
+}
+
+impl ChainId {
+    pub fn eip155(chain_id: u64) -> Self {
+        Self {
+            namespace: EIP155.to_string(),
+            reference: chain_id.to_string(),
+        }
+    }
+
+    /// Numeric chain ID of an `eip155` chain
+    pub fn evm_chain_id(&self) -> Option<u64> {
+        if self.namespace != EIP155 {
+            return None;
+        }
+        self.reference.parse().ok()
+    }
+}
+
+impl FromStr for ChainId {
+    type Err = CaipError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || CaipError::InvalidChain(value.to_string());
+        let (namespace, reference) = value.split_once(':').ok_or_else(invalid)?;
+        if !valid_namespace(namespace) || !valid_reference(reference) {
+            return Err(invalid());
+        }
+        if namespace == EIP155 && !valid_evm_chain_id(reference) {
+            return Err(CaipError::InvalidChainId(reference.to_string()));
+        }
+        Ok(Self {
+            namespace: namespace.to_string(),
+            reference: reference.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for ChainId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.namespace, self.reference)
+    }
+}
+
+/// A CAIP-10 account ID (EVM addresses checksummed)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AccountId {
+    pub chain: ChainId,
+    pub address: String,
+}
+
+impl AccountId {
+    /// Account on an EVM chain
+    pub fn eip155(chain_id: u64, address: &str) -> Result<Self, CaipError> {
+        Ok(Self {
+            chain: ChainId::eip155(chain_id),
+            address: checksum_address(address)?,
+        })
+    }
+
+    /// CAIP-10 string of an EVM account, or the plain concatenation if the
+    /// address isn't one (kept as given rather than dropped)
+    pub fn eip155_string(chain_id: u64, address: &str) -> String {
+        match Self::eip155(chain_id, address) {
+            Ok(account) => account.to_string(),
+            Err(_) => format!("{}:{}:{}", EIP155, chain_id, address),
+        }
+    }
+}
+
+impl FromStr for AccountId {
+    type Err = CaipError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (chain, address) = value
+            .rsplit_once(':')
+            .ok_or_else(|| CaipError::InvalidAccount(value.to_string()))?;
+        let chain: ChainId = chain
+            .parse()
+            .map_err(|_| CaipError::InvalidAccount(value.to_string()))?;
+        if !valid_account_address(address) {
+            return Err(CaipError::InvalidAccount(value.to_string()));
+        }
+        let address = if chain.namespace == EIP155 {
+            checksum_address(address)?
+        } else {
+            address.to_string()
+        };
+        Ok(Self { chain, address })
+    }
+}
+
+impl fmt::Display for AccountId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.chain, self.address)
+    }
+}
+
+/// EIP-55 form of an EVM address; mixed-case input must already be checksummed
+pub fn checksum_address(address: &str) -> Result<String, CaipError> {
+    let parsed = Address::from_str(address)
+        .ok()
+        .filter(|_| address.len() == 42 && address.starts_with("0x"))
+        .ok_or_else(|| CaipError::InvalidAddress(address.to_string()))?;
+    let hex = &address[2..];
+    let mixed_case = hex.chars().any(|c| c.is_ascii_uppercase())
+        && hex.chars().any(|c| c.is_ascii_lowercase());
+    let checksummed = parsed.to_checksum(None);
+    if mixed_case && checksummed != address {
+        return Err(CaipError::BadChecksum(address.to_string()));
+    }
+    Ok(checksummed)
+}
+
+/// Chain ID from a request parameter: a decimal chain ID, a CAIP-2
+/// `eip155:<chainId>` or a chain name ("base-sepolia"). Numeric IDs Watchy
+/// doesn't know pass through, so callers can say which chains are supported.
+pub fn parse_chain_param(value: &str) -> Result<u64, CaipError> {
+    let value = value.trim();
+    if let Ok(chain_id) = value.parse() {
+        return Ok(chain_id);
+    }
+    if value.contains(':') {
+        let chain: ChainId = value.parse()?;
+        return chain.evm_chain_id().ok_or(CaipError::NotEvm(value.to_string()));
+    }
+    if let Some(chain) = chains::get_chain_by_name(&value.to_lowercase()) {
+        return Ok(chain.chain_id);
+    }
+    let mut names: Vec<&str> = chains::supported_chain_ids()
+        .into_iter()
+        .filter_map(chains::get_chain)
+        .map(|chain| chain.name)
+        .collect();
+    names.sort_unstable();
+    Err(CaipError::UnknownChain(value.to_string(), names.join(", ")))
+}
+
+/// Deserialize a chain ID given as a number, a CAIP-2 string or a chain name
+pub fn deserialize_chain_id<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    deserialize_optional_chain_id(deserializer)?
+        .ok_or_else(|| de::Error::custom("chain_id must not be null"))
+}
+
+/// `deserialize_chain_id` for optional fields (use with `#[serde(default)]`)
+pub fn deserialize_optional_chain_id<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<u64>, D::Error> {
+    deserializer.deserialize_any(ChainIdVisitor)
+}
+
+struct ChainIdVisitor;
+
+impl<'de> Visitor<'de> for ChainIdVisitor {
+    type Value = Option<u64>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a chain ID, a CAIP-2 chain (eip155:<chainId>) or a chain name")
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+        Ok(Some(value))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+        u64::try_from(value)
+            .map(Some)
+            .map_err(|_| E::custom(format!("{} is not a chain ID", value)))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        parse_chain_param(value).map(Some).map_err(E::custom)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+/// `[-a-z0-9]{3,8}`
+fn valid_namespace(namespace: &str) -> bool {
+    (3..=8).contains(&namespace.len())
+        && namespace
+            .bytes()
+            .all(|b| b == b'-' || b.is_ascii_lowercase() || b.is_ascii_digit())
+}
+
+/// `[-_a-zA-Z0-9]{1,32}`
+fn valid_reference(reference: &str) -> bool {
+    (1..=32).contains(&reference.len())
+        && reference
+            .bytes()
+            .all(|b| b == b'-' || b == b'_' || b.is_ascii_alphanumeric())
+}
+
+/// `[-.%a-zA-Z0-9]{1,128}`
+fn valid_account_address(address: &str) -> bool {
+    (1..=128).contains(&address.len())
+        && address
+            .bytes()
+            .all(|b| b == b'-' || b == b'.' || b == b'%' || b.is_ascii_alphanumeric())
+}
+
+/// Decimal without leading zeros that fits a u64
+fn valid_evm_chain_id(reference: &str) -> bool {
+    !(reference.len() > 1 && reference.starts_with('0')) && reference.parse::<u64>().is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checksummed example address from EIP-55
+    const ADDRESS: &str = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+
+    #[test]
+    fn test_parse_account() {
+        let lower = format!("eip155:8453:{}", ADDRESS.to_lowercase());
+        let account: AccountId = lower.parse().unwrap();
+        assert_eq!(account.chain.evm_chain_id(), Some(8453));
+        assert_eq!(account.to_string(), format!("eip155:8453:{}", ADDRESS));
+        assert_eq!(account, format!("eip155:8453:{}", ADDRESS).parse().unwrap());
+
+        let bad_checksum = "eip155:8453:0x5aaeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+        assert!(matches!(bad_checksum.parse::<AccountId>(), Err(CaipError::BadChecksum(_))));
+        assert!("eip155:8453".parse::<AccountId>().is_err());
+        assert!(format!("eip155:08453:{}", ADDRESS).parse::<AccountId>().is_err());
+        let solana: AccountId = "solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp:7S3P4HxJpyyigGzodYwHtCxZyUQe9JiBMHyRWXArAaKv"
+            .parse()
+            .unwrap();
+        assert_eq!(solana.chain.evm_chain_id(), None);
+    }
+
+    #[test]
+    fn test_parse_chain_param() {
+        assert_eq!(parse_chain_param("8453"), Ok(8453));
+        assert_eq!(parse_chain_param(" eip155:1 "), Ok(1));
+        assert!(matches!(parse_chain_param("cosmos:cosmoshub-4"), Err(CaipError::NotEvm(_))));
+        assert!(parse_chain_param("eip155:").is_err());
+        assert!(parse_chain_param("eip155:base").is_err());
+        assert_eq!(parse_chain_param(" Base-Sepolia "), Ok(84532));
+        assert_eq!(parse_chain_param("999999"), Ok(999999));
+        let unknown = parse_chain_param("basee").unwrap_err();
+        assert!(matches!(&unknown, CaipError::UnknownChain(..)));
+        assert!(unknown.to_string().contains("base-sepolia"));
+
+        #[derive(serde::Deserialize)]
+        struct Query {
+            #[serde(default, deserialize_with = "deserialize_optional_chain_id")]
+            chain_id: Option<u64>,
+        }
+        let parse = |json| serde_json::from_str::<Query>(json).map(|query| query.chain_id);
+        assert_eq!(parse(r#"{"chain_id":"eip155:8453"}"#).unwrap(), Some(8453));
+        assert_eq!(parse(r#"{"chain_id":10}"#).unwrap(), Some(10));
+        assert_eq!(parse(r#"{"chain_id":"base-sepolia"}"#).unwrap(), Some(84532));
+        assert_eq!(parse(r#"{"chain_id":null}"#).unwrap(), None);
+        assert_eq!(parse("{}").unwrap(), None);
+        assert!(parse(r#"{"chain_id":"eip155:x"}"#).is_err());
+    }
+}
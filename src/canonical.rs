@@ -0,0 +1,148 @@
+//! Canonical JSON for hashing and signing reports
+//!
+//! Follows the JSON Canonicalization Scheme (RFC 8785): no whitespace, object
+//! keys sorted by their UTF-16 code units, numbers in their shortest
+//! ECMAScript form (`100.0` is written `100`, `1e21` as `1e+21`) and strings
+//! with only the mandatory escapes. The bytes don't depend on serde_json's map
+//! ordering or float printing, so hashes stay stable across versions and can
+//! be reproduced by any JCS implementation.
+
+use serde_json::{Number, Value};
+
+/// Scheme recorded on reports hashed and signed over canonical bytes
+pub const SCHEME: &str = "jcs-rfc8785";
+
+/// Name of the report field declaring the scheme
+pub const FIELD: &str = "canonicalization";
+
+/// Bytes a report is hashed and signed over: canonical for reports declaring
+/// the scheme, serde_json's compact output for reports published before it
+pub fn report_bytes(report: &Value) -> Vec<u8> {
+    match report.get(FIELD).and_then(Value::as_str) {
+        Some(SCHEME) => to_vec(report),
+        _ => serde_json::to_vec(report).unwrap_or_default(),
+    }
+}
+
+/// Canonical bytes of a JSON value
+pub fn to_vec(value: &Value) -> Vec<u8> {
+    let mut out = String::new();
+    write_value(value, &mut out);
+    out.into_bytes()
+}
+
+fn write_value(value: &Value, out: &mut String) {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => write_number(n, out),
+        Value::String(s) => write_string(s, out),
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_value(item, out);
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+            out.push('{');
+            for (i, (key, item)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_string(key, out);
+                out.push(':');
+                write_value(item, out);
+            }
+            out.push('}');
+        }
+    }
+}
+
+fn write_number(n: &Number, out: &mut String) {
+    if let Some(i) = n.as_i64() {
+        out.push_str(&i.to_string());
+    } else if let Some(u) = n.as_u64() {
+        out.push_str(&u.to_string());
+    } else {
+        // serde_json numbers are always finite
+        out.push_str(&format_f64(n.as_f64().unwrap_or_default()));
+    }
+}
+
+/// ECMAScript `Number.prototype.toString` for finite values
+fn format_f64(f: f64) -> String {
+    if f == 0.0 {
+        return "0".to_string();
+    }
+    let abs = f.abs();
+    if (1e-6..1e21).contains(&abs) {
+        // Rust prints the shortest round-trip digits without an exponent
+        return f.to_string();
+    }
+    let exp = format!("{:e}", f);
+    match exp.split_once('e') {
+        Some((mantissa, power)) if !power.starts_with('-') => format!("{}e+{}", mantissa, power),
+        _ => exp,
+    }
+}
+
+fn write_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{08}' => out.push_str("\\b"),
+            '\u{0c}' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn canonical(value: Value) -> String {
+        String::from_utf8(to_vec(&value)).unwrap()
+    }
+
+    #[test]
+    fn test_canonical_json() {
+        assert_eq!(
+            canonical(
+                json!({ "b": [1, 2.5, 100.0], "a": { "z": null, "y": true }, "": "\n\u{1f}é" })
+            ),
+            r#"{"":"\n\u001fé","a":{"y":true,"z":null},"b":[1,2.5,100]}"#
+        );
+        assert_eq!(
+            canonical(json!([1e21, 1e-7, -0.0, 0.1, -3])),
+            "[1e+21,1e-7,0,0.1,-3]"
+        );
+        // Keys sort by UTF-16 code units, not UTF-8 bytes
+        assert_eq!(
+            canonical(json!({ "\u{e000}": 1, "\u{10000}": 2 })),
+            "{\"\u{10000}\":2,\"\u{e000}\":1}"
+        );
+
+        // Only reports declaring the scheme are hashed canonically
+        let report = json!({ "value": 85.0, "canonicalization": SCHEME });
+        assert_eq!(report_bytes(&report), to_vec(&report));
+        assert_eq!(
+            report_bytes(&json!({ "value": 85.0 })),
+            br#"{"value":85.0}"#
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::sync::LazyLock;
+use tracing::warn;
 
 /// Chain type for different blockchain ecosystems
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -19,8 +20,46 @@ pub struct ChainConfig {
     pub rpcs: Vec<&'static str>,
     #[allow(dead_code)]
     pub block_explorer: &'static str,
+    /// Chain whose ENS registry names this chain's addresses (None = no ENS)
+    pub ens_chain_id: Option<u64>,
+    /// Ethereum Attestation Service contract (None = not deployed)
+    pub eas_address: Option<&'static str>,
+    /// A block at or before the identity registry's deployment, where event
+    /// scans start (0 = no registry)
+    pub registry_deploy_block: u64,
+    /// Scoring and publishing defaults for audits on this chain
+    pub profile: ChainProfile,
 }
 
+/// How audits on a chain are scored and published
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChainProfile {
+    pub name: &'static str,
+    /// Submit on-chain feedback to the reputation registry
+    pub feedback: bool,
+    /// Multipliers for the configured category weights, keyed as in
+    /// `SCORE_WEIGHTS`; unlisted categories keep their weight
+    pub weight_factors: &'static [(&'static str, f64)],
+}
+
+/// Configured weights, feedback published
+pub const MAINNET_PROFILE: ChainProfile = ChainProfile {
+    name: "mainnet",
+    feedback: true,
+    weight_factors: &[],
+};
+
+/// No feedback (it would only clutter testnet registries), and endpoint health
+/// counts for more than a testnet's on-chain record; only used where
+/// `AUDIT_PROFILE_<CHAIN>` asks for it
+pub const TESTNET_PROFILE: ChainProfile = ChainProfile {
+    name: "testnet",
+    feedback: false,
+    weight_factors: &[("availability", 1.5), ("performance", 1.5), ("onchain", 0.5)],
+};
+
+pub const PROFILES: &[ChainProfile] = &[MAINNET_PROFILE, TESTNET_PROFILE];
+
 impl ChainConfig {
     /// Get the first available RPC URL
     pub fn primary_rpc(&self) -> Option<&str> {
@@ -56,6 +95,10 @@ pub static CHAINS: LazyLock<HashMap<u64, ChainConfig>> = LazyLock::new(|| {
                 "https://base-mainnet.public.blastapi.io",
             ],
             block_explorer: "https://basescan.org",
+            ens_chain_id: Some(1),
+            eas_address: Some("0x4200000000000000000000000000000000000021"),
+            registry_deploy_block: 28_000_000,
+            profile: MAINNET_PROFILE,
         },
         ChainConfig {
             chain_id: 1,
@@ -70,6 +113,10 @@ pub static CHAINS: LazyLock<HashMap<u64, ChainConfig>> = LazyLock::new(|| {
                 "https://eth.drpc.org",
             ],
             block_explorer: "https://etherscan.io",
+            ens_chain_id: Some(1),
+            eas_address: Some("0xA1207F3BBa224E2c9c3c6D5aF63D0eb1582Ce587"),
+            registry_deploy_block: 22_000_000,
+            profile: MAINNET_PROFILE,
         },
         // ===== TESTNETS =====
         ChainConfig {
@@ -84,6 +131,10 @@ pub static CHAINS: LazyLock<HashMap<u64, ChainConfig>> = LazyLock::new(|| {
                 "https://base-sepolia.publicnode.com",
             ],
             block_explorer: "https://sepolia.basescan.org",
+            ens_chain_id: Some(11155111),
+            eas_address: Some("0x4200000000000000000000000000000000000021"),
+            registry_deploy_block: 20_000_000,
+            profile: MAINNET_PROFILE,
         },
         ChainConfig {
             chain_id: 11155111,
@@ -97,6 +148,10 @@ pub static CHAINS: LazyLock<HashMap<u64, ChainConfig>> = LazyLock::new(|| {
                 "https://rpc.ankr.com/eth_sepolia",
             ],
             block_explorer: "https://sepolia.etherscan.io",
+            ens_chain_id: Some(11155111),
+            eas_address: Some("0xC2679fBD37d54388Ce493F1DB75320D236e1815e"),
+            registry_deploy_block: 7_000_000,
+            profile: MAINNET_PROFILE,
         },
         // ===== SOLANA =====
         ChainConfig {
@@ -110,6 +165,10 @@ pub static CHAINS: LazyLock<HashMap<u64, ChainConfig>> = LazyLock::new(|| {
                 "https://solana-api.projectserum.com",
             ],
             block_explorer: "https://solscan.io",
+            ens_chain_id: None,
+            eas_address: None,
+            registry_deploy_block: 0,
+            profile: MAINNET_PROFILE,
         },
         ChainConfig {
             chain_id: 103, // Solana devnet (unofficial ID for our purposes)
@@ -121,6 +180,10 @@ pub static CHAINS: LazyLock<HashMap<u64, ChainConfig>> = LazyLock::new(|| {
                 "https://api.devnet.solana.com",
             ],
             block_explorer: "https://solscan.io/?cluster=devnet",
+            ens_chain_id: None,
+            eas_address: None,
+            registry_deploy_block: 0,
+            profile: MAINNET_PROFILE,
         },
     ];
 
@@ -133,7 +196,6 @@ pub fn get_chain(chain_id: u64) -> Option<&'static ChainConfig> {
 }
 
 /// Get chain config by name
-#[allow(dead_code)]
 pub fn get_chain_by_name(name: &str) -> Option<&'static ChainConfig> {
     CHAINS.values().find(|c| c.name == name)
 }
@@ -186,6 +248,51 @@ pub fn get_all_rpcs(chain_id: u64) -> Vec<String> {
     rpcs
 }
 
+/// First block to scan for registry events, from REGISTRY_START_BLOCK_{CHAIN_NAME}
+///
+/// Defaults to the chain's registry deployment block, so scans don't walk the
+/// chain's history before the registry existed.
+pub fn registry_start_block(chain_id: u64) -> u64 {
+    let Some(chain) = get_chain(chain_id) else {
+        return 0;
+    };
+    let env_key = format!(
+        "REGISTRY_START_BLOCK_{}",
+        chain.name.to_uppercase().replace('-', "_")
+    );
+    std::env::var(env_key)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(chain.registry_deploy_block)
+}
+
+/// Audit profile for a chain, from AUDIT_PROFILE_{CHAIN_NAME} or the registry
+///
+/// Unknown chains get the mainnet profile; an unknown profile name is ignored.
+pub fn chain_profile(chain_id: u64) -> ChainProfile {
+    let Some(chain) = get_chain(chain_id) else {
+        return MAINNET_PROFILE;
+    };
+    let env_key = format!(
+        "AUDIT_PROFILE_{}",
+        chain.name.to_uppercase().replace('-', "_")
+    );
+    match std::env::var(&env_key) {
+        Ok(name) => profile_by_name(&name).unwrap_or_else(|| {
+            warn!(
+                "{}: unknown profile '{}', using {}",
+                env_key, name, chain.profile.name
+            );
+            chain.profile
+        }),
+        Err(_) => chain.profile,
+    }
+}
+
+pub fn profile_by_name(name: &str) -> Option<ChainProfile> {
+    PROFILES.iter().find(|profile| profile.name == name).copied()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -217,6 +324,8 @@ mod tests {
         assert!(chains.iter().any(|c| c.name == "sepolia"));
         // Solana doesn't have a registry yet
         assert!(!chains.iter().any(|c| c.name == "solana"));
+        // Event scans start at the deployment, not genesis
+        assert!(chains.iter().all(|c| c.registry_deploy_block > 0));
     }
 
     #[test]
@@ -225,4 +334,14 @@ mod tests {
         assert_eq!(chain.name, "solana");
         assert_eq!(chain.chain_type, ChainType::Solana);
     }
+
+    #[test]
+    fn test_chain_profiles() {
+        assert_eq!(chain_profile(8453), MAINNET_PROFILE);
+        // Testnets keep publishing feedback unless the operator opts in
+        assert!(CHAINS.values().all(|chain| chain.profile == MAINNET_PROFILE));
+        assert_eq!(chain_profile(42), MAINNET_PROFILE);
+        assert_eq!(profile_by_name("testnet"), Some(TESTNET_PROFILE));
+        assert_eq!(profile_by_name("staging"), None);
+    }
 }
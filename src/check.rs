@@ -0,0 +1,360 @@
+//! `watchy --check-config`: validate a deployment's configuration and exit
+//!
+//! Loads the environment and settings file as a start would, checks the
+//! built-in chain definitions and RPC overrides, derives the wallet, and
+//! connects to Redis (or opens SQLite) and every chain's RPC. Prints one line
+//! per check and exits non-zero if any failed, so CI can run it against a new
+//! environment before deploying. Warnings (an optional chain's RPC down, no
+//! signing key) don't fail the check.
+
+use alloy::primitives::Address;
+use std::env;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::chains::{self, ChainType};
+use crate::config::Config;
+use crate::config_file::Applied;
+use crate::readiness;
+use crate::redis_conn::RedisConnection;
+use crate::store::sqlite::SqliteStore;
+
+/// Timeout for the Redis connection and PING
+const REDIS_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Outcome {
+    Ok,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug)]
+pub struct Check {
+    pub name: String,
+    pub outcome: Outcome,
+    pub detail: String,
+}
+
+#[derive(Debug, Default)]
+pub struct Report {
+    pub checks: Vec<Check>,
+}
+
+impl Report {
+    fn push(&mut self, outcome: Outcome, name: impl Into<String>, detail: impl Into<String>) {
+        self.checks.push(Check {
+            name: name.into(),
+            outcome,
+            detail: detail.into(),
+        });
+    }
+
+    pub fn passed(&self) -> bool {
+        self.checks
+            .iter()
+            .all(|check| check.outcome != Outcome::Fail)
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            let label = match check.outcome {
+                Outcome::Ok => "ok",
+                Outcome::Warn => "warn",
+                Outcome::Fail => "FAIL",
+            };
+            writeln!(f, "{:<5} {}: {}", label, check.name, check.detail)?;
+        }
+        let failed = self
+            .checks
+            .iter()
+            .filter(|check| check.outcome == Outcome::Fail)
+            .count();
+        match failed {
+            0 => write!(f, "Configuration OK"),
+            1 => write!(f, "1 check failed"),
+            n => write!(f, "{} checks failed", n),
+        }
+    }
+}
+
+/// Run every check; the configuration must load for the connection checks
+///
+/// `settings_file` is the settings file as loaded and applied at startup.
+pub async fn run(settings_file: anyhow::Result<Option<(PathBuf, Applied)>>) -> Report {
+    let mut report = Report::default();
+
+    match settings_file {
+        Ok(Some((path, applied))) => {
+            let detail = if applied.overridden.is_empty() {
+                path.display().to_string()
+            } else {
+                format!(
+                    "{} (overridden by the environment: {})",
+                    path.display(),
+                    applied.overridden.join(", ")
+                )
+            };
+            report.push(Outcome::Ok, "settings file", detail);
+        }
+        Ok(None) => report.push(Outcome::Ok, "settings file", "none, environment only"),
+        Err(e) => report.push(Outcome::Fail, "settings file", format!("{:#}", e)),
+    }
+
+    let problems = chain_problems(&env::vars().collect::<Vec<_>>());
+    if problems.is_empty() {
+        report.push(
+            Outcome::Ok,
+            "chain definitions",
+            format!("{} chains", chains::CHAINS.len()),
+        );
+    }
+    for problem in problems {
+        report.push(Outcome::Fail, "chain definitions", problem);
+    }
+
+    let config = match Config::from_env() {
+        Ok(config) => config,
+        Err(e) => {
+            report.push(Outcome::Fail, "configuration", format!("{:#}", e));
+            return report;
+        }
+    };
+    let default = chains::get_chain(config.default_chain_id)
+        .map(|chain| format!("{} ({})", chain.name, chain.chain_id))
+        .unwrap_or_else(|| config.default_chain_id.to_string());
+    report.push(
+        Outcome::Ok,
+        "configuration",
+        format!("default chain {}", default),
+    );
+
+    if let Some(self_registration) = &config.self_registration {
+        let chains: Vec<String> = self_registration
+            .chains
+            .iter()
+            .map(|chain_id| match self_registration.agent_ids.get(chain_id) {
+                Some(agent_id) => format!("{} (agent {})", chain_id, agent_id),
+                None => chain_id.to_string(),
+            })
+            .collect();
+        report.push(Outcome::Ok, "self registration", chains.join(", "));
+    }
+    check_wallet(&config, &mut report);
+    check_storage(&config, &mut report).await;
+    check_rpcs(&config, &mut report).await;
+    report
+}
+
+/// Malformed built-in chain data, and overrides in `vars` (the environment)
+/// for chains that don't exist
+pub fn chain_problems(vars: &[(String, String)]) -> Vec<String> {
+    let var = |name: &str| {
+        vars.iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    };
+    let mut problems = vec![];
+    let mut chains: Vec<_> = chains::CHAINS.values().collect();
+    chains.sort_by_key(|chain| chain.chain_id);
+    for chain in chains {
+        if chain.chain_type != ChainType::Evm {
+            continue;
+        }
+        let addresses = [
+            ("registry", chain.registry_address),
+            ("reputation", chain.reputation_address),
+            ("EAS", chain.eas_address),
+        ];
+        for (kind, address) in addresses {
+            if let Some(address) = address {
+                if Address::from_str(address).is_err() {
+                    problems.push(format!(
+                        "{}: invalid {} address {}",
+                        chain.name, kind, address
+                    ));
+                }
+            }
+        }
+        let rpc_override = var(&format!(
+            "RPC_URL_{}",
+            chain.name.to_uppercase().replace('-', "_")
+        ));
+        for rpc in rpc_override.iter().chain(chain.rpcs.iter()) {
+            if url::Url::parse(rpc).is_err() {
+                // Leave the URL out: overrides may carry API keys
+                problems.push(format!("{}: invalid RPC URL", chain.name));
+            }
+        }
+        if chain.rpcs.is_empty() && rpc_override.is_none() {
+            problems.push(format!("{}: no RPC", chain.name));
+        }
+    }
+
+    // A misspelled chain in an override is silently ignored at runtime
+    let known: Vec<String> = chains::CHAINS
+        .values()
+        .map(|chain| chain.name.to_uppercase().replace('-', "_"))
+        .collect();
+    for (name, value) in vars {
+        if name.starts_with("AUDIT_PROFILE_") && chains::profile_by_name(value).is_none() {
+            problems.push(format!("{}: unknown profile '{}'", name, value));
+        }
+        for prefix in ["RPC_URL_", "REGISTRY_START_BLOCK_", "AUDIT_PROFILE_"] {
+            let Some(chain) = name.strip_prefix(prefix) else {
+                continue;
+            };
+            if !known.iter().any(|known| known == chain) {
+                problems.push(format!("{} doesn't name a supported chain", name));
+            }
+        }
+    }
+    problems
+}
+
+fn check_wallet(config: &Config, report: &mut Report) {
+    let wallet = &config.wallet;
+    if !wallet.can_sign() {
+        report.push(
+            Outcome::Warn,
+            "wallet",
+            "no signing key: reports won't be signed or published",
+        );
+        return;
+    }
+    let signers: Vec<&str> = wallet
+        .signers
+        .iter()
+        .map(|signer| signer.address.as_str())
+        .collect();
+    report.push(
+        Outcome::Ok,
+        "wallet",
+        format!(
+            "{} key, signers {}",
+            wallet.mode.as_str(),
+            signers.join(", ")
+        ),
+    );
+    for (role, key) in [
+        ("upload key", &wallet.upload_signer),
+        ("feedback key", &wallet.feedback_signer),
+    ] {
+        if let Some(key) = key {
+            report.push(Outcome::Ok, role, key.address.clone());
+        }
+    }
+}
+
+async fn check_storage(config: &Config, report: &mut Report) {
+    if let Some(redis) = &config.redis {
+        let ping = async {
+            let mut conn = RedisConnection::connect(redis).await?;
+            redis::cmd("PING").query_async::<_, String>(&mut conn).await
+        };
+        match tokio::time::timeout(REDIS_TIMEOUT, ping).await {
+            Ok(Ok(_)) => report.push(
+                Outcome::Ok,
+                "storage",
+                format!("Redis ({}) answered PING", redis.mode.as_str()),
+            ),
+            Ok(Err(e)) => report.push(Outcome::Fail, "storage", format!("Redis: {}", e)),
+            Err(_) => report.push(Outcome::Fail, "storage", "Redis: timed out"),
+        }
+        return;
+    }
+    let Some(path) = &config.sqlite_path else {
+        report.push(
+            Outcome::Warn,
+            "storage",
+            "in-memory: audits are lost on restart",
+        );
+        return;
+    };
+    // Don't create a database where the check runs (e.g. CI)
+    if !path.exists() {
+        let dir = path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or(std::path::Path::new("."));
+        let (outcome, detail) = if dir.is_dir() {
+            (Outcome::Ok, "will be created")
+        } else {
+            (Outcome::Fail, "directory doesn't exist")
+        };
+        report.push(
+            outcome,
+            "storage",
+            format!("SQLite {}: {}", path.display(), detail),
+        );
+        return;
+    }
+    // Read-only: checking a configuration mustn't create or migrate tables
+    match SqliteStore::check(path) {
+        Ok(_) => report.push(Outcome::Ok, "storage", format!("SQLite {}", path.display())),
+        Err(e) => report.push(
+            Outcome::Fail,
+            "storage",
+            format!("SQLite {}: {}", path.display(), e),
+        ),
+    }
+}
+
+/// The readiness checks: required chains fail, others warn
+async fn check_rpcs(config: &Config, report: &mut Report) {
+    for status in readiness::check_chains(&config.readiness).await {
+        let name = format!("rpc {} ({})", status.name, status.chain_id);
+        match (status.ok, status.required) {
+            (true, _) => report.push(Outcome::Ok, name, "chain ID matches"),
+            (false, required) => report.push(
+                if required {
+                    Outcome::Fail
+                } else {
+                    Outcome::Warn
+                },
+                name,
+                status.error.unwrap_or_default(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_report() {
+        // Built-in data only, whatever the environment running the test holds
+        assert!(chain_problems(&[]).is_empty());
+        let vars = [
+            ("RPC_URL_BASE".to_string(), "not a url".to_string()),
+            ("RPC_URL_BASEE".to_string(), "https://rpc.example".to_string()),
+            ("AUDIT_PROFILE_BASE".to_string(), "lenient".to_string()),
+        ];
+        assert_eq!(
+            chain_problems(&vars),
+            [
+                "base: invalid RPC URL",
+                "RPC_URL_BASEE doesn't name a supported chain",
+                "AUDIT_PROFILE_BASE: unknown profile 'lenient'",
+            ]
+        );
+
+        let mut report = Report::default();
+        report.push(Outcome::Ok, "configuration", "default chain base (8453)");
+        report.push(Outcome::Warn, "rpc sepolia (11155111)", "RPC timed out");
+        assert!(report.passed());
+        assert!(report.to_string().ends_with("Configuration OK"));
+
+        report.push(Outcome::Fail, "storage", "Redis: timed out");
+        assert!(!report.passed());
+        let printed = report.to_string();
+        assert!(printed.contains("FAIL  storage: Redis: timed out"));
+        assert!(printed.ends_with("1 check failed"));
+    }
+}
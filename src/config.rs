@@ -1,8 +1,43 @@
 use anyhow::Result;
 use std::env;
+use std::path::PathBuf;
 
+use crate::arweave::irys::DEFAULT_ARWEAVE_GATEWAY;
+use crate::attestation::AttestationConfig;
+use crate::arweave::uploader::ArweaveProvider;
+#[cfg(feature = "llm")]
+use crate::audit::claims::LlmConfig;
+use crate::audit::i18n::Lang;
+use crate::audit::policy::ProbePolicy;
+use crate::audit::pricing::PricingConfig;
+use crate::audit::reputation::ReputationProvider;
+use crate::audit::scoring::{FeedbackPolicy, FeedbackTags, ScoreWeights};
+use crate::blockchain::eas::EasConfig;
+use crate::breaker::BreakerConfig;
+use crate::chains;
+use crate::readiness::ReadinessConfig;
+use crate::self_registration::SelfRegistrationConfig;
+use crate::server::{ListenAddr, ServerLimits};
+use crate::tls::TlsConfig;
+use crate::http::{HttpTimeouts, ProxyConfig, ResponseLimits};
+use crate::indexer::IndexerConfig;
+use crate::ipfs::client::DEFAULT_IPFS_GATEWAY;
+use crate::publish::ReportStore;
+use crate::redis_conn::RedisConfig;
+use crate::s3::S3Config;
+use crate::ssrf::OutboundGuard;
+use crate::store::RetentionPolicy;
+use crate::subscriptions::SmtpConfig;
+use crate::tenant::TenantKeys;
+use crate::usage::UsageConfig;
 use crate::wallet::{KeyMode, WalletConfig};
 
+/// Chains accepted when `ALLOWED_CHAINS` isn't set: Base, Base Sepolia, Sepolia
+const DEFAULT_ALLOWED_CHAINS: &[u64] = &[8453, 84532, 11155111];
+
+/// Endpoint response bytes kept per audit by default (256 KiB)
+const DEFAULT_ENDPOINT_ARTIFACT_BYTES: usize = 256 * 1024;
+
 /// Application configuration
 ///
 /// Chain-specific settings (RPC URLs, registry addresses) are stored in chains.rs
@@ -10,16 +45,145 @@ use crate::wallet::{KeyMode, WalletConfig};
 #[derive(Clone)]
 pub struct Config {
     pub port: u16,
+    /// TCP port, Unix socket or systemd-activated socket to serve on
+    pub listen: ListenAddr,
+    /// Request body, concurrency and timeout limits of the API server
+    pub server_limits: ServerLimits,
+    /// Built-in HTTPS (None = plain HTTP, e.g. behind a TLS proxy)
+    pub tls: Option<TlsConfig>,
     pub default_chain_id: u64,
-    pub redis_url: Option<String>,
+    /// Chains accepted for audits, registrations and subscriptions (None = all);
+    /// the value at startup, see `reload::LiveSettings`
+    pub allowed_chains: Option<Vec<u64>>,
+    /// Seconds between checks of the settings file for changes (0 = off)
+    pub config_watch_secs: u64,
+    pub redis: Option<RedisConfig>,
+    /// SQLite database file, instead of Redis
+    pub sqlite_path: Option<PathBuf>,
     pub ipfs_api_url: String,
     pub ipfs_api_key: Option<String>,
+    /// Primary report destination (arweave or ipfs)
+    pub report_store: ReportStore,
+    /// Bucket every published MD/JSON report is also copied to
+    pub report_mirror: Option<S3Config>,
+    /// Key for signed Markdown report links (links disabled when unset)
+    pub report_link_secret: Option<String>,
+    /// Key for receipt requester ids (the primary signer's key when unset)
+    pub receipt_secret: Option<String>,
+    /// EAS attestation of each published audit on the audited chain (optional)
+    pub eas: Option<EasConfig>,
+    /// Where to load Watchy's own TEE attestation quote (unset outside a TEE)
+    pub tee_attestation: Option<AttestationConfig>,
+    /// Outbound mail for owner email subscriptions (email disabled when unset)
+    pub smtp: Option<SmtpConfig>,
+    /// Markdown report language when the request doesn't set `lang`
+    pub report_lang: Lang,
+    /// IPFS Pinning Service API endpoint to replicate report CIDs to (optional)
+    pub ipfs_pin_service_url: Option<String>,
+    pub ipfs_pin_service_token: Option<String>,
+    /// Pin status polls before giving up on a replica pin
+    pub ipfs_pin_poll_attempts: u32,
+    /// Gateway for generated IPFS links
+    pub ipfs_gateway: String,
+    /// Store the audited metadata bytes next to each report
+    pub pin_metadata_snapshots: bool,
+    /// Keep the endpoint responses each audit judged with its job
+    pub store_endpoint_artifacts: bool,
+    /// Most response bytes kept per audit; larger responses keep only their hash
+    pub endpoint_artifact_bytes: usize,
+    /// Gateway for generated Arweave links
+    pub arweave_gateway: String,
+    /// Arweave upload provider (turbo, irys, bundler)
+    pub arweave_provider: ArweaveProvider,
+    /// Upload service base URL override (required for the bundler provider)
+    pub arweave_upload_url: Option<String>,
+    /// Upload the Markdown and JSON reports as a single ANS-104 bundle
+    pub arweave_bundle_reports: bool,
+    /// Gateway retrieval attempts to confirm Arweave uploads (0 = don't verify)
+    pub arweave_verify_attempts: u32,
+    /// Largest `POST /admin/funding/topup` transfer in wei (top-ups disabled when unset)
+    pub arweave_topup_max_wei: Option<u128>,
+    /// Score category and per-service-type weights
+    pub score_weights: ScoreWeights,
+    /// tag1/tag2 of reports and on-chain feedback
+    pub feedback_tags: FeedbackTags,
+    /// Scores that get on-chain feedback
+    pub feedback_policy: FeedbackPolicy,
+    /// Audit deadline when the request doesn't set `timeout_secs`
+    pub audit_timeout_secs: u64,
+    /// Upper bound for a requested `timeout_secs`
+    pub audit_max_timeout_secs: u64,
+    /// Endpoint checks (`POST /check/endpoint`) running at once
+    pub endpoint_check_concurrency: usize,
+    /// How long a report stays valid before it's marked stale (0 = forever)
+    pub audit_validity_secs: u64,
+    /// Allowlist restricting which endpoints are probed (empty = probe all)
+    pub probe_policy: ProbePolicy,
+    /// Timeouts for the per-purpose outbound HTTP clients
+    pub http_timeouts: HttpTimeouts,
+    /// Egress proxies, globally and per purpose (probes vs RPC)
+    pub proxies: ProxyConfig,
+    /// SSRF protection for agent-supplied URLs
+    pub outbound_guard: OutboundGuard,
+    /// Size caps for endpoint responses and agent images
+    pub response_limits: ResponseLimits,
+    /// User-Agent sent with metadata fetches and endpoint probes
+    pub probe_user_agent: String,
+    /// Honor `/.well-known/watchy.json` opt-outs and rate limits on agent hosts
+    pub honor_host_policy: bool,
+    /// Probe hosts with both A and AAAA records over IPv4 and IPv6 separately
+    pub probe_dual_stack: bool,
+    /// Threat intelligence provider for endpoint and image URLs (optional)
+    pub url_reputation: Option<ReputationProvider>,
+    /// USD price feed and sanity bound for advertised x402 prices
+    pub x402_pricing: PricingConfig,
+    /// Analyze the owner address's age and activity
+    pub owner_risk_checks: bool,
+    /// Check and reweight agents by their declared `supportedTrust` mechanisms
+    pub trust_profiles: bool,
+    /// Etherscan (v2 multichain) API key, for the owner's first transaction
+    pub etherscan_api_key: Option<String>,
+    /// Chat completions endpoint for claims analysis (check disabled when unset)
+    #[cfg(feature = "llm")]
+    pub llm: Option<LlmConfig>,
+    /// GitHub token for `sourceCode` verification (higher rate limit, private repo detection)
+    pub github_token: Option<String>,
+    /// Registry event indexer (and auto-audit of new agents)
+    pub indexer: IndexerConfig,
+    /// Back-off of automatic audits for agents that keep failing
+    pub breaker: BreakerConfig,
+    /// Chains checked before the service reports ready
+    pub readiness: ReadinessConfig,
+    /// Seconds between report integrity checks (0 disables the worker)
+    pub integrity_check_interval_secs: u64,
+    /// Published reports re-checked per run
+    pub integrity_sample_size: usize,
+    /// Optional webhook receiving failed integrity checks as JSON
+    pub integrity_alert_webhook: Option<String>,
+    /// Per-status job retention
+    pub audit_retention: RetentionPolicy,
+    /// Seconds between retention sweeps (0 disables archival and in-memory expiry)
+    pub retention_sweep_interval_secs: u64,
+    /// Bucket completed jobs are archived to before they expire
+    pub archive: Option<S3Config>,
     /// Wallet configuration (supports both PRIVATE_KEY and MNEMONIC modes)
     pub wallet: WalletConfig,
+    /// /health warns when the signer's balance on an allowed chain drops below this (wei)
+    pub signer_min_balance_wei: Option<u128>,
     /// API key for service-to-service authentication (optional)
     pub api_key: Option<String>,
+    /// Per-tenant API keys, each seeing only its own audits
+    pub tenant_keys: TenantKeys,
+    /// Monthly audit quotas per API key and the metering webhook
+    pub usage: UsageConfig,
     /// Admin API key for privileged operations like agent registration (optional)
     pub admin_api_key: Option<String>,
+    /// Public base URL of this deployment, for the A2A agent card (see `api::a2a`)
+    pub public_url: Option<String>,
+    /// Watchy's own registry entries (see `self_registration`)
+    pub self_registration: Option<SelfRegistrationConfig>,
+    /// Subsystems paused from startup (see `pause`)
+    pub paused_subsystems: Vec<crate::pause::Subsystem>,
 }
 
 impl Config {
@@ -27,36 +191,272 @@ impl Config {
         // Initialize wallet from environment
         let wallet = WalletConfig::from_env()?;
 
+        let arweave_provider = match env::var("ARWEAVE_PROVIDER") {
+            Ok(value) => ArweaveProvider::parse(&value).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Invalid ARWEAVE_PROVIDER '{}' (expected turbo, irys or bundler)",
+                    value
+                )
+            })?,
+            Err(_) => ArweaveProvider::Turbo,
+        };
+        let arweave_upload_url = env::var("ARWEAVE_UPLOAD_URL").ok();
+
+        let report_store = match env::var("REPORT_STORE") {
+            Ok(value) => ReportStore::parse(&value).ok_or_else(|| {
+                anyhow::anyhow!("Invalid REPORT_STORE '{}' (expected arweave or ipfs)", value)
+            })?,
+            Err(_) => ReportStore::Arweave,
+        };
+        let feedback_policy = match env::var("FEEDBACK_POLICY") {
+            Ok(value) => FeedbackPolicy::parse(&value).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Invalid FEEDBACK_POLICY '{}' (expected always, min:<score> or max:<score>)",
+                    value
+                )
+            })?,
+            Err(_) => FeedbackPolicy::Always,
+        };
+        let report_lang = match env::var("REPORT_LANG") {
+            Ok(value) => Lang::parse(&value).ok_or_else(|| {
+                anyhow::anyhow!("Invalid REPORT_LANG '{}' (expected en, zh or es)", value)
+            })?,
+            Err(_) => Lang::En,
+        };
+        if arweave_provider == ArweaveProvider::Bundler && arweave_upload_url.is_none() {
+            anyhow::bail!("ARWEAVE_UPLOAD_URL is required when ARWEAVE_PROVIDER=bundler");
+        }
+        let api_key = env::var("API_KEY").ok();
+        let tenant_keys = TenantKeys::from_env()?;
+        if api_key.as_deref().is_some_and(|key| tenant_keys.contains_key(key)) {
+            anyhow::bail!("API_KEY is also listed in TENANT_API_KEYS");
+        }
+        let redis = RedisConfig::from_env()?;
+        let sqlite_path = env::var("SQLITE_PATH").ok().map(PathBuf::from);
+        if redis.is_some() && sqlite_path.is_some() {
+            anyhow::bail!("Set either REDIS_URL or SQLITE_PATH, not both");
+        }
+        // Default chain if not specified in request
+        let default_chain_id = env::var("DEFAULT_CHAIN_ID")
+            .unwrap_or_else(|_| "8453".to_string()) // Base mainnet
+            .parse()?;
+        let allowed_chains = allowed_chains_from_vars(default_chain_id, &env_var)?;
+        let public_url = env::var("PUBLIC_URL")
+            .ok()
+            .map(|url| url.trim_end_matches('/').to_string());
+        let self_registration = SelfRegistrationConfig::from_env(
+            allowed_chains.as_deref(),
+            public_url.as_deref(),
+            wallet.can_sign(),
+        )?;
+
+        // APP_PORT (EigenCloud TLS) takes precedence over PORT
+        let port = env::var("APP_PORT")
+            .or_else(|_| env::var("PORT"))
+            .unwrap_or_else(|_| "8080".to_string())
+            .parse()?;
+
         Ok(Self {
-            // APP_PORT (EigenCloud TLS) takes precedence over PORT
-            port: env::var("APP_PORT")
-                .or_else(|_| env::var("PORT"))
-                .unwrap_or_else(|_| "8080".to_string())
-                .parse()?,
+            port,
+            listen: ListenAddr::from_env(port)?,
+            server_limits: ServerLimits::from_vars(&env_var)?,
+            tls: TlsConfig::from_env()?,
 
-            // Default chain if not specified in request
-            default_chain_id: env::var("DEFAULT_CHAIN_ID")
-                .unwrap_or_else(|_| "8453".to_string()) // Base mainnet
+            default_chain_id,
+            allowed_chains,
+            config_watch_secs: env::var("CONFIG_WATCH_SECS")
+                .unwrap_or_else(|_| "30".to_string())
                 .parse()?,
 
-            // Redis for job persistence (optional, falls back to in-memory)
-            redis_url: env::var("REDIS_URL").ok(),
+            // Redis or SQLite for job persistence (optional, falls back to in-memory)
+            redis,
+            sqlite_path,
 
             ipfs_api_url: env::var("IPFS_API_URL")
                 .unwrap_or_else(|_| "https://api.pinata.cloud".to_string()),
 
             ipfs_api_key: env::var("IPFS_API_KEY").ok(),
 
+            report_store,
+            report_lang,
+            // Copy of every published report (REPORT_MIRROR_S3_* for the bucket)
+            report_mirror: S3Config::from_env("REPORT_MIRROR")?,
+            report_link_secret: env::var("REPORT_LINK_SECRET").ok(),
+            receipt_secret: env::var("RECEIPT_SECRET").ok(),
+            smtp: SmtpConfig::from_env()?,
+            tee_attestation: AttestationConfig::from_env()?,
+            eas: EasConfig::from_env()?,
+
+            // Replicate IPFS reports to a second provider (Pinning Service API)
+            ipfs_pin_service_url: env::var("IPFS_PIN_SERVICE_URL").ok(),
+            ipfs_pin_service_token: env::var("IPFS_PIN_SERVICE_TOKEN").ok(),
+            ipfs_pin_poll_attempts: env::var("IPFS_PIN_POLL_ATTEMPTS")
+                .unwrap_or_else(|_| "6".to_string())
+                .parse()?,
+
+            // Gateways for report links (protocol-native ar:// / ipfs:// URIs are always included)
+            ipfs_gateway: env::var("IPFS_GATEWAY_URL")
+                .unwrap_or_else(|_| DEFAULT_IPFS_GATEWAY.to_string()),
+            arweave_gateway: env::var("ARWEAVE_GATEWAY_URL")
+                .unwrap_or_else(|_| DEFAULT_ARWEAVE_GATEWAY.to_string()),
+
+            // Pin the audited metadata to the report store (its hash is always recorded)
+            pin_metadata_snapshots: env::var("PIN_METADATA_SNAPSHOTS")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+
+            // Raw A2A / MCP / OASF responses, for inspecting disputed findings
+            store_endpoint_artifacts: env::var("STORE_ENDPOINT_ARTIFACTS")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            endpoint_artifact_bytes: match env::var("ENDPOINT_ARTIFACT_MAX_BYTES") {
+                Ok(value) => value.parse()?,
+                Err(_) => DEFAULT_ENDPOINT_ARTIFACT_BYTES,
+            },
+
+            arweave_provider,
+            arweave_upload_url,
+
+            arweave_bundle_reports: env::var("ARWEAVE_BUNDLE_REPORTS")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+
+            // Confirm uploads are served by the gateway before using them as feedbackURI
+            arweave_verify_attempts: if env::var("ARWEAVE_VERIFY_UPLOADS")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false)
+            {
+                env::var("ARWEAVE_VERIFY_ATTEMPTS")
+                    .unwrap_or_else(|_| "6".to_string())
+                    .parse()?
+            } else {
+                0
+            },
+
+            // Funding the upload provider from the signer wallet is opt-in
+            arweave_topup_max_wei: env::var("ARWEAVE_TOPUP_MAX_WEI")
+                .ok()
+                .map(|v| v.parse())
+                .transpose()?,
+
+            // Scoring weights (optional, defaults documented in .env.example)
+            score_weights: ScoreWeights::from_vars(&env_var)?,
+            feedback_tags: FeedbackTags::from_env()?,
+            feedback_policy,
+
+            // Overall audit deadline (per request via timeout_secs, capped)
+            audit_timeout_secs: env::var("AUDIT_TIMEOUT_SECS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()?,
+            audit_max_timeout_secs: env::var("AUDIT_MAX_TIMEOUT_SECS")
+                .unwrap_or_else(|_| "120".to_string())
+                .parse()?,
+            endpoint_check_concurrency: env::var("ENDPOINT_CHECK_CONCURRENCY")
+                .unwrap_or_else(|_| "4".to_string())
+                .parse::<usize>()?
+                .max(1),
+            audit_validity_secs: env::var("AUDIT_VALIDITY_DAYS")
+                .unwrap_or_else(|_| "90".to_string())
+                .parse::<u64>()?
+                * 24
+                * 60
+                * 60,
+
+            // Locked-down mode: only probe allowlisted domains / ASNs
+            probe_policy: ProbePolicy::from_env()?,
+
+            // Outbound HTTP client timeouts (metadata, probes, assets, uploads, webhooks)
+            http_timeouts: HttpTimeouts::from_vars(&env_var)?,
+
+            // Optional egress proxies (probe proxies rotate per request)
+            proxies: ProxyConfig::from_env()?,
+
+            // Block agent URLs resolving to private / link-local / loopback addresses
+            outbound_guard: OutboundGuard::from_env()?,
+
+            // Cap agent response sizes so a hostile endpoint can't exhaust memory
+            response_limits: ResponseLimits::from_env()?,
+
+            // Identify auditor traffic and let agent operators opt out / slow it down
+            probe_user_agent: env::var("PROBE_USER_AGENT").unwrap_or_else(|_| {
+                format!(
+                    "Watchy-Auditor/{} (+https://github.com/builders-garden/watchy)",
+                    env!("CARGO_PKG_VERSION")
+                )
+            }),
+            honor_host_policy: env::var("HONOR_HOST_POLICY")
+                .map(|v| v != "false" && v != "0")
+                .unwrap_or(true),
+            probe_dual_stack: env::var("PROBE_DUAL_STACK")
+                .map(|v| v != "false" && v != "0")
+                .unwrap_or(true),
+
+            // Phishing / malware lookups (Google Safe Browsing or URLhaus)
+            url_reputation: ReputationProvider::from_env()?,
+
+            // Normalize x402 prices to USD (stablecoins at their peg unless a feed is set)
+            x402_pricing: PricingConfig::from_env()?,
+
+            // Owner address age, activity and wallet reuse (informational)
+            owner_risk_checks: env::var("OWNER_RISK_CHECKS")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            trust_profiles: env::var("TRUST_PROFILES")
+                .map(|v| v != "false" && v != "0")
+                .unwrap_or(true),
+            etherscan_api_key: env::var("ETHERSCAN_API_KEY").ok().filter(|k| !k.is_empty()),
+            github_token: env::var("GITHUB_TOKEN").ok().filter(|t| !t.is_empty()),
+            #[cfg(feature = "llm")]
+            llm: LlmConfig::from_env()?,
+
+            indexer: IndexerConfig::from_env(default_chain_id)?,
+            breaker: BreakerConfig::from_env()?,
+            readiness: ReadinessConfig::from_env(default_chain_id)?,
+
+            // Periodically re-fetch published reports and verify hash + signature
+            integrity_check_interval_secs: env::var("REPORT_INTEGRITY_INTERVAL_SECS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()?,
+            integrity_sample_size: env::var("REPORT_INTEGRITY_SAMPLE_SIZE")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()?,
+            integrity_alert_webhook: env::var("REPORT_INTEGRITY_ALERT_WEBHOOK").ok(),
+
+            // Job retention and archival (ARCHIVE_S3_* for the bucket)
+            audit_retention: RetentionPolicy::from_env()?,
+            retention_sweep_interval_secs: env::var("RETENTION_SWEEP_INTERVAL_SECS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()?,
+            archive: S3Config::from_env("ARCHIVE")?,
+
             wallet,
+            signer_min_balance_wei: env::var("SIGNER_MIN_BALANCE_WEI")
+                .ok()
+                .map(|v| v.parse())
+                .transpose()?,
 
             // API key for service-to-service auth (if set, all requests must include X-API-Key header)
-            api_key: env::var("API_KEY").ok(),
+            api_key,
+            tenant_keys,
+            usage: UsageConfig::from_vars(&env_var)?,
 
             // Admin API key for privileged operations (agent registration, etc.)
             admin_api_key: env::var("ADMIN_API_KEY").ok(),
+            public_url,
+            self_registration,
+            paused_subsystems: crate::pause::parse_list(
+                &env::var("PAUSED_SUBSYSTEMS").unwrap_or_default(),
+            )?,
         })
     }
 
+    /// Effective audit deadline for a requested timeout
+    pub fn audit_timeout(&self, requested: Option<u64>) -> u64 {
+        requested
+            .unwrap_or(self.audit_timeout_secs)
+            .min(self.audit_max_timeout_secs)
+    }
+
     /// Get private key if available (for backward compatibility)
     pub fn private_key(&self) -> Option<&str> {
         self.wallet.private_key.as_deref()
@@ -72,3 +472,42 @@ impl Config {
         &self.wallet.mode
     }
 }
+
+/// Looks up a setting by its environment variable name
+pub type Vars<'a> = &'a dyn Fn(&str) -> Option<String>;
+
+/// The process environment, as `Vars`
+pub fn env_var(name: &str) -> Option<String> {
+    env::var(name).ok()
+}
+
+/// Load `ALLOWED_CHAINS` (comma-separated chain IDs, `*` = all; `None` = all)
+///
+/// An explicit list must contain the default chain.
+pub fn allowed_chains_from_vars(default_chain_id: u64, vars: Vars) -> Result<Option<Vec<u64>>> {
+    let value = match vars("ALLOWED_CHAINS") {
+        Some(value) if value.trim() == "*" => return Ok(None),
+        Some(value) => value,
+        None => return Ok(Some(DEFAULT_ALLOWED_CHAINS.to_vec())),
+    };
+    let allowed = value
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            s.parse()
+                .map_err(|_| anyhow::anyhow!("Invalid chain ID '{}' in ALLOWED_CHAINS", s))
+        })
+        .collect::<Result<Vec<u64>>>()?;
+    if let Some(unknown) = allowed.iter().find(|&&id| chains::get_chain(id).is_none()) {
+        anyhow::bail!("ALLOWED_CHAINS: unsupported chain {}", unknown);
+    }
+    if !allowed.contains(&default_chain_id) {
+        anyhow::bail!(
+            "DEFAULT_CHAIN_ID {} is not in ALLOWED_CHAINS {:?}",
+            default_chain_id,
+            allowed
+        );
+    }
+    Ok(Some(allowed))
+}
@@ -0,0 +1,452 @@
+//! `watchy.toml`: the main settings in one file
+//!
+//! Read from `WATCHY_CONFIG`, or `./watchy.toml` if it exists, before the
+//! environment is parsed. Every key stands for an environment variable, which
+//! takes precedence when both are set: a deployment can share one file and
+//! override single settings per environment. Unknown keys, wrong types and
+//! unknown chains fail startup with the offending key and line.
+//!
+//! ```toml
+//! [server]
+//! port = 8080
+//!
+//! [chains]
+//! default = 8453
+//! allowed = [8453, 84532]
+//! rpc = { base = "https://base.example.com" }
+//!
+//! [scoring.weights]
+//! security = 0.3
+//!
+//! [timeouts]
+//! audit_secs = 120
+//!
+//! [storage]
+//! sqlite_path = "./data/watchy.db"
+//!
+//! [quotas]
+//! per_month = 1000
+//! ```
+//!
+//! Score weights, allowed chains, quotas, the probe timeout and the request
+//! limits can be changed without a restart (see `reload`).
+
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::path::{Path, PathBuf};
+
+use crate::chains;
+
+/// Read when `WATCHY_CONFIG` isn't set
+pub const DEFAULT_PATH: &str = "watchy.toml";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigFile {
+    pub server: ServerSection,
+    pub chains: ChainsSection,
+    pub scoring: ScoringSection,
+    pub timeouts: TimeoutsSection,
+    pub storage: StorageSection,
+    pub quotas: QuotasSection,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerSection {
+    pub port: Option<u16>,
+    pub unix_socket: Option<PathBuf>,
+    /// Octal, as a string ("660")
+    pub unix_socket_mode: Option<String>,
+    pub max_request_body_bytes: Option<u64>,
+    pub max_concurrent_requests: Option<u64>,
+    pub request_timeout_secs: Option<u64>,
+    pub header_read_timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChainsSection {
+    pub default: Option<u64>,
+    /// Chains accepted for audits and registrations
+    pub allowed: Option<Vec<u64>>,
+    pub readiness: Option<Vec<u64>>,
+    pub indexed: Option<Vec<u64>>,
+    /// RPC overrides by chain name
+    pub rpc: BTreeMap<String, String>,
+    /// Audit profiles ("mainnet", "testnet") by chain name
+    pub profiles: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScoringSection {
+    pub weights: CategoryWeights,
+    /// Weights by service type
+    pub services: BTreeMap<String, f64>,
+    /// Feedback tags; `{band}` stands for the score band
+    pub feedback_tag1: Option<String>,
+    pub feedback_tag2: Option<String>,
+    /// "always", "min:<score>" or "max:<score>"
+    pub feedback_policy: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CategoryWeights {
+    pub availability: Option<f64>,
+    pub performance: Option<f64>,
+    pub security: Option<f64>,
+    pub metadata: Option<f64>,
+    pub onchain: Option<f64>,
+    pub consistency: Option<f64>,
+    pub content: Option<f64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TimeoutsSection {
+    pub audit_secs: Option<u64>,
+    pub audit_max_secs: Option<u64>,
+    pub http_connect_secs: Option<u64>,
+    pub http_metadata_secs: Option<u64>,
+    pub http_probe_secs: Option<u64>,
+    pub http_assets_secs: Option<u64>,
+    pub http_upload_secs: Option<u64>,
+    pub http_webhook_secs: Option<u64>,
+    pub llm_secs: Option<u64>,
+    pub startup_rpc_secs: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct QuotasSection {
+    /// Audits per month and API key (0 = unlimited)
+    pub per_month: Option<u64>,
+    /// Per-tenant audits per month (0 = unlimited)
+    pub tenants: BTreeMap<String, u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageSection {
+    pub redis_url: Option<String>,
+    pub redis_mode: Option<String>,
+    pub redis_sentinel_master: Option<String>,
+    pub sqlite_path: Option<PathBuf>,
+    pub report_store: Option<String>,
+    pub retention_pending_secs: Option<u64>,
+    pub retention_completed_secs: Option<u64>,
+    pub retention_failed_secs: Option<u64>,
+}
+
+impl ConfigFile {
+    /// The file named by `WATCHY_CONFIG`, else `./watchy.toml` if present
+    ///
+    /// A missing `WATCHY_CONFIG` file is an error, a missing default one isn't.
+    pub fn load() -> anyhow::Result<Option<(PathBuf, Self)>> {
+        let path = match env::var("WATCHY_CONFIG") {
+            Ok(path) => PathBuf::from(path),
+            Err(_) if Path::new(DEFAULT_PATH).exists() => PathBuf::from(DEFAULT_PATH),
+            Err(_) => return Ok(None),
+        };
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| anyhow::anyhow!("Can't read {}: {}", path.display(), e))?;
+        let file =
+            Self::parse(&text).map_err(|e| anyhow::anyhow!("Invalid {}: {}", path.display(), e))?;
+        Ok(Some((path, file)))
+    }
+
+    pub fn parse(text: &str) -> anyhow::Result<Self> {
+        let file: Self = toml::from_str(text)?;
+        file.validate()?;
+        Ok(file)
+    }
+
+    /// Chain references the TOML types can't check
+    fn validate(&self) -> anyhow::Result<()> {
+        let lists = [
+            ("chains.allowed", &self.chains.allowed),
+            ("chains.readiness", &self.chains.readiness),
+            ("chains.indexed", &self.chains.indexed),
+        ];
+        let ids = self
+            .chains
+            .default
+            .iter()
+            .map(|&id| ("chains.default", id))
+            .chain(
+                lists
+                    .into_iter()
+                    .flat_map(|(key, ids)| ids.iter().flatten().map(move |&id| (key, id))),
+            );
+        for (key, chain_id) in ids {
+            if chains::get_chain(chain_id).is_none() {
+                anyhow::bail!(
+                    "{}: unsupported chain {} (supported: {:?})",
+                    key,
+                    chain_id,
+                    sorted(chains::supported_chain_ids())
+                );
+            }
+        }
+        if let (Some(default), Some(allowed)) = (self.chains.default, &self.chains.allowed) {
+            if !allowed.contains(&default) {
+                anyhow::bail!("chains.default {} is not in chains.allowed", default);
+            }
+        }
+        let names = [
+            ("chains.rpc", self.chains.rpc.keys()),
+            ("chains.profiles", self.chains.profiles.keys()),
+        ];
+        for (key, name) in names
+            .into_iter()
+            .flat_map(|(key, names)| names.map(move |n| (key, n)))
+        {
+            if chains::get_chain_by_name(name).is_none() {
+                let mut names: Vec<_> = chains::CHAINS.values().map(|c| c.name).collect();
+                names.sort_unstable();
+                anyhow::bail!(
+                    "{}: unknown chain '{}' (known: {})",
+                    key,
+                    name,
+                    names.join(", ")
+                );
+            }
+        }
+        for (name, profile) in &self.chains.profiles {
+            if chains::profile_by_name(profile).is_none() {
+                let profiles: Vec<_> = chains::PROFILES.iter().map(|p| p.name).collect();
+                anyhow::bail!(
+                    "chains.profiles.{}: unknown profile '{}' (known: {})",
+                    name,
+                    profile,
+                    profiles.join(", ")
+                );
+            }
+        }
+        if let Some(mode) = &self.server.unix_socket_mode {
+            if u32::from_str_radix(mode, 8).is_err() {
+                anyhow::bail!("server.unix_socket_mode must be octal, e.g. \"660\"");
+            }
+        }
+        Ok(())
+    }
+
+    /// The environment variables this file stands for
+    pub fn vars(&self) -> Vec<(String, String)> {
+        let mut vars = Vec::new();
+        let mut set = |name: &str, value: Option<String>| {
+            if let Some(value) = value {
+                vars.push((name.to_string(), value));
+            }
+        };
+        let list = |ids: &Option<Vec<u64>>| {
+            ids.as_ref()
+                .map(|ids| ids.iter().map(u64::to_string).collect::<Vec<_>>().join(","))
+        };
+        let weights = |pairs: Vec<(&str, Option<f64>)>| {
+            let pairs: Vec<String> = pairs
+                .into_iter()
+                .filter_map(|(key, weight)| Some(format!("{}={}", key, weight?)))
+                .collect();
+            (!pairs.is_empty()).then(|| pairs.join(","))
+        };
+        let text = |value: &Option<String>| value.clone();
+        let path = |value: &Option<PathBuf>| value.as_ref().map(|p| p.display().to_string());
+        let num = |value: Option<u64>| value.map(|v| v.to_string());
+
+        let server = &self.server;
+        set("PORT", server.port.map(|p| p.to_string()));
+        set("LISTEN_UNIX_SOCKET", path(&server.unix_socket));
+        set("LISTEN_UNIX_SOCKET_MODE", text(&server.unix_socket_mode));
+        set("MAX_REQUEST_BODY_BYTES", num(server.max_request_body_bytes));
+        set(
+            "MAX_CONCURRENT_REQUESTS",
+            num(server.max_concurrent_requests),
+        );
+        set("REQUEST_TIMEOUT_SECS", num(server.request_timeout_secs));
+        set(
+            "HEADER_READ_TIMEOUT_SECS",
+            num(server.header_read_timeout_secs),
+        );
+
+        let chain = &self.chains;
+        set("DEFAULT_CHAIN_ID", num(chain.default));
+        set("ALLOWED_CHAINS", list(&chain.allowed));
+        set("READINESS_CHAINS", list(&chain.readiness));
+        set("INDEXER_CHAINS", list(&chain.indexed));
+        for (name, url) in &chain.rpc {
+            let env_key = format!("RPC_URL_{}", name.to_uppercase().replace('-', "_"));
+            set(&env_key, Some(url.clone()));
+        }
+        for (name, profile) in &chain.profiles {
+            let env_key = format!("AUDIT_PROFILE_{}", name.to_uppercase().replace('-', "_"));
+            set(&env_key, Some(profile.clone()));
+        }
+
+        let w = &self.scoring.weights;
+        set(
+            "SCORE_WEIGHTS",
+            weights(vec![
+                ("availability", w.availability),
+                ("performance", w.performance),
+                ("security", w.security),
+                ("metadata", w.metadata),
+                ("onchain", w.onchain),
+                ("consistency", w.consistency),
+                ("content", w.content),
+            ]),
+        );
+        set(
+            "SERVICE_WEIGHTS",
+            weights(
+                self.scoring
+                    .services
+                    .iter()
+                    .map(|(key, &weight)| (key.as_str(), Some(weight)))
+                    .collect(),
+            ),
+        );
+        set("FEEDBACK_TAG1", text(&self.scoring.feedback_tag1));
+        set("FEEDBACK_TAG2", text(&self.scoring.feedback_tag2));
+        set("FEEDBACK_POLICY", text(&self.scoring.feedback_policy));
+
+        let t = &self.timeouts;
+        set("AUDIT_TIMEOUT_SECS", num(t.audit_secs));
+        set("AUDIT_MAX_TIMEOUT_SECS", num(t.audit_max_secs));
+        set("HTTP_CONNECT_TIMEOUT_SECS", num(t.http_connect_secs));
+        set("HTTP_METADATA_TIMEOUT_SECS", num(t.http_metadata_secs));
+        set("HTTP_PROBE_TIMEOUT_SECS", num(t.http_probe_secs));
+        set("HTTP_ASSETS_TIMEOUT_SECS", num(t.http_assets_secs));
+        set("HTTP_UPLOAD_TIMEOUT_SECS", num(t.http_upload_secs));
+        set("HTTP_WEBHOOK_TIMEOUT_SECS", num(t.http_webhook_secs));
+        set("LLM_TIMEOUT_SECS", num(t.llm_secs));
+        set("STARTUP_RPC_TIMEOUT_SECS", num(t.startup_rpc_secs));
+
+        let s = &self.storage;
+        set("REDIS_URL", text(&s.redis_url));
+        set("REDIS_MODE", text(&s.redis_mode));
+        set("REDIS_SENTINEL_MASTER", text(&s.redis_sentinel_master));
+        set("SQLITE_PATH", path(&s.sqlite_path));
+        set("REPORT_STORE", text(&s.report_store));
+        set(
+            "AUDIT_RETENTION_PENDING_SECS",
+            num(s.retention_pending_secs),
+        );
+        set(
+            "AUDIT_RETENTION_COMPLETED_SECS",
+            num(s.retention_completed_secs),
+        );
+        set("AUDIT_RETENTION_FAILED_SECS", num(s.retention_failed_secs));
+
+        let q = &self.quotas;
+        set("AUDIT_QUOTA_PER_MONTH", num(q.per_month));
+        let tenants: Vec<String> = q
+            .tenants
+            .iter()
+            .map(|(tenant, quota)| format!("{}:{}", tenant, quota))
+            .collect();
+        set(
+            "TENANT_AUDIT_QUOTAS",
+            (!tenants.is_empty()).then(|| tenants.join(",")),
+        );
+
+        vars
+    }
+
+    /// Set this file's variables that the environment doesn't set already
+    ///
+    /// Call it before the runtime or any other thread starts; reloads never
+    /// touch the environment.
+    pub fn apply(&self) -> Applied {
+        let mut applied = Applied::default();
+        for (name, value) in self.vars() {
+            if env::var_os(&name).is_some() {
+                applied.overridden.push(name);
+            } else {
+                env::set_var(&name, &value);
+                applied.set.insert(name, value);
+            }
+        }
+        applied
+    }
+}
+
+/// What applying a settings file did to the environment
+#[derive(Debug, Default)]
+pub struct Applied {
+    /// Variables taken from the file, with their values
+    pub set: BTreeMap<String, String>,
+    /// Variables the environment already set, which win over the file
+    pub overridden: Vec<String>,
+}
+
+fn sorted(mut ids: Vec<u64>) -> Vec<u64> {
+    ids.sort_unstable();
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_file() {
+        let file = ConfigFile::parse(
+            r#"
+            [server]
+            port = 9000
+
+            [chains]
+            default = 84532
+            allowed = [84532, 11155111]
+            rpc = { base-sepolia = "https://rpc.example.com" }
+            profiles = { base-sepolia = "mainnet" }
+
+            [scoring.weights]
+            security = 0.5
+            content = 0
+
+            [timeouts]
+            audit_secs = 90
+
+            [quotas.tenants]
+            acme = 500
+            default = 0
+            "#,
+        )
+        .unwrap();
+        let vars = file.vars();
+        let get = |name: &str| {
+            vars.iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.as_str())
+        };
+        assert_eq!(get("PORT"), Some("9000"));
+        assert_eq!(get("ALLOWED_CHAINS"), Some("84532,11155111"));
+        assert_eq!(get("RPC_URL_BASE_SEPOLIA"), Some("https://rpc.example.com"));
+        assert_eq!(get("AUDIT_PROFILE_BASE_SEPOLIA"), Some("mainnet"));
+        assert_eq!(get("SCORE_WEIGHTS"), Some("security=0.5,content=0"));
+        assert_eq!(get("AUDIT_TIMEOUT_SECS"), Some("90"));
+        assert_eq!(get("TENANT_AUDIT_QUOTAS"), Some("acme:500,default:0"));
+        assert_eq!(get("REDIS_URL"), None);
+
+        assert!(ConfigFile::parse(include_str!("../watchy.example.toml")).is_ok());
+
+        // Errors name the key
+        let typo = ConfigFile::parse("[server]\nprot = 9000").unwrap_err();
+        assert!(typo.to_string().contains("prot"));
+        let wrong_type = ConfigFile::parse("[server]\nport = \"9000\"").unwrap_err();
+        assert!(wrong_type.to_string().contains("port"));
+        let chain = ConfigFile::parse("[chains]\nallowed = [8453, 42]").unwrap_err();
+        assert!(chain
+            .to_string()
+            .contains("chains.allowed: unsupported chain 42"));
+        let default = ConfigFile::parse("[chains]\ndefault = 1\nallowed = [8453]").unwrap_err();
+        assert!(default.to_string().contains("not in chains.allowed"));
+        assert!(ConfigFile::parse("[chains.rpc]\nbasee = \"https://x\"").is_err());
+        assert!(ConfigFile::parse("[chains.profiles]\nbase = \"staging\"").is_err());
+    }
+}
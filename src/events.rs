@@ -0,0 +1,196 @@
+//! Per-audit event log
+//!
+//! An append-only timeline of what happened during an audit (state
+//! transitions, phases, RPCs and gateways used or failed over, uploads,
+//! feedback transactions), appended next to the job record (a Redis list or
+//! SQLite rows) rather than rewriting it, so concurrent events aren't lost.
+//! Served raw at `GET /audit/:id/events` and relative to job creation at
+//! `GET /audit/:id/timeline`.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::AppState;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    /// Job status transition (recorded by the store)
+    StatusChanged,
+    AuditStarted,
+    AuditCompleted,
+    AuditFailed,
+    /// A failed or partial audit was re-run under the same ID
+    AuditRetried,
+    PhaseStarted,
+    PhaseFinished,
+    /// A phase was cut short by the audit deadline
+    DeadlineExceeded,
+    /// An RPC failed and the next one is tried
+    RpcFailover,
+    /// RPC that served the on-chain data
+    RpcUsed,
+    /// A gateway failed and the next one is tried
+    GatewayFallback,
+    /// Gateway that served the metadata
+    GatewayUsed,
+    UploadAttempt,
+    UploadFailed,
+    FeedbackSubmitted,
+    FeedbackFailed,
+    AttestationCreated,
+    AttestationFailed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEvent {
+    /// Unix timestamp in milliseconds
+    pub timestamp: u64,
+    pub kind: EventKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phase: Option<String>,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
+impl AuditEvent {
+    pub fn new(kind: EventKind, message: impl Into<String>) -> Self {
+        Self {
+            timestamp: chrono::Utc::now().timestamp_millis() as u64,
+            kind,
+            phase: None,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    pub fn with_phase(mut self, phase: &str) -> Self {
+        self.phase = Some(phase.to_string());
+        self
+    }
+
+    pub fn with_data(mut self, data: serde_json::Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+}
+
+/// An event positioned relative to job creation
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineEntry {
+    /// Milliseconds since the job was created
+    pub offset_ms: u64,
+    #[serde(flatten)]
+    pub event: AuditEvent,
+}
+
+/// Order events by time and offset them from `created_at` (Unix seconds)
+pub fn timeline(created_at: u64, events: &[AuditEvent]) -> Vec<TimelineEntry> {
+    let origin_ms = created_at.saturating_mul(1000);
+    let mut entries: Vec<TimelineEntry> = events
+        .iter()
+        .map(|event| TimelineEntry {
+            offset_ms: event.timestamp.saturating_sub(origin_ms),
+            event: event.clone(),
+        })
+        .collect();
+    entries.sort_by_key(|entry| entry.event.timestamp);
+    entries
+}
+
+/// Handle for appending events to one audit's log
+///
+/// A disabled log (for code paths outside an audit job) drops all events.
+#[derive(Clone)]
+pub struct EventLog {
+    target: Option<(Arc<AppState>, String)>,
+}
+
+impl EventLog {
+    pub fn new(state: Arc<AppState>, audit_id: &str) -> Self {
+        Self {
+            target: Some((state, audit_id.to_string())),
+        }
+    }
+
+    pub fn disabled() -> Self {
+        Self { target: None }
+    }
+
+    pub async fn record(&self, event: AuditEvent) {
+        if let Some((state, audit_id)) = &self.target {
+            state.audit_store.append_event(audit_id, &event).await;
+        }
+    }
+
+    pub async fn emit(&self, kind: EventKind, message: impl Into<String>) {
+        self.record(AuditEvent::new(kind, message)).await;
+    }
+
+    /// Record the start of a phase; pass the returned instant to `phase_finished`
+    pub async fn phase_started(&self, phase: &str) -> Instant {
+        self.record(AuditEvent::new(EventKind::PhaseStarted, format!("{} started", phase)).with_phase(phase))
+            .await;
+        Instant::now()
+    }
+
+    pub async fn phase_finished(&self, phase: &str, started: Instant) {
+        let duration_ms = started.elapsed().as_millis() as u64;
+        self.record(
+            AuditEvent::new(
+                EventKind::PhaseFinished,
+                format!("{} finished in {}ms", phase, duration_ms),
+            )
+            .with_phase(phase)
+            .with_data(serde_json::json!({ "durationMs": duration_ms })),
+        )
+        .await;
+    }
+
+    /// Record an upload attempt and its outcome
+    pub async fn upload(&self, destination: &str, name: &str, result: Result<&str, String>) {
+        let event = match result {
+            Ok(location) => AuditEvent::new(
+                EventKind::UploadAttempt,
+                format!("Uploaded {} to {}", name, destination),
+            )
+            .with_data(serde_json::json!({
+                "destination": destination,
+                "name": name,
+                "location": location,
+            })),
+            Err(error) => AuditEvent::new(
+                EventKind::UploadFailed,
+                format!("Failed to upload {} to {}", name, destination),
+            )
+            .with_data(serde_json::json!({
+                "destination": destination,
+                "name": name,
+                "error": error,
+            })),
+        };
+        self.record(event.with_phase("publish")).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timeline_offsets() {
+        let mut late = AuditEvent::new(EventKind::AuditCompleted, "done");
+        late.timestamp = 1_700_000_002_500;
+        let mut early = AuditEvent::new(EventKind::AuditStarted, "started");
+        early.timestamp = 1_700_000_000_250;
+
+        let entries = timeline(1_700_000_000, &[late, early]);
+        assert_eq!(entries[0].event.kind, EventKind::AuditStarted);
+        assert_eq!(entries[0].offset_ms, 250);
+        assert_eq!(entries[1].offset_ms, 2_500);
+    }
+}
@@ -0,0 +1,673 @@
+//! Outbound HTTP clients, one per purpose
+//!
+//! Each kind of traffic gets its own client so timeouts, redirect policies and
+//! connection pools fit the job: a slow agent endpoint shouldn't share a pool
+//! (or a 10s timeout) with multi-megabyte report uploads.
+
+use std::env;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use url::Url;
+
+use crate::config::Vars;
+use crate::ssrf::{redirect_policy, AddressFamily, GuardedResolver, OutboundGuard};
+
+/// Timeouts per client, in seconds
+#[derive(Debug, Clone)]
+pub struct HttpTimeouts {
+    /// TCP + TLS handshake, for every client
+    pub connect_secs: u64,
+    pub metadata_secs: u64,
+    pub probe_secs: u64,
+    pub assets_secs: u64,
+    pub upload_secs: u64,
+    pub webhook_secs: u64,
+}
+
+impl Default for HttpTimeouts {
+    fn default() -> Self {
+        Self {
+            connect_secs: 5,
+            metadata_secs: 10,
+            probe_secs: 10,
+            assets_secs: 5,
+            upload_secs: 120,
+            webhook_secs: 5,
+        }
+    }
+}
+
+impl HttpTimeouts {
+    /// Load from `HTTP_{CONNECT,METADATA,PROBE,ASSETS,UPLOAD,WEBHOOK}_TIMEOUT_SECS`
+    pub fn from_vars(vars: Vars) -> anyhow::Result<Self> {
+        let defaults = Self::default();
+        let secs = |name: &str, default: u64| -> anyhow::Result<u64> {
+            match vars(name) {
+                Some(value) => Ok(value.parse()?),
+                None => Ok(default),
+            }
+        };
+
+        Ok(Self {
+            connect_secs: secs("HTTP_CONNECT_TIMEOUT_SECS", defaults.connect_secs)?,
+            metadata_secs: secs("HTTP_METADATA_TIMEOUT_SECS", defaults.metadata_secs)?,
+            probe_secs: secs("HTTP_PROBE_TIMEOUT_SECS", defaults.probe_secs)?,
+            assets_secs: secs("HTTP_ASSETS_TIMEOUT_SECS", defaults.assets_secs)?,
+            upload_secs: secs("HTTP_UPLOAD_TIMEOUT_SECS", defaults.upload_secs)?,
+            webhook_secs: secs("HTTP_WEBHOOK_TIMEOUT_SECS", defaults.webhook_secs)?,
+        })
+    }
+}
+
+/// Egress proxies (`http://`, `https://`, `socks5://` or `socks5h://` URLs)
+///
+/// Behind a proxy, agent hostnames are resolved by the proxy: the SSRF guard
+/// still checks URLs before they're fetched, but enforcing the address policy
+/// on redirects and at connect time is up to the proxy's egress rules.
+#[derive(Debug, Clone, Default)]
+pub struct ProxyConfig {
+    /// Traffic without a more specific proxy
+    pub default: Option<Url>,
+    /// Agent-facing clients (metadata, probes, assets, subscriptions); with
+    /// several, each request uses the next one
+    pub probe: Vec<Url>,
+    /// Chain RPC calls (HTTP(S) proxies only)
+    pub rpc: Option<Url>,
+}
+
+impl ProxyConfig {
+    /// Load from `OUTBOUND_PROXY`, `PROBE_PROXY` (comma-separated) and `RPC_PROXY`
+    pub fn from_env() -> anyhow::Result<Self> {
+        let parse = |name: &str, value: &str| -> anyhow::Result<Url> {
+            let url = Url::parse(value.trim())
+                .map_err(|e| anyhow::anyhow!("Invalid proxy URL in {}: {}", name, e))?;
+            if !["http", "https", "socks5", "socks5h"].contains(&url.scheme()) {
+                anyhow::bail!("Unsupported proxy scheme '{}' in {}", url.scheme(), name);
+            }
+            Ok(url)
+        };
+        let single = |name: &str| -> anyhow::Result<Option<Url>> {
+            match env::var(name) {
+                Ok(value) if !value.trim().is_empty() => Ok(Some(parse(name, &value)?)),
+                _ => Ok(None),
+            }
+        };
+
+        let config = Self {
+            default: single("OUTBOUND_PROXY")?,
+            probe: env::var("PROBE_PROXY")
+                .unwrap_or_default()
+                .split(',')
+                .filter(|s| !s.trim().is_empty())
+                .map(|s| parse("PROBE_PROXY", s))
+                .collect::<anyhow::Result<_>>()?,
+            rpc: single("RPC_PROXY")?,
+        };
+        if let Some(rpc) = config.rpc_proxy() {
+            if rpc.scheme().starts_with("socks") {
+                anyhow::bail!("RPC traffic needs an HTTP(S) proxy: set RPC_PROXY");
+            }
+        }
+        Ok(config)
+    }
+
+    /// Proxies for agent-facing clients
+    pub fn probe_proxies(&self) -> Vec<Url> {
+        if self.probe.is_empty() {
+            self.default.iter().cloned().collect()
+        } else {
+            self.probe.clone()
+        }
+    }
+
+    /// Proxy for chain RPC calls
+    pub fn rpc_proxy(&self) -> Option<&Url> {
+        self.rpc.as_ref().or(self.default.as_ref())
+    }
+
+    /// Hostnames of the agent-facing proxies
+    pub fn probe_hosts(&self) -> Vec<String> {
+        self.probe_proxies()
+            .iter()
+            .filter_map(|url| url.host_str().map(str::to_string))
+            .collect()
+    }
+}
+
+/// Proxy applied to a group of clients
+#[derive(Clone)]
+struct ClientProxy {
+    proxy: reqwest::Proxy,
+    /// Several proxies, taken in turn
+    rotating: bool,
+}
+
+impl ClientProxy {
+    fn new(urls: Vec<Url>) -> reqwest::Result<Option<Self>> {
+        let rotating = urls.len() > 1;
+        let proxy = match urls.len() {
+            0 => return Ok(None),
+            1 => reqwest::Proxy::all(urls[0].clone())?,
+            len => {
+                let next = AtomicUsize::new(0);
+                reqwest::Proxy::custom(move |_| {
+                    Some(urls[next.fetch_add(1, Ordering::Relaxed) % len].clone())
+                })
+            }
+        };
+        Ok(Some(Self { proxy, rotating }))
+    }
+}
+
+/// Build a client, through the proxy if one is set
+///
+/// Rotating clients keep no idle connections: the pool is keyed by target
+/// host, so a reused connection would stick to one proxy.
+fn build_client(
+    builder: reqwest::ClientBuilder,
+    proxy: &Option<ClientProxy>,
+) -> reqwest::Result<reqwest::Client> {
+    match proxy {
+        Some(proxy) if proxy.rotating => builder
+            .proxy(proxy.proxy.clone())
+            .pool_max_idle_per_host(0)
+            .build(),
+        Some(proxy) => builder.proxy(proxy.proxy.clone()).build(),
+        None => builder.build(),
+    }
+}
+/// Purpose-built clients shared through `AppState`
+///
+/// `reqwest::Client` is reference-counted, so cloning one into a component
+/// (e.g. an upload client) shares its connection pool. Clients that fetch
+/// agent- or owner-supplied URLs (metadata, probe, assets, subscriptions) go
+/// through the SSRF guard; upload and alert webhook targets are
+/// operator-configured and require TLS 1.2. Agent-facing clients accept older
+/// TLS so the security checks can report it instead of the probe failing.
+/// Endpoint probing has its own clients (`ProbeClients`), rebuilt when the
+/// probe timeout is reloaded.
+pub struct HttpClients {
+    /// Guard applied to agent-supplied URLs
+    pub guard: Arc<OutboundGuard>,
+    /// Agent metadata and gateway content (published reports)
+    pub metadata: reqwest::Client,
+    /// Image and link checks, URL reputation lookups
+    pub assets: reqwest::Client,
+    /// Arweave, IPFS and object storage uploads
+    pub upload: reqwest::Client,
+    /// Alert webhooks
+    pub webhook: reqwest::Client,
+    /// Owner-registered subscription webhooks
+    pub subscriber: reqwest::Client,
+}
+
+impl HttpClients {
+    /// `user_agent` identifies the agent-facing clients to endpoint operators
+    pub fn new(
+        timeouts: &HttpTimeouts,
+        guard: OutboundGuard,
+        user_agent: &str,
+        proxies: &ProxyConfig,
+    ) -> reqwest::Result<Self> {
+        let secs = Duration::from_secs;
+        let guard = Arc::new(guard);
+        let resolver = Arc::new(
+            GuardedResolver::new(guard.clone()).with_proxy_hosts(proxies.probe_hosts()),
+        );
+        let probe_proxy = ClientProxy::new(proxies.probe_proxies())?;
+        let default_proxy = ClientProxy::new(proxies.default.iter().cloned().collect())?;
+
+        Ok(Self {
+            metadata: build_client(
+                reqwest::Client::builder()
+                    .connect_timeout(secs(timeouts.connect_secs))
+                    .timeout(secs(timeouts.metadata_secs))
+                    .user_agent(user_agent)
+                    .dns_resolver(resolver.clone())
+                    .redirect(redirect_policy(guard.clone(), 10)),
+                &probe_proxy,
+            )?,
+            assets: build_client(
+                reqwest::Client::builder()
+                    .connect_timeout(secs(timeouts.connect_secs))
+                    .timeout(secs(timeouts.assets_secs))
+                    .user_agent(user_agent)
+                    .dns_resolver(resolver.clone())
+                    .redirect(redirect_policy(guard.clone(), 5))
+                    .pool_max_idle_per_host(2),
+                &probe_proxy,
+            )?,
+            upload: build_client(
+                reqwest::Client::builder()
+                    .connect_timeout(secs(timeouts.connect_secs))
+                    .timeout(secs(timeouts.upload_secs))
+                    .min_tls_version(reqwest::tls::Version::TLS_1_2),
+                &default_proxy,
+            )?,
+            webhook: build_client(
+                reqwest::Client::builder()
+                    .connect_timeout(secs(timeouts.connect_secs))
+                    .timeout(secs(timeouts.webhook_secs))
+                    .min_tls_version(reqwest::tls::Version::TLS_1_2)
+                    .redirect(reqwest::redirect::Policy::none()),
+                &default_proxy,
+            )?,
+            subscriber: build_client(
+                reqwest::Client::builder()
+                    .connect_timeout(secs(timeouts.connect_secs))
+                    .timeout(secs(timeouts.webhook_secs))
+                    .dns_resolver(resolver)
+                    .redirect(reqwest::redirect::Policy::none()),
+                &probe_proxy,
+            )?,
+            guard,
+        })
+    }
+}
+
+/// Agent endpoint probing clients, bound by `HTTP_PROBE_TIMEOUT_SECS`
+pub struct ProbeClients {
+    /// Endpoint probing and security header checks
+    pub probe: reqwest::Client,
+    /// Probing without following redirects (HTTP → HTTPS enforcement)
+    pub probe_no_redirect: reqwest::Client,
+    /// Probing pinned to IPv4 / IPv6 (dual-stack checks); unset when probes
+    /// go through a proxy, which does its own resolution
+    pub probe_families: Option<FamilyClients>,
+    /// gRPC probing (HTTP/2 without negotiation, so plaintext h2c works too)
+    pub grpc: reqwest::Client,
+}
+
+impl ProbeClients {
+    pub fn new(
+        timeouts: &HttpTimeouts,
+        guard: &Arc<OutboundGuard>,
+        user_agent: &str,
+        proxies: &ProxyConfig,
+    ) -> reqwest::Result<Self> {
+        let secs = Duration::from_secs;
+        let resolver = Arc::new(
+            GuardedResolver::new(guard.clone()).with_proxy_hosts(proxies.probe_hosts()),
+        );
+        let probe_proxy = ClientProxy::new(proxies.probe_proxies())?;
+        let family_client = |family| {
+            reqwest::Client::builder()
+                .connect_timeout(secs(timeouts.connect_secs))
+                .timeout(secs(timeouts.probe_secs))
+                .user_agent(user_agent)
+                .dns_resolver(Arc::new(
+                    GuardedResolver::new(guard.clone()).with_family(family),
+                ))
+                .redirect(redirect_policy(guard.clone(), 5))
+                .pool_max_idle_per_host(0)
+                .build()
+        };
+        let probe_families = if probe_proxy.is_some() {
+            None
+        } else {
+            Some(FamilyClients {
+                ipv4: family_client(AddressFamily::V4)?,
+                ipv6: family_client(AddressFamily::V6)?,
+            })
+        };
+
+        Ok(Self {
+            // Agents are contacted rarely: don't keep idle connections around
+            probe: build_client(
+                reqwest::Client::builder()
+                    .connect_timeout(secs(timeouts.connect_secs))
+                    .timeout(secs(timeouts.probe_secs))
+                    .user_agent(user_agent)
+                    .dns_resolver(resolver.clone())
+                    .redirect(redirect_policy(guard.clone(), 5))
+                    .pool_idle_timeout(secs(30))
+                    .pool_max_idle_per_host(2),
+                &probe_proxy,
+            )?,
+            probe_no_redirect: build_client(
+                reqwest::Client::builder()
+                    .connect_timeout(secs(timeouts.connect_secs))
+                    .timeout(secs(timeouts.probe_secs))
+                    .user_agent(user_agent)
+                    .dns_resolver(resolver.clone())
+                    .redirect(reqwest::redirect::Policy::none())
+                    .pool_max_idle_per_host(0),
+                &probe_proxy,
+            )?,
+            probe_families,
+            grpc: build_client(
+                reqwest::Client::builder()
+                    .connect_timeout(secs(timeouts.connect_secs))
+                    .timeout(secs(timeouts.probe_secs))
+                    .user_agent(user_agent)
+                    .dns_resolver(resolver)
+                    .redirect(reqwest::redirect::Policy::none())
+                    .http2_prior_knowledge()
+                    .pool_max_idle_per_host(0),
+                &probe_proxy,
+            )?,
+        })
+    }
+}
+
+/// Probe clients that only connect over one IP version
+pub struct FamilyClients {
+    pub ipv4: reqwest::Client,
+    pub ipv6: reqwest::Client,
+}
+
+/// Caps on agent-supplied response bodies
+#[derive(Debug, Clone)]
+pub struct ResponseLimits {
+    /// Endpoint JSON responses (A2A agent cards, MCP manifests, OASF records)
+    pub endpoint_json_bytes: usize,
+    /// Agent image, checked against its Content-Length
+    pub image_bytes: u64,
+}
+
+impl Default for ResponseLimits {
+    fn default() -> Self {
+        Self {
+            endpoint_json_bytes: 1024 * 1024,
+            image_bytes: 5 * 1024 * 1024,
+        }
+    }
+}
+
+impl ResponseLimits {
+    /// Load from `ENDPOINT_MAX_RESPONSE_BYTES` and `IMAGE_MAX_BYTES`
+    pub fn from_env() -> anyhow::Result<Self> {
+        let defaults = Self::default();
+        Ok(Self {
+            endpoint_json_bytes: match env::var("ENDPOINT_MAX_RESPONSE_BYTES") {
+                Ok(value) => value.parse()?,
+                Err(_) => defaults.endpoint_json_bytes,
+            },
+            image_bytes: match env::var("IMAGE_MAX_BYTES") {
+                Ok(value) => value.parse()?,
+                Err(_) => defaults.image_bytes,
+            },
+        })
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BodyError {
+    #[error("response exceeds {0} bytes")]
+    TooLarge(usize),
+    #[error("failed to read response body: {0}")]
+    Read(String),
+}
+
+/// Read a response body, giving up as soon as it exceeds `max_bytes`
+///
+/// Unlike `Response::bytes`, this doesn't trust Content-Length alone: chunked
+/// responses are cut off while streaming.
+pub async fn read_body_limited(
+    mut response: reqwest::Response,
+    max_bytes: usize,
+) -> Result<Vec<u8>, BodyError> {
+    if response
+        .content_length()
+        .is_some_and(|len| len > max_bytes as u64)
+    {
+        return Err(BodyError::TooLarge(max_bytes));
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| BodyError::Read(e.to_string()))?
+    {
+        if body.len() + chunk.len() > max_bytes {
+            return Err(BodyError::TooLarge(max_bytes));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+/// Content-Type of a response when it isn't JSON (`application/json` or `*/*+json`)
+///
+/// A missing Content-Type is accepted.
+pub fn non_json_content_type(response: &reqwest::Response) -> Option<String> {
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)?
+        .to_str()
+        .unwrap_or_default();
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase();
+
+    if mime == "application/json" || mime.ends_with("+json") {
+        None
+    } else {
+        Some(content_type.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_read_body_limited() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_raw(vec![b'a'; 2048], "text/html; charset=utf-8"),
+            )
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let response = client.get(server.uri()).send().await.unwrap();
+        assert_eq!(
+            non_json_content_type(&response).as_deref(),
+            Some("text/html; charset=utf-8")
+        );
+        assert!(matches!(
+            read_body_limited(response, 1024).await,
+            Err(BodyError::TooLarge(1024))
+        ));
+
+        let response = client.get(server.uri()).send().await.unwrap();
+        assert_eq!(read_body_limited(response, 4096).await.unwrap().len(), 2048);
+    }
+
+    #[tokio::test]
+    async fn test_clients_per_purpose() {
+        let vars = |name: &str| match name {
+            "HTTP_METADATA_TIMEOUT_SECS" | "HTTP_PROBE_TIMEOUT_SECS" => Some("1".to_string()),
+            "HTTP_WEBHOOK_TIMEOUT_SECS" => Some("1".to_string()),
+            "HTTP_ASSETS_TIMEOUT_SECS" | "HTTP_UPLOAD_TIMEOUT_SECS" => Some("5".to_string()),
+            _ => None,
+        };
+        let timeouts = HttpTimeouts::from_vars(&vars).unwrap();
+        assert_eq!((timeouts.metadata_secs, timeouts.upload_secs), (1, 5));
+        let guard = OutboundGuard {
+            enabled: false,
+            ..Default::default()
+        };
+        let proxies = ProxyConfig::default();
+        let clients = HttpClients::new(&timeouts, guard, "watchy-test/1.0", &proxies).unwrap();
+        let probes = ProbeClients::new(&timeouts, &clients.guard, "watchy-test/1.0", &proxies)
+            .unwrap();
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(2_500)))
+            .mount(&server)
+            .await;
+        let purposes = [
+            ("metadata", &clients.metadata, true),
+            ("assets", &clients.assets, false),
+            ("upload", &clients.upload, false),
+            ("webhook", &clients.webhook, true),
+            ("subscriber", &clients.subscriber, true),
+            ("probe", &probes.probe, true),
+            ("probe_no_redirect", &probes.probe_no_redirect, true),
+        ];
+        let requests = purposes.iter().map(|(purpose, client, _)| {
+            client.get(format!("{}/{}", server.uri(), purpose)).send()
+        });
+        let responses = futures_util::future::join_all(requests).await;
+        for ((purpose, _, times_out), response) in purposes.iter().zip(responses) {
+            match response {
+                Ok(_) => assert!(!times_out, "{} should time out", purpose),
+                Err(e) => assert!(*times_out && e.is_timeout(), "{}: {}", purpose, e),
+            }
+        }
+
+        // Clients fetching agent content identify themselves; webhooks and uploads don't
+        for request in server.received_requests().await.unwrap() {
+            let user_agent = request.headers.get("user-agent").map(|ua| ua.to_str().unwrap());
+            match request.url.path() {
+                "/upload" | "/webhook" | "/subscriber" => assert_eq!(user_agent, None),
+                path => assert_eq!(user_agent, Some("watchy-test/1.0"), "{}", path),
+            }
+        }
+    }
+
+    /// TLS versions a ClientHello offers (its `supported_versions` extension)
+    fn offered_tls_versions(hello: &[u8]) -> Vec<u16> {
+        let u16_at = |i: usize| u16::from_be_bytes([hello[i], hello[i + 1]]);
+        // Record and handshake headers, client version, random
+        let mut i = 5 + 4 + 2 + 32;
+        i += 1 + hello[i] as usize; // session ID
+        i += 2 + u16_at(i) as usize; // cipher suites
+        i += 1 + hello[i] as usize; // compression methods
+        let end = i + 2 + u16_at(i) as usize;
+        i += 2;
+        while i < end {
+            let (kind, len) = (u16_at(i), u16_at(i + 2) as usize);
+            if kind == 0x002b {
+                let versions = &hello[i + 5..i + 4 + len];
+                return versions.chunks(2).map(|v| u16::from_be_bytes([v[0], v[1]])).collect();
+            }
+            i += 4 + len;
+        }
+        Vec::new()
+    }
+
+    /// A server that takes connections and never answers the TLS handshake,
+    /// passing on the ClientHello of each
+    async fn silent_tls_server() -> (String, tokio::sync::mpsc::UnboundedReceiver<Vec<u8>>) {
+        use tokio::io::AsyncReadExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("https://{}/", listener.local_addr().unwrap());
+        let (hellos, received) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let mut open = Vec::new();
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut hello = vec![0; 4096];
+                let len = socket.read(&mut hello).await.unwrap_or(0);
+                hello.truncate(len);
+                let _ = hellos.send(hello);
+                open.push(socket);
+            }
+        });
+        (url, received)
+    }
+
+    #[tokio::test]
+    async fn test_connect_timeout_and_tls_minimum() {
+        let vars = |name: &str| (name == "HTTP_CONNECT_TIMEOUT_SECS").then(|| "1".to_string());
+        let timeouts = HttpTimeouts::from_vars(&vars).unwrap();
+        assert_eq!((timeouts.connect_secs, timeouts.upload_secs), (1, 120));
+        let guard = OutboundGuard {
+            enabled: false,
+            ..Default::default()
+        };
+        let proxies = ProxyConfig::default();
+        let clients = HttpClients::new(&timeouts, guard, "watchy", &proxies).unwrap();
+        let (url, mut hellos) = silent_tls_server().await;
+
+        for (purpose, client, operator) in [
+            ("metadata", &clients.metadata, false),
+            ("upload", &clients.upload, true),
+            ("webhook", &clients.webhook, true),
+        ] {
+            // A stalled handshake counts against the connect timeout, well
+            // before the request timeout
+            let started = std::time::Instant::now();
+            let error = client.get(&url).send().await.unwrap_err();
+            assert!(error.is_connect() || error.is_timeout(), "{}: {}", purpose, error);
+            assert!(started.elapsed() < Duration::from_secs(4), "{}", purpose);
+
+            let offered = offered_tls_versions(&hellos.recv().await.unwrap());
+            assert!(offered.contains(&0x0303), "{} offers {:x?}", purpose, offered);
+            // Operator-configured targets never get TLS 1.0 or 1.1 offered; for
+            // agent-facing clients that's left to the system's OpenSSL policy
+            if operator {
+                assert!(offered.iter().all(|&version| version >= 0x0303), "{}", purpose);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rotating_proxy() {
+        let proxies = [MockServer::start().await, MockServer::start().await];
+        for proxy in &proxies {
+            Mock::given(method("GET"))
+                .respond_with(ResponseTemplate::new(200))
+                .expect(2)
+                .mount(proxy)
+                .await;
+        }
+
+        let config = ProxyConfig {
+            default: Some(Url::parse("http://egress.internal:3128").unwrap()),
+            probe: proxies.iter().map(|p| Url::parse(&p.uri()).unwrap()).collect(),
+            rpc: None,
+        };
+        assert_eq!(config.rpc_proxy(), config.default.as_ref());
+        assert_eq!(config.probe_hosts(), vec!["127.0.0.1", "127.0.0.1"]);
+
+        let proxy = ClientProxy::new(config.probe_proxies()).unwrap();
+        let client = build_client(reqwest::Client::builder(), &proxy).unwrap();
+        for _ in 0..4 {
+            let response = client.get("http://agent.example/").send().await.unwrap();
+            assert!(response.status().is_success());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_proxy_host_exempt_only_at_connect() {
+        let proxy = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&proxy)
+            .await;
+        let proxy_url = Url::parse(&format!("http://localhost:{}", proxy.address().port())).unwrap();
+        let config = ProxyConfig {
+            default: None,
+            probe: vec![proxy_url.clone()],
+            rpc: None,
+        };
+
+        let guard = Arc::new(OutboundGuard::default());
+        let resolver = GuardedResolver::new(guard.clone()).with_proxy_hosts(config.probe_hosts());
+        let client = build_client(
+            reqwest::Client::builder().dns_resolver(Arc::new(resolver)),
+            &ClientProxy::new(config.probe_proxies()).unwrap(),
+        )
+        .unwrap();
+
+        // Watchy reaches its own proxy on loopback...
+        let response = client.get("http://agent.example/").send().await.unwrap();
+        assert!(response.status().is_success());
+        // ...but an agent URL naming the proxy is still refused
+        assert!(guard.check(proxy_url.as_str()).await.is_err());
+    }
+}
@@ -0,0 +1,426 @@
+//! Registry event indexer
+//!
+//! Periodically scans the identity registry on the configured chains for
+//! `Registered`, `URIUpdated` and `Transfer` events and keeps a table of every
+//! agent (id, current owner, URI, registration block) in the store, served at
+//! `GET /agents`. Each
+//! chain resumes from its last indexed block. With auto-audit on, agents
+//! registered after the last indexed block are audited as they appear, so
+//! registrations made while Watchy was down are caught up on restart; the
+//! first backfill of a chain never triggers audits. With `reaudit_secs` set,
+//! audited agents are audited again once their latest audit is that old.
+//! Audits are queued and at most `max_auto_audits` start per scan; the
+//! circuit breaker (see `breaker`) holds back agents that keep failing.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use crate::api::handlers::spawn_audit;
+use crate::blockchain::registry::RegistryClient;
+use crate::chains::{get_all_rpcs, get_chain, registry_start_block};
+use crate::pause::Subsystem;
+use crate::store::{AuditParams, AuditStore};
+use crate::types::WatchyError;
+use crate::AppState;
+
+/// Background indexer settings
+#[derive(Debug, Clone)]
+pub struct IndexerConfig {
+    /// Seconds between scans (0 disables the indexer)
+    pub interval_secs: u64,
+    pub chains: Vec<u64>,
+    /// Blocks per `eth_getLogs` query
+    pub max_block_range: u64,
+    /// Audit agents registered while the indexer runs
+    pub auto_audit: bool,
+    /// Auto-audits started per scan; the rest wait for the next one
+    pub max_auto_audits: usize,
+    /// Re-audit agents whose latest audit is this old (0 = never)
+    pub reaudit_secs: u64,
+}
+
+/// Auto-audits waiting for a later scan before the oldest are dropped
+const MAX_QUEUED_AUTO_AUDITS: usize = 1000;
+
+impl IndexerConfig {
+    /// Load from `INDEXER_INTERVAL_SECS`, `INDEXER_CHAINS` (comma-separated chain
+    /// IDs, default: the default chain), `INDEXER_MAX_BLOCK_RANGE`,
+    /// `AUTO_AUDIT_NEW_AGENTS`, `INDEXER_MAX_AUTO_AUDITS` and `AUTO_REAUDIT_SECS`
+    pub fn from_env(default_chain_id: u64) -> anyhow::Result<Self> {
+        let chains = match env::var("INDEXER_CHAINS") {
+            Ok(value) => value
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(|s| {
+                    s.parse()
+                        .map_err(|_| anyhow::anyhow!("Invalid chain ID '{}' in INDEXER_CHAINS", s))
+                })
+                .collect::<anyhow::Result<Vec<u64>>>()?,
+            Err(_) => vec![default_chain_id],
+        };
+        let max_block_range = env::var("INDEXER_MAX_BLOCK_RANGE")
+            .unwrap_or_else(|_| "10000".to_string())
+            .parse()?;
+        if max_block_range == 0 {
+            anyhow::bail!("INDEXER_MAX_BLOCK_RANGE must be greater than 0");
+        }
+        let max_auto_audits = env::var("INDEXER_MAX_AUTO_AUDITS")
+            .unwrap_or_else(|_| "5".to_string())
+            .parse()?;
+        if max_auto_audits == 0 {
+            anyhow::bail!("INDEXER_MAX_AUTO_AUDITS must be greater than 0");
+        }
+
+        Ok(Self {
+            interval_secs: env::var("INDEXER_INTERVAL_SECS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()?,
+            chains,
+            max_block_range,
+            auto_audit: env::var("AUTO_AUDIT_NEW_AGENTS")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            max_auto_audits,
+            reaudit_secs: env::var("AUTO_REAUDIT_SECS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()?,
+        })
+    }
+}
+
+/// An agent as recorded by the registry's events
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegisteredAgent {
+    pub chain_id: u64,
+    pub agent_id: u64,
+    /// Current owner: the registering address, updated on transfers
+    pub owner: String,
+    /// Latest agent URI
+    pub uri: String,
+    pub registered_block: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tx_hash: Option<String>,
+    /// Block of the latest URI update, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uri_updated_block: Option<u64>,
+}
+
+/// A registry event, in the order it was emitted
+#[derive(Debug, Clone, PartialEq)]
+pub enum RegistryChange {
+    Registered(RegisteredAgent),
+    UriUpdated {
+        agent_id: u64,
+        uri: String,
+        block: u64,
+    },
+    Transferred {
+        agent_id: u64,
+        owner: String,
+        block: u64,
+    },
+}
+
+impl RegistryChange {
+    fn block(&self) -> u64 {
+        match self {
+            RegistryChange::Registered(agent) => agent.registered_block,
+            RegistryChange::UriUpdated { block, .. } => *block,
+            RegistryChange::Transferred { block, .. } => *block,
+        }
+    }
+}
+
+/// Start the periodic indexer (no-op if the interval is 0)
+pub fn spawn_indexer(state: Arc<AppState>) {
+    let config = state.config.indexer.clone();
+    if config.interval_secs == 0 {
+        info!("Registry indexer disabled");
+        return;
+    }
+
+    info!(
+        "Registry indexer every {}s on chains {:?} (auto-audit: {})",
+        config.interval_secs, config.chains, config.auto_audit
+    );
+
+    tokio::spawn(async move {
+        // Block per chain after which registrations are new (fixed on the first scan)
+        let mut live_from: HashMap<u64, u64> = HashMap::new();
+        let mut queued: VecDeque<(u64, u64)> = VecDeque::new();
+        let mut interval = tokio::time::interval(Duration::from_secs(config.interval_secs));
+        loop {
+            interval.tick().await;
+            if state.pauses.is_paused(Subsystem::Indexer) {
+                continue;
+            }
+            for &chain_id in &config.chains {
+                match index_chain(&state, chain_id, &mut live_from).await {
+                    Ok(new_agents) => {
+                        if !new_agents.is_empty() {
+                            info!(
+                                "Indexed {} new agents on chain {}",
+                                new_agents.len(),
+                                chain_id
+                            );
+                        }
+                        if config.auto_audit {
+                            queued.extend(new_agents.into_iter().map(|agent_id| (chain_id, agent_id)));
+                        }
+                    }
+                    Err(e) => warn!("Indexing chain {} failed: {}", chain_id, e),
+                }
+            }
+            // Re-audits of agents whose latest audit is older than the interval
+            if config.reaudit_secs > 0 {
+                let now = chrono::Utc::now().timestamp() as u64;
+                for agent in state.breaker.due(config.reaudit_secs, now) {
+                    if config.chains.contains(&agent.0) && !queued.contains(&agent) {
+                        queued.push_back(agent);
+                    }
+                }
+            }
+            if queued.len() > MAX_QUEUED_AUTO_AUDITS {
+                let dropped = queued.len() - MAX_QUEUED_AUTO_AUDITS;
+                warn!("Auto-audit queue full, dropping {} oldest agents", dropped);
+                queued.drain(..dropped);
+            }
+            start_auto_audits(&state, &mut queued, config.max_auto_audits).await;
+        }
+    });
+}
+
+/// Start up to `limit` queued auto-audits, skipping agents the breaker holds back
+async fn start_auto_audits(state: &Arc<AppState>, queued: &mut VecDeque<(u64, u64)>, limit: usize) {
+    let mut started = 0;
+    while started < limit {
+        let Some((chain_id, agent_id)) = queued.pop_front() else {
+            break;
+        };
+        let now = chrono::Utc::now().timestamp() as u64;
+        if !state.breaker.allows_automatic_audit(chain_id, agent_id, now) {
+            info!("Agent {} unresponsive, auto-audit backed off", agent_id);
+            continue;
+        }
+        let params = AuditParams {
+            timeout_secs: state.config.audit_timeout(None),
+            lang: state.config.report_lang,
+            requester: None,
+            batch_id: None,
+            metadata_uri: None,
+        };
+        let audit_id = spawn_audit(state, Some(agent_id), chain_id, params, None, None).await;
+        info!("Auto-audit {} started for agent {}", audit_id, agent_id);
+        started += 1;
+    }
+    if !queued.is_empty() {
+        debug!("{} auto-audits wait for the next scan", queued.len());
+    }
+}
+
+/// Bring one chain up to date, with RPC failover
+///
+/// Returns agents registered after `live_from` for the chain.
+pub async fn index_chain(
+    state: &AppState,
+    chain_id: u64,
+    live_from: &mut HashMap<u64, u64>,
+) -> Result<Vec<u64>, WatchyError> {
+    let registry_address = get_chain(chain_id)
+        .and_then(|chain| chain.registry_address)
+        .ok_or_else(|| {
+            WatchyError::InvalidRequest(format!("No identity registry on chain {}", chain_id))
+        })?;
+
+    let mut last_error = format!("No RPC URLs available for chain {}", chain_id);
+    for rpc_url in get_all_rpcs(chain_id) {
+        let result = match RegistryClient::new(&rpc_url, registry_address) {
+            Ok(registry) => try_index_chain(state, &registry, chain_id, live_from).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(new_agents) => return Ok(new_agents),
+            Err(e) => {
+                warn!("Indexing via {} failed: {}", rpc_url, e);
+                last_error = e.to_string();
+            }
+        }
+    }
+    Err(WatchyError::BlockchainError(last_error))
+}
+
+async fn try_index_chain(
+    state: &AppState,
+    registry: &RegistryClient,
+    chain_id: u64,
+    live_from: &mut HashMap<u64, u64>,
+) -> Result<Vec<u64>, WatchyError> {
+    let store = &state.audit_store;
+    let latest = registry.block_number().await?;
+    let cursor = store.indexer_cursor(chain_id).await;
+    // Resuming: everything past the cursor is new, including registrations made
+    // while Watchy was down. A first backfill audits nothing.
+    let live_from = *live_from.entry(chain_id).or_insert(cursor.unwrap_or(latest));
+    let mut from = match cursor {
+        Some(cursor) => cursor + 1,
+        None => registry_start_block(chain_id),
+    };
+
+    let mut new_agents = Vec::new();
+    while from <= latest {
+        let to = latest.min(from + state.config.indexer.max_block_range - 1);
+        debug!("Indexing chain {} blocks {}-{}", chain_id, from, to);
+        let events = registry.registry_events(from, to).await?;
+
+        let mut changes: Vec<((u64, u64), RegistryChange)> = Vec::new();
+        for (log, event) in events.registered {
+            let Ok(agent_id) = u64::try_from(event.agentId) else {
+                continue;
+            };
+            let agent = RegisteredAgent {
+                chain_id,
+                agent_id,
+                owner: format!("{:?}", event.owner),
+                uri: event.agentURI,
+                registered_block: log.block_number.unwrap_or(from),
+                tx_hash: log.transaction_hash.map(|h| format!("0x{}", hex::encode(h))),
+                uri_updated_block: None,
+            };
+            let position = (agent.registered_block, log.log_index.unwrap_or_default());
+            changes.push((position, RegistryChange::Registered(agent)));
+        }
+        for (log, event) in events.updates {
+            let Ok(agent_id) = u64::try_from(event.agentId) else {
+                continue;
+            };
+            let block = log.block_number.unwrap_or(from);
+            let change = RegistryChange::UriUpdated {
+                agent_id,
+                uri: event.newURI,
+                block,
+            };
+            changes.push(((block, log.log_index.unwrap_or_default()), change));
+        }
+        for (log, event) in events.transfers {
+            let Ok(agent_id) = u64::try_from(event.tokenId) else {
+                continue;
+            };
+            let block = log.block_number.unwrap_or(from);
+            let change = RegistryChange::Transferred {
+                agent_id,
+                owner: format!("{:?}", event.to),
+                block,
+            };
+            changes.push(((block, log.log_index.unwrap_or_default()), change));
+        }
+        changes.sort_by_key(|(position, _)| *position);
+
+        let changes: Vec<RegistryChange> = changes.into_iter().map(|(_, change)| change).collect();
+        new_agents.extend(apply_changes(store, chain_id, changes, live_from).await);
+        store.set_indexer_cursor(chain_id, to).await;
+        from = to + 1;
+    }
+    Ok(new_agents)
+}
+
+/// Record changes in the store; returns agents registered after `live_from`
+pub async fn apply_changes(
+    store: &AuditStore,
+    chain_id: u64,
+    changes: Vec<RegistryChange>,
+    live_from: u64,
+) -> Vec<u64> {
+    let mut new_agents = Vec::new();
+    for change in changes {
+        let block = change.block();
+        match change {
+            RegistryChange::Registered(agent) => {
+                if block > live_from {
+                    new_agents.push(agent.agent_id);
+                }
+                store.put_registered_agent(&agent).await;
+            }
+            RegistryChange::UriUpdated { agent_id, uri, block } => {
+                // Agents registered before the start block aren't in the table
+                let Some(mut agent) = store.registered_agent(chain_id, agent_id).await else {
+                    debug!("URI update for unindexed agent {} on chain {}", agent_id, chain_id);
+                    continue;
+                };
+                agent.uri = uri;
+                agent.uri_updated_block = Some(block);
+                store.put_registered_agent(&agent).await;
+            }
+            RegistryChange::Transferred { agent_id, owner, .. } => {
+                let Some(mut agent) = store.registered_agent(chain_id, agent_id).await else {
+                    debug!("Transfer of unindexed agent {} on chain {}", agent_id, chain_id);
+                    continue;
+                };
+                agent.owner = owner;
+                store.put_registered_agent(&agent).await;
+            }
+        }
+    }
+    new_agents
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registered(agent_id: u64, block: u64) -> RegistryChange {
+        RegistryChange::Registered(RegisteredAgent {
+            chain_id: 8453,
+            agent_id,
+            owner: "0x0000000000000000000000000000000000000001".to_string(),
+            uri: format!("ipfs://agent-{}", agent_id),
+            registered_block: block,
+            tx_hash: None,
+            uri_updated_block: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_apply_changes() {
+        let store = AuditStore::in_memory();
+        let changes = vec![
+            registered(1, 100),
+            RegistryChange::UriUpdated {
+                agent_id: 1,
+                uri: "ar://updated".to_string(),
+                block: 150,
+            },
+            registered(2, 200),
+            RegistryChange::Transferred {
+                agent_id: 2,
+                owner: "0x0000000000000000000000000000000000000002".to_string(),
+                block: 205,
+            },
+            RegistryChange::UriUpdated {
+                agent_id: 9,
+                uri: "ar://unknown".to_string(),
+                block: 210,
+            },
+        ];
+
+        let new_agents = apply_changes(&store, 8453, changes, 150).await;
+        assert_eq!(new_agents, vec![2]);
+
+        let agent = store.registered_agent(8453, 1).await.unwrap();
+        assert_eq!(agent.uri, "ar://updated");
+        assert_eq!(agent.uri_updated_block, Some(150));
+        assert!(store.registered_agent(8453, 9).await.is_none());
+        assert_eq!(store.registered_agents(8453).await.len(), 2);
+
+        // The owner index follows the transfer
+        let owner = "0x0000000000000000000000000000000000000002";
+        let owned = store.owned_agents(8453, owner).await;
+        assert_eq!(owned.iter().map(|a| a.agent_id).collect::<Vec<_>>(), vec![2]);
+        let first = store.owned_agents(8453, "0x0000000000000000000000000000000000000001").await;
+        assert_eq!(first.iter().map(|a| a.agent_id).collect::<Vec<_>>(), vec![1]);
+    }
+}
@@ -0,0 +1,336 @@
+//! Background integrity checks for published reports
+//!
+//! Periodically samples completed audits whose JSON report was published,
+//! re-fetches it through the configured gateway and checks that:
+//! 1. It's still retrievable (unpinned or lost data)
+//! 2. Its keccak256 matches the recorded feedbackHash (gateway tampering)
+//! 3. The embedded signature recovers to the auditor address
+//!
+//! Failures are logged as alerts (and posted to `REPORT_INTEGRITY_ALERT_WEBHOOK`
+//! if set). Running totals are served at `GET /admin/integrity`.
+
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+use crate::arweave::irys::verify_report_signature;
+use crate::blockchain::reputation::compute_feedback_hash;
+use crate::pause::Subsystem;
+use crate::types::{AuditReport, AuditStatus};
+use crate::AppState;
+
+/// Number of recent failures kept for the admin endpoint
+const MAX_RECENT_FAILURES: usize = 50;
+
+/// Result of re-checking one published report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrityOutcome {
+    Verified,
+    /// Gateway didn't serve the report
+    Unavailable,
+    /// Content doesn't hash to the recorded feedbackHash
+    HashMismatch,
+    /// Signature missing or not from the auditor
+    InvalidSignature,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityFailure {
+    pub audit_id: String,
+    pub agent_id: u64,
+    pub url: String,
+    pub outcome: IntegrityOutcome,
+    pub detail: String,
+    pub checked_at: u64,
+}
+
+/// Running totals since startup
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityStats {
+    pub runs: u64,
+    pub checked: u64,
+    pub verified: u64,
+    pub unavailable: u64,
+    pub hash_mismatch: u64,
+    pub invalid_signature: u64,
+    pub last_run_at: Option<u64>,
+    /// Most recent failures, newest last
+    pub recent_failures: Vec<IntegrityFailure>,
+}
+
+impl IntegrityStats {
+    fn record(&mut self, outcome: IntegrityOutcome) {
+        self.checked += 1;
+        match outcome {
+            IntegrityOutcome::Verified => self.verified += 1,
+            IntegrityOutcome::Unavailable => self.unavailable += 1,
+            IntegrityOutcome::HashMismatch => self.hash_mismatch += 1,
+            IntegrityOutcome::InvalidSignature => self.invalid_signature += 1,
+        }
+    }
+
+    fn push_failure(&mut self, failure: IntegrityFailure) {
+        self.recent_failures.push(failure);
+        if self.recent_failures.len() > MAX_RECENT_FAILURES {
+            let excess = self.recent_failures.len() - MAX_RECENT_FAILURES;
+            self.recent_failures.drain(..excess);
+        }
+    }
+}
+
+/// Start the periodic integrity worker (no-op if the interval is 0)
+pub fn spawn_integrity_worker(state: Arc<AppState>) {
+    let interval_secs = state.config.integrity_check_interval_secs;
+    if interval_secs == 0 {
+        info!("Report integrity checks disabled");
+        return;
+    }
+
+    info!(
+        "Report integrity checks every {}s (sample size {})",
+        interval_secs, state.config.integrity_sample_size
+    );
+
+    tokio::spawn(async move {
+        let period = Duration::from_secs(interval_secs);
+        let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+        loop {
+            interval.tick().await;
+            if state.pauses.is_paused(Subsystem::Integrity) {
+                continue;
+            }
+            run_integrity_check(&state).await;
+        }
+    });
+}
+
+/// Check a random sample of published reports and update the stats
+pub async fn run_integrity_check(state: &AppState) {
+    let candidates: Vec<(String, AuditReport)> = state
+        .audit_store
+        .list_jobs()
+        .await
+        .into_iter()
+        .filter(|job| job.status == AuditStatus::Completed)
+        .filter_map(|job| job.result.map(|report| (job.id, report)))
+        .filter(|(_, report)| report.report_json_url.is_some() && report.report_json_hash.is_some())
+        .collect();
+
+    let sample = sample(candidates, state.config.integrity_sample_size);
+    debug!("Integrity check sampling {} published reports", sample.len());
+
+    let mut results = Vec::with_capacity(sample.len());
+    for (audit_id, report) in sample {
+        let url = report.report_json_url.clone().unwrap_or_default();
+        let (outcome, detail) = check_published_report(&state.http.metadata, &url, &report).await;
+        results.push((audit_id, report.agent_id, url, outcome, detail));
+    }
+
+    let now = chrono::Utc::now().timestamp() as u64;
+    let mut failures = Vec::new();
+    {
+        let mut stats = state.integrity.write().await;
+        stats.runs += 1;
+        stats.last_run_at = Some(now);
+
+        for (audit_id, agent_id, url, outcome, detail) in results {
+            stats.record(outcome);
+            if outcome == IntegrityOutcome::Verified {
+                continue;
+            }
+            let failure = IntegrityFailure {
+                audit_id,
+                agent_id,
+                url,
+                outcome,
+                detail,
+                checked_at: now,
+            };
+            stats.push_failure(failure.clone());
+            failures.push(failure);
+        }
+    }
+
+    for failure in &failures {
+        alert(state, failure).await;
+    }
+}
+
+/// Re-fetch a report from its gateway URL and verify it
+async fn check_published_report(
+    client: &reqwest::Client,
+    url: &str,
+    report: &AuditReport,
+) -> (IntegrityOutcome, String) {
+    let response = match client.get(url).send().await {
+        Ok(response) => response,
+        Err(e) => return (IntegrityOutcome::Unavailable, format!("Request failed: {}", e)),
+    };
+
+    if !response.status().is_success() {
+        return (
+            IntegrityOutcome::Unavailable,
+            format!("Gateway returned {}", response.status()),
+        );
+    }
+
+    let body = match response.bytes().await {
+        Ok(body) => body,
+        Err(e) => return (IntegrityOutcome::Unavailable, format!("Failed to read body: {}", e)),
+    };
+
+    verify_report_content(
+        &body,
+        report.report_json_hash.as_deref().unwrap_or_default(),
+        report.auditor.address.as_deref(),
+    )
+}
+
+/// Verify fetched report bytes against the recorded hash and auditor address
+///
+/// The hash is taken over the canonical re-serialization, so gateway formatting
+/// (e.g. pretty-printed uploads) doesn't matter. The signature check is skipped
+/// if the report has no auditor address.
+fn verify_report_content(
+    body: &[u8],
+    expected_hash: &str,
+    auditor_address: Option<&str>,
+) -> (IntegrityOutcome, String) {
+    let mut report_json: serde_json::Value = match serde_json::from_slice(body) {
+        Ok(json) => json,
+        Err(e) => {
+            return (
+                IntegrityOutcome::HashMismatch,
+                format!("Content is not valid JSON: {}", e),
+            )
+        }
+    };
+
+    let actual_hash = match compute_feedback_hash(&report_json) {
+        Ok(hash) => format!("0x{}", hex::encode(hash)),
+        Err(e) => return (IntegrityOutcome::HashMismatch, e.to_string()),
+    };
+    if !actual_hash.eq_ignore_ascii_case(expected_hash) {
+        return (
+            IntegrityOutcome::HashMismatch,
+            format!("Expected {}, got {}", expected_hash, actual_hash),
+        );
+    }
+
+    let Some(address) = auditor_address else {
+        return (IntegrityOutcome::Verified, String::new());
+    };
+
+    // The signature covers the report without its own field
+    let signature = report_json
+        .as_object_mut()
+        .and_then(|obj| obj.remove("signature"))
+        .and_then(|sig| sig.as_str().map(|s| s.to_string()));
+    let Some(signature) = signature else {
+        return (
+            IntegrityOutcome::InvalidSignature,
+            "Report has no signature".to_string(),
+        );
+    };
+
+    match verify_report_signature(&report_json, &signature, address) {
+        Ok(true) => (IntegrityOutcome::Verified, String::new()),
+        Ok(false) => (
+            IntegrityOutcome::InvalidSignature,
+            format!("Signature doesn't recover to {}", address),
+        ),
+        Err(e) => (IntegrityOutcome::InvalidSignature, e.to_string()),
+    }
+}
+
+/// Log a failed check and forward it to the alert webhook, if configured
+async fn alert(state: &AppState, failure: &IntegrityFailure) {
+    error!(
+        alert = "report_integrity",
+        "Integrity check failed for audit {} ({:?}): {} - {}",
+        failure.audit_id,
+        failure.outcome,
+        failure.url,
+        failure.detail
+    );
+
+    let Some(webhook) = &state.config.integrity_alert_webhook else {
+        return;
+    };
+
+    match state.http.webhook.post(webhook).json(failure).send().await {
+        Ok(response) if !response.status().is_success() => {
+            warn!("Integrity alert webhook returned {}", response.status());
+        }
+        Ok(_) => {}
+        Err(e) => {
+            warn!("Failed to post integrity alert: {}", e);
+        }
+    }
+}
+
+/// Pick up to `n` items uniformly at random (partial Fisher-Yates)
+fn sample<T>(mut items: Vec<T>, n: usize) -> Vec<T> {
+    let n = n.min(items.len());
+    for i in 0..n {
+        let j = i + (uuid::Uuid::new_v4().as_u128() % (items.len() - i) as u128) as usize;
+        items.swap(i, j);
+    }
+    items.truncate(n);
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arweave::irys::sign_report;
+
+    // Well-known test key (anvil account 0)
+    const TEST_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const TEST_ADDRESS: &str = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+
+    async fn published_report() -> (Vec<u8>, String) {
+        let mut json = serde_json::json!({ "agentId": 17, "scores": { "overall": 85 } });
+        let signature = sign_report(&json, TEST_KEY).await.unwrap();
+        json.as_object_mut()
+            .unwrap()
+            .insert("signature".to_string(), serde_json::json!(signature));
+
+        let hash = format!("0x{}", hex::encode(compute_feedback_hash(&json).unwrap()));
+        // Gateways serve the pretty-printed upload
+        (serde_json::to_vec_pretty(&json).unwrap(), hash)
+    }
+
+    #[tokio::test]
+    async fn test_verify_report_content() {
+        let (body, hash) = published_report().await;
+
+        let (outcome, _) = verify_report_content(&body, &hash, Some(TEST_ADDRESS));
+        assert_eq!(outcome, IntegrityOutcome::Verified);
+
+        let tampered = String::from_utf8(body.clone()).unwrap().replace("85", "95");
+        let (outcome, _) = verify_report_content(tampered.as_bytes(), &hash, Some(TEST_ADDRESS));
+        assert_eq!(outcome, IntegrityOutcome::HashMismatch);
+
+        let other = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+        let (outcome, _) = verify_report_content(&body, &hash, Some(other));
+        assert_eq!(outcome, IntegrityOutcome::InvalidSignature);
+    }
+
+    #[test]
+    fn test_sample() {
+        let picked = sample((0..20).collect::<Vec<_>>(), 5);
+        assert_eq!(picked.len(), 5);
+        let mut unique = picked.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), 5);
+
+        assert_eq!(sample(vec![1, 2], 10).len(), 2);
+        assert!(sample(Vec::<u8>::new(), 3).is_empty());
+    }
+}
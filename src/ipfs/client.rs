@@ -9,13 +9,17 @@ use crate::types::WatchyError;
 /// - Pinata (https://api.pinata.cloud)
 /// - Infura (https://ipfs.infura.io)
 /// - Local node (http://localhost:5001)
-
 pub struct IpfsClient {
     http_client: reqwest::Client,
     api_url: String,
     api_key: Option<String>,
+    /// Gateway base URL for generated links
+    gateway: String,
 }
 
+/// Gateway used for generated links unless overridden
+pub const DEFAULT_IPFS_GATEWAY: &str = "https://ipfs.io";
+
 #[derive(Debug, Serialize)]
 #[allow(non_snake_case)]
 struct PinataUpload {
@@ -40,9 +44,22 @@ impl IpfsClient {
             http_client: reqwest::Client::new(),
             api_url,
             api_key,
+            gateway: DEFAULT_IPFS_GATEWAY.to_string(),
         }
     }
 
+    /// Upload through a shared client instead of a private one
+    pub fn with_http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.http_client = http_client;
+        self
+    }
+
+    /// Generate links through a different gateway
+    pub fn with_gateway(mut self, gateway: &str) -> Self {
+        self.gateway = gateway.trim_end_matches('/').to_string();
+        self
+    }
+
     /// Upload JSON content to IPFS
     ///
     /// Returns the CID (Content Identifier) of the uploaded content
@@ -106,16 +123,99 @@ impl IpfsClient {
         Ok(pinata_response.ipfs_hash)
     }
 
+    /// Upload raw bytes to IPFS, preserving them exactly
+    ///
+    /// Unlike `upload_json`, the stored content is byte-for-byte what was passed in,
+    /// so hashes computed over `data` match what's served at the CID.
+    pub async fn upload_bytes(
+        &self,
+        data: Vec<u8>,
+        filename: &str,
+        content_type: &str,
+    ) -> Result<String, WatchyError> {
+        debug!("Uploading {} bytes to IPFS: {}", data.len(), filename);
+
+        if self.api_url.contains("pinata") {
+            self.upload_pinata_file(data, filename, content_type).await
+        } else {
+            self.upload_generic_bytes(data, filename, content_type).await
+        }
+    }
+
+    async fn upload_pinata_file(
+        &self,
+        data: Vec<u8>,
+        filename: &str,
+        content_type: &str,
+    ) -> Result<String, WatchyError> {
+        let api_key = self
+            .api_key
+            .as_ref()
+            .ok_or_else(|| WatchyError::IpfsError("Pinata API key required".to_string()))?;
+
+        let metadata = serde_json::to_string(&PinataMetadata {
+            name: filename.to_string(),
+        })
+        .map_err(|e| WatchyError::IpfsError(e.to_string()))?;
+
+        let form = reqwest::multipart::Form::new()
+            .part(
+                "file",
+                reqwest::multipart::Part::bytes(data)
+                    .file_name(filename.to_string())
+                    .mime_str(content_type)
+                    .map_err(|e| WatchyError::IpfsError(e.to_string()))?,
+            )
+            .text("pinataMetadata", metadata);
+
+        let response = self
+            .http_client
+            .post(format!("{}/pinning/pinFileToIPFS", self.api_url))
+            .header("Authorization", format!("Bearer {}", api_key))
+            .multipart(form)
+            .send()
+            .await
+            .map_err(|e| WatchyError::IpfsError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(WatchyError::IpfsError(format!(
+                "Pinata upload failed: {} - {}",
+                status, body
+            )));
+        }
+
+        let pinata_response: PinataResponse = response
+            .json()
+            .await
+            .map_err(|e| WatchyError::IpfsError(format!("Failed to parse Pinata response: {}", e)))?;
+
+        info!("Uploaded to IPFS: {}", pinata_response.ipfs_hash);
+
+        Ok(pinata_response.ipfs_hash)
+    }
+
     async fn upload_generic(&self, content: &serde_json::Value) -> Result<String, WatchyError> {
-        // Generic IPFS HTTP API (local node or other providers)
         let json_bytes = serde_json::to_vec(content)
             .map_err(|e| WatchyError::IpfsError(format!("JSON serialization failed: {}", e)))?;
 
+        self.upload_generic_bytes(json_bytes, "audit.json", "application/json")
+            .await
+    }
+
+    async fn upload_generic_bytes(
+        &self,
+        data: Vec<u8>,
+        filename: &str,
+        content_type: &str,
+    ) -> Result<String, WatchyError> {
+        // Generic IPFS HTTP API (local node or other providers)
         let form = reqwest::multipart::Form::new().part(
             "file",
-            reqwest::multipart::Part::bytes(json_bytes)
-                .file_name("audit.json")
-                .mime_str("application/json")
+            reqwest::multipart::Part::bytes(data)
+                .file_name(filename.to_string())
+                .mime_str(content_type)
                 .map_err(|e| WatchyError::IpfsError(e.to_string()))?,
         );
 
@@ -157,9 +257,7 @@ impl IpfsClient {
     }
 
     /// Get the gateway URL for a CID
-    #[allow(dead_code)]
     pub fn gateway_url(&self, cid: &str) -> String {
-        // Use public gateway or configured gateway
-        format!("https://ipfs.io/ipfs/{}", cid)
+        format!("{}/ipfs/{}", self.gateway, cid)
     }
 }
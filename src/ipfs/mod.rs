@@ -1,3 +1,5 @@
 pub mod client;
+pub mod pinning;
 
 pub use client::IpfsClient;
+pub use pinning::{PinState, PinningService};
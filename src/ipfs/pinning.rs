@@ -0,0 +1,244 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use crate::types::WatchyError;
+
+/// Upper bound for the delay between pin status polls
+const MAX_POLL_BACKOFF_MS: u64 = 30_000;
+
+/// Client for an IPFS Pinning Service API provider
+///
+/// Used to replicate CIDs to a second provider (e.g. web3.storage at
+/// https://api.web3.storage or Pinata at https://api.pinata.cloud/psa).
+/// Spec: https://ipfs.github.io/pinning-services-api-spec/
+pub struct PinningService {
+    http_client: reqwest::Client,
+    endpoint: String,
+    token: String,
+}
+
+/// Pin lifecycle as reported by the service
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PinState {
+    Queued,
+    Pinning,
+    Pinned,
+    Failed,
+}
+
+#[derive(Debug, Serialize)]
+struct PinRequest<'a> {
+    cid: &'a str,
+    name: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct PinStatusResponse {
+    requestid: String,
+    status: PinState,
+}
+
+impl PinningService {
+    pub fn new(endpoint: &str, token: &str) -> Self {
+        Self {
+            http_client: reqwest::Client::new(),
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            token: token.to_string(),
+        }
+    }
+
+    /// Pin through a shared client instead of a private one
+    pub fn with_http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.http_client = http_client;
+        self
+    }
+
+    /// Ask the service to pin a CID. Returns the request ID for status polling.
+    pub async fn pin(&self, cid: &str, name: &str) -> Result<String, WatchyError> {
+        let response = self
+            .http_client
+            .post(format!("{}/pins", self.endpoint))
+            .bearer_auth(&self.token)
+            .json(&PinRequest { cid, name })
+            .send()
+            .await
+            .map_err(|e| WatchyError::IpfsError(format!("Pin request failed: {}", e)))?;
+
+        let status = parse_status(response).await?;
+        debug!("Pin {} for {} is {:?}", status.requestid, cid, status.status);
+        Ok(status.requestid)
+    }
+
+    /// Current state of a pin request
+    pub async fn status(&self, request_id: &str) -> Result<PinState, WatchyError> {
+        let response = self
+            .http_client
+            .get(format!("{}/pins/{}", self.endpoint, request_id))
+            .bearer_auth(&self.token)
+            .send()
+            .await
+            .map_err(|e| WatchyError::IpfsError(format!("Pin status request failed: {}", e)))?;
+
+        Ok(parse_status(response).await?.status)
+    }
+
+    /// Pin a CID and poll until it's pinned, failed, or `attempts` polls have passed
+    ///
+    /// Returns the last observed state.
+    pub async fn pin_and_wait(
+        &self,
+        cid: &str,
+        name: &str,
+        attempts: u32,
+    ) -> Result<PinState, WatchyError> {
+        let request_id = self.pin(cid, name).await?;
+
+        let mut state = PinState::Queued;
+        for attempt in 1..=attempts {
+            tokio::time::sleep(poll_backoff(attempt)).await;
+
+            state = match self.status(&request_id).await {
+                Ok(state) => state,
+                Err(e) => {
+                    warn!("Pin status poll {}/{} for {} failed: {}", attempt, attempts, cid, e);
+                    continue;
+                }
+            };
+
+            match state {
+                PinState::Pinned => {
+                    info!("CID {} pinned at {}", cid, self.endpoint);
+                    return Ok(state);
+                }
+                PinState::Failed => {
+                    return Err(WatchyError::IpfsError(format!(
+                        "Pinning {} at {} failed",
+                        cid, self.endpoint
+                    )));
+                }
+                PinState::Queued | PinState::Pinning => {}
+            }
+        }
+
+        Ok(state)
+    }
+}
+
+async fn parse_status(response: reqwest::Response) -> Result<PinStatusResponse, WatchyError> {
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(WatchyError::IpfsError(format!(
+            "Pinning service error: {} - {}",
+            status, body
+        )));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| WatchyError::IpfsError(format!("Failed to parse pin status: {}", e)))
+}
+
+/// Delay before the given poll (1-based), doubling from 1s up to a cap
+fn poll_backoff(attempt: u32) -> Duration {
+    let factor = 1u64 << attempt.saturating_sub(1).min(16);
+    Duration::from_millis((1_000 * factor).min(MAX_POLL_BACKOFF_MS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn pin_status(status: &str) -> ResponseTemplate {
+        ResponseTemplate::new(200)
+            .set_body_json(serde_json::json!({ "requestid": "req1", "status": status }))
+    }
+
+    /// A pinning service that queues pins and then reports `status`
+    async fn service(status: &str) -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/pins"))
+            .respond_with(pin_status("queued"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/pins/req1"))
+            .respond_with(pin_status(status))
+            .mount(&server)
+            .await;
+        server
+    }
+
+    // Polls are spaced by seconds: the paused clock skips the waits
+    #[tokio::test(start_paused = true)]
+    async fn test_pin_and_wait() {
+        let server = service("pinned").await;
+        let pins = PinningService::new(&server.uri(), "token");
+        assert_eq!(pins.pin_and_wait("bafy1", "report.json", 3).await.unwrap(), PinState::Pinned);
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
+
+        let server = service("failed").await;
+        let pins = PinningService::new(&server.uri(), "token");
+        assert!(pins.pin_and_wait("bafy1", "report.json", 3).await.is_err());
+
+        // Without polls, the pin is left queued
+        let server = service("pinned").await;
+        let pins = PinningService::new(&server.uri(), "token");
+        assert_eq!(pins.pin_and_wait("bafy1", "report.json", 0).await.unwrap(), PinState::Queued);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_pin_and_wait_gives_up() {
+        // Still pinning after every poll: the last state is returned, not an error
+        let server = service("pinning").await;
+        let pins = PinningService::new(&server.uri(), "token");
+        let state = pins.pin_and_wait("bafy1", "report.json", 2).await.unwrap();
+        assert_eq!(state, PinState::Pinning);
+        let polls = server.received_requests().await.unwrap().len() - 1;
+        assert_eq!(polls, 2);
+
+        // A failed poll is retried on the next one
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/pins"))
+            .respond_with(pin_status("queued"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(pin_status("pinned"))
+            .mount(&server)
+            .await;
+        let pins = PinningService::new(&server.uri(), "token");
+        assert_eq!(pins.pin_and_wait("bafy1", "report.json", 2).await.unwrap(), PinState::Pinned);
+
+        // A refused pin request fails without polling
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+        let pins = PinningService::new(&server.uri(), "token");
+        assert!(pins.pin_and_wait("bafy1", "report.json", 2).await.is_err());
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_poll_backoff() {
+        assert_eq!(poll_backoff(1), Duration::from_secs(1));
+        assert_eq!(poll_backoff(3), Duration::from_secs(4));
+        assert_eq!(poll_backoff(u32::MAX), Duration::from_millis(MAX_POLL_BACKOFF_MS));
+    }
+}
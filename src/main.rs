@@ -1,48 +1,186 @@
 use anyhow::Result;
-use axum::{middleware, routing::get, Router};
+use axum::{middleware, routing::{get, post}, Router};
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::net::TcpListener;
 use tokio::signal;
+use tokio::sync::RwLock;
 use tower_http::cors::CorsLayer;
 use tower_http::trace::TraceLayer;
-use tracing::info;
+use tracing::{info, warn};
 
 mod abi;
 mod api;
 mod arweave;
+mod attestation;
 mod audit;
 mod blockchain;
+mod breaker;
+mod caip;
+mod canonical;
 mod chains;
+mod check;
 mod config;
+mod config_file;
+mod events;
+mod http;
+mod indexer;
+mod integrity;
 mod ipfs;
+mod mcp_server;
+mod pause;
+mod publish;
+mod readiness;
+mod receipts;
+mod redis_conn;
+mod reload;
+mod report_links;
+mod retention;
+mod s3;
+mod search;
+mod self_registration;
+mod server;
+mod ssrf;
+mod stats;
 mod services;
 mod store;
+mod subscriptions;
+mod tenant;
+mod tls;
 mod types;
+mod usage;
 mod wallet;
 
 use config::Config;
+use store::sqlite::SqliteStore;
 use store::AuditStore;
 
 pub struct AppState {
     pub config: Config,
-    pub http_client: reqwest::Client,
+    /// Settings a reload can change (see `reload`)
+    pub live: reload::Live,
+    pub http: http::HttpClients,
     pub audit_store: AuditStore,
+    pub integrity: RwLock<integrity::IntegrityStats>,
+    /// Watchy's own TEE attestation, loaded at startup
+    pub attestation: Option<attestation::Attestation>,
+    /// Signer balance by chain as (checked at, wei), cached for /health
+    pub signer_balance: RwLock<HashMap<u64, (u64, u128)>>,
+    /// Aggregate stats by window (hours) as (computed at, stats), cached for /stats
+    pub stats: RwLock<HashMap<u64, (u64, stats::AuditStats)>>,
+    /// Startup checks, gating /health/ready
+    pub readiness: readiness::Readiness,
+    /// Paused background subsystems (see `pause`)
+    pub pauses: pause::Pauses,
+    /// Watchy's own registry entries (see `self_registration`)
+    pub self_agents: self_registration::SelfAgents,
+    /// Running audits, waited for before `--mcp-stdio` exits
+    pub audits: tokio_util::task::TaskTracker,
+    /// Back-off of automatic audits (see `breaker`)
+    pub breaker: breaker::Breaker,
+    /// Slots for `POST /check/endpoint` (ENDPOINT_CHECK_CONCURRENCY)
+    pub endpoint_checks: tokio::sync::Semaphore,
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    // Initialize tracing
-    tracing_subscriber::fmt()
+#[cfg(test)]
+impl AppState {
+    /// In-memory state for tests, signing with Hardhat's first development key
+    pub fn for_tests(configure: impl FnOnce(&mut Config)) -> Arc<Self> {
+        let mut config = Config::from_env().unwrap();
+        let private_key =
+            "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80".to_string();
+        config.wallet.private_key = Some(private_key.clone());
+        config.wallet.signers = vec![wallet::SignerKey {
+            label: "key:0".to_string(),
+            private_key,
+            address: "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266".to_string(),
+        }];
+        configure(&mut config);
+        let http = http::HttpClients::new(
+            &config.http_timeouts,
+            config.outbound_guard.clone(),
+            &config.probe_user_agent,
+            &config.proxies,
+        )
+        .unwrap();
+        let probes = http::ProbeClients::new(
+            &config.http_timeouts,
+            &http.guard,
+            &config.probe_user_agent,
+            &config.proxies,
+        )
+        .unwrap();
+        Arc::new(AppState {
+            live: reload::Live::new(reload::LiveSettings::new(&config, probes), Default::default()),
+            http,
+            audit_store: AuditStore::in_memory(),
+            integrity: Default::default(),
+            attestation: None,
+            signer_balance: Default::default(),
+            stats: Default::default(),
+            readiness: readiness::Readiness::default(),
+            pauses: pause::Pauses::new(&config.paused_subsystems),
+            self_agents: Default::default(),
+            audits: Default::default(),
+            breaker: breaker::Breaker::new(config.breaker.clone()),
+            endpoint_checks: tokio::sync::Semaphore::new(config.endpoint_check_concurrency),
+            config,
+        })
+    }
+}
+
+fn main() -> Result<()> {
+    // The environment is set up here, while this is the only thread: nothing
+    // changes it once the runtime runs
+    let systemd_sockets = server::take_systemd_sockets();
+    dotenvy::dotenv().ok();
+    let settings_file = config_file::ConfigFile::load()
+        .map(|file| file.map(|(path, file)| (path, file.apply())));
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?;
+
+    // `--check-config`: validate the configuration, print a report and exit
+    if std::env::args().skip(1).any(|arg| arg == "--check-config") {
+        let report = runtime.block_on(check::run(settings_file));
+        println!("{}", report);
+        std::process::exit(if report.passed() { 0 } else { 1 });
+    }
+    runtime.block_on(run(settings_file?, systemd_sockets))
+}
+
+async fn run(
+    settings_file: Option<(PathBuf, config_file::Applied)>,
+    systemd_sockets: Option<usize>,
+) -> Result<()> {
+
+    // `--mcp-stdio`: serve MCP on stdin/stdout instead of HTTP (see `mcp_server`)
+    let mcp_stdio = std::env::args().skip(1).any(|arg| arg == "--mcp-stdio");
+
+    // Initialize tracing (on stderr when stdout carries MCP)
+    let subscriber = tracing_subscriber::fmt()
         .with_env_filter(
             tracing_subscriber::EnvFilter::from_default_env()
                 .add_directive("watchy=debug".parse()?),
         )
-        .json()
-        .init();
+        .json();
+    if mcp_stdio {
+        subscriber.with_writer(std::io::stderr).init();
+    } else {
+        subscriber.init();
+    }
 
     // Load configuration
-    dotenvy::dotenv().ok();
-    let config = Config::from_env()?;
+    if let Some((path, applied)) = &settings_file {
+        info!("Loaded settings from {}", path.display());
+        if !applied.overridden.is_empty() {
+            info!("Overridden by the environment: {}", applied.overridden.join(", "));
+        }
+    }
+    let mut config = Config::from_env()?;
+    if let Some(sockets) = systemd_sockets {
+        config.listen = server::ListenAddr::Systemd(sockets);
+    }
 
     info!("Starting Watchy v{}", env!("CARGO_PKG_VERSION"));
     info!("Default chain: {}", config.default_chain_id);
@@ -59,28 +197,110 @@ async fn main() -> Result<()> {
     );
 
     // Initialize audit store (with Redis if configured)
-    let audit_store = AuditStore::new(config.redis_url.as_deref()).await;
-    info!(
-        "Storage backend: {}",
-        if audit_store.has_redis() { "Redis" } else { "In-memory" }
-    );
+    let audit_store = match &config.sqlite_path {
+        Some(path) => AuditStore::with_backend(Arc::new(SqliteStore::open(path)?)),
+        None => AuditStore::new(config.redis.as_ref()).await,
+    }
+    .with_retention(config.audit_retention.clone());
+    info!("Storage backend: {}", audit_store.backend_name());
+    info!("Report store: {}", config.report_store.as_str());
+    if !config.outbound_guard.enabled {
+        info!("SSRF protection disabled: agent URLs may reach private addresses");
+    }
+    if config.probe_policy.is_enabled() {
+        info!(
+            "Probe allowlist enabled: domains {:?}, ASNs {:?}",
+            config.probe_policy.domains, config.probe_policy.asns
+        );
+    }
     info!(
         "Wallet mode: {} (address: {})",
         config.key_mode().as_str(),
         config.signer_address().unwrap_or("none")
     );
 
+    if let Some(proxy) = config.proxies.rpc_proxy() {
+        blockchain::set_rpc_proxy(proxy)?;
+        info!("RPC proxy: {}", proxy.host_str().unwrap_or_default());
+    }
+    let probe_proxies = config.proxies.probe_proxies();
+    if !probe_proxies.is_empty() {
+        info!("Probing through {} proxies", probe_proxies.len());
+    }
+
     // Create shared state
-    let http_client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(10))
-        .build()?;
+    // Configured gateways are trusted even when they're on a private network;
+    // proxies are exempted only in the resolvers of the clients that use them
+    let guard = [&config.ipfs_gateway, &config.arweave_gateway]
+        .into_iter()
+        .filter_map(|gateway| url::Url::parse(gateway).ok()?.host_str().map(str::to_string))
+        .fold(config.outbound_guard.clone(), |guard, host| {
+            guard.with_allowed_host(&host)
+        });
+    let http = http::HttpClients::new(
+        &config.http_timeouts,
+        guard,
+        &config.probe_user_agent,
+        &config.proxies,
+    )?;
+
+    // The attestation service is operator-configured (and usually local): unguarded client
+    let attestation = match &config.tee_attestation {
+        Some(tee) => match attestation::load(tee, &http.upload, config.signer_address()).await {
+            Ok(attestation) => Some(attestation),
+            Err(e) => {
+                warn!("TEE attestation unavailable, reports won't reference it: {}", e);
+                None
+            }
+        },
+        None => None,
+    };
+
+    let probes = http::ProbeClients::new(
+        &config.http_timeouts,
+        &http.guard,
+        &config.probe_user_agent,
+        &config.proxies,
+    )?;
+    let file_vars = settings_file
+        .as_ref()
+        .map(|(_, applied)| reload::FileVars::new(applied.set.clone()))
+        .unwrap_or_default();
 
     let state = Arc::new(AppState {
         config: config.clone(),
-        http_client,
+        live: reload::Live::new(reload::LiveSettings::new(&config, probes), file_vars),
+        http,
         audit_store,
+        integrity: RwLock::new(integrity::IntegrityStats::default()),
+        attestation,
+        signer_balance: RwLock::new(HashMap::new()),
+        stats: RwLock::new(HashMap::new()),
+        readiness: readiness::Readiness::default(),
+        pauses: pause::Pauses::new(&config.paused_subsystems),
+        self_agents: Default::default(),
+        audits: Default::default(),
+        breaker: breaker::Breaker::new(config.breaker.clone()),
+        endpoint_checks: tokio::sync::Semaphore::new(config.endpoint_check_concurrency),
     });
 
+    // Audits run in this process; the background workers are the server's
+    if mcp_stdio {
+        return mcp_server::serve_stdio(state).await;
+    }
+
+    readiness::spawn_startup_checks(state.clone());
+    integrity::spawn_integrity_worker(state.clone());
+    retention::spawn_retention_worker(state.clone());
+    self_registration::spawn_self_registration(state.clone());
+    state.breaker.load(&state.audit_store).await;
+    indexer::spawn_indexer(state.clone());
+    if let Some((path, _)) = settings_file {
+        if config.config_watch_secs > 0 {
+            reload::spawn_watcher(state.clone(), path, config.config_watch_secs);
+        }
+    }
+
     // Log API key status
     if config.api_key.is_some() {
         info!("API key authentication enabled");
@@ -98,6 +318,11 @@ async fn main() -> Result<()> {
     let protected_routes = Router::new()
         .nest("/audit", api::routes::audit_routes())
         .nest("/agents", api::routes::agent_routes())
+        .merge(api::routes::graphql_routes())
+        .merge(api::routes::usage_routes())
+        .merge(api::routes::check_routes())
+        .merge(api::routes::a2a_routes())
+        .merge(api::routes::mcp_routes())
         .route_layer(middleware::from_fn_with_state(
             state.clone(),
             api::middleware::require_api_key,
@@ -111,22 +336,60 @@ async fn main() -> Result<()> {
             api::middleware::require_admin_api_key,
         ));
 
-    let app = Router::new()
+    let routes = Router::new()
         .route("/health", get(api::handlers::health))
+        .route("/health/live", get(api::handlers::health_live))
+        .route("/health/ready", get(api::handlers::health_ready))
+        .route("/stats", get(api::handlers::get_stats))
+        .route("/issues/catalog", get(api::handlers::get_issue_catalog))
+        .route("/attestation", get(api::handlers::get_attestation))
+        .route("/verify", post(api::handlers::verify_report))
+        .route("/verify/receipt", post(api::handlers::verify_receipt))
+        .route("/.well-known/agent.json", get(api::a2a::agent_card))
+        .route("/.well-known/agent-card.json", get(api::a2a::agent_card))
+        .route("/schema/audit-report.json", get(api::handlers::get_report_schema))
+        // API key or signed link, checked in the handler
+        .route("/audit/:audit_id/report.md", get(api::handlers::get_audit_markdown))
+        // Owner signature, checked in the handler
+        .route("/subscriptions", post(api::handlers::create_subscription))
+        .route("/subscriptions/challenge", post(api::handlers::subscription_challenge))
+        .route(
+            "/subscriptions/confirm",
+            get(api::handlers::confirm_subscription_page).post(api::handlers::confirm_subscription),
+        )
+        .route(
+            "/subscriptions/unsubscribe",
+            get(api::handlers::unsubscribe_page).post(api::handlers::remove_subscription),
+        )
+        .route(
+            "/subscriptions/unsubscribe/link",
+            post(api::handlers::unsubscribe_with_link),
+        )
         .merge(protected_routes)
         .merge(admin_routes)
+        .with_state(state.clone());
+
+    let limits_state = state.clone();
+    let app = server::with_limits(routes, move || limits_state.live.get().limits.clone())
+        // Plain-text rejections (extractors, 404/405, body limit) get the JSON envelope
+        .layer(middleware::from_fn(api::middleware::json_errors))
         .layer(TraceLayer::new_for_http())
         .layer(CorsLayer::permissive())
-        .with_state(state);
+        // Outermost, so every response carries the ID and every log line its span
+        .layer(middleware::from_fn(api::middleware::request_id));
 
     // Start server with graceful shutdown
-    let addr = format!("0.0.0.0:{}", config.port);
-    info!("Listening on {}", addr);
+    let tls = match &config.tls {
+        Some(tls) => {
+            info!("HTTPS enabled ({})", tls.describe());
+            Some(tls::acceptor(tls, state.http.upload.clone()).await?)
+        }
+        None => None,
+    };
 
-    let listener = TcpListener::bind(&addr).await?;
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await?;
+    let listener = server::Listener::bind(&config.listen).await?;
+    info!("Listening on {}", listener.describe());
+    server::serve(listener, app, &config.server_limits, tls, shutdown_signal()).await?;
 
     info!("Server shutdown complete");
     Ok(())
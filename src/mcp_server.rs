@@ -0,0 +1,465 @@
+//! MCP server: Watchy's tools for LLM assistants
+//!
+//! Serves the Model Context Protocol over two transports: `POST /mcp` (HTTP,
+//! behind the API key; one JSON response per request) and stdio
+//! (`watchy --mcp-stdio`, newline-delimited JSON-RPC, for assistants that
+//! spawn Watchy as a local process). Tools run against the same engine and
+//! store as the REST API:
+//!
+//! - `request_audit`: start an audit (the `POST /audit` body)
+//! - `get_audit`: an audit's status and score summary
+//! - `get_audit_report`: the full report, as JSON or Markdown
+//! - `compare_agents`: latest audit scores of several agents side by side
+
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tracing::{info, warn};
+use validator::Validate;
+
+use crate::api::handlers::{audit_status, create_audit, AuditStatusResponse};
+use crate::api::middleware::RequestId;
+use crate::audit::generate_markdown_report;
+use crate::caip;
+use crate::receipts;
+use crate::tenant::Tenant;
+use crate::types::{AuditRequest, AuditStatus};
+use crate::AppState;
+
+/// Protocol versions served, newest first
+const PROTOCOL_VERSIONS: [&str; 3] = ["2025-06-18", "2025-03-26", "2024-11-05"];
+/// Most agents `compare_agents` takes at once
+const MAX_COMPARED_AGENTS: usize = 10;
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Who a request acts for
+#[derive(Debug, Clone, Default)]
+pub struct Caller {
+    pub tenant: Tenant,
+    pub request_id: Option<String>,
+    /// API key hash for receipts (see `receipts`)
+    pub requester: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RpcRequest {
+    jsonrpc: String,
+    /// Absent for notifications, which get no response
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// Handle one JSON-RPC message; `None` for notifications
+pub async fn handle(state: &Arc<AppState>, caller: &Caller, message: &str) -> Option<Value> {
+    let request: RpcRequest = match serde_json::from_str(message) {
+        Ok(request) => request,
+        Err(e) => {
+            let code = match e.classify() {
+                serde_json::error::Category::Data => INVALID_REQUEST,
+                _ => PARSE_ERROR,
+            };
+            return Some(error_response(Value::Null, code, e.to_string()));
+        }
+    };
+    let Some(id) = request.id else {
+        // notifications/initialized, notifications/cancelled: nothing to do
+        return None;
+    };
+    if request.jsonrpc != "2.0" {
+        return Some(error_response(id, INVALID_REQUEST, "jsonrpc must be 2.0"));
+    }
+
+    let result = match request.method.as_str() {
+        "initialize" => Ok(initialize(&request.params)),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tools() })),
+        "tools/call" => match serde_json::from_value::<ToolCall>(request.params) {
+            Ok(call) => Ok(call_tool(state, caller, call).await),
+            Err(e) => Err((INVALID_PARAMS, format!("Invalid params: {}", e))),
+        },
+        method => Err((METHOD_NOT_FOUND, format!("Method not found: {}", method))),
+    };
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => error_response(id, code, message),
+    })
+}
+
+fn error_response(id: Value, code: i64, message: impl Into<String>) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message.into() } })
+}
+
+/// `initialize`: the client's protocol version if served, else the newest
+fn initialize(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let version = PROTOCOL_VERSIONS
+        .into_iter()
+        .find(|v| Some(*v) == requested)
+        .unwrap_or(PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": { "listChanged": false } },
+        "serverInfo": { "name": "watchy", "version": env!("CARGO_PKG_VERSION") },
+        "instructions": "Watchy audits EIP-8004 registered agents. Start an audit with \
+            request_audit, poll get_audit until it is completed, then read \
+            get_audit_report. compare_agents ranks agents by their latest audits."
+    })
+}
+
+fn tools() -> Value {
+    let agent = json!({
+        "type": "object",
+        "properties": {
+            "agent_id": { "type": "integer", "minimum": 1, "description": "EIP-8004 agent ID" },
+            "chain_id": {
+                "type": ["integer", "string"],
+                "description": "Chain ID, CAIP-2 ID or name, e.g. 8453, \"eip155:8453\" or \
+                    \"base\" for Base (default: the deployment's)"
+            }
+        },
+        "required": ["agent_id"]
+    });
+    let audit_id = json!({
+        "type": "object",
+        "properties": { "audit_id": { "type": "string", "description": "ID from request_audit" } },
+        "required": ["audit_id"]
+    });
+    let mut request_audit = agent.clone();
+    request_audit["properties"]["timeout_secs"] =
+        json!({ "type": "integer", "minimum": 1, "description": "Audit deadline in seconds" });
+    request_audit["properties"]["lang"] =
+        json!({ "type": "string", "enum": ["en", "zh", "es"], "description": "Report language" });
+    let mut report = audit_id.clone();
+    report["properties"]["format"] = json!({ "type": "string", "enum": ["json", "markdown"] });
+
+    json!([
+        {
+            "name": "request_audit",
+            "description": "Start an audit of an EIP-8004 agent: metadata, on-chain \
+                registration, endpoints and security. Returns the audit_id to poll.",
+            "inputSchema": request_audit
+        },
+        {
+            "name": "get_audit",
+            "description": "Status of an audit; once completed, its scores (0-100) and \
+                issue counts.",
+            "inputSchema": audit_id
+        },
+        {
+            "name": "get_audit_report",
+            "description": "Full report of a completed audit with every issue found, as JSON \
+                (default) or Markdown.",
+            "inputSchema": report
+        },
+        {
+            "name": "compare_agents",
+            "description": "Compare the latest completed audits of several agents, ranked by \
+                overall score.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "agents": {
+                        "type": "array",
+                        "items": agent,
+                        "minItems": 2,
+                        "maxItems": MAX_COMPARED_AGENTS
+                    }
+                },
+                "required": ["agents"]
+            }
+        }
+    ])
+}
+
+/// Names of the served tools, for Watchy's own registration file
+pub fn tool_names() -> Vec<String> {
+    tools()
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|tool| tool["name"].as_str().map(str::to_string))
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct ToolCall {
+    name: String,
+    #[serde(default)]
+    arguments: Value,
+}
+
+#[derive(Deserialize)]
+struct AuditIdArgs {
+    audit_id: String,
+    #[serde(default)]
+    format: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AgentArgs {
+    agent_id: u64,
+    #[serde(default, deserialize_with = "caip::deserialize_optional_chain_id")]
+    chain_id: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct CompareArgs {
+    agents: Vec<AgentArgs>,
+}
+
+/// `tools/call`: tool failures are results with `isError`, not protocol errors
+async fn call_tool(state: &Arc<AppState>, caller: &Caller, call: ToolCall) -> Value {
+    let result = match call.name.as_str() {
+        "request_audit" => request_audit(state, caller, call.arguments).await,
+        "get_audit" => get_audit(state, caller, call.arguments).await,
+        "get_audit_report" => get_audit_report(state, caller, call.arguments).await,
+        "compare_agents" => compare_agents(state, caller, call.arguments).await,
+        name => Err(format!("Unknown tool: {}", name)),
+    };
+    match result {
+        Ok(Value::String(text)) => json!({ "content": [{ "type": "text", "text": text }] }),
+        Ok(value) => json!({
+            "content": [{ "type": "text", "text": value.to_string() }],
+            "structuredContent": value
+        }),
+        Err(message) => {
+            json!({ "content": [{ "type": "text", "text": message }], "isError": true })
+        }
+    }
+}
+
+fn arguments<T: serde::de::DeserializeOwned>(arguments: Value) -> Result<T, String> {
+    serde_json::from_value(arguments).map_err(|e| format!("Invalid arguments: {}", e))
+}
+
+async fn request_audit(
+    state: &Arc<AppState>,
+    caller: &Caller,
+    args: Value,
+) -> Result<Value, String> {
+    let request: AuditRequest = arguments(args)?;
+    request
+        .validate()
+        .map_err(|e| format!("Invalid arguments: {}", e))?;
+    let created = create_audit(
+        state,
+        caller.tenant.clone(),
+        caller.request_id.clone(),
+        caller.requester.clone(),
+        request,
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+    serde_json::to_value(created).map_err(|e| e.to_string())
+}
+
+async fn get_audit(state: &Arc<AppState>, caller: &Caller, args: Value) -> Result<Value, String> {
+    let AuditIdArgs { audit_id, .. } = arguments(args)?;
+    let job = state
+        .audit_store
+        .get_tenant_job(&audit_id, caller.tenant.id())
+        .await
+        .ok_or_else(|| format!("Audit not found: {}", audit_id))?;
+    serde_json::to_value(audit_status(state, &job).await).map_err(|e| e.to_string())
+}
+
+async fn get_audit_report(
+    state: &Arc<AppState>,
+    caller: &Caller,
+    args: Value,
+) -> Result<Value, String> {
+    let AuditIdArgs { audit_id, format } = arguments(args)?;
+    let job = state
+        .audit_store
+        .get_tenant_job(&audit_id, caller.tenant.id())
+        .await
+        .ok_or_else(|| format!("Audit not found: {}", audit_id))?;
+    let Some(report) = job.result else {
+        return Err(match job.status {
+            AuditStatus::Failed => format!(
+                "Audit failed: {}",
+                job.error.map(|error| error.message).unwrap_or_default()
+            ),
+            _ => "Audit not yet completed".to_string(),
+        });
+    };
+    match format.as_deref() {
+        None | Some("json") => serde_json::to_value(report).map_err(|e| e.to_string()),
+        Some("markdown") => Ok(Value::String(generate_markdown_report(&report, None))),
+        Some(other) => Err(format!(
+            "Unknown format '{}' (expected json or markdown)",
+            other
+        )),
+    }
+}
+
+async fn compare_agents(
+    state: &Arc<AppState>,
+    caller: &Caller,
+    args: Value,
+) -> Result<Value, String> {
+    let CompareArgs { agents } = arguments(args)?;
+    if !(2..=MAX_COMPARED_AGENTS).contains(&agents.len()) {
+        return Err(format!("Compare 2 to {} agents", MAX_COMPARED_AGENTS));
+    }
+
+    let mut compared = Vec::new();
+    for agent in agents {
+        let chain_id = agent.chain_id.unwrap_or(state.config.default_chain_id);
+        let latest = state
+            .audit_store
+            .latest_completed(agent.agent_id, chain_id, caller.tenant.id())
+            .await;
+        compared.push((
+            agent.agent_id,
+            chain_id,
+            latest.as_ref().map(AuditStatusResponse::from),
+        ));
+    }
+    Ok(comparison(compared))
+}
+
+/// Agents with their latest audit, and the audited ones ranked by overall score
+fn comparison(agents: Vec<(u64, u64, Option<AuditStatusResponse>)>) -> Value {
+    let score = |audit: &Option<AuditStatusResponse>| {
+        audit
+            .as_ref()
+            .and_then(|audit| audit.result.as_ref())
+            .map(|result| result.scores.overall)
+    };
+    let mut ranked: Vec<_> = agents
+        .iter()
+        .filter_map(|(agent_id, chain_id, audit)| {
+            score(audit).map(|score| (score, *agent_id, *chain_id))
+        })
+        .collect();
+    ranked.sort_by_key(|ranked| std::cmp::Reverse(ranked.0));
+
+    json!({
+        "agents": agents
+            .iter()
+            .map(|(agent_id, chain_id, audit)| json!({
+                "agent_id": agent_id,
+                "chain_id": chain_id,
+                "audited": audit.is_some(),
+                "latest_audit": audit
+            }))
+            .collect::<Vec<_>>(),
+        "ranking": ranked
+            .iter()
+            .map(|(score, agent_id, chain_id)| json!({
+                "agent_id": agent_id,
+                "chain_id": chain_id,
+                "overall": score
+            }))
+            .collect::<Vec<_>>()
+    })
+}
+
+/// POST /mcp - MCP over HTTP: one JSON response per request, 202 for notifications
+pub async fn http(
+    State(state): State<Arc<AppState>>,
+    Extension(tenant): Extension<Tenant>,
+    request_id: Option<Extension<RequestId>>,
+    headers: HeaderMap,
+    body: String,
+) -> Response {
+    let caller = Caller {
+        tenant,
+        request_id: request_id.map(|Extension(RequestId(id))| id),
+        requester: receipts::requester(&state.config, &headers),
+    };
+    match handle(&state, &caller, &body).await {
+        Some(response) => Json(response).into_response(),
+        None => StatusCode::ACCEPTED.into_response(),
+    }
+}
+
+/// Serve MCP on stdin/stdout until stdin closes, as the default tenant
+///
+/// Audits still running when stdin closes are finished (and stored) before
+/// returning, so the process doesn't exit under them.
+pub async fn serve_stdio(state: Arc<AppState>) -> anyhow::Result<()> {
+    info!("Serving MCP on stdio");
+    let caller = Caller::default();
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = handle(&state, &caller, &line).await {
+            let mut out = response.to_string();
+            out.push('\n');
+            stdout.write_all(out.as_bytes()).await?;
+            stdout.flush().await?;
+        }
+    }
+    warn!("stdin closed, MCP server stopping");
+    state.audits.close();
+    if !state.audits.is_empty() {
+        info!("Waiting for {} running audit(s) to finish", state.audits.len());
+        state.audits.wait().await;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_protocol_negotiation_and_ranking() {
+        let reply = initialize(&json!({ "protocolVersion": "2025-03-26" }));
+        assert_eq!(reply["protocolVersion"], "2025-03-26");
+        let reply = initialize(&json!({ "protocolVersion": "1999-01-01" }));
+        assert_eq!(reply["protocolVersion"], PROTOCOL_VERSIONS[0]);
+
+        assert_eq!(
+            tool_names(),
+            [
+                "request_audit",
+                "get_audit",
+                "get_audit_report",
+                "compare_agents"
+            ]
+        );
+
+        let store = crate::store::AuditStore::in_memory();
+        for (agent_id, overall) in [(17, 40), (18, 85)] {
+            let id = store.create_job(Some(agent_id), 8453, None, None, None).await;
+            let mut report =
+                crate::types::AuditReport::new(agent_id, 8453, "0x8004", String::new(), None);
+            report.scores.overall = overall;
+            store.set_result(&id, report, &[]).await;
+        }
+        let mut agents = Vec::new();
+        for agent_id in [17, 18, 19] {
+            let latest = store.latest_completed(agent_id, 8453, None).await;
+            agents.push((
+                agent_id,
+                8453,
+                latest.as_ref().map(AuditStatusResponse::from),
+            ));
+        }
+        let comparison = comparison(agents);
+        assert_eq!(comparison["agents"][2]["audited"], false);
+        assert_eq!(comparison["ranking"][0]["agent_id"], 18);
+        assert_eq!(comparison["ranking"][1]["overall"], 40);
+        assert_eq!(comparison["ranking"].as_array().unwrap().len(), 2);
+    }
+}
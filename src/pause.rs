@@ -0,0 +1,157 @@
+//! Pausing background subsystems
+//!
+//! `POST /admin/pause/:subsystem` stops a subsystem from starting new work until
+//! `POST /admin/resume/:subsystem`; work already running finishes. Pausing
+//! `onchain` stops spending gas during an incident: reports are still uploaded,
+//! but no feedback or EAS attestation is submitted. `PAUSED_SUBSYSTEMS` pauses
+//! subsystems from startup, so a restart doesn't resume them. /health lists
+//! what's paused.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Subsystem {
+    /// Registry indexer, and the auto-audits of new agents
+    Indexer,
+    /// Periodic re-checks of stored reports
+    Integrity,
+    /// Retention sweep (archiving and expiry)
+    Retention,
+    /// On-chain feedback and EAS attestations of published reports
+    Onchain,
+}
+
+impl Subsystem {
+    pub const ALL: [Subsystem; 4] = [
+        Subsystem::Indexer,
+        Subsystem::Integrity,
+        Subsystem::Retention,
+        Subsystem::Onchain,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|subsystem| subsystem.as_str() == value.trim().to_lowercase())
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Subsystem::Indexer => "indexer",
+            Subsystem::Integrity => "integrity",
+            Subsystem::Retention => "retention",
+            Subsystem::Onchain => "onchain",
+        }
+    }
+}
+
+/// Parse `PAUSED_SUBSYSTEMS` ("indexer,onchain")
+pub fn parse_list(value: &str) -> anyhow::Result<Vec<Subsystem>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            Subsystem::parse(name).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Unknown subsystem '{}' in PAUSED_SUBSYSTEMS (expected {})",
+                    name,
+                    names()
+                )
+            })
+        })
+        .collect()
+}
+
+/// Subsystem names, for error messages
+pub fn names() -> String {
+    Subsystem::ALL
+        .map(|subsystem| subsystem.as_str())
+        .join(", ")
+}
+
+/// A paused subsystem, as reported by /health
+#[derive(Debug, Clone, Serialize)]
+pub struct Paused {
+    pub subsystem: Subsystem,
+    /// Unix seconds
+    pub since: u64,
+}
+
+/// Which subsystems are paused, and since when
+#[derive(Default)]
+pub struct Pauses {
+    paused: RwLock<BTreeMap<Subsystem, u64>>,
+}
+
+impl Pauses {
+    pub fn new(initial: &[Subsystem]) -> Self {
+        let now = chrono::Utc::now().timestamp() as u64;
+        Self {
+            paused: RwLock::new(initial.iter().map(|&subsystem| (subsystem, now)).collect()),
+        }
+    }
+
+    pub fn is_paused(&self, subsystem: Subsystem) -> bool {
+        self.paused
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains_key(&subsystem)
+    }
+
+    /// Pause a subsystem; returns when it was paused (earlier if it already was)
+    pub fn pause(&self, subsystem: Subsystem) -> u64 {
+        let now = chrono::Utc::now().timestamp() as u64;
+        *self
+            .paused
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(subsystem)
+            .or_insert(now)
+    }
+
+    /// Resume a subsystem; returns whether it was paused
+    pub fn resume(&self, subsystem: Subsystem) -> bool {
+        self.paused
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&subsystem)
+            .is_some()
+    }
+
+    pub fn paused(&self) -> Vec<Paused> {
+        self.paused
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|(&subsystem, &since)| Paused { subsystem, since })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pauses() {
+        assert_eq!(
+            parse_list("indexer, ONCHAIN,").unwrap(),
+            vec![Subsystem::Indexer, Subsystem::Onchain]
+        );
+        assert!(parse_list("scheduler").is_err());
+
+        let pauses = Pauses::new(&[Subsystem::Onchain]);
+        assert!(pauses.is_paused(Subsystem::Onchain));
+        assert!(!pauses.is_paused(Subsystem::Indexer));
+
+        let since = pauses.pause(Subsystem::Onchain);
+        assert_eq!(pauses.pause(Subsystem::Onchain), since);
+        assert!(pauses.resume(Subsystem::Onchain));
+        assert!(!pauses.resume(Subsystem::Onchain));
+        assert!(pauses.paused().is_empty());
+    }
+}
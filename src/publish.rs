@@ -0,0 +1,917 @@
+//! Report publishing
+//!
+//! Once an audit completes, its reports are stored permanently and on-chain
+//! feedback is submitted pointing at the JSON:
+//! 1. Generate the markdown report and upload it → report_markdown_url
+//! 2. Sign the JSON report (which now includes the MD URL)
+//! 3. Upload the signed JSON → feedbackURI
+//! 4. Submit on-chain feedback, hashing the exact JSON that was uploaded
+//! 5. With `EAS_ATTESTATIONS`, attest the same hash and score via EAS
+//!
+//! Reports go to Arweave (default) or IPFS, selected by `REPORT_STORE`. With
+//! `PIN_METADATA_SNAPSHOTS` the audited metadata bytes are stored there first,
+//! so the report can link the exact document it describes. With
+//! `REPORT_MIRROR_S3_BUCKET` both reports are also copied to an S3-compatible
+//! bucket the operator controls.
+//!
+//! Signing, uploads and transactions can use different keys (`SigningKeys`).
+
+use alloy::primitives::Address;
+use tracing::{error, info, warn};
+
+use crate::arweave::{irys::sign_report, uploader_from_config, IrysClient};
+use crate::audit::generate_markdown_report;
+use crate::blockchain::eas::{encode_audit_data, EasClient, EasConfig};
+use crate::blockchain::reputation::{compute_feedback_hash, ReputationClient};
+use crate::chains::{chain_profile, get_chain, get_rpc_url};
+use crate::events::{AuditEvent, EventKind, EventLog};
+use crate::ipfs::{IpfsClient, PinState, PinningService};
+use crate::pause::Subsystem;
+use crate::s3::S3Client;
+use crate::audit::scoring::FeedbackPolicy;
+use crate::types::{AgentMetadata, AuditReport, EasAttestation, FeedbackDecision, WatchyError};
+use crate::wallet::SigningKeys;
+use crate::AppState;
+
+/// Primary destination for audit reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportStore {
+    /// Arweave via the configured bundler (feedbackURI is a gateway URL)
+    Arweave,
+    /// IPFS via IPFS_API_URL, optionally replicated (feedbackURI is ipfs://CID)
+    Ipfs,
+}
+
+impl ReportStore {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "arweave" | "ar" => Some(ReportStore::Arweave),
+            "ipfs" => Some(ReportStore::Ipfs),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReportStore::Arweave => "arweave",
+            ReportStore::Ipfs => "ipfs",
+        }
+    }
+}
+
+/// Upload reports to the configured store and submit on-chain feedback
+///
+/// Upload failures before the JSON is stored abort publishing (no feedback is
+/// submitted without a retrievable feedbackURI); a failed MD upload is logged
+/// and publishing continues without the MD link.
+pub async fn publish_report(
+    state: &AppState,
+    report: &mut AuditReport,
+    agent_metadata: Option<&AgentMetadata>,
+    audit_id: &str,
+    chain_id: u64,
+    keys: SigningKeys<'_>,
+    events: &EventLog,
+) -> Result<(), WatchyError> {
+    let md_filename = format!("watchy-audit-{}-{}.md", report.agent_id, audit_id);
+    let json_filename = format!("watchy-audit-{}-{}.json", report.agent_id, audit_id);
+    let started = events.phase_started("publish").await;
+    report.canonicalization = Some(crate::canonical::SCHEME.to_string());
+    // Set again from the current config, so the on-chain tags match the JSON
+    report.set_feedback_tags(&state.config.feedback_tags);
+    let decision = feedback_decision(
+        state.config.feedback_policy,
+        chain_id,
+        state.pauses.is_paused(Subsystem::Onchain),
+        report.scores.overall,
+    );
+    report.feedback_policy = Some(decision.clone());
+    if keys.feedback.address != keys.report.address {
+        report.auditor.feedback_address = Some(keys.feedback.address.clone());
+    }
+
+    if state.config.pin_metadata_snapshots {
+        if let Some(metadata) = agent_metadata.filter(|m| !m.source.is_empty()) {
+            let filename = format!("watchy-metadata-{}-{}.json", report.agent_id, audit_id);
+            let upload_key = &keys.upload.private_key;
+            pin_metadata_snapshot(state, report, &metadata.source, &filename, upload_key, events)
+                .await;
+        }
+    }
+
+    let markdown = generate_markdown_report(report, agent_metadata);
+    let files = ReportFiles {
+        markdown: &markdown,
+        md_filename: &md_filename,
+        json_filename: &json_filename,
+    };
+
+    let (feedback_uri, report_json) = match state.config.report_store {
+        ReportStore::Arweave => {
+            publish_to_arweave(state, report, &files, keys, events).await?
+        }
+        ReportStore::Ipfs => publish_to_ipfs(state, report, &files, keys, events).await?,
+    };
+
+    // Recorded so the integrity worker can re-check the stored copy later
+    let report_hash = compute_feedback_hash(&report_json)?;
+    report.set_json_hash(&report_hash);
+
+    mirror_to_s3(state, report, chain_id, &files, &report_json, events).await;
+
+    let feedback_key = &keys.feedback.private_key;
+    if state.pauses.is_paused(Subsystem::Onchain) {
+        info!("On-chain submission is paused, skipping feedback and attestation");
+        events.phase_finished("publish", started).await;
+        return Ok(());
+    }
+    if decision.submit {
+        submit_feedback(report, chain_id, feedback_key, &feedback_uri, &report_json, events).await;
+    } else {
+        info!(
+            "Skipping on-chain feedback: {}",
+            decision.reason.as_deref().unwrap_or_default()
+        );
+    }
+    if let Some(eas) = &state.config.eas {
+        attest_report(eas, report, chain_id, feedback_key, &feedback_uri, report_hash, events)
+            .await;
+    }
+    events.phase_finished("publish", started).await;
+    Ok(())
+}
+
+/// Decide whether a report gets on-chain feedback
+///
+/// Made before the upload, so the signed JSON says what actually happens.
+fn feedback_decision(
+    policy: FeedbackPolicy,
+    chain_id: u64,
+    onchain_paused: bool,
+    overall: u8,
+) -> FeedbackDecision {
+    let profile = chain_profile(chain_id);
+    let reason = if onchain_paused {
+        Some("on-chain submission is paused".to_string())
+    } else if !profile.feedback {
+        Some(format!("chain {} uses the {} profile", chain_id, profile.name))
+    } else if !policy.allows(overall) {
+        Some(format!("score {} is outside the policy", overall))
+    } else {
+        None
+    };
+    FeedbackDecision {
+        policy: policy.to_string(),
+        submit: reason.is_none(),
+        reason,
+    }
+}
+
+/// Report contents and file names for one publish
+struct ReportFiles<'a> {
+    markdown: &'a str,
+    md_filename: &'a str,
+    json_filename: &'a str,
+}
+
+async fn publish_to_arweave(
+    state: &AppState,
+    report: &mut AuditReport,
+    files: &ReportFiles<'_>,
+    keys: SigningKeys<'_>,
+    events: &EventLog,
+) -> Result<(String, serde_json::Value), WatchyError> {
+    let ReportFiles {
+        markdown,
+        md_filename,
+        json_filename,
+    } = *files;
+    let irys = arweave_client(state, &keys.upload.private_key)?;
+
+    // When bundling, the MD is only signed here: its URL is known from the
+    // signature and it's uploaded together with the JSON
+    let mut bundled_items = Vec::new();
+    if state.config.arweave_bundle_reports {
+        match irys.sign_markdown(markdown, md_filename).await {
+            Ok(md_item) => {
+                report.set_markdown_url(
+                    &irys.url_for(&md_item.id()),
+                    &IrysClient::uri_for(&md_item.id()),
+                );
+                bundled_items.push(md_item);
+            }
+            Err(e) => {
+                error!("Failed to sign MD DataItem: {}", e);
+            }
+        }
+    } else {
+        match irys.upload_markdown(markdown, md_filename).await {
+            Ok(md_result) => {
+                info!("Markdown uploaded to Arweave: {}", md_result.arweave_url);
+                events.upload("arweave", md_filename, Ok(&md_result.uri)).await;
+                report.set_markdown_url(&md_result.arweave_url, &md_result.uri);
+            }
+            Err(e) => {
+                error!("Failed to upload MD to Arweave: {}", e);
+                events.upload("arweave", md_filename, Err(e.to_string())).await;
+            }
+        }
+    }
+    let md_bundled = !bundled_items.is_empty();
+
+    let report_json = signed_report_json(report, &keys.report.private_key).await?;
+
+    match irys
+        .upload_json_bundled(&report_json, json_filename, bundled_items)
+        .await
+    {
+        Ok(json_result) => {
+            info!("JSON report uploaded to Arweave: {}", json_result.arweave_url);
+            events.upload("arweave", json_filename, Ok(&json_result.uri)).await;
+            report.set_json_url(&json_result.arweave_url, &json_result.uri);
+            Ok((json_result.arweave_url, report_json))
+        }
+        Err(e) => {
+            events.upload("arweave", json_filename, Err(e.to_string())).await;
+            // The MD went up in the same bundle, so it wasn't stored either
+            if md_bundled {
+                report.clear_markdown_url();
+            }
+            Err(e)
+        }
+    }
+}
+
+async fn publish_to_ipfs(
+    state: &AppState,
+    report: &mut AuditReport,
+    files: &ReportFiles<'_>,
+    keys: SigningKeys<'_>,
+    events: &EventLog,
+) -> Result<(String, serde_json::Value), WatchyError> {
+    let ReportFiles {
+        markdown,
+        md_filename,
+        json_filename,
+    } = *files;
+    let (ipfs, replica) = ipfs_clients(state);
+
+    match pin_to_ipfs(
+        state,
+        &ipfs,
+        replica.as_ref(),
+        markdown.as_bytes().to_vec(),
+        md_filename,
+        "text/markdown",
+    )
+    .await
+    {
+        Ok(cid) => {
+            let url = ipfs.gateway_url(&cid);
+            info!("Markdown uploaded to IPFS: {}", url);
+            events.upload("ipfs", md_filename, Ok(&cid)).await;
+            report.set_markdown_url(&url, &format!("ipfs://{}", cid));
+        }
+        Err(e) => {
+            error!("Failed to upload MD to IPFS: {}", e);
+            events.upload("ipfs", md_filename, Err(e.to_string())).await;
+        }
+    }
+
+    let report_json = signed_report_json(report, &keys.report.private_key).await?;
+
+    // The bytes feedbackHash is computed over
+    let json_bytes = crate::canonical::report_bytes(&report_json);
+    let cid = match pin_to_ipfs(
+        state,
+        &ipfs,
+        replica.as_ref(),
+        json_bytes,
+        json_filename,
+        "application/json",
+    )
+    .await
+    {
+        Ok(cid) => {
+            events.upload("ipfs", json_filename, Ok(&cid)).await;
+            cid
+        }
+        Err(e) => {
+            events.upload("ipfs", json_filename, Err(e.to_string())).await;
+            return Err(e);
+        }
+    };
+
+    let url = ipfs.gateway_url(&cid);
+    let uri = format!("ipfs://{}", cid);
+    info!("JSON report uploaded to IPFS: {}", uri);
+    report.set_json_url(&url, &uri);
+    report.set_ipfs_location(&url, &cid);
+
+    Ok((uri, report_json))
+}
+
+/// Copy both reports to the mirror bucket, if configured
+///
+/// Best-effort: the primary store already has them. Objects are stored at
+/// `{prefix}{chain_id}/{agent_id}/{filename}`; the JSON is the exact signed
+/// document that was published.
+async fn mirror_to_s3(
+    state: &AppState,
+    report: &AuditReport,
+    chain_id: u64,
+    files: &ReportFiles<'_>,
+    report_json: &serde_json::Value,
+    events: &EventLog,
+) {
+    let Some(config) = &state.config.report_mirror else {
+        return;
+    };
+    let json_bytes = crate::canonical::report_bytes(report_json);
+
+    let s3 = S3Client::new(config.clone()).with_http_client(state.http.upload.clone());
+    let key = |filename: &str| s3.key(&format!("{}/{}/{}", chain_id, report.agent_id, filename));
+
+    let uploads = [
+        (files.md_filename, files.markdown.as_bytes().to_vec(), "text/markdown"),
+        (files.json_filename, json_bytes, "application/json"),
+    ];
+    for (filename, body, content_type) in uploads {
+        match s3.put_object(&key(filename), body, content_type).await {
+            Ok(url) => {
+                info!("Mirrored {} to {}", filename, url);
+                events.upload("s3", filename, Ok(&url)).await;
+            }
+            Err(e) => {
+                warn!("Failed to mirror {} to S3: {}", filename, e);
+                events.upload("s3", filename, Err(e.to_string())).await;
+            }
+        }
+    }
+}
+
+fn arweave_client(state: &AppState, private_key: &str) -> Result<IrysClient, WatchyError> {
+    Ok(IrysClient::new(Some(private_key))?
+        .with_uploader(uploader_from_config(&state.config, state.http.upload.clone()))
+        .with_http_client(state.http.upload.clone())
+        .with_gateway(&state.config.arweave_gateway)
+        .with_verification(state.config.arweave_verify_attempts))
+}
+
+/// Primary IPFS client and the optional replica pinning service
+fn ipfs_clients(state: &AppState) -> (IpfsClient, Option<PinningService>) {
+    let ipfs = IpfsClient::new(
+        state.config.ipfs_api_url.clone(),
+        state.config.ipfs_api_key.clone(),
+    )
+    .with_http_client(state.http.upload.clone())
+    .with_gateway(&state.config.ipfs_gateway);
+
+    let replica = match (
+        &state.config.ipfs_pin_service_url,
+        &state.config.ipfs_pin_service_token,
+    ) {
+        (Some(url), Some(token)) => {
+            Some(PinningService::new(url, token).with_http_client(state.http.upload.clone()))
+        }
+        _ => None,
+    };
+
+    (ipfs, replica)
+}
+
+/// Store the audited metadata bytes and link them from the report's snapshot
+///
+/// Best-effort: the snapshot hash is in the report either way.
+async fn pin_metadata_snapshot(
+    state: &AppState,
+    report: &mut AuditReport,
+    source: &[u8],
+    filename: &str,
+    private_key: &str,
+    events: &EventLog,
+) {
+    let destination = state.config.report_store.as_str();
+    let result = match state.config.report_store {
+        ReportStore::Arweave => match arweave_client(state, private_key) {
+            Ok(irys) => irys
+                .upload(
+                    source,
+                    "application/json",
+                    vec![("filename", filename), ("App-Name", "Watchy")],
+                )
+                .await
+                .map(|result| (result.arweave_url, result.uri)),
+            Err(e) => Err(e),
+        },
+        ReportStore::Ipfs => {
+            let (ipfs, replica) = ipfs_clients(state);
+            let data = source.to_vec();
+            pin_to_ipfs(state, &ipfs, replica.as_ref(), data, filename, "application/json")
+                .await
+                .map(|cid| (ipfs.gateway_url(&cid), format!("ipfs://{}", cid)))
+        }
+    };
+
+    match result {
+        Ok((url, uri)) => {
+            info!("Metadata snapshot stored: {}", uri);
+            events.upload(destination, filename, Ok(&uri)).await;
+            if let Some(snapshot) = report.agent.metadata_snapshot.as_mut() {
+                snapshot.uri = Some(uri);
+                snapshot.url = Some(url);
+            }
+        }
+        Err(e) => {
+            warn!("Failed to store metadata snapshot: {}", e);
+            events.upload(destination, filename, Err(e.to_string())).await;
+        }
+    }
+}
+
+/// Upload to the primary IPFS provider, then replicate to the pinning service
+///
+/// Replication is best-effort: the CID is returned as soon as the primary has it.
+async fn pin_to_ipfs(
+    state: &AppState,
+    ipfs: &IpfsClient,
+    replica: Option<&PinningService>,
+    data: Vec<u8>,
+    filename: &str,
+    content_type: &str,
+) -> Result<String, WatchyError> {
+    let cid = ipfs.upload_bytes(data, filename, content_type).await?;
+
+    if let Some(replica) = replica {
+        match replica
+            .pin_and_wait(&cid, filename, state.config.ipfs_pin_poll_attempts)
+            .await
+        {
+            Ok(PinState::Pinned) => {}
+            Ok(pin_state) => {
+                warn!("Replica pin for {} still {:?} after polling", cid, pin_state);
+            }
+            Err(e) => {
+                warn!("Failed to replicate {} to pinning service: {}", cid, e);
+            }
+        }
+    }
+
+    Ok(cid)
+}
+
+/// Serialize the report and embed its signature
+async fn signed_report_json(
+    report: &AuditReport,
+    private_key: &str,
+) -> Result<serde_json::Value, WatchyError> {
+    let mut report_json = serde_json::to_value(report)
+        .map_err(|e| WatchyError::Internal(format!("Failed to serialize report: {}", e)))?;
+
+    let signature = sign_report(&report_json, private_key).await?;
+    info!(
+        "Report signed: {}...{}",
+        &signature[..10],
+        &signature[signature.len() - 8..]
+    );
+
+    if let Some(obj) = report_json.as_object_mut() {
+        obj.insert("signature".to_string(), serde_json::json!(signature));
+    }
+
+    Ok(report_json)
+}
+
+/// Submit on-chain feedback for the uploaded JSON report
+///
+/// `report_json` must be the exact JSON that was uploaded so feedbackHash
+/// matches the content at feedbackURI. Failures are logged, not returned.
+async fn submit_feedback(
+    report: &mut AuditReport,
+    chain_id: u64,
+    private_key: &str,
+    feedback_uri: &str,
+    report_json: &serde_json::Value,
+    events: &EventLog,
+) {
+    let chain = get_chain(chain_id);
+    let rpc_url = get_rpc_url(chain_id);
+
+    let (Some(chain), Some(rpc), Some(rep_addr)) =
+        (chain, rpc_url, chain.and_then(|c| c.reputation_address))
+    else {
+        info!(
+            "No reputation registry on chain {}, skipping on-chain feedback",
+            chain_id
+        );
+        return;
+    };
+
+    info!(
+        "Submitting on-chain feedback to {} ({})",
+        chain.name, rep_addr
+    );
+
+    let rep_client = match ReputationClient::new(&rpc, rep_addr, Some(private_key)) {
+        Ok(client) => client,
+        Err(e) => {
+            error!("Failed to create reputation client: {}", e);
+            return;
+        }
+    };
+
+    match rep_client
+        .submit_feedback(
+            report.agent_id,
+            report.scores.overall,
+            report.tag1.as_deref().unwrap_or(""),
+            report.tag2.as_deref().unwrap_or(""),
+            report.endpoint.as_deref(),
+            feedback_uri,
+            report_json,
+        )
+        .await
+    {
+        Ok(tx_hash) => {
+            info!(
+                "On-chain feedback submitted: {} (tx: {})",
+                feedback_uri, tx_hash
+            );
+            events
+                .record(
+                    AuditEvent::new(EventKind::FeedbackSubmitted, "On-chain feedback submitted")
+                        .with_phase("publish")
+                        .with_data(serde_json::json!({ "chainId": chain_id, "txHash": tx_hash })),
+                )
+                .await;
+            report.set_feedback_tx(chain_id, &tx_hash);
+        }
+        Err(e) => {
+            error!("Failed to submit on-chain feedback: {}", e);
+            events
+                .record(
+                    AuditEvent::new(EventKind::FeedbackFailed, "On-chain feedback failed")
+                        .with_phase("publish")
+                        .with_data(serde_json::json!({ "chainId": chain_id, "error": e.to_string() })),
+                )
+                .await;
+        }
+    }
+}
+
+/// Attest the published report on the audited chain via EAS
+///
+/// The recipient is the agent's owner, so attestations can be looked up per
+/// owner as well as by UID; the attestation expires with the report. Failures
+/// are logged, not returned.
+async fn attest_report(
+    eas: &EasConfig,
+    report: &mut AuditReport,
+    chain_id: u64,
+    private_key: &str,
+    report_uri: &str,
+    report_hash: [u8; 32],
+    events: &EventLog,
+) {
+    let (Some(rpc), Some(eas_address)) = (
+        get_rpc_url(chain_id),
+        get_chain(chain_id).and_then(|c| c.eas_address),
+    ) else {
+        info!("No EAS contract on chain {}, skipping attestation", chain_id);
+        return;
+    };
+
+    let recipient = report
+        .agent
+        .owner
+        .as_deref()
+        .and_then(|owner| owner.parse::<Address>().ok())
+        .unwrap_or(Address::ZERO);
+    let data = encode_audit_data(report.agent_id, report.scores.overall, report_hash, report_uri);
+
+    let result = match EasClient::new(&rpc, eas_address, private_key) {
+        Ok(client) => {
+            client
+                .attest(eas.schema_uid, recipient, report.valid_until.unwrap_or(0), data)
+                .await
+        }
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok((uid, tx_hash)) => {
+            info!("EAS attestation created: {} (tx: {})", uid, tx_hash);
+            events
+                .record(
+                    AuditEvent::new(EventKind::AttestationCreated, "EAS attestation created")
+                        .with_phase("publish")
+                        .with_data(serde_json::json!({
+                            "chainId": chain_id,
+                            "uid": uid,
+                            "txHash": tx_hash,
+                        })),
+                )
+                .await;
+            report.set_eas_attestation(EasAttestation {
+                chain_id,
+                uid,
+                schema_uid: eas.schema_uid.to_string(),
+                tx_hash,
+            });
+        }
+        Err(e) => {
+            error!("Failed to create EAS attestation: {}", e);
+            events
+                .record(
+                    AuditEvent::new(EventKind::AttestationFailed, "EAS attestation failed")
+                        .with_phase("publish")
+                        .with_data(serde_json::json!({ "chainId": chain_id, "error": e.to_string() })),
+                )
+                .await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arweave::ans104::DataItem;
+    use crate::s3::S3Config;
+    use alloy::primitives::keccak256;
+    use std::sync::Arc;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+    const FILES: ReportFiles<'static> = ReportFiles {
+        markdown: "# Audit report",
+        md_filename: "report.md",
+        json_filename: "report.json",
+    };
+
+    fn test_report() -> AuditReport {
+        let mut report = AuditReport::new(17, 8453, "0x8004", String::new(), None);
+        report.canonicalization = Some(crate::canonical::SCHEME.to_string());
+        report
+    }
+
+    fn keys(state: &AppState) -> SigningKeys<'_> {
+        let key = &state.config.wallet.signers[0];
+        SigningKeys {
+            report: key,
+            upload: key,
+            feedback: key,
+        }
+    }
+
+    /// The file part of a multipart upload
+    fn uploaded_file(request: &Request) -> Vec<u8> {
+        let find = |haystack: &[u8], needle: &[u8]| {
+            haystack.windows(needle.len()).position(|window| window == needle)
+        };
+        let start = find(&request.body, b"\r\n\r\n").unwrap() + 4;
+        let end = start + find(&request.body[start..], b"\r\n--").unwrap();
+        request.body[start..end].to_vec()
+    }
+
+    /// An IPFS API answering every upload with `cid`
+    async fn ipfs_api(cid: &str) -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v0/add"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "Hash": cid })),
+            )
+            .mount(&server)
+            .await;
+        server
+    }
+
+    /// A self-hosted bundler accepting every DataItem
+    async fn bundler() -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/tx"))
+            .respond_with(|request: &Request| {
+                let item = DataItem::from_bytes(&request.body).unwrap();
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "id": item.id() }))
+            })
+            .mount(&server)
+            .await;
+        server
+    }
+
+    /// State uploading to the mock IPFS API and bundler, with on-chain submission paused
+    fn uploading_state(
+        store: ReportStore,
+        ipfs: &MockServer,
+        arweave: &MockServer,
+    ) -> Arc<AppState> {
+        AppState::for_tests(|config| {
+            config.report_store = store;
+            config.ipfs_api_url = ipfs.uri();
+            config.ipfs_pin_service_url = None;
+            config.arweave_provider = crate::arweave::uploader::ArweaveProvider::Bundler;
+            config.arweave_upload_url = Some(arweave.uri());
+            config.arweave_verify_attempts = 0;
+            config.report_mirror = None;
+            config.paused_subsystems = vec![Subsystem::Onchain];
+        })
+    }
+
+    async fn requests(server: &MockServer) -> Vec<Request> {
+        server.received_requests().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_uploads_hash_to_feedback_hash() {
+        let (ipfs, arweave) = (ipfs_api("bafyreport").await, bundler().await);
+        let state = uploading_state(ReportStore::Ipfs, &ipfs, &arweave);
+        let events = EventLog::disabled();
+
+        // IPFS: the uploaded file is the hashed bytes
+        let mut report = test_report();
+        let (uri, report_json) = publish_to_ipfs(&state, &mut report, &FILES, keys(&state), &events)
+            .await
+            .unwrap();
+        assert_eq!(uri, "ipfs://bafyreport");
+        let uploaded = uploaded_file(requests(&ipfs).await.last().unwrap());
+        assert_eq!(keccak256(&uploaded), compute_feedback_hash(&report_json).unwrap());
+
+        // Arweave: the DataItem's data is the hashed bytes
+        let mut report = test_report();
+        let (_, report_json) =
+            publish_to_arweave(&state, &mut report, &FILES, keys(&state), &events)
+                .await
+                .unwrap();
+        let uploaded = DataItem::from_bytes(&requests(&arweave).await.last().unwrap().body)
+            .unwrap()
+            .data;
+        assert_eq!(keccak256(&uploaded), compute_feedback_hash(&report_json).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_publish_to_primary_store() {
+        let events = EventLog::disabled();
+
+        // Both reports go to IPFS only; the feedbackURI is the CID
+        let (ipfs, arweave) = (ipfs_api("bafyreport").await, bundler().await);
+        let state = uploading_state(ReportStore::Ipfs, &ipfs, &arweave);
+        let mut report = test_report();
+        publish_report(&state, &mut report, None, "aud_1", 8453, keys(&state), &events)
+            .await
+            .unwrap();
+        assert_eq!(report.report_json_uri.as_deref(), Some("ipfs://bafyreport"));
+        assert_eq!(report.report_markdown_uri.as_deref(), Some("ipfs://bafyreport"));
+        assert!(report.report_json_hash.is_some());
+        assert_eq!((requests(&ipfs).await.len(), requests(&arweave).await.len()), (2, 0));
+
+        // ...or to Arweave only
+        let (ipfs, arweave) = (ipfs_api("bafyreport").await, bundler().await);
+        let state = uploading_state(ReportStore::Arweave, &ipfs, &arweave);
+        let mut report = test_report();
+        publish_report(&state, &mut report, None, "aud_1", 8453, keys(&state), &events)
+            .await
+            .unwrap();
+        assert!(report.report_json_uri.as_deref().unwrap().starts_with("ar://"));
+        assert!(report.report_markdown_uri.as_deref().unwrap().starts_with("ar://"));
+        assert_eq!((requests(&ipfs).await.len(), requests(&arweave).await.len()), (0, 2));
+
+        // Without the JSON stored, nothing is published
+        let failing = MockServer::start().await;
+        let state = uploading_state(ReportStore::Ipfs, &failing, &arweave);
+        let mut report = test_report();
+        let published =
+            publish_report(&state, &mut report, None, "aud_1", 8453, keys(&state), &events).await;
+        assert!(published.is_err());
+        assert_eq!(report.report_json_uri, None);
+    }
+
+    fn mirror(server: &MockServer) -> S3Config {
+        S3Config {
+            endpoint: server.uri(),
+            bucket: "reports".to_string(),
+            region: "us-east-1".to_string(),
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "secret".to_string(),
+            prefix: "watchy/".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mirror_to_s3() {
+        let bucket = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&bucket)
+            .await;
+        let state = AppState::for_tests(|config| config.report_mirror = Some(mirror(&bucket)));
+        let report = test_report();
+        let report_json = serde_json::to_value(&report).unwrap();
+        mirror_to_s3(&state, &report, 8453, &FILES, &report_json, &EventLog::disabled()).await;
+
+        // `{prefix}{chain_id}/{agent_id}/{filename}` in the bucket
+        let uploads = requests(&bucket).await;
+        let paths: Vec<&str> = uploads.iter().map(|request| request.url.path()).collect();
+        assert_eq!(
+            paths,
+            vec!["/reports/watchy/8453/17/report.md", "/reports/watchy/8453/17/report.json"]
+        );
+        assert_eq!(uploads[0].body, FILES.markdown.as_bytes());
+        assert_eq!(uploads[1].body, crate::canonical::report_bytes(&report_json));
+    }
+
+    #[tokio::test]
+    async fn test_mirror_failure_keeps_publish() {
+        let (ipfs, bucket) = (ipfs_api("bafyreport").await, MockServer::start().await);
+        Mock::given(method("PUT"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&bucket)
+            .await;
+        let state = AppState::for_tests(|config| {
+            config.report_store = ReportStore::Ipfs;
+            config.ipfs_api_url = ipfs.uri();
+            config.ipfs_pin_service_url = None;
+            config.report_mirror = Some(mirror(&bucket));
+            config.paused_subsystems = vec![Subsystem::Onchain];
+        });
+        let mut report = test_report();
+        let events = EventLog::disabled();
+        publish_report(&state, &mut report, None, "aud_1", 8453, keys(&state), &events)
+            .await
+            .unwrap();
+        assert_eq!(report.report_json_uri.as_deref(), Some("ipfs://bafyreport"));
+        assert!(report.report_json_hash.is_some());
+        // Both objects were attempted
+        assert_eq!(requests(&bucket).await.len(), 2);
+    }
+
+    /// A pinning service answering pin requests with `status`, then reporting `polled`
+    async fn replica(status: u16, polled: &str) -> MockServer {
+        let server = MockServer::start().await;
+        let pin = |state: &str| serde_json::json!({ "requestid": "req1", "status": state });
+        Mock::given(method("POST"))
+            .and(path("/pins"))
+            .respond_with(ResponseTemplate::new(status).set_body_json(pin("queued")))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/pins/req1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(pin(polled)))
+            .mount(&server)
+            .await;
+        server
+    }
+
+    /// Upload through `ipfs`, replicating to the mock pinning service
+    async fn pin_replicated(
+        state: &AppState,
+        ipfs: &IpfsClient,
+        replica: &MockServer,
+    ) -> Result<String, WatchyError> {
+        let replica = PinningService::new(&replica.uri(), "token");
+        let data = b"{}".to_vec();
+        pin_to_ipfs(state, ipfs, Some(&replica), data, "report.json", "application/json").await
+    }
+
+    // Replica polls are spaced by seconds: the paused clock skips the waits
+    #[tokio::test(start_paused = true)]
+    async fn test_pin_to_ipfs_replica() {
+        let state = AppState::for_tests(|config| config.ipfs_pin_poll_attempts = 2);
+        let primary = ipfs_api("bafy1").await;
+        let ipfs = IpfsClient::new(primary.uri(), None);
+
+        // Pinned on the first poll
+        let pinned = replica(200, "pinned").await;
+        assert_eq!(pin_replicated(&state, &ipfs, &pinned).await.unwrap(), "bafy1");
+        assert_eq!(requests(&pinned).await.len(), 2);
+
+        // The CID is returned whether the replica refuses, fails or never finishes
+        for (status, polled) in [(500, "queued"), (200, "failed"), (200, "pinning")] {
+            let replica = replica(status, polled).await;
+            assert_eq!(pin_replicated(&state, &ipfs, &replica).await.unwrap(), "bafy1");
+        }
+
+        // Nothing is replicated when the primary upload fails
+        let failing = MockServer::start().await;
+        let ipfs = IpfsClient::new(failing.uri(), None);
+        let pinned = replica(200, "pinned").await;
+        assert!(pin_replicated(&state, &ipfs, &pinned).await.is_err());
+        assert!(requests(&pinned).await.is_empty());
+    }
+
+    #[test]
+    fn test_feedback_decision() {
+        let submit = feedback_decision(FeedbackPolicy::Min(70), 1, false, 80);
+        assert!(submit.submit);
+        assert_eq!(submit.policy, "min:70");
+        assert_eq!(submit.reason, None);
+
+        let filtered = feedback_decision(FeedbackPolicy::Min(70), 1, false, 60);
+        assert!(!filtered.submit);
+        assert!(filtered.reason.unwrap().contains("outside the policy"));
+
+        let paused = feedback_decision(FeedbackPolicy::Always, 1, true, 80);
+        assert!(!paused.submit);
+        assert!(paused.reason.unwrap().contains("paused"));
+    }
+}
@@ -0,0 +1,309 @@
+//! Liveness, readiness and the startup gate
+//!
+//! The server listens as soon as storage is up, so `GET /health/live` answers
+//! while the startup checks run in the background. Those open a connection to
+//! the RPC of every chain with a registry (warming the shared RPC client's
+//! pool) and check that it answers `eth_chainId` with the configured chain
+//! ID, which catches a wrong `RPC_URL_<CHAIN>` override. `GET /health/ready`
+//! stays 503 until every chain in `READINESS_CHAINS` has a valid RPC; other
+//! chains only log a warning. Failed checks are retried every
+//! `STARTUP_RETRY_SECS`. Once ready, the checks rerun every
+//! `READINESS_RECHECK_SECS` and a failing required chain makes the service
+//! unready again until it recovers.
+
+use serde::Serialize;
+use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tokio::task::JoinSet;
+use tracing::{debug, info, warn};
+
+use crate::blockchain::account::AccountClient;
+use crate::chains::{self, ChainType};
+use crate::AppState;
+
+/// Startup check settings
+#[derive(Debug, Clone)]
+pub struct ReadinessConfig {
+    /// Chains that need a valid RPC before the service reports ready
+    pub chains: Vec<u64>,
+    /// Timeout for each RPC's `eth_chainId` call
+    pub rpc_timeout_secs: u64,
+    /// Wait between failed startup checks
+    pub retry_secs: u64,
+    /// Wait between checks once ready
+    pub recheck_secs: u64,
+}
+
+impl ReadinessConfig {
+    /// Load from `READINESS_CHAINS` (comma-separated chain IDs, default: the
+    /// default chain), `STARTUP_RPC_TIMEOUT_SECS`, `STARTUP_RETRY_SECS` and
+    /// `READINESS_RECHECK_SECS`
+    pub fn from_env(default_chain_id: u64) -> anyhow::Result<Self> {
+        let chains = match env::var("READINESS_CHAINS") {
+            Ok(value) => value
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(|s| {
+                    s.parse().map_err(|_| {
+                        anyhow::anyhow!("Invalid chain ID '{}' in READINESS_CHAINS", s)
+                    })
+                })
+                .collect::<anyhow::Result<Vec<u64>>>()?,
+            Err(_) => vec![default_chain_id],
+        };
+        for &chain_id in &chains {
+            match chains::get_chain(chain_id) {
+                Some(chain) if chain.chain_type == ChainType::Evm => {}
+                Some(chain) => {
+                    anyhow::bail!("READINESS_CHAINS: {} is not an EVM chain", chain.name)
+                }
+                None => anyhow::bail!("READINESS_CHAINS: unsupported chain {}", chain_id),
+            }
+        }
+
+        Ok(Self {
+            chains,
+            rpc_timeout_secs: env::var("STARTUP_RPC_TIMEOUT_SECS")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()?,
+            retry_secs: env::var("STARTUP_RETRY_SECS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()?,
+            recheck_secs: env::var("READINESS_RECHECK_SECS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse::<u64>()?
+                .max(1),
+        })
+    }
+}
+
+/// Result of a chain's RPC check
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainStatus {
+    pub chain_id: u64,
+    pub name: String,
+    /// Needed for readiness
+    pub required: bool,
+    /// The chain's RPC answered with the configured chain ID
+    pub ok: bool,
+    /// Why the check failed (RPC URLs are left out: they may carry API keys)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Startup state shared with the health handlers
+pub struct Readiness {
+    ready: AtomicBool,
+    /// Was ready at least once
+    started_up: AtomicBool,
+    started: Instant,
+    chains: RwLock<Vec<ChainStatus>>,
+}
+
+impl Default for Readiness {
+    fn default() -> Self {
+        Self {
+            ready: AtomicBool::new(false),
+            started_up: AtomicBool::new(false),
+            started: Instant::now(),
+            chains: RwLock::new(vec![]),
+        }
+    }
+}
+
+impl Readiness {
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Acquire)
+    }
+
+    /// "starting", "ready", or "degraded" once a recheck failed
+    pub fn status(&self) -> &'static str {
+        match (self.is_ready(), self.started_up.load(Ordering::Acquire)) {
+            (true, _) => "ready",
+            (false, false) => "starting",
+            (false, true) => "degraded",
+        }
+    }
+
+    /// Record the result of a check run; returns whether readiness changed
+    fn set_ready(&self, ready: bool) -> bool {
+        if ready {
+            self.started_up.store(true, Ordering::Release);
+        }
+        self.ready.swap(ready, Ordering::AcqRel) != ready
+    }
+
+    pub fn uptime_secs(&self) -> u64 {
+        self.started.elapsed().as_secs()
+    }
+
+    /// Chain checks of the latest run
+    pub async fn chains(&self) -> Vec<ChainStatus> {
+        self.chains.read().await.clone()
+    }
+}
+
+/// Whether every required chain has a passing check
+pub fn all_required_ok(statuses: &[ChainStatus], required: &[u64]) -> bool {
+    required
+        .iter()
+        .all(|&chain_id| statuses.iter().any(|s| s.chain_id == chain_id && s.ok))
+}
+
+/// Compare the chain ID an RPC reports with the configured one
+pub fn check_chain_id(expected: u64, reported: u64) -> Result<(), String> {
+    if reported == expected {
+        Ok(())
+    } else {
+        Err(format!(
+            "RPC reports chain ID {}, expected {}",
+            reported, expected
+        ))
+    }
+}
+
+/// Run the startup checks until the required chains pass, then keep rechecking
+/// them and mark the service ready or unready on each transition
+pub fn spawn_startup_checks(state: Arc<AppState>) {
+    let config = state.config.readiness.clone();
+    info!(
+        "Startup checks: readiness waits for chains {:?}",
+        config.chains
+    );
+
+    tokio::spawn(async move {
+        loop {
+            let statuses = check_chains(&config).await;
+            let ready = all_required_ok(&statuses, &config.chains);
+            for status in statuses.iter().filter(|s| !s.ok) {
+                warn!(
+                    "Readiness check failed for {} ({}): {}",
+                    status.name,
+                    status.chain_id,
+                    status.error.as_deref().unwrap_or("unknown error")
+                );
+            }
+            *state.readiness.chains.write().await = statuses;
+
+            if state.readiness.set_ready(ready) {
+                if ready {
+                    info!(
+                        "Readiness checks passed after {}s, ready for traffic",
+                        state.readiness.uptime_secs()
+                    );
+                } else {
+                    warn!("A required chain's RPC failed, no longer ready");
+                }
+            }
+            let wait = if ready {
+                config.recheck_secs
+            } else {
+                config.retry_secs
+            };
+            tokio::time::sleep(Duration::from_secs(wait)).await;
+        }
+    });
+}
+
+/// Check the required chains and every EVM chain with a registry, concurrently
+pub async fn check_chains(config: &ReadinessConfig) -> Vec<ChainStatus> {
+    let mut chain_ids: Vec<u64> = chains::chains_with_registry()
+        .iter()
+        .filter(|chain| chain.chain_type == ChainType::Evm)
+        .map(|chain| chain.chain_id)
+        .chain(config.chains.iter().copied())
+        .collect();
+    chain_ids.sort_unstable();
+    chain_ids.dedup();
+
+    let timeout = Duration::from_secs(config.rpc_timeout_secs);
+    let mut checks = JoinSet::new();
+    for chain_id in chain_ids {
+        let required = config.chains.contains(&chain_id);
+        checks.spawn(async move {
+            let result = check_chain(chain_id, timeout).await;
+            ChainStatus {
+                chain_id,
+                name: chains::get_chain(chain_id)
+                    .map(|chain| chain.name.to_string())
+                    .unwrap_or_default(),
+                required,
+                ok: result.is_ok(),
+                error: result.err(),
+            }
+        });
+    }
+
+    let mut statuses = vec![];
+    while let Some(status) = checks.join_next().await {
+        if let Ok(status) = status {
+            statuses.push(status);
+        }
+    }
+    statuses.sort_by_key(|s| s.chain_id);
+    statuses
+}
+
+/// Check the RPC the service uses for the chain (its override or first default)
+async fn check_chain(chain_id: u64, timeout: Duration) -> Result<(), String> {
+    let rpc = chains::get_rpc_url(chain_id).ok_or_else(|| "No RPC configured".to_string())?;
+    let client = AccountClient::new(&rpc).map_err(|e| e.to_string())?;
+    match tokio::time::timeout(timeout, client.chain_id()).await {
+        Ok(Ok(reported)) => check_chain_id(chain_id, reported)?,
+        Ok(Err(e)) => {
+            debug!("Chain {} RPC check failed: {}", chain_id, e);
+            return Err("RPC request failed".to_string());
+        }
+        Err(_) => return Err("RPC timed out".to_string()),
+    }
+    debug!("Chain {} RPC ready", chain_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(chain_id: u64, ok: bool) -> ChainStatus {
+        ChainStatus {
+            chain_id,
+            name: String::new(),
+            required: chain_id == 8453,
+            ok,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_readiness_gate() {
+        assert!(check_chain_id(8453, 8453).is_ok());
+        assert!(check_chain_id(8453, 84532).is_err());
+
+        let required = [8453];
+        assert!(all_required_ok(
+            &[status(8453, true), status(1, false)],
+            &required
+        ));
+        assert!(!all_required_ok(
+            &[status(8453, false), status(1, true)],
+            &required
+        ));
+        // A required chain without a check result isn't ready either
+        assert!(!all_required_ok(&[status(1, true)], &required));
+
+        // Readiness follows each check run, not just the first success
+        let readiness = Readiness::default();
+        assert!(!readiness.is_ready());
+        assert_eq!(readiness.status(), "starting");
+        assert!(readiness.set_ready(true));
+        assert!(!readiness.set_ready(true));
+        assert_eq!(readiness.status(), "ready");
+        assert!(readiness.set_ready(false));
+        assert!(!readiness.is_ready());
+        assert_eq!(readiness.status(), "degraded");
+    }
+}
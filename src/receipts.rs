@@ -0,0 +1,282 @@
+//! Signed audit receipts
+//!
+//! `POST /audit` (and a retry) returns a receipt that the audit was requested;
+//! `GET /audit/:audit_id` returns one that it completed once it has. A receipt
+//! names the audit, agent, chain and time, and a keyed hash of the API key
+//! that requested it. It is signed by the active primary signer over a
+//! receipt-only domain, so a requester can prove to a third party which audit
+//! it requested and obtained without disclosing its key. `POST /verify/receipt`
+//! checks one, and only accepts Watchy's own signers.
+
+use alloy::primitives::{keccak256, Address, PrimitiveSignature, B256};
+use alloy::signers::local::PrivateKeySigner;
+use alloy::signers::Signer;
+use axum::http::HeaderMap;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use tracing::warn;
+
+use crate::canonical;
+use crate::config::Config;
+use crate::store::{AuditJob, RetiredSigner};
+use crate::types::AuditStatus;
+use crate::wallet::{SignerKey, WalletConfig};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Prefixed to the signed bytes, so a receipt signature can't pass for a
+/// report's (or the other way around)
+const DOMAIN: &[u8] = b"watchy-receipt-v1\n";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiptKind {
+    Requested,
+    Completed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Receipt {
+    pub kind: ReceiptKind,
+    pub audit_id: String,
+    /// `None` for an unregistered agent (`POST /audit/by-uri`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent_id: Option<u64>,
+    pub chain_id: u64,
+    /// Attempt of the audit (see `POST /audit/:audit_id/retry`)
+    pub attempt: u32,
+    /// When the audit was requested or completed (unix seconds)
+    pub timestamp: u64,
+    /// Keyed hash of the requesting API key (none in open mode and for automatic audits)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requester: Option<String>,
+    /// Completed receipts: final status, overall score and the report's JSON hash
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<AuditStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub report_hash: Option<String>,
+    /// Always canonical JSON, so receipts verify like current reports
+    pub canonicalization: String,
+    pub signer: String,
+    pub signature: String,
+}
+
+/// Id of the API key of a request, `None` when keys aren't required
+///
+/// Keyed with `RECEIPT_SECRET` (or the primary signer's key), so a key can't
+/// be confirmed from a receipt by hashing guesses.
+pub fn requester(config: &Config, headers: &HeaderMap) -> Option<String> {
+    if config.api_key.is_none() && config.tenant_keys.is_empty() {
+        return None;
+    }
+    let secret = config.receipt_secret.as_deref().or(config.private_key())?;
+    let key = headers.get("X-API-Key").and_then(|v| v.to_str().ok())?;
+    Some(key_id(secret, key))
+}
+
+/// `0x`-prefixed hex HMAC-SHA256 of an API key
+pub fn key_id(secret: &str, key: &str) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(key.as_bytes());
+    format!("0x{}", hex::encode(mac.finalize().into_bytes()))
+}
+
+impl Receipt {
+    /// Receipt that an attempt of an audit was requested
+    pub fn requested(
+        audit_id: &str,
+        agent_id: Option<u64>,
+        chain_id: u64,
+        attempt: u32,
+        requester: Option<String>,
+        timestamp: u64,
+    ) -> Self {
+        Self {
+            kind: ReceiptKind::Requested,
+            audit_id: audit_id.to_string(),
+            agent_id,
+            chain_id,
+            attempt,
+            timestamp,
+            requester,
+            status: None,
+            score: None,
+            report_hash: None,
+            canonicalization: crate::canonical::SCHEME.to_string(),
+            signer: String::new(),
+            signature: String::new(),
+        }
+    }
+
+    /// Receipt that a job completed or failed; `None` while it runs
+    pub fn completed(job: &AuditJob) -> Option<Self> {
+        let timestamp = job.completed_at?;
+        if matches!(job.status, AuditStatus::Pending | AuditStatus::InProgress) {
+            return None;
+        }
+        let requester = job.params.as_ref().and_then(|p| p.requester.clone());
+        let report = job.result.as_ref();
+        Some(Self {
+            kind: ReceiptKind::Completed,
+            status: Some(job.status.clone()),
+            score: report.map(|r| r.scores.overall),
+            report_hash: report.and_then(|r| r.report_json_hash.clone()),
+            ..Self::requested(
+                &job.id,
+                job.agent_id,
+                job.chain_id,
+                job.attempt,
+                requester,
+                timestamp,
+            )
+        })
+    }
+
+    /// Sign with `key`; `None` if signing fails
+    pub async fn sign(mut self, key: &SignerKey) -> Option<Self> {
+        self.signer = key.address.clone();
+        let signed = async {
+            let digest = digest(&self)?;
+            let signer: PrivateKeySigner =
+                key.private_key.trim_start_matches("0x").parse().ok()?;
+            signer.sign_hash(&digest).await.ok()
+        };
+        match signed.await {
+            Some(signature) => {
+                self.signature = format!("0x{}", hex::encode(signature.as_bytes()));
+                Some(self)
+            }
+            None => {
+                warn!("Receipt for audit {} not signed", self.audit_id);
+                None
+            }
+        }
+    }
+
+    /// Whether the signature is valid and `signer` is one of Watchy's active
+    /// report signers
+    ///
+    /// A retired signer's receipts don't count: whoever holds its key can set
+    /// `timestamp` to any time before the retirement.
+    pub fn verify(&self, wallet: &WalletConfig, retired: &[RetiredSigner]) -> bool {
+        wallet.is_report_signer(&self.signer, None, retired)
+            && self.signature_matches()
+    }
+
+    fn signature_matches(&self) -> bool {
+        let recovered = || {
+            let bytes = hex::decode(self.signature.trim_start_matches("0x")).ok()?;
+            let signature = PrimitiveSignature::try_from(bytes.as_slice()).ok()?;
+            signature.recover_address_from_prehash(&digest(self)?).ok()
+        };
+        let expected = self.signer.parse::<Address>().ok();
+        expected.is_some() && recovered() == expected
+    }
+}
+
+/// keccak256 of the domain prefix and the receipt's canonical JSON
+fn digest(receipt: &Receipt) -> Option<B256> {
+    let unsigned = unsigned_json(receipt)?;
+    Some(keccak256([DOMAIN, &canonical::report_bytes(&unsigned)].concat()))
+}
+
+/// The receipt's JSON without `signature`, as signed
+fn unsigned_json(receipt: &Receipt) -> Option<Value> {
+    let mut json = serde_json::to_value(receipt).ok()?;
+    json.as_object_mut()?.remove("signature");
+    Some(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const ADDRESS: &str = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+    const OTHER_KEY: &str = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+
+    fn wallet() -> WalletConfig {
+        let key = SignerKey {
+            label: "key:0".to_string(),
+            private_key: KEY.to_string(),
+            address: ADDRESS.to_string(),
+        };
+        WalletConfig {
+            mode: crate::wallet::KeyMode::PrivateKey,
+            private_key: Some(KEY.to_string()),
+            address: Some(ADDRESS.to_string()),
+            signers: vec![key],
+            rotation: crate::wallet::RotationPolicy::Fixed,
+            upload_signer: None,
+            feedback_signer: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_receipt_signature() {
+        let wallet = wallet();
+        let requester = Some(key_id("secret", "sk_1"));
+        let receipt = Receipt::requested("aud_1", Some(17), 8453, 1, requester, 1_700)
+            .sign(&wallet.signers[0])
+            .await
+            .unwrap();
+        assert_eq!(receipt.signer, ADDRESS);
+        assert!(receipt.verify(&wallet, &[]));
+
+        let mut tampered = receipt.clone();
+        tampered.agent_id = Some(18);
+        assert!(!tampered.verify(&wallet, &[]));
+        tampered = receipt.clone();
+        tampered.requester = Some(key_id("secret", "sk_2"));
+        assert!(!tampered.verify(&wallet, &[]));
+
+        // A receipt can't be replayed as a report signature
+        let unsigned = unsigned_json(&receipt).unwrap();
+        let report_valid = crate::arweave::irys::verify_report_signature(
+            &unsigned,
+            &receipt.signature,
+            ADDRESS,
+        );
+        assert!(!report_valid.unwrap());
+
+        // Self-signed by a stranger
+        let stranger = SignerKey {
+            label: "key:0".to_string(),
+            private_key: OTHER_KEY.to_string(),
+            address: "0x70997970C51812dc3A010C7d01b50e0d17dc79C8".to_string(),
+        };
+        let forged = Receipt::requested("aud_1", Some(17), 8453, 1, None, 1_700)
+            .sign(&stranger)
+            .await
+            .unwrap();
+        assert!(forged.signature_matches());
+        assert!(!forged.verify(&wallet, &[]));
+
+        // Retired before the receipt's time
+        let retired = [RetiredSigner {
+            address: ADDRESS.to_string(),
+            retired_at: 1_000,
+        }];
+        assert!(!receipt.verify(&wallet, &retired));
+
+        // Backdated to before the retirement with the retired key
+        let backdated = Receipt::requested("aud_2", Some(17), 8453, 1, None, 900)
+            .sign(&wallet.signers[0])
+            .await
+            .unwrap();
+        assert!(backdated.signature_matches());
+        assert!(!backdated.verify(&wallet, &retired));
+    }
+
+    #[test]
+    fn test_key_id() {
+        assert_eq!(key_id("secret", "sk_1"), key_id("secret", "sk_1"));
+        assert_ne!(key_id("secret", "sk_1"), key_id("other", "sk_1"));
+        assert_eq!(key_id("secret", "sk_1").len(), 66);
+    }
+}
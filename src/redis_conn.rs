@@ -0,0 +1,389 @@
+//! Redis connections for the audit store
+//!
+//! A single server, a Cluster or a Sentinel-managed master, behind one
+//! connection type the store issues commands on. It's a cheap clone of
+//! multiplexed connections, so commands run concurrently without a lock
+//! around the connection. Connection failures after
+//! startup mark the store degraded (reported by `/health`) until a command
+//! succeeds again:
+//!
+//! - single: the connection manager reconnects in the background with backoff
+//! - cluster: the cluster client re-routes and reconnects to nodes itself
+//! - sentinel: the current master is looked up again, backing off between attempts
+//!
+//! SCAN only walks the node it's sent to, so [`RedisConnection::scan_match`]
+//! sends it to every primary of a cluster.
+
+use redis::aio::{ConnectionLike, ConnectionManager, MultiplexedConnection};
+use redis::cluster::ClusterClient;
+use redis::cluster_async::ClusterConnection;
+use redis::cluster_routing::{Route, RoutingInfo, SingleNodeRoutingInfo, SlotAddr};
+use redis::sentinel::{SentinelClient, SentinelServerType};
+use redis::{AsyncCommands, Client, Cmd, Pipeline, RedisError, RedisFuture, Value};
+use std::env;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+/// First wait before looking up the master again, doubled per failure
+const SENTINEL_BACKOFF_MIN: Duration = Duration::from_millis(500);
+const SENTINEL_BACKOFF_MAX: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedisMode {
+    Single,
+    Cluster,
+    Sentinel,
+}
+
+impl RedisMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RedisMode::Single => "single",
+            RedisMode::Cluster => "cluster",
+            RedisMode::Sentinel => "sentinel",
+        }
+    }
+}
+
+/// Where the store's Redis lives
+#[derive(Debug, Clone)]
+pub struct RedisConfig {
+    pub mode: RedisMode,
+    /// Server URL, cluster seed nodes or sentinel URLs
+    pub urls: Vec<String>,
+    /// Master name monitored by the sentinels
+    pub sentinel_master: String,
+}
+
+impl RedisConfig {
+    /// Load from `REDIS_URL` (comma-separated for cluster/sentinel),
+    /// `REDIS_MODE` (`single`, `cluster` or `sentinel`) and
+    /// `REDIS_SENTINEL_MASTER` (default `mymaster`); `None` if `REDIS_URL` is unset
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        let Ok(value) = env::var("REDIS_URL") else {
+            return Ok(None);
+        };
+        let urls: Vec<String> = value
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        let mode = match env::var("REDIS_MODE").as_deref() {
+            Err(_) | Ok("single") => RedisMode::Single,
+            Ok("cluster") => RedisMode::Cluster,
+            Ok("sentinel") => RedisMode::Sentinel,
+            Ok(other) => anyhow::bail!(
+                "Invalid REDIS_MODE '{}' (expected single, cluster or sentinel)",
+                other
+            ),
+        };
+        if mode == RedisMode::Single && urls.len() > 1 {
+            anyhow::bail!("REDIS_URL lists several servers: set REDIS_MODE to cluster or sentinel");
+        }
+
+        Ok(Some(Self {
+            mode,
+            urls,
+            sentinel_master: env::var("REDIS_SENTINEL_MASTER")
+                .unwrap_or_else(|_| "mymaster".to_string()),
+        }))
+    }
+}
+
+/// Whether Redis has been failing since startup
+#[derive(Debug, Default)]
+pub struct RedisHealth {
+    /// Unix time of the first failure in the current outage (0 = healthy)
+    degraded_since: AtomicU64,
+}
+
+impl RedisHealth {
+    pub fn degraded_since(&self) -> Option<u64> {
+        match self.degraded_since.load(Ordering::Relaxed) {
+            0 => None,
+            since => Some(since),
+        }
+    }
+
+    pub(crate) fn record<T>(&self, result: &Result<T, RedisError>) {
+        match result {
+            Ok(_) => {
+                if self.degraded_since.swap(0, Ordering::Relaxed) != 0 {
+                    info!("Redis connection restored");
+                }
+            }
+            Err(e) if is_connection_error(e) => {
+                let now = chrono::Utc::now().timestamp().max(1) as u64;
+                if self
+                    .degraded_since
+                    .compare_exchange(0, now, Ordering::Relaxed, Ordering::Relaxed)
+                    .is_ok()
+                {
+                    warn!("Redis connection lost ({}): store is degraded", e);
+                }
+            }
+            Err(_) => {}
+        }
+    }
+}
+
+/// Failures that mean the server is unreachable, not that a command was wrong
+fn is_connection_error(e: &RedisError) -> bool {
+    e.is_io_error() || e.is_connection_dropped() || e.is_connection_refusal() || e.is_timeout()
+}
+
+/// Sentinel-discovered master, reconnected after failover
+struct SentinelConnection {
+    /// Current master; cloned out for each command
+    conn: RwLock<MultiplexedConnection>,
+    lookup: Mutex<SentinelLookup>,
+}
+
+struct SentinelLookup {
+    client: SentinelClient,
+    backoff: Duration,
+    next_attempt: Instant,
+}
+
+impl SentinelConnection {
+    fn conn(&self) -> MultiplexedConnection {
+        self.conn.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Look the master up again unless still backing off or already looking
+    async fn reconnect(&self) {
+        let Ok(mut lookup) = self.lookup.try_lock() else {
+            return;
+        };
+        if Instant::now() < lookup.next_attempt {
+            return;
+        }
+        match lookup.client.get_async_connection().await {
+            Ok(conn) => {
+                info!("Reconnected to the Redis master via sentinel");
+                *self.conn.write().unwrap_or_else(|e| e.into_inner()) = conn;
+                lookup.backoff = SENTINEL_BACKOFF_MIN;
+            }
+            Err(e) => {
+                warn!("Sentinel master lookup failed, retrying in {:?}: {}", lookup.backoff, e);
+                lookup.next_attempt = Instant::now() + lookup.backoff;
+                lookup.backoff = (lookup.backoff * 2).min(SENTINEL_BACKOFF_MAX);
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+enum Backend {
+    Single(ConnectionManager),
+    Cluster(ClusterConnection),
+    Sentinel(Arc<SentinelConnection>),
+}
+
+/// Connection the store sends commands on (clone it per command)
+#[derive(Clone)]
+pub struct RedisConnection {
+    backend: Backend,
+    health: Arc<RedisHealth>,
+}
+
+impl RedisConnection {
+    pub async fn connect(config: &RedisConfig) -> Result<Self, RedisError> {
+        let backend = match config.mode {
+            RedisMode::Single => {
+                let url = config.urls.first().map(String::as_str).unwrap_or_default();
+                Backend::Single(Client::open(url)?.get_connection_manager().await?)
+            }
+            RedisMode::Cluster => Backend::Cluster(
+                ClusterClient::new(config.urls.clone())?
+                    .get_async_connection()
+                    .await?,
+            ),
+            RedisMode::Sentinel => {
+                let mut client = SentinelClient::build(
+                    config.urls.clone(),
+                    config.sentinel_master.clone(),
+                    None,
+                    SentinelServerType::Master,
+                )?;
+                let conn = client.get_async_connection().await?;
+                Backend::Sentinel(Arc::new(SentinelConnection {
+                    conn: RwLock::new(conn),
+                    lookup: Mutex::new(SentinelLookup {
+                        client,
+                        backoff: SENTINEL_BACKOFF_MIN,
+                        next_attempt: Instant::now(),
+                    }),
+                }))
+            }
+        };
+        Ok(Self {
+            backend,
+            health: Arc::new(RedisHealth::default()),
+        })
+    }
+
+    pub fn health(&self) -> Arc<RedisHealth> {
+        self.health.clone()
+    }
+
+    /// Keys matching `pattern`, from every primary of a cluster
+    pub async fn scan_match(&mut self, pattern: &str) -> Result<Vec<String>, RedisError> {
+        let Backend::Cluster(conn) = &mut self.backend else {
+            let mut keys = Vec::new();
+            let mut iter = AsyncCommands::scan_match::<_, String>(self, pattern).await?;
+            while let Some(key) = iter.next_item().await {
+                keys.push(key);
+            }
+            return Ok(keys);
+        };
+
+        let random = RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random);
+        let slots = conn
+            .route_command(redis::cmd("CLUSTER").arg("SLOTS"), random)
+            .await;
+        self.health.record(&slots);
+        let mut keys = Vec::new();
+        for slot in primary_slots(&slots?) {
+            let route = RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(Route::new(
+                slot,
+                SlotAddr::Master,
+            )));
+            let mut cursor = 0u64;
+            loop {
+                let mut scan = redis::cmd("SCAN");
+                scan.arg(cursor).arg("MATCH").arg(pattern).arg("COUNT").arg(100);
+                let result = conn.route_command(&scan, route.clone()).await;
+                self.health.record(&result);
+                let (next, batch): (u64, Vec<String>) = redis::from_redis_value(&result?)?;
+                keys.extend(batch);
+                if next == 0 {
+                    break;
+                }
+                cursor = next;
+            }
+        }
+        Ok(keys)
+    }
+}
+
+/// One slot served by each primary, from a `CLUSTER SLOTS` reply
+fn primary_slots(reply: &Value) -> Vec<u16> {
+    let Value::Bulk(ranges) = reply else {
+        return Vec::new();
+    };
+    let mut seen = Vec::new();
+    let mut slots = Vec::new();
+    for range in ranges {
+        // [start, end, [host, port, id, ...], replicas...]
+        let Value::Bulk(fields) = range else { continue };
+        let (Some(Value::Int(start)), Some(Value::Bulk(primary))) = (fields.first(), fields.get(2))
+        else {
+            continue;
+        };
+        let address = format!("{:?}", &primary[..primary.len().min(2)]);
+        if !seen.contains(&address) {
+            seen.push(address);
+            slots.push(*start as u16);
+        }
+    }
+    slots
+}
+
+impl ConnectionLike for RedisConnection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        Box::pin(async move {
+            let result = match &mut self.backend {
+                Backend::Single(conn) => conn.req_packed_command(cmd).await,
+                Backend::Cluster(conn) => conn.req_packed_command(cmd).await,
+                Backend::Sentinel(sentinel) => {
+                    let result = sentinel.conn().req_packed_command(cmd).await;
+                    if result.as_ref().is_err_and(is_connection_error) {
+                        sentinel.reconnect().await;
+                    }
+                    result
+                }
+            };
+            self.health.record(&result);
+            result
+        })
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        cmd: &'a Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisFuture<'a, Vec<Value>> {
+        Box::pin(async move {
+            let result = match &mut self.backend {
+                Backend::Single(conn) => conn.req_packed_commands(cmd, offset, count).await,
+                Backend::Cluster(conn) => conn.req_packed_commands(cmd, offset, count).await,
+                Backend::Sentinel(sentinel) => {
+                    let result = sentinel.conn().req_packed_commands(cmd, offset, count).await;
+                    if result.as_ref().is_err_and(is_connection_error) {
+                        sentinel.reconnect().await;
+                    }
+                    result
+                }
+            };
+            self.health.record(&result);
+            result
+        })
+    }
+
+    fn get_db(&self) -> i64 {
+        match &self.backend {
+            Backend::Single(conn) => conn.get_db(),
+            Backend::Cluster(conn) => conn.get_db(),
+            Backend::Sentinel(sentinel) => sentinel.conn().get_db(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_primary_slots() {
+        let node = |host: &str, port: i64| {
+            Value::Bulk(vec![Value::Data(host.as_bytes().to_vec()), Value::Int(port)])
+        };
+        let range = |start: i64, end: i64, nodes: Vec<Value>| {
+            Value::Bulk([vec![Value::Int(start), Value::Int(end)], nodes].concat())
+        };
+        let reply = Value::Bulk(vec![
+            range(0, 5460, vec![node("10.0.0.1", 6379), node("10.0.0.4", 6379)]),
+            range(5461, 10922, vec![node("10.0.0.2", 6379)]),
+            range(10923, 12000, vec![node("10.0.0.3", 6379)]),
+            // A primary serving two ranges is scanned once
+            range(12001, 16383, vec![node("10.0.0.1", 6379)]),
+        ]);
+        assert_eq!(primary_slots(&reply), vec![0, 5461, 10923]);
+        assert!(primary_slots(&Value::Nil).is_empty());
+    }
+
+    #[test]
+    fn test_health_transitions() {
+        let health = RedisHealth::default();
+        let refused = RedisError::from(std::io::Error::from(std::io::ErrorKind::ConnectionRefused));
+        let wrong_type = RedisError::from((redis::ErrorKind::TypeError, "wrong type"));
+
+        health.record::<()>(&Err(wrong_type));
+        assert!(health.degraded_since().is_none());
+        health.record::<()>(&Err(refused));
+        let since = health.degraded_since().unwrap();
+        health.record::<()>(&Err(RedisError::from(std::io::Error::from(
+            std::io::ErrorKind::BrokenPipe,
+        ))));
+        assert_eq!(health.degraded_since(), Some(since));
+        health.record(&Ok(()));
+        assert!(health.degraded_since().is_none());
+    }
+}
@@ -0,0 +1,328 @@
+//! Reloading settings without a restart
+//!
+//! Score weights, allowed chains, audit quotas, the probe timeout and the
+//! request limits live in `LiveSettings`, which `POST /admin/reload` rebuilds
+//! from the settings file (`watchy.toml`), as does a watcher that checks the
+//! file every `CONFIG_WATCH_SECS`. An audit takes a snapshot when it starts, so
+//! audits in flight finish with the settings they started with. Other changed
+//! keys are left alone and reported as needing a restart; variables set in the
+//! environment still win over the file. The environment itself is never
+//! changed after startup: reloaded values only go into `LiveSettings`.
+
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::env;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::audit::scoring::ScoreWeights;
+use crate::config::{self, Config, Vars};
+use crate::config_file::ConfigFile;
+use crate::http::{HttpTimeouts, ProbeClients};
+use crate::server::ServerLimits;
+use crate::usage::UsageConfig;
+use crate::AppState;
+
+/// Variables a reload applies
+pub const LIVE_VARS: &[&str] = &[
+    "SCORE_WEIGHTS",
+    "SERVICE_WEIGHTS",
+    "ALLOWED_CHAINS",
+    "AUDIT_QUOTA_PER_MONTH",
+    "TENANT_AUDIT_QUOTAS",
+    "HTTP_PROBE_TIMEOUT_SECS",
+    "MAX_REQUEST_BODY_BYTES",
+    "MAX_CONCURRENT_REQUESTS",
+    "REQUEST_TIMEOUT_SECS",
+];
+
+/// Settings that can change while the service runs
+pub struct LiveSettings {
+    pub score_weights: ScoreWeights,
+    /// Chains accepted for audits, registrations and subscriptions (None = all)
+    pub allowed_chains: Option<Vec<u64>>,
+    pub usage: UsageConfig,
+    /// Endpoint probing clients, bound by the probe timeout
+    pub probes: ProbeClients,
+    /// Request body, concurrency and timeout limits (the header read timeout
+    /// stays as loaded at startup)
+    pub limits: ServerLimits,
+}
+
+impl LiveSettings {
+    /// Settings as loaded at startup
+    pub fn new(config: &Config, probes: ProbeClients) -> Self {
+        Self {
+            score_weights: config.score_weights.clone(),
+            allowed_chains: config.allowed_chains.clone(),
+            usage: config.usage.clone(),
+            probes,
+            limits: config.server_limits.clone(),
+        }
+    }
+
+    /// Whether requests may target this chain
+    pub fn chain_allowed(&self, chain_id: u64) -> bool {
+        self.allowed_chains
+            .as_ref()
+            .is_none_or(|allowed| allowed.contains(&chain_id))
+    }
+}
+
+/// The current settings, swapped on reload
+pub struct Live {
+    current: RwLock<Arc<LiveSettings>>,
+    /// The settings file's variables in effect; also keeps reloads from
+    /// running concurrently
+    file_vars: Mutex<FileVars>,
+}
+
+impl Live {
+    pub fn new(settings: LiveSettings, file_vars: FileVars) -> Self {
+        Self {
+            current: RwLock::new(Arc::new(settings)),
+            file_vars: Mutex::new(file_vars),
+        }
+    }
+
+    pub fn get(&self) -> Arc<LiveSettings> {
+        self.current
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    fn set(&self, settings: LiveSettings) {
+        *self.current.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(settings);
+    }
+}
+
+/// Variables the settings file provides
+#[derive(Debug, Default)]
+pub struct FileVars {
+    /// Put into the environment at startup, so not set by the environment itself
+    startup: BTreeSet<String>,
+    /// Values in effect (for live variables, as of the last reload)
+    current: BTreeMap<String, String>,
+}
+
+impl FileVars {
+    /// The variables the file set at startup, with their values
+    pub fn new(set: BTreeMap<String, String>) -> Self {
+        Self {
+            startup: set.keys().cloned().collect(),
+            current: set,
+        }
+    }
+
+    /// The environment's own value, which wins over the file
+    fn env_override(&self, name: &str) -> Option<String> {
+        if self.startup.contains(name) {
+            return None;
+        }
+        env::var(name).ok()
+    }
+}
+
+/// Outcome of a reload
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReloadReport {
+    pub path: String,
+    /// Variables now in effect with their new values
+    pub applied: Vec<String>,
+    /// Changed in the file but only read at startup
+    pub restart_required: Vec<String>,
+    /// Set in the file but overridden by the environment
+    pub overridden: Vec<String>,
+}
+
+/// How a changed file variable is handled
+#[derive(Debug, PartialEq)]
+enum Change {
+    Unchanged,
+    Overridden,
+    Apply,
+    RestartRequired,
+}
+
+fn classify(
+    name: &str,
+    new: Option<&String>,
+    current: Option<&String>,
+    overridden: bool,
+) -> Change {
+    if overridden {
+        return Change::Overridden;
+    }
+    if new == current {
+        Change::Unchanged
+    } else if LIVE_VARS.contains(&name) {
+        Change::Apply
+    } else {
+        Change::RestartRequired
+    }
+}
+
+/// Re-read the settings file and apply its live settings
+///
+/// Nothing changes if the new settings are invalid.
+pub async fn reload(state: &AppState) -> anyhow::Result<ReloadReport> {
+    let mut file_vars = state.live.file_vars.lock().await;
+    let (path, file) = ConfigFile::load()?.ok_or_else(|| {
+        anyhow::anyhow!("No settings file: set WATCHY_CONFIG or create ./watchy.toml")
+    })?;
+    let vars: BTreeMap<String, String> = file.vars().into_iter().collect();
+    let names: BTreeSet<String> = vars.keys().chain(file_vars.current.keys()).cloned().collect();
+
+    let mut report = ReloadReport {
+        path: path.display().to_string(),
+        ..Default::default()
+    };
+    for name in names {
+        let new = vars.get(&name);
+        let overridden = file_vars.env_override(&name).is_some();
+        match classify(&name, new, file_vars.current.get(&name), overridden) {
+            Change::Unchanged => {}
+            Change::Overridden if new.is_some() => report.overridden.push(name),
+            Change::Overridden => {}
+            Change::RestartRequired => report.restart_required.push(name),
+            Change::Apply => report.applied.push(name),
+        }
+    }
+    if report.applied.is_empty() {
+        return Ok(report);
+    }
+
+    // Live variables as the file now sets them, the rest as loaded at startup
+    let lookup = |name: &str| {
+        if LIVE_VARS.contains(&name) {
+            file_vars.env_override(name).or_else(|| vars.get(name).cloned())
+        } else {
+            env::var(name).ok()
+        }
+    };
+    state.live.set(load_settings(state, &lookup)?);
+    for name in &report.applied {
+        match vars.get(name) {
+            Some(value) => file_vars.current.insert(name.clone(), value.clone()),
+            None => file_vars.current.remove(name),
+        };
+    }
+    Ok(report)
+}
+
+/// Live settings from `vars`
+fn load_settings(state: &AppState, vars: Vars) -> anyhow::Result<LiveSettings> {
+    let config = &state.config;
+    let timeouts = HttpTimeouts {
+        probe_secs: HttpTimeouts::from_vars(vars)?.probe_secs,
+        ..config.http_timeouts.clone()
+    };
+    Ok(LiveSettings {
+        score_weights: ScoreWeights::from_vars(vars)?,
+        allowed_chains: config::allowed_chains_from_vars(config.default_chain_id, vars)?,
+        usage: UsageConfig::from_vars(vars)?,
+        probes: ProbeClients::new(
+            &timeouts,
+            &state.http.guard,
+            &config.probe_user_agent,
+            &config.proxies,
+        )?,
+        limits: ServerLimits {
+            header_read_timeout_secs: config.server_limits.header_read_timeout_secs,
+            ..ServerLimits::from_vars(vars)?
+        },
+    })
+}
+
+/// Reload whenever the settings file changes
+pub fn spawn_watcher(state: Arc<AppState>, path: PathBuf, interval_secs: u64) {
+    let modified =
+        |path: &Path| -> Option<SystemTime> { std::fs::metadata(path).ok()?.modified().ok() };
+    tokio::spawn(async move {
+        let mut loaded = modified(&path);
+        loop {
+            tokio::time::sleep(Duration::from_secs(interval_secs)).await;
+            let current = modified(&path);
+            if current == loaded {
+                continue;
+            }
+            loaded = current;
+            match reload(&state).await {
+                Ok(report) => log_report(&report),
+                Err(e) => warn!(
+                    "Settings reload failed, keeping the current settings: {:#}",
+                    e
+                ),
+            }
+        }
+    });
+}
+
+pub fn log_report(report: &ReloadReport) {
+    if !report.applied.is_empty() {
+        info!("Reloaded {}: {}", report.path, report.applied.join(", "));
+    }
+    if !report.restart_required.is_empty() {
+        warn!(
+            "{} changed settings that need a restart: {}",
+            report.path,
+            report.restart_required.join(", ")
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reload_classification() {
+        let weights = "security=1".to_string();
+        let other = "security=2".to_string();
+        let port = "9000".to_string();
+
+        assert_eq!(
+            classify("SCORE_WEIGHTS", Some(&other), Some(&weights), false),
+            Change::Apply
+        );
+        // Removed from the file: back to the default
+        assert_eq!(
+            classify("SCORE_WEIGHTS", None, Some(&weights), false),
+            Change::Apply
+        );
+        assert_eq!(
+            classify("SCORE_WEIGHTS", Some(&weights), Some(&weights), false),
+            Change::Unchanged
+        );
+        // The environment wins, as at startup
+        assert_eq!(
+            classify("SCORE_WEIGHTS", Some(&other), None, true),
+            Change::Overridden
+        );
+        assert_eq!(
+            classify("SCORE_WEIGHTS", Some(&other), None, false),
+            Change::Apply
+        );
+        assert_eq!(
+            classify("MAX_CONCURRENT_REQUESTS", Some(&port), None, false),
+            Change::Apply
+        );
+        assert_eq!(
+            classify("PORT", Some(&port), None, false),
+            Change::RestartRequired
+        );
+
+        // What the file set at startup doesn't count as set by the environment
+        let file_vars = FileVars::new(BTreeMap::from([(
+            "WATCHY_TEST_FILE_VAR".to_string(),
+            "1".to_string(),
+        )]));
+        assert_eq!(file_vars.env_override("WATCHY_TEST_FILE_VAR"), None);
+        assert_eq!(file_vars.env_override("PATH"), env::var("PATH").ok());
+    }
+}
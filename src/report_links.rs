@@ -0,0 +1,65 @@
+//! Signed report links
+//!
+//! `GET /audit/:audit_id/report.md` normally requires the API key. A link
+//! signed with `REPORT_LINK_SECRET` grants access to one audit's Markdown
+//! report until it expires, so reports can be shared with people who don't
+//! hold a key.
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Link lifetime when the request doesn't set `ttl_secs`
+pub const DEFAULT_TTL_SECS: u64 = 15 * 60;
+/// Longest lifetime a link can be issued for
+pub const MAX_TTL_SECS: u64 = 24 * 3600;
+
+/// Hex HMAC-SHA256 over `{audit_id}:{expires}`
+pub fn sign(secret: &str, audit_id: &str, expires: u64) -> String {
+    hex::encode(mac(secret, audit_id, expires).finalize().into_bytes())
+}
+
+/// Whether `signature` is valid for the audit and hasn't expired at `now`
+pub fn verify(secret: &str, audit_id: &str, expires: u64, signature: &str, now: u64) -> bool {
+    if expires < now {
+        return false;
+    }
+    let Ok(signature) = hex::decode(signature) else {
+        return false;
+    };
+    mac(secret, audit_id, expires).verify_slice(&signature).is_ok()
+}
+
+/// Path and query of a signed Markdown report link
+pub fn path(secret: &str, audit_id: &str, expires: u64) -> String {
+    format!(
+        "/audit/{}/report.md?expires={}&signature={}",
+        audit_id,
+        expires,
+        sign(secret, audit_id, expires)
+    )
+}
+
+fn mac(secret: &str, audit_id: &str, expires: u64) -> HmacSha256 {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(format!("{}:{}", audit_id, expires).as_bytes());
+    mac
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify() {
+        let signature = sign("secret", "aud_1", 1_000);
+        assert!(verify("secret", "aud_1", 1_000, &signature, 999));
+        assert!(!verify("secret", "aud_1", 1_000, &signature, 1_001));
+        assert!(!verify("secret", "aud_2", 1_000, &signature, 999));
+        assert!(!verify("other", "aud_1", 1_000, &signature, 999));
+        assert!(!verify("secret", "aud_1", 2_000, &signature, 999));
+        assert!(!verify("secret", "aud_1", 1_000, "zz", 999));
+    }
+}
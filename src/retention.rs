@@ -0,0 +1,197 @@
+//! Audit job retention
+//!
+//! Jobs expire per status (see [`crate::store::RetentionPolicy`]). Redis drops them via TTL;
+//! a periodic sweep deletes expired in-memory jobs and, when an archive bucket
+//! is configured, copies completed jobs to S3-compatible storage shortly
+//! before they expire.
+
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info};
+
+use crate::pause::Subsystem;
+use crate::s3::S3Client;
+use crate::store::AuditJob;
+use crate::types::{AuditStatus, WatchyError};
+use crate::AppState;
+
+/// Sweep intervals before expiry at which completed jobs are archived
+///
+/// Two intervals leave one retry if the first archive attempt fails.
+const ARCHIVE_LEAD_INTERVALS: u64 = 2;
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SweepResult {
+    pub archived: usize,
+    pub expired: usize,
+}
+
+/// Selection for an admin purge (all set filters must match)
+#[derive(Debug, Default)]
+pub struct PurgeFilter {
+    pub agent_id: Option<u64>,
+    pub chain_id: Option<u64>,
+    pub status: Option<AuditStatus>,
+    /// Only jobs created at least this many seconds ago
+    pub older_than_secs: Option<u64>,
+}
+
+impl PurgeFilter {
+    fn matches(&self, job: &AuditJob, now: u64) -> bool {
+        self.agent_id.is_none_or(|id| job.agent_id == Some(id))
+            && self.chain_id.is_none_or(|id| job.chain_id == id)
+            && self.status.as_ref().is_none_or(|status| &job.status == status)
+            && self
+                .older_than_secs
+                .is_none_or(|age| now.saturating_sub(job.created_at) >= age)
+    }
+}
+
+/// Start the periodic retention sweep (no-op if the interval is 0)
+pub fn spawn_retention_worker(state: Arc<AppState>) {
+    let interval_secs = state.config.retention_sweep_interval_secs;
+    if interval_secs == 0 {
+        info!("Retention sweep disabled");
+        return;
+    }
+
+    let archive = state.config.archive.as_ref().map(|a| a.bucket.as_str());
+    info!(
+        "Retention sweep every {}s (archive bucket: {})",
+        interval_secs,
+        archive.unwrap_or("none")
+    );
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+        loop {
+            interval.tick().await;
+            if state.pauses.is_paused(Subsystem::Retention) {
+                continue;
+            }
+            let result = run_retention_sweep(&state).await;
+            if result.archived > 0 || result.expired > 0 {
+                info!(
+                    "Retention sweep: {} archived, {} expired",
+                    result.archived, result.expired
+                );
+            }
+        }
+    });
+}
+
+/// Archive completed jobs nearing expiry and delete expired ones
+pub async fn run_retention_sweep(state: &AppState) -> SweepResult {
+    let store = &state.audit_store;
+    let archive = state
+        .config
+        .archive
+        .clone()
+        .map(|config| S3Client::new(config).with_http_client(state.http.upload.clone()));
+    let lead = state
+        .config
+        .retention_sweep_interval_secs
+        .saturating_mul(ARCHIVE_LEAD_INTERVALS);
+    let now = chrono::Utc::now().timestamp() as u64;
+
+    // Jobs a Redis outage left in memory go back to Redis (and its TTLs) first
+    store.reconcile_jobs().await;
+
+    let mut result = SweepResult::default();
+    for job in store.list_jobs().await {
+        let expires_at = store.retention().expires_at(&job);
+
+        if let Some(archive) = &archive {
+            if job.status == AuditStatus::Completed
+                && job.archived_to.is_none()
+                && now.saturating_add(lead) >= expires_at
+            {
+                match archive_job(archive, &job).await {
+                    Ok(location) => {
+                        store.set_archived(&job.id, &location).await;
+                        result.archived += 1;
+                    }
+                    Err(e) => {
+                        error!("Failed to archive audit {}: {}", job.id, e);
+                    }
+                }
+            }
+        }
+
+        // Redis expires jobs itself; this catches the in-memory store
+        if now >= expires_at && store.delete_job(&job.id).await {
+            result.expired += 1;
+        }
+    }
+
+    result
+}
+
+/// Delete all jobs matching the filter. Returns the deleted IDs.
+pub async fn purge_jobs(state: &AppState, filter: &PurgeFilter) -> Vec<String> {
+    let now = chrono::Utc::now().timestamp() as u64;
+    let mut purged = Vec::new();
+
+    for job in state.audit_store.list_jobs().await {
+        if filter.matches(&job, now) && state.audit_store.delete_job(&job.id).await {
+            purged.push(job.id);
+        }
+    }
+
+    info!("Purged {} audit jobs ({:?})", purged.len(), filter);
+    purged
+}
+
+/// Upload the full job (including its report) to the archive bucket
+async fn archive_job(archive: &S3Client, job: &AuditJob) -> Result<String, WatchyError> {
+    let agent = job.agent_id.map_or_else(|| "unregistered".to_string(), |id| id.to_string());
+    let key = archive.key(&format!("{}/{}/{}.json", job.chain_id, agent, job.id));
+    let body = serde_json::to_vec(job)
+        .map_err(|e| WatchyError::Internal(format!("Failed to serialize job: {}", e)))?;
+
+    let location = archive.put_object(&key, body, "application/json").await?;
+    info!("Archived audit {} to {}", job.id, location);
+    Ok(location)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(agent_id: u64, created_at: u64) -> AuditJob {
+        AuditJob {
+            id: format!("aud_{}", agent_id),
+            agent_id: Some(agent_id),
+            chain_id: 8453,
+            status: AuditStatus::Completed,
+            created_at,
+            completed_at: Some(created_at),
+            result: None,
+            error: None,
+            archived_to: None,
+            events: Vec::new(),
+            tenant: None,
+            request_id: None,
+            params: None,
+            attempt: 1,
+            attempts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_purge_filter() {
+        let now = 10_000;
+        let filter = PurgeFilter {
+            agent_id: Some(17),
+            older_than_secs: Some(3_600),
+            ..Default::default()
+        };
+
+        assert!(filter.matches(&job(17, 1_000), now));
+        assert!(!filter.matches(&job(17, 9_000), now));
+        assert!(!filter.matches(&job(18, 1_000), now));
+        assert!(PurgeFilter::default().matches(&job(18, 9_000), now));
+    }
+}
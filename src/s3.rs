@@ -0,0 +1,249 @@
+//! Minimal S3-compatible object storage client
+//!
+//! Supports PUT with AWS Signature Version 4 and path-style URLs
+//! (`{endpoint}/{bucket}/{key}`), which works with AWS S3, Cloudflare R2,
+//! MinIO and most S3-compatible providers.
+
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::env;
+use tracing::debug;
+
+use crate::types::WatchyError;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Connection settings for an S3-compatible bucket
+#[derive(Debug, Clone)]
+pub struct S3Config {
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// Prefix prepended to every object key (e.g. "watchy/audits/")
+    pub prefix: String,
+}
+
+impl S3Config {
+    /// Load settings from `{PREFIX}_S3_*` environment variables
+    ///
+    /// Returns `None` if `{PREFIX}_S3_BUCKET` is unset. Endpoint and credentials
+    /// are required once a bucket is configured.
+    pub fn from_env(var_prefix: &str) -> anyhow::Result<Option<Self>> {
+        let var = |name: &str| format!("{}_S3_{}", var_prefix, name);
+
+        let Ok(bucket) = env::var(var("BUCKET")) else {
+            return Ok(None);
+        };
+        let required = |name: &str| {
+            env::var(var(name)).map_err(|_| {
+                anyhow::anyhow!("{} is required when {} is set", var(name), var("BUCKET"))
+            })
+        };
+
+        Ok(Some(Self {
+            endpoint: required("ENDPOINT")?,
+            bucket,
+            region: env::var(var("REGION")).unwrap_or_else(|_| "us-east-1".to_string()),
+            access_key_id: required("ACCESS_KEY_ID")?,
+            secret_access_key: required("SECRET_ACCESS_KEY")?,
+            prefix: env::var(var("PREFIX")).unwrap_or_default(),
+        }))
+    }
+}
+
+pub struct S3Client {
+    http_client: reqwest::Client,
+    config: S3Config,
+}
+
+impl S3Client {
+    pub fn new(config: S3Config) -> Self {
+        Self {
+            http_client: reqwest::Client::new(),
+            config,
+        }
+    }
+
+    /// Upload through a shared client instead of a private one
+    pub fn with_http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.http_client = http_client;
+        self
+    }
+
+    /// Upload an object, returning its URL
+    pub async fn put_object(
+        &self,
+        key: &str,
+        body: Vec<u8>,
+        content_type: &str,
+    ) -> Result<String, WatchyError> {
+        let url = self.object_url(key)?;
+        let host = match url.port() {
+            Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+
+        let now = chrono::Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let payload_hash = hex::encode(Sha256::digest(&body));
+
+        let headers = [
+            ("content-type", content_type),
+            ("host", host.as_str()),
+            ("x-amz-content-sha256", payload_hash.as_str()),
+            ("x-amz-date", amz_date.as_str()),
+        ];
+        let authorization = self.authorization("PUT", url.path(), &headers, &payload_hash, &amz_date);
+
+        let response = self
+            .http_client
+            .put(url.clone())
+            .header("content-type", content_type)
+            .header("x-amz-content-sha256", &payload_hash)
+            .header("x-amz-date", &amz_date)
+            .header("authorization", authorization)
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| WatchyError::StorageError(format!("S3 PUT failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(WatchyError::StorageError(format!(
+                "S3 error: {} - {}",
+                status, body
+            )));
+        }
+
+        debug!("Stored s3://{}/{}", self.config.bucket, key);
+        Ok(url.to_string())
+    }
+
+    /// Full object key including the configured prefix
+    pub fn key(&self, name: &str) -> String {
+        format!("{}{}", self.config.prefix, name)
+    }
+
+    fn object_url(&self, key: &str) -> Result<url::Url, WatchyError> {
+        let encoded_key = key
+            .split('/')
+            .map(|segment| urlencoding::encode(segment).into_owned())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        url::Url::parse(&format!(
+            "{}/{}/{}",
+            self.config.endpoint.trim_end_matches('/'),
+            self.config.bucket,
+            encoded_key
+        ))
+        .map_err(|e| WatchyError::StorageError(format!("Invalid S3 endpoint: {}", e)))
+    }
+
+    /// `Authorization` header value for a request (headers must be lowercase and sorted)
+    fn authorization(
+        &self,
+        method: &str,
+        path: &str,
+        headers: &[(&str, &str)],
+        payload_hash: &str,
+        amz_date: &str,
+    ) -> String {
+        let date = &amz_date[..8];
+        let scope = format!("{}/{}/s3/aws4_request", date, self.config.region);
+        let signed_headers = headers.iter().map(|(k, _)| *k).collect::<Vec<_>>().join(";");
+
+        let request = canonical_request(method, path, "", headers, payload_hash);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            hex::encode(Sha256::digest(request.as_bytes()))
+        );
+        let signature = sign_v4(
+            &self.config.secret_access_key,
+            date,
+            &self.config.region,
+            &string_to_sign,
+        );
+
+        format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            self.config.access_key_id, scope, signed_headers, signature
+        )
+    }
+}
+
+fn canonical_request(
+    method: &str,
+    path: &str,
+    query: &str,
+    headers: &[(&str, &str)],
+    payload_hash: &str,
+) -> String {
+    let canonical_headers: String = headers
+        .iter()
+        .map(|(k, v)| format!("{}:{}\n", k, v.trim()))
+        .collect();
+    let signed_headers = headers.iter().map(|(k, _)| *k).collect::<Vec<_>>().join(";");
+
+    format!(
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        method, path, query, canonical_headers, signed_headers, payload_hash
+    )
+}
+
+/// Derive the SigV4 signing key and sign `string_to_sign` (hex)
+fn sign_v4(secret: &str, date: &str, region: &str, string_to_sign: &str) -> String {
+    let k_date = hmac(format!("AWS4{}", secret).as_bytes(), date.as_bytes());
+    let k_region = hmac(&k_date, region.as_bytes());
+    let k_service = hmac(&k_region, b"s3");
+    let k_signing = hmac(&k_service, b"aws4_request");
+    hex::encode(hmac(&k_signing, string_to_sign.as_bytes()))
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Example GET Object request from the AWS SigV4 documentation
+    #[test]
+    fn test_sign_v4_aws_example() {
+        let empty_hash = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+        let request = canonical_request(
+            "GET",
+            "/test.txt",
+            "",
+            &[
+                ("host", "examplebucket.s3.amazonaws.com"),
+                ("range", "bytes=0-9"),
+                ("x-amz-content-sha256", empty_hash),
+                ("x-amz-date", "20130524T000000Z"),
+            ],
+            empty_hash,
+        );
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n20130524T000000Z\n20130524/us-east-1/s3/aws4_request\n{}",
+            hex::encode(Sha256::digest(request.as_bytes()))
+        );
+
+        assert_eq!(
+            sign_v4(
+                "wJalrXUtnFEMI/K7MDENG/bPxRfiCYEXAMPLEKEY",
+                "20130524",
+                "us-east-1",
+                &string_to_sign
+            ),
+            "f0e8bdb87c964420e857bd35b5d6ed310bd44f0170aba48dd91039c6036bdb41"
+        );
+    }
+}
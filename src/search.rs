@@ -0,0 +1,311 @@
+//! Skill index over audited agents
+//!
+//! Each completed audit records the agent's declared skills (A2A skills and
+//! OASF skills from the registration metadata), marking those the live agent
+//! card confirmed as verified. `GET /agents/search` looks agents up by skill,
+//! ranked by their latest overall score.
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{AgentMetadata, AuditReport, SkillStatus};
+
+/// Most results a search returns
+pub const MAX_RESULTS: usize = 100;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexedSkill {
+    pub name: String,
+    /// Also present in the agent card the audit fetched
+    pub verified: bool,
+}
+
+/// An agent's skills and score as of its latest completed audit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedAgent {
+    pub agent_id: u64,
+    pub chain_id: u64,
+    pub name: Option<String>,
+    pub score: u8,
+    pub audit_id: String,
+    /// When the audit completed (unix seconds)
+    pub audited_at: u64,
+    /// When the audit goes stale (unix seconds)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<u64>,
+    pub skills: Vec<IndexedSkill>,
+    /// Consecutive audits that found the agent dead (see `breaker`)
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub consecutive_failures: u32,
+}
+
+fn is_zero(n: &u32) -> bool {
+    *n == 0
+}
+
+impl IndexedAgent {
+    pub fn is_stale(&self, now: u64) -> bool {
+        self.valid_until.is_some_and(|until| now >= until)
+    }
+}
+
+/// A search hit, flagged when its audit is past `valid_until`
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchResult {
+    #[serde(flatten)]
+    pub agent: IndexedAgent,
+    pub stale: bool,
+}
+
+/// Index entry for a completed audit
+pub fn index_entry(
+    audit_id: &str,
+    chain_id: u64,
+    report: &AuditReport,
+    metadata: Option<&AgentMetadata>,
+    audited_at: u64,
+) -> IndexedAgent {
+    let verified: Vec<&str> = report
+        .checks
+        .endpoints
+        .iter()
+        .flat_map(|endpoint| &endpoint.skills)
+        .filter(|skill| skill.status == SkillStatus::Matched)
+        .filter_map(|skill| skill.declared.as_deref())
+        .collect();
+
+    let mut skills: Vec<IndexedSkill> = Vec::new();
+    for name in metadata.map(declared_skills).unwrap_or_default() {
+        if skills.iter().all(|skill| skill.name != name) {
+            skills.push(IndexedSkill {
+                verified: verified.contains(&name.as_str()),
+                name,
+            });
+        }
+    }
+
+    IndexedAgent {
+        agent_id: report.agent.agent_id,
+        chain_id,
+        name: metadata.and_then(|m| m.name.clone()),
+        score: report.scores.overall,
+        audit_id: audit_id.to_string(),
+        audited_at,
+        valid_until: report.valid_until,
+        skills,
+        consecutive_failures: 0,
+    }
+}
+
+/// A2A skills and OASF skills (strings, or objects with a `name`)
+fn declared_skills(metadata: &AgentMetadata) -> Vec<String> {
+    let mut skills = Vec::new();
+    for service in &metadata.services {
+        skills.extend(service.a2a_skills.iter().cloned());
+        skills.extend(service.skills.iter().filter_map(|skill| {
+            skill
+                .as_str()
+                .or_else(|| skill.get("name").and_then(|n| n.as_str()))
+                .map(str::to_string)
+        }));
+    }
+    skills
+}
+
+/// Case-insensitive match on the whole skill, a leading part of its taxonomy
+/// path (`natural_language_processing`) or its last segment (`summarization`)
+pub fn skill_matches(query: &str, skill: &str) -> bool {
+    let query = query.trim().trim_matches('/').to_lowercase();
+    let skill = skill.trim().trim_matches('/').to_lowercase();
+    if query.is_empty() {
+        return false;
+    }
+    skill == query
+        || skill.starts_with(&format!("{}/", query))
+        || skill.rsplit('/').next() == Some(query.as_str())
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SearchQuery {
+    pub skill: String,
+    #[serde(default)]
+    pub min_score: u8,
+    /// Only count skills the agent card confirmed
+    #[serde(default)]
+    pub verified: bool,
+    pub chain_id: Option<u64>,
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+fn default_limit() -> usize {
+    20
+}
+
+/// Agents offering the skill, best score first (newest audit breaks ties)
+pub fn search(agents: Vec<IndexedAgent>, query: &SearchQuery, now: u64) -> Vec<SearchResult> {
+    let mut results: Vec<IndexedAgent> = agents
+        .into_iter()
+        .filter(|agent| agent.score >= query.min_score)
+        .filter(|agent| query.chain_id.is_none_or(|chain_id| agent.chain_id == chain_id))
+        .filter(|agent| {
+            agent.skills.iter().any(|skill| {
+                (skill.verified || !query.verified) && skill_matches(&query.skill, &skill.name)
+            })
+        })
+        .collect();
+    results.sort_by(|a, b| b.score.cmp(&a.score).then(b.audited_at.cmp(&a.audited_at)));
+    results.truncate(query.limit.min(MAX_RESULTS));
+    results
+        .into_iter()
+        .map(|agent| SearchResult {
+            stale: agent.is_stale(now),
+            agent,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{EndpointCheck, SkillMatch};
+
+    fn agent(agent_id: u64, score: u8, skills: &[(&str, bool)]) -> IndexedAgent {
+        IndexedAgent {
+            agent_id,
+            chain_id: 8453,
+            name: None,
+            score,
+            audit_id: format!("aud_{}", agent_id),
+            audited_at: agent_id,
+            valid_until: Some(100),
+            skills: skills
+                .iter()
+                .map(|(name, verified)| IndexedSkill {
+                    name: name.to_string(),
+                    verified: *verified,
+                })
+                .collect(),
+            consecutive_failures: 0,
+        }
+    }
+
+    #[test]
+    fn test_index_entry() {
+        let metadata: AgentMetadata = serde_json::from_value(serde_json::json!({
+            "name": "Summarizer",
+            "services": [
+                { "name": "A2A", "a2aSkills": ["summarize", "translate"] },
+                { "name": "OASF", "skills": [{ "name": "natural_language_processing/summarization" }] }
+            ]
+        }))
+        .unwrap();
+        let mut report = AuditReport::new(7, 8453, "0x8004", "ipfs://meta".to_string(), None);
+        report.checks.endpoints.push(EndpointCheck {
+            service: "A2A".to_string(),
+            endpoint: "https://agent.example.com".to_string(),
+            reachable: true,
+            valid_schema: Some(true),
+            skills_match: Some(false),
+            latency: None,
+            error: None,
+            not_probed: None,
+            host_policy: None,
+            skills: vec![SkillMatch {
+                status: SkillStatus::Matched,
+                declared: Some("summarize".to_string()),
+                card_id: Some("summarize".to_string()),
+                card_name: None,
+                tags: vec![],
+            }],
+            transport: None,
+            tool_fingerprints: Default::default(),
+            content_hash: None,
+            availability: None,
+            rate_limit: None,
+            dual_stack: None,
+            websocket: None,
+            grpc: None,
+            issues: vec![],
+        });
+
+        let entry = index_entry("aud_1", 8453, &report, Some(&metadata), 100);
+        assert_eq!(entry.name.as_deref(), Some("Summarizer"));
+        assert_eq!(
+            entry.skills,
+            vec![
+                IndexedSkill { name: "summarize".to_string(), verified: true },
+                IndexedSkill { name: "translate".to_string(), verified: false },
+                IndexedSkill {
+                    name: "natural_language_processing/summarization".to_string(),
+                    verified: false
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_search() {
+        assert!(skill_matches("Summarization", "natural_language_processing/summarization"));
+        assert!(skill_matches("natural_language_processing", "natural_language_processing/summarization"));
+        assert!(!skill_matches("natural", "natural_language_processing/summarization"));
+
+        let agents = vec![
+            agent(1, 60, &[("summarize", true)]),
+            agent(2, 90, &[("summarize", false)]),
+            agent(3, 40, &[("summarize", true)]),
+            agent(4, 95, &[("translate", true)]),
+        ];
+        let query = |min_score, verified| SearchQuery {
+            skill: "summarize".to_string(),
+            min_score,
+            verified,
+            chain_id: None,
+            limit: 20,
+        };
+
+        let ids = |results: Vec<SearchResult>| {
+            results.iter().map(|r| r.agent.agent_id).collect::<Vec<_>>()
+        };
+        assert_eq!(ids(search(agents.clone(), &query(50, false), 0)), vec![2, 1]);
+        assert_eq!(ids(search(agents.clone(), &query(0, true), 0)), vec![1, 3]);
+        assert!(search(agents, &query(0, false), 100).iter().all(|r| r.stale));
+    }
+
+    #[test]
+    fn test_search_flags_stale() {
+        let mut agents = vec![
+            agent(1, 60, &[("summarize", true)]),
+            agent(2, 90, &[("summarize", true)]),
+            agent(3, 80, &[("summarize", true)]),
+        ];
+        agents[1].valid_until = Some(200);
+        // Audited without a validity window
+        agents[2].valid_until = None;
+        let query = SearchQuery {
+            skill: "summarize".to_string(),
+            min_score: 0,
+            verified: false,
+            chain_id: None,
+            limit: 20,
+        };
+        let stale = |now| {
+            search(agents.clone(), &query, now)
+                .into_iter()
+                .map(|r| (r.agent.agent_id, r.stale))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(stale(99), vec![(2, false), (3, false), (1, false)]);
+        // Stale audits stay listed in score order, flagged
+        assert_eq!(stale(100), vec![(2, false), (3, false), (1, true)]);
+        assert_eq!(stale(u64::MAX), vec![(2, true), (3, false), (1, true)]);
+
+        // The index entry keeps the report's window
+        let mut report = AuditReport::new(7, 8453, "0x8004", String::new(), None);
+        report.set_validity(3_600);
+        let entry = index_entry("aud_7", 8453, &report, None, report.timestamp);
+        assert_eq!(entry.valid_until, Some(report.timestamp + 3_600));
+        report.set_validity(0);
+        assert!(!index_entry("aud_7", 8453, &report, None, 0).is_stale(u64::MAX));
+    }
+}
@@ -0,0 +1,474 @@
+//! Watchy's own EIP-8004 registration
+//!
+//! With `SELF_REGISTER=true`, a startup task makes sure Watchy is a registered
+//! agent on each chain in `SELF_REGISTER_CHAINS` (default: the allowed chains
+//! with a registry), owned by the primary signer. The agent ID comes from
+//! `SELF_AGENT_IDS` (`<chain_id>:<agent_id>` pairs), the registry table, or the
+//! registry's `Registered` events for the signer. Watchy only mints a new agent
+//! when the signer owns none. Its URI is then kept at a data URI describing this deployment
+//! under `PUBLIC_URL`: the A2A card, `/mcp`, `/health` and, inside a TEE,
+//! `/attestation`. A URI that already matches costs nothing; paused on-chain
+//! submission (`onchain`) skips the transactions. `/health` lists the result
+//! per chain under `self_agents`.
+
+use alloy::primitives::Address;
+use base64::Engine;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::env;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use crate::blockchain::registry::RegistryClient;
+use crate::caip::AccountId;
+use crate::chains::{self, ChainType};
+use crate::indexer::RegisteredAgent;
+use crate::pause::Subsystem;
+use crate::types::{WatchyError, EIP8004_TYPE};
+use crate::AppState;
+
+/// Self-registration settings (`None` in `Config` unless `SELF_REGISTER=true`)
+#[derive(Debug, Clone)]
+pub struct SelfRegistrationConfig {
+    /// Chains Watchy registers itself on
+    pub chains: Vec<u64>,
+    /// Known agent IDs by chain
+    pub agent_ids: BTreeMap<u64, u64>,
+    /// Image in the registration file (optional)
+    pub image: Option<String>,
+}
+
+impl SelfRegistrationConfig {
+    /// Load from `SELF_REGISTER`, `SELF_REGISTER_CHAINS`, `SELF_AGENT_IDS` and
+    /// `SELF_REGISTER_IMAGE`; needs `PUBLIC_URL` and a signing key
+    pub fn from_env(
+        allowed_chains: Option<&[u64]>,
+        public_url: Option<&str>,
+        can_sign: bool,
+    ) -> anyhow::Result<Option<Self>> {
+        let enabled = env::var("SELF_REGISTER")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(false);
+        if !enabled {
+            return Ok(None);
+        }
+        if public_url.is_none() {
+            anyhow::bail!("SELF_REGISTER requires PUBLIC_URL (the URLs Watchy registers)");
+        }
+        if !can_sign {
+            anyhow::bail!("SELF_REGISTER requires a signing key (MNEMONIC or PRIVATE_KEY)");
+        }
+
+        let chains = match env::var("SELF_REGISTER_CHAINS") {
+            Ok(value) => parse_chains(&value)?,
+            Err(_) => chains::chains_with_registry()
+                .iter()
+                .filter(|chain| chain.chain_type == ChainType::Evm)
+                .map(|chain| chain.chain_id)
+                .filter(|chain_id| allowed_chains.is_none_or(|allowed| allowed.contains(chain_id)))
+                .collect(),
+        };
+        for &chain_id in &chains {
+            match chains::get_chain(chain_id) {
+                Some(chain) if chain.chain_type == ChainType::Evm && chain.has_registry() => {}
+                Some(chain) => anyhow::bail!(
+                    "SELF_REGISTER_CHAINS: {} has no EIP-8004 registry",
+                    chain.name
+                ),
+                None => anyhow::bail!("SELF_REGISTER_CHAINS: unsupported chain {}", chain_id),
+            }
+        }
+
+        Ok(Some(Self {
+            chains,
+            agent_ids: parse_agent_ids(&env::var("SELF_AGENT_IDS").unwrap_or_default())?,
+            image: env::var("SELF_REGISTER_IMAGE")
+                .ok()
+                .filter(|s| !s.is_empty()),
+        }))
+    }
+}
+
+/// Parse a comma-separated list of chain IDs
+fn parse_chains(value: &str) -> anyhow::Result<Vec<u64>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            s.parse()
+                .map_err(|_| anyhow::anyhow!("Invalid chain ID '{}' in SELF_REGISTER_CHAINS", s))
+        })
+        .collect()
+}
+
+/// Parse `SELF_AGENT_IDS` ("8453:412,84532:97")
+fn parse_agent_ids(value: &str) -> anyhow::Result<BTreeMap<u64, u64>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|pair| {
+            let parsed = pair.split_once(':').and_then(|(chain, agent)| {
+                Some((chain.trim().parse().ok()?, agent.trim().parse().ok()?))
+            });
+            parsed.ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Invalid entry '{}' in SELF_AGENT_IDS (expected <chain_id>:<agent_id>)",
+                    pair
+                )
+            })
+        })
+        .collect()
+}
+
+/// Watchy's registration on one chain, as reported by /health
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfAgent {
+    pub chain_id: u64,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent_id: Option<u64>,
+    /// The agent's URI describes this deployment
+    pub up_to_date: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Results of the latest self-registration run
+#[derive(Default)]
+pub struct SelfAgents {
+    agents: RwLock<Vec<SelfAgent>>,
+}
+
+impl SelfAgents {
+    pub async fn list(&self) -> Vec<SelfAgent> {
+        self.agents.read().await.clone()
+    }
+}
+
+/// Registration file describing this deployment
+pub fn registration_file(
+    public_url: &str,
+    chain_id: u64,
+    registry: &str,
+    agent_id: u64,
+    image: Option<&str>,
+    tee: bool,
+) -> Value {
+    let mut file = json!({
+        "type": EIP8004_TYPE,
+        "name": "Watchy",
+        "description": "Audits EIP-8004 agents: metadata, on-chain registration, \
+            endpoints and security, published as a signed report and on-chain feedback.",
+        "services": [
+            {
+                "name": "A2A",
+                "endpoint": format!("{}/.well-known/agent-card.json", public_url),
+                "version": "0.3.0"
+            },
+            {
+                "name": "MCP",
+                "endpoint": format!("{}/mcp", public_url),
+                "version": "2025-06-18",
+                "mcpTools": crate::mcp_server::tool_names()
+            },
+            { "name": "web", "endpoint": format!("{}/health", public_url) }
+        ],
+        "registrations": [{
+            "agentId": agent_id,
+            "agentRegistry": AccountId::eip155_string(chain_id, registry)
+        }],
+        "version": env!("CARGO_PKG_VERSION"),
+        "sourceCode": "https://github.com/builders-garden/watchy",
+        "active": true
+    });
+    if let Some(image) = image {
+        file["image"] = json!(image);
+    }
+    if tee {
+        file["supportedTrust"] = json!(["tee-attestation"]);
+        file["attestation"] = json!(format!("{}/attestation", public_url));
+    }
+    file
+}
+
+/// Base64 data URI of a registration file
+pub fn data_uri(file: &Value) -> String {
+    format!(
+        "data:application/json;base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(file.to_string())
+    )
+}
+
+/// Whether a registry table entry is a registration Watchy made for `public_url`
+pub fn is_own_registration(agent: &RegisteredAgent, signer: &str, public_url: &str) -> bool {
+    agent.owner.eq_ignore_ascii_case(signer) && describes_deployment(&agent.uri, public_url)
+}
+
+/// Whether an agent URI is a registration file of the deployment at `public_url`
+fn describes_deployment(uri: &str, public_url: &str) -> bool {
+    let Some(encoded) = uri.strip_prefix("data:application/json;base64,") else {
+        return false;
+    };
+    let Some(file) = base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<Value>(&bytes).ok())
+    else {
+        return false;
+    };
+    file["services"].as_array().is_some_and(|services| {
+        services.iter().any(|service| {
+            service["endpoint"]
+                .as_str()
+                .is_some_and(|endpoint| endpoint == format!("{}/mcp", public_url))
+        })
+    })
+}
+
+/// Register Watchy and update its URI on each configured chain, once
+pub fn spawn_self_registration(state: Arc<AppState>) {
+    let Some(config) = state.config.self_registration.clone() else {
+        return;
+    };
+    tokio::spawn(async move {
+        let mut results = vec![];
+        for &chain_id in &config.chains {
+            let name = chains::get_chain(chain_id)
+                .map(|chain| chain.name.to_string())
+                .unwrap_or_default();
+            let result = if state.pauses.is_paused(Subsystem::Onchain) {
+                Err(WatchyError::Internal(
+                    "On-chain submission paused, registration not checked".to_string(),
+                ))
+            } else {
+                ensure_registered(&state, &config, chain_id).await
+            };
+            let agent = match result {
+                Ok(agent_id) => SelfAgent {
+                    chain_id,
+                    name,
+                    agent_id: Some(agent_id),
+                    up_to_date: true,
+                    error: None,
+                },
+                Err(e) => {
+                    warn!("Self-registration on {} ({}) failed: {}", name, chain_id, e);
+                    SelfAgent {
+                        chain_id,
+                        name,
+                        agent_id: config.agent_ids.get(&chain_id).copied(),
+                        up_to_date: false,
+                        error: Some(e.to_string()),
+                    }
+                }
+            };
+            results.push(agent);
+        }
+        *state.self_agents.agents.write().await = results;
+    });
+}
+
+/// Find or mint Watchy's agent on a chain and bring its URI up to date
+async fn ensure_registered(
+    state: &AppState,
+    config: &SelfRegistrationConfig,
+    chain_id: u64,
+) -> Result<u64, WatchyError> {
+    let missing = |what: &str| WatchyError::Internal(format!("No {} for chain {}", what, chain_id));
+    let public_url = state
+        .config
+        .public_url
+        .as_deref()
+        .ok_or_else(|| missing("PUBLIC_URL"))?;
+    let private_key = state
+        .config
+        .private_key()
+        .ok_or_else(|| missing("signing key"))?;
+    let signer = state
+        .config
+        .signer_address()
+        .ok_or_else(|| missing("signer address"))?;
+    let registry_address = chains::get_chain(chain_id)
+        .and_then(|chain| chain.registry_address)
+        .ok_or_else(|| missing("registry"))?;
+    let rpc_url = chains::get_rpc_url(chain_id).ok_or_else(|| missing("RPC URL"))?;
+    let registry = RegistryClient::new(&rpc_url, registry_address)?;
+
+    let known = match config.agent_ids.get(&chain_id) {
+        Some(&agent_id) => Some(agent_id),
+        None => state
+            .audit_store
+            .registered_agents(chain_id)
+            .await
+            .iter()
+            .find(|agent| is_own_registration(agent, signer, public_url))
+            .map(|agent| agent.agent_id),
+    };
+    let known = match known {
+        Some(agent_id) => Some(agent_id),
+        None => find_on_chain(&registry, chain_id, signer, public_url).await?,
+    };
+    let agent_id = match known {
+        Some(agent_id) => {
+            let owner = registry.owner_of(agent_id).await?.to_string();
+            if !owner.eq_ignore_ascii_case(signer) {
+                return Err(WatchyError::InvalidRequest(format!(
+                    "Agent {} is owned by {}, not the signer {}",
+                    agent_id, owner, signer
+                )));
+            }
+            agent_id
+        }
+        None => {
+            let (agent_id, tx_hash) = registry.register_agent(private_key).await?;
+            info!(
+                "Registered Watchy as agent {} on chain {} (tx: {})",
+                agent_id, chain_id, tx_hash
+            );
+            state
+                .audit_store
+                .put_registered_agent(&RegisteredAgent {
+                    chain_id,
+                    agent_id,
+                    owner: signer.to_string(),
+                    uri: String::new(),
+                    // Filled in when the indexer sees the Registered event
+                    registered_block: 0,
+                    tx_hash: Some(tx_hash),
+                    uri_updated_block: None,
+                })
+                .await;
+            agent_id
+        }
+    };
+
+    let uri = data_uri(&registration_file(
+        public_url,
+        chain_id,
+        registry_address,
+        agent_id,
+        config.image.as_deref(),
+        state.attestation.is_some(),
+    ));
+    if registry.token_uri(agent_id).await? == uri {
+        info!(
+            "Watchy's agent {} on chain {} is up to date",
+            agent_id, chain_id
+        );
+        return Ok(agent_id);
+    }
+    let tx_hash = registry.set_agent_uri(agent_id, &uri, private_key).await?;
+    info!(
+        "Updated Watchy's agent {} URI on chain {} (tx: {})",
+        agent_id, chain_id, tx_hash
+    );
+    if let Some(mut agent) = state.audit_store.registered_agent(chain_id, agent_id).await {
+        agent.uri = uri;
+        state.audit_store.put_registered_agent(&agent).await;
+    }
+    Ok(agent_id)
+}
+
+/// Watchy's agent among those the signer owns on chain, when the registry
+/// table doesn't know it (e.g. the store was reset)
+///
+/// A signer that owns agents but none describing this deployment is an error,
+/// so a restart never mints a duplicate: set `SELF_AGENT_IDS` instead.
+async fn find_on_chain(
+    registry: &RegistryClient,
+    chain_id: u64,
+    signer: &str,
+    public_url: &str,
+) -> Result<Option<u64>, WatchyError> {
+    let owner: Address = signer
+        .parse()
+        .map_err(|_| WatchyError::InvalidAddress(signer.to_string()))?;
+    let owned = registry.balance_of(owner).await?;
+    if owned == 0 {
+        return Ok(None);
+    }
+    let from_block = chains::get_chain(chain_id)
+        .map(|chain| chain.registry_deploy_block)
+        .unwrap_or_default();
+    for agent_id in registry.registered_to(owner, from_block).await? {
+        if registry.owner_of(agent_id).await? == owner
+            && describes_deployment(&registry.token_uri(agent_id).await?, public_url)
+        {
+            info!(
+                "Found Watchy's agent {} on chain {} in the registry",
+                agent_id, chain_id
+            );
+            return Ok(Some(agent_id));
+        }
+    }
+    Err(WatchyError::InvalidRequest(format!(
+        "The signer {} owns {} agent(s) on chain {} but none describes {}; \
+         set SELF_AGENT_IDS to the one to use",
+        signer, owned, chain_id, public_url
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registration_file_and_ownership() {
+        assert_eq!(
+            parse_agent_ids("8453:412, 84532:97").unwrap(),
+            BTreeMap::from([(8453, 412), (84532, 97)])
+        );
+        assert!(parse_agent_ids("8453").is_err());
+
+        let registry = "0x8004A169FB4a3325136EB29fA0ceB6D2e539a432";
+        let file = registration_file(
+            "https://watchy.example.com",
+            8453,
+            registry,
+            412,
+            None,
+            true,
+        );
+        assert_eq!(file["type"], EIP8004_TYPE);
+        assert_eq!(
+            file["registrations"][0]["agentRegistry"],
+            format!("eip155:8453:{}", registry)
+        );
+        assert_eq!(
+            file["attestation"],
+            "https://watchy.example.com/attestation"
+        );
+        assert!(file.get("image").is_none());
+
+        let signer = "0xAbC0000000000000000000000000000000000001";
+        let mut agent = RegisteredAgent {
+            chain_id: 8453,
+            agent_id: 412,
+            owner: signer.to_lowercase(),
+            uri: data_uri(&file),
+            registered_block: 0,
+            tx_hash: None,
+            uri_updated_block: None,
+        };
+        assert!(is_own_registration(
+            &agent,
+            signer,
+            "https://watchy.example.com"
+        ));
+        assert!(!is_own_registration(
+            &agent,
+            signer,
+            "https://other.example.com"
+        ));
+        agent.owner = "0x0000000000000000000000000000000000000002".to_string();
+        assert!(!is_own_registration(
+            &agent,
+            signer,
+            "https://watchy.example.com"
+        ));
+    }
+}
@@ -0,0 +1,502 @@
+//! HTTP server and its limits against cheap abuse
+//!
+//! Request bodies over `MAX_REQUEST_BODY_BYTES` are refused with 413, requests
+//! beyond `MAX_CONCURRENT_REQUESTS` in flight get an immediate 503 instead of
+//! queueing, and requests still running after `REQUEST_TIMEOUT_SECS` get a 504.
+//! Connections that haven't sent their request headers within
+//! `HEADER_READ_TIMEOUT_SECS` are closed, so slow clients can't hold sockets
+//! open (the TLS handshake, with built-in TLS, gets the same time). Audits run
+//! in the background: the request timeout only bounds the API call itself.
+//! The body size, concurrency and timeout limits are read per request, so a
+//! settings reload changes them (see `reload`).
+//!
+//! The server listens on `PORT`, a Unix socket (`LISTEN_UNIX_SOCKET`) or the
+//! socket systemd passed through socket activation.
+
+use axum::body::Body;
+use axum::extract::{DefaultBodyLimit, Request};
+use axum::http::header::CONTENT_LENGTH;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use http_body_util::Limited;
+use hyper_util::rt::{TokioExecutor, TokioIo, TokioTimer};
+use hyper_util::server::conn::auto;
+use hyper_util::server::graceful::{GracefulShutdown, Watcher};
+use hyper_util::service::TowerToHyperService;
+use std::env;
+use std::future::Future;
+use std::os::fd::{FromRawFd, IntoRawFd, RawFd};
+use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{TcpListener, TcpStream, UnixListener, UnixStream};
+use tokio_rustls::TlsAcceptor;
+use tracing::{debug, info, warn};
+
+use crate::config::Vars;
+use crate::types::WatchyError;
+
+/// Wait for open connections to finish after a shutdown signal
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(30);
+
+/// TLS handshake timeout when `HEADER_READ_TIMEOUT_SECS` is off
+const DEFAULT_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Request and connection limits (0 disables a limit)
+#[derive(Debug, Clone)]
+pub struct ServerLimits {
+    pub max_body_bytes: usize,
+    pub max_concurrent_requests: usize,
+    pub request_timeout_secs: u64,
+    pub header_read_timeout_secs: u64,
+}
+
+impl Default for ServerLimits {
+    fn default() -> Self {
+        Self {
+            max_body_bytes: 1024 * 1024,
+            max_concurrent_requests: 512,
+            request_timeout_secs: 60,
+            header_read_timeout_secs: 10,
+        }
+    }
+}
+
+impl ServerLimits {
+    /// Load from `MAX_REQUEST_BODY_BYTES`, `MAX_CONCURRENT_REQUESTS`,
+    /// `REQUEST_TIMEOUT_SECS` and `HEADER_READ_TIMEOUT_SECS`
+    pub fn from_vars(vars: Vars) -> anyhow::Result<Self> {
+        let defaults = Self::default();
+        let var = |name: &str, default: u64| -> anyhow::Result<u64> {
+            match vars(name) {
+                Some(value) => Ok(value.parse()?),
+                None => Ok(default),
+            }
+        };
+        Ok(Self {
+            max_body_bytes: var("MAX_REQUEST_BODY_BYTES", defaults.max_body_bytes as u64)? as usize,
+            max_concurrent_requests: var(
+                "MAX_CONCURRENT_REQUESTS",
+                defaults.max_concurrent_requests as u64,
+            )? as usize,
+            request_timeout_secs: var("REQUEST_TIMEOUT_SECS", defaults.request_timeout_secs)?,
+            header_read_timeout_secs: var(
+                "HEADER_READ_TIMEOUT_SECS",
+                defaults.header_read_timeout_secs,
+            )?,
+        })
+    }
+}
+
+/// Apply the body size, concurrency and timeout limits to every route
+///
+/// `limits` is called for every request. The concurrency limit is shared by
+/// all routes (one counter), not per route.
+pub fn with_limits<F>(router: Router, limits: F) -> Router
+where
+    F: Fn() -> ServerLimits + Clone + Send + Sync + 'static,
+{
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    router
+        .layer(axum::middleware::from_fn(move |request: Request, next: Next| {
+            let limits = limits();
+            let in_flight = in_flight.clone();
+            async move { enforce_limits(request, next, limits, in_flight).await }
+        }))
+        // Extractors enforce their own default (2 MB); the body is limited above
+        .layer(DefaultBodyLimit::disable())
+}
+
+async fn enforce_limits(
+    request: Request,
+    next: Next,
+    limits: ServerLimits,
+    in_flight: Arc<AtomicUsize>,
+) -> Response {
+    let _slot = match limits.max_concurrent_requests {
+        0 => None,
+        max => match InFlight::acquire(in_flight, max) {
+            Some(slot) => Some(slot),
+            None => return WatchyError::Overloaded.into_response(),
+        },
+    };
+
+    let request = match limits.max_body_bytes {
+        0 => request,
+        max => {
+            let declared = request
+                .headers()
+                .get(CONTENT_LENGTH)
+                .and_then(|value| value.to_str().ok()?.parse::<usize>().ok());
+            if declared.is_some_and(|len| len > max) {
+                return WatchyError::PayloadTooLarge(format!(
+                    "Request body exceeds {} bytes",
+                    max
+                ))
+                .into_response();
+            }
+            // Chunked bodies are cut off while they're read
+            request.map(|body| Body::new(Limited::new(body, max)))
+        }
+    };
+
+    match limits.request_timeout_secs {
+        0 => next.run(request).await,
+        secs => match tokio::time::timeout(Duration::from_secs(secs), next.run(request)).await {
+            Ok(response) => response,
+            Err(_) => {
+                WatchyError::Timeout(format!("Request didn't finish within {}s", secs))
+                    .into_response()
+            }
+        },
+    }
+}
+
+/// A request counted against `MAX_CONCURRENT_REQUESTS` until dropped
+struct InFlight(Arc<AtomicUsize>);
+
+impl InFlight {
+    fn acquire(counter: Arc<AtomicUsize>, max: usize) -> Option<Self> {
+        if counter.fetch_add(1, Ordering::AcqRel) >= max {
+            counter.fetch_sub(1, Ordering::AcqRel);
+            return None;
+        }
+        Some(Self(counter))
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Where the API listens
+#[derive(Debug, Clone)]
+pub enum ListenAddr {
+    /// All interfaces on this port
+    Tcp(u16),
+    /// Unix domain socket, created with `mode` permissions if set
+    Unix { path: PathBuf, mode: Option<u32> },
+    /// Sockets passed in by systemd socket activation (how many)
+    Systemd(usize),
+}
+
+/// Number of sockets systemd passed to this process (`LISTEN_PID`,
+/// `LISTEN_FDS`), if any
+///
+/// Clears the activation variables so child processes don't take the sockets
+/// for theirs. Call it before the runtime or any other thread starts.
+pub fn take_systemd_sockets() -> Option<usize> {
+    let activated = env::var("LISTEN_PID")
+        .ok()
+        .and_then(|pid| pid.parse::<u32>().ok())
+        .is_some_and(|pid| pid == std::process::id());
+    let fds = env::var("LISTEN_FDS")
+        .ok()
+        .and_then(|fds| fds.parse::<usize>().ok());
+    env::remove_var("LISTEN_PID");
+    env::remove_var("LISTEN_FDS");
+    env::remove_var("LISTEN_FDNAMES");
+    fds.filter(|&fds| activated && fds > 0)
+}
+
+impl ListenAddr {
+    /// `LISTEN_UNIX_SOCKET` (with `LISTEN_UNIX_SOCKET_MODE`, octal), else TCP
+    /// on `port`; sockets from systemd (see `take_systemd_sockets`) override both
+    pub fn from_env(port: u16) -> anyhow::Result<Self> {
+        match env::var("LISTEN_UNIX_SOCKET") {
+            Ok(path) => Ok(Self::Unix {
+                path: path.into(),
+                mode: env::var("LISTEN_UNIX_SOCKET_MODE")
+                    .ok()
+                    .map(|mode| {
+                        u32::from_str_radix(&mode, 8).map_err(|_| {
+                            anyhow::anyhow!("LISTEN_UNIX_SOCKET_MODE must be octal, e.g. 660")
+                        })
+                    })
+                    .transpose()?,
+            }),
+            Err(_) => Ok(Self::Tcp(port)),
+        }
+    }
+}
+
+/// A bound listening socket
+pub enum Listener {
+    Tcp(TcpListener),
+    /// With the path to remove on shutdown (not for sockets owned by systemd)
+    Unix(UnixListener, Option<PathBuf>),
+}
+
+/// An accepted connection
+enum Stream {
+    Tcp(TcpStream),
+    Unix(UnixStream),
+}
+
+impl Listener {
+    pub async fn bind(addr: &ListenAddr) -> anyhow::Result<Self> {
+        match addr {
+            ListenAddr::Tcp(port) => Ok(Self::Tcp(TcpListener::bind(("0.0.0.0", *port)).await?)),
+            ListenAddr::Unix { path, mode } => {
+                // A socket left behind by an earlier run would fail the bind;
+                // anything else at the path is left alone and fails it
+                if is_socket(path) {
+                    std::fs::remove_file(path)?;
+                }
+                let listener = UnixListener::bind(path)
+                    .map_err(|e| anyhow::anyhow!("Can't bind {}: {}", path.display(), e))?;
+                if let Some(mode) = mode {
+                    std::fs::set_permissions(path, std::fs::Permissions::from_mode(*mode))?;
+                }
+                Ok(Self::Unix(listener, Some(path.clone())))
+            }
+            ListenAddr::Systemd(fds) => Self::from_systemd(*fds),
+        }
+    }
+
+    /// Take over the first socket systemd passed (file descriptor 3)
+    fn from_systemd(fds: usize) -> anyhow::Result<Self> {
+        const SD_LISTEN_FDS_START: RawFd = 3;
+        if fds > 1 {
+            warn!(
+                "systemd passed {} sockets, listening on the first only",
+                fds
+            );
+        }
+
+        // SAFETY: systemd hands this process the open descriptor; nothing else owns it
+        let tcp = unsafe { std::net::TcpListener::from_raw_fd(SD_LISTEN_FDS_START) };
+        if tcp.local_addr().is_ok() {
+            tcp.set_nonblocking(true)?;
+            return Ok(Self::Tcp(TcpListener::from_std(tcp)?));
+        }
+        // Not an IP socket: take the descriptor back as a Unix socket
+        // SAFETY: the descriptor was released by `into_raw_fd`
+        let unix = unsafe { std::os::unix::net::UnixListener::from_raw_fd(tcp.into_raw_fd()) };
+        unix.local_addr()
+            .map_err(|e| anyhow::anyhow!("systemd socket is neither TCP nor Unix: {}", e))?;
+        unix.set_nonblocking(true)?;
+        Ok(Self::Unix(UnixListener::from_std(unix)?, None))
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Tcp(listener) => listener
+                .local_addr()
+                .map(|addr| addr.to_string())
+                .unwrap_or_else(|_| "TCP socket".to_string()),
+            Self::Unix(_, Some(path)) => format!("unix:{}", path.display()),
+            Self::Unix(_, None) => "Unix socket from systemd".to_string(),
+        }
+    }
+
+    async fn accept(&self) -> std::io::Result<(Stream, String)> {
+        match self {
+            Self::Tcp(listener) => {
+                let (stream, remote) = listener.accept().await?;
+                Ok((Stream::Tcp(stream), remote.to_string()))
+            }
+            Self::Unix(listener, _) => {
+                let (stream, _) = listener.accept().await?;
+                Ok((Stream::Unix(stream), "unix socket peer".to_string()))
+            }
+        }
+    }
+}
+
+/// Whether `path` itself (not a symlink's target) is a Unix socket
+fn is_socket(path: &std::path::Path) -> bool {
+    std::fs::symlink_metadata(path).is_ok_and(|meta| meta.file_type().is_socket())
+}
+
+/// Serve the app until `shutdown` resolves, then let open connections finish
+///
+/// Connections are TLS when an acceptor is given (see `tls`).
+pub async fn serve(
+    listener: Listener,
+    app: Router,
+    limits: &ServerLimits,
+    tls: Option<TlsAcceptor>,
+    shutdown: impl Future<Output = ()>,
+) -> anyhow::Result<()> {
+    let mut builder = auto::Builder::new(TokioExecutor::new());
+    if limits.header_read_timeout_secs > 0 {
+        builder
+            .http1()
+            .timer(TokioTimer::new())
+            .header_read_timeout(Duration::from_secs(limits.header_read_timeout_secs));
+    }
+    let handshake_timeout = match limits.header_read_timeout_secs {
+        0 => DEFAULT_HANDSHAKE_TIMEOUT,
+        secs => Duration::from_secs(secs),
+    };
+    let graceful = GracefulShutdown::new();
+    tokio::pin!(shutdown);
+
+    loop {
+        let (stream, remote) = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    // Out of file descriptors, usually: give connections time to close
+                    warn!("Failed to accept connection: {}", e);
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    continue;
+                }
+            },
+            _ = &mut shutdown => break,
+        };
+
+        let connection = Connection {
+            app: app.clone(),
+            builder: builder.clone(),
+            watcher: graceful.watcher(),
+            tls: tls.clone(),
+            handshake_timeout,
+            remote,
+        };
+        match stream {
+            Stream::Tcp(stream) => tokio::spawn(connection.run(stream)),
+            Stream::Unix(stream) => tokio::spawn(connection.run(stream)),
+        };
+    }
+
+    if let Listener::Unix(_, Some(path)) = &listener {
+        if is_socket(path) {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+    drop(listener);
+    info!("Waiting for open connections to finish");
+    if tokio::time::timeout(SHUTDOWN_GRACE, graceful.shutdown())
+        .await
+        .is_err()
+    {
+        warn!(
+            "Connections still open after {}s, closing",
+            SHUTDOWN_GRACE.as_secs()
+        );
+    }
+    Ok(())
+}
+
+/// An accepted connection and what it needs to be served
+struct Connection {
+    app: Router,
+    builder: auto::Builder<TokioExecutor>,
+    watcher: Watcher,
+    tls: Option<TlsAcceptor>,
+    handshake_timeout: Duration,
+    remote: String,
+}
+
+impl Connection {
+    async fn run<I>(self, io: I)
+    where
+        I: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        match self.tls.clone() {
+            None => self.serve(io).await,
+            Some(acceptor) => {
+                match tokio::time::timeout(self.handshake_timeout, acceptor.accept(io)).await {
+                    Ok(Ok(stream)) => self.serve(stream).await,
+                    Ok(Err(e)) => debug!("TLS handshake with {} failed: {}", self.remote, e),
+                    Err(_) => debug!("TLS handshake with {} timed out", self.remote),
+                }
+            }
+        }
+    }
+
+    async fn serve<I>(self, io: I)
+    where
+        I: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        let service = TowerToHyperService::new(self.app);
+        let connection = self
+            .builder
+            .serve_connection_with_upgrades(TokioIo::new(io), service);
+        if let Err(e) = self.watcher.watch(connection.into_owned()).await {
+            debug!("Connection from {} closed: {}", self.remote, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use axum::routing::post;
+    use tower::Service;
+
+    #[tokio::test]
+    async fn test_unix_socket() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let path = std::env::temp_dir().join(format!("watchy-{}.sock", std::process::id()));
+        let addr = ListenAddr::Unix {
+            path: path.clone(),
+            mode: Some(0o660),
+        };
+        let listener = Listener::bind(&addr).await.unwrap();
+        assert_eq!(listener.describe(), format!("unix:{}", path.display()));
+        let app = Router::new().route("/health/live", axum::routing::get(|| async { "alive" }));
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(async move {
+            let shutdown = async {
+                let _ = stopped.await;
+            };
+            serve(listener, app, &ServerLimits::default(), None, shutdown).await
+        });
+
+        let mut stream = UnixStream::connect(&path).await.unwrap();
+        stream
+            .write_all(b"GET /health/live HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"));
+        assert!(response.ends_with("alive"));
+
+        stop.send(()).unwrap();
+        server.await.unwrap().unwrap();
+        // The socket file goes with the server
+        assert!(!path.exists());
+
+        // A regular file at the path is neither removed nor bound over
+        std::fs::write(&path, "keep").unwrap();
+        assert!(Listener::bind(&addr).await.is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "keep");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_body_limit() {
+        let limits = ServerLimits {
+            max_body_bytes: 16,
+            ..ServerLimits::default()
+        };
+        let mut app = with_limits(
+            Router::new().route("/", post(|body: String| async { body })),
+            move || limits.clone(),
+        );
+
+        let request = |body: &str| {
+            Request::post("/")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+        // Router is always ready, so `call` needs no `poll_ready` first
+        let response = app.call(request("short")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app.call(request(&"x".repeat(17))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
@@ -0,0 +1,433 @@
+//! Outbound request guard (SSRF protection)
+//!
+//! Metadata URIs, image URLs and service endpoints come from agents, so an
+//! agent could point them at `http://169.254.169.254` or an internal service.
+//! The guard rejects loopback, private, link-local and other non-public
+//! addresses after DNS resolution, unless the host or range is allowlisted.
+//!
+//! It is enforced in three places:
+//! - [`GuardedResolver`] on the agent-facing clients, so every connection
+//!   (including redirects and DNS rebinding) is checked at connect time
+//! - [`redirect_policy`], because IP-literal URLs bypass the resolver
+//! - [`OutboundGuard::check`] / [`OutboundGuard::check_literal`] before a
+//!   fetch, so blocked URLs are reported as such instead of as unreachable
+
+use ipnet::IpNet;
+use reqwest::dns::{Addrs, Resolve, Resolving};
+use std::env;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use url::{Host, Url};
+
+use crate::audit::policy::domain_matches;
+
+/// Which outbound destinations agent-supplied URLs may reach
+#[derive(Debug, Clone)]
+pub struct OutboundGuard {
+    pub enabled: bool,
+    /// Hosts (and their subdomains) exempt from the address check
+    pub allowed_hosts: Vec<String>,
+    /// Non-public ranges that may be contacted anyway
+    pub allowed_nets: Vec<IpNet>,
+}
+
+impl Default for OutboundGuard {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            allowed_hosts: Vec::new(),
+            allowed_nets: Vec::new(),
+        }
+    }
+}
+
+impl OutboundGuard {
+    /// Load from `SSRF_PROTECTION` (default true) and `SSRF_ALLOWLIST`
+    ///
+    /// The allowlist is comma-separated hostnames, IP addresses and CIDR ranges.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let enabled = env::var("SSRF_PROTECTION")
+            .map(|v| v != "false" && v != "0")
+            .unwrap_or(true);
+
+        let mut guard = Self {
+            enabled,
+            ..Default::default()
+        };
+        if let Ok(value) = env::var("SSRF_ALLOWLIST") {
+            for entry in value.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+                if let Ok(net) = entry.parse::<IpNet>() {
+                    guard.allowed_nets.push(net);
+                } else if let Ok(ip) = entry.parse::<IpAddr>() {
+                    guard.allowed_nets.push(IpNet::from(ip));
+                } else if entry.contains('/') {
+                    anyhow::bail!("Invalid CIDR '{}' in SSRF_ALLOWLIST", entry);
+                } else {
+                    guard = guard.with_allowed_host(entry);
+                }
+            }
+        }
+
+        Ok(guard)
+    }
+
+    /// Exempt a host (e.g. a configured gateway on the local network)
+    pub fn with_allowed_host(mut self, host: &str) -> Self {
+        let host = host.trim_end_matches('.').to_lowercase();
+        if !host.is_empty() && !self.allowed_hosts.contains(&host) {
+            self.allowed_hosts.push(host);
+        }
+        self
+    }
+
+    fn host_allowed(&self, host: &str) -> bool {
+        let host = host.to_lowercase();
+        self.allowed_hosts.iter().any(|allowed| domain_matches(allowed, &host))
+    }
+
+    fn ip_allowed(&self, ip: IpAddr) -> bool {
+        is_public(ip) || self.allowed_nets.iter().any(|net| net.contains(&ip))
+    }
+
+    /// Check the addresses a host resolved to
+    pub fn check_resolved(&self, host: &str, addrs: &[SocketAddr]) -> Result<(), String> {
+        if !self.enabled || self.host_allowed(host) {
+            return Ok(());
+        }
+        match addrs.iter().find(|addr| !self.ip_allowed(addr.ip())) {
+            Some(addr) => Err(format!("{} resolves to non-public address {}", host, addr.ip())),
+            None => Ok(()),
+        }
+    }
+
+    /// Reject URLs whose host is a non-public IP literal (no DNS lookup)
+    pub fn check_literal(&self, url: &str) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+        // Unparseable URLs fail on their own when fetched
+        let Ok(url) = Url::parse(url) else {
+            return Ok(());
+        };
+        if !matches!(url.scheme(), "http" | "https" | "ws" | "wss" | "grpc" | "grpcs") {
+            return Ok(());
+        }
+        let ip = match url.host() {
+            Some(Host::Ipv4(ip)) => IpAddr::V4(ip),
+            Some(Host::Ipv6(ip)) => IpAddr::V6(ip),
+            _ => return Ok(()),
+        };
+        if self.ip_allowed(ip) {
+            Ok(())
+        } else {
+            Err(format!("{} is a non-public address", ip))
+        }
+    }
+
+    /// Resolve a host and check every address, for raw socket connections
+    ///
+    /// Connect to the returned addresses rather than the host name, so a
+    /// second lookup cannot rebind to a blocked address.
+    pub async fn resolve(&self, host: &str, port: u16) -> Result<Vec<SocketAddr>, String> {
+        let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+            .await
+            .map_err(|e| format!("Failed to resolve {}: {}", host, e))?
+            .collect();
+        self.check_resolved(host, &addrs)?;
+        Ok(addrs)
+    }
+
+    /// Check a URL before fetching it, resolving its host
+    pub async fn check(&self, url: &str) -> Result<(), String> {
+        self.check_literal(url)?;
+        if !self.enabled {
+            return Ok(());
+        }
+        let Ok(parsed) = Url::parse(url) else {
+            return Ok(());
+        };
+        if !matches!(parsed.scheme(), "http" | "https" | "ws" | "wss" | "grpc" | "grpcs") {
+            return Ok(());
+        }
+        let Some(Host::Domain(host)) = parsed.host() else {
+            return Ok(());
+        };
+        if self.host_allowed(host) {
+            return Ok(());
+        }
+
+        let port = parsed.port_or_known_default().unwrap_or(443);
+        let result = match tokio::net::lookup_host((host, port)).await {
+            Ok(addrs) => self.check_resolved(host, &addrs.collect::<Vec<_>>()),
+            // Resolution failures surface as normal fetch errors
+            Err(_) => Ok(()),
+        };
+        result
+    }
+}
+
+/// A connection or redirect the guard refused, as the source of a client error
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub struct Blocked(pub String);
+
+/// The guard's reason, if a client error came from a refused connection or redirect
+pub fn blocked_reason(error: &(dyn std::error::Error + 'static)) -> Option<String> {
+    let mut source = Some(error);
+    while let Some(cause) = source {
+        if let Some(blocked) = cause.downcast_ref::<Blocked>() {
+            return Some(blocked.0.clone());
+        }
+        source = cause.source();
+    }
+    None
+}
+
+/// IP version a resolver can be pinned to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressFamily {
+    V4,
+    V6,
+}
+
+impl AddressFamily {
+    pub fn matches(self, ip: IpAddr) -> bool {
+        match self {
+            Self::V4 => ip.is_ipv4(),
+            Self::V6 => ip.is_ipv6(),
+        }
+    }
+}
+
+/// DNS resolver that refuses to hand out non-public addresses
+pub struct GuardedResolver {
+    guard: Arc<OutboundGuard>,
+    family: Option<AddressFamily>,
+    /// The client's own proxies, resolved without the address check
+    proxy_hosts: Arc<Vec<String>>,
+}
+
+impl GuardedResolver {
+    pub fn new(guard: Arc<OutboundGuard>) -> Self {
+        Self {
+            guard,
+            family: None,
+            proxy_hosts: Arc::default(),
+        }
+    }
+
+    /// Let the client reach its proxies even on a private network
+    ///
+    /// Behind a proxy the resolver only sees the proxy's own hostname, so this
+    /// exempts Watchy's connection to it, not agent URLs naming the same host:
+    /// those are still refused by [`OutboundGuard::check`].
+    pub fn with_proxy_hosts(mut self, hosts: Vec<String>) -> Self {
+        self.proxy_hosts = Arc::new(hosts.iter().map(|host| host.to_lowercase()).collect());
+        self
+    }
+
+    /// Only hand out addresses of one family (per-family reachability probes)
+    pub fn with_family(mut self, family: AddressFamily) -> Self {
+        self.family = Some(family);
+        self
+    }
+}
+
+impl Resolve for GuardedResolver {
+    fn resolve(&self, name: hyper::client::connect::dns::Name) -> Resolving {
+        let guard = self.guard.clone();
+        let family = self.family;
+        let proxy_hosts = self.proxy_hosts.clone();
+        Box::pin(async move {
+            let host = name.as_str();
+            let mut addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, 0)).await?.collect();
+            if !proxy_hosts.iter().any(|proxy| proxy.eq_ignore_ascii_case(host)) {
+                guard.check_resolved(host, &addrs).map_err(Blocked)?;
+            }
+            if let Some(family) = family {
+                addrs.retain(|addr| family.matches(addr.ip()));
+                if addrs.is_empty() {
+                    return Err(format!("{} has no {:?} address", host, family).into());
+                }
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Follow up to `max` redirects, refusing non-public IP-literal targets
+pub fn redirect_policy(guard: Arc<OutboundGuard>, max: usize) -> reqwest::redirect::Policy {
+    reqwest::redirect::Policy::custom(move |attempt| {
+        if attempt.previous().len() >= max {
+            attempt.error("too many redirects")
+        } else if let Err(reason) = guard.check_literal(attempt.url().as_str()) {
+            attempt.error(Blocked(reason))
+        } else {
+            attempt.follow()
+        }
+    })
+}
+
+/// Whether an address is publicly routable
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => match embedded_v4(ip) {
+            Some(embedded) => is_public_v4(embedded),
+            None => is_public_v6(ip),
+        },
+    }
+}
+
+/// The IPv4 address an IPv6 address reaches, for ranges that translate or
+/// tunnel to IPv4
+fn embedded_v4(ip: Ipv6Addr) -> Option<Ipv4Addr> {
+    let segments = ip.segments();
+    let [_, _, _, _, _, _, _, _, _, _, _, _, a, b, c, d] = ip.octets();
+    match segments {
+        // NAT64 well-known (64:ff9b::/96) and local-use (64:ff9b:1::/48,
+        // RFC 6052 /96 layout): the IPv4 address is the last 32 bits
+        [0x64, 0xff9b, 0, 0, 0, 0, _, _] | [0x64, 0xff9b, 1, ..] => {
+            Some(Ipv4Addr::new(a, b, c, d))
+        }
+        // 6to4 (2002::/16): the IPv4 address follows the prefix
+        [0x2002, high, low, ..] => {
+            Some(Ipv4Addr::from((u32::from(high) << 16) | u32::from(low)))
+        }
+        // IPv4-mapped (::ffff:a.b.c.d) and the deprecated IPv4-compatible (::a.b.c.d)
+        _ => ip.to_ipv4(),
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+    !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        || a == 0
+        // Shared address space (carrier-grade NAT)
+        || (a == 100 && (64..128).contains(&b))
+        // IETF protocol assignments
+        || (a == 192 && b == 0 && c == 0)
+        // Benchmarking
+        || (a == 198 && (b == 18 || b == 19))
+        // Reserved
+        || a >= 240)
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    !(ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        // Unique local (fc00::/7)
+        || (first & 0xfe00) == 0xfc00
+        // Link-local (fe80::/10)
+        || (first & 0xffc0) == 0xfe80
+        // Deprecated site-local (fec0::/10)
+        || (first & 0xffc0) == 0xfec0
+        // Teredo (2001::/32), tunnelling to an IPv4 address we can't check
+        || (first == 0x2001 && ip.segments()[1] == 0)
+        // Documentation (2001:db8::/32)
+        || (first == 0x2001 && ip.segments()[1] == 0x0db8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_public() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:169.254.169.254",
+            // NAT64, 6to4 and IPv4-compatible forms of private addresses
+            "64:ff9b::a9fe:a9fe",
+            "64:ff9b::127.0.0.1",
+            "64:ff9b:1::10.0.0.1",
+            "2002:a9fe:a9fe::1",
+            "2002:c0a8:0101::",
+            "::169.254.169.254",
+            "::10.0.0.1",
+            "fec0::1",
+            "2001:0:4136:e378:8000:63bf:3fff:fdd2",
+        ] {
+            assert!(!is_public(ip.parse().unwrap()), "{} should not be public", ip);
+        }
+        for ip in [
+            "1.1.1.1",
+            "8.8.8.8",
+            "2606:4700:4700::1111",
+            "64:ff9b::1.1.1.1",
+            "64:ff9b:1::8.8.8.8",
+            "2002:0101:0101::1",
+            "::8.8.8.8",
+        ] {
+            assert!(is_public(ip.parse().unwrap()), "{} should be public", ip);
+        }
+    }
+
+    #[test]
+    fn test_check_literal() {
+        let guard = OutboundGuard::default();
+        assert!(guard.check_literal("http://169.254.169.254/latest/meta-data").is_err());
+        assert!(guard.check_literal("http://[::1]:8080/").is_err());
+        assert!(guard.check_literal("https://1.1.1.1/").is_ok());
+        assert!(guard.check_literal("https://example.com/").is_ok());
+
+        let guard = OutboundGuard {
+            allowed_nets: vec!["10.0.0.0/8".parse().unwrap()],
+            ..Default::default()
+        };
+        assert!(guard.check_literal("http://10.2.3.4/agent.json").is_ok());
+        assert!(guard.check_literal("http://192.168.0.1/").is_err());
+
+        let disabled = OutboundGuard {
+            enabled: false,
+            ..Default::default()
+        };
+        assert!(disabled.check_literal("http://127.0.0.1/").is_ok());
+    }
+
+    #[test]
+    fn test_check_resolved() {
+        let guard = OutboundGuard::default().with_allowed_host("gateway.internal");
+        let private: Vec<SocketAddr> = vec!["10.0.0.5:443".parse().unwrap()];
+        let mixed: Vec<SocketAddr> = vec![
+            "1.1.1.1:443".parse().unwrap(),
+            "127.0.0.1:443".parse().unwrap(),
+        ];
+
+        assert!(guard.check_resolved("agent.example.com", &private).is_err());
+        assert!(guard.check_resolved("agent.example.com", &mixed).is_err());
+        assert!(guard.check_resolved("gateway.internal", &private).is_ok());
+        assert!(guard.check_resolved("ipfs.gateway.internal", &private).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_checks_addresses() {
+        let guard = OutboundGuard::default();
+        assert!(guard.resolve("127.0.0.1", 443).await.is_err());
+        assert!(guard.resolve("::1", 443).await.is_err());
+
+        let allowed = OutboundGuard {
+            allowed_nets: vec!["127.0.0.0/8".parse().unwrap()],
+            ..Default::default()
+        };
+        let addrs = allowed.resolve("127.0.0.1", 443).await.unwrap();
+        assert_eq!(addrs, vec!["127.0.0.1:443".parse::<SocketAddr>().unwrap()]);
+    }
+}
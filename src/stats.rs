@@ -0,0 +1,245 @@
+//! Aggregate audit statistics
+//!
+//! Ecosystem-level view over the stored audits in a time window: how many ran,
+//! how many failed, average scores overall and per chain, and the issue codes
+//! that come up most. Served publicly at `GET /stats`.
+
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::chains::get_chain;
+use crate::store::AuditJob;
+use crate::types::{AuditStatus, Scores, Severity};
+
+/// Issue codes listed in `top_issues`
+const TOP_ISSUES: usize = 10;
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AuditStats {
+    /// Start of the window (unix seconds)
+    pub since: u64,
+    pub window_hours: u64,
+    pub audits: StatusCounts,
+    /// Failed audits as a share of finished (completed + failed) audits
+    pub failure_rate: f64,
+    /// Averages over completed audits
+    pub average_scores: Option<AverageScores>,
+    pub chains: Vec<ChainStats>,
+    pub top_issues: Vec<IssueFrequency>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StatusCounts {
+    pub total: usize,
+    pub completed: usize,
+    pub failed: usize,
+    pub pending: usize,
+    pub in_progress: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AverageScores {
+    pub overall: f64,
+    pub metadata: f64,
+    pub onchain: f64,
+    pub endpoint_availability: f64,
+    pub endpoint_performance: f64,
+    pub security: f64,
+    pub consistency: f64,
+    pub content: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainStats {
+    pub chain_id: u64,
+    pub chain_name: Option<String>,
+    pub audits: usize,
+    pub completed: usize,
+    pub failed: usize,
+    pub failure_rate: f64,
+    pub average_score: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IssueFrequency {
+    pub code: String,
+    pub severity: Severity,
+    /// Completed audits reporting this code at least once
+    pub audits: usize,
+}
+
+/// Statistics over jobs created at or after `since`
+pub fn compute(jobs: &[AuditJob], since: u64, window_hours: u64) -> AuditStats {
+    let jobs: Vec<&AuditJob> = jobs.iter().filter(|job| job.created_at >= since).collect();
+
+    let mut chains: BTreeMap<u64, Vec<&AuditJob>> = BTreeMap::new();
+    for job in &jobs {
+        chains.entry(job.chain_id).or_default().push(job);
+    }
+
+    let audits = count_statuses(&jobs);
+    AuditStats {
+        since,
+        window_hours,
+        failure_rate: failure_rate(&audits),
+        average_scores: average_scores(&jobs),
+        chains: chains
+            .into_iter()
+            .map(|(chain_id, jobs)| {
+                let counts = count_statuses(&jobs);
+                ChainStats {
+                    chain_id,
+                    chain_name: get_chain(chain_id).map(|c| c.name.to_string()),
+                    audits: counts.total,
+                    completed: counts.completed,
+                    failed: counts.failed,
+                    failure_rate: failure_rate(&counts),
+                    average_score: average_scores(&jobs).map(|s| s.overall),
+                }
+            })
+            .collect(),
+        top_issues: top_issues(&jobs),
+        audits,
+    }
+}
+
+fn count_statuses(jobs: &[&AuditJob]) -> StatusCounts {
+    let mut counts = StatusCounts {
+        total: jobs.len(),
+        ..Default::default()
+    };
+    for job in jobs {
+        match job.status {
+            AuditStatus::Completed => counts.completed += 1,
+            AuditStatus::Failed => counts.failed += 1,
+            AuditStatus::Pending => counts.pending += 1,
+            AuditStatus::InProgress => counts.in_progress += 1,
+        }
+    }
+    counts
+}
+
+fn failure_rate(counts: &StatusCounts) -> f64 {
+    let finished = counts.completed + counts.failed;
+    if finished == 0 {
+        return 0.0;
+    }
+    round2(counts.failed as f64 / finished as f64)
+}
+
+fn average_scores(jobs: &[&AuditJob]) -> Option<AverageScores> {
+    let scores: Vec<&Scores> = jobs
+        .iter()
+        .filter(|job| job.status == AuditStatus::Completed)
+        .filter_map(|job| job.result.as_ref().map(|r| &r.scores))
+        .collect();
+    if scores.is_empty() {
+        return None;
+    }
+
+    let avg = |field: fn(&Scores) -> u8| {
+        round2(scores.iter().map(|s| field(s) as f64).sum::<f64>() / scores.len() as f64)
+    };
+    Some(AverageScores {
+        overall: avg(|s| s.overall),
+        metadata: avg(|s| s.metadata),
+        onchain: avg(|s| s.onchain),
+        endpoint_availability: avg(|s| s.endpoint_availability),
+        endpoint_performance: avg(|s| s.endpoint_performance),
+        security: avg(|s| s.security),
+        consistency: avg(|s| s.consistency),
+        content: avg(|s| s.content),
+    })
+}
+
+/// Most common issue codes, counted once per audit
+fn top_issues(jobs: &[&AuditJob]) -> Vec<IssueFrequency> {
+    let mut counts: HashMap<&str, (Severity, usize)> = HashMap::new();
+    for report in jobs.iter().filter_map(|job| job.result.as_ref()) {
+        let mut seen = std::collections::HashSet::new();
+        for (_, issue) in report.issues_by_category() {
+            if seen.insert(issue.code.as_str()) {
+                counts
+                    .entry(issue.code.as_str())
+                    .or_insert_with(|| (issue.severity.clone(), 0))
+                    .1 += 1;
+            }
+        }
+    }
+
+    let mut top: Vec<IssueFrequency> = counts
+        .into_iter()
+        .map(|(code, (severity, audits))| IssueFrequency {
+            code: code.to_string(),
+            severity,
+            audits,
+        })
+        .collect();
+    top.sort_by(|a, b| b.audits.cmp(&a.audits).then_with(|| a.code.cmp(&b.code)));
+    top.truncate(TOP_ISSUES);
+    top
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{AuditReport, Issue};
+
+    fn job(chain_id: u64, created_at: u64, status: AuditStatus, overall: u8, issues: &[&str]) -> AuditJob {
+        let result = (status == AuditStatus::Completed).then(|| {
+            let mut report = AuditReport::new(1, chain_id, "0x8004", String::new(), None);
+            report.scores.overall = overall;
+            report.checks.metadata.issues = issues
+                .iter()
+                .map(|code| Issue::new(Severity::Warning, code.to_string(), String::new()))
+                .collect();
+            report
+        });
+        AuditJob {
+            id: format!("aud_{}_{}", chain_id, created_at),
+            agent_id: Some(1),
+            chain_id,
+            status,
+            created_at,
+            completed_at: None,
+            result,
+            error: None,
+            archived_to: None,
+            events: Vec::new(),
+            tenant: None,
+            request_id: None,
+            params: None,
+            attempt: 1,
+            attempts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_compute() {
+        let jobs = vec![
+            job(8453, 100, AuditStatus::Completed, 80, &["NO_WALLET", "NO_WALLET"]),
+            job(8453, 200, AuditStatus::Completed, 60, &["NO_WALLET", "MISSING_IMAGE"]),
+            job(8453, 300, AuditStatus::Failed, 0, &[]),
+            job(84532, 400, AuditStatus::Pending, 0, &[]),
+            job(8453, 10, AuditStatus::Completed, 10, &["OLD"]),
+        ];
+
+        let stats = compute(&jobs, 50, 24);
+        assert_eq!(stats.audits.total, 4);
+        assert_eq!(stats.failure_rate, 0.33);
+        assert_eq!(stats.average_scores.unwrap().overall, 70.0);
+        assert_eq!(stats.chains.len(), 2);
+        assert_eq!(stats.chains[0].chain_name.as_deref(), Some("base"));
+        assert_eq!(stats.chains[1].average_score, None);
+        let top: Vec<(&str, usize)> = stats
+            .top_issues
+            .iter()
+            .map(|i| (i.code.as_str(), i.audits))
+            .collect();
+        assert_eq!(top, vec![("NO_WALLET", 2), ("MISSING_IMAGE", 1)]);
+    }
+}
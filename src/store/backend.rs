@@ -0,0 +1,155 @@
+//! Storage backend interface
+//!
+//! `AuditStore` owns the job lifecycle (statuses, events, retention, falling
+//! back to memory when the backend fails); a `JobStore` only persists records.
+//! Every method reports failures so the caller can fall back.
+
+use async_trait::async_trait;
+
+use super::{AuditJob, RetiredSigner};
+use crate::events::AuditEvent;
+use crate::indexer::RegisteredAgent;
+use crate::search::IndexedAgent;
+use crate::subscriptions::Subscription;
+use crate::types::EndpointArtifact;
+
+#[derive(Debug, thiserror::Error)]
+pub enum StoreError {
+    #[error("{0}")]
+    Backend(String),
+    #[error("serialization failed: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+impl From<redis::RedisError> for StoreError {
+    fn from(e: redis::RedisError) -> Self {
+        StoreError::Backend(e.to_string())
+    }
+}
+
+impl From<rusqlite::Error> for StoreError {
+    fn from(e: rusqlite::Error) -> Self {
+        StoreError::Backend(e.to_string())
+    }
+}
+
+pub type StoreResult<T> = Result<T, StoreError>;
+
+/// Persistence for jobs, subscriptions, the skill index and the registry table
+#[async_trait]
+pub trait JobStore: Send + Sync {
+    /// Short name for logs and `/health` (e.g. "redis")
+    fn name(&self) -> &'static str;
+
+    /// Insert or replace a job, dropping it after `ttl_secs` if the backend expires records
+    async fn put_job(&self, job: &AuditJob, ttl_secs: u64) -> StoreResult<()>;
+    /// `put_job` plus the job's endpoint artifacts, in one write; an empty
+    /// slice drops stored artifacts
+    async fn put_job_with_artifacts(
+        &self,
+        job: &AuditJob,
+        artifacts: &[EndpointArtifact],
+        ttl_secs: u64,
+    ) -> StoreResult<()>;
+    async fn get_job(&self, id: &str) -> StoreResult<Option<AuditJob>>;
+    /// A job's endpoint artifacts, empty if none were kept
+    async fn artifacts(&self, id: &str) -> StoreResult<Vec<EndpointArtifact>>;
+    /// Returns whether the job existed; its events and artifacts go with it
+    async fn delete_job(&self, id: &str) -> StoreResult<bool>;
+    async fn list_jobs(&self) -> StoreResult<Vec<AuditJob>>;
+
+    /// Append to a job's event log in one write, so concurrent events can't be lost
+    async fn append_event(&self, id: &str, event: &AuditEvent, ttl_secs: u64) -> StoreResult<()>;
+    /// A job's appended events, oldest first
+    async fn events(&self, id: &str) -> StoreResult<Vec<AuditEvent>>;
+
+    /// An agent's jobs, in any order
+    async fn agent_jobs(&self, chain_id: u64, agent_id: u64) -> StoreResult<Vec<AuditJob>> {
+        Ok(self
+            .list_jobs()
+            .await?
+            .into_iter()
+            .filter(|job| job.chain_id == chain_id && job.agent_id == Some(agent_id))
+            .collect())
+    }
+
+    /// A batch's jobs (`AuditParams::batch_id`), in any order
+    async fn batch_jobs(&self, batch_id: &str) -> StoreResult<Vec<AuditJob>> {
+        Ok(self
+            .list_jobs()
+            .await?
+            .into_iter()
+            .filter(|job| job.batch_id() == Some(batch_id))
+            .collect())
+    }
+
+    /// Record an agent's most recent completed audit for a tenant
+    async fn set_latest(
+        &self,
+        tenant: Option<&str>,
+        chain_id: u64,
+        agent_id: u64,
+        id: &str,
+        ttl_secs: u64,
+    ) -> StoreResult<()>;
+    /// ID of an agent's most recent completed audit for a tenant
+    async fn latest(
+        &self,
+        tenant: Option<&str>,
+        chain_id: u64,
+        agent_id: u64,
+    ) -> StoreResult<Option<String>>;
+
+    /// Insert or replace a subscription
+    async fn put_subscription(&self, subscription: &Subscription) -> StoreResult<()>;
+    async fn subscriptions(&self, chain_id: u64, agent_id: u64) -> StoreResult<Vec<Subscription>>;
+    /// Returns whether the subscription existed
+    async fn remove_subscription(
+        &self,
+        chain_id: u64,
+        agent_id: u64,
+        id: &str,
+    ) -> StoreResult<bool>;
+
+    /// Insert or replace an agent's skill index entry
+    async fn put_indexed_agent(&self, entry: &IndexedAgent) -> StoreResult<()>;
+    async fn indexed_agents(&self) -> StoreResult<Vec<IndexedAgent>>;
+
+    /// Insert or replace an agent in the registry table
+    async fn put_registered_agent(&self, agent: &RegisteredAgent) -> StoreResult<()>;
+    async fn registered_agent(
+        &self,
+        chain_id: u64,
+        agent_id: u64,
+    ) -> StoreResult<Option<RegisteredAgent>>;
+    async fn registered_agents(&self, chain_id: u64) -> StoreResult<Vec<RegisteredAgent>>;
+    /// Agents in a chain's registry table owned by `owner` (any case), by agent ID
+    async fn owned_agents(&self, chain_id: u64, owner: &str) -> StoreResult<Vec<RegisteredAgent>> {
+        Ok(self
+            .registered_agents(chain_id)
+            .await?
+            .into_iter()
+            .filter(|agent| agent.owner.eq_ignore_ascii_case(owner))
+            .collect())
+    }
+    async fn indexer_cursor(&self, chain_id: u64) -> StoreResult<Option<u64>>;
+    async fn set_indexer_cursor(&self, chain_id: u64, block: u64) -> StoreResult<()>;
+
+    /// Add to an account's audit count for a month (`YYYY-MM`), returning the new count
+    async fn add_usage(&self, account: &str, month: &str, delta: i64) -> StoreResult<u64>;
+    async fn usage(&self, account: &str, month: &str) -> StoreResult<u64>;
+
+    /// Record a signer as retired, keeping the first retirement time
+    async fn retire_signer(&self, signer: &RetiredSigner) -> StoreResult<()>;
+    async fn retired_signers(&self) -> StoreResult<Vec<RetiredSigner>>;
+
+    /// Check the backend is reachable
+    async fn ping(&self) -> StoreResult<()> {
+        Ok(())
+    }
+
+    /// When the backend became unreachable, if it has gone away since startup
+    fn degraded_since(&self) -> Option<u64> {
+        None
+    }
+}
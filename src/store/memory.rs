@@ -0,0 +1,284 @@
+//! In-memory backend
+//!
+//! Used on its own when no Redis is configured, and by `AuditStore` to hold
+//! records the primary backend failed to take. Nothing expires here: the
+//! retention sweep deletes jobs, and an agent's latest audit is derived from
+//! the stored jobs.
+
+use async_trait::async_trait;
+use std::collections::{BTreeMap, HashMap};
+use tokio::sync::RwLock;
+
+use super::backend::{JobStore, StoreResult};
+use super::{AuditJob, RetiredSigner};
+use crate::events::AuditEvent;
+use crate::indexer::RegisteredAgent;
+use crate::search::IndexedAgent;
+use crate::subscriptions::Subscription;
+use crate::types::{AuditStatus, EndpointArtifact};
+
+#[derive(Default)]
+pub struct MemoryStore {
+    jobs: RwLock<HashMap<String, AuditJob>>,
+    /// Event logs by job ID
+    events: RwLock<HashMap<String, Vec<AuditEvent>>>,
+    /// Endpoint artifacts by job ID; empty when a write dropped them
+    artifacts: RwLock<HashMap<String, Vec<EndpointArtifact>>>,
+    /// Subscriptions by (chain_id, agent_id)
+    subscriptions: RwLock<HashMap<(u64, u64), Vec<Subscription>>>,
+    /// Skill index by (chain_id, agent_id)
+    agent_index: RwLock<HashMap<(u64, u64), IndexedAgent>>,
+    /// Registry table by (chain_id, agent_id)
+    registry: RwLock<HashMap<(u64, u64), RegisteredAgent>>,
+    /// Last indexed block by chain
+    indexer_cursors: RwLock<HashMap<u64, u64>>,
+    /// Audit counts by (account, month)
+    usage: RwLock<HashMap<(String, String), u64>>,
+    /// Retirement times by signer address
+    retired_signers: RwLock<BTreeMap<String, u64>>,
+}
+
+impl MemoryStore {
+    pub async fn job_count(&self) -> usize {
+        self.jobs.read().await.len()
+    }
+
+    /// Jobs with events held here
+    pub async fn event_log_count(&self) -> usize {
+        self.events.read().await.len()
+    }
+
+    /// Drop a job record and its artifacts, keeping its events (a pending copy
+    /// reached the backend)
+    pub async fn remove_job(&self, id: &str) {
+        self.artifacts.write().await.remove(id);
+        self.jobs.write().await.remove(id);
+    }
+
+    /// Artifacts written with a job, if any write held them here
+    pub async fn pending_artifacts(&self, id: &str) -> Option<Vec<EndpointArtifact>> {
+        self.artifacts.read().await.get(id).cloned()
+    }
+
+    /// Move every held event log out, by job ID
+    pub async fn take_events(&self) -> Vec<(String, Vec<AuditEvent>)> {
+        self.events.write().await.drain().collect()
+    }
+}
+
+#[async_trait]
+impl JobStore for MemoryStore {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    async fn put_job(&self, job: &AuditJob, _ttl_secs: u64) -> StoreResult<()> {
+        self.jobs.write().await.insert(job.id.clone(), job.clone());
+        Ok(())
+    }
+
+    async fn put_job_with_artifacts(
+        &self,
+        job: &AuditJob,
+        artifacts: &[EndpointArtifact],
+        _ttl_secs: u64,
+    ) -> StoreResult<()> {
+        self.artifacts.write().await.insert(job.id.clone(), artifacts.to_vec());
+        self.jobs.write().await.insert(job.id.clone(), job.clone());
+        Ok(())
+    }
+
+    async fn get_job(&self, id: &str) -> StoreResult<Option<AuditJob>> {
+        Ok(self.jobs.read().await.get(id).cloned())
+    }
+
+    async fn artifacts(&self, id: &str) -> StoreResult<Vec<EndpointArtifact>> {
+        Ok(self.pending_artifacts(id).await.unwrap_or_default())
+    }
+
+    async fn delete_job(&self, id: &str) -> StoreResult<bool> {
+        self.events.write().await.remove(id);
+        self.artifacts.write().await.remove(id);
+        Ok(self.jobs.write().await.remove(id).is_some())
+    }
+
+    async fn list_jobs(&self) -> StoreResult<Vec<AuditJob>> {
+        Ok(self.jobs.read().await.values().cloned().collect())
+    }
+
+    async fn append_event(&self, id: &str, event: &AuditEvent, _ttl_secs: u64) -> StoreResult<()> {
+        self.events
+            .write()
+            .await
+            .entry(id.to_string())
+            .or_default()
+            .push(event.clone());
+        Ok(())
+    }
+
+    async fn events(&self, id: &str) -> StoreResult<Vec<AuditEvent>> {
+        Ok(self.events.read().await.get(id).cloned().unwrap_or_default())
+    }
+
+    async fn set_latest(
+        &self,
+        _tenant: Option<&str>,
+        _chain_id: u64,
+        _agent_id: u64,
+        _id: &str,
+        _ttl_secs: u64,
+    ) -> StoreResult<()> {
+        Ok(())
+    }
+
+    async fn latest(
+        &self,
+        tenant: Option<&str>,
+        chain_id: u64,
+        agent_id: u64,
+    ) -> StoreResult<Option<String>> {
+        Ok(self
+            .jobs
+            .read()
+            .await
+            .values()
+            .filter(|job| {
+                job.agent_id == Some(agent_id)
+                    && job.chain_id == chain_id
+                    && job.tenant.as_deref() == tenant
+                    && job.status == AuditStatus::Completed
+                    && job.result.is_some()
+            })
+            .max_by_key(|job| job.completed_at)
+            .map(|job| job.id.clone()))
+    }
+
+    async fn put_subscription(&self, subscription: &Subscription) -> StoreResult<()> {
+        let key = (subscription.request.chain_id, subscription.request.agent_id);
+        let mut subscriptions = self.subscriptions.write().await;
+        let entry = subscriptions.entry(key).or_default();
+        entry.retain(|existing| existing.id != subscription.id);
+        entry.push(subscription.clone());
+        Ok(())
+    }
+
+    async fn subscriptions(&self, chain_id: u64, agent_id: u64) -> StoreResult<Vec<Subscription>> {
+        Ok(self
+            .subscriptions
+            .read()
+            .await
+            .get(&(chain_id, agent_id))
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn remove_subscription(
+        &self,
+        chain_id: u64,
+        agent_id: u64,
+        id: &str,
+    ) -> StoreResult<bool> {
+        let mut subscriptions = self.subscriptions.write().await;
+        let Some(entry) = subscriptions.get_mut(&(chain_id, agent_id)) else {
+            return Ok(false);
+        };
+        let before = entry.len();
+        entry.retain(|subscription| subscription.id != id);
+        Ok(entry.len() < before)
+    }
+
+    async fn put_indexed_agent(&self, entry: &IndexedAgent) -> StoreResult<()> {
+        self.agent_index
+            .write()
+            .await
+            .insert((entry.chain_id, entry.agent_id), entry.clone());
+        Ok(())
+    }
+
+    async fn indexed_agents(&self) -> StoreResult<Vec<IndexedAgent>> {
+        Ok(self.agent_index.read().await.values().cloned().collect())
+    }
+
+    async fn put_registered_agent(&self, agent: &RegisteredAgent) -> StoreResult<()> {
+        self.registry
+            .write()
+            .await
+            .insert((agent.chain_id, agent.agent_id), agent.clone());
+        Ok(())
+    }
+
+    async fn registered_agent(
+        &self,
+        chain_id: u64,
+        agent_id: u64,
+    ) -> StoreResult<Option<RegisteredAgent>> {
+        Ok(self
+            .registry
+            .read()
+            .await
+            .get(&(chain_id, agent_id))
+            .cloned())
+    }
+
+    async fn registered_agents(&self, chain_id: u64) -> StoreResult<Vec<RegisteredAgent>> {
+        let agents: BTreeMap<u64, RegisteredAgent> = self
+            .registry
+            .read()
+            .await
+            .values()
+            .filter(|agent| agent.chain_id == chain_id)
+            .map(|agent| (agent.agent_id, agent.clone()))
+            .collect();
+        Ok(agents.into_values().collect())
+    }
+
+    async fn indexer_cursor(&self, chain_id: u64) -> StoreResult<Option<u64>> {
+        Ok(self.indexer_cursors.read().await.get(&chain_id).copied())
+    }
+
+    async fn set_indexer_cursor(&self, chain_id: u64, block: u64) -> StoreResult<()> {
+        self.indexer_cursors.write().await.insert(chain_id, block);
+        Ok(())
+    }
+
+    async fn retire_signer(&self, signer: &RetiredSigner) -> StoreResult<()> {
+        self.retired_signers
+            .write()
+            .await
+            .entry(signer.address.clone())
+            .or_insert(signer.retired_at);
+        Ok(())
+    }
+
+    async fn retired_signers(&self) -> StoreResult<Vec<RetiredSigner>> {
+        Ok(self
+            .retired_signers
+            .read()
+            .await
+            .iter()
+            .map(|(address, &retired_at)| RetiredSigner {
+                address: address.clone(),
+                retired_at,
+            })
+            .collect())
+    }
+
+    async fn add_usage(&self, account: &str, month: &str, delta: i64) -> StoreResult<u64> {
+        let mut usage = self.usage.write().await;
+        let count = usage
+            .entry((account.to_string(), month.to_string()))
+            .or_default();
+        *count = count.saturating_add_signed(delta);
+        Ok(*count)
+    }
+
+    async fn usage(&self, account: &str, month: &str) -> StoreResult<u64> {
+        Ok(self
+            .usage
+            .read()
+            .await
+            .get(&(account.to_string(), month.to_string()))
+            .copied()
+            .unwrap_or_default())
+    }
+}
@@ -0,0 +1,1587 @@
+//! Audit job storage
+//!
+//! `AuditStore` runs the job lifecycle on top of a pluggable `JobStore`
+//! backend (Redis or SQLite when configured), with an in-memory store taking over
+//! whatever the backend fails to persist.
+
+pub mod backend;
+pub mod memory;
+pub mod redis_store;
+pub mod sqlite;
+
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::{debug, error, info, warn};
+
+use crate::events::{AuditEvent, EventKind};
+use crate::indexer::RegisteredAgent;
+use crate::redis_conn::{RedisConfig, RedisConnection};
+use crate::search::IndexedAgent;
+use crate::subscriptions::Subscription;
+use crate::audit::i18n::Lang;
+use crate::types::{AuditError, AuditReport, AuditStatus, EndpointArtifact, PhaseOutcome};
+use backend::JobStore;
+use memory::MemoryStore;
+use redis_store::RedisStore;
+
+/// Default TTL for audit jobs (7 days)
+const AUDIT_TTL_SECONDS: u64 = 7 * 24 * 60 * 60;
+
+/// How long jobs are kept, by status
+///
+/// Pending and in-progress jobs count from creation (or their latest retry),
+/// completed and failed jobs from completion.
+#[derive(Debug, Clone)]
+pub struct RetentionPolicy {
+    pub pending_secs: u64,
+    pub completed_secs: u64,
+    pub failed_secs: u64,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            pending_secs: AUDIT_TTL_SECONDS,
+            completed_secs: AUDIT_TTL_SECONDS,
+            failed_secs: AUDIT_TTL_SECONDS,
+        }
+    }
+}
+
+impl RetentionPolicy {
+    /// Load from `AUDIT_RETENTION_{PENDING,COMPLETED,FAILED}_SECS` (default 7 days each)
+    pub fn from_env() -> anyhow::Result<Self> {
+        let secs = |name: &str| -> anyhow::Result<u64> {
+            match std::env::var(name) {
+                Ok(value) => Ok(value.parse()?),
+                Err(_) => Ok(AUDIT_TTL_SECONDS),
+            }
+        };
+
+        Ok(Self {
+            pending_secs: secs("AUDIT_RETENTION_PENDING_SECS")?,
+            completed_secs: secs("AUDIT_RETENTION_COMPLETED_SECS")?,
+            failed_secs: secs("AUDIT_RETENTION_FAILED_SECS")?,
+        })
+    }
+
+    /// Longest retention of any status, kept by event logs
+    pub fn longest_secs(&self) -> u64 {
+        self.pending_secs.max(self.completed_secs).max(self.failed_secs)
+    }
+
+    pub fn ttl_for(&self, status: &AuditStatus) -> u64 {
+        match status {
+            AuditStatus::Pending | AuditStatus::InProgress => self.pending_secs,
+            AuditStatus::Completed => self.completed_secs,
+            AuditStatus::Failed => self.failed_secs,
+        }
+    }
+
+    /// Unix timestamp after which the job is dropped
+    pub fn expires_at(&self, job: &AuditJob) -> u64 {
+        let since = match job.status {
+            AuditStatus::Pending | AuditStatus::InProgress => job
+                .attempts
+                .last()
+                .map_or(job.created_at, |attempt| attempt.retried_at),
+            AuditStatus::Completed | AuditStatus::Failed => {
+                job.completed_at.unwrap_or(job.created_at)
+            }
+        };
+        since.saturating_add(self.ttl_for(&job.status))
+    }
+}
+
+/// A signer taken out of rotation
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetiredSigner {
+    /// Lowercase hex address
+    pub address: String,
+    /// When it was retired (unix seconds)
+    pub retired_at: u64,
+}
+
+/// Represents an audit job
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct AuditJob {
+    pub id: String,
+    /// `None` for an agent that isn't registered yet (`POST /audit/by-uri`)
+    pub agent_id: Option<u64>,
+    pub chain_id: u64,
+    pub status: AuditStatus,
+    pub created_at: u64,
+    pub completed_at: Option<u64>,
+    pub result: Option<AuditReport>,
+    #[serde(default, deserialize_with = "deserialize_error")]
+    pub error: Option<AuditError>,
+    /// Object storage URL of the archived copy, once archived
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archived_to: Option<String>,
+    /// Timeline of jobs stored before events were appended separately (see
+    /// `AuditStore::job_events`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<AuditEvent>,
+    /// Tenant that requested the audit (`None` = default tenant)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+    /// `X-Request-Id` of the API request that created the job (none for automatic audits)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// Parameters the audit was requested with, reused by retries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<AuditParams>,
+    /// Current attempt, from 1; each retry adds one
+    #[serde(default = "first_attempt")]
+    pub attempt: u32,
+    /// Attempts replaced by retries, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attempts: Vec<AuditAttempt>,
+}
+
+fn first_attempt() -> u32 {
+    1
+}
+
+/// Request parameters of an audit besides the agent and chain
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AuditParams {
+    pub timeout_secs: u64,
+    pub lang: Lang,
+    /// Keyed hash of the requesting API key, for the completion receipt (see `receipts`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requester: Option<String>,
+    /// Batch the audit was queued in (`POST /audit/by-owner`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub batch_id: Option<String>,
+    /// Metadata URI of an unregistered agent (`POST /audit/by-uri`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata_uri: Option<String>,
+}
+
+/// Outcome of an attempt that was retried
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AuditAttempt {
+    pub attempt: u32,
+    pub status: AuditStatus,
+    pub completed_at: Option<u64>,
+    /// When the retry replaced this attempt
+    pub retried_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<AuditError>,
+    /// Overall score of a partially completed attempt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<u8>,
+    /// Phases that failed or were skipped
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub incomplete_phases: Vec<String>,
+    /// Published JSON report of a partially completed attempt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub report_uri: Option<String>,
+}
+
+impl AuditJob {
+    /// Phases of the result that failed or were skipped
+    fn incomplete_phases(&self) -> Vec<String> {
+        self.result
+            .iter()
+            .flat_map(|report| &report.phases)
+            .filter(|phase| phase.outcome != PhaseOutcome::Completed)
+            .map(|phase| phase.phase.clone())
+            .collect()
+    }
+
+    /// Batch the job was queued in (`POST /audit/by-owner`)
+    pub fn batch_id(&self) -> Option<&str> {
+        self.params.as_ref().and_then(|params| params.batch_id.as_deref())
+    }
+
+    /// Earlier attempt whose report was already published
+    pub fn published_attempt(&self) -> Option<&AuditAttempt> {
+        self.attempts.iter().find(|attempt| attempt.report_uri.is_some())
+    }
+
+    /// Failed, or completed without some of its phases
+    pub fn retryable(&self) -> bool {
+        match self.status {
+            AuditStatus::Failed => true,
+            AuditStatus::Completed => !self.incomplete_phases().is_empty(),
+            AuditStatus::Pending | AuditStatus::InProgress => false,
+        }
+    }
+}
+
+/// Jobs stored before errors were structured hold only a message
+fn deserialize_error<'de, D>(deserializer: D) -> Result<Option<AuditError>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum StoredError {
+        Structured(AuditError),
+        Message(String),
+    }
+
+    let stored: Option<StoredError> = serde::Deserialize::deserialize(deserializer)?;
+    Ok(stored.map(|stored| match stored {
+        StoredError::Structured(error) => error,
+        StoredError::Message(message) => AuditError {
+            code: "internal_error".to_string(),
+            message,
+            phase: None,
+            retryable: false,
+            cause: None,
+        },
+    }))
+}
+
+fn status_event(status: &AuditStatus) -> AuditEvent {
+    AuditEvent::new(EventKind::StatusChanged, format!("Status: {:?}", status))
+        .with_data(serde_json::json!({ "status": status }))
+}
+
+/// Audit store: a primary backend with an in-memory fallback
+///
+/// Jobs are written through to the backend. The in-memory store holds every
+/// job when there's no backend; otherwise only jobs whose latest write failed
+/// to reach it. Those copies are newer than the backend's, so reads check them
+/// first, and they're pushed back once the backend answers again.
+pub struct AuditStore {
+    backend: Option<Arc<dyn JobStore>>,
+    /// All records without a backend; records the backend missed with one
+    memory: MemoryStore,
+    /// Serializes job writes with reconciliation, so a pending copy can't
+    /// overwrite a newer write
+    job_writes: Mutex<()>,
+    /// Serializes retries, so concurrent requests can't reset a job twice
+    retries: Mutex<()>,
+    retention: RetentionPolicy,
+}
+
+impl AuditStore {
+    /// Create a new store with Redis connection
+    pub async fn new(redis_config: Option<&RedisConfig>) -> Self {
+        let Some(config) = redis_config else {
+            info!("No Redis URL configured. Using in-memory store.");
+            return Self::in_memory();
+        };
+        match RedisConnection::connect(config).await {
+            Ok(conn) => {
+                info!(
+                    "Connected to Redis ({}) at {}",
+                    config.mode.as_str(),
+                    config.urls.join(",")
+                );
+                Self::with_backend(Arc::new(RedisStore::new(conn)))
+            }
+            Err(e) => {
+                warn!("Failed to connect to Redis: {}. Using in-memory fallback.", e);
+                Self::in_memory()
+            }
+        }
+    }
+
+    /// Create a store on a backend, falling back to memory when it fails
+    pub fn with_backend(backend: Arc<dyn JobStore>) -> Self {
+        Self {
+            backend: Some(backend),
+            ..Self::in_memory()
+        }
+    }
+
+    /// Create a new in-memory only store (for testing)
+    pub fn in_memory() -> Self {
+        Self {
+            backend: None,
+            memory: MemoryStore::default(),
+            job_writes: Mutex::new(()),
+            retries: Mutex::new(()),
+            retention: RetentionPolicy::default(),
+        }
+    }
+
+    /// Set per-status retention (applied to backend TTLs and in-memory sweeps)
+    pub fn with_retention(mut self, retention: RetentionPolicy) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    pub fn retention(&self) -> &RetentionPolicy {
+        &self.retention
+    }
+
+    /// Backend name ("redis"), or "memory" without one
+    pub fn backend_name(&self) -> &'static str {
+        match &self.backend {
+            Some(backend) => backend.name(),
+            None => self.memory.name(),
+        }
+    }
+
+    /// Create a new audit job for a tenant and return its ID
+    pub async fn create_job(
+        &self,
+        agent_id: Option<u64>,
+        chain_id: u64,
+        tenant: Option<&str>,
+        request_id: Option<&str>,
+        params: Option<AuditParams>,
+    ) -> String {
+        let id = format!("aud_{}", uuid::Uuid::new_v4().simple());
+        let now = chrono::Utc::now().timestamp() as u64;
+
+        let job = AuditJob {
+            id: id.clone(),
+            agent_id,
+            chain_id,
+            status: AuditStatus::Pending,
+            created_at: now,
+            completed_at: None,
+            result: None,
+            error: None,
+            archived_to: None,
+            events: Vec::new(),
+            tenant: tenant.map(str::to_string),
+            request_id: request_id.map(str::to_string),
+            params,
+            attempt: 1,
+            attempts: Vec::new(),
+        };
+        self.update_job(&job).await;
+        self.append_event(&id, &status_event(&job.status)).await;
+
+        id
+    }
+
+    /// Reset a retryable job to pending as its next attempt
+    ///
+    /// The current outcome moves to `attempts`. Returns the reset job, or `None`
+    /// if the job doesn't exist or can't be retried.
+    pub async fn retry_job(&self, id: &str) -> Option<AuditJob> {
+        // Checked and reset under the lock: the loser of a race sees the
+        // pending job and gets `None`
+        let _retry = self.retries.lock().await;
+        let mut job = self.get_job(id).await.filter(AuditJob::retryable)?;
+        let now = chrono::Utc::now().timestamp() as u64;
+        let incomplete_phases = job.incomplete_phases();
+        let result = job.result.take();
+        job.attempts.push(AuditAttempt {
+            attempt: job.attempt,
+            status: job.status.clone(),
+            completed_at: job.completed_at.take(),
+            retried_at: now,
+            error: job.error.take(),
+            score: result.as_ref().map(|report| report.scores.overall),
+            incomplete_phases,
+            report_uri: result.and_then(|report| report.report_json_uri),
+        });
+        job.attempt += 1;
+        job.status = AuditStatus::Pending;
+        self.write_job(&job, Some(&[])).await;
+        let retried = AuditEvent::new(EventKind::AuditRetried, format!("Attempt {}", job.attempt))
+            .with_data(serde_json::json!({ "attempt": job.attempt }));
+        self.append_event(id, &retried).await;
+        self.append_event(id, &status_event(&job.status)).await;
+        Some(job)
+    }
+
+    /// Get a job by ID, if it belongs to the tenant
+    pub async fn get_tenant_job(&self, id: &str, tenant: Option<&str>) -> Option<AuditJob> {
+        self.get_job(id)
+            .await
+            .filter(|job| job.tenant.as_deref() == tenant)
+    }
+
+    /// Get a job by ID
+    pub async fn get_job(&self, id: &str) -> Option<AuditJob> {
+        // A pending copy is newer than whatever the backend has
+        if let Ok(Some(job)) = self.memory.get_job(id).await {
+            return Some(job);
+        }
+
+        let backend = self.backend.as_ref()?;
+        match backend.get_job(id).await {
+            Ok(job) => job,
+            Err(e) => {
+                error!("{} GET failed for job {}: {}", backend.name(), id, e);
+                None
+            }
+        }
+    }
+
+    /// Remaining retention of a job, counted from its status' reference time
+    fn ttl(&self, job: &AuditJob) -> u64 {
+        let now = chrono::Utc::now().timestamp() as u64;
+        self.retention.expires_at(job).saturating_sub(now).max(1)
+    }
+
+    /// Write a job through to the backend, keeping it in memory if that fails
+    async fn update_job(&self, job: &AuditJob) {
+        self.write_job(job, None).await;
+    }
+
+    /// Write a job, and its artifacts when given, in one update
+    async fn write_job(&self, job: &AuditJob, artifacts: Option<&[EndpointArtifact]>) {
+        let Some(backend) = &self.backend else {
+            let _ = match artifacts {
+                Some(artifacts) => self.memory.put_job_with_artifacts(job, artifacts, 0).await,
+                None => self.memory.put_job(job, 0).await,
+            };
+            return;
+        };
+
+        let _writes = self.job_writes.lock().await;
+        // Artifacts a failed write left in memory go out with the next one
+        let artifacts = match artifacts {
+            Some(artifacts) => Some(artifacts.to_vec()),
+            None => self.memory.pending_artifacts(&job.id).await,
+        };
+        let written = match &artifacts {
+            Some(artifacts) => backend.put_job_with_artifacts(job, artifacts, self.ttl(job)).await,
+            None => backend.put_job(job, self.ttl(job)).await,
+        };
+        match written {
+            Ok(()) => {
+                debug!("Stored job {} in {}", job.id, backend.name());
+                self.memory.remove_job(&job.id).await;
+                // The backend is answering again: flush what earlier failures left behind
+                if self.memory.job_count().await > 0 || self.memory.event_log_count().await > 0 {
+                    self.reconcile_locked(backend.as_ref()).await;
+                }
+            }
+            Err(e) => {
+                error!(
+                    "{} SET failed for job {}: {}. Keeping it in memory.",
+                    backend.name(),
+                    job.id,
+                    e
+                );
+                let _ = match &artifacts {
+                    Some(artifacts) => self.memory.put_job_with_artifacts(job, artifacts, 0).await,
+                    None => self.memory.put_job(job, 0).await,
+                };
+            }
+        }
+    }
+
+    /// Push jobs kept in memory during a backend outage back to the backend
+    ///
+    /// Returns how many jobs were synced.
+    pub async fn reconcile_jobs(&self) -> usize {
+        let Some(backend) = &self.backend else {
+            return 0;
+        };
+        let _writes = self.job_writes.lock().await;
+        self.reconcile_locked(backend.as_ref()).await
+    }
+
+    async fn reconcile_locked(&self, backend: &dyn JobStore) -> usize {
+        let mut synced = 0;
+        for job in self.memory.list_jobs().await.unwrap_or_default() {
+            let written = match self.memory.pending_artifacts(&job.id).await {
+                Some(artifacts) => {
+                    backend.put_job_with_artifacts(&job, &artifacts, self.ttl(&job)).await
+                }
+                None => backend.put_job(&job, self.ttl(&job)).await,
+            };
+            if let Err(e) = written {
+                warn!("Reconciling job {} with {} failed: {}", job.id, backend.name(), e);
+                break;
+            }
+            if job.status == AuditStatus::Completed {
+                self.set_latest_if_newer(backend, &job).await;
+            }
+            self.memory.remove_job(&job.id).await;
+            synced += 1;
+        }
+        self.reconcile_events(backend).await;
+        if synced > 0 {
+            info!(
+                "Reconciled {} jobs with {} ({} still pending)",
+                synced,
+                backend.name(),
+                self.memory.job_count().await
+            );
+        }
+        synced
+    }
+
+    /// Push events kept in memory during a backend outage to the backend
+    async fn reconcile_events(&self, backend: &dyn JobStore) {
+        let ttl = self.retention.longest_secs();
+        let mut logs = self.memory.take_events().await.into_iter();
+        while let Some((id, events)) = logs.next() {
+            for (i, event) in events.iter().enumerate() {
+                if let Err(e) = backend.append_event(&id, event, ttl).await {
+                    warn!("Reconciling events of job {} with {} failed: {}", id, backend.name(), e);
+                    // Keep what didn't make it for the next attempt
+                    let unsent = std::iter::once((id, events[i..].to_vec())).chain(logs);
+                    for (id, events) in unsent {
+                        for event in events {
+                            let _ = self.memory.append_event(&id, &event, 0).await;
+                        }
+                    }
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Jobs held in memory because the backend missed their latest write
+    pub async fn pending_jobs(&self) -> usize {
+        if self.backend.is_some() {
+            self.memory.job_count().await
+        } else {
+            0
+        }
+    }
+
+    /// Update job status
+    pub async fn update_status(&self, id: &str, status: AuditStatus) {
+        if let Some(mut job) = self.get_job(id).await {
+            let event = status_event(&status);
+            job.status = status;
+            self.update_job(&job).await;
+            self.append_event(id, &event).await;
+        }
+    }
+
+    /// Set job result (marks as completed), with the endpoint responses the
+    /// audit judged
+    pub async fn set_result(&self, id: &str, result: AuditReport, artifacts: &[EndpointArtifact]) {
+        if let Some(mut job) = self.get_job(id).await {
+            job.status = AuditStatus::Completed;
+            job.completed_at = Some(chrono::Utc::now().timestamp() as u64);
+            job.result = Some(result);
+            self.write_job(&job, Some(artifacts)).await;
+            self.append_event(id, &status_event(&job.status)).await;
+
+            // An unregistered agent has no latest audit to point at
+            if let (Some(backend), Some(agent_id)) = (&self.backend, job.agent_id) {
+                let ttl = self.retention.completed_secs;
+                let tenant = job.tenant.as_deref();
+                if let Err(e) = backend.set_latest(tenant, job.chain_id, agent_id, &job.id, ttl).await {
+                    error!("{} SET failed for latest audit of {}: {}", backend.name(), agent_id, e);
+                }
+            }
+        }
+    }
+
+    /// Point the agent's latest audit at a reconciled job unless a newer one is there
+    async fn set_latest_if_newer(&self, backend: &dyn JobStore, job: &AuditJob) {
+        let Some(agent_id) = job.agent_id else {
+            return;
+        };
+        let tenant = job.tenant.as_deref();
+        if let Ok(Some(id)) = backend.latest(tenant, job.chain_id, agent_id).await {
+            let newer = backend
+                .get_job(&id)
+                .await
+                .ok()
+                .flatten()
+                .is_some_and(|current| current.completed_at >= job.completed_at);
+            if newer {
+                return;
+            }
+        }
+        let ttl = self.retention.completed_secs;
+        if let Err(e) = backend.set_latest(tenant, job.chain_id, agent_id, &job.id, ttl).await {
+            error!("{} SET failed for latest audit of {}: {}", backend.name(), agent_id, e);
+        }
+    }
+
+    /// Most recent completed audit of an agent for a tenant, if still retained
+    pub async fn latest_completed(
+        &self,
+        agent_id: u64,
+        chain_id: u64,
+        tenant: Option<&str>,
+    ) -> Option<AuditJob> {
+        let mut latest = None;
+        if let Some(backend) = &self.backend {
+            match backend.latest(tenant, chain_id, agent_id).await {
+                Ok(Some(id)) => latest = self.get_job(&id).await,
+                Ok(None) => {}
+                Err(e) => error!("{} GET failed for latest audit of {}: {}", backend.name(), agent_id, e),
+            }
+        }
+
+        // Completed while the backend was unreachable, or no backend at all
+        let in_memory = match self.memory.latest(tenant, chain_id, agent_id).await {
+            Ok(Some(id)) => self.memory.get_job(&id).await.ok().flatten(),
+            _ => None,
+        };
+        match (latest, in_memory) {
+            (Some(latest), Some(in_memory)) if in_memory.completed_at > latest.completed_at => {
+                Some(in_memory)
+            }
+            (latest, in_memory) => latest.or(in_memory),
+        }
+    }
+
+    /// Set job error (marks as failed)
+    pub async fn set_error(&self, id: &str, error: AuditError) {
+        if let Some(mut job) = self.get_job(id).await {
+            job.status = AuditStatus::Failed;
+            job.completed_at = Some(chrono::Utc::now().timestamp() as u64);
+            job.error = Some(error);
+            self.update_job(&job).await;
+            self.append_event(id, &status_event(&job.status)).await;
+        }
+    }
+
+    /// Record where a job was archived
+    pub async fn set_archived(&self, id: &str, location: &str) {
+        if let Some(mut job) = self.get_job(id).await {
+            job.archived_to = Some(location.to_string());
+            self.update_job(&job).await;
+        }
+    }
+
+    /// Endpoint responses a job's audit judged
+    pub async fn artifacts(&self, id: &str) -> Vec<EndpointArtifact> {
+        // A pending copy is newer than whatever the backend has
+        if let Some(artifacts) = self.memory.pending_artifacts(id).await {
+            return artifacts;
+        }
+        let Some(backend) = &self.backend else {
+            return Vec::new();
+        };
+        match backend.artifacts(id).await {
+            Ok(artifacts) => artifacts,
+            Err(e) => {
+                error!("{} GET failed for artifacts of job {}: {}", backend.name(), id, e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Append an event to a job's timeline
+    ///
+    /// A single append, not a rewrite of the job, so events recorded
+    /// concurrently (phases running in parallel) are all kept.
+    pub async fn append_event(&self, id: &str, event: &AuditEvent) {
+        if let Some(backend) = &self.backend {
+            match backend.append_event(id, event, self.retention.longest_secs()).await {
+                Ok(()) => return,
+                Err(e) => error!(
+                    "{} append failed for events of job {}: {}. Keeping it in memory.",
+                    backend.name(),
+                    id,
+                    e
+                ),
+            }
+        }
+        let _ = self.memory.append_event(id, event, 0).await;
+    }
+
+    /// Get a job's events in the order they were recorded
+    pub async fn get_events(&self, id: &str) -> Vec<AuditEvent> {
+        match self.get_job(id).await {
+            Some(job) => self.job_events(&job).await,
+            None => Vec::new(),
+        }
+    }
+
+    /// A job's events in the order they were recorded, including any held in
+    /// memory during a backend outage
+    pub async fn job_events(&self, job: &AuditJob) -> Vec<AuditEvent> {
+        let mut events = job.events.clone();
+        if let Some(backend) = &self.backend {
+            match backend.events(&job.id).await {
+                Ok(stored) => events.extend(stored),
+                Err(e) => error!("{} GET failed for events of job {}: {}", backend.name(), job.id, e),
+            }
+        }
+        events.extend(self.memory.events(&job.id).await.unwrap_or_default());
+        events.sort_by_key(|event| event.timestamp);
+        events
+    }
+
+    /// Delete a job. Returns whether it existed.
+    pub async fn delete_job(&self, id: &str) -> bool {
+        let mut deleted = self.memory.delete_job(id).await.unwrap_or_default();
+
+        if let Some(backend) = &self.backend {
+            match backend.delete_job(id).await {
+                Ok(existed) => deleted |= existed,
+                Err(e) => error!("{} DEL failed for job {}: {}", backend.name(), id, e),
+            }
+        }
+
+        deleted
+    }
+
+    /// List all stored jobs (the backend's, overlaid with jobs pending a write)
+    pub async fn list_jobs(&self) -> Vec<AuditJob> {
+        let mut jobs: std::collections::HashMap<String, AuditJob> =
+            std::collections::HashMap::new();
+
+        if let Some(backend) = &self.backend {
+            match backend.list_jobs().await {
+                Ok(listed) => jobs.extend(listed.into_iter().map(|job| (job.id.clone(), job))),
+                Err(e) => error!("{} listing failed: {}. Listing memory only.", backend.name(), e),
+            }
+        }
+        for job in self.memory.list_jobs().await.unwrap_or_default() {
+            jobs.insert(job.id.clone(), job);
+        }
+
+        jobs.into_values().collect()
+    }
+
+    /// An agent's jobs for a tenant, newest first
+    pub async fn agent_jobs(
+        &self,
+        chain_id: u64,
+        agent_id: u64,
+        tenant: Option<&str>,
+    ) -> Vec<AuditJob> {
+        let mut jobs: std::collections::HashMap<String, AuditJob> =
+            std::collections::HashMap::new();
+
+        if let Some(backend) = &self.backend {
+            match backend.agent_jobs(chain_id, agent_id).await {
+                Ok(listed) => jobs.extend(listed.into_iter().map(|job| (job.id.clone(), job))),
+                Err(e) => error!("{} listing failed for agent {}: {}", backend.name(), agent_id, e),
+            }
+        }
+        for job in self.memory.agent_jobs(chain_id, agent_id).await.unwrap_or_default() {
+            jobs.insert(job.id.clone(), job);
+        }
+
+        let mut jobs: Vec<AuditJob> = jobs
+            .into_values()
+            .filter(|job| job.tenant.as_deref() == tenant)
+            .collect();
+        jobs.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| b.id.cmp(&a.id)));
+        jobs
+    }
+
+    /// A batch's jobs for a tenant, oldest first
+    pub async fn batch_jobs(&self, batch_id: &str, tenant: Option<&str>) -> Vec<AuditJob> {
+        let mut jobs: std::collections::HashMap<String, AuditJob> =
+            std::collections::HashMap::new();
+
+        if let Some(backend) = &self.backend {
+            match backend.batch_jobs(batch_id).await {
+                Ok(listed) => jobs.extend(listed.into_iter().map(|job| (job.id.clone(), job))),
+                Err(e) => error!("{} listing failed for batch {}: {}", backend.name(), batch_id, e),
+            }
+        }
+        for job in self.memory.batch_jobs(batch_id).await.unwrap_or_default() {
+            jobs.insert(job.id.clone(), job);
+        }
+
+        let mut jobs: Vec<AuditJob> = jobs
+            .into_values()
+            .filter(|job| job.tenant.as_deref() == tenant)
+            .collect();
+        jobs.sort_by(|a, b| {
+            a.created_at
+                .cmp(&b.created_at)
+                .then_with(|| a.agent_id.cmp(&b.agent_id))
+        });
+        jobs
+    }
+
+    /// Add or replace a notification subscription
+    pub async fn put_subscription(&self, subscription: &Subscription) {
+        if let Some(backend) = &self.backend {
+            match backend.put_subscription(subscription).await {
+                Ok(()) => return,
+                Err(e) => error!(
+                    "{} write failed for subscription {}: {}",
+                    backend.name(),
+                    subscription.id,
+                    e
+                ),
+            }
+        }
+        let _ = self.memory.put_subscription(subscription).await;
+    }
+
+    /// Notification subscriptions for an agent
+    pub async fn subscriptions(&self, chain_id: u64, agent_id: u64) -> Vec<Subscription> {
+        let mut subscriptions = self
+            .memory
+            .subscriptions(chain_id, agent_id)
+            .await
+            .unwrap_or_default();
+
+        if let Some(backend) = &self.backend {
+            match backend.subscriptions(chain_id, agent_id).await {
+                Ok(stored) => subscriptions.extend(stored),
+                Err(e) => error!(
+                    "{} read failed for subscriptions of {}: {}",
+                    backend.name(),
+                    agent_id,
+                    e
+                ),
+            }
+        }
+
+        subscriptions
+    }
+
+    /// Remove a subscription. Returns whether it existed.
+    pub async fn remove_subscription(&self, chain_id: u64, agent_id: u64, id: &str) -> bool {
+        let mut removed = self
+            .memory
+            .remove_subscription(chain_id, agent_id, id)
+            .await
+            .unwrap_or_default();
+
+        if let Some(backend) = &self.backend {
+            match backend.remove_subscription(chain_id, agent_id, id).await {
+                Ok(existed) => removed |= existed,
+                Err(e) => error!("{} delete failed for subscription {}: {}", backend.name(), id, e),
+            }
+        }
+
+        removed
+    }
+
+    /// Record an agent's latest skills and score, replacing its previous entry
+    pub async fn index_agent(&self, entry: &IndexedAgent) {
+        if let Some(backend) = &self.backend {
+            match backend.put_indexed_agent(entry).await {
+                Ok(()) => return,
+                Err(e) => error!(
+                    "{} write failed for index entry {}: {}",
+                    backend.name(),
+                    entry.agent_id,
+                    e
+                ),
+            }
+        }
+        let _ = self.memory.put_indexed_agent(entry).await;
+    }
+
+    /// Every agent in the skill index
+    pub async fn indexed_agents(&self) -> Vec<IndexedAgent> {
+        let mut agents: std::collections::HashMap<(u64, u64), IndexedAgent> = self
+            .memory
+            .indexed_agents()
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|agent| ((agent.chain_id, agent.agent_id), agent))
+            .collect();
+
+        if let Some(backend) = &self.backend {
+            match backend.indexed_agents().await {
+                Ok(stored) => {
+                    agents.extend(stored.into_iter().map(|a| ((a.chain_id, a.agent_id), a)))
+                }
+                Err(e) => error!("{} read failed for the skill index: {}", backend.name(), e),
+            }
+        }
+
+        agents.into_values().collect()
+    }
+
+    /// Add or update an agent in the registry table
+    pub async fn put_registered_agent(&self, agent: &RegisteredAgent) {
+        if let Some(backend) = &self.backend {
+            match backend.put_registered_agent(agent).await {
+                Ok(()) => return,
+                Err(e) => error!(
+                    "{} write failed for agent {}: {}",
+                    backend.name(),
+                    agent.agent_id,
+                    e
+                ),
+            }
+        }
+        let _ = self.memory.put_registered_agent(agent).await;
+    }
+
+    /// An agent from the registry table
+    pub async fn registered_agent(&self, chain_id: u64, agent_id: u64) -> Option<RegisteredAgent> {
+        if let Some(backend) = &self.backend {
+            match backend.registered_agent(chain_id, agent_id).await {
+                Ok(Some(agent)) => return Some(agent),
+                Ok(None) => {}
+                Err(e) => error!("{} read failed for agent {}: {}", backend.name(), agent_id, e),
+            }
+        }
+
+        self.memory
+            .registered_agent(chain_id, agent_id)
+            .await
+            .ok()
+            .flatten()
+    }
+
+    /// Every agent in a chain's registry table, by agent ID
+    pub async fn registered_agents(&self, chain_id: u64) -> Vec<RegisteredAgent> {
+        let mut agents: std::collections::BTreeMap<u64, RegisteredAgent> = self
+            .memory
+            .registered_agents(chain_id)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|agent| (agent.agent_id, agent))
+            .collect();
+
+        if let Some(backend) = &self.backend {
+            match backend.registered_agents(chain_id).await {
+                Ok(stored) => agents.extend(stored.into_iter().map(|a| (a.agent_id, a))),
+                Err(e) => error!(
+                    "{} read failed for registry of chain {}: {}",
+                    backend.name(),
+                    chain_id,
+                    e
+                ),
+            }
+        }
+
+        agents.into_values().collect()
+    }
+
+    /// Agents in a chain's registry table owned by `owner` (any case), by agent ID
+    pub async fn owned_agents(&self, chain_id: u64, owner: &str) -> Vec<RegisteredAgent> {
+        let mut agents: std::collections::BTreeMap<u64, RegisteredAgent> = self
+            .memory
+            .owned_agents(chain_id, owner)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|agent| (agent.agent_id, agent))
+            .collect();
+
+        if let Some(backend) = &self.backend {
+            match backend.owned_agents(chain_id, owner).await {
+                Ok(stored) => agents.extend(stored.into_iter().map(|a| (a.agent_id, a))),
+                Err(e) => error!(
+                    "{} read failed for agents of {} on chain {}: {}",
+                    backend.name(),
+                    owner,
+                    chain_id,
+                    e
+                ),
+            }
+        }
+
+        agents.into_values().collect()
+    }
+
+    /// Last block the indexer has processed on a chain
+    pub async fn indexer_cursor(&self, chain_id: u64) -> Option<u64> {
+        if let Some(backend) = &self.backend {
+            match backend.indexer_cursor(chain_id).await {
+                Ok(Some(block)) => return Some(block),
+                Ok(None) => {}
+                Err(e) => error!(
+                    "{} read failed for indexer cursor of chain {}: {}",
+                    backend.name(),
+                    chain_id,
+                    e
+                ),
+            }
+        }
+
+        self.memory.indexer_cursor(chain_id).await.ok().flatten()
+    }
+
+    pub async fn set_indexer_cursor(&self, chain_id: u64, block: u64) {
+        if let Some(backend) = &self.backend {
+            match backend.set_indexer_cursor(chain_id, block).await {
+                Ok(()) => return,
+                Err(e) => error!(
+                    "{} write failed for indexer cursor of chain {}: {}",
+                    backend.name(),
+                    chain_id,
+                    e
+                ),
+            }
+        }
+        let _ = self.memory.set_indexer_cursor(chain_id, block).await;
+    }
+
+    /// Add to an account's audit count for a month, returning the new count
+    ///
+    /// Increments the backend misses are counted in memory and added on read.
+    pub async fn add_usage(&self, account: &str, month: &str, delta: i64) -> u64 {
+        if let Some(backend) = &self.backend {
+            match backend.add_usage(account, month, delta).await {
+                Ok(count) => {
+                    let missed = self.memory.usage(account, month).await.unwrap_or_default();
+                    return count + missed;
+                }
+                Err(e) => error!("{} write failed for usage of {}: {}", backend.name(), account, e),
+            }
+        }
+        let _ = self.memory.add_usage(account, month, delta).await;
+        self.usage(account, month).await
+    }
+
+    /// An account's audit count for a month (`YYYY-MM`)
+    pub async fn usage(&self, account: &str, month: &str) -> u64 {
+        let mut count = self.memory.usage(account, month).await.unwrap_or_default();
+        if let Some(backend) = &self.backend {
+            match backend.usage(account, month).await {
+                Ok(stored) => count += stored,
+                Err(e) => error!("{} read failed for usage of {}: {}", backend.name(), account, e),
+            }
+        }
+        count
+    }
+
+    /// Stop using a signer address for reports and feedback from now on
+    pub async fn retire_signer(&self, address: &str) {
+        let signer = RetiredSigner {
+            address: address.to_lowercase(),
+            retired_at: chrono::Utc::now().timestamp() as u64,
+        };
+        if let Some(backend) = &self.backend {
+            match backend.retire_signer(&signer).await {
+                Ok(()) => return,
+                Err(e) => error!(
+                    "{} write failed for retired signer {}: {}",
+                    backend.name(),
+                    signer.address,
+                    e
+                ),
+            }
+        }
+        let _ = self.memory.retire_signer(&signer).await;
+    }
+
+    /// Retired signers, by address
+    ///
+    /// Retirements the backend missed are kept in memory and always included;
+    /// an address retired twice keeps the earlier time.
+    pub async fn retired_signers(&self) -> Vec<RetiredSigner> {
+        let mut retired = self.memory.retired_signers().await.unwrap_or_default();
+        if let Some(backend) = &self.backend {
+            match backend.retired_signers().await {
+                Ok(stored) => retired.extend(stored),
+                Err(e) => error!("{} read failed for retired signers: {}", backend.name(), e),
+            }
+        }
+        retired.sort_by(|a, b| (&a.address, a.retired_at).cmp(&(&b.address, b.retired_at)));
+        retired.dedup_by(|later, earlier| later.address == earlier.address);
+        retired
+    }
+
+    /// When the backend became unreachable, if it has gone away since startup
+    ///
+    /// Pings it first, so an outage shows up even when nothing else is
+    /// touching the store.
+    pub async fn degraded_since(&self) -> Option<u64> {
+        let backend = self.backend.as_ref()?;
+        if let Err(e) = backend.ping().await {
+            debug!("{} PING failed: {}", backend.name(), e);
+        }
+        backend.degraded_since()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::WatchyError;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    #[tokio::test]
+    async fn test_events_in_memory() {
+        let store = AuditStore::in_memory();
+        let id = store.create_job(Some(17), 8453, None, None, None).await;
+
+        store
+            .append_event(&id, &AuditEvent::new(EventKind::AuditStarted, "started"))
+            .await;
+        store
+            .append_event(&id, &AuditEvent::new(EventKind::AuditCompleted, "done"))
+            .await;
+
+        store.update_status(&id, AuditStatus::InProgress).await;
+
+        let events = store.get_events(&id).await;
+        let kinds: Vec<EventKind> = events.iter().map(|e| e.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                EventKind::StatusChanged,
+                EventKind::AuditStarted,
+                EventKind::AuditCompleted,
+                EventKind::StatusChanged
+            ]
+        );
+
+        assert!(store.delete_job(&id).await);
+        assert!(store.get_events(&id).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_latest_completed() {
+        let store = AuditStore::in_memory();
+        let report = || AuditReport::new(17, 8453, "0x8004", "ipfs://meta".to_string(), None);
+
+        let first = store.create_job(Some(17), 8453, None, None, None).await;
+        store.set_result(&first, report(), &[]).await;
+        let failed = store.create_job(Some(17), 8453, None, None, None).await;
+        store
+            .set_error(&failed, (&WatchyError::Internal("boom".to_string())).into())
+            .await;
+        store.create_job(Some(17), 8453, None, None, None).await;
+
+        let latest = store.latest_completed(17, 8453, None).await.unwrap();
+        assert_eq!(latest.id, first);
+        let failed = store.get_job(&failed).await.unwrap();
+        assert_eq!(failed.error.as_ref().unwrap().code, "internal_error");
+
+        // Jobs stored with a plain error message still load
+        let mut legacy = serde_json::to_value(&failed).unwrap();
+        legacy["error"] = serde_json::json!("boom");
+        let legacy: AuditJob = serde_json::from_value(legacy).unwrap();
+        assert_eq!(legacy.error.unwrap().message, "boom");
+        assert!(store.latest_completed(17, 1, None).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_retry_job() {
+        let store = AuditStore::in_memory();
+        let params = AuditParams {
+            timeout_secs: 60,
+            lang: Lang::Es,
+            requester: None,
+            batch_id: None,
+            metadata_uri: None,
+        };
+        let id = store.create_job(Some(17), 8453, None, None, Some(params)).await;
+        // Pending and complete jobs can't be retried
+        assert!(store.retry_job(&id).await.is_none());
+        let complete = AuditReport::new(17, 8453, "0x8004", "ipfs://meta".to_string(), None);
+        store.set_result(&id, complete, &[]).await;
+        assert!(store.retry_job(&id).await.is_none());
+
+        let failed = store.create_job(Some(17), 8453, None, None, None).await;
+        store
+            .set_error(&failed, (&WatchyError::Internal("boom".to_string())).into())
+            .await;
+        let job = store.retry_job(&failed).await.unwrap();
+        assert_eq!(job.attempt, 2);
+        assert_eq!(job.status, AuditStatus::Pending);
+        assert!(job.error.is_none() && job.completed_at.is_none());
+        assert_eq!(job.attempts.len(), 1);
+        assert_eq!(job.attempts[0].status, AuditStatus::Failed);
+        assert_eq!(job.attempts[0].error.as_ref().unwrap().message, "Internal error: boom");
+
+        // Partial: a phase was skipped
+        let mut partial = AuditReport::new(17, 8453, "0x8004", "ipfs://meta".to_string(), None);
+        partial.skip_phases(&["endpoints"], "metadata unavailable");
+        store.set_result(&failed, partial, &[artifact()]).await;
+        assert_eq!(store.artifacts(&failed).await.len(), 1);
+        let job = store.retry_job(&failed).await.unwrap();
+        assert!(store.artifacts(&failed).await.is_empty());
+        assert_eq!(job.attempt, 3);
+        assert_eq!(job.attempts[1].incomplete_phases, vec!["endpoints"]);
+        assert!(job.result.is_none());
+        assert!(job.published_attempt().is_none());
+        assert_eq!(store.get_job(&id).await.unwrap().params.unwrap().lang, Lang::Es);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_retries() {
+        let store = Arc::new(AuditStore::in_memory());
+        let id = store.create_job(Some(17), 8453, None, None, None).await;
+        let mut published = AuditReport::new(17, 8453, "0x8004", "ipfs://meta".to_string(), None);
+        published.skip_phases(&["endpoints"], "metadata unavailable");
+        published.report_json_uri = Some("ar://report".to_string());
+        store.set_result(&id, published, &[]).await;
+
+        let retries = (0..8).map(|_| {
+            let store = store.clone();
+            let id = id.clone();
+            tokio::spawn(async move { store.retry_job(&id).await })
+        });
+        let mut won = Vec::new();
+        for retry in retries {
+            won.extend(retry.await.unwrap());
+        }
+        assert_eq!(won.len(), 1);
+        assert_eq!(won[0].attempt, 2);
+        let attempt = won[0].published_attempt().unwrap();
+        assert_eq!(attempt.report_uri.as_deref(), Some("ar://report"));
+    }
+
+    #[tokio::test]
+    async fn test_agent_index_replaces_entry() {
+        let store = AuditStore::in_memory();
+        let report = AuditReport::new(17, 8453, "0x8004", "ipfs://meta".to_string(), None);
+
+        store
+            .index_agent(&crate::search::index_entry("aud_1", 8453, &report, None, 1))
+            .await;
+        store
+            .index_agent(&crate::search::index_entry("aud_2", 8453, &report, None, 2))
+            .await;
+
+        let agents = store.indexed_agents().await;
+        assert_eq!(agents.len(), 1);
+        assert_eq!(agents[0].audit_id, "aud_2");
+    }
+
+    /// Minimal RESP server: GET/SETEX/DEL/PING, failing writes while `failing` is set
+    async fn fake_redis(failing: Arc<AtomicBool>) -> String {
+        use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+
+        async fn read_command<R: tokio::io::AsyncBufRead + Unpin>(
+            reader: &mut R,
+        ) -> Option<Vec<String>> {
+            let mut line = String::new();
+            reader.read_line(&mut line).await.ok()?;
+            let count: usize = line.trim().strip_prefix('*')?.parse().ok()?;
+            let mut args = Vec::new();
+            for _ in 0..count {
+                line.clear();
+                reader.read_line(&mut line).await.ok()?;
+                let len: usize = line.trim().strip_prefix('$')?.parse().ok()?;
+                let mut buf = vec![0; len + 2];
+                reader.read_exact(&mut buf).await.ok()?;
+                args.push(String::from_utf8_lossy(&buf[..len]).into_owned());
+            }
+            Some(args)
+        }
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("redis://{}", listener.local_addr().unwrap());
+        let data = Arc::new(std::sync::Mutex::new(std::collections::HashMap::new()));
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                let (data, failing) = (data.clone(), failing.clone());
+                tokio::spawn(async move {
+                    let (read, mut write) = socket.into_split();
+                    let mut reader = BufReader::new(read);
+                    while let Some(args) = read_command(&mut reader).await {
+                        let command = args[0].to_uppercase();
+                        let writes = ["SET", "SETEX", "DEL"].contains(&command.as_str());
+                        let reply = {
+                            let mut data = data.lock().unwrap();
+                            match command.as_str() {
+                                _ if writes && failing.load(Ordering::SeqCst) => {
+                                    "-ERR injected failure\r\n".to_string()
+                                }
+                                "PING" => "+PONG\r\n".to_string(),
+                                "SETEX" => {
+                                    data.insert(args[1].clone(), args[3].clone());
+                                    "+OK\r\n".to_string()
+                                }
+                                "GET" => match data.get(&args[1]) {
+                                    Some(value) => format!("${}\r\n{}\r\n", value.len(), value),
+                                    None => "$-1\r\n".to_string(),
+                                },
+                                "DEL" => format!(":{}\r\n", data.remove(&args[1]).is_some() as u8),
+                                _ => "+OK\r\n".to_string(),
+                            }
+                        };
+                        if write.write_all(reply.as_bytes()).await.is_err() {
+                            return;
+                        }
+                    }
+                });
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_write_through_with_redis_failures() {
+        let failing = Arc::new(AtomicBool::new(false));
+        let config = RedisConfig {
+            mode: crate::redis_conn::RedisMode::Single,
+            urls: vec![fake_redis(failing.clone()).await],
+            sentinel_master: "mymaster".to_string(),
+        };
+        let store = AuditStore::new(Some(&config)).await;
+        assert_eq!(store.backend_name(), "redis");
+
+        let first = store.create_job(Some(17), 8453, None, None, None).await;
+        let second = store.create_job(Some(18), 8453, None, None, None).await;
+        assert_eq!(store.pending_jobs().await, 0);
+
+        // Created in Redis, updated while its writes fail: the newer copy wins
+        failing.store(true, Ordering::SeqCst);
+        store.update_status(&first, AuditStatus::InProgress).await;
+        let report = AuditReport::new(18, 8453, "0x8004", "ipfs://meta".to_string(), None);
+        store.set_result(&second, report, &[]).await;
+        assert_eq!(store.pending_jobs().await, 2);
+        assert_eq!(store.get_job(&first).await.unwrap().status, AuditStatus::InProgress);
+        assert_eq!(store.latest_completed(18, 8453, None).await.unwrap().id, second);
+        let listed = store.list_jobs().await;
+        assert_eq!(listed.len(), 2);
+        assert!(listed.iter().all(|job| job.status != AuditStatus::Pending));
+
+        // The next successful write flushes the pending jobs back to Redis
+        failing.store(false, Ordering::SeqCst);
+        let third = store.create_job(Some(19), 8453, None, None, None).await;
+        assert_eq!(store.pending_jobs().await, 0);
+        assert_eq!(store.get_job(&first).await.unwrap().status, AuditStatus::InProgress);
+        assert_eq!(store.latest_completed(18, 8453, None).await.unwrap().id, second);
+        assert_eq!(store.get_job(&third).await.unwrap().status, AuditStatus::Pending);
+    }
+
+    #[tokio::test]
+    async fn test_tenants_are_isolated() {
+        let store = AuditStore::in_memory();
+        let acme = store.create_job(Some(17), 8453, Some("acme"), None, None).await;
+        let default = store.create_job(Some(17), 8453, None, None, None).await;
+        for id in [&acme, &default] {
+            let report = AuditReport::new(17, 8453, "0x8004", "ipfs://meta".to_string(), None);
+            store.set_result(id, report, &[]).await;
+        }
+
+        assert!(store.get_tenant_job(&acme, Some("acme")).await.is_some());
+        assert!(store.get_tenant_job(&acme, None).await.is_none());
+        assert!(store.get_tenant_job(&acme, Some("globex")).await.is_none());
+        assert_eq!(store.latest_completed(17, 8453, Some("acme")).await.unwrap().id, acme);
+        assert_eq!(store.latest_completed(17, 8453, None).await.unwrap().id, default);
+        assert!(store.latest_completed(17, 8453, Some("globex")).await.is_none());
+        let listed = store.agent_jobs(8453, 17, Some("acme")).await;
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, acme);
+    }
+
+    #[tokio::test]
+    async fn test_batch_jobs() {
+        let store = AuditStore::in_memory();
+        let params = AuditParams {
+            timeout_secs: 30,
+            lang: Lang::En,
+            requester: None,
+            batch_id: Some("bat_1".to_string()),
+            metadata_uri: None,
+        };
+        let first = store.create_job(Some(17), 8453, None, None, Some(params.clone())).await;
+        let second = store.create_job(Some(18), 8453, None, None, Some(params.clone())).await;
+        store.create_job(Some(19), 8453, None, None, None).await;
+        store.create_job(Some(20), 8453, Some("acme"), None, Some(params)).await;
+
+        let batch: Vec<String> = store
+            .batch_jobs("bat_1", None)
+            .await
+            .into_iter()
+            .map(|job| job.id)
+            .collect();
+        assert_eq!(batch, [first, second]);
+        assert_eq!(store.batch_jobs("bat_1", Some("acme")).await.len(), 1);
+        assert!(store.batch_jobs("bat_2", None).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_unregistered_audit_is_not_latest() {
+        let store = AuditStore::in_memory();
+        let params = AuditParams {
+            timeout_secs: 30,
+            lang: Lang::En,
+            requester: None,
+            batch_id: None,
+            metadata_uri: Some("ipfs://draft".to_string()),
+        };
+        let id = store.create_job(None, 8453, None, None, Some(params)).await;
+        let report = AuditReport::new(0, 8453, "0x8004", "ipfs://draft".to_string(), None);
+        store.set_result(&id, report, &[]).await;
+
+        let job = store.get_job(&id).await.unwrap();
+        assert_eq!(job.status, AuditStatus::Completed);
+        // Agent 0 is a valid token id; the draft stays out of its history
+        assert!(store.latest_completed(0, 8453, None).await.is_none());
+        assert!(store.agent_jobs(8453, 0, None).await.is_empty());
+    }
+
+    fn artifact() -> EndpointArtifact {
+        EndpointArtifact {
+            service: "MCP".to_string(),
+            endpoint: "https://agent.example/mcp".to_string(),
+            content_hash: "abc".to_string(),
+            size_bytes: 2,
+            body: Some(serde_json::json!({})),
+        }
+    }
+
+    /// In-memory backend whose writes fail while `failing` is set
+    struct FlakyStore {
+        inner: MemoryStore,
+        failing: Arc<AtomicBool>,
+    }
+
+    impl FlakyStore {
+        fn check(&self) -> backend::StoreResult<()> {
+            if self.failing.load(Ordering::SeqCst) {
+                return Err(backend::StoreError::Backend("unavailable".to_string()));
+            }
+            Ok(())
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl JobStore for FlakyStore {
+        fn name(&self) -> &'static str {
+            "flaky"
+        }
+        async fn put_job(&self, job: &AuditJob, ttl_secs: u64) -> backend::StoreResult<()> {
+            self.check()?;
+            self.inner.put_job(job, ttl_secs).await
+        }
+        async fn put_job_with_artifacts(
+            &self,
+            job: &AuditJob,
+            artifacts: &[EndpointArtifact],
+            ttl_secs: u64,
+        ) -> backend::StoreResult<()> {
+            self.check()?;
+            self.inner.put_job_with_artifacts(job, artifacts, ttl_secs).await
+        }
+        async fn get_job(&self, id: &str) -> backend::StoreResult<Option<AuditJob>> {
+            self.inner.get_job(id).await
+        }
+        async fn artifacts(&self, id: &str) -> backend::StoreResult<Vec<EndpointArtifact>> {
+            self.inner.artifacts(id).await
+        }
+        async fn delete_job(&self, id: &str) -> backend::StoreResult<bool> {
+            self.inner.delete_job(id).await
+        }
+        async fn list_jobs(&self) -> backend::StoreResult<Vec<AuditJob>> {
+            self.inner.list_jobs().await
+        }
+        async fn append_event(&self, id: &str, e: &AuditEvent, ttl: u64) -> backend::StoreResult<()> {
+            self.check()?;
+            self.inner.append_event(id, e, ttl).await
+        }
+        async fn events(&self, id: &str) -> backend::StoreResult<Vec<AuditEvent>> {
+            self.inner.events(id).await
+        }
+        async fn set_latest(
+            &self,
+            t: Option<&str>,
+            c: u64,
+            a: u64,
+            id: &str,
+            ttl: u64,
+        ) -> backend::StoreResult<()> {
+            self.check()?;
+            self.inner.set_latest(t, c, a, id, ttl).await
+        }
+        async fn latest(
+            &self,
+            t: Option<&str>,
+            c: u64,
+            a: u64,
+        ) -> backend::StoreResult<Option<String>> {
+            self.inner.latest(t, c, a).await
+        }
+        async fn put_subscription(&self, s: &Subscription) -> backend::StoreResult<()> {
+            self.check()?;
+            self.inner.put_subscription(s).await
+        }
+        async fn subscriptions(&self, c: u64, a: u64) -> backend::StoreResult<Vec<Subscription>> {
+            self.inner.subscriptions(c, a).await
+        }
+        async fn remove_subscription(&self, c: u64, a: u64, id: &str) -> backend::StoreResult<bool> {
+            self.inner.remove_subscription(c, a, id).await
+        }
+        async fn put_indexed_agent(&self, e: &IndexedAgent) -> backend::StoreResult<()> {
+            self.check()?;
+            self.inner.put_indexed_agent(e).await
+        }
+        async fn indexed_agents(&self) -> backend::StoreResult<Vec<IndexedAgent>> {
+            self.inner.indexed_agents().await
+        }
+        async fn put_registered_agent(&self, a: &RegisteredAgent) -> backend::StoreResult<()> {
+            self.check()?;
+            self.inner.put_registered_agent(a).await
+        }
+        async fn registered_agent(
+            &self,
+            c: u64,
+            a: u64,
+        ) -> backend::StoreResult<Option<RegisteredAgent>> {
+            self.inner.registered_agent(c, a).await
+        }
+        async fn registered_agents(&self, c: u64) -> backend::StoreResult<Vec<RegisteredAgent>> {
+            self.inner.registered_agents(c).await
+        }
+        async fn indexer_cursor(&self, c: u64) -> backend::StoreResult<Option<u64>> {
+            self.inner.indexer_cursor(c).await
+        }
+        async fn set_indexer_cursor(&self, c: u64, block: u64) -> backend::StoreResult<()> {
+            self.check()?;
+            self.inner.set_indexer_cursor(c, block).await
+        }
+        async fn add_usage(&self, a: &str, m: &str, delta: i64) -> backend::StoreResult<u64> {
+            self.check()?;
+            self.inner.add_usage(a, m, delta).await
+        }
+        async fn usage(&self, a: &str, m: &str) -> backend::StoreResult<u64> {
+            self.inner.usage(a, m).await
+        }
+        async fn retire_signer(&self, signer: &RetiredSigner) -> backend::StoreResult<()> {
+            self.check()?;
+            self.inner.retire_signer(signer).await
+        }
+        async fn retired_signers(&self) -> backend::StoreResult<Vec<RetiredSigner>> {
+            self.inner.retired_signers().await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_backend_composition() {
+        let failing = Arc::new(AtomicBool::new(false));
+        let backend = Arc::new(FlakyStore {
+            inner: MemoryStore::default(),
+            failing: failing.clone(),
+        });
+        let store = AuditStore::with_backend(backend.clone());
+        assert_eq!(store.backend_name(), "flaky");
+
+        let id = store.create_job(Some(17), 8453, None, None, None).await;
+        assert!(backend.inner.get_job(&id).await.unwrap().is_some());
+
+        // Failed writes stay readable and are reconciled once the backend is back
+        failing.store(true, Ordering::SeqCst);
+        store.update_status(&id, AuditStatus::InProgress).await;
+        store.set_indexer_cursor(8453, 120).await;
+        assert_eq!(store.pending_jobs().await, 1);
+        assert_eq!(store.get_job(&id).await.unwrap().status, AuditStatus::InProgress);
+        assert_eq!(store.indexer_cursor(8453).await, Some(120));
+
+        failing.store(false, Ordering::SeqCst);
+        assert_eq!(store.reconcile_jobs().await, 1);
+        assert_eq!(store.pending_jobs().await, 0);
+        let stored = backend.inner.get_job(&id).await.unwrap().unwrap();
+        assert_eq!(stored.status, AuditStatus::InProgress);
+        // Both status events, the one appended during the outage included
+        assert_eq!(backend.inner.events(&id).await.unwrap().len(), 2);
+
+        // Artifacts go with the job write that missed the backend
+        failing.store(true, Ordering::SeqCst);
+        let report = AuditReport::new(17, 8453, "0x8004", "ipfs://meta".to_string(), None);
+        store.set_result(&id, report, &[artifact()]).await;
+        assert_eq!(store.artifacts(&id).await.len(), 1);
+        assert!(backend.inner.artifacts(&id).await.unwrap().is_empty());
+        failing.store(false, Ordering::SeqCst);
+        assert_eq!(store.reconcile_jobs().await, 1);
+        assert_eq!(backend.inner.artifacts(&id).await.unwrap().len(), 1);
+        assert_eq!(store.artifacts(&id).await.len(), 1);
+
+        // Concurrent appends are all kept
+        let store = Arc::new(store);
+        let appends: Vec<_> = (0..20)
+            .map(|i| {
+                let (store, id) = (store.clone(), id.clone());
+                tokio::spawn(async move {
+                    let event = AuditEvent::new(EventKind::PhaseStarted, format!("phase {}", i));
+                    store.append_event(&id, &event).await;
+                })
+            })
+            .collect();
+        for append in appends {
+            append.await.unwrap();
+        }
+        assert_eq!(store.get_events(&id).await.len(), 23);
+    }
+}
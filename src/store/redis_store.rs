@@ -0,0 +1,440 @@
+//! Redis backend
+
+use async_trait::async_trait;
+use redis::AsyncCommands;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::error;
+
+use super::backend::{JobStore, StoreError, StoreResult};
+use super::{AuditJob, RetiredSigner};
+use crate::events::AuditEvent;
+use crate::indexer::RegisteredAgent;
+use crate::redis_conn::{RedisConnection, RedisHealth};
+use crate::search::IndexedAgent;
+use crate::subscriptions::Subscription;
+use crate::types::EndpointArtifact;
+
+/// Redis key prefix for audit jobs
+const AUDIT_KEY_PREFIX: &str = "watchy:audit:";
+/// Redis key prefix for each job's event log (list of JSON events)
+const EVENTS_KEY_PREFIX: &str = "watchy:events:";
+/// Redis key prefix for each job's endpoint artifacts (JSON array)
+const ARTIFACTS_KEY_PREFIX: &str = "watchy:artifacts:";
+/// Redis key prefix for each agent's latest completed audit ID
+const LATEST_KEY_PREFIX: &str = "watchy:latest:";
+/// Redis key prefix for tenants' records, followed by the tenant ID
+const TENANT_KEY_PREFIX: &str = "watchy:tenant:";
+/// Redis key prefix for each agent's notification subscriptions (hash, no TTL)
+const SUBSCRIPTIONS_KEY_PREFIX: &str = "watchy:subscriptions:";
+/// Redis hash of audited agents' skill index entries, by "{chain_id}:{agent_id}"
+const AGENT_INDEX_KEY: &str = "watchy:agents:index";
+/// Redis key prefix for each chain's indexed registry agents (hash by agent ID)
+const REGISTRY_KEY_PREFIX: &str = "watchy:registry:";
+/// Redis key prefix for each chain's agents by owner (set of agent IDs), by
+/// "{chain_id}:{lowercase owner}"
+const OWNER_KEY_PREFIX: &str = "watchy:owners:";
+/// Redis key prefix for each batch's job IDs (set, expiring with its longest-lived job)
+const BATCH_KEY_PREFIX: &str = "watchy:batch:";
+/// Redis key prefix for each chain's last indexed block
+const INDEXER_CURSOR_KEY_PREFIX: &str = "watchy:indexer:cursor:";
+/// Redis hash of signer address -> retirement time (unix seconds)
+const RETIRED_SIGNERS_KEY: &str = "watchy:signers:retirements";
+/// Redis key prefix for monthly audit counts, by "{account}:{month}"
+const USAGE_KEY_PREFIX: &str = "watchy:usage:";
+/// How long monthly counts are kept (a bit over a year)
+const USAGE_TTL_SECONDS: i64 = 400 * 24 * 60 * 60;
+
+/// Longest wait for a health check PING
+const PING_TIMEOUT: Duration = Duration::from_secs(2);
+
+pub struct RedisStore {
+    conn: RedisConnection,
+    health: Arc<RedisHealth>,
+}
+
+impl RedisStore {
+    pub fn new(conn: RedisConnection) -> Self {
+        Self {
+            health: conn.health(),
+            conn,
+        }
+    }
+
+    /// A handle for one operation; clones share the underlying connections
+    fn conn(&self) -> RedisConnection {
+        self.conn.clone()
+    }
+
+    fn job_key(id: &str) -> String {
+        format!("{}{}", AUDIT_KEY_PREFIX, id)
+    }
+
+    fn events_key(id: &str) -> String {
+        format!("{}{}", EVENTS_KEY_PREFIX, id)
+    }
+
+    fn artifacts_key(id: &str) -> String {
+        format!("{}{}", ARTIFACTS_KEY_PREFIX, id)
+    }
+
+    fn latest_key(tenant: Option<&str>, chain_id: u64, agent_id: u64) -> String {
+        match tenant {
+            Some(tenant) => format!("{}{}:latest:{}:{}", TENANT_KEY_PREFIX, tenant, chain_id, agent_id),
+            None => format!("{}{}:{}", LATEST_KEY_PREFIX, chain_id, agent_id),
+        }
+    }
+
+    fn subscriptions_key(chain_id: u64, agent_id: u64) -> String {
+        format!("{}{}:{}", SUBSCRIPTIONS_KEY_PREFIX, chain_id, agent_id)
+    }
+
+    fn registry_key(chain_id: u64) -> String {
+        format!("{}{}", REGISTRY_KEY_PREFIX, chain_id)
+    }
+
+    fn owner_key(chain_id: u64, owner: &str) -> String {
+        format!("{}{}:{}", OWNER_KEY_PREFIX, chain_id, owner.to_lowercase())
+    }
+
+    fn batch_key(batch_id: &str) -> String {
+        format!("{}{}", BATCH_KEY_PREFIX, batch_id)
+    }
+
+    /// Deserialize hash values, skipping (and logging) malformed entries
+    fn parse_entries<T: serde::de::DeserializeOwned>(entries: HashMap<String, String>) -> Vec<T> {
+        entries
+            .into_iter()
+            .filter_map(|(field, json)| match serde_json::from_str(&json) {
+                Ok(value) => Some(value),
+                Err(e) => {
+                    error!("Failed to deserialize entry {}: {}", field, e);
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl JobStore for RedisStore {
+    fn name(&self) -> &'static str {
+        "redis"
+    }
+
+    async fn put_job(&self, job: &AuditJob, ttl_secs: u64) -> StoreResult<()> {
+        let json = serde_json::to_string(job)?;
+        let mut conn = self.conn();
+        let _: () = conn.set_ex(Self::job_key(&job.id), json, ttl_secs.max(1)).await?;
+        if let Some(batch_id) = job.batch_id() {
+            let key = Self::batch_key(batch_id);
+            let _: u64 = conn.sadd(&key, &job.id).await?;
+            // Only ever extended, so the set outlives each of its jobs
+            let remaining: i64 = conn.ttl(&key).await?;
+            if remaining < ttl_secs.max(1) as i64 {
+                let _: bool = conn.expire(&key, ttl_secs.max(1) as i64).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn put_job_with_artifacts(
+        &self,
+        job: &AuditJob,
+        artifacts: &[EndpointArtifact],
+        ttl_secs: u64,
+    ) -> StoreResult<()> {
+        // Artifacts first: a job is never stored pointing at stale ones
+        let key = Self::artifacts_key(&job.id);
+        let mut conn = self.conn();
+        if artifacts.is_empty() {
+            let _: u64 = conn.del(&key).await?;
+        } else {
+            let json = serde_json::to_string(artifacts)?;
+            let _: () = conn.set_ex(&key, json, ttl_secs.max(1)).await?;
+        }
+        self.put_job(job, ttl_secs).await
+    }
+
+    async fn get_job(&self, id: &str) -> StoreResult<Option<AuditJob>> {
+        let result: Option<String> = self.conn().get(Self::job_key(id)).await?;
+        Ok(result.map(|json| serde_json::from_str(&json)).transpose()?)
+    }
+
+    async fn artifacts(&self, id: &str) -> StoreResult<Vec<EndpointArtifact>> {
+        let result: Option<String> = self.conn().get(Self::artifacts_key(id)).await?;
+        Ok(match result {
+            Some(json) => serde_json::from_str(&json)?,
+            None => Vec::new(),
+        })
+    }
+
+    async fn delete_job(&self, id: &str) -> StoreResult<bool> {
+        let mut conn = self.conn();
+        let _: u64 = conn.del(Self::events_key(id)).await?;
+        let _: u64 = conn.del(Self::artifacts_key(id)).await?;
+        let count: u64 = conn.del(Self::job_key(id)).await?;
+        Ok(count > 0)
+    }
+
+    async fn append_event(&self, id: &str, event: &AuditEvent, ttl_secs: u64) -> StoreResult<()> {
+        let key = Self::events_key(id);
+        let json = serde_json::to_string(event)?;
+        let mut conn = self.conn();
+        let _: u64 = conn.rpush(&key, json).await?;
+        let _: bool = conn.expire(&key, ttl_secs.max(1) as i64).await?;
+        Ok(())
+    }
+
+    async fn events(&self, id: &str) -> StoreResult<Vec<AuditEvent>> {
+        let entries: Vec<String> = self.conn().lrange(Self::events_key(id), 0, -1).await?;
+        Ok(entries
+            .iter()
+            .filter_map(|json| match serde_json::from_str(json) {
+                Ok(event) => Some(event),
+                Err(e) => {
+                    error!("Failed to deserialize event of job {}: {}", id, e);
+                    None
+                }
+            })
+            .collect())
+    }
+
+    async fn list_jobs(&self) -> StoreResult<Vec<AuditJob>> {
+        let mut conn = self.conn();
+        let keys = conn.scan_match(&format!("{}*", AUDIT_KEY_PREFIX)).await?;
+
+        let mut jobs = Vec::new();
+        for key in keys {
+            let result: Result<Option<String>, redis::RedisError> = conn.get(&key).await;
+            match result {
+                Ok(Some(json)) => match serde_json::from_str::<AuditJob>(&json) {
+                    Ok(job) => jobs.push(job),
+                    Err(e) => error!("Failed to deserialize job at {}: {}", key, e),
+                },
+                // Expired between SCAN and GET
+                Ok(None) => {}
+                Err(e) => error!("Redis GET failed for {}: {}", key, e),
+            }
+        }
+        Ok(jobs)
+    }
+
+    async fn set_latest(
+        &self,
+        tenant: Option<&str>,
+        chain_id: u64,
+        agent_id: u64,
+        id: &str,
+        ttl_secs: u64,
+    ) -> StoreResult<()> {
+        let result: Result<(), redis::RedisError> = self
+            .conn()
+            .set_ex(Self::latest_key(tenant, chain_id, agent_id), id, ttl_secs.max(1))
+            .await;
+        Ok(result?)
+    }
+
+    async fn latest(
+        &self,
+        tenant: Option<&str>,
+        chain_id: u64,
+        agent_id: u64,
+    ) -> StoreResult<Option<String>> {
+        Ok(self
+            .conn()
+            .get(Self::latest_key(tenant, chain_id, agent_id))
+            .await?)
+    }
+
+    async fn put_subscription(&self, subscription: &Subscription) -> StoreResult<()> {
+        let key =
+            Self::subscriptions_key(subscription.request.chain_id, subscription.request.agent_id);
+        let json = serde_json::to_string(subscription)?;
+        let result: Result<(), redis::RedisError> = self
+            .conn()
+            .hset(key, &subscription.id, json)
+            .await;
+        Ok(result?)
+    }
+
+    async fn subscriptions(&self, chain_id: u64, agent_id: u64) -> StoreResult<Vec<Subscription>> {
+        let entries: HashMap<String, String> = self
+            .conn()
+            .hgetall(Self::subscriptions_key(chain_id, agent_id))
+            .await?;
+        Ok(Self::parse_entries(entries))
+    }
+
+    async fn remove_subscription(
+        &self,
+        chain_id: u64,
+        agent_id: u64,
+        id: &str,
+    ) -> StoreResult<bool> {
+        let count: u64 = self
+            .conn()
+            .hdel(Self::subscriptions_key(chain_id, agent_id), id)
+            .await?;
+        Ok(count > 0)
+    }
+
+    async fn put_indexed_agent(&self, entry: &IndexedAgent) -> StoreResult<()> {
+        let field = format!("{}:{}", entry.chain_id, entry.agent_id);
+        let json = serde_json::to_string(entry)?;
+        let result: Result<(), redis::RedisError> = self
+            .conn()
+            .hset(AGENT_INDEX_KEY, field, json)
+            .await;
+        Ok(result?)
+    }
+
+    async fn batch_jobs(&self, batch_id: &str) -> StoreResult<Vec<AuditJob>> {
+        let mut conn = self.conn();
+        let ids: Vec<String> = conn.smembers(Self::batch_key(batch_id)).await?;
+        let mut jobs = Vec::new();
+        // Members outlive deleted and expired jobs; those are skipped
+        for id in ids {
+            if let Some(job) = self.get_job(&id).await? {
+                jobs.push(job);
+            }
+        }
+        Ok(jobs)
+    }
+
+    async fn indexed_agents(&self) -> StoreResult<Vec<IndexedAgent>> {
+        let entries: HashMap<String, String> =
+            self.conn().hgetall(AGENT_INDEX_KEY).await?;
+        Ok(Self::parse_entries(entries))
+    }
+
+    async fn put_registered_agent(&self, agent: &RegisteredAgent) -> StoreResult<()> {
+        let json = serde_json::to_string(agent)?;
+        let key = Self::registry_key(agent.chain_id);
+        let mut conn = self.conn();
+        let previous: Option<String> = conn.hget(&key, agent.agent_id).await?;
+        let previous_owner = previous
+            .and_then(|json| serde_json::from_str::<RegisteredAgent>(&json).ok())
+            .map(|previous| previous.owner)
+            .filter(|owner| !owner.eq_ignore_ascii_case(&agent.owner));
+        if let Some(owner) = previous_owner {
+            let _: u64 = conn
+                .srem(Self::owner_key(agent.chain_id, &owner), agent.agent_id)
+                .await?;
+        }
+        let _: u64 = conn
+            .sadd(Self::owner_key(agent.chain_id, &agent.owner), agent.agent_id)
+            .await?;
+        let _: () = conn.hset(&key, agent.agent_id, json).await?;
+        Ok(())
+    }
+
+    async fn registered_agent(
+        &self,
+        chain_id: u64,
+        agent_id: u64,
+    ) -> StoreResult<Option<RegisteredAgent>> {
+        let result: Option<String> = self
+            .conn()
+            .hget(Self::registry_key(chain_id), agent_id)
+            .await?;
+        Ok(result.map(|json| serde_json::from_str(&json)).transpose()?)
+    }
+
+    async fn registered_agents(&self, chain_id: u64) -> StoreResult<Vec<RegisteredAgent>> {
+        let entries: HashMap<String, String> = self
+            .conn()
+            .hgetall(Self::registry_key(chain_id))
+            .await?;
+        let agents: BTreeMap<u64, RegisteredAgent> =
+            Self::parse_entries::<RegisteredAgent>(entries)
+                .into_iter()
+                .map(|agent| (agent.agent_id, agent))
+                .collect();
+        Ok(agents.into_values().collect())
+    }
+
+    async fn owned_agents(&self, chain_id: u64, owner: &str) -> StoreResult<Vec<RegisteredAgent>> {
+        let mut conn = self.conn();
+        let mut ids: Vec<u64> = conn.smembers(Self::owner_key(chain_id, owner)).await?;
+        ids.sort_unstable();
+        let mut agents = Vec::new();
+        for agent_id in ids {
+            // The set is written before the table: check the entry agrees
+            if let Some(agent) = self.registered_agent(chain_id, agent_id).await? {
+                if agent.owner.eq_ignore_ascii_case(owner) {
+                    agents.push(agent);
+                }
+            }
+        }
+        Ok(agents)
+    }
+
+    async fn indexer_cursor(&self, chain_id: u64) -> StoreResult<Option<u64>> {
+        let key = format!("{}{}", INDEXER_CURSOR_KEY_PREFIX, chain_id);
+        Ok(self.conn().get(key).await?)
+    }
+
+    async fn set_indexer_cursor(&self, chain_id: u64, block: u64) -> StoreResult<()> {
+        let key = format!("{}{}", INDEXER_CURSOR_KEY_PREFIX, chain_id);
+        let result: Result<(), redis::RedisError> = self.conn().set(key, block).await;
+        Ok(result?)
+    }
+
+    async fn add_usage(&self, account: &str, month: &str, delta: i64) -> StoreResult<u64> {
+        let key = format!("{}{}:{}", USAGE_KEY_PREFIX, account, month);
+        let mut conn = self.conn();
+        let count: i64 = conn.incr(&key, delta).await?;
+        let _: bool = conn.expire(&key, USAGE_TTL_SECONDS).await?;
+        Ok(count.max(0) as u64)
+    }
+
+    async fn usage(&self, account: &str, month: &str) -> StoreResult<u64> {
+        let key = format!("{}{}:{}", USAGE_KEY_PREFIX, account, month);
+        let count: Option<i64> = self.conn().get(key).await?;
+        Ok(count.unwrap_or_default().max(0) as u64)
+    }
+
+    async fn retire_signer(&self, signer: &RetiredSigner) -> StoreResult<()> {
+        let _: bool = self
+            .conn()
+            .hset_nx(RETIRED_SIGNERS_KEY, &signer.address, signer.retired_at)
+            .await?;
+        Ok(())
+    }
+
+    async fn retired_signers(&self) -> StoreResult<Vec<RetiredSigner>> {
+        let entries: HashMap<String, u64> = self.conn().hgetall(RETIRED_SIGNERS_KEY).await?;
+        Ok(entries
+            .into_iter()
+            .map(|(address, retired_at)| RetiredSigner { address, retired_at })
+            .collect())
+    }
+
+    async fn ping(&self) -> StoreResult<()> {
+        let mut conn = self.conn();
+        let ping = async {
+            let result: Result<String, redis::RedisError> =
+                redis::cmd("PING").query_async(&mut conn).await;
+            result
+        };
+        match tokio::time::timeout(PING_TIMEOUT, ping).await {
+            Ok(result) => {
+                result?;
+                Ok(())
+            }
+            // A hung server never fails the command itself
+            Err(_) => {
+                let timeout =
+                    redis::RedisError::from(std::io::Error::from(std::io::ErrorKind::TimedOut));
+                self.health.record::<()>(&Err(timeout));
+                Err(StoreError::Backend("PING timed out".to_string()))
+            }
+        }
+    }
+
+    fn degraded_since(&self) -> Option<u64> {
+        self.health.degraded_since()
+    }
+}
@@ -0,0 +1,824 @@
+//! SQLite backend
+//!
+//! Durable single-node storage in one file, without running a server. Each
+//! record is kept as JSON next to the columns it's looked up by; expired rows
+//! are skipped on read and swept on job writes.
+//!
+//! `PRAGMA user_version` records the schema version; databases written by
+//! earlier versions are migrated on open.
+
+use async_trait::async_trait;
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use super::backend::{JobStore, StoreError, StoreResult};
+use super::{AuditJob, RetiredSigner};
+use crate::events::AuditEvent;
+use crate::indexer::RegisteredAgent;
+use crate::search::IndexedAgent;
+use crate::subscriptions::Subscription;
+use crate::types::EndpointArtifact;
+
+const SCHEMA: &str = "
+PRAGMA journal_mode = WAL;
+PRAGMA busy_timeout = 5000;
+
+CREATE TABLE IF NOT EXISTS jobs (
+    id TEXT PRIMARY KEY,
+    chain_id INTEGER NOT NULL,
+    agent_id INTEGER,
+    created_at INTEGER NOT NULL,
+    expires_at INTEGER NOT NULL,
+    job TEXT NOT NULL,
+    batch_id TEXT
+);
+-- Agent history, newest first
+CREATE INDEX IF NOT EXISTS jobs_by_agent ON jobs (chain_id, agent_id, created_at);
+CREATE INDEX IF NOT EXISTS jobs_by_expiry ON jobs (expires_at);
+
+-- Event logs, one row per event so appends don't rewrite the job
+CREATE TABLE IF NOT EXISTS job_events (
+    seq INTEGER PRIMARY KEY AUTOINCREMENT,
+    job_id TEXT NOT NULL,
+    expires_at INTEGER NOT NULL,
+    event TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS job_events_by_job ON job_events (job_id, seq);
+
+-- Endpoint artifacts, apart from the job so job reads don't load them
+CREATE TABLE IF NOT EXISTS job_artifacts (
+    job_id TEXT PRIMARY KEY,
+    expires_at INTEGER NOT NULL,
+    artifacts TEXT NOT NULL
+);
+
+-- Per tenant; the default tenant is ''
+CREATE TABLE IF NOT EXISTS latest_audits (
+    tenant TEXT NOT NULL,
+    chain_id INTEGER NOT NULL,
+    agent_id INTEGER NOT NULL,
+    job_id TEXT NOT NULL,
+    expires_at INTEGER NOT NULL,
+    PRIMARY KEY (tenant, chain_id, agent_id)
+);
+
+CREATE TABLE IF NOT EXISTS subscriptions (
+    chain_id INTEGER NOT NULL,
+    agent_id INTEGER NOT NULL,
+    id TEXT NOT NULL,
+    subscription TEXT NOT NULL,
+    PRIMARY KEY (chain_id, agent_id, id)
+);
+
+CREATE TABLE IF NOT EXISTS agent_index (
+    chain_id INTEGER NOT NULL,
+    agent_id INTEGER NOT NULL,
+    entry TEXT NOT NULL,
+    PRIMARY KEY (chain_id, agent_id)
+);
+
+-- `owner` is lowercase
+CREATE TABLE IF NOT EXISTS registry (
+    chain_id INTEGER NOT NULL,
+    agent_id INTEGER NOT NULL,
+    agent TEXT NOT NULL,
+    owner TEXT NOT NULL DEFAULT '',
+    PRIMARY KEY (chain_id, agent_id)
+);
+
+CREATE TABLE IF NOT EXISTS indexer_cursors (
+    chain_id INTEGER PRIMARY KEY,
+    block INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS usage (
+    account TEXT NOT NULL,
+    month TEXT NOT NULL,
+    audits INTEGER NOT NULL,
+    PRIMARY KEY (account, month)
+);
+
+CREATE TABLE IF NOT EXISTS retired_signers (
+    address TEXT PRIMARY KEY,
+    retired_at INTEGER NOT NULL
+);
+";
+
+/// Schema version this build writes (`PRAGMA user_version`)
+const SCHEMA_VERSION: i64 = 2;
+
+/// Version 1: latest audits are tracked per tenant
+const MIGRATION_TENANT_LATEST: &str = "
+BEGIN;
+ALTER TABLE latest_audits RENAME TO latest_audits_v0;
+CREATE TABLE latest_audits (
+    tenant TEXT NOT NULL,
+    chain_id INTEGER NOT NULL,
+    agent_id INTEGER NOT NULL,
+    job_id TEXT NOT NULL,
+    expires_at INTEGER NOT NULL,
+    PRIMARY KEY (tenant, chain_id, agent_id)
+);
+INSERT INTO latest_audits (tenant, chain_id, agent_id, job_id, expires_at)
+    SELECT '', chain_id, agent_id, job_id, expires_at FROM latest_audits_v0;
+DROP TABLE latest_audits_v0;
+COMMIT;
+";
+
+/// Version 2: index jobs by batch
+const MIGRATION_JOB_BATCH: &str = "
+BEGIN;
+ALTER TABLE jobs ADD COLUMN batch_id TEXT;
+UPDATE jobs SET batch_id = json_extract(job, '$.params.batch_id');
+COMMIT;
+";
+
+/// Version 2: index registered agents by owner
+const MIGRATION_REGISTRY_OWNER: &str = "
+BEGIN;
+ALTER TABLE registry ADD COLUMN owner TEXT NOT NULL DEFAULT '';
+UPDATE registry SET owner = lower(json_extract(agent, '$.owner'));
+COMMIT;
+";
+
+/// Indexes on columns added by migrations, created once they exist
+const LOOKUP_INDEXES: &str = "
+CREATE INDEX IF NOT EXISTS jobs_by_batch ON jobs (batch_id) WHERE batch_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS registry_by_owner ON registry (chain_id, owner);
+";
+
+/// Bring a database up to `SCHEMA_VERSION`
+///
+/// Runs after `SCHEMA`, which creates missing tables but never alters them.
+fn migrate(conn: &Connection) -> StoreResult<()> {
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version > SCHEMA_VERSION {
+        return Err(StoreError::Backend(format!(
+            "Database schema version {} is newer than this build's ({})",
+            version, SCHEMA_VERSION
+        )));
+    }
+    if version < 1 && !has_column(conn, "latest_audits", "tenant")? {
+        conn.execute_batch(MIGRATION_TENANT_LATEST)?;
+    }
+    if version < 2 {
+        if !has_column(conn, "jobs", "batch_id")? {
+            conn.execute_batch(MIGRATION_JOB_BATCH)?;
+        }
+        if !has_column(conn, "registry", "owner")? {
+            conn.execute_batch(MIGRATION_REGISTRY_OWNER)?;
+        }
+    }
+    conn.execute_batch(LOOKUP_INDEXES)?;
+    conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+    Ok(())
+}
+
+fn has_column(conn: &Connection, table: &str, column: &str) -> StoreResult<bool> {
+    Ok(conn.query_row(
+        "SELECT COUNT(*) > 0 FROM pragma_table_info(?1) WHERE name = ?2",
+        params![table, column],
+        |row| row.get(0),
+    )?)
+}
+
+/// A job serialized for the `jobs` table
+struct JobRow {
+    id: String,
+    chain_id: u64,
+    agent_id: Option<u64>,
+    created_at: u64,
+    batch_id: Option<String>,
+    json: String,
+}
+
+impl JobRow {
+    fn new(job: &AuditJob) -> StoreResult<Self> {
+        Ok(Self {
+            id: job.id.clone(),
+            chain_id: job.chain_id,
+            agent_id: job.agent_id,
+            created_at: job.created_at,
+            batch_id: job.batch_id().map(str::to_string),
+            json: serde_json::to_string(job)?,
+        })
+    }
+
+    /// Insert or replace the row, sweeping expired jobs, events and artifacts
+    fn insert(&self, conn: &Connection, ttl_secs: u64) -> StoreResult<()> {
+        let now = SqliteStore::now();
+        conn.execute("DELETE FROM jobs WHERE expires_at <= ?1", params![now])?;
+        conn.execute("DELETE FROM job_events WHERE expires_at <= ?1", params![now])?;
+        conn.execute("DELETE FROM job_artifacts WHERE expires_at <= ?1", params![now])?;
+        conn.execute(
+            "INSERT OR REPLACE INTO jobs
+             (id, chain_id, agent_id, created_at, expires_at, job, batch_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                self.id,
+                self.chain_id,
+                self.agent_id,
+                self.created_at,
+                now + ttl_secs.max(1),
+                self.json,
+                self.batch_id
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+pub struct SqliteStore {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl SqliteStore {
+    /// Open (creating if needed) the database at `path`
+    pub fn open(path: &Path) -> StoreResult<Self> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(SCHEMA)?;
+        migrate(&conn)?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    /// Check an existing database without changing it: opened read-only, it
+    /// must pass `PRAGMA quick_check`
+    pub fn check(path: &Path) -> StoreResult<()> {
+        let conn = Connection::open_with_flags(
+            path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        let result: String = conn.query_row("PRAGMA quick_check", [], |row| row.get(0))?;
+        if result != "ok" {
+            return Err(StoreError::Backend(format!("integrity check: {}", result)));
+        }
+        Ok(())
+    }
+
+    /// Run a query on the blocking pool
+    async fn call<T, F>(&self, f: F) -> StoreResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Connection) -> StoreResult<T> + Send + 'static,
+    {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|e| e.into_inner());
+            f(&conn)
+        })
+        .await
+        .map_err(|e| StoreError::Backend(e.to_string()))?
+    }
+
+    fn now() -> u64 {
+        chrono::Utc::now().timestamp() as u64
+    }
+
+    /// Deserialize the JSON column of every row a query returns
+    fn query_json<T: serde::de::DeserializeOwned>(
+        conn: &Connection,
+        sql: &str,
+        params: impl rusqlite::Params,
+    ) -> StoreResult<Vec<T>> {
+        let mut stmt = conn.prepare_cached(sql)?;
+        let rows = stmt.query_map(params, |row| row.get::<_, String>(0))?;
+        let mut values = Vec::new();
+        for json in rows {
+            values.push(serde_json::from_str(&json?)?);
+        }
+        Ok(values)
+    }
+}
+
+#[async_trait]
+impl JobStore for SqliteStore {
+    fn name(&self) -> &'static str {
+        "sqlite"
+    }
+
+    async fn put_job(&self, job: &AuditJob, ttl_secs: u64) -> StoreResult<()> {
+        let job = JobRow::new(job)?;
+        self.call(move |conn| job.insert(conn, ttl_secs)).await
+    }
+
+    async fn put_job_with_artifacts(
+        &self,
+        job: &AuditJob,
+        artifacts: &[EndpointArtifact],
+        ttl_secs: u64,
+    ) -> StoreResult<()> {
+        let job = JobRow::new(job)?;
+        let artifacts = (!artifacts.is_empty())
+            .then(|| serde_json::to_string(artifacts))
+            .transpose()?;
+        self.call(move |conn| {
+            let tx = conn.unchecked_transaction()?;
+            job.insert(&tx, ttl_secs)?;
+            match artifacts {
+                Some(json) => tx.execute(
+                    "INSERT OR REPLACE INTO job_artifacts (job_id, expires_at, artifacts)
+                     VALUES (?1, ?2, ?3)",
+                    params![job.id, Self::now() + ttl_secs.max(1), json],
+                )?,
+                None => tx.execute("DELETE FROM job_artifacts WHERE job_id = ?1", params![job.id])?,
+            };
+            tx.commit()?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn get_job(&self, id: &str) -> StoreResult<Option<AuditJob>> {
+        let id = id.to_string();
+        self.call(move |conn| {
+            let json: Option<String> = conn
+                .query_row(
+                    "SELECT job FROM jobs WHERE id = ?1 AND expires_at > ?2",
+                    params![id, Self::now()],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            Ok(json.map(|json| serde_json::from_str(&json)).transpose()?)
+        })
+        .await
+    }
+
+    async fn artifacts(&self, id: &str) -> StoreResult<Vec<EndpointArtifact>> {
+        let id = id.to_string();
+        self.call(move |conn| {
+            let json: Option<String> = conn
+                .query_row(
+                    "SELECT artifacts FROM job_artifacts WHERE job_id = ?1 AND expires_at > ?2",
+                    params![id, Self::now()],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            Ok(json.map(|json| serde_json::from_str(&json)).transpose()?.unwrap_or_default())
+        })
+        .await
+    }
+
+    async fn delete_job(&self, id: &str) -> StoreResult<bool> {
+        let id = id.to_string();
+        self.call(move |conn| {
+            conn.execute("DELETE FROM job_events WHERE job_id = ?1", params![id])?;
+            conn.execute("DELETE FROM job_artifacts WHERE job_id = ?1", params![id])?;
+            Ok(conn.execute("DELETE FROM jobs WHERE id = ?1", params![id])? > 0)
+        })
+        .await
+    }
+
+    async fn append_event(&self, id: &str, event: &AuditEvent, ttl_secs: u64) -> StoreResult<()> {
+        let (id, json) = (id.to_string(), serde_json::to_string(event)?);
+        self.call(move |conn| {
+            conn.execute(
+                "INSERT INTO job_events (job_id, expires_at, event) VALUES (?1, ?2, ?3)",
+                params![id, Self::now() + ttl_secs.max(1), json],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn events(&self, id: &str) -> StoreResult<Vec<AuditEvent>> {
+        let id = id.to_string();
+        self.call(move |conn| {
+            Self::query_json(
+                conn,
+                "SELECT event FROM job_events WHERE job_id = ?1 AND expires_at > ?2 ORDER BY seq",
+                params![id, Self::now()],
+            )
+        })
+        .await
+    }
+
+    async fn list_jobs(&self) -> StoreResult<Vec<AuditJob>> {
+        self.call(|conn| {
+            Self::query_json(
+                conn,
+                "SELECT job FROM jobs WHERE expires_at > ?1",
+                params![Self::now()],
+            )
+        })
+        .await
+    }
+
+    async fn batch_jobs(&self, batch_id: &str) -> StoreResult<Vec<AuditJob>> {
+        let batch_id = batch_id.to_string();
+        self.call(move |conn| {
+            Self::query_json(
+                conn,
+                "SELECT job FROM jobs WHERE batch_id = ?1 AND expires_at > ?2",
+                params![batch_id, Self::now()],
+            )
+        })
+        .await
+    }
+
+    async fn agent_jobs(&self, chain_id: u64, agent_id: u64) -> StoreResult<Vec<AuditJob>> {
+        self.call(move |conn| {
+            Self::query_json(
+                conn,
+                "SELECT job FROM jobs
+                 WHERE chain_id = ?1 AND agent_id = ?2 AND expires_at > ?3
+                 ORDER BY created_at DESC",
+                params![chain_id, agent_id, Self::now()],
+            )
+        })
+        .await
+    }
+
+    async fn set_latest(
+        &self,
+        tenant: Option<&str>,
+        chain_id: u64,
+        agent_id: u64,
+        id: &str,
+        ttl_secs: u64,
+    ) -> StoreResult<()> {
+        let (tenant, id) = (tenant.unwrap_or_default().to_string(), id.to_string());
+        self.call(move |conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO latest_audits (tenant, chain_id, agent_id, job_id, expires_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![tenant, chain_id, agent_id, id, Self::now() + ttl_secs.max(1)],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn latest(
+        &self,
+        tenant: Option<&str>,
+        chain_id: u64,
+        agent_id: u64,
+    ) -> StoreResult<Option<String>> {
+        let tenant = tenant.unwrap_or_default().to_string();
+        self.call(move |conn| {
+            Ok(conn
+                .query_row(
+                    "SELECT job_id FROM latest_audits
+                     WHERE tenant = ?1 AND chain_id = ?2 AND agent_id = ?3 AND expires_at > ?4",
+                    params![tenant, chain_id, agent_id, Self::now()],
+                    |row| row.get(0),
+                )
+                .optional()?)
+        })
+        .await
+    }
+
+    async fn put_subscription(&self, subscription: &Subscription) -> StoreResult<()> {
+        let json = serde_json::to_string(subscription)?;
+        let (chain_id, agent_id, id) = (
+            subscription.request.chain_id,
+            subscription.request.agent_id,
+            subscription.id.clone(),
+        );
+        self.call(move |conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO subscriptions (chain_id, agent_id, id, subscription)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![chain_id, agent_id, id, json],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn subscriptions(&self, chain_id: u64, agent_id: u64) -> StoreResult<Vec<Subscription>> {
+        self.call(move |conn| {
+            Self::query_json(
+                conn,
+                "SELECT subscription FROM subscriptions WHERE chain_id = ?1 AND agent_id = ?2",
+                params![chain_id, agent_id],
+            )
+        })
+        .await
+    }
+
+    async fn remove_subscription(
+        &self,
+        chain_id: u64,
+        agent_id: u64,
+        id: &str,
+    ) -> StoreResult<bool> {
+        let id = id.to_string();
+        self.call(move |conn| {
+            Ok(conn.execute(
+                "DELETE FROM subscriptions WHERE chain_id = ?1 AND agent_id = ?2 AND id = ?3",
+                params![chain_id, agent_id, id],
+            )? > 0)
+        })
+        .await
+    }
+
+    async fn put_indexed_agent(&self, entry: &IndexedAgent) -> StoreResult<()> {
+        let json = serde_json::to_string(entry)?;
+        let (chain_id, agent_id) = (entry.chain_id, entry.agent_id);
+        self.call(move |conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO agent_index (chain_id, agent_id, entry) VALUES (?1, ?2, ?3)",
+                params![chain_id, agent_id, json],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn indexed_agents(&self) -> StoreResult<Vec<IndexedAgent>> {
+        self.call(|conn| Self::query_json(conn, "SELECT entry FROM agent_index", []))
+            .await
+    }
+
+    async fn put_registered_agent(&self, agent: &RegisteredAgent) -> StoreResult<()> {
+        let json = serde_json::to_string(agent)?;
+        let (chain_id, agent_id) = (agent.chain_id, agent.agent_id);
+        let owner = agent.owner.to_lowercase();
+        self.call(move |conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO registry (chain_id, agent_id, agent, owner)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![chain_id, agent_id, json, owner],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn registered_agent(
+        &self,
+        chain_id: u64,
+        agent_id: u64,
+    ) -> StoreResult<Option<RegisteredAgent>> {
+        self.call(move |conn| {
+            Ok(Self::query_json(
+                conn,
+                "SELECT agent FROM registry WHERE chain_id = ?1 AND agent_id = ?2",
+                params![chain_id, agent_id],
+            )?
+            .pop())
+        })
+        .await
+    }
+
+    async fn registered_agents(&self, chain_id: u64) -> StoreResult<Vec<RegisteredAgent>> {
+        self.call(move |conn| {
+            Self::query_json(
+                conn,
+                "SELECT agent FROM registry WHERE chain_id = ?1 ORDER BY agent_id",
+                params![chain_id],
+            )
+        })
+        .await
+    }
+
+    async fn owned_agents(&self, chain_id: u64, owner: &str) -> StoreResult<Vec<RegisteredAgent>> {
+        let owner = owner.to_lowercase();
+        self.call(move |conn| {
+            Self::query_json(
+                conn,
+                "SELECT agent FROM registry WHERE chain_id = ?1 AND owner = ?2 ORDER BY agent_id",
+                params![chain_id, owner],
+            )
+        })
+        .await
+    }
+
+    async fn indexer_cursor(&self, chain_id: u64) -> StoreResult<Option<u64>> {
+        self.call(move |conn| {
+            Ok(conn
+                .query_row(
+                    "SELECT block FROM indexer_cursors WHERE chain_id = ?1",
+                    params![chain_id],
+                    |row| row.get(0),
+                )
+                .optional()?)
+        })
+        .await
+    }
+
+    async fn set_indexer_cursor(&self, chain_id: u64, block: u64) -> StoreResult<()> {
+        self.call(move |conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO indexer_cursors (chain_id, block) VALUES (?1, ?2)",
+                params![chain_id, block],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn add_usage(&self, account: &str, month: &str, delta: i64) -> StoreResult<u64> {
+        let (account, month) = (account.to_string(), month.to_string());
+        self.call(move |conn| {
+            let count: i64 = conn.query_row(
+                "INSERT INTO usage (account, month, audits) VALUES (?1, ?2, MAX(?3, 0))
+                 ON CONFLICT (account, month) DO UPDATE SET audits = MAX(audits + ?3, 0)
+                 RETURNING audits",
+                params![account, month, delta],
+                |row| row.get(0),
+            )?;
+            Ok(count as u64)
+        })
+        .await
+    }
+
+    async fn usage(&self, account: &str, month: &str) -> StoreResult<u64> {
+        let (account, month) = (account.to_string(), month.to_string());
+        self.call(move |conn| {
+            let count: Option<i64> = conn
+                .query_row(
+                    "SELECT audits FROM usage WHERE account = ?1 AND month = ?2",
+                    params![account, month],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            Ok(count.unwrap_or_default() as u64)
+        })
+        .await
+    }
+
+    async fn retire_signer(&self, signer: &RetiredSigner) -> StoreResult<()> {
+        let signer = signer.clone();
+        self.call(move |conn| {
+            conn.execute(
+                "INSERT OR IGNORE INTO retired_signers (address, retired_at) VALUES (?1, ?2)",
+                params![signer.address, signer.retired_at],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn retired_signers(&self) -> StoreResult<Vec<RetiredSigner>> {
+        self.call(|conn| {
+            let mut stmt =
+                conn.prepare_cached("SELECT address, retired_at FROM retired_signers")?;
+            let signers = stmt
+                .query_map([], |row| {
+                    Ok(RetiredSigner {
+                        address: row.get(0)?,
+                        retired_at: row.get(1)?,
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(signers)
+        })
+        .await
+    }
+
+    async fn ping(&self) -> StoreResult<()> {
+        self.call(|conn| {
+            conn.query_row("SELECT 1", [], |_| Ok(()))?;
+            Ok(())
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::AuditStore;
+    use crate::types::{AuditReport, AuditStatus};
+
+    #[tokio::test]
+    async fn test_persists_across_reopen() {
+        let path = std::env::temp_dir().join(format!("watchy-{}.db", uuid::Uuid::new_v4()));
+        // The read-only check doesn't create a database
+        assert!(SqliteStore::check(&path).is_err());
+        assert!(!path.exists());
+
+        let store = AuditStore::with_backend(Arc::new(SqliteStore::open(&path).unwrap()));
+        assert!(SqliteStore::check(&path).is_ok());
+        let first = store.create_job(Some(17), 8453, None, None, None).await;
+        let report = AuditReport::new(17, 8453, "0x8004", "ipfs://meta".to_string(), None);
+        store.set_result(&first, report, &[]).await;
+        let second = store.create_job(Some(17), 8453, None, None, None).await;
+        store.create_job(Some(18), 8453, None, None, None).await;
+        store.set_indexer_cursor(8453, 120).await;
+        store.retire_signer("0xAbC").await;
+        drop(store);
+
+        let store = AuditStore::with_backend(Arc::new(SqliteStore::open(&path).unwrap()));
+        assert_eq!(store.backend_name(), "sqlite");
+        let history: Vec<String> = store
+            .agent_jobs(8453, 17, None)
+            .await
+            .into_iter()
+            .map(|job| job.id)
+            .collect();
+        assert_eq!(history.len(), 2);
+        assert!(history.contains(&first) && history.contains(&second));
+        assert_eq!(
+            store.get_job(&first).await.unwrap().status,
+            AuditStatus::Completed
+        );
+        assert_eq!(store.latest_completed(17, 8453, None).await.unwrap().id, first);
+        assert_eq!(store.indexer_cursor(8453).await, Some(120));
+        let retired = store.retired_signers().await;
+        assert_eq!(retired.len(), 1);
+        assert_eq!(retired[0].address, "0xabc");
+        assert!(retired[0].retired_at > 0);
+        assert_eq!(store.list_jobs().await.len(), 3);
+
+        assert!(store.delete_job(&second).await);
+        assert_eq!(store.agent_jobs(8453, 17, None).await.len(), 1);
+
+        let params = crate::store::AuditParams {
+            timeout_secs: 30,
+            lang: Default::default(),
+            requester: None,
+            batch_id: Some("bat_1".to_string()),
+            metadata_uri: None,
+        };
+        let batched = store.create_job(Some(19), 8453, None, None, Some(params)).await;
+        let batch = store.batch_jobs("bat_1", None).await;
+        assert_eq!(batch.iter().map(|job| &job.id).collect::<Vec<_>>(), vec![&batched]);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_artifacts_stored_apart() {
+        let path = std::env::temp_dir().join(format!("watchy-{}.db", uuid::Uuid::new_v4()));
+        let artifact = EndpointArtifact {
+            service: "A2A".to_string(),
+            endpoint: "https://agent.example/.well-known/agent-card.json".to_string(),
+            content_hash: "abc".to_string(),
+            size_bytes: 2,
+            body: Some(serde_json::json!({})),
+        };
+
+        let store = AuditStore::with_backend(Arc::new(SqliteStore::open(&path).unwrap()));
+        let id = store.create_job(Some(17), 8453, None, None, None).await;
+        let report = AuditReport::new(17, 8453, "0x8004", "ipfs://meta".to_string(), None);
+        store.set_result(&id, report, &[artifact]).await;
+        drop(store);
+
+        let backend = SqliteStore::open(&path).unwrap();
+        let job: String = backend
+            .call({
+                let id = id.clone();
+                move |conn| {
+                    Ok(conn.query_row("SELECT job FROM jobs WHERE id = ?1", [id], |row| row.get(0))?)
+                }
+            })
+            .await
+            .unwrap();
+        assert!(!job.contains("agent-card"));
+
+        let store = AuditStore::with_backend(Arc::new(backend));
+        let artifacts = store.artifacts(&id).await;
+        assert_eq!(artifacts.len(), 1);
+        assert_eq!(artifacts[0].content_hash, "abc");
+        assert!(store.delete_job(&id).await);
+        assert!(store.artifacts(&id).await.is_empty());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_migrates_pre_tenant_database() {
+        let path = std::env::temp_dir().join(format!("watchy-{}.db", uuid::Uuid::new_v4()));
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE latest_audits (
+                chain_id INTEGER NOT NULL,
+                agent_id INTEGER NOT NULL,
+                job_id TEXT NOT NULL,
+                expires_at INTEGER NOT NULL,
+                PRIMARY KEY (chain_id, agent_id)
+            );
+            INSERT INTO latest_audits VALUES (8453, 17, 'audit-1', 4102444800);
+            CREATE TABLE registry (
+                chain_id INTEGER NOT NULL,
+                agent_id INTEGER NOT NULL,
+                agent TEXT NOT NULL,
+                PRIMARY KEY (chain_id, agent_id)
+            );
+            INSERT INTO registry VALUES (8453, 17, '{\"chain_id\":8453,\"agent_id\":17,
+                \"owner\":\"0xAbC\",\"uri\":\"\",\"registered_block\":1}');",
+        )
+        .unwrap();
+        drop(conn);
+
+        let store = SqliteStore::open(&path).unwrap();
+        assert_eq!(store.latest(None, 8453, 17).await.unwrap().as_deref(), Some("audit-1"));
+        assert!(store.latest(Some("acme"), 8453, 17).await.unwrap().is_none());
+        // Owners are backfilled from the stored agents
+        assert_eq!(store.owned_agents(8453, "0xABC").await.unwrap().len(), 1);
+        drop(store);
+
+        let conn = Connection::open(&path).unwrap();
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION + 1).unwrap();
+        drop(conn);
+        assert!(SqliteStore::open(&path).is_err());
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
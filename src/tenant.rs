@@ -0,0 +1,112 @@
+//! Tenants: independent products sharing one deployment
+//!
+//! Each tenant has its own API key. Its audits are only visible through that
+//! key, and agents' latest audits are tracked per tenant. The deployment-wide
+//! `API_KEY` (and open mode) acts as the default tenant.
+
+use std::collections::HashMap;
+use std::env;
+
+/// Tenant a request acts for, from its API key (`None` = default tenant)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Tenant(pub Option<String>);
+
+impl Tenant {
+    pub fn id(&self) -> Option<&str> {
+        self.0.as_deref()
+    }
+}
+
+/// Tenant API keys, from `TENANT_API_KEYS`
+#[derive(Debug, Clone, Default)]
+pub struct TenantKeys {
+    /// Tenant ID by API key
+    tenants: HashMap<String, String>,
+}
+
+impl TenantKeys {
+    /// Load `TENANT_API_KEYS` (`tenant:key` pairs, comma-separated)
+    pub fn from_env() -> anyhow::Result<Self> {
+        match env::var("TENANT_API_KEYS") {
+            Ok(value) => Self::parse(&value),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    fn parse(value: &str) -> anyhow::Result<Self> {
+        let mut tenants = HashMap::new();
+        for pair in value.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let Some((tenant, key)) = pair.split_once(':') else {
+                anyhow::bail!("Invalid TENANT_API_KEYS entry (expected tenant:key)");
+            };
+            let valid_id = !tenant.is_empty()
+                && tenant
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+            if !valid_id {
+                anyhow::bail!(
+                    "Invalid tenant ID '{}' in TENANT_API_KEYS (letters, digits, '-' and '_')",
+                    tenant
+                );
+            }
+            // Usage of the default tenant is metered under this name
+            if tenant.eq_ignore_ascii_case(crate::usage::DEFAULT_ACCOUNT) {
+                anyhow::bail!(
+                    "Tenant ID '{}' in TENANT_API_KEYS is reserved for the default tenant",
+                    tenant
+                );
+            }
+            if key.is_empty() {
+                anyhow::bail!("Empty API key for tenant '{}' in TENANT_API_KEYS", tenant);
+            }
+            if tenants
+                .insert(key.to_string(), tenant.to_string())
+                .is_some()
+            {
+                anyhow::bail!(
+                    "API key of tenant '{}' is listed twice in TENANT_API_KEYS",
+                    tenant
+                );
+            }
+        }
+        Ok(Self { tenants })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tenants.is_empty()
+    }
+
+    /// Tenant owning an API key
+    pub fn tenant_for(&self, key: &str) -> Option<Tenant> {
+        self.tenants
+            .get(key)
+            .map(|tenant| Tenant(Some(tenant.clone())))
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.tenants.contains_key(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tenant_keys() {
+        let keys = TenantKeys::parse("acme:sk_1, globex:sk:2,").unwrap();
+        assert_eq!(
+            keys.tenant_for("sk_1"),
+            Some(Tenant(Some("acme".to_string())))
+        );
+        assert_eq!(keys.tenant_for("sk:2").unwrap().id(), Some("globex"));
+        assert!(keys.tenant_for("sk_3").is_none());
+
+        assert!(TenantKeys::parse("acme").is_err());
+        assert!(TenantKeys::parse("ac me:sk_1").is_err());
+        assert!(TenantKeys::parse("acme:").is_err());
+        assert!(TenantKeys::parse("acme:sk_1,globex:sk_1").is_err());
+        assert!(TenantKeys::parse("default:sk_1").is_err());
+        assert!(TenantKeys::parse("Default:sk_1").is_err());
+    }
+}
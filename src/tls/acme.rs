@@ -0,0 +1,519 @@
+//! Certificates from an ACME CA (RFC 8555), e.g. Let's Encrypt
+//!
+//! Just enough of the protocol for one order covering every `ACME_DOMAINS`
+//! name: an ES256 account key, HTTP-01 challenges answered by a listener on
+//! `ACME_HTTP_PORT` (the CA connects to port 80, so that port must reach it)
+//! and a P-256 certificate key, with keys and the CSR from `rcgen`. The
+//! account key and the issued certificate (chain and key in one file) are
+//! kept in `ACME_CACHE_DIR`, readable by the owner only and replaced
+//! atomically, so restarts reuse them instead of running into the CA's rate
+//! limits.
+
+use axum::{
+    extract::{Path as UrlPath, State},
+    http::StatusCode,
+    routing::get,
+    Router,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use rcgen::{CertificateParams, KeyPair as CertKeyPair, PKCS_ECDSA_P256_SHA256};
+use ring::rand::SystemRandom;
+use ring::signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_FIXED_SIGNING};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::PrivatePkcs8KeyDer;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tracing::{debug, info, warn};
+
+/// Let's Encrypt production directory (the default `ACME_DIRECTORY_URL`)
+pub const LETS_ENCRYPT_DIRECTORY: &str = "https://acme-v02.api.letsencrypt.org/directory";
+
+/// Status polls of an authorization or order before giving up
+const POLL_ATTEMPTS: u32 = 30;
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+const ACCOUNT_KEY_FILE: &str = "account.pk8";
+/// Certificate key (PKCS#8 PEM) followed by the chain
+const CERTIFICATE_FILE: &str = "certificate.pem";
+
+/// Key authorizations of pending HTTP-01 challenges, by token
+pub type Challenges = Arc<RwLock<HashMap<String, String>>>;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Directory {
+    new_nonce: String,
+    new_account: String,
+    new_order: String,
+}
+
+#[derive(Deserialize)]
+struct Order {
+    status: String,
+    #[serde(default)]
+    authorizations: Vec<String>,
+    finalize: String,
+    certificate: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Authorization {
+    status: String,
+    identifier: Identifier,
+    challenges: Vec<Challenge>,
+}
+
+#[derive(Deserialize)]
+struct Identifier {
+    value: String,
+}
+
+#[derive(Deserialize)]
+struct Challenge {
+    #[serde(rename = "type")]
+    kind: String,
+    url: String,
+    token: String,
+}
+
+/// A certificate chain (PEM) and its PKCS#8 key (DER)
+pub struct Issued {
+    pub chain_pem: String,
+    pub key_pkcs8: Vec<u8>,
+}
+
+/// Previously issued certificate in the cache directory, if any
+pub fn load_cached(cache_dir: &Path) -> Option<Issued> {
+    let pem = std::fs::read_to_string(cache_dir.join(CERTIFICATE_FILE)).ok()?;
+    let key = PrivatePkcs8KeyDer::from_pem_slice(pem.as_bytes()).ok()?;
+    let chain_start = pem.find("-----BEGIN CERTIFICATE-----")?;
+    Some(Issued {
+        chain_pem: pem[chain_start..].to_string(),
+        key_pkcs8: key.secret_pkcs8_der().to_vec(),
+    })
+}
+
+fn store_cached(cache_dir: &Path, issued: &Issued) -> anyhow::Result<()> {
+    let key = CertKeyPair::try_from(issued.key_pkcs8.as_slice())?;
+    let pem = format!("{}{}", key.serialize_pem(), issued.chain_pem);
+    write_private(&cache_dir.join(CERTIFICATE_FILE), pem.as_bytes())
+}
+
+/// Replace `path` with `contents` through an owner-only temporary file, so
+/// readers never see a partial write and the key is never world-readable
+fn write_private(path: &Path, contents: &[u8]) -> anyhow::Result<()> {
+    let tmp = path.with_extension("tmp");
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(&tmp)?;
+    // The mode only applies to new files; tighten one left by a crash too
+    #[cfg(unix)]
+    std::fs::set_permissions(
+        &tmp,
+        std::os::unix::fs::PermissionsExt::from_mode(0o600),
+    )?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Answer HTTP-01 challenges on `port` until the process exits
+pub async fn spawn_challenge_listener(port: u16, challenges: Challenges) -> anyhow::Result<()> {
+    let listener = TcpListener::bind(("0.0.0.0", port)).await?;
+    let app = Router::new()
+        .route(
+            "/.well-known/acme-challenge/:token",
+            get(challenge_response),
+        )
+        .with_state(challenges);
+    info!("Answering ACME HTTP-01 challenges on port {}", port);
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, app).await {
+            warn!("ACME challenge listener stopped: {}", e);
+        }
+    });
+    Ok(())
+}
+
+async fn challenge_response(
+    State(challenges): State<Challenges>,
+    UrlPath(token): UrlPath<String>,
+) -> Result<String, StatusCode> {
+    let challenges = challenges.read().unwrap_or_else(|e| e.into_inner());
+    challenges.get(&token).cloned().ok_or(StatusCode::NOT_FOUND)
+}
+
+/// ACME account session: directory, account key and the next replay nonce
+pub struct AcmeClient {
+    http: reqwest::Client,
+    directory: Directory,
+    key: EcdsaKeyPair,
+    /// Account URL, the `kid` of requests after registration
+    account: Option<String>,
+    nonce: Option<String>,
+    rng: SystemRandom,
+}
+
+impl AcmeClient {
+    /// Fetch the directory and register (or look up) the account
+    ///
+    /// The account key is created in `cache_dir` on first use.
+    pub async fn connect(
+        http: reqwest::Client,
+        directory_url: &str,
+        email: Option<&str>,
+        cache_dir: &Path,
+    ) -> anyhow::Result<Self> {
+        let rng = SystemRandom::new();
+        let key_path = cache_dir.join(ACCOUNT_KEY_FILE);
+        let pkcs8 = match std::fs::read(&key_path) {
+            Ok(pkcs8) => pkcs8,
+            Err(_) => {
+                let pkcs8 = generate_key()?;
+                write_private(&key_path, &pkcs8)?;
+                pkcs8
+            }
+        };
+        let key = EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &pkcs8, &rng)
+            .map_err(|e| anyhow::anyhow!("Invalid ACME account key: {}", e))?;
+        let directory = http
+            .get(directory_url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let mut client = Self {
+            http,
+            directory,
+            key,
+            account: None,
+            nonce: None,
+            rng,
+        };
+        let mut account = json!({ "termsOfServiceAgreed": true });
+        if let Some(email) = email {
+            account["contact"] = json!([format!("mailto:{}", email)]);
+        }
+        let url = client.directory.new_account.clone();
+        let response = client.post(&url, Some(&account)).await?;
+        let location = header(&response, "location")
+            .ok_or_else(|| anyhow::anyhow!("ACME account response has no Location"))?;
+        debug!("ACME account: {}", location);
+        client.account = Some(location);
+        Ok(client)
+    }
+
+    /// Order a certificate for `domains`, answering the HTTP-01 challenges
+    pub async fn issue(
+        &mut self,
+        domains: &[String],
+        challenges: &Challenges,
+        cache_dir: &Path,
+    ) -> anyhow::Result<Issued> {
+        let identifiers: Vec<Value> = domains
+            .iter()
+            .map(|domain| json!({ "type": "dns", "value": domain }))
+            .collect();
+        let url = self.directory.new_order.clone();
+        let response = self
+            .post(&url, Some(&json!({ "identifiers": identifiers })))
+            .await?;
+        let order_url = header(&response, "location")
+            .ok_or_else(|| anyhow::anyhow!("ACME order response has no Location"))?;
+        let order: Order = response.json().await?;
+
+        for authorization in &order.authorizations {
+            self.authorize(authorization, challenges).await?;
+        }
+
+        let cert_key = CertKeyPair::generate_for(&PKCS_ECDSA_P256_SHA256)?;
+        let csr = certificate_request(domains, &cert_key)?;
+        self.post(
+            &order.finalize,
+            Some(&json!({ "csr": URL_SAFE_NO_PAD.encode(csr) })),
+        )
+        .await?;
+
+        let order = self.poll_order(&order_url).await?;
+        let certificate = order
+            .certificate
+            .ok_or_else(|| anyhow::anyhow!("ACME order is valid but has no certificate"))?;
+        let chain_pem = self.post(&certificate, None).await?.text().await?;
+
+        let issued = Issued {
+            chain_pem,
+            key_pkcs8: cert_key.serialize_der(),
+        };
+        store_cached(cache_dir, &issued)?;
+        Ok(issued)
+    }
+
+    /// Complete one authorization's HTTP-01 challenge
+    async fn authorize(&mut self, url: &str, challenges: &Challenges) -> anyhow::Result<()> {
+        let authorization: Authorization = self.post(url, None).await?.json().await?;
+        if authorization.status == "valid" {
+            return Ok(());
+        }
+        let challenge = authorization
+            .challenges
+            .iter()
+            .find(|challenge| challenge.kind == "http-01")
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "CA offers no http-01 challenge for {}",
+                    authorization.identifier.value
+                )
+            })?;
+
+        let key_authorization = format!("{}.{}", challenge.token, thumbprint(&self.jwk()));
+        challenges
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(challenge.token.clone(), key_authorization);
+        self.post(&challenge.url, Some(&json!({}))).await?;
+
+        let result = self.poll_authorization(url).await;
+        challenges
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&challenge.token);
+        result.map_err(|e| anyhow::anyhow!("{}: {}", authorization.identifier.value, e))
+    }
+
+    async fn poll_authorization(&mut self, url: &str) -> anyhow::Result<()> {
+        for _ in 0..POLL_ATTEMPTS {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            let authorization: Authorization = self.post(url, None).await?.json().await?;
+            match authorization.status.as_str() {
+                "valid" => return Ok(()),
+                "pending" | "processing" => continue,
+                status => anyhow::bail!("authorization {}", status),
+            }
+        }
+        anyhow::bail!("authorization still pending")
+    }
+
+    async fn poll_order(&mut self, url: &str) -> anyhow::Result<Order> {
+        for _ in 0..POLL_ATTEMPTS {
+            let order: Order = self.post(url, None).await?.json().await?;
+            match order.status.as_str() {
+                "valid" => return Ok(order),
+                "pending" | "ready" | "processing" => {
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                }
+                status => anyhow::bail!("ACME order {}", status),
+            }
+        }
+        anyhow::bail!("ACME order still processing")
+    }
+
+    /// Signed POST (POST-as-GET when `payload` is `None`), retried once on a stale nonce
+    async fn post(
+        &mut self,
+        url: &str,
+        payload: Option<&Value>,
+    ) -> anyhow::Result<reqwest::Response> {
+        let mut retried = false;
+        loop {
+            let nonce = match self.nonce.take() {
+                Some(nonce) => nonce,
+                None => self.new_nonce().await?,
+            };
+            let body = self.sign(url, &nonce, payload)?;
+            let response = self
+                .http
+                .post(url)
+                .header("Content-Type", "application/jose+json")
+                .body(body)
+                .send()
+                .await?;
+            self.nonce = header(&response, "replay-nonce");
+            if response.status().is_success() {
+                return Ok(response);
+            }
+
+            let status = response.status();
+            let problem: Value = response.json().await.unwrap_or_default();
+            let kind = problem["type"].as_str().unwrap_or_default();
+            if kind.ends_with(":badNonce") && !retried {
+                retried = true;
+                continue;
+            }
+            anyhow::bail!(
+                "ACME request to {} failed ({}): {}",
+                url,
+                status,
+                problem["detail"].as_str().unwrap_or(kind)
+            );
+        }
+    }
+
+    async fn new_nonce(&self) -> anyhow::Result<String> {
+        let response = self.http.head(&self.directory.new_nonce).send().await?;
+        header(&response, "replay-nonce")
+            .ok_or_else(|| anyhow::anyhow!("ACME server returned no nonce"))
+    }
+
+    /// Flattened JWS with the account URL as `kid`, or the JWK before registration
+    fn sign(&self, url: &str, nonce: &str, payload: Option<&Value>) -> anyhow::Result<String> {
+        let mut protected = json!({ "alg": "ES256", "nonce": nonce, "url": url });
+        match &self.account {
+            Some(account) => protected["kid"] = json!(account),
+            None => protected["jwk"] = self.jwk(),
+        }
+        let protected = URL_SAFE_NO_PAD.encode(protected.to_string());
+        let payload = payload
+            .map(|payload| URL_SAFE_NO_PAD.encode(payload.to_string()))
+            .unwrap_or_default();
+        let signature = self
+            .key
+            .sign(&self.rng, format!("{}.{}", protected, payload).as_bytes())
+            .map_err(|e| anyhow::anyhow!("Signing ACME request failed: {}", e))?;
+        Ok(json!({
+            "protected": protected,
+            "payload": payload,
+            "signature": URL_SAFE_NO_PAD.encode(signature.as_ref()),
+        })
+        .to_string())
+    }
+
+    /// The account public key as a JWK
+    fn jwk(&self) -> Value {
+        // Uncompressed point: 0x04 || x || y
+        let point = self.key.public_key().as_ref();
+        json!({
+            "crv": "P-256",
+            "kty": "EC",
+            "x": URL_SAFE_NO_PAD.encode(&point[1..33]),
+            "y": URL_SAFE_NO_PAD.encode(&point[33..65]),
+        })
+    }
+}
+
+fn header(response: &reqwest::Response, name: &str) -> Option<String> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+/// New P-256 key, PKCS#8 DER
+fn generate_key() -> anyhow::Result<Vec<u8>> {
+    Ok(CertKeyPair::generate_for(&PKCS_ECDSA_P256_SHA256)?.serialize_der())
+}
+
+/// RFC 7638 thumbprint of an EC JWK (members in lexicographic order)
+fn thumbprint(jwk: &Value) -> String {
+    let canonical = format!(
+        r#"{{"crv":"{}","kty":"{}","x":"{}","y":"{}"}}"#,
+        jwk["crv"].as_str().unwrap_or_default(),
+        jwk["kty"].as_str().unwrap_or_default(),
+        jwk["x"].as_str().unwrap_or_default(),
+        jwk["y"].as_str().unwrap_or_default(),
+    );
+    URL_SAFE_NO_PAD.encode(Sha256::digest(canonical.as_bytes()))
+}
+
+/// PKCS#10 request for `domains` (the first is the subject CN), signed by `key`
+fn certificate_request(domains: &[String], key: &CertKeyPair) -> anyhow::Result<Vec<u8>> {
+    let common_name = domains
+        .first()
+        .ok_or_else(|| anyhow::anyhow!("No domain to request a certificate for"))?;
+    let mut params = CertificateParams::new(domains.to_vec())?;
+    params
+        .distinguished_name
+        .push(rcgen::DnType::CommonName, common_name.as_str());
+    Ok(params.serialize_request(key)?.der().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use x509_parser::prelude::*;
+
+    #[test]
+    fn test_certificate_request() {
+        let key = CertKeyPair::generate_for(&PKCS_ECDSA_P256_SHA256).unwrap();
+        let domains = [
+            "watchy.example.com".to_string(),
+            "api.example.com".to_string(),
+        ];
+        let csr = certificate_request(&domains, &key).unwrap();
+
+        let (rest, request) = X509CertificationRequest::from_der(&csr).unwrap();
+        assert!(rest.is_empty());
+        let info = &request.certification_request_info;
+        ring::signature::UnparsedPublicKey::new(
+            &ring::signature::ECDSA_P256_SHA256_ASN1,
+            key.public_key_raw(),
+        )
+        .verify(info.raw, &request.signature_value.data)
+        .unwrap();
+        assert_eq!(
+            info.subject
+                .iter_common_name()
+                .next()
+                .unwrap()
+                .as_str()
+                .unwrap(),
+            "watchy.example.com"
+        );
+        let names: Vec<String> = request
+            .requested_extensions()
+            .unwrap()
+            .filter_map(|extension| match extension {
+                ParsedExtension::SubjectAlternativeName(san) => Some(san),
+                _ => None,
+            })
+            .flat_map(|san| san.general_names.iter().map(|name| name.to_string()))
+            .collect();
+        assert_eq!(
+            names,
+            ["DNSName(watchy.example.com)", "DNSName(api.example.com)"]
+        );
+    }
+
+    #[test]
+    fn test_cache_files() {
+        let dir = std::env::temp_dir().join(format!("watchy-acme-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let key = CertKeyPair::generate_for(&PKCS_ECDSA_P256_SHA256).unwrap();
+        let cert = CertificateParams::new(vec!["watchy.example.com".to_string()])
+            .unwrap()
+            .self_signed(&key)
+            .unwrap();
+        let issued = Issued {
+            chain_pem: cert.pem(),
+            key_pkcs8: key.serialize_der(),
+        };
+        store_cached(&dir, &issued).unwrap();
+
+        let cached = load_cached(&dir).unwrap();
+        assert_eq!(cached.chain_pem, issued.chain_pem);
+        assert_eq!(cached.key_pkcs8, issued.key_pkcs8);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(dir.join(CERTIFICATE_FILE))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        assert!(!dir.join("certificate.tmp").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
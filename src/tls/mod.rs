@@ -0,0 +1,304 @@
+//! Built-in HTTPS
+//!
+//! Off unless configured: most deployments sit behind a TLS-terminating proxy.
+//! `TLS_CERT_PATH` and `TLS_KEY_PATH` serve a PEM certificate chain and key,
+//! re-read when the certificate file changes, so an external renewer needs no
+//! restart. `ACME_DOMAINS` instead obtains a certificate from an ACME CA and
+//! renews it 30 days before expiry (see `acme`). Either way the API is served
+//! over HTTPS (HTTP/2 or HTTP/1.1) on `PORT`.
+
+pub mod acme;
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+use tokio_rustls::rustls::crypto::ring::{default_provider, sign::any_supported_type};
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+use tokio_rustls::rustls::server::{ClientHello, ResolvesServerCert};
+use tokio_rustls::rustls::sign::CertifiedKey;
+use tokio_rustls::rustls::ServerConfig;
+use tokio_rustls::TlsAcceptor;
+use tracing::{info, warn};
+
+use acme::{AcmeClient, Challenges};
+
+/// How often certificate files are checked for changes
+const RELOAD_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// How often ACME certificates are checked for renewal
+const RENEW_CHECK_INTERVAL: Duration = Duration::from_secs(12 * 60 * 60);
+
+/// Renew ACME certificates this long before they expire
+const RENEW_BEFORE_SECS: i64 = 30 * 24 * 60 * 60;
+
+/// Where the server certificate comes from
+#[derive(Debug, Clone)]
+pub enum TlsConfig {
+    /// PEM files managed outside Watchy
+    Files {
+        cert_path: PathBuf,
+        key_path: PathBuf,
+    },
+    /// Certificates issued and renewed through ACME
+    Acme(AcmeConfig),
+}
+
+#[derive(Debug, Clone)]
+pub struct AcmeConfig {
+    pub domains: Vec<String>,
+    /// Contact for expiry and account notices from the CA
+    pub email: Option<String>,
+    pub directory_url: String,
+    /// Account key, certificate key and chain
+    pub cache_dir: PathBuf,
+    /// Port of the HTTP-01 challenge listener
+    pub http_port: u16,
+}
+
+impl TlsConfig {
+    /// Load from `TLS_CERT_PATH` + `TLS_KEY_PATH`, or `ACME_DOMAINS`
+    /// (comma-separated), `ACME_EMAIL`, `ACME_DIRECTORY_URL`, `ACME_CACHE_DIR`
+    /// and `ACME_HTTP_PORT`
+    ///
+    /// Returns `None` (plain HTTP) if neither is set.
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        let cert_path = env::var("TLS_CERT_PATH").ok();
+        let key_path = env::var("TLS_KEY_PATH").ok();
+        let domains: Vec<String> = env::var("ACME_DOMAINS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_lowercase)
+            .collect();
+
+        match (cert_path, key_path) {
+            (Some(_), Some(_)) if !domains.is_empty() => {
+                anyhow::bail!("Set either TLS_CERT_PATH/TLS_KEY_PATH or ACME_DOMAINS, not both")
+            }
+            (Some(cert_path), Some(key_path)) => Ok(Some(Self::Files {
+                cert_path: cert_path.into(),
+                key_path: key_path.into(),
+            })),
+            (Some(_), None) | (None, Some(_)) => {
+                anyhow::bail!("TLS_CERT_PATH and TLS_KEY_PATH must be set together")
+            }
+            (None, None) if domains.is_empty() => Ok(None),
+            (None, None) => Ok(Some(Self::Acme(AcmeConfig {
+                domains,
+                email: env::var("ACME_EMAIL").ok(),
+                directory_url: env::var("ACME_DIRECTORY_URL")
+                    .unwrap_or_else(|_| acme::LETS_ENCRYPT_DIRECTORY.to_string()),
+                cache_dir: env::var("ACME_CACHE_DIR")
+                    .unwrap_or_else(|_| "./data/acme".to_string())
+                    .into(),
+                http_port: env::var("ACME_HTTP_PORT")
+                    .unwrap_or_else(|_| "80".to_string())
+                    .parse()?,
+            }))),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Files { cert_path, .. } => format!("certificate file {}", cert_path.display()),
+            Self::Acme(acme) => format!("ACME for {}", acme.domains.join(", ")),
+        }
+    }
+}
+
+/// Certificate handed to new connections, swapped on reload or renewal
+#[derive(Debug)]
+struct CertStore(RwLock<Arc<CertifiedKey>>);
+
+impl CertStore {
+    fn get(&self) -> Arc<CertifiedKey> {
+        self.0.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    fn set(&self, key: CertifiedKey) {
+        *self.0.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(key);
+    }
+}
+
+impl ResolvesServerCert for CertStore {
+    fn resolve(&self, _: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        Some(self.get())
+    }
+}
+
+/// Build the TLS acceptor and start reloading or renewing its certificate
+///
+/// With ACME, the first start waits for the certificate to be issued.
+/// `http` reaches the ACME CA.
+pub async fn acceptor(config: &TlsConfig, http: reqwest::Client) -> anyhow::Result<TlsAcceptor> {
+    let store = match config {
+        TlsConfig::Files {
+            cert_path,
+            key_path,
+        } => {
+            let store = Arc::new(CertStore(RwLock::new(Arc::new(load_files(
+                cert_path, key_path,
+            )?))));
+            spawn_file_reload(store.clone(), cert_path.clone(), key_path.clone());
+            store
+        }
+        TlsConfig::Acme(acme) => {
+            std::fs::create_dir_all(&acme.cache_dir)?;
+            let challenges = Challenges::default();
+            acme::spawn_challenge_listener(acme.http_port, challenges.clone()).await?;
+
+            let now = chrono::Utc::now().timestamp();
+            let issued = match acme::load_cached(&acme.cache_dir) {
+                Some(cached) if !needs_renewal(&cached.chain_pem, &acme.domains, now) => cached,
+                cached => match (issue(acme, &http, &challenges).await, cached) {
+                    (Ok(issued), _) => issued,
+                    // An old certificate beats none; the renewal task keeps trying
+                    (Err(e), Some(cached)) => {
+                        warn!("Certificate renewal failed, serving the cached one: {}", e);
+                        cached
+                    }
+                    (Err(e), None) => return Err(e),
+                },
+            };
+            let store = Arc::new(CertStore(RwLock::new(Arc::new(acme_key(&issued)?))));
+            spawn_acme_renewal(store.clone(), acme.clone(), http, challenges);
+            store
+        }
+    };
+
+    let mut server = ServerConfig::builder_with_provider(Arc::new(default_provider()))
+        .with_safe_default_protocol_versions()?
+        .with_no_client_auth()
+        .with_cert_resolver(store);
+    server.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(TlsAcceptor::from(Arc::new(server)))
+}
+
+fn load_files(cert_path: &Path, key_path: &Path) -> anyhow::Result<CertifiedKey> {
+    let chain = std::fs::read(cert_path)
+        .map_err(|e| anyhow::anyhow!("Can't read {}: {}", cert_path.display(), e))?;
+    let key = std::fs::read(key_path)
+        .map_err(|e| anyhow::anyhow!("Can't read {}: {}", key_path.display(), e))?;
+    let key = PrivateKeyDer::from_pem_slice(&key)
+        .map_err(|e| anyhow::anyhow!("No private key in {}: {}", key_path.display(), e))?;
+    certified_key(&chain, &key)
+}
+
+fn acme_key(issued: &acme::Issued) -> anyhow::Result<CertifiedKey> {
+    let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(issued.key_pkcs8.clone()));
+    certified_key(issued.chain_pem.as_bytes(), &key)
+}
+
+fn certified_key(chain_pem: &[u8], key: &PrivateKeyDer<'_>) -> anyhow::Result<CertifiedKey> {
+    let chain = CertificateDer::pem_slice_iter(chain_pem)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| anyhow::anyhow!("Invalid certificate chain: {}", e))?;
+    if chain.is_empty() {
+        anyhow::bail!("Certificate chain is empty");
+    }
+    let key =
+        any_supported_type(key).map_err(|e| anyhow::anyhow!("Unsupported private key: {}", e))?;
+    Ok(CertifiedKey::new(chain, key))
+}
+
+/// Whether a chain's leaf expires within the renewal window or misses a domain
+fn needs_renewal(chain_pem: &str, domains: &[String], now: i64) -> bool {
+    let Some(Ok(leaf)) = CertificateDer::pem_slice_iter(chain_pem.as_bytes()).next() else {
+        return true;
+    };
+    let Ok((_, cert)) = x509_parser::parse_x509_certificate(&leaf) else {
+        return true;
+    };
+    if cert.validity().not_after.timestamp() - now < RENEW_BEFORE_SECS {
+        return true;
+    }
+    let names: Vec<String> = cert
+        .subject_alternative_name()
+        .ok()
+        .flatten()
+        .map(|san| {
+            san.value
+                .general_names
+                .iter()
+                .filter_map(|name| match name {
+                    x509_parser::extensions::GeneralName::DNSName(dns) => Some(dns.to_lowercase()),
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    !domains.iter().all(|domain| names.contains(domain))
+}
+
+async fn issue(
+    config: &AcmeConfig,
+    http: &reqwest::Client,
+    challenges: &Challenges,
+) -> anyhow::Result<acme::Issued> {
+    info!("Requesting a certificate for {}", config.domains.join(", "));
+    let mut client = AcmeClient::connect(
+        http.clone(),
+        &config.directory_url,
+        config.email.as_deref(),
+        &config.cache_dir,
+    )
+    .await?;
+    let issued = client
+        .issue(&config.domains, challenges, &config.cache_dir)
+        .await?;
+    info!("Certificate issued for {}", config.domains.join(", "));
+    Ok(issued)
+}
+
+fn spawn_file_reload(store: Arc<CertStore>, cert_path: PathBuf, key_path: PathBuf) {
+    let modified =
+        |path: &Path| -> Option<SystemTime> { std::fs::metadata(path).ok()?.modified().ok() };
+    tokio::spawn(async move {
+        let mut loaded = modified(&cert_path);
+        loop {
+            tokio::time::sleep(RELOAD_INTERVAL).await;
+            let current = modified(&cert_path);
+            if current == loaded {
+                continue;
+            }
+            // Keep serving the old certificate if the new files are incomplete
+            match load_files(&cert_path, &key_path) {
+                Ok(key) => {
+                    store.set(key);
+                    loaded = current;
+                    info!("Reloaded TLS certificate from {}", cert_path.display());
+                }
+                Err(e) => warn!("TLS certificate reload failed: {}", e),
+            }
+        }
+    });
+}
+
+fn spawn_acme_renewal(
+    store: Arc<CertStore>,
+    config: AcmeConfig,
+    http: reqwest::Client,
+    challenges: Challenges,
+) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(RENEW_CHECK_INTERVAL).await;
+            let now = chrono::Utc::now().timestamp();
+            let current = acme::load_cached(&config.cache_dir);
+            if current.is_some_and(|issued| !needs_renewal(&issued.chain_pem, &config.domains, now))
+            {
+                continue;
+            }
+            match issue(&config, &http, &challenges)
+                .await
+                .and_then(|issued| acme_key(&issued))
+            {
+                Ok(key) => store.set(key),
+                Err(e) => warn!("Certificate renewal failed, retrying later: {}", e),
+            }
+        }
+    });
+}
@@ -1,12 +1,30 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use validator::Validate;
+
+use crate::attestation::AttestationRef;
+use crate::audit::i18n::Lang;
+use crate::audit::policy::HostPolicy;
+use crate::audit::scoring::{self, FeedbackTags, ScoreWeights};
+use crate::caip::AccountId;
 
 /// Audit request from API
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
 pub struct AuditRequest {
+    #[validate(range(min = 1, message = "agent_id must be greater than 0"))]
     pub agent_id: u64,
-    /// Chain ID (e.g., 8453 for Base, 1 for Ethereum)
+    /// Chain ID (e.g., 8453 for Base, 1 for Ethereum) or CAIP-2 ID ("eip155:8453")
     /// If not provided, uses default chain from config
+    #[serde(default, deserialize_with = "crate::caip::deserialize_optional_chain_id")]
     pub chain_id: Option<u64>,
+    /// Overall audit deadline in seconds (capped by AUDIT_MAX_TIMEOUT_SECS)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1, message = "timeout_secs must be greater than 0"))]
+    pub timeout_secs: Option<u64>,
+    /// Report language (en, zh, es); REPORT_LANG when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lang: Option<Lang>,
 }
 
 /// Audit status
@@ -19,9 +37,12 @@ pub enum AuditStatus {
     Failed,
 }
 
+/// Report format version, recorded in every report as `version`
+pub const REPORT_VERSION: &str = "2.0.0";
+
 /// Full audit report (uploaded to Arweave)
 /// This also serves as the off-chain feedback file per EIP-8004 Reputation spec
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct AuditReport {
     // ===== FEEDBACK REQUIRED FIELDS (EIP-8004 Reputation) =====
@@ -39,10 +60,10 @@ pub struct AuditReport {
     pub value_decimals: u8,
 
     // ===== FEEDBACK OPTIONAL FIELDS =====
-    /// Feedback tag1 (e.g., "auditScore")
+    /// Feedback tag1, as submitted on-chain (e.g., "starred")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tag1: Option<String>,
-    /// Feedback tag2 (e.g., "infrastructure")
+    /// Feedback tag2, as submitted on-chain (e.g., the score band "excellent")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tag2: Option<String>,
     /// Primary endpoint tested
@@ -52,11 +73,23 @@ pub struct AuditReport {
     // ===== AUDIT REPORT FIELDS =====
     pub version: String,
     pub auditor: AuditorInfo,
+    /// Language of the Markdown report
+    #[serde(default)]
+    pub lang: Lang,
     pub timestamp: u64,
+    /// Unix seconds after which the audit is stale and shouldn't be relied on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<u64>,
     pub block_number: u64,
     pub agent: AgentInfo,
+    /// Registration and tokenURI update events
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history: Option<AgentHistory>,
     pub scores: Scores,
     pub checks: Checks,
+    /// How each phase ended; phases that failed or were skipped give a reason
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub phases: Vec<PhaseResult>,
 
     // ===== LINKS =====
     /// URL to markdown report on Arweave
@@ -65,35 +98,118 @@ pub struct AuditReport {
     /// URL to JSON report on Arweave
     #[serde(skip_serializing_if = "Option::is_none")]
     pub report_json_url: Option<String>,
+    /// Protocol-native URI of the markdown report (e.g. "ar://<tx>")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub report_markdown_uri: Option<String>,
+    /// Protocol-native URI of the JSON report (e.g. "ar://<tx>")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub report_json_uri: Option<String>,
+    /// keccak256 of the published JSON (the on-chain feedbackHash)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub report_json_hash: Option<String>,
+    /// IPFS copy of the JSON report ("ipfs://<cid>"), if pinned
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub report_ipfs_uri: Option<String>,
+    /// Gateway URL for the IPFS copy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub report_ipfs_url: Option<String>,
+    /// Serialization the signature and feedbackHash are computed over
+    /// (`jcs-rfc8785`; absent on reports hashed over serde_json's compact output)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub canonicalization: Option<String>,
     /// Signature of the report
     #[serde(skip_serializing_if = "Option::is_none")]
     pub signature: Option<String>,
 
     // ===== ON-CHAIN FEEDBACK =====
+    /// Whether this report gets on-chain feedback, and why not if it doesn't
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feedback_policy: Option<FeedbackDecision>,
     /// Chain ID where feedback was submitted
     #[serde(skip_serializing_if = "Option::is_none")]
     pub feedback_chain_id: Option<u64>,
     /// Transaction hash of the feedback submission
     #[serde(skip_serializing_if = "Option::is_none")]
     pub feedback_tx_hash: Option<String>,
+    /// EAS attestation of this audit (with `EAS_ATTESTATIONS`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eas_attestation: Option<EasAttestation>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Feedback decision recorded in the published report
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct FeedbackDecision {
+    /// `FEEDBACK_POLICY` at publish time ("always", "min:<score>", "max:<score>")
+    pub policy: String,
+    pub submit: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// On-chain EAS attestation carrying the report hash and score
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EasAttestation {
+    pub chain_id: u64,
+    /// Attestation UID, queryable with `EAS.getAttestation(uid)`
+    pub uid: String,
+    pub schema_uid: String,
+    pub tx_hash: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AuditorInfo {
     pub name: String,
     pub address: Option<String>,
+    /// Which configured signer `address` is ("mnemonic:{index}" or "key:{position}")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signer: Option<String>,
+    /// Address that submits the on-chain feedback, when it isn't `address`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feedback_address: Option<String>,
     pub version: String,
+    /// Watchy's own TEE attestation (full quote at `GET /attestation`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attestation: Option<AttestationRef>,
+    /// Audit profile of the audited chain ("mainnet", "testnet")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AgentInfo {
     pub agent_id: u64,
     pub registry: String,
     pub metadata_uri: String,
     pub owner: Option<String>,
+    /// Owner's primary ENS name (forward-verified)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner_ens: Option<String>,
+    /// Agent wallet set in the registry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wallet: Option<String>,
+    /// Agent wallet's primary ENS name (forward-verified)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wallet_ens: Option<String>,
+    /// The metadata document this audit was run against
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata_snapshot: Option<MetadataSnapshot>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Content-addressed record of the audited metadata bytes
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MetadataSnapshot {
+    /// SHA-256 of the exact bytes, 0x-prefixed hex
+    pub sha256: String,
+    pub size_bytes: usize,
+    /// Protocol-native URI of the pinned copy (e.g. "ar://<tx>", "ipfs://<cid>")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+    /// Gateway URL of the pinned copy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Scores {
     pub overall: u8,
     pub metadata: u8,
@@ -108,7 +224,26 @@ pub struct Scores {
     pub content: u8,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// How an audit phase ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PhaseOutcome {
+    Completed,
+    /// Ran but couldn't finish; its score counts as found
+    Failed,
+    /// Never ran (nothing to run it on); its categories score 0
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PhaseResult {
+    pub phase: String,
+    pub outcome: PhaseOutcome,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Checks {
     pub metadata: MetadataChecks,
     pub onchain: OnchainChecks,
@@ -119,30 +254,258 @@ pub struct Checks {
     pub consistency: ConsistencyChecks,
     #[serde(default)]
     pub content: ContentChecks,
+    /// Checks required by the agent's declared `supportedTrust` mechanisms
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trust: Option<TrustChecks>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Audit profile selected by a declared `supportedTrust` value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TrustProfile {
+    Tee,
+    Reputation,
+    CryptoEconomic,
+}
+
+/// Results of the trust-profile checks
+///
+/// Issues are reported under the category they affect: TEE under security,
+/// reputation and crypto-economic under on-chain.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct TrustChecks {
+    pub profiles: Vec<TrustProfile>,
+    /// TEE: attestation endpoint declared in metadata
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attestation_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attestation_reachable: Option<bool>,
+    /// Reputation: feedback entries received (not revoked)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feedback_count: Option<u64>,
+    /// Reputation: distinct clients that left feedback
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feedback_clients: Option<u64>,
+    /// Crypto-economic: agent wallet balance
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wallet_balance_wei: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct MetadataChecks {
     pub passed: bool,
     pub required_fields: CheckResult,
     pub type_field: CheckResult,
     pub urls_valid: CheckResult,
     pub recommended_fields: RecommendedFieldsCheck,
+    /// Where the metadata URI points and whether its content can change
+    #[serde(default)]
+    pub storage: StorageCheck,
+    /// DIDs and verifiable credentials found in the metadata
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credentials: Option<CredentialChecks>,
     #[serde(default)]
     pub issues: Vec<Issue>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct CredentialChecks {
+    pub dids: Vec<DidCheck>,
+    pub credentials: Vec<CredentialCheck>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DidCheck {
+    pub did: String,
+    /// JSON pointer to where the DID appears in the metadata
+    pub path: String,
+    /// DID method (e.g. "web", "key")
+    pub method: String,
+    /// DID document was resolved
+    pub resolved: bool,
+    /// IDs of the verification methods in the DID document
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub verification_methods: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CredentialFormat {
+    /// JWT with a `vc` claim
+    Jwt,
+    /// JSON credential with an embedded Data Integrity proof
+    DataIntegrity,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CredentialCheck {
+    /// JSON pointer to the credential in the metadata
+    pub path: String,
+    pub format: CredentialFormat,
+    /// Credential types (e.g. "VerifiableCredential")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub types: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issuer: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    /// Expiry (RFC 3339)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires: Option<String>,
+    pub expired: bool,
+    /// Signature verified against the issuer's DID document
+    /// (`None` when the proof type or algorithm isn't supported)
+    pub signature_valid: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Storage backing the agent's metadata URI
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MetadataStorage {
+    /// Inline `data:` URI stored on-chain
+    Data,
+    /// IPFS (`ipfs://` or an IPFS gateway URL)
+    Ipfs,
+    /// Arweave (`ar://` or an Arweave gateway URL)
+    Arweave,
+    /// Regular HTTPS host
+    Https,
+    /// Plain HTTP host
+    Http,
+    #[default]
+    Unknown,
+}
+
+impl MetadataStorage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MetadataStorage::Data => "data",
+            MetadataStorage::Ipfs => "ipfs",
+            MetadataStorage::Arweave => "arweave",
+            MetadataStorage::Https => "https",
+            MetadataStorage::Http => "http",
+            MetadataStorage::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct StorageCheck {
+    pub passed: bool,
+    pub storage_type: MetadataStorage,
+    /// Content is pinned by a content address (ipfs:// or ar://)
+    ///
+    /// data: URIs are rewritten in place by `setAgentURI`, leaving no
+    /// retrievable copy of the previous document, so they count as mutable.
+    pub immutable: bool,
+    /// Size of the URI in bytes (only recorded for data: URIs)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct OnchainChecks {
     pub passed: bool,
     pub agent_exists: bool,
     pub uri_matches: bool,
     pub wallet_set: bool,
+    /// tokenURI is content-addressed (ipfs:// or ar://)
+    #[serde(default)]
+    pub uri_immutable: bool,
+    /// Owner address heuristics (with `OWNER_RISK_CHECKS`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner_risk: Option<OwnerRisk>,
+    /// Registrations the metadata lists, each checked on its own chain
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub registrations: Vec<RegistrationCheck>,
     #[serde(default)]
     pub issues: Vec<Issue>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A registration listed in the metadata, as found on its chain
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RegistrationCheck {
+    /// Registry as listed (`eip155:<chainId>:<address>`)
+    pub agent_registry: String,
+    pub agent_id: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<u64>,
+    pub status: RegistrationStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_uri: Option<String>,
+    /// Why the registration couldn't be checked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// What a listed registration points at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RegistrationStatus {
+    /// The registration being audited
+    Audited,
+    /// Exists with the audited tokenURI
+    Consistent,
+    /// Exists with a different tokenURI
+    UriMismatch,
+    /// The registry has no such token
+    NotFound,
+    /// Unsupported chain, malformed or unknown registry, or RPC failure
+    Unverified,
+}
+
+/// On-chain provenance of a registration
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AgentHistory {
+    /// The `Registered` event (None if outside the scanned block range)
+    pub registered: Option<RegistryEvent>,
+    /// `URIUpdated` events, oldest first
+    pub uri_updates: Vec<RegistryEvent>,
+    /// First block scanned for events
+    pub from_block: u64,
+}
+
+/// A registry event for the audited agent
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RegistryEvent {
+    pub block_number: u64,
+    /// ISO 8601 block timestamp
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tx_hash: Option<String>,
+    /// Registrant (`Registered`) or updater (`URIUpdated`)
+    pub actor: String,
+    pub uri: String,
+}
+
+/// Activity of the address that owns the registration
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OwnerRisk {
+    pub address: String,
+    /// Transactions sent (nonce)
+    pub tx_count: u64,
+    pub balance_wei: String,
+    /// Owner is a contract (multisig or smart account)
+    pub is_contract: bool,
+    /// First transaction to or from the owner (needs an explorer API key)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_seen: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub age_days: Option<u64>,
+    /// Recently created or barely used
+    pub fresh: bool,
+    /// The agent wallet is the owner address
+    pub wallet_is_owner: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct EndpointCheck {
     pub service: String,
     pub endpoint: String,
@@ -151,39 +514,222 @@ pub struct EndpointCheck {
     pub skills_match: Option<bool>,
     pub latency: Option<LatencyMetrics>,
     pub error: Option<String>,
+    /// Why the endpoint wasn't contacted (e.g. outside the probe allowlist)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_probed: Option<String>,
+    /// Policy the host's operator published at `/.well-known/watchy.json`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host_policy: Option<HostPolicy>,
+    /// Per-skill comparison of declared A2A skills and the agent card
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skills: Vec<SkillMatch>,
+    /// Negotiated protocol, compression and keep-alive support
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transport: Option<TransportInfo>,
+    /// MCP tool name -> SHA-256 of its canonical input schema
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tool_fingerprints: BTreeMap<String, String>,
+    /// `0x`-prefixed SHA-256 of the response body (agent card, MCP manifest, ...)
+    /// as canonical JSON (RFC 8785), to detect changes after the audit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+    /// How sure the reachability verdict is, and the attempts behind it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub availability: Option<Availability>,
+    /// Rate limits the endpoint advertised, and how often it throttled probes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimitInfo>,
+    /// Reachability over IPv4 and IPv6, for hosts that resolve to both
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dual_stack: Option<DualStackCheck>,
+    /// Handshake and keepalive results for `ws://` / `wss://` endpoints
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub websocket: Option<WebSocketInfo>,
+    /// Health and reflection results for gRPC endpoints
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grpc: Option<GrpcInfo>,
     #[serde(default)]
     pub issues: Vec<Issue>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Availability {
+    pub confidence: Confidence,
+    /// Probe attempts made (a failed first attempt is confirmed once)
+    pub attempts: u32,
+    /// Error of each failed attempt, in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Confidence {
+    /// Answered the first attempt, or failed every attempt the same way
+    High,
+    /// Answered only on retry, or failed differently each time
+    Medium,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TransportInfo {
+    /// Negotiated HTTP version (e.g. "HTTP/2.0")
+    pub http_version: String,
+    pub gzip: bool,
+    pub brotli: bool,
+    /// Connection stays open for reuse
+    pub keep_alive: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WebSocketInfo {
+    /// Connect, TLS and upgrade time in milliseconds
+    pub handshake_ms: u64,
+    /// Subprotocol the server selected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subprotocol: Option<String>,
+    /// Server answered pings with pongs
+    pub ping_pong: bool,
+    /// Server answered the JSON-RPC agent card request (A2A only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jsonrpc: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GrpcInfo {
+    /// `grpc.health.v1` serving status (e.g. "SERVING"), if implemented
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health: Option<String>,
+    /// Server answered a reflection query
+    pub reflection: bool,
+    /// Services listed by reflection
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub services: Vec<String>,
+}
+
+/// Endpoint response kept with an audit job, served at `GET /audit/:id/artifacts`
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EndpointArtifact {
+    pub service: String,
+    pub endpoint: String,
+    /// Same as the endpoint check's `content_hash`
+    pub content_hash: String,
+    /// Size of the canonical JSON
+    pub size_bytes: usize,
+    /// The response; `None` when it didn't fit the audit's artifact budget
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<serde_json::Value>,
+}
+
+/// Per-family results for a host with both A and AAAA records
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DualStackCheck {
+    pub ipv4: FamilyReachability,
+    pub ipv6: FamilyReachability,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FamilyReachability {
+    /// Addresses the host resolved to in this family
+    pub addresses: Vec<String>,
+    pub reachable: bool,
+    pub latency: Option<LatencyMetrics>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Rate-limit headers (`RateLimit-*` / `X-RateLimit-*`, `Retry-After`) seen while probing
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct RateLimitInfo {
+    /// Requests allowed per window
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+    /// Requests left in the current window
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remaining: Option<u64>,
+    /// Seconds until the window resets
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reset_secs: Option<u64>,
+    /// Longest `Retry-After` on a 429, in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_after_secs: Option<u64>,
+    /// Probes answered with 429 Too Many Requests
+    pub throttled: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SkillStatus {
+    /// Declared in metadata and present in the agent card
+    Matched,
+    /// Declared in metadata but missing from the agent card
+    DeclaredOnly,
+    /// In the agent card but not declared in metadata
+    CardOnly,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SkillMatch {
+    pub status: SkillStatus,
+    /// Skill as declared in the registration metadata
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub declared: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub card_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub card_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct LatencyMetrics {
     pub p50: u64,
     pub p95: u64,
     pub p99: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CheckResult {
     pub passed: bool,
     #[serde(default)]
     pub details: serde_json::Value,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RecommendedFieldsCheck {
     pub passed: bool,
     #[serde(default)]
     pub missing: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Issue {
     pub severity: Severity,
     pub code: String,
     pub message: String,
+    /// How to fix it, from the issue catalog
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remediation: Option<String>,
+    /// Further reading on the underlying standard or practice
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub docs_url: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+impl Issue {
+    /// Issue without remediation or docs (the catalog fills those in)
+    pub fn new(severity: Severity, code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            code: code.into(),
+            message: message.into(),
+            remediation: None,
+            docs_url: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum Severity {
     Critical,
@@ -193,7 +739,7 @@ pub enum Severity {
 }
 
 /// Security checks for endpoints
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct SecurityChecks {
     pub passed: bool,
     pub tls_valid: bool,
@@ -202,11 +748,80 @@ pub struct SecurityChecks {
     pub certificate_days_remaining: Option<i64>,
     pub security_headers: SecurityHeadersCheck,
     pub https_enforced: bool,
+    /// Certificate chain details captured during the TLS handshake
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub certificate: Option<CertificateInfo>,
+    /// Vulnerability disclosure policy (RFC 9116), if the host publishes one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub security_txt: Option<SecurityTxt>,
+    /// Threat intelligence lookup of endpoints and image (when a provider is configured)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reputation: Option<ReputationCheck>,
     #[serde(default)]
     pub issues: Vec<Issue>,
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ReputationCheck {
+    /// Provider consulted (`safe_browsing` or `urlhaus`)
+    pub provider: String,
+    pub urls_checked: usize,
+    #[serde(default)]
+    pub flagged: Vec<FlaggedUrl>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FlaggedUrl {
+    pub url: String,
+    /// Provider's threat classification (e.g. "malware", "social_engineering")
+    pub threat: String,
+}
+
+/// Parsed `security.txt` fields
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct SecurityTxt {
+    /// Where it was found
+    pub url: String,
+    pub contacts: Vec<String>,
+    /// `Expires` as written (RFC 3339)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires: Option<String>,
+    pub expired: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy: Option<String>,
+}
+
+/// Leaf certificate and chain details from the TLS handshake
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct CertificateInfo {
+    pub subject: String,
+    pub issuer: String,
+    #[serde(default)]
+    pub san_dns_names: Vec<String>,
+    #[serde(default)]
+    pub san_ip_addresses: Vec<String>,
+    /// Unix timestamp
+    pub not_before: i64,
+    /// Unix timestamp
+    pub not_after: i64,
+    pub key_algorithm: String,
+    pub key_bits: Option<usize>,
+    pub signature_algorithm: String,
+    /// Number of certificates presented by the server (leaf + intermediates)
+    pub chain_length: usize,
+    /// Whether the chain validated against the Mozilla root store
+    pub chain_trusted: bool,
+    pub validation_error: Option<String>,
+    pub cipher_suite: Option<String>,
+    /// Hostname the endpoint was probed on
+    #[serde(default)]
+    pub hostname: String,
+    /// Whether the SANs cover the probed hostname
+    #[serde(default)]
+    pub hostname_matches: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct SecurityHeadersCheck {
     pub x_content_type_options: bool,
     pub x_frame_options: bool,
@@ -216,30 +831,119 @@ pub struct SecurityHeadersCheck {
 }
 
 /// Consistency checks across metadata and endpoints
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct ConsistencyChecks {
     pub passed: bool,
     pub name_consistent: bool,
     pub skills_consistent: bool,
     pub version_consistent: bool,
     pub image_accessible: bool,
+    /// Metadata author's claimed ENS name matches the owner or wallet (None = nothing to check)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author_identity_consistent: Option<bool>,
     #[serde(default)]
     pub issues: Vec<Issue>,
 }
 
 /// Content quality checks
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct ContentChecks {
     pub passed: bool,
     pub description_quality: DescriptionQuality,
     pub valid_skill_taxonomy: bool,
     pub has_contact_info: bool,
     pub x402_valid: Option<X402Check>,
+    /// `sourceCode` repository verification, when metadata declares one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_code: Option<SourceCodeCheck>,
+    /// `documentation` link check, when metadata declares one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub documentation: Option<DocumentationCheck>,
+    /// LLM review of the description against declared skills (`llm` feature)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claims_analysis: Option<ClaimsAnalysis>,
     #[serde(default)]
     pub issues: Vec<Issue>,
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+/// Model verdict on whether an agent's claims are coherent
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ClaimsAnalysis {
+    pub model: String,
+    /// None when the model couldn't be asked or its reply was unusable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub consistent: Option<bool>,
+    #[serde(default)]
+    pub findings: Vec<ClaimFinding>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// A contradictory or nonsensical claim, with the model's reasoning
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ClaimFinding {
+    pub claim: String,
+    pub reasoning: String,
+}
+
+/// Liveness and quality of the metadata's `documentation` link
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct DocumentationCheck {
+    pub url: String,
+    pub reachable: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    /// Visible (non-markup, non-whitespace) characters on the page
+    pub text_chars: usize,
+    /// Page looks like a parked or for-sale domain
+    pub parked: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Verification of the metadata's `sourceCode` repository
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct SourceCodeCheck {
+    pub url: String,
+    /// `owner/repo` for GitHub repositories
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repository: Option<String>,
+    pub exists: bool,
+    /// None when visibility couldn't be determined
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public: Option<bool>,
+    /// Repository name, description or README mentions the agent name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub references_name: Option<bool>,
+    /// Commit stated in metadata (`sourceCommit` or a commit URL)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit_found: Option<bool>,
+    /// Release tag stated in metadata (`sourceRelease` or a release URL)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub release: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub release_found: Option<bool>,
+    /// Lookup failure that left the check inconclusive (e.g. rate limiting)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl SourceCodeCheck {
+    /// Repository is public and every stated commit/release exists
+    ///
+    /// Inconclusive lookups don't count against the agent.
+    pub fn verified(&self) -> bool {
+        self.error.is_some()
+            || (self.exists
+                && self.public != Some(false)
+                && self.commit_found != Some(false)
+                && self.release_found != Some(false))
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct DescriptionQuality {
     pub score: u8,
     pub length: usize,
@@ -247,7 +951,7 @@ pub struct DescriptionQuality {
     pub is_meaningful: bool,
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct X402Check {
     pub valid: bool,
     pub returns_402: bool,
@@ -258,6 +962,73 @@ pub struct X402Check {
     pub payment_amount: Option<String>,
     pub payment_network: Option<String>,
     pub error: Option<String>,
+    /// Payment requirements from the 402 bodies (`accepts`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub accepts: Vec<X402Requirement>,
+    /// Whether every payTo address is the agent wallet or owner
+    /// (None when there's nothing to compare)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pay_to_matches_wallet: Option<bool>,
+    /// Payment assets checked against the stablecoin list and the chain
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assets: Vec<X402AssetCheck>,
+    /// Advertised price per call of each requirement, normalized to USD
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prices: Vec<X402Price>,
+}
+
+/// Price per call of an x402 requirement
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct X402Price {
+    pub network: String,
+    pub asset: String,
+    /// Advertised amount in the asset's base units (`maxAmountRequired`)
+    pub max_amount_required: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    /// Amount in whole tokens (known assets only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price_usd: Option<f64>,
+    /// Where the USD rate came from ("pegged", "coingecko")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price_source: Option<String>,
+}
+
+/// Payment asset of an x402 requirement
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct X402AssetCheck {
+    pub network: String,
+    pub asset: String,
+    /// Chain the network refers to (None when Watchy doesn't support it)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<u64>,
+    /// Symbol when the asset is a known stablecoin
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    /// Whether a contract is deployed at the asset address
+    /// (None when it couldn't be looked up, past the lookup cap, or on non-EVM chains)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exists: Option<bool>,
+}
+
+/// One x402 payment requirement, as advertised by the agent
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct X402Requirement {
+    #[serde(default)]
+    pub scheme: String,
+    #[serde(default)]
+    pub network: String,
+    /// Amount in the asset's base units
+    #[serde(default)]
+    pub max_amount_required: String,
+    #[serde(default)]
+    pub pay_to: String,
+    /// Token contract address
+    #[serde(default)]
+    pub asset: String,
 }
 
 /// API response for audit status
@@ -302,11 +1073,32 @@ pub struct IssueCount {
     pub info: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[allow(dead_code)]
+/// Why an audit failed, as stored on its job
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct AuditError {
+    /// Machine-readable code (the API's error codes, e.g. `blockchain_error`)
     pub code: String,
     pub message: String,
+    /// Audit phase that failed (`onchain`, `metadata`, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phase: Option<String>,
+    /// Requesting the audit again may succeed
+    #[serde(default)]
+    pub retryable: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cause: Option<ErrorCause>,
+}
+
+/// Upstream an audit failure came from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCause {
+    /// Chain RPC
+    Rpc,
+    /// IPFS/Arweave/HTTP gateway serving metadata or reports
+    Gateway,
+    /// The agent's own endpoints
+    Endpoint,
 }
 
 impl AuditReport {
@@ -326,9 +1118,9 @@ impl AuditReport {
         client_address: Option<&str>,
     ) -> Self {
         let now = chrono::Utc::now();
-        let registry_full = format!("eip155:{}:{}", chain_id, registry_address);
+        let registry_full = AccountId::eip155_string(chain_id, registry_address);
         let client_full = client_address
-            .map(|addr| format!("eip155:{}:{}", chain_id, addr))
+            .map(|addr| AccountId::eip155_string(chain_id, addr))
             .unwrap_or_default();
 
         Self {
@@ -341,25 +1133,36 @@ impl AuditReport {
             value_decimals: 0,
 
             // Feedback optional fields
-            tag1: Some("starred".to_string()),
+            tag1: None, // Set with the overall score
             tag2: None,
             endpoint: None, // Will be set if endpoints exist
 
             // Audit report fields
-            version: "1.0.0".to_string(),
+            version: REPORT_VERSION.to_string(),
             auditor: AuditorInfo {
                 name: "watchy".to_string(),
                 address: client_address.map(|s| s.to_string()),
+                signer: None,
+                feedback_address: None,
                 version: env!("CARGO_PKG_VERSION").to_string(),
+                attestation: None,
+                profile: None,
             },
+            lang: Lang::default(),
             timestamp: now.timestamp() as u64,
+            valid_until: None,
             block_number: 0,
             agent: AgentInfo {
                 agent_id,
                 registry: registry_full,
                 metadata_uri,
                 owner: None,
+                owner_ens: None,
+                wallet: None,
+                wallet_ens: None,
+                metadata_snapshot: None,
             },
+            history: None,
             scores: Scores {
                 overall: 0,
                 metadata: 0,
@@ -389,6 +1192,8 @@ impl AuditReport {
                         passed: false,
                         missing: vec![],
                     },
+                    storage: StorageCheck::default(),
+                    credentials: None,
                     issues: vec![],
                 },
                 onchain: OnchainChecks {
@@ -396,54 +1201,131 @@ impl AuditReport {
                     agent_exists: false,
                     uri_matches: false,
                     wallet_set: false,
+                    uri_immutable: false,
+                    owner_risk: None,
+                    registrations: vec![],
                     issues: vec![],
                 },
                 endpoints: vec![],
                 security: SecurityChecks::default(),
                 consistency: ConsistencyChecks::default(),
                 content: ContentChecks::default(),
+                trust: None,
             },
 
             // Links
+            phases: Vec::new(),
             report_markdown_url: None,
             report_json_url: None,
+            report_markdown_uri: None,
+            report_json_uri: None,
+            report_json_hash: None,
+            report_ipfs_uri: None,
+            report_ipfs_url: None,
+            canonicalization: None,
             signature: None,
 
             // On-chain feedback
             feedback_chain_id: None,
+            feedback_policy: None,
             feedback_tx_hash: None,
+            eas_attestation: None,
         }
     }
 
+    /// JSON Schema of the published report, identified by format version
+    pub fn json_schema() -> serde_json::Value {
+        let mut schema = schemars::schema_for!(AuditReport);
+        let metadata = schema.schema.metadata();
+        metadata.id = Some(format!("urn:watchy:audit-report:{}", REPORT_VERSION));
+        metadata.title = Some(format!("Watchy audit report v{}", REPORT_VERSION));
+        serde_json::to_value(schema).unwrap_or_default()
+    }
+
     /// Calculate overall score from component scores
-    /// Weights: availability 35%, performance 20%, security 10%, metadata 15%, onchain 10%, consistency 5%, content 5%
-    pub fn calculate_overall_score(&mut self) {
-        self.scores.overall = (
-            self.scores.endpoint_availability as f64 * 0.35
-            + self.scores.endpoint_performance as f64 * 0.20
-            + self.scores.security as f64 * 0.10
-            + self.scores.metadata as f64 * 0.15
-            + self.scores.onchain as f64 * 0.10
-            + self.scores.consistency as f64 * 0.05
-            + self.scores.content as f64 * 0.05
-        ) as u8;
+    /// Default weights: availability 35%, performance 20%, security 10%, metadata 15%, onchain 10%, consistency 5%, content 5%
+    pub fn calculate_overall_score(&mut self, weights: &ScoreWeights) {
+        let skipped = self
+            .phases
+            .iter()
+            .filter(|phase| phase.outcome == PhaseOutcome::Skipped)
+            .map(|phase| phase.phase.as_str());
+        scoring::zero_skipped(&mut self.scores, skipped);
+        self.scores.overall = weights.overall(&self.scores);
 
         // Also set the feedback value
         self.value = self.scores.overall as i128;
     }
 
+    pub fn record_phase(&mut self, phase: &str, outcome: PhaseOutcome, reason: Option<String>) {
+        self.phases.push(PhaseResult {
+            phase: phase.to_string(),
+            outcome,
+            reason,
+        });
+    }
+
+    pub fn skip_phases(&mut self, phases: &[&str], reason: &str) {
+        for phase in phases {
+            self.record_phase(phase, PhaseOutcome::Skipped, Some(reason.to_string()));
+        }
+    }
+
+    /// Whether a phase was skipped (its checks are empty, not failed)
+    pub fn is_skipped(&self, phase: &str) -> bool {
+        self.phases
+            .iter()
+            .any(|result| result.phase == phase && result.outcome == PhaseOutcome::Skipped)
+    }
+
+    /// Expire the audit `validity_secs` after it was run (0 = never)
+    pub fn set_validity(&mut self, validity_secs: u64) {
+        self.valid_until =
+            (validity_secs > 0).then(|| self.timestamp.saturating_add(validity_secs));
+    }
+
+    /// Whether the audit's validity window has passed
+    pub fn is_stale(&self, now: u64) -> bool {
+        self.valid_until.is_some_and(|until| now >= until)
+    }
+
+    /// Set tag1/tag2 from the overall score; empty tags are left out
+    pub fn set_feedback_tags(&mut self, tags: &FeedbackTags) {
+        let (tag1, tag2) = tags.render(self.scores.overall);
+        self.tag1 = Some(tag1).filter(|tag| !tag.is_empty());
+        self.tag2 = Some(tag2).filter(|tag| !tag.is_empty());
+    }
+
     /// Set the primary endpoint for feedback
     pub fn set_primary_endpoint(&mut self, endpoint: &str) {
         self.endpoint = Some(endpoint.to_string());
     }
 
-    /// Set the markdown report URL
-    pub fn set_markdown_url(&mut self, url: &str) {
+    /// Set the markdown report gateway URL and native URI
+    pub fn set_markdown_url(&mut self, url: &str, uri: &str) {
         self.report_markdown_url = Some(url.to_string());
+        self.report_markdown_uri = Some(uri.to_string());
+    }
+
+    pub fn clear_markdown_url(&mut self) {
+        self.report_markdown_url = None;
+        self.report_markdown_uri = None;
     }
 
-    pub fn set_json_url(&mut self, url: &str) {
+    pub fn set_json_url(&mut self, url: &str, uri: &str) {
         self.report_json_url = Some(url.to_string());
+        self.report_json_uri = Some(uri.to_string());
+    }
+
+    /// Record the hash of the published JSON report
+    pub fn set_json_hash(&mut self, hash: &[u8; 32]) {
+        self.report_json_hash = Some(format!("0x{}", hex::encode(hash)));
+    }
+
+    /// Record the IPFS copy of the JSON report
+    pub fn set_ipfs_location(&mut self, url: &str, cid: &str) {
+        self.report_ipfs_url = Some(url.to_string());
+        self.report_ipfs_uri = Some(format!("ipfs://{}", cid));
     }
 
     pub fn set_feedback_tx(&mut self, chain_id: u64, tx_hash: &str) {
@@ -451,6 +1333,32 @@ impl AuditReport {
         self.feedback_tx_hash = Some(tx_hash.to_string());
     }
 
+    pub fn set_eas_attestation(&mut self, attestation: EasAttestation) {
+        self.eas_attestation = Some(attestation);
+    }
+
+    /// Every issue in the report with the check category it came from
+    pub fn issues_by_category(&self) -> Vec<(&'static str, &Issue)> {
+        let mut sources: Vec<(&'static str, &[Issue])> = vec![
+            ("metadata", &self.checks.metadata.issues),
+            ("onchain", &self.checks.onchain.issues),
+        ];
+        sources.extend(
+            self.checks
+                .endpoints
+                .iter()
+                .map(|e| ("endpoints", e.issues.as_slice())),
+        );
+        sources.push(("security", &self.checks.security.issues));
+        sources.push(("consistency", &self.checks.consistency.issues));
+        sources.push(("content", &self.checks.content.issues));
+
+        sources
+            .into_iter()
+            .flat_map(|(category, issues)| issues.iter().map(move |issue| (category, issue)))
+            .collect()
+    }
+
     /// Count issues by severity
     pub fn count_issues(&self) -> IssueCount {
         let mut count = IssueCount {
@@ -460,18 +1368,7 @@ impl AuditReport {
             info: 0,
         };
 
-        let all_issues = self
-            .checks
-            .metadata
-            .issues
-            .iter()
-            .chain(self.checks.onchain.issues.iter())
-            .chain(self.checks.endpoints.iter().flat_map(|e| e.issues.iter()))
-            .chain(self.checks.security.issues.iter())
-            .chain(self.checks.consistency.issues.iter())
-            .chain(self.checks.content.issues.iter());
-
-        for issue in all_issues {
+        for (_, issue) in self.issues_by_category() {
             match issue.severity {
                 Severity::Critical => count.critical += 1,
                 Severity::Error => count.error += 1,
@@ -483,3 +1380,45 @@ impl AuditReport {
         count
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_schema_matches_report() {
+        let schema = AuditReport::json_schema();
+        assert_eq!(schema["$id"], format!("urn:watchy:audit-report:{}", REPORT_VERSION));
+
+        // Every field of a serialized report is described under its wire name
+        let mut report = AuditReport::new(1, 8453, "0x8004", "ipfs://meta".to_string(), None);
+        report.record_phase("metadata", PhaseOutcome::Skipped, None);
+        let report = serde_json::to_value(&report).unwrap();
+        for key in report.as_object().unwrap().keys() {
+            assert!(schema["properties"].get(key).is_some(), "{} missing from schema", key);
+        }
+        assert!(schema["definitions"]["PhaseOutcome"].is_object());
+    }
+
+    #[test]
+    fn test_validity() {
+        let mut report = AuditReport::new(1, 8453, "0x8004", String::new(), None);
+        report.timestamp = 1_000;
+
+        report.set_validity(90 * 24 * 60 * 60);
+        assert_eq!(report.valid_until, Some(1_000 + 7_776_000));
+        assert!(!report.is_stale(1_000));
+        assert!(!report.is_stale(7_776_999));
+        // Stale once `valid_until` is reached
+        assert!(report.is_stale(7_777_000));
+
+        // No window: never stale
+        report.set_validity(0);
+        assert_eq!(report.valid_until, None);
+        assert!(!report.is_stale(u64::MAX));
+
+        report.set_validity(u64::MAX);
+        assert_eq!(report.valid_until, Some(u64::MAX));
+        assert!(!report.is_stale(u64::MAX - 1));
+    }
+}
@@ -6,11 +6,20 @@ use axum::{
 use serde::Serialize;
 use thiserror::Error;
 
+use super::{AuditError, ErrorCause};
+
 #[derive(Error, Debug)]
 pub enum WatchyError {
     #[error("Invalid request: {0}")]
     InvalidRequest(String),
 
+    #[error("Validation failed: {message}")]
+    Validation {
+        message: String,
+        /// Errors by field, from `api::validation`
+        fields: serde_json::Value,
+    },
+
     #[error("Invalid address: {0}")]
     InvalidAddress(String),
 
@@ -26,15 +35,146 @@ pub enum WatchyError {
     #[error("Blockchain error: {0}")]
     BlockchainError(String),
 
+    /// The signer can't pay for a transaction's gas
+    #[error("Insufficient funds: {0}")]
+    InsufficientFunds(String),
+
     #[error("IPFS error: {0}")]
     IpfsError(String),
 
+    #[error("Timed out: {0}")]
+    Timeout(String),
+
+    #[error("Storage error: {0}")]
+    StorageError(String),
+
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    /// Valid request the server refuses (e.g. admin endpoints while disabled)
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
+    #[error("Quota exceeded: {0}")]
+    QuotaExceeded(String),
+
     #[error("Rate limited")]
     #[allow(dead_code)]
     RateLimited,
 
+    #[error("Request body too large: {0}")]
+    PayloadTooLarge(String),
+
+    /// Too many requests in flight (MAX_CONCURRENT_REQUESTS)
+    #[error("Server overloaded")]
+    Overloaded,
+
     #[error("Internal error: {0}")]
     Internal(String),
+
+    /// An error attributed to the audit phase it happened in
+    #[error("{phase} phase failed: {source}")]
+    Phase {
+        phase: &'static str,
+        source: Box<WatchyError>,
+    },
+}
+
+impl WatchyError {
+    /// Attribute this error to an audit phase (the innermost phase wins)
+    pub fn in_phase(self, phase: &'static str) -> Self {
+        match self {
+            WatchyError::Phase { .. } => self,
+            other => WatchyError::Phase {
+                phase,
+                source: Box::new(other),
+            },
+        }
+    }
+
+    /// The error itself, without its phase
+    fn root(&self) -> &WatchyError {
+        match self {
+            WatchyError::Phase { source, .. } => source.root(),
+            other => other,
+        }
+    }
+
+    pub fn phase(&self) -> Option<&'static str> {
+        match self {
+            WatchyError::Phase { phase, .. } => Some(phase),
+            _ => None,
+        }
+    }
+
+    /// Machine-readable code, as returned in `ErrorResponse::error`
+    pub fn code(&self) -> &'static str {
+        match self.root() {
+            WatchyError::InvalidRequest(_) => "invalid_request",
+            WatchyError::Validation { .. } => "validation_failed",
+            WatchyError::InvalidAddress(_) => "invalid_address",
+            WatchyError::AgentNotFound(_) => "agent_not_found",
+            WatchyError::AuditNotFound(_) => "audit_not_found",
+            WatchyError::MetadataFetchFailed(_) => "metadata_fetch_failed",
+            WatchyError::BlockchainError(_) => "blockchain_error",
+            WatchyError::InsufficientFunds(_) => "insufficient_funds",
+            WatchyError::IpfsError(_) => "ipfs_error",
+            WatchyError::Timeout(_) => "timeout",
+            WatchyError::StorageError(_) => "storage_error",
+            WatchyError::Unauthorized(_) => "unauthorized",
+            WatchyError::Forbidden(_) => "forbidden",
+            WatchyError::QuotaExceeded(_) => "quota_exceeded",
+            WatchyError::RateLimited => "rate_limited",
+            WatchyError::PayloadTooLarge(_) => "payload_too_large",
+            WatchyError::Overloaded => "overloaded",
+            WatchyError::Internal(_) | WatchyError::Phase { .. } => "internal_error",
+        }
+    }
+
+    /// Whether the same request may succeed later (upstream or transient failures)
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self.root(),
+            WatchyError::MetadataFetchFailed(_)
+                | WatchyError::BlockchainError(_)
+                | WatchyError::IpfsError(_)
+                | WatchyError::Timeout(_)
+                | WatchyError::StorageError(_)
+                | WatchyError::QuotaExceeded(_)
+                | WatchyError::RateLimited
+                | WatchyError::Overloaded
+        )
+    }
+
+    /// Upstream the failure came from; timeouts are blamed on the phase's upstream
+    pub fn cause(&self) -> Option<ErrorCause> {
+        match (self.root(), self.phase()) {
+            (WatchyError::BlockchainError(_), _) => Some(ErrorCause::Rpc),
+            (WatchyError::MetadataFetchFailed(_) | WatchyError::IpfsError(_), _) => {
+                Some(ErrorCause::Gateway)
+            }
+            (WatchyError::Timeout(_), Some("onchain" | "history" | "owner risk")) => {
+                Some(ErrorCause::Rpc)
+            }
+            (WatchyError::Timeout(_), Some("metadata")) => Some(ErrorCause::Gateway),
+            (WatchyError::Timeout(_), Some("endpoints" | "security" | "consistency")) => {
+                Some(ErrorCause::Endpoint)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl From<&WatchyError> for AuditError {
+    fn from(error: &WatchyError) -> Self {
+        Self {
+            code: error.code().to_string(),
+            message: error.root().to_string(),
+            phase: error.phase().map(str::to_string),
+            retryable: error.is_retryable(),
+            cause: error.cause(),
+        }
+    }
 }
 
 #[derive(Serialize)]
@@ -49,50 +189,57 @@ pub struct ErrorResponse {
 
 impl IntoResponse for WatchyError {
     fn into_response(self) -> Response {
-        let (status, error_code, message) = match &self {
-            WatchyError::InvalidRequest(msg) => {
-                (StatusCode::BAD_REQUEST, "invalid_request", msg.clone())
-            }
-            WatchyError::InvalidAddress(msg) => {
-                (StatusCode::BAD_REQUEST, "invalid_address", msg.clone())
-            }
-            WatchyError::AgentNotFound(id) => (
-                StatusCode::NOT_FOUND,
-                "agent_not_found",
-                format!("Agent {} not found", id),
-            ),
-            WatchyError::AuditNotFound(id) => (
-                StatusCode::NOT_FOUND,
-                "audit_not_found",
-                format!("Audit {} not found", id),
-            ),
-            WatchyError::MetadataFetchFailed(msg) => {
-                (StatusCode::BAD_GATEWAY, "metadata_fetch_failed", msg.clone())
+        if let WatchyError::Phase { source, .. } = self {
+            return source.into_response();
+        }
+
+        let (status, message) = match &self {
+            WatchyError::InvalidRequest(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
+            WatchyError::Validation { message, .. } => {
+                (StatusCode::UNPROCESSABLE_ENTITY, message.clone())
             }
-            WatchyError::BlockchainError(msg) => {
-                (StatusCode::BAD_GATEWAY, "blockchain_error", msg.clone())
+            WatchyError::InvalidAddress(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
+            WatchyError::AgentNotFound(id) => {
+                (StatusCode::NOT_FOUND, format!("Agent {} not found", id))
             }
-            WatchyError::IpfsError(msg) => {
-                (StatusCode::BAD_GATEWAY, "ipfs_error", msg.clone())
+            WatchyError::AuditNotFound(id) => {
+                (StatusCode::NOT_FOUND, format!("Audit {} not found", id))
             }
+            WatchyError::MetadataFetchFailed(msg) => (StatusCode::BAD_GATEWAY, msg.clone()),
+            WatchyError::BlockchainError(msg) => (StatusCode::BAD_GATEWAY, msg.clone()),
+            WatchyError::InsufficientFunds(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg.clone()),
+            WatchyError::IpfsError(msg) => (StatusCode::BAD_GATEWAY, msg.clone()),
+            WatchyError::Timeout(msg) => (StatusCode::GATEWAY_TIMEOUT, msg.clone()),
+            WatchyError::StorageError(msg) => (StatusCode::BAD_GATEWAY, msg.clone()),
+            WatchyError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, msg.clone()),
+            WatchyError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg.clone()),
+            WatchyError::QuotaExceeded(msg) => (StatusCode::TOO_MANY_REQUESTS, msg.clone()),
             WatchyError::RateLimited => (
                 StatusCode::TOO_MANY_REQUESTS,
-                "rate_limited",
                 "Too many requests".to_string(),
             ),
-            WatchyError::Internal(msg) => {
-                (StatusCode::INTERNAL_SERVER_ERROR, "internal_error", msg.clone())
-            }
+            WatchyError::PayloadTooLarge(msg) => (StatusCode::PAYLOAD_TOO_LARGE, msg.clone()),
+            WatchyError::Overloaded => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Too many requests in flight, retry shortly".to_string(),
+            ),
+            WatchyError::Phase { .. } => unreachable!("phase errors respond as their source"),
+            WatchyError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
         };
 
         let body = ErrorResponse {
-            error: error_code.to_string(),
+            error: self.code().to_string(),
             message,
-            details: None,
-            retry_after: if matches!(self, WatchyError::RateLimited) {
-                Some(3600)
-            } else {
-                None
+            details: match &self {
+                WatchyError::Validation { fields, .. } => {
+                    Some(serde_json::json!({ "fields": fields }))
+                }
+                _ => None,
+            },
+            retry_after: match self {
+                WatchyError::RateLimited => Some(3600),
+                WatchyError::Overloaded => Some(1),
+                _ => None,
             },
         };
 
@@ -111,3 +258,30 @@ impl From<serde_json::Error> for WatchyError {
         WatchyError::InvalidRequest(format!("JSON parse error: {}", err))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audit_error_taxonomy() {
+        let rpc = WatchyError::BlockchainError("all RPCs failed".to_string()).in_phase("onchain");
+        let error = AuditError::from(&rpc);
+        assert_eq!(error.code, "blockchain_error");
+        assert_eq!(error.phase.as_deref(), Some("onchain"));
+        assert_eq!(error.message, "Blockchain error: all RPCs failed");
+        assert!(error.retryable);
+        assert_eq!(error.cause, Some(ErrorCause::Rpc));
+
+        // Timeouts are blamed on the phase's upstream; phases don't nest
+        let timeout = WatchyError::Timeout("deadline".to_string())
+            .in_phase("endpoints")
+            .in_phase("audit");
+        assert_eq!(timeout.phase(), Some("endpoints"));
+        assert_eq!(timeout.cause(), Some(ErrorCause::Endpoint));
+
+        let missing = AuditError::from(&WatchyError::AgentNotFound(7).in_phase("onchain"));
+        assert!(!missing.retryable);
+        assert_eq!(missing.cause, None);
+    }
+}
@@ -1,9 +1,11 @@
 use serde::{Deserialize, Serialize};
 
+use crate::caip::{AccountId, CaipError};
+
 pub const EIP8004_TYPE: &str = "https://eips.ethereum.org/EIPS/eip-8004#registration-v1";
 
 /// EIP-8004 Agent Metadata (off-chain JSON)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AgentMetadata {
     #[serde(rename = "type")]
     pub metadata_type: Option<String>,
@@ -40,6 +42,14 @@ pub struct AgentMetadata {
     #[serde(alias = "sourceCode")]
     pub source_code: Option<String>,
 
+    /// Commit hash the deployed agent was built from
+    #[serde(default, alias = "sourceCommit")]
+    pub source_commit: Option<String>,
+
+    /// Release tag the deployed agent was built from
+    #[serde(default, alias = "sourceRelease")]
+    pub source_release: Option<String>,
+
     pub documentation: Option<String>,
 
     pub author: Option<Author>,
@@ -48,6 +58,14 @@ pub struct AgentMetadata {
 
     #[serde(default)]
     pub tags: Vec<String>,
+
+    /// The document as fetched, including fields not modelled above
+    #[serde(skip)]
+    pub raw: serde_json::Value,
+
+    /// Exact bytes the document was parsed from
+    #[serde(skip)]
+    pub source: Vec<u8>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -69,6 +87,10 @@ pub struct Service {
     #[serde(default, alias = "mcpPrompts")]
     pub mcp_prompts: Vec<String>,
 
+    // gRPC specific (fully qualified service names)
+    #[serde(default, alias = "grpcServices")]
+    pub grpc_services: Vec<String>,
+
     // OASF specific
     #[serde(default)]
     pub skills: Vec<serde_json::Value>, // Can be string or object
@@ -112,46 +134,31 @@ impl AgentMetadata {
     }
 
     /// Find registration matching the given agent ID and registry
-    /// Registry format expected: "eip155:<chainId>:<address>"
+    /// Registry format expected: "eip155:<chainId>:<address>" (case-insensitive: a
+    /// broken checksum is flagged as a malformed registry, not a mismatch)
     pub fn find_registration(&self, agent_id: u64, registry: &str) -> Option<&Registration> {
-        let registry_lower = registry.to_lowercase();
-
-        self.registrations.iter().find(|r| {
-            if r.agent_id != agent_id {
-                return false;
-            }
-
-            let r_registry_lower = r.agent_registry.to_lowercase();
-
-            // Exact match
-            if r_registry_lower == registry_lower {
-                return true;
-            }
-
-            // Parse CAIP-10 format: eip155:<chainId>:<address>
-            // Allow matching if chain and address match (ignoring case)
-            let r_parts: Vec<&str> = r_registry_lower.split(':').collect();
-            let registry_parts: Vec<&str> = registry_lower.split(':').collect();
-
-            // Both should have format eip155:chainId:address
-            if r_parts.len() == 3 && registry_parts.len() == 3 {
-                // Match namespace (eip155), chain_id, and full address
-                r_parts[0] == registry_parts[0]
-                    && r_parts[1] == registry_parts[1]
-                    && r_parts[2] == registry_parts[2]
-            } else {
-                false
-            }
-        })
+        let registry = registry.to_lowercase();
+        self.registrations
+            .iter()
+            .find(|r| r.agent_id == agent_id && r.agent_registry.to_lowercase() == registry)
+    }
+}
+
+impl Registration {
+    /// The registry as a CAIP-10 account
+    pub fn registry(&self) -> Result<AccountId, CaipError> {
+        self.agent_registry.parse()
     }
 }
 
 /// Service type enum for easier handling
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ServiceType {
     A2A,
     MCP,
     OASF,
+    Grpc,
     Web,
     Twitter,
     Email,
@@ -164,6 +171,7 @@ impl From<&str> for ServiceType {
             "A2A" => ServiceType::A2A,
             "MCP" => ServiceType::MCP,
             "OASF" => ServiceType::OASF,
+            "GRPC" => ServiceType::Grpc,
             "WEB" => ServiceType::Web,
             "TWITTER" => ServiceType::Twitter,
             "EMAIL" => ServiceType::Email,
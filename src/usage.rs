@@ -0,0 +1,240 @@
+//! Monthly audit quotas and metering per API key
+//!
+//! Every audit (and endpoint check) requested through an API key counts
+//! towards that key's tenant for the calendar month (UTC). Past its quota, `POST /audit` is refused with
+//! 429 until the next month. Each counted audit can be posted to a metering
+//! webhook for billing.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use tracing::warn;
+
+use crate::config::Vars;
+use crate::store::AuditStore;
+use crate::tenant::Tenant;
+use crate::types::WatchyError;
+use crate::AppState;
+
+/// Account name of the default tenant (`API_KEY` / open mode)
+pub const DEFAULT_ACCOUNT: &str = "default";
+
+#[derive(Debug, Clone, Default)]
+pub struct UsageConfig {
+    /// Audits per month for every key without its own quota (`None` = unlimited)
+    pub monthly_quota: Option<u64>,
+    /// Per-tenant quotas, overriding `monthly_quota` (0 = unlimited)
+    pub tenant_quotas: HashMap<String, u64>,
+    /// Receives a metering event for every counted audit
+    pub webhook: Option<String>,
+}
+
+impl UsageConfig {
+    /// Load from `AUDIT_QUOTA_PER_MONTH`, `TENANT_AUDIT_QUOTAS`
+    /// (`tenant:quota`, comma-separated; `default` for `API_KEY`) and `USAGE_WEBHOOK_URL`
+    pub fn from_vars(vars: Vars) -> anyhow::Result<Self> {
+        let monthly_quota = match vars("AUDIT_QUOTA_PER_MONTH") {
+            Some(value) => Some(value.parse::<u64>()?).filter(|quota| *quota > 0),
+            None => None,
+        };
+
+        let mut tenant_quotas = HashMap::new();
+        if let Some(value) = vars("TENANT_AUDIT_QUOTAS") {
+            for pair in value.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+                let Some((tenant, quota)) = pair.split_once(':') else {
+                    anyhow::bail!(
+                        "Invalid TENANT_AUDIT_QUOTAS entry '{}' (expected tenant:quota)",
+                        pair
+                    );
+                };
+                tenant_quotas.insert(tenant.trim().to_string(), quota.trim().parse()?);
+            }
+        }
+
+        Ok(Self {
+            monthly_quota,
+            tenant_quotas,
+            webhook: vars("USAGE_WEBHOOK_URL"),
+        })
+    }
+
+    /// A tenant's monthly quota (`None` = unlimited)
+    pub fn quota_for(&self, tenant: &Tenant) -> Option<u64> {
+        match self.tenant_quotas.get(account(tenant)) {
+            Some(0) => None,
+            Some(quota) => Some(*quota),
+            None => self.monthly_quota,
+        }
+    }
+}
+
+/// Name usage is counted under
+pub fn account(tenant: &Tenant) -> &str {
+    tenant.id().unwrap_or(DEFAULT_ACCOUNT)
+}
+
+/// Calendar month (UTC) of a Unix time, as `YYYY-MM`
+pub fn month(timestamp: u64) -> String {
+    chrono::DateTime::from_timestamp(timestamp as i64, 0)
+        .unwrap_or_default()
+        .format("%Y-%m")
+        .to_string()
+}
+
+/// A tenant's audits in a month, against its quota
+#[derive(Debug, Serialize)]
+pub struct Usage {
+    pub account: String,
+    pub month: String,
+    pub audits: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quota: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remaining: Option<u64>,
+}
+
+impl Usage {
+    fn new(tenant: &Tenant, month: String, audits: u64, quota: Option<u64>) -> Self {
+        Self {
+            account: account(tenant).to_string(),
+            month,
+            audits,
+            quota,
+            remaining: quota.map(|quota| quota.saturating_sub(audits)),
+        }
+    }
+}
+
+/// A tenant's usage for a month (`YYYY-MM`)
+pub async fn usage(
+    store: &AuditStore,
+    config: &UsageConfig,
+    tenant: &Tenant,
+    month: String,
+) -> Usage {
+    let audits = store.usage(account(tenant), &month).await;
+    Usage::new(tenant, month, audits, config.quota_for(tenant))
+}
+
+/// Count an audit against the tenant's quota, refusing it once the quota is used up
+pub async fn reserve(
+    store: &AuditStore,
+    config: &UsageConfig,
+    tenant: &Tenant,
+    now: u64,
+) -> Result<Usage, WatchyError> {
+    let month = month(now);
+    let quota = config.quota_for(tenant);
+    let audits = store.add_usage(account(tenant), &month, 1).await;
+
+    if let Some(quota) = quota.filter(|quota| audits > *quota) {
+        store.add_usage(account(tenant), &month, -1).await;
+        return Err(WatchyError::QuotaExceeded(format!(
+            "Monthly audit quota of {} reached for {}",
+            quota, month
+        )));
+    }
+    Ok(Usage::new(tenant, month, audits, quota))
+}
+
+/// Give back an audit counted by `reserve` that didn't run
+pub async fn release(store: &AuditStore, usage: &Usage) {
+    store.add_usage(&usage.account, &usage.month, -1).await;
+}
+
+/// Post a metering event for a counted audit, if a webhook is configured
+pub fn meter(
+    state: &AppState,
+    usage: Usage,
+    audit_id: &str,
+    agent_id: Option<u64>,
+    chain_id: u64,
+) {
+    let event = serde_json::json!({
+        "event": "audit_requested",
+        "account": usage.account,
+        "month": usage.month,
+        "audits": usage.audits,
+        "quota": usage.quota,
+        "auditId": audit_id,
+        "agentId": agent_id,
+        "chainId": chain_id,
+        "timestamp": chrono::Utc::now().timestamp(),
+    });
+    post_event(state, event);
+}
+
+/// Post a metering event for a counted endpoint check (`POST /check/endpoint`)
+pub fn meter_check(state: &AppState, usage: Usage, url: &str) {
+    let event = serde_json::json!({
+        "event": "endpoint_checked",
+        "account": usage.account,
+        "month": usage.month,
+        "audits": usage.audits,
+        "quota": usage.quota,
+        "url": url,
+        "timestamp": chrono::Utc::now().timestamp(),
+    });
+    post_event(state, event);
+}
+
+fn post_event(state: &AppState, event: serde_json::Value) {
+    let Some(webhook) = state.config.usage.webhook.clone() else {
+        return;
+    };
+    let client = state.http.webhook.clone();
+    tokio::spawn(async move {
+        match client.post(&webhook).json(&event).send().await {
+            Ok(response) if !response.status().is_success() => {
+                warn!("Usage webhook returned {}", response.status());
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Failed to post usage event: {}", e),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_quota_enforced_per_tenant() {
+        let store = AuditStore::in_memory();
+        let config = UsageConfig {
+            monthly_quota: Some(2),
+            tenant_quotas: HashMap::from([("acme".to_string(), 0)]),
+            webhook: None,
+        };
+        let default = Tenant::default();
+        let acme = Tenant(Some("acme".to_string()));
+        let now = 1_790_000_000; // 2026-09
+
+        assert_eq!(
+            reserve(&store, &config, &default, now)
+                .await
+                .unwrap()
+                .remaining,
+            Some(1)
+        );
+        reserve(&store, &config, &default, now).await.unwrap();
+        assert!(matches!(
+            reserve(&store, &config, &default, now).await,
+            Err(WatchyError::QuotaExceeded(_))
+        ));
+        // Refused audits don't count, and tenants have their own quota
+        let used = usage(&store, &config, &default, month(now)).await;
+        assert_eq!((used.audits, used.remaining), (2, Some(0)));
+        for _ in 0..3 {
+            reserve(&store, &config, &acme, now).await.unwrap();
+        }
+        // A new month starts from zero
+        let next_month = now + 31 * 24 * 3600;
+        assert_eq!(
+            reserve(&store, &config, &default, next_month)
+                .await
+                .unwrap()
+                .audits,
+            1
+        );
+    }
+}
@@ -3,11 +3,28 @@
 //! Supports two key modes:
 //! - `private_key`: Direct private key from PRIVATE_KEY env var
 //! - `mnemonic`: Derive from MNEMONIC env var (EigenCloud KMS)
+//!
+//! Either mode can configure several signers (`PRIVATE_KEYS`, or
+//! `DERIVATION_INDEXES` of the mnemonic). Reports and feedback are signed by
+//! one of them, chosen by `SIGNER_ROTATION`; signers retired at runtime
+//! (`POST /admin/signers/retire`) are skipped. Admin transactions
+//! (registration, URI updates) always use the primary signer.
+//!
+//! Uploads and feedback transactions can be given their own keys
+//! (`UPLOAD_PRIVATE_KEY` / `FEEDBACK_PRIVATE_KEY`, or `*_DERIVATION_INDEX` of
+//! the mnemonic), keeping the on-chain spender apart from the identity that
+//! report signatures are verified against. Unset roles use the report signer.
 
 use alloy::signers::local::{coins_bip39::English, MnemonicBuilder, PrivateKeySigner};
 use std::env;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use tracing::info;
 
+use crate::store::RetiredSigner;
+
+/// Position of the next round-robin signer
+static NEXT_SIGNER: AtomicUsize = AtomicUsize::new(0);
+
 /// Key mode for wallet initialization
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum KeyMode {
@@ -34,7 +51,7 @@ impl KeyMode {
         // Auto-detect based on available env vars
         if env::var("MNEMONIC").is_ok() {
             KeyMode::Mnemonic
-        } else if env::var("PRIVATE_KEY").is_ok() {
+        } else if env::var("PRIVATE_KEY").is_ok() || env::var("PRIVATE_KEYS").is_ok() {
             KeyMode::PrivateKey
         } else {
             KeyMode::None
@@ -50,12 +67,72 @@ impl KeyMode {
     }
 }
 
+/// How reports and feedback are spread across signers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RotationPolicy {
+    /// Always the first active signer
+    Fixed,
+    /// Each audit takes the next active signer
+    RoundRobin,
+    /// The same active signer for every audit of an agent, keeping its feedback
+    /// in one client's history
+    PerAgent,
+}
+
+impl RotationPolicy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "fixed" | "primary" | "none" => Some(RotationPolicy::Fixed),
+            "round_robin" | "round-robin" => Some(RotationPolicy::RoundRobin),
+            "per_agent" | "per-agent" | "sticky" => Some(RotationPolicy::PerAgent),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RotationPolicy::Fixed => "fixed",
+            RotationPolicy::RoundRobin => "round_robin",
+            RotationPolicy::PerAgent => "per_agent",
+        }
+    }
+}
+
+/// One configured signing key
+#[derive(Debug, Clone)]
+pub struct SignerKey {
+    /// Where the key comes from: "mnemonic:{index}", "key:{position}", or its
+    /// role ("upload", "feedback:mnemonic:{index}", ...)
+    pub label: String,
+    pub private_key: String,
+    pub address: String,
+}
+
 /// Wallet configuration derived from environment
 #[derive(Debug, Clone)]
 pub struct WalletConfig {
     pub mode: KeyMode,
+    /// Primary signer's key
     pub private_key: Option<String>,
     pub address: Option<String>,
+    /// Every configured signer, the primary first
+    pub signers: Vec<SignerKey>,
+    pub rotation: RotationPolicy,
+    /// Dedicated key for ANS-104 uploads (and the upload credit it holds)
+    pub upload_signer: Option<SignerKey>,
+    /// Dedicated key for reputation feedback and EAS attestation transactions
+    pub feedback_signer: Option<SignerKey>,
+}
+
+/// Keys for each signing role of one published report
+#[derive(Debug, Clone, Copy)]
+pub struct SigningKeys<'a> {
+    /// Signs the JSON report (the auditor identity consumers verify)
+    pub report: &'a SignerKey,
+    /// Signs ANS-104 DataItems and pays for uploads
+    pub upload: &'a SignerKey,
+    /// Sends feedback and attestation transactions
+    pub feedback: &'a SignerKey,
 }
 
 impl WalletConfig {
@@ -66,67 +143,277 @@ impl WalletConfig {
     /// - `MNEMONIC`: BIP-39 mnemonic phrase (12/24 words). Used when mode=mnemonic.
     /// - `PRIVATE_KEY`: Hex-encoded private key. Used when mode=private_key.
     /// - `DERIVATION_INDEX`: Optional. HD wallet index for mnemonic mode. Default: 0.
+    /// - `DERIVATION_INDEXES`: Optional. Comma-separated indexes of several mnemonic
+    ///   signers (replaces `DERIVATION_INDEX`; the first is the primary).
+    /// - `PRIVATE_KEYS`: Optional. Comma-separated keys signing besides `PRIVATE_KEY`.
+    /// - `SIGNER_ROTATION`: Optional. fixed (default), round_robin or per_agent.
     pub fn from_env() -> anyhow::Result<Self> {
         let mode = KeyMode::from_env();
+        let rotation = match env::var("SIGNER_ROTATION") {
+            Ok(value) => RotationPolicy::parse(&value)
+                .ok_or_else(|| anyhow::anyhow!("Unknown SIGNER_ROTATION: {}", value))?,
+            Err(_) => RotationPolicy::Fixed,
+        };
 
-        match mode {
+        let signers = match mode {
             KeyMode::Mnemonic => {
                 let mnemonic = env::var("MNEMONIC")
                     .map_err(|_| anyhow::anyhow!("MNEMONIC env var required for mnemonic mode"))?;
 
-                let index: u32 = env::var("DERIVATION_INDEX")
-                    .unwrap_or_else(|_| "0".to_string())
-                    .parse()
-                    .unwrap_or(0);
-
-                let (private_key, address) = derive_from_mnemonic(&mnemonic, index)?;
-
-                info!(
-                    "Wallet initialized from mnemonic (mode: {}, index: {}, address: {})",
-                    mode.as_str(),
-                    index,
-                    address
-                );
-
-                Ok(Self {
-                    mode,
-                    private_key: Some(private_key),
-                    address: Some(address),
-                })
+                let indexes: Vec<u32> = match env::var("DERIVATION_INDEXES") {
+                    Ok(list) => list
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|s| !s.is_empty())
+                        .map(|s| s.parse())
+                        .collect::<Result<_, _>>()?,
+                    Err(_) => vec![env::var("DERIVATION_INDEX")
+                        .unwrap_or_else(|_| "0".to_string())
+                        .parse()
+                        .unwrap_or(0)],
+                };
+
+                let mut signers = Vec::new();
+                for index in indexes {
+                    let (private_key, address) = derive_from_mnemonic(&mnemonic, index)?;
+                    info!(
+                        "Wallet initialized from mnemonic (mode: {}, index: {}, address: {})",
+                        mode.as_str(),
+                        index,
+                        address
+                    );
+                    signers.push(SignerKey {
+                        label: format!("mnemonic:{}", index),
+                        private_key,
+                        address,
+                    });
+                }
+                signers
             }
             KeyMode::PrivateKey => {
-                let private_key = env::var("PRIVATE_KEY")
-                    .map_err(|_| anyhow::anyhow!("PRIVATE_KEY env var required for private_key mode"))?;
-
-                let address = derive_address(&private_key)?;
-
-                info!(
-                    "Wallet initialized from private key (mode: {}, address: {})",
-                    mode.as_str(),
-                    address
-                );
-
-                Ok(Self {
-                    mode,
-                    private_key: Some(private_key),
-                    address: Some(address),
-                })
+                let mut keys: Vec<String> = env::var("PRIVATE_KEY").into_iter().collect();
+                if let Ok(list) = env::var("PRIVATE_KEYS") {
+                    keys.extend(
+                        list.split(',')
+                            .map(str::trim)
+                            .filter(|s| !s.is_empty())
+                            .map(str::to_string),
+                    );
+                }
+
+                let mut signers = Vec::new();
+                for (position, private_key) in keys.into_iter().enumerate() {
+                    let address = derive_address(&private_key)?;
+                    info!(
+                        "Wallet initialized from private key (mode: {}, address: {})",
+                        mode.as_str(),
+                        address
+                    );
+                    signers.push(SignerKey {
+                        label: format!("key:{}", position),
+                        private_key,
+                        address,
+                    });
+                }
+                signers
             }
             KeyMode::None => {
                 info!("No wallet configured (mode: none). Signing features disabled.");
-                Ok(Self {
-                    mode,
-                    private_key: None,
-                    address: None,
-                })
+                Vec::new()
             }
+        };
+
+        if mode != KeyMode::None && signers.is_empty() {
+            anyhow::bail!("{} mode requires at least one signer", mode.as_str());
         }
+        let upload_signer = role_signer(&mode, "UPLOAD")?;
+        let feedback_signer = role_signer(&mode, "FEEDBACK")?;
+
+        if signers.len() > 1 {
+            info!(
+                "{} signers configured (rotation: {})",
+                signers.len(),
+                rotation.as_str()
+            );
+        }
+
+        Ok(Self {
+            mode,
+            private_key: signers.first().map(|s| s.private_key.clone()),
+            address: signers.first().map(|s| s.address.clone()),
+            signers,
+            rotation,
+            upload_signer,
+            feedback_signer,
+        })
+    }
+
+    /// Lowercased addresses of every key Watchy signs with, any role
+    pub fn own_addresses(&self) -> Vec<String> {
+        let mut addresses: Vec<String> = self
+            .address
+            .iter()
+            .chain(self.signers.iter().map(|signer| &signer.address))
+            .chain(self.upload_signer.iter().map(|signer| &signer.address))
+            .chain(self.feedback_signer.iter().map(|signer| &signer.address))
+            .map(|address| address.to_lowercase())
+            .collect();
+        addresses.sort();
+        addresses.dedup();
+        addresses
+    }
+
+    /// Whether `address` is one of the report signers (any case) and wasn't
+    /// retired by `signed_at` (unix seconds)
+    ///
+    /// A report from a retired signer without a signing time isn't trusted.
+    pub fn is_report_signer(
+        &self,
+        address: &str,
+        signed_at: Option<u64>,
+        retired: &[RetiredSigner],
+    ) -> bool {
+        let configured = self
+            .signers
+            .iter()
+            .any(|signer| signer.address.eq_ignore_ascii_case(address));
+        let retired_at = retired
+            .iter()
+            .find(|signer| signer.address.eq_ignore_ascii_case(address))
+            .map(|signer| signer.retired_at);
+        configured
+            && match (retired_at, signed_at) {
+                (None, _) => true,
+                (Some(retired_at), Some(signed_at)) => signed_at < retired_at,
+                (Some(_), None) => false,
+            }
     }
 
     /// Check if signing is available
     pub fn can_sign(&self) -> bool {
         self.private_key.is_some()
     }
+
+    /// Keys for publishing a report signed by `report`; a retired role key
+    /// falls back to `report`
+    pub fn signing_keys<'a>(
+        &'a self,
+        report: &'a SignerKey,
+        retired: &[RetiredSigner],
+    ) -> SigningKeys<'a> {
+        SigningKeys {
+            report,
+            upload: active_role(&self.upload_signer, retired).unwrap_or(report),
+            feedback: active_role(&self.feedback_signer, retired).unwrap_or(report),
+        }
+    }
+
+    /// Key holding the upload credit outside of a publish (the primary by
+    /// default, or when the upload key is retired)
+    pub fn upload_key(&self, retired: &[RetiredSigner]) -> Option<&SignerKey> {
+        active_role(&self.upload_signer, retired).or(self.signers.first())
+    }
+
+    /// Key paying for feedback transactions outside of a publish (the primary
+    /// by default, or when the feedback key is retired)
+    pub fn feedback_key(&self, retired: &[RetiredSigner]) -> Option<&SignerKey> {
+        active_role(&self.feedback_signer, retired).or(self.signers.first())
+    }
+
+    /// First report signer that isn't retired (the primary unless it was),
+    /// for receipts
+    pub fn receipt_signer(&self, retired: &[RetiredSigner]) -> Option<&SignerKey> {
+        pick_signer(&self.signers, RotationPolicy::Fixed, 0, 0, 0, retired)
+    }
+
+    /// Dedicated upload and feedback keys, by role
+    pub fn role_keys(&self) -> impl Iterator<Item = (&'static str, &SignerKey)> {
+        let upload = self.upload_signer.iter().map(|key| ("upload", key));
+        upload.chain(self.feedback_signer.iter().map(|key| ("feedback", key)))
+    }
+
+    /// Signer for an audit of the agent, skipping `retired` addresses
+    ///
+    /// `None` when no signer is configured or all of them are retired.
+    pub fn select_signer(
+        &self,
+        chain_id: u64,
+        agent_id: u64,
+        retired: &[RetiredSigner],
+    ) -> Option<&SignerKey> {
+        let sequence = match self.rotation {
+            RotationPolicy::RoundRobin => NEXT_SIGNER.fetch_add(1, Ordering::Relaxed),
+            _ => 0,
+        };
+        pick_signer(&self.signers, self.rotation, sequence, chain_id, agent_id, retired)
+    }
+}
+
+/// Dedicated key for a role from `{ROLE}_PRIVATE_KEY` or `{ROLE}_DERIVATION_INDEX`
+fn role_signer(mode: &KeyMode, role: &str) -> anyhow::Result<Option<SignerKey>> {
+    let label = role.to_lowercase();
+    if let Ok(private_key) = env::var(format!("{}_PRIVATE_KEY", role)) {
+        let address = derive_address(&private_key)?;
+        info!("{} signer: {}", role, address);
+        return Ok(Some(SignerKey {
+            label,
+            private_key,
+            address,
+        }));
+    }
+    if *mode == KeyMode::Mnemonic {
+        if let Ok(index) = env::var(format!("{}_DERIVATION_INDEX", role)) {
+            let mnemonic = env::var("MNEMONIC")?;
+            let index: u32 = index.parse()?;
+            let (private_key, address) = derive_from_mnemonic(&mnemonic, index)?;
+            info!("{} signer: {} (index {})", role, address, index);
+            return Ok(Some(SignerKey {
+                label: format!("{}:mnemonic:{}", label, index),
+                private_key,
+                address,
+            }));
+        }
+    }
+    Ok(None)
+}
+
+/// A role's dedicated key, unless it was retired
+fn active_role<'a>(
+    key: &'a Option<SignerKey>,
+    retired: &[RetiredSigner],
+) -> Option<&'a SignerKey> {
+    key.as_ref().filter(|key| {
+        !retired
+            .iter()
+            .any(|retired| retired.address.eq_ignore_ascii_case(&key.address))
+    })
+}
+
+fn pick_signer<'a>(
+    signers: &'a [SignerKey],
+    rotation: RotationPolicy,
+    sequence: usize,
+    chain_id: u64,
+    agent_id: u64,
+    retired: &[RetiredSigner],
+) -> Option<&'a SignerKey> {
+    let active: Vec<&SignerKey> = signers
+        .iter()
+        .filter(|signer| {
+            !retired
+                .iter()
+                .any(|retired| retired.address.eq_ignore_ascii_case(&signer.address))
+        })
+        .collect();
+    if active.is_empty() {
+        return None;
+    }
+    let position = match rotation {
+        RotationPolicy::Fixed => 0,
+        RotationPolicy::RoundRobin => sequence % active.len(),
+        RotationPolicy::PerAgent => ((chain_id << 32) ^ agent_id) as usize % active.len(),
+    };
+    Some(active[position])
 }
 
 /// Derive private key and address from BIP-39 mnemonic
@@ -182,6 +469,95 @@ mod tests {
         assert_eq!(address.to_lowercase(), "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266");
     }
 
+    #[test]
+    fn test_pick_signer() {
+        let mnemonic = "test test test test test test test test test test test junk";
+        let signers: Vec<SignerKey> = (0..3)
+            .map(|index| {
+                let (private_key, address) = derive_from_mnemonic(mnemonic, index).unwrap();
+                SignerKey {
+                    label: format!("mnemonic:{}", index),
+                    private_key,
+                    address,
+                }
+            })
+            .collect();
+        let label = |signer: Option<&SignerKey>| signer.map(|s| s.label.clone());
+
+        let fixed = pick_signer(&signers, RotationPolicy::Fixed, 5, 8453, 7, &[]);
+        assert_eq!(label(fixed).as_deref(), Some("mnemonic:0"));
+        let rotated = pick_signer(&signers, RotationPolicy::RoundRobin, 4, 8453, 7, &[]);
+        assert_eq!(label(rotated).as_deref(), Some("mnemonic:1"));
+
+        // Per-agent selection is stable across audits
+        let first = pick_signer(&signers, RotationPolicy::PerAgent, 0, 8453, 7, &[]);
+        let again = pick_signer(&signers, RotationPolicy::PerAgent, 9, 8453, 7, &[]);
+        assert_eq!(label(first), label(again));
+
+        // Retired signers are skipped, and nothing is left when all are retired
+        let retire = |signer: &SignerKey| RetiredSigner {
+            address: signer.address.to_lowercase(),
+            retired_at: 0,
+        };
+        let retired = vec![retire(&signers[0])];
+        let fixed = pick_signer(&signers, RotationPolicy::Fixed, 0, 8453, 7, &retired);
+        assert_eq!(label(fixed).as_deref(), Some("mnemonic:1"));
+        let all: Vec<RetiredSigner> = signers.iter().map(retire).collect();
+        assert!(pick_signer(&signers, RotationPolicy::Fixed, 0, 8453, 7, &all).is_none());
+    }
+
+    #[test]
+    fn test_signing_keys_fallback() {
+        let key = |label: &str| SignerKey {
+            label: label.to_string(),
+            private_key: String::new(),
+            address: String::new(),
+        };
+        let mut wallet = WalletConfig {
+            mode: KeyMode::PrivateKey,
+            private_key: None,
+            address: None,
+            signers: vec![key("key:0")],
+            rotation: RotationPolicy::Fixed,
+            upload_signer: None,
+            feedback_signer: Some(key("feedback")),
+        };
+
+        wallet.address = Some("0xABC".to_string());
+        wallet.signers[0].address = "0xabc".to_string();
+        wallet.feedback_signer.as_mut().unwrap().address = "0xDEF".to_string();
+        assert_eq!(wallet.own_addresses(), vec!["0xabc", "0xdef"]);
+        // The feedback key pays for transactions but doesn't sign reports
+        assert!(wallet.is_report_signer("0xABC", None, &[]));
+        assert!(!wallet.is_report_signer("0xdef", None, &[]));
+        // A retired signer only vouches for reports signed before it was retired
+        let retired = [RetiredSigner {
+            address: "0xabc".to_string(),
+            retired_at: 1000,
+        }];
+        assert!(wallet.is_report_signer("0xABC", Some(999), &retired));
+        assert!(!wallet.is_report_signer("0xABC", Some(1000), &retired));
+        assert!(!wallet.is_report_signer("0xABC", None, &retired));
+
+        let keys = wallet.signing_keys(&wallet.signers[0], &[]);
+        assert_eq!(keys.report.label, "key:0");
+        assert_eq!(keys.upload.label, "key:0");
+        assert_eq!(keys.feedback.label, "feedback");
+        assert_eq!(wallet.feedback_key(&[]).unwrap().label, "feedback");
+
+        wallet.upload_signer = Some(key("upload"));
+        assert_eq!(wallet.upload_key(&[]).unwrap().label, "upload");
+
+        // A retired role key falls back to the report signer
+        let retired = [RetiredSigner {
+            address: "0xdef".to_string(),
+            retired_at: 1000,
+        }];
+        let keys = wallet.signing_keys(&wallet.signers[0], &retired);
+        assert_eq!(keys.feedback.label, "key:0");
+        assert_eq!(wallet.feedback_key(&retired).unwrap().label, "key:0");
+    }
+
     #[test]
     fn test_key_mode_detection() {
         // Without env vars set, should be None